@@ -22,3 +22,11 @@ echo "--- Run unit tests in deterministic simulation mode"
     -p risingwave_source \
     -p risingwave_storage \
     -p risingwave_stream
+
+echo "--- Run recovery test in deterministic simulation mode"
+for seed in 1 2 3; do
+    ~/cargo-make/makers ssim \
+        --seed "$seed" \
+        --kill-compute \
+        --upload-error-percent 5
+done
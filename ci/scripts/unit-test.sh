@@ -16,9 +16,15 @@ cargo doc --document-private-items --no-deps
 echo "--- Run rust failpoints test"
 cargo nextest run failpoints  --features failpoints --no-fail-fast
 
+echo "--- Run rust rocksdb local state store test"
+cargo nextest run -p risingwave_storage rocksdb --features rocksdb-local --no-fail-fast
+
 echo "--- Run rust doc check"
 cargo test --doc
 
+echo "--- Run sqllogictest on the embedded cluster"
+timeout 5m cargo run -p risingwave_sqllogictest -- './e2e_test/batch/**/*.slt' './e2e_test/streaming/**/*.slt'
+
 echo "--- Run rust test with coverage"
 cargo llvm-cov nextest --lcov --output-path lcov.info -- --no-fail-fast
 
@@ -26,4 +26,11 @@ export async function getFragments(){
 
 export async function getMaterializedViews(){
   return await api.get("/api/materialized_views");
-}
\ No newline at end of file
+}
+export async function getBarrierStatus(){
+  return await api.get("/api/barrier");
+}
+
+export async function getDdlJobs(){
+  return await api.get("/api/ddl_jobs");
+}
@@ -19,6 +19,7 @@ use risingwave_common::catalog::{Field, Schema, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
+use risingwave_common::util::chunk_coalesce::{DataChunkBuilder, SlicedDataChunk};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_source::SourceManagerRef;
 
@@ -27,6 +28,31 @@ use crate::executor::{
 };
 use crate::task::BatchTaskContext;
 
+/// The max number of rows written to the table source at once by `DELETE` and `UPDATE`.
+const DML_WRITE_BATCH_SIZE: usize = 8192;
+
+/// Coalesces the output of the child of a DML executor into chunks of up to
+/// [`DML_WRITE_BATCH_SIZE`] rows, so that the small chunks from selective scans and filters are
+/// written in a few large batches rather than one by one.
+#[try_stream(boxed, ok = DataChunk, error = RwError)]
+pub(super) async fn coalesce_for_write(child: BoxedExecutor) {
+    let mut builder = DataChunkBuilder::new(child.schema().data_types(), DML_WRITE_BATCH_SIZE);
+    #[for_await]
+    for data_chunk in child.execute() {
+        let mut sliced_data_chunk = Some(SlicedDataChunk::new_checked(data_chunk?)?);
+        while let Some(input) = sliced_data_chunk.take() {
+            let (left_data, output) = builder.append_chunk(input)?;
+            sliced_data_chunk = left_data;
+            if let Some(output) = output {
+                yield output;
+            }
+        }
+    }
+    if let Some(output) = builder.consume_all()? {
+        yield output;
+    }
+}
+
 /// [`DeleteExecutor`] implements table deletion with values from its child executor.
 // TODO: concurrent `DELETE` may cause problems. A scheduler might be required.
 pub struct DeleteExecutor {
@@ -76,10 +102,9 @@ impl DeleteExecutor {
         let mut notifiers = Vec::new();
 
         #[for_await]
-        for data_chunk in self.child.execute() {
+        for data_chunk in coalesce_for_write(self.child) {
             let data_chunk = data_chunk?;
             let len = data_chunk.cardinality();
-            assert!(data_chunk.visibility().is_none());
 
             let chunk = StreamChunk::from_parts(vec![Op::Delete; len], data_chunk);
 
@@ -179,11 +204,15 @@ mod tests {
             })
             .collect();
 
+        // The chunks are deleted in a single batch.
         mock_executor.add(DataChunk::from_pretty(
             "i  i
              1  2
              3  4
-             5  6
+             5  6",
+        ));
+        mock_executor.add(DataChunk::from_pretty(
+            "i  i
              7  8
              9 10",
         ));
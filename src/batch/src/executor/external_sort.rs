@@ -0,0 +1,373 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! External merge sort for the executors whose inputs may not fit in memory. The input is buffered
+//! until it exceeds the memory quota, then the buffered rows are sorted and spilled to the local
+//! disk as a sorted run. At last, all runs are merged into the sorted output. Rows are compared by
+//! the memcomparable encoding of `OrderedRow` on the order columns.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use futures::future::try_join_all;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_common::util::chunk_coalesce::DataChunkBuilder;
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_common::util::sort_util::OrderPair;
+
+use crate::executor::spill::{estimated_chunk_size, SpillFile, SpillReader};
+use crate::task::MemoryTracker;
+
+/// The max number of runs merged at once. Once there are more runs, each group of this many runs
+/// is merged into a longer run, with the groups merged concurrently.
+const MERGE_FAN_IN: usize = 16;
+
+/// Encodes the order columns of rows into memcomparable sort keys.
+#[derive(Clone)]
+struct SortKeyEncoder {
+    order_pairs: Arc<Vec<OrderPair>>,
+    serializer: OrderedRowSerializer,
+}
+
+impl SortKeyEncoder {
+    fn new(order_pairs: Arc<Vec<OrderPair>>) -> Self {
+        let serializer =
+            OrderedRowSerializer::new(order_pairs.iter().map(|pair| pair.order_type).collect_vec());
+        Self {
+            order_pairs,
+            serializer,
+        }
+    }
+
+    /// Encodes the keys of all rows of a compacted chunk.
+    fn encode_chunk(&self, data_chunk: &DataChunk) -> Vec<Vec<u8>> {
+        data_chunk
+            .rows()
+            .map(|row| {
+                let mut key = vec![];
+                self.serializer.serialize_datum_refs(
+                    self.order_pairs
+                        .iter()
+                        .map(|pair| row.value_at(pair.column_idx)),
+                    &mut key,
+                );
+                key
+            })
+            .collect()
+    }
+}
+
+/// Sorts its input, spilling sorted runs to the local disk when the input exceeds the memory quota.
+pub(crate) struct ExternalSorter {
+    encoder: SortKeyEncoder,
+    data_types: Vec<DataType>,
+    memory_quota: usize,
+    chunk_size: usize,
+    /// Only the rows in `offset..offset + limit` of the sorted output are returned if set.
+    limit: Option<(usize, usize)>,
+    buffered: Vec<DataChunk>,
+    buffered_size: usize,
+    /// Tracks the memory of the buffered chunks.
+    mem_tracker: MemoryTracker,
+    runs: Vec<SpillReader>,
+}
+
+impl ExternalSorter {
+    pub(crate) fn new(
+        order_pairs: Arc<Vec<OrderPair>>,
+        data_types: Vec<DataType>,
+        memory_quota: usize,
+        chunk_size: usize,
+        mem_tracker: MemoryTracker,
+    ) -> Self {
+        Self {
+            encoder: SortKeyEncoder::new(order_pairs),
+            data_types,
+            memory_quota,
+            chunk_size,
+            limit: None,
+            buffered: vec![],
+            buffered_size: 0,
+            mem_tracker,
+            runs: vec![],
+        }
+    }
+
+    /// Only returns `limit` rows after skipping the first `offset` rows. Each spilled run is
+    /// truncated to `limit + offset` rows then.
+    #[must_use]
+    pub(crate) fn with_limit(mut self, limit: usize, offset: usize) -> Self {
+        self.limit = Some((limit, offset));
+        self
+    }
+
+    /// The number of rows each run needs to keep.
+    fn run_size_limit(&self) -> Option<usize> {
+        self.limit.map(|(limit, offset)| limit + offset)
+    }
+
+    pub(crate) fn push_chunk(&mut self, data_chunk: DataChunk) -> Result<()> {
+        let data_chunk = data_chunk.compact()?;
+        if data_chunk.cardinality() == 0 {
+            return Ok(());
+        }
+        let chunk_size = estimated_chunk_size(&data_chunk);
+        self.mem_tracker.reserve(chunk_size)?;
+        self.buffered_size += chunk_size;
+        self.buffered.push(data_chunk);
+        if self.buffered_size > self.memory_quota {
+            self.spill_buffered()?;
+        }
+        Ok(())
+    }
+
+    /// Sorts the buffered rows and returns the rows in `skip..skip + take` of them.
+    fn sort_buffered(&mut self, skip: usize, take: Option<usize>) -> Result<Vec<DataChunk>> {
+        let chunks = std::mem::take(&mut self.buffered);
+        self.buffered_size = 0;
+
+        let mut keys = chunks
+            .iter()
+            .enumerate()
+            .flat_map(|(chunk_idx, data_chunk)| {
+                self.encoder
+                    .encode_chunk(data_chunk)
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(row_idx, key)| (key, chunk_idx, row_idx))
+            })
+            .collect_vec();
+        // The sort is stable, so that equal rows are kept in the order of input.
+        keys.sort_by(|(lhs, ..), (rhs, ..)| lhs.cmp(rhs));
+
+        let mut builder = DataChunkBuilder::new(self.data_types.clone(), self.chunk_size);
+        let mut output = vec![];
+        for (_, chunk_idx, row_idx) in keys.into_iter().skip(skip).take(take.unwrap_or(usize::MAX))
+        {
+            let row = chunks[chunk_idx].row_at_unchecked_vis(row_idx);
+            if let Some(data_chunk) = builder.append_one_row_ref(row)? {
+                output.push(data_chunk);
+            }
+        }
+        if let Some(data_chunk) = builder.consume_all()? {
+            output.push(data_chunk);
+        }
+        Ok(output)
+    }
+
+    /// Sorts the buffered rows and writes them into a new run.
+    fn spill_buffered(&mut self) -> Result<()> {
+        let sorted = self.sort_buffered(0, self.run_size_limit())?;
+        if sorted.is_empty() {
+            return Ok(());
+        }
+        let mut file = SpillFile::new()?;
+        for data_chunk in &sorted {
+            file.write_chunk(data_chunk)?;
+        }
+        self.runs.push(file.finish()?);
+        self.mem_tracker.release_all();
+        Ok(())
+    }
+
+    /// Reduces the runs to at most `MERGE_FAN_IN` by merging groups of them on blocking threads.
+    async fn merge_runs(&mut self) -> Result<()> {
+        while self.runs.len() > MERGE_FAN_IN {
+            let groups = self
+                .runs
+                .drain(..)
+                .chunks(MERGE_FAN_IN)
+                .into_iter()
+                .map(|group| group.collect_vec())
+                .collect_vec();
+            let merges = groups.into_iter().map(|runs| {
+                let encoder = self.encoder.clone();
+                let data_types = self.data_types.clone();
+                let chunk_size = self.chunk_size;
+                let run_size_limit = self.run_size_limit();
+                tokio::task::spawn_blocking(move || {
+                    let mut merger = RunMerger::new(runs, encoder, data_types, chunk_size)?;
+                    if let Some(limit) = run_size_limit {
+                        merger = merger.with_limit(limit, 0);
+                    }
+                    merger.merge_into_run()
+                })
+            });
+            self.runs = try_join_all(merges)
+                .await
+                .map_err(|e| InternalError(format!("failed to merge sorted runs: {}", e)))?
+                .into_iter()
+                .collect::<Result<_>>()?;
+        }
+        Ok(())
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    pub(crate) async fn into_stream(mut self) {
+        if self.runs.is_empty() {
+            let (skip, take) = match self.limit {
+                Some((limit, offset)) => (offset, Some(limit)),
+                None => (0, None),
+            };
+            for data_chunk in self.sort_buffered(skip, take)? {
+                yield data_chunk;
+            }
+        } else {
+            self.spill_buffered()?;
+            self.merge_runs().await?;
+
+            let runs = std::mem::take(&mut self.runs);
+            let mut merger = RunMerger::new(
+                runs,
+                self.encoder.clone(),
+                self.data_types.clone(),
+                self.chunk_size,
+            )?;
+            if let Some((limit, offset)) = self.limit {
+                merger = merger.with_limit(limit, offset);
+            }
+            while let Some(data_chunk) = merger.next_chunk()? {
+                yield data_chunk;
+            }
+        }
+    }
+}
+
+/// The position of the merge in a sorted run.
+struct RunCursor {
+    reader: SpillReader,
+    data_chunk: DataChunk,
+    keys: Vec<Vec<u8>>,
+    pos: usize,
+}
+
+impl RunCursor {
+    /// Returns `None` if the run is empty.
+    fn new(mut reader: SpillReader, encoder: &SortKeyEncoder) -> Result<Option<Self>> {
+        Ok(reader.read_chunk()?.map(|data_chunk| Self {
+            keys: encoder.encode_chunk(&data_chunk),
+            reader,
+            data_chunk,
+            pos: 0,
+        }))
+    }
+
+    /// Moves to the next row of the run. Returns false if the run is exhausted.
+    fn advance(&mut self, encoder: &SortKeyEncoder) -> Result<bool> {
+        self.pos += 1;
+        if self.pos < self.data_chunk.cardinality() {
+            return Ok(true);
+        }
+        match self.reader.read_chunk()? {
+            Some(data_chunk) => {
+                self.keys = encoder.encode_chunk(&data_chunk);
+                self.data_chunk = data_chunk;
+                self.pos = 0;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Merges sorted runs with a min-heap on the sort keys of their current rows. Equal rows are taken
+/// from the runs in the order of runs, so the merge is stable.
+struct RunMerger {
+    encoder: SortKeyEncoder,
+    cursors: Vec<RunCursor>,
+    heap: BinaryHeap<Reverse<(Vec<u8>, usize)>>,
+    builder: DataChunkBuilder,
+    skip: usize,
+    remaining: Option<usize>,
+}
+
+impl RunMerger {
+    fn new(
+        runs: Vec<SpillReader>,
+        encoder: SortKeyEncoder,
+        data_types: Vec<DataType>,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        let mut cursors = vec![];
+        for run in runs {
+            if let Some(cursor) = RunCursor::new(run, &encoder)? {
+                cursors.push(cursor);
+            }
+        }
+        let heap = cursors
+            .iter_mut()
+            .enumerate()
+            .map(|(idx, cursor)| Reverse((std::mem::take(&mut cursor.keys[0]), idx)))
+            .collect();
+        Ok(Self {
+            encoder,
+            cursors,
+            heap,
+            builder: DataChunkBuilder::new(data_types, chunk_size),
+            skip: 0,
+            remaining: None,
+        })
+    }
+
+    #[must_use]
+    fn with_limit(mut self, limit: usize, offset: usize) -> Self {
+        self.skip = offset;
+        self.remaining = Some(limit);
+        self
+    }
+
+    /// Returns the next chunk of the merged rows, or `None` if all are returned.
+    fn next_chunk(&mut self) -> Result<Option<DataChunk>> {
+        while self.remaining != Some(0) {
+            let idx = match self.heap.pop() {
+                Some(Reverse((_, idx))) => idx,
+                None => break,
+            };
+            let cursor = &mut self.cursors[idx];
+            let mut output = None;
+            if self.skip > 0 {
+                self.skip -= 1;
+            } else {
+                let row = cursor.data_chunk.row_at_unchecked_vis(cursor.pos);
+                output = self.builder.append_one_row_ref(row)?;
+                if let Some(remaining) = self.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+            }
+            if cursor.advance(&self.encoder)? {
+                let key = std::mem::take(&mut cursor.keys[cursor.pos]);
+                self.heap.push(Reverse((key, idx)));
+            }
+            if output.is_some() {
+                return Ok(output);
+            }
+        }
+        self.builder.consume_all()
+    }
+
+    /// Writes all merged rows into a new run.
+    fn merge_into_run(mut self) -> Result<SpillReader> {
+        let mut file = SpillFile::new()?;
+        while let Some(data_chunk) = self.next_chunk()? {
+            file.write_chunk(&data_chunk)?;
+        }
+        file.finish()
+    }
+}
@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::mem::size_of;
 use std::sync::Arc;
 use std::vec;
 
@@ -35,10 +36,13 @@ use risingwave_pb::batch_plan::HashAggNode;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchTaskContext, MemoryContext, TaskId};
 
 type AggHashMap<K> = HashMap<K, Vec<BoxedAggState>, PrecomputedBuildHasher>;
 
+/// A rough estimation of the size of an aggregation state on the heap.
+const ESTIMATED_AGG_STATE_SIZE: usize = 64;
+
 struct HashAggExecutorBuilderDispatcher;
 
 /// A dispatcher to help create specialized hash agg executor.
@@ -59,6 +63,7 @@ pub struct HashAggExecutorBuilder {
     schema: Schema,
     task_id: TaskId,
     identity: String,
+    memory_context: MemoryContext,
 }
 
 impl HashAggExecutorBuilder {
@@ -67,6 +72,7 @@ impl HashAggExecutorBuilder {
         child: BoxedExecutor,
         task_id: TaskId,
         identity: String,
+        memory_context: MemoryContext,
     ) -> Result<BoxedExecutor> {
         let group_key_columns = hash_agg_node
             .get_group_keys()
@@ -104,6 +110,7 @@ impl HashAggExecutorBuilder {
             schema: Schema { fields },
             task_id,
             identity,
+            memory_context,
         };
 
         Ok(HashAggExecutorBuilderDispatcher::dispatch_by_kind(
@@ -129,7 +136,13 @@ impl BoxedExecutorBuilder for HashAggExecutorBuilder {
         )?;
 
         let identity = source.plan_node().get_identity().clone();
-        Self::deserialize(hash_agg_node, child, source.task_id.clone(), identity)
+        Self::deserialize(
+            hash_agg_node,
+            child,
+            source.task_id.clone(),
+            identity,
+            source.context().memory_context(),
+        )
     }
 }
 
@@ -145,6 +158,7 @@ pub(crate) struct HashAggExecutor<K> {
     group_key_types: Vec<DataType>,
     schema: Schema,
     identity: String,
+    memory_context: MemoryContext,
     _phantom: PhantomData<K>,
 }
 
@@ -157,6 +171,7 @@ impl<K> HashAggExecutor<K> {
             group_key_types: builder.group_key_types,
             schema: builder.schema,
             identity: builder.identity,
+            memory_context: builder.memory_context,
             _phantom: PhantomData,
         }
     }
@@ -181,6 +196,10 @@ impl<K: HashKey + Send + Sync> HashAggExecutor<K> {
     async fn do_execute(self: Box<Self>) {
         // hash map for each agg groups
         let mut groups = AggHashMap::<K>::default();
+        let mut mem_tracker = self.memory_context.new_tracker();
+        let group_size = size_of::<K>()
+            + size_of::<Vec<BoxedAggState>>()
+            + self.agg_factories.len() * (size_of::<BoxedAggState>() + ESTIMATED_AGG_STATE_SIZE);
 
         // consume all chunks to compute the agg result
         #[for_await]
@@ -188,6 +207,9 @@ impl<K: HashKey + Send + Sync> HashAggExecutor<K> {
             let chunk = chunk?.compact()?;
             let keys = K::build(self.group_key_columns.as_slice(), &chunk)?;
             for (row_id, key) in keys.into_iter().enumerate() {
+                if !groups.contains_key(&key) {
+                    mem_tracker.reserve(group_size)?;
+                }
                 let mut err_flag = Ok(());
                 let states: &mut Vec<BoxedAggState> = groups.entry(key).or_insert_with(|| {
                     self.agg_factories
@@ -255,7 +277,9 @@ impl<K: HashKey + Send + Sync> HashAggExecutor<K> {
 
 #[cfg(test)]
 mod tests {
+    use futures::StreamExt;
     use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::error::ErrorCode;
     use risingwave_common::test_prelude::DataChunkTestExt;
     use risingwave_pb::data::data_type::TypeName;
     use risingwave_pb::data::DataType as ProstDataType;
@@ -317,6 +341,7 @@ mod tests {
             Box::new(src_exec),
             TaskId::default(),
             "HashAggExecutor".to_string(),
+            MemoryContext::default(),
         )
         .unwrap();
 
@@ -382,6 +407,7 @@ mod tests {
             Box::new(src_exec),
             TaskId::default(),
             "HashAggExecutor".to_string(),
+            MemoryContext::default(),
         )
         .unwrap();
         let schema = Schema {
@@ -397,4 +423,47 @@ mod tests {
         );
         diff_executor_output(actual_exec, Box::new(expect_exec)).await;
     }
+
+    #[tokio::test]
+    async fn execute_out_of_memory() {
+        let t32 = DataType::Int32;
+        let src_exec = MockExecutor::with_chunk(
+            DataChunk::from_pretty(
+                "i
+                 0
+                 1
+                 2",
+            ),
+            Schema {
+                fields: vec![Field::unnamed(t32)],
+            },
+        );
+
+        let agg_call = AggCall {
+            r#type: Type::Count as i32,
+            args: vec![],
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Int64 as i32,
+                ..Default::default()
+            }),
+            distinct: false,
+        };
+
+        let agg_prost = HashAggNode {
+            group_keys: vec![0],
+            agg_calls: vec![agg_call],
+        };
+
+        let actual_exec = HashAggExecutorBuilder::deserialize(
+            &agg_prost,
+            Box::new(src_exec),
+            TaskId::default(),
+            "HashAggExecutor".to_string(),
+            MemoryContext::new(1),
+        )
+        .unwrap();
+
+        let err = actual_exec.execute().next().await.unwrap().unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::QueryOutOfMemory(_)));
+    }
 }
@@ -126,7 +126,7 @@ impl HopWindowExecutor {
             .exact_div(&window_slide)
             .and_then(|x| NonZeroUsize::new(usize::try_from(x).ok()?))
             .ok_or_else(|| {
-                RwError::from(ErrorCode::InternalError(format!(
+                RwError::from(ErrorCode::InvalidParameterValue(format!(
                     "window_size {} cannot be divided by window_slide {}",
                     window_size, window_slide
                 )))
@@ -145,7 +145,7 @@ impl HopWindowExecutor {
         // tumble_start(`time_col` - (`window_size` - `window_slide`), `window_slide`).
         // Let's pre calculate (`window_size` - `window_slide`).
         let window_size_sub_slide = window_size.checked_sub(&window_slide).ok_or_else(|| {
-            RwError::from(ErrorCode::InternalError(format!(
+            RwError::from(ErrorCode::InvalidParameterValue(format!(
                 "window_size {} cannot be subtracted by window_slide {}",
                 window_size, window_slide
             )))
@@ -172,7 +172,7 @@ impl HopWindowExecutor {
 
         for i in 0..units {
             let window_start_offset = window_slide.checked_mul_int(i).ok_or_else(|| {
-                RwError::from(ErrorCode::InternalError(format!(
+                RwError::from(ErrorCode::InvalidParameterValue(format!(
                     "window_slide {} cannot be multiplied by {}",
                     window_slide, i
                 )))
@@ -183,7 +183,7 @@ impl HopWindowExecutor {
             )
             .boxed();
             let window_end_offset = window_slide.checked_mul_int(i + units).ok_or_else(|| {
-                RwError::from(ErrorCode::InternalError(format!(
+                RwError::from(ErrorCode::InvalidParameterValue(format!(
                     "window_slide {} cannot be multiplied by {}",
                     window_slide, i
                 )))
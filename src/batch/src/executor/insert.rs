@@ -12,20 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::iter::once;
 
 use futures::future::try_join_all;
 use futures_async_stream::try_stream;
+use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{
-    ArrayBuilder, DataChunk, I64ArrayBuilder, Op, PrimitiveArrayBuilder, StreamChunk,
+    ArrayBuilder, DataChunk, I64ArrayBuilder, Op, PrimitiveArrayBuilder, Row, StreamChunk,
 };
-use risingwave_common::catalog::{Field, Schema, TableId};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::{ColumnDesc, Field, Schema, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_common::util::sort_util::OrderPair;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::plan_common::ConflictBehavior;
 use risingwave_source::SourceManagerRef;
+use risingwave_storage::table::cell_based_table::CellBasedTable;
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStoreImpl};
 
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
@@ -41,6 +49,12 @@ pub struct InsertExecutor {
     child: BoxedExecutor,
     schema: Schema,
     identity: String,
+
+    /// Set if rows with an existing primary key should be skipped or reported.
+    conflict_check: Option<PkConflictCheck>,
+
+    /// Indices of the columns of the child which must not be null.
+    not_null_columns: Vec<usize>,
 }
 
 impl InsertExecutor {
@@ -53,8 +67,102 @@ impl InsertExecutor {
                 fields: vec![Field::unnamed(DataType::Int64)],
             },
             identity: "InsertExecutor".to_string(),
+            conflict_check: None,
+            not_null_columns: vec![],
         }
     }
+
+    #[must_use]
+    pub fn with_not_null_columns(mut self, not_null_columns: Vec<usize>) -> Self {
+        self.not_null_columns = not_null_columns;
+        self
+    }
+
+    #[must_use]
+    pub fn with_conflict_check(mut self, conflict_check: PkConflictCheck) -> Self {
+        self.conflict_check = Some(conflict_check);
+        self
+    }
+}
+
+/// Looks up inserted rows in the materialized table of a table with a user-defined primary key.
+/// Rows whose key already exists, either in the table or earlier in the same statement, are
+/// skipped or reported as an error.
+pub struct PkConflictCheck {
+    /// Id of the materialized table.
+    table_id: TableId,
+    state_store: StateStoreImpl,
+    column_descs: Vec<ColumnDesc>,
+    /// Pk of the materialized table, whose indices also refer to the inserted rows with the
+    /// row id column.
+    pk: Vec<OrderPair>,
+    /// Whether to skip conflicting rows instead of failing.
+    ignore_conflict: bool,
+    epoch: u64,
+}
+
+impl PkConflictCheck {
+    pub fn new(
+        table_id: TableId,
+        state_store: StateStoreImpl,
+        column_descs: Vec<ColumnDesc>,
+        pk: Vec<OrderPair>,
+        ignore_conflict: bool,
+        epoch: u64,
+    ) -> Self {
+        Self {
+            table_id,
+            state_store,
+            column_descs,
+            pk,
+            ignore_conflict,
+            epoch,
+        }
+    }
+
+    /// Removes the conflicting rows from `chunk`. `seen` collects the keys inserted by the
+    /// statement so far.
+    async fn apply(&self, chunk: DataChunk, seen: &mut HashSet<Row>) -> Result<DataChunk> {
+        let order_types = self.pk.iter().map(|p| p.order_type).collect();
+        dispatch_state_store!(&self.state_store, state_store, {
+            let keyspace = Keyspace::table_root(state_store.clone(), &self.table_id);
+            let table = CellBasedTable::new(
+                keyspace,
+                self.column_descs.clone(),
+                Some(OrderedRowSerializer::new(order_types)),
+                state_store.stats(),
+                None,
+            );
+
+            let mut visibility = Vec::with_capacity(chunk.cardinality());
+            for idx in 0..chunk.cardinality() {
+                let key = Row(self
+                    .pk
+                    .iter()
+                    .map(|p| chunk.column_at(p.column_idx).array_ref().datum_at(idx))
+                    .collect_vec());
+                let conflict =
+                    seen.contains(&key) || table.get_row(&key, self.epoch).await?.is_some();
+                if conflict && !self.ignore_conflict {
+                    let key = key
+                        .0
+                        .iter()
+                        .map(|d| d.as_ref().map_or("NULL".to_string(), |d| d.to_string()))
+                        .join(", ");
+                    return Err(
+                        ErrorCode::UniqueViolation(format!("({}) already exists", key)).into(),
+                    );
+                }
+                if !conflict {
+                    seen.insert(key);
+                }
+                visibility.push(!conflict);
+            }
+            chunk
+                .with_visibility(Bitmap::try_from(visibility)?)
+                .compact()
+        })
+    }
 }
 
 impl Executor for InsertExecutor {
@@ -78,12 +186,30 @@ impl InsertExecutor {
         let source = source_desc.source.as_table_v2().expect("not table source");
 
         let mut notifiers = Vec::new();
+        let mut seen_keys = HashSet::new();
 
         #[for_await]
         for data_chunk in self.child.execute() {
-            let data_chunk = data_chunk?;
+            // The chunks of `INSERT ... SELECT` may be filtered.
+            let data_chunk = data_chunk?.compact()?;
             let len = data_chunk.cardinality();
-            assert!(data_chunk.visibility().is_none());
+            if len == 0 {
+                continue;
+            }
+
+            for &idx in &self.not_null_columns {
+                if data_chunk
+                    .column_at(idx)
+                    .array_ref()
+                    .null_bitmap()
+                    .num_high_bits()
+                    != len
+                {
+                    // The row id column comes first in the table.
+                    let name = source_desc.columns[idx + 1].name.clone();
+                    return Err(ErrorCode::NotNullViolation(name).into());
+                }
+            }
 
             // add row-id column as first column
             let mut builder = I64ArrayBuilder::new(len).unwrap();
@@ -97,6 +223,19 @@ impl InsertExecutor {
             // Materialize plan is assembled manually with Rust frontend, so we put the row
             // id column to the first.
             let columns = rowid_column.chain(child_columns).collect();
+            let (columns, len) = match &self.conflict_check {
+                Some(conflict_check) => {
+                    let data_chunk = conflict_check
+                        .apply(DataChunk::new(columns, len), &mut seen_keys)
+                        .await?;
+                    let len = data_chunk.cardinality();
+                    if len == 0 {
+                        continue;
+                    }
+                    (data_chunk.into_parts().0, len)
+                }
+                None => (columns, len),
+            };
             let chunk = StreamChunk::new(vec![Op::Insert; len], columns, None);
 
             let notifier = source.write_chunk(chunk)?;
@@ -146,14 +285,47 @@ impl BoxedExecutorBuilder for InsertExecutor {
         })?;
         let child = source.clone_for_plan(proto_child).build().await?;
 
-        Ok(Box::new(Self::new(
+        let executor = Self::new(
             table_id,
             source
                 .context()
                 .source_manager_ref()
                 .ok_or_else(|| InternalError("Source manager not found".to_string()))?,
             child,
-        )))
+        )
+        .with_not_null_columns(
+            insert_node
+                .not_null_column_indices
+                .iter()
+                .map(|idx| *idx as usize)
+                .collect(),
+        );
+
+        let ignore_conflict = match insert_node.get_conflict_behavior()? {
+            ConflictBehavior::NoCheck | ConflictBehavior::Overwrite => {
+                return Ok(Box::new(executor));
+            }
+            ConflictBehavior::IgnoreConflict => true,
+            ConflictBehavior::ErrorOnConflict => false,
+        };
+        let conflict_check = PkConflictCheck::new(
+            TableId::new(insert_node.table_id),
+            source.context().try_get_state_store()?,
+            insert_node
+                .column_descs
+                .iter()
+                .map(|column_desc| ColumnDesc::from(column_desc.clone()))
+                .collect(),
+            insert_node
+                .pk_orders
+                .iter()
+                .map(OrderPair::from_prost)
+                .collect(),
+            ignore_conflict,
+            source.epoch,
+        );
+
+        Ok(Box::new(executor.with_conflict_check(conflict_check)))
     }
 }
 
@@ -310,4 +482,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert_executor_filtered_input() -> Result<()> {
+        let source_manager = Arc::new(MemSourceManager::default());
+
+        let schema = schema_test_utils::ii();
+        let mut mock_executor = MockExecutor::new(schema.clone());
+        let table_columns: Vec<_> = schema_test_utils::iii()
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| ColumnDesc {
+                data_type: f.data_type.clone(),
+                column_id: ColumnId::from(i as i32),
+                name: format!("v{}", i),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            })
+            .collect();
+        let table_id = TableId::new(0);
+        source_manager.create_table_source(&table_id, table_columns)?;
+
+        // The rows filtered out, e.g., by the WHERE clause of `INSERT ... SELECT`, are not
+        // inserted.
+        let col1 = column_nonnull! { I32Array, [1, 3, 5] };
+        let col2 = column_nonnull! { I32Array, [2, 4, 6] };
+        mock_executor.add(
+            DataChunk::builder()
+                .columns(vec![col1, col2])
+                .build()
+                .with_visibility(Bitmap::try_from(vec![true, false, true])?),
+        );
+
+        let source_desc = source_manager.get_source(&table_id)?;
+        let source = source_desc.source.as_table_v2().unwrap();
+        let mut reader = source
+            .stream_reader(vec![0.into(), 1.into(), 2.into()])
+            .await?;
+
+        let insert_executor = Box::new(InsertExecutor::new(
+            table_id,
+            source_manager.clone(),
+            Box::new(mock_executor),
+        ));
+        let handle = tokio::spawn(async move {
+            let mut stream = insert_executor.execute();
+            let result = stream.next().await.unwrap().unwrap();
+            assert_eq!(
+                result.column_at(0).array().as_int64().iter().collect_vec(),
+                vec![Some(2)]
+            );
+        });
+
+        let chunk = reader.next().await?;
+        assert_eq!(
+            chunk.chunk.columns()[1]
+                .array()
+                .as_int32()
+                .iter()
+                .collect_vec(),
+            vec![Some(1), Some(5)]
+        );
+        assert_eq!(
+            chunk.chunk.columns()[2]
+                .array()
+                .as_int32()
+                .iter()
+                .collect_vec(),
+            vec![Some(2), Some(6)]
+        );
+
+        handle.await.unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_executor_not_null() -> Result<()> {
+        let source_manager = Arc::new(MemSourceManager::default());
+
+        let schema = schema_test_utils::ii();
+        let mut mock_executor = MockExecutor::new(schema.clone());
+        let table_columns: Vec<_> = schema_test_utils::iii()
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| ColumnDesc {
+                data_type: f.data_type.clone(),
+                column_id: ColumnId::from(i as i32),
+                name: format!("v{}", i),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            })
+            .collect();
+        let table_id = TableId::new(0);
+        source_manager.create_table_source(&table_id, table_columns)?;
+
+        let col1 = column_nonnull! { I32Array, [1, 3] };
+        let col2 = Column::new(Arc::new(array! { I32Array, [Some(2), None] }.into()));
+        mock_executor.add(DataChunk::builder().columns(vec![col1, col2]).build());
+
+        let insert_executor = Box::new(
+            InsertExecutor::new(table_id, source_manager.clone(), Box::new(mock_executor))
+                .with_not_null_columns(vec![0, 1]),
+        );
+        let mut stream = insert_executor.execute();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(
+            matches!(err.inner(), ErrorCode::NotNullViolation(name) if name == "v2"),
+            "{}",
+            err
+        );
+
+        Ok(())
+    }
 }
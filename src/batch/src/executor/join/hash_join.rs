@@ -14,24 +14,28 @@
 
 use std::convert::TryInto;
 use std::marker::PhantomData;
+use std::mem::size_of;
 
 use futures::StreamExt;
 use futures_async_stream::try_stream;
+use itertools::Itertools;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
 use risingwave_common::types::DataType;
-use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_common::util::chunk_coalesce::{DataChunkBuilder, DEFAULT_CHUNK_BUFFER_SIZE};
 use risingwave_expr::expr::{build_from_prost, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
+use crate::executor::join::hash_join_spill::{SpillWriter, SPILL_PARTITION_COUNT};
 use crate::executor::join::hash_join_state::{BuildTable, ProbeTable};
-use crate::executor::join::JoinType;
+use crate::executor::join::{JoinType, RowId};
+use crate::executor::spill::estimated_chunk_size;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchTaskContext, MemoryContext, MemoryTracker, TaskId};
 
 /// Parameters of equi-join.
 ///
@@ -72,6 +76,10 @@ pub(super) struct HashJoinExecutor<K> {
     params: EquiJoinParams,
     schema: Schema,
     identity: String,
+    /// The max size in bytes of the build side kept in memory. Both sides are spilled to disk
+    /// once exceeded.
+    memory_quota: usize,
+    memory_context: MemoryContext,
     _phantom: PhantomData<K>,
 }
 
@@ -135,16 +143,107 @@ impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(mut self: Box<Self>) {
         let mut right_child_stream = self.right_child.take().unwrap().execute();
+        let build_key_columns = self.params.build_key_columns().to_vec();
+        let probe_key_columns = self.params.probe_key_columns().to_vec();
         let mut build_table = BuildTable::with_params(self.params);
-
+        let mut mem_tracker = self.memory_context.new_tracker();
+        // Probing with a selective join condition may produce many tiny chunks, which are
+        // coalesced so that the downstream sees well-sized batches.
+        let mut output_builder = DataChunkBuilder::with_default_size(self.schema.data_types());
+
+        // Keep the build side in memory until it exceeds the quota, then spill all of it.
+        let mut build_size = 0;
+        let mut build_spill_writer = None;
         while let Some(chunk) = right_child_stream.next().await {
             let chunk = chunk?;
+            if let Some(writer) = build_spill_writer.as_mut() {
+                writer.write_chunk(chunk)?;
+                continue;
+            }
+            let chunk_size = estimated_chunk_size(&chunk);
+            mem_tracker.reserve(chunk_size)?;
+            build_size += chunk_size;
             build_table.append_build_chunk(chunk)?;
+            if build_size > self.memory_quota {
+                tracing::info!(
+                    "{} spills to disk since its build side exceeds {} bytes",
+                    self.identity,
+                    self.memory_quota
+                );
+                let mut writer =
+                    SpillWriter::new(build_key_columns.clone(), SPILL_PARTITION_COUNT)?;
+                for chunk in build_table.take_build_data() {
+                    writer.write_chunk(chunk)?;
+                }
+                mem_tracker.release_all();
+                build_spill_writer = Some(writer);
+            }
+        }
+
+        let left_child_stream = self.left_child.take().unwrap().execute();
+        match build_spill_writer {
+            None => {
+                Self::reserve_hash_table(&mut mem_tracker, &build_table)?;
+                let mut probe_table: ProbeTable<K> = build_table.try_into()?;
+                #[for_await]
+                for chunk in Self::probe(&mut probe_table, left_child_stream) {
+                    for output in output_builder.push_chunk(chunk?)? {
+                        yield output;
+                    }
+                }
+            }
+            Some(build_spill_writer) => {
+                // Partition the probe side in the same way, and join the partitions pair by pair.
+                let mut probe_spill_writer =
+                    SpillWriter::new(probe_key_columns, SPILL_PARTITION_COUNT)?;
+                #[for_await]
+                for chunk in left_child_stream {
+                    probe_spill_writer.write_chunk(chunk?)?;
+                }
+
+                let mut params = build_table.into_params();
+                for (mut build_reader, probe_reader) in build_spill_writer
+                    .finish()?
+                    .into_iter()
+                    .zip_eq(probe_spill_writer.finish()?)
+                {
+                    let mut build_table = BuildTable::with_params(params);
+                    while let Some(chunk) = build_reader.read_chunk()? {
+                        mem_tracker.reserve(estimated_chunk_size(&chunk))?;
+                        build_table.append_build_chunk(chunk)?;
+                    }
+                    Self::reserve_hash_table(&mut mem_tracker, &build_table)?;
+                    let mut probe_table: ProbeTable<K> = build_table.try_into()?;
+                    #[for_await]
+                    for chunk in Self::probe(&mut probe_table, probe_reader.into_stream()) {
+                        for output in output_builder.push_chunk(chunk?)? {
+                            yield output;
+                        }
+                    }
+                    params = probe_table.into_params();
+                    mem_tracker.release_all();
+                }
+            }
         }
-        let mut probe_table: ProbeTable<K> = build_table.try_into()?;
+        if let Some(output) = output_builder.consume_all()? {
+            yield output;
+        }
+    }
 
+    /// Reserves the memory of the hash table to be built on the build side, roughly estimated by
+    /// its number of rows.
+    fn reserve_hash_table(mem_tracker: &mut MemoryTracker, build_table: &BuildTable) -> Result<()> {
+        let entry_size = size_of::<(K, RowId)>() + size_of::<Option<RowId>>();
+        mem_tracker.reserve(build_table.row_count() * entry_size)
+    }
+
+    /// Probes the table built from the build side with the chunks of the probe side.
+    #[try_stream(ok = DataChunk, error = RwError)]
+    async fn probe<'a>(
+        probe_table: &'a mut ProbeTable<K>,
+        mut left_child_stream: BoxedDataChunkStream,
+    ) {
         let mut state = HashJoinState::Probe;
-        let mut left_child_stream = self.left_child.take().unwrap().execute();
 
         // first probe
         match left_child_stream.next().await {
@@ -153,7 +252,12 @@ impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
                 probe_table.set_probe_data(data_chunk)?;
             }
             None => {
-                state = HashJoinState::Done;
+                // The rows of the build side may still need to be output without any probe rows.
+                if probe_table.join_type().need_join_remaining() {
+                    state = HashJoinState::ProbeRemaining;
+                } else {
+                    state = HashJoinState::Done;
+                }
             }
         }
         // probe
@@ -245,6 +349,8 @@ impl<K> HashJoinExecutor<K> {
         params: EquiJoinParams,
         schema: Schema,
         identity: String,
+        memory_quota: usize,
+        memory_context: MemoryContext,
     ) -> Self {
         HashJoinExecutor {
             left_child: Some(left_child),
@@ -252,6 +358,8 @@ impl<K> HashJoinExecutor<K> {
             params,
             schema,
             identity,
+            memory_quota,
+            memory_context,
             _phantom: PhantomData,
         }
     }
@@ -263,6 +371,8 @@ pub struct HashJoinExecutorBuilder {
     right_child: BoxedExecutor,
     schema: Schema,
     task_id: TaskId,
+    memory_quota: usize,
+    memory_context: MemoryContext,
 }
 
 struct HashJoinExecutorBuilderDispatcher;
@@ -279,6 +389,8 @@ impl HashKeyDispatcher for HashJoinExecutorBuilderDispatcher {
             input.params,
             input.schema,
             format!("HashJoinExecutor{:?}", input.task_id),
+            input.memory_quota,
+            input.memory_context,
         ))
     }
 }
@@ -371,6 +483,8 @@ impl BoxedExecutorBuilder for HashJoinExecutorBuilder {
                 fields: schema_fields,
             },
             task_id: context.task_id.clone(),
+            memory_quota: context.context().hash_join_memory_quota(),
+            memory_context: context.context().memory_context(),
         };
 
         Ok(HashJoinExecutorBuilderDispatcher::dispatch_by_kind(
@@ -387,7 +501,7 @@ mod tests {
     use futures::StreamExt;
     use itertools::Itertools;
     use risingwave_common::array::column::Column;
-    use risingwave_common::array::{ArrayBuilderImpl, DataChunk};
+    use risingwave_common::array::{ArrayBuilderImpl, DataChunk, Row};
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::error::Result;
     use risingwave_common::hash::Key32;
@@ -401,6 +515,7 @@ mod tests {
     use crate::executor::join::JoinType;
     use crate::executor::test_utils::MockExecutor;
     use crate::executor::BoxedExecutor;
+    use crate::task::MemoryContext;
     struct DataChunkMerger {
         data_types: Vec<DataType>,
         array_builders: Vec<ArrayBuilderImpl>,
@@ -456,6 +571,7 @@ mod tests {
         left_types: Vec<DataType>,
         right_types: Vec<DataType>,
         join_type: JoinType,
+        memory_quota: usize,
     }
 
     /// Sql for creating test data:
@@ -480,9 +596,16 @@ mod tests {
                 left_types: vec![DataType::Int32, DataType::Float32],
                 right_types: vec![DataType::Int32, DataType::Float64],
                 join_type,
+                memory_quota: usize::MAX,
             }
         }
 
+        /// Spill both sides to disk from the first chunk on.
+        fn with_spill(mut self) -> Self {
+            self.memory_quota = 0;
+            self
+        }
+
         fn create_left_executor(&self) -> BoxedExecutor {
             let schema = Schema {
                 fields: vec![
@@ -637,6 +760,8 @@ mod tests {
                 params,
                 schema,
                 "HashJoinExecutor2".to_string(),
+                self.memory_quota,
+                MemoryContext::default(),
             )) as BoxedExecutor
         }
 
@@ -691,6 +816,46 @@ mod tests {
             // assert_eq!(expected, result_chunk);
             assert!(is_data_chunk_eq(&expected, &output_chunk));
         }
+
+        async fn sorted_output_rows(&self, has_non_equi_cond: bool) -> Vec<Row> {
+            let mut stream = self.create_join_executor(has_non_equi_cond).execute();
+            let mut rows = vec![];
+            while let Some(data_chunk) = stream.next().await {
+                let data_chunk = data_chunk.unwrap().compact().unwrap();
+                rows.extend(data_chunk.rows().map(|row| row.to_owned_row()));
+            }
+            rows.sort();
+            rows
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_with_spill() {
+        for join_type in [
+            JoinType::Inner,
+            JoinType::LeftOuter,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightOuter,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::FullOuter,
+        ] {
+            for has_non_equi_cond in [false, true] {
+                let expected = TestFixture::with_join_type(join_type)
+                    .sorted_output_rows(has_non_equi_cond)
+                    .await;
+                let spilled = TestFixture::with_join_type(join_type)
+                    .with_spill()
+                    .sorted_output_rows(has_non_equi_cond)
+                    .await;
+                assert_eq!(
+                    expected, spilled,
+                    "{:?} join with non-equi condition: {}",
+                    join_type, has_non_equi_cond
+                );
+            }
+        }
     }
 
     /// Sql:
@@ -0,0 +1,135 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Spilling of hash join inputs. Once the build side of a hash join exceeds its memory quota, both
+//! sides are split into partitions by the hash of the join keys and written to temporary files on
+//! the local disk. Rows with equal keys always land in the partitions of the same index, so each
+//! pair of partitions can be joined in memory one after another.
+
+use risingwave_common::array::DataChunk;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::Result;
+use risingwave_common::hash::HashCode;
+use risingwave_common::util::hash_util::CRC32FastBuilder;
+
+use crate::executor::spill::{SpillFile, SpillReader};
+
+/// The number of partitions each input is split into once spilled.
+pub(super) const SPILL_PARTITION_COUNT: usize = 16;
+
+/// The partition of a row. The low bits of the hash code are left to the hash table built from
+/// the partition, otherwise all keys in one partition would fall into the same buckets.
+fn partition_of(hash_code: &HashCode, partition_count: usize) -> usize {
+    (hash_code.hash_code() >> 32) as usize % partition_count
+}
+
+/// Writes the chunks of one join input into partitioned temporary files.
+pub(super) struct SpillWriter {
+    key_columns: Vec<usize>,
+    files: Vec<SpillFile>,
+}
+
+impl SpillWriter {
+    pub(super) fn new(key_columns: Vec<usize>, partition_count: usize) -> Result<Self> {
+        let files = (0..partition_count)
+            .map(|_| SpillFile::new())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { key_columns, files })
+    }
+
+    pub(super) fn write_chunk(&mut self, data_chunk: DataChunk) -> Result<()> {
+        let data_chunk = data_chunk.compact()?;
+        if data_chunk.cardinality() == 0 {
+            return Ok(());
+        }
+
+        let partition_count = self.files.len();
+        let partitions = data_chunk
+            .get_hash_values(&self.key_columns, CRC32FastBuilder)?
+            .iter()
+            .map(|hash_code| partition_of(hash_code, partition_count))
+            .collect::<Vec<_>>();
+
+        for (partition, file) in self.files.iter_mut().enumerate() {
+            let visibility = partitions
+                .iter()
+                .map(|p| *p == partition)
+                .collect::<Vec<_>>();
+            let partition_chunk = data_chunk
+                .with_visibility(Bitmap::try_from(visibility)?)
+                .compact()?;
+            if partition_chunk.cardinality() == 0 {
+                continue;
+            }
+            file.write_chunk(&partition_chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Finishes writing and returns the readers of all partitions, in the order of partitions.
+    pub(super) fn finish(self) -> Result<Vec<SpillReader>> {
+        self.files.into_iter().map(SpillFile::finish).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::test_prelude::DataChunkTestExt;
+    use risingwave_common::types::ScalarImpl;
+
+    use super::*;
+
+    #[test]
+    fn test_spill_partitions() {
+        let mut writer = SpillWriter::new(vec![0], 4).unwrap();
+        writer
+            .write_chunk(DataChunk::from_pretty(
+                "i f
+                 1 6.1
+                 2 .
+                 . 8.4
+                 1 3.9",
+            ))
+            .unwrap();
+        writer
+            .write_chunk(DataChunk::from_pretty(
+                "i f
+                 2 6.6
+                 3 .",
+            ))
+            .unwrap();
+
+        let mut partitions_of_keys = vec![vec![]; 4];
+        let mut row_count = 0;
+        for (partition, mut reader) in writer.finish().unwrap().into_iter().enumerate() {
+            while let Some(data_chunk) = reader.read_chunk().unwrap() {
+                for row in data_chunk.rows() {
+                    row_count += 1;
+                    partitions_of_keys[partition]
+                        .push(row.value_at(0).map(|v| v.into_scalar_impl()));
+                }
+            }
+        }
+        assert_eq!(row_count, 6);
+
+        // Rows with the same key are in the same partition.
+        for key in [1, 2, 3] {
+            let key = Some(ScalarImpl::Int32(key));
+            let partitions = partitions_of_keys
+                .iter()
+                .filter(|keys| keys.contains(&key))
+                .count();
+            assert_eq!(partitions, 1);
+        }
+    }
+}
@@ -61,6 +61,20 @@ impl BuildTable {
         Ok(())
     }
 
+    pub(super) fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Takes all chunks appended so far, e.g., to spill them out.
+    pub(super) fn take_build_data(&mut self) -> Vec<DataChunk> {
+        self.row_count = 0;
+        mem::take(&mut self.build_data)
+    }
+
+    pub(super) fn into_params(self) -> EquiJoinParams {
+        self.params
+    }
+
     fn build_hash_map<K: HashKey>(&self) -> Result<(ChunkedData<Option<RowId>>, JoinHashMap<K>)> {
         let mut hash_map =
             JoinHashMap::with_capacity_and_hasher(self.row_count, PrecomputedBuildHasher);
@@ -221,6 +235,11 @@ impl<K: HashKey> TryFrom<BuildTable> for ProbeTable<K> {
 }
 
 impl<K: HashKey> ProbeTable<K> {
+    /// Returns the parameters for building the table of the next spilled partition.
+    pub(super) fn into_params(self) -> EquiJoinParams {
+        self.params
+    }
+
     pub(super) fn build_data_empty(&self) -> bool {
         self.build_data.is_empty()
     }
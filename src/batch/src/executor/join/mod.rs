@@ -14,9 +14,9 @@
 
 mod chunked_data;
 pub mod hash_join;
+mod hash_join_spill;
 mod hash_join_state;
 pub mod nested_loop_join;
-mod row_level_iter;
 mod sort_merge_join;
 
 pub use chunked_data::*;
@@ -12,86 +12,62 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::option::Option::Some;
+use std::ops::Range;
 use std::sync::Arc;
 
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::column::Column;
-use risingwave_common::array::data_chunk_iter::RowRef;
-use risingwave_common::array::{ArrayBuilderImpl, DataChunk, Row};
+use risingwave_common::array::{ArrayBuilderImpl, DataChunk};
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::{DataType, DatumRef};
-use risingwave_common::util::chunk_coalesce::{DataChunkBuilder, SlicedDataChunk};
+use risingwave_common::util::chunk_coalesce::{
+    DataChunkBuilder, SlicedDataChunk, DEFAULT_CHUNK_BUFFER_SIZE,
+};
 use risingwave_expr::expr::{build_from_prost as expr_build_from_prost, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
-use crate::executor::join::chunked_data::RowId;
-use crate::executor::join::row_level_iter::RowLevelIter;
+use crate::executor::join::chunked_data::{ChunkedData, RowId};
 use crate::executor::join::JoinType;
+use crate::executor::spill::estimated_chunk_size;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::BatchTaskContext;
+use crate::task::{BatchTaskContext, MemoryContext};
 
-/// Nested loop join executor.
-///
+/// Block nested loop join executor. It's only used for joins without any equal condition, for
+/// which a hash join is not applicable.
 ///
 /// High Level Idea:
-/// 1. Iterate tuple from probe table.
-/// 2. Concatenated with inner chunk, eval expression and get sel vector
-/// 3. Create new chunk with new sel vector and yield to upper.
+/// 1. Load all chunks of the build side (right) into memory.
+/// 2. For each chunk of the probe side (left), join it with one build chunk at a time: build the
+///    cross product of a block of probe rows and the build chunk, and evaluate the join condition
+///    on the whole product to get the visibility of the joined rows. The blocks are sized to keep
+///    the products around the size of a chunk.
+/// 3. Mark the matched probe rows and build rows to output the unmatched ones of outer, semi and
+///    anti joins.
 pub struct NestedLoopJoinExecutor {
     /// Expression to eval join condition
     join_expr: BoxedExpression,
     /// Executor should handle different join type.
     join_type: JoinType,
     schema: Schema,
-    /// Return data chunk in batch.
-    chunk_builder: DataChunkBuilder,
-    /// Cache the chunk that has not been written into chunk builder
-    /// during join probing. Flush it in begin of execution.
-    last_chunk: Option<SlicedDataChunk>,
-    /// The data type of probe side. Cache to avoid copy too much.
-    probe_side_schema: Vec<DataType>,
-    /// Row-level iteration of probe side.
-    probe_side_source: RowLevelIter,
-    /// The table used for look up matched rows.
-    build_table: RowLevelIter,
-
-    /// Used in probe remaining iteration.
-    probe_remain_chunk_idx: usize,
-    probe_remain_row_idx: usize,
+    /// The probe side.
+    left_child: BoxedExecutor,
+    /// The build side, which is loaded into memory entirely.
+    right_child: BoxedExecutor,
+    /// The join fails once it outputs more rows than this. `None` for no limit.
+    max_output_rows: Option<usize>,
+    /// Tracks the memory of the build side.
+    memory_context: MemoryContext,
 
     /// Identity string of the executor
     identity: String,
 }
 
-/// If current row finished probe, executor will advance to next row.
-/// If there is batched chunk generated in probing, return it. Note that:
-/// `None` do not mean no matched row find (it is written in
-/// [`NestedLoopJoinExecutor::chunk_builder`])
-struct ProbeResult {
-    cur_row_finished: bool,
-    chunk: Option<DataChunk>,
-}
-
-#[derive(PartialEq)]
-enum NestedLoopJoinState {
-    /// [`Self::Build`] should load all inner table into memory.
-    Build,
-    /// One Difference between [`Self::FirstProbe`] and [`Self::Probe`]:
-    /// Only init the probe side source until [`Self::FirstProbe`] so that
-    /// avoid unnecessary init if build side fail.
-    FirstProbe,
-    /// [`Self::Probe`] finds matching rows for all outer table.
-    Probe,
-    ProbeRemaining,
-    Done,
-}
-
 impl Executor for NestedLoopJoinExecutor {
     fn schema(&self) -> &Schema {
         &self.schema
@@ -107,52 +83,170 @@ impl Executor for NestedLoopJoinExecutor {
 }
 
 impl NestedLoopJoinExecutor {
+    pub(super) fn new(
+        join_expr: BoxedExpression,
+        join_type: JoinType,
+        left_child: BoxedExecutor,
+        right_child: BoxedExecutor,
+        max_output_rows: Option<usize>,
+        memory_context: MemoryContext,
+        identity: String,
+    ) -> Self {
+        // TODO(Bowen): Merge this with derive schema in Logical Join (#790).
+        let fields = match join_type {
+            JoinType::LeftSemi | JoinType::LeftAnti => left_child.schema().fields.clone(),
+            JoinType::RightSemi | JoinType::RightAnti => right_child.schema().fields.clone(),
+            _ => left_child
+                .schema()
+                .fields
+                .iter()
+                .chain(right_child.schema().fields.iter())
+                .cloned()
+                .collect(),
+        };
+
+        Self {
+            join_expr,
+            join_type,
+            schema: Schema { fields },
+            left_child,
+            right_child,
+            max_output_rows,
+            memory_context,
+            identity,
+        }
+    }
+
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
-    async fn do_execute(mut self: Box<Self>) {
-        let mut state = NestedLoopJoinState::Build;
-        loop {
-            match state {
-                NestedLoopJoinState::Build => {
-                    self.build_table.load_data().await?;
-                    state = NestedLoopJoinState::FirstProbe;
-                }
-                NestedLoopJoinState::FirstProbe => {
-                    let ret = self.probe(true, &mut state).await?;
-                    if let Some(data_chunk) = ret {
-                        yield data_chunk;
+    async fn do_execute(self: Box<Self>) {
+        let Self {
+            join_expr,
+            join_type,
+            schema,
+            left_child,
+            right_child,
+            max_output_rows,
+            memory_context,
+            ..
+        } = *self;
+        let probe_side_types = left_child.schema().data_types();
+        let build_side_types = right_child.schema().data_types();
+
+        // Build: load the whole build side.
+        let mut build_chunks = vec![];
+        let mut mem_tracker = memory_context.new_tracker();
+        #[for_await]
+        for chunk in right_child.execute() {
+            let chunk = chunk?.compact()?;
+            if chunk.cardinality() > 0 {
+                mem_tracker.reserve(estimated_chunk_size(&chunk))?;
+                build_chunks.push(chunk);
+            }
+        }
+        let mut build_matched =
+            ChunkedData::<bool>::with_chunk_sizes(build_chunks.iter().map(|c| c.capacity()))?;
+
+        let mut output = OutputBuilder::new(schema.data_types(), max_output_rows);
+
+        // Probe: join each probe chunk with the build chunks one by one.
+        #[for_await]
+        for probe_chunk in left_child.execute() {
+            let probe_chunk = probe_chunk?.compact()?;
+            let mut probe_matched = vec![false; probe_chunk.capacity()];
+
+            for (build_chunk_idx, build_chunk) in build_chunks.iter().enumerate() {
+                let build_len = build_chunk.capacity();
+                let block_size = (DEFAULT_CHUNK_BUFFER_SIZE / build_len).max(1);
+                for block_start in (0..probe_chunk.capacity()).step_by(block_size) {
+                    let block = block_start..(block_start + block_size).min(probe_chunk.capacity());
+                    let joined_chunk = Self::cross_product(&probe_chunk, block, build_chunk)?;
+                    let vis: Bitmap = join_expr.eval(&joined_chunk)?.as_bool().try_into()?;
+                    if vis.num_high_bits() == 0 {
+                        continue;
                     }
-                }
-                NestedLoopJoinState::Probe => {
-                    let ret = self.probe(false, &mut state).await?;
-                    if let Some(data_chunk) = ret {
-                        yield data_chunk;
+
+                    // The `i`-th joined row is of the probe row `block_start + i / build_len` and
+                    // the build row `i % build_len`.
+                    for (joined_row_idx, matched) in vis.iter().enumerate() {
+                        if matched {
+                            probe_matched[block_start + joined_row_idx / build_len] = true;
+                            if join_type.need_build() {
+                                let build_row_idx = joined_row_idx % build_len;
+                                build_matched[RowId::new(build_chunk_idx, build_row_idx)] = true;
+                            }
+                        }
+                    }
+                    if matches!(
+                        join_type,
+                        JoinType::Inner | JoinType::LeftOuter | JoinType::RightOuter
+                    ) {
+                        for chunk in output.append_chunk(joined_chunk.with_visibility(vis))? {
+                            yield chunk;
+                        }
                     }
                 }
+            }
 
-                NestedLoopJoinState::ProbeRemaining => {
-                    let ret = self.probe_remaining()?;
-                    if let Some(data_chunk) = ret {
-                        yield data_chunk;
-                    }
-                    state = NestedLoopJoinState::Done;
+            // Output the probe rows according to whether they are matched, and pad the unmatched
+            // ones with nulls for left outer joins.
+            let probe_vis: Bitmap = match join_type {
+                JoinType::LeftSemi => probe_matched,
+                JoinType::LeftAnti | JoinType::LeftOuter => {
+                    probe_matched.into_iter().map(|m| !m).collect()
                 }
+                _ => continue,
+            }
+            .try_into()?;
+            let chunk = if join_type == JoinType::LeftOuter {
+                let null_row = vec![None; build_side_types.len()];
+                let null_chunk = Self::convert_datum_refs_to_chunk(
+                    &null_row,
+                    probe_chunk.capacity(),
+                    &build_side_types,
+                )?;
+                Self::concatenate(&probe_chunk, &null_chunk)?
+            } else {
+                probe_chunk
+            };
+            for chunk in output.append_chunk(chunk.with_visibility(probe_vis))? {
+                yield chunk;
+            }
+        }
 
-                NestedLoopJoinState::Done => {
-                    if let Some(data_chunk) = self.chunk_builder.consume_all()? {
-                        yield data_chunk;
-                    } else {
-                        break;
-                    };
+        // Probe remaining: output the build rows according to whether they are matched.
+        if join_type.need_build() {
+            for (build_chunk_idx, build_chunk) in build_chunks.into_iter().enumerate() {
+                let build_vis: Bitmap = (0..build_chunk.capacity())
+                    .map(|build_row_idx| {
+                        let matched = build_matched[RowId::new(build_chunk_idx, build_row_idx)];
+                        matched == (join_type == JoinType::RightSemi)
+                    })
+                    .collect_vec()
+                    .try_into()?;
+                let chunk = if join_type == JoinType::RightOuter {
+                    let null_row = vec![None; probe_side_types.len()];
+                    let null_chunk = Self::convert_datum_refs_to_chunk(
+                        &null_row,
+                        build_chunk.capacity(),
+                        &probe_side_types,
+                    )?;
+                    Self::concatenate(&null_chunk, &build_chunk)?
+                } else {
+                    build_chunk
+                };
+                for chunk in output.append_chunk(chunk.with_visibility(build_vis))? {
+                    yield chunk;
                 }
             }
         }
+
+        if let Some(chunk) = output.consume_all()? {
+            yield chunk;
+        }
     }
-}
 
-impl NestedLoopJoinExecutor {
     /// Create constant data chunk (one tuple repeat `num_tuples` times).
     fn convert_datum_refs_to_chunk(
-        &self,
         datum_refs: &[DatumRef<'_>],
         num_tuples: usize,
         data_types: &[DataType],
@@ -176,15 +270,40 @@ impl NestedLoopJoinExecutor {
         Ok(DataChunk::builder().columns(result_columns).build())
     }
 
-    /// Create constant data chunk (one tuple repeat `num_tuples` times).
-    fn convert_row_to_chunk(
-        &self,
-        row_ref: &RowRef<'_>,
-        num_tuples: usize,
-        data_types: &[DataType],
+    /// Create the cross product of the rows `probe_rows` of `probe_chunk` and all the rows of
+    /// `build_chunk`. The probe columns are followed by the build columns, and the rows are ordered
+    /// by the probe row first and then the build row.
+    fn cross_product(
+        probe_chunk: &DataChunk,
+        probe_rows: Range<usize>,
+        build_chunk: &DataChunk,
     ) -> Result<DataChunk> {
-        let datum_refs = row_ref.values().collect_vec();
-        self.convert_datum_refs_to_chunk(&datum_refs, num_tuples, data_types)
+        let build_len = build_chunk.capacity();
+        let num_rows = probe_rows.len() * build_len;
+        let probe_columns = probe_chunk.columns().iter().map(|column| {
+            let array = column.array_ref();
+            let mut builder = array.create_builder(num_rows)?;
+            for probe_row_idx in probe_rows.clone() {
+                for _ in 0..build_len {
+                    builder.append_array_element(array, probe_row_idx)?;
+                }
+            }
+            Ok(Column::new(Arc::new(builder.finish()?)))
+        });
+        let build_columns = build_chunk.columns().iter().map(|column| {
+            let array = column.array_ref();
+            let mut builder = array.create_builder(num_rows)?;
+            for _ in probe_rows.clone() {
+                for build_row_idx in 0..build_len {
+                    builder.append_array_element(array, build_row_idx)?;
+                }
+            }
+            Ok(Column::new(Arc::new(builder.finish()?)))
+        });
+        let columns = probe_columns
+            .chain(build_columns)
+            .collect::<Result<Vec<Column>>>()?;
+        Ok(DataChunk::builder().columns(columns).build())
     }
 }
 
@@ -202,6 +321,10 @@ impl BoxedExecutorBuilder for NestedLoopJoinExecutor {
 
         let join_type = JoinType::from_prost(nested_loop_join_node.get_join_type()?);
         let join_expr = expr_build_from_prost(nested_loop_join_node.get_join_cond()?)?;
+        let max_output_rows = match nested_loop_join_node.max_output_rows {
+            0 => None,
+            max_output_rows => Some(max_output_rows as usize),
+        };
         // Note: Assume that optimizer has set up probe side and build side. Left is the probe side,
         // right is the build side. This is the same for all join executors.
         let left_plan_opt = source.plan_node().get_children().get(0);
@@ -209,25 +332,8 @@ impl BoxedExecutorBuilder for NestedLoopJoinExecutor {
         match (left_plan_opt, right_plan_opt) {
             (Some(left_plan), Some(right_plan)) => {
                 let left_child = source.clone_for_plan(left_plan).build().await?;
-                let probe_side_schema = left_child.schema().data_types();
                 let right_child = source.clone_for_plan(right_plan).build().await?;
 
-                // TODO(Bowen): Merge this with derive schema in Logical Join (#790).
-                let fields = match join_type {
-                    JoinType::LeftSemi => left_child.schema().fields.clone(),
-                    JoinType::LeftAnti => left_child.schema().fields.clone(),
-                    JoinType::RightSemi => right_child.schema().fields.clone(),
-                    JoinType::RightAnti => right_child.schema().fields.clone(),
-                    _ => left_child
-                        .schema()
-                        .fields
-                        .iter()
-                        .chain(right_child.schema().fields.iter())
-                        .cloned()
-                        .collect(),
-                };
-
-                let schema = Schema { fields };
                 match join_type {
                     JoinType::Inner
                     | JoinType::LeftOuter
@@ -235,24 +341,16 @@ impl BoxedExecutorBuilder for NestedLoopJoinExecutor {
                     | JoinType::LeftSemi
                     | JoinType::LeftAnti
                     | JoinType::RightSemi
-                    | JoinType::RightAnti => {
-                        // TODO: Support FULL OUTER.
-                        let outer_table_source = RowLevelIter::new(left_child);
-
-                        Ok(Box::new(Self {
-                            join_expr,
-                            join_type,
-                            chunk_builder: DataChunkBuilder::with_default_size(schema.data_types()),
-                            schema,
-                            last_chunk: None,
-                            probe_side_schema,
-                            probe_side_source: outer_table_source,
-                            build_table: RowLevelIter::new(right_child),
-                            probe_remain_chunk_idx: 0,
-                            probe_remain_row_idx: 0,
-                            identity: "NestedLoopJoinExecutor2".to_string(),
-                        }))
-                    }
+                    | JoinType::RightAnti => Ok(Box::new(Self::new(
+                        join_expr,
+                        join_type,
+                        left_child,
+                        right_child,
+                        max_output_rows,
+                        source.context().memory_context(),
+                        "NestedLoopJoinExecutor".to_string(),
+                    ))),
+                    // TODO: Support FULL OUTER.
                     _ => Err(ErrorCode::NotImplemented(
                         format!("Do not support {:?} join type now.", join_type),
                         None.into(),
@@ -264,278 +362,8 @@ impl BoxedExecutorBuilder for NestedLoopJoinExecutor {
         }
     }
 }
-impl NestedLoopJoinExecutor {
-    /// Probe matched rows in `probe_table`.
-    /// # Arguments
-    /// * `first_probe`: whether the first probing. Init outer source if yes.
-    /// * `probe_table`: Table to be probed.
-    /// If nothing gained from `probe_table.join()`, Advance to next row until all outer tuples are
-    /// exhausted.
-    async fn probe(
-        &mut self,
-        first_probe: bool,
-        state: &mut NestedLoopJoinState,
-    ) -> Result<Option<DataChunk>> {
-        if first_probe {
-            self.probe_side_source.load_data().await?;
-            *state = NestedLoopJoinState::Probe;
-        }
-        let cur_row = self.probe_side_source.get_current_row_ref();
-        // TODO(Bowen): If we assume the scanned chunk is always compact (no invisible tuples),
-        // remove this check.
-        if cur_row.is_some() {
-            // Dispatch to each kind of join type
-            let probe_result = match self.join_type {
-                JoinType::Inner => self.do_inner_join(),
-                JoinType::LeftOuter => self.do_left_outer_join(),
-                JoinType::LeftSemi => self.do_left_semi_join(),
-                JoinType::LeftAnti => self.do_left_anti_join(),
-                JoinType::RightOuter => self.do_right_outer_join(),
-                JoinType::RightSemi => self.do_right_semi_join(),
-                JoinType::RightAnti => self.do_right_anti_join(),
-                _ => Err(ErrorCode::NotImplemented(
-                    "Do not support other join types!".to_string(),
-                    None.into(),
-                )
-                .into()),
-            }?;
-
-            if probe_result.cur_row_finished {
-                self.probe_side_source.advance_row();
-                // Probe row is changed, scan from the starting point of build table again.
-                self.build_table.reset_chunk();
-            }
-
-            if let Some(ret_chunk) = probe_result.chunk {
-                // Note: we can avoid the append chunk in join -- Only do it in the begin of outer
-                // loop. But currently seems like it do not have too much gain. Will
-                // keep look on it.
-                if ret_chunk.capacity() > 0 {
-                    let (mut left_data_chunk, return_data_chunk) = self
-                        .chunk_builder
-                        .append_chunk(SlicedDataChunk::new_checked(ret_chunk)?)?;
-                    // Have checked last chunk is None in before. Now swap to buffer it.
-                    std::mem::swap(&mut self.last_chunk, &mut left_data_chunk);
-                    if let Some(inner_chunk) = return_data_chunk {
-                        return Ok(Some(inner_chunk));
-                    }
-                }
-            }
-        } else {
-            *state = if self.join_type.need_join_remaining() {
-                NestedLoopJoinState::ProbeRemaining
-            } else {
-                NestedLoopJoinState::Done
-            };
-        }
-        Ok(None)
-    }
-
-    /// Similar to [`crate::executor::hash_join::HashJoinState::ProbeRemaining`]. For nested loop
-    /// join, iterate the build table and append row if not matched in
-    /// [`NestedLoopJoinState::Probe`].
-    fn probe_remaining(&mut self) -> Result<Option<DataChunk>> {
-        match self.join_type {
-            JoinType::RightOuter => self.do_probe_remaining_right_outer(),
-            JoinType::RightAnti => self.do_probe_remaining_right_anti(),
-            _ => Err(ErrorCode::NotImplemented(
-                "unsupported type for probe_remaining".to_string(),
-                None.into(),
-            )
-            .into()),
-        }
-    }
-
-    fn do_inner_join(&mut self) -> Result<ProbeResult> {
-        if let Some(build_side_chunk) = self.build_table.get_current_chunk() {
-            // Checked the option before, so impossible to panic.
-            let probe_row = self.probe_side_source.get_current_row_ref().unwrap();
-            let const_row_chunk = self.convert_row_to_chunk(
-                &probe_row,
-                build_side_chunk.capacity(),
-                &self.probe_side_schema,
-            )?;
-            let new_chunk = Self::concatenate(&const_row_chunk, build_side_chunk)?;
-            // Join with current row.
-            let sel_vector = self.join_expr.eval(&new_chunk)?;
-            let ret_chunk = new_chunk.with_visibility(sel_vector.as_bool().try_into()?);
-            self.build_table.advance_chunk();
-            Ok(ProbeResult {
-                cur_row_finished: false,
-                chunk: Some(ret_chunk),
-            })
-        } else {
-            self.build_table.reset_chunk();
-            Ok(ProbeResult {
-                cur_row_finished: true,
-                chunk: None,
-            })
-        }
-    }
-
-    fn do_left_outer_join(&mut self) -> Result<ProbeResult> {
-        let ret = self.do_inner_join()?;
-        if let Some(inner) = ret.chunk.as_ref() {
-            if inner.cardinality() > 0 {
-                self.probe_side_source.set_cur_row_matched(true);
-            }
-        }
-        // Append (probed_row, None) to chunk builder if current row finished probing and do not
-        // find any match.
-        if ret.cur_row_finished && !self.probe_side_source.get_cur_row_matched() {
-            assert!(ret.chunk.is_none());
-            let mut probe_datum_refs = self
-                .probe_side_source
-                .get_current_row_ref()
-                .unwrap()
-                .values()
-                .collect_vec();
-            for _ in 0..self.build_table.get_schema().fields.len() {
-                probe_datum_refs.push(None);
-            }
-            let one_row_chunk =
-                self.convert_datum_refs_to_chunk(&probe_datum_refs, 1, &self.schema.data_types())?;
-            return Ok(ProbeResult {
-                cur_row_finished: true,
-                chunk: Some(one_row_chunk),
-            });
-        }
-        Ok(ret)
-    }
-
-    fn do_left_semi_join(&mut self) -> Result<ProbeResult> {
-        let mut ret = self.do_inner_join()?;
-        if let Some(inner) = ret.chunk.as_ref() {
-            if inner.cardinality() > 0 {
-                self.probe_side_source.set_cur_row_matched(true);
-            }
-        }
-        ret.chunk = None;
-        // Append (probed_row, None) to chunk builder if current row finished probing and do not
-        // find any match.
-        if self.probe_side_source.get_cur_row_matched() {
-            let probe_row = self.probe_side_source.get_current_row_ref().unwrap();
-            let one_row_chunk =
-                self.convert_row_to_chunk(&probe_row, 1, &self.probe_side_schema)?;
-            return Ok(ProbeResult {
-                cur_row_finished: true,
-                chunk: Some(one_row_chunk),
-            });
-        }
-        Ok(ret)
-    }
-
-    fn do_left_anti_join(&mut self) -> Result<ProbeResult> {
-        let mut ret = self.do_inner_join()?;
-        if let Some(inner) = ret.chunk.as_ref() {
-            if inner.cardinality() > 0 {
-                self.probe_side_source.set_cur_row_matched(true);
-            }
-        }
-        ret.chunk = None;
-        if ret.cur_row_finished && !self.probe_side_source.get_cur_row_matched() {
-            assert!(ret.chunk.is_none());
-            let probe_row = self.probe_side_source.get_current_row_ref().unwrap();
-            let one_row_chunk =
-                self.convert_row_to_chunk(&probe_row, 1, &self.probe_side_schema)?;
-            return Ok(ProbeResult {
-                cur_row_finished: true,
-                chunk: Some(one_row_chunk),
-            });
-        }
-        Ok(ret)
-    }
-
-    fn do_right_outer_join(&mut self) -> Result<ProbeResult> {
-        let ret = self.do_inner_join()?;
-        // Mark matched rows to prepare for probe remaining.
-        self.mark_matched_rows(&ret)?;
-        Ok(ret)
-    }
-
-    /// Scan through probe results chunk and mark matched rows in build table. Therefore in probe
-    /// remaining, scan through build table and we can know each row whether has been matched in
-    /// probing. Used by `RIGHT_SEMI/ANTI/OUTER` Join.
-    fn mark_matched_rows<'a>(&'a mut self, ret: &'a ProbeResult) -> Result<Vec<Row>> {
-        let mut rows_ref = vec![];
-        if let Some(inner_chunk) = ret.chunk.as_ref() {
-            for row_idx in 0..inner_chunk.capacity() {
-                let (row_ref, vis) = inner_chunk.row_at(row_idx)?;
-                if vis {
-                    let row_id = RowId::new(self.build_table.get_chunk_idx() - 1, row_idx);
-                    // Only write this row if it have not been marked before.
-                    if !self.build_table.is_build_matched(row_id)?
-                        && self.join_type == JoinType::RightSemi
-                    {
-                        rows_ref.push(row_ref.row_by_indices(
-                            &(self.probe_side_schema.len()..row_ref.size()).collect::<Vec<usize>>(),
-                        ));
-                    }
-                    self.build_table.set_build_matched(row_id)?;
-                    self.probe_side_source.set_cur_row_matched(true);
-                }
-            }
-        }
-        Ok(rows_ref)
-    }
-
-    fn do_right_semi_join(&mut self) -> Result<ProbeResult> {
-        let mut ret = self.do_inner_join()?;
-        let rows = self.mark_matched_rows(&ret)?;
-        ret.chunk = None;
-        if !rows.is_empty() {
-            let ret_chunk =
-                DataChunk::from_rows(&rows, &self.build_table.get_schema().data_types())?;
-            ret.chunk = Some(ret_chunk);
-        }
-        Ok(ret)
-    }
-
-    fn do_right_anti_join(&mut self) -> Result<ProbeResult> {
-        let mut ret = self.do_inner_join()?;
-        // Mark matched rows to prepare for probe remaining.
-        self.mark_matched_rows(&ret)?;
-        ret.chunk = None;
-        Ok(ret)
-    }
-
-    fn do_probe_remaining_right_outer(&mut self) -> Result<Option<DataChunk>> {
-        while let Some(cur_row) = self.build_table.get_current_row_ref() {
-            // If the build row has not been matched by probe row before.
-            if !self
-                .build_table
-                .is_build_matched(self.build_table.get_current_row_id())?
-            {
-                let datum_refs = std::iter::repeat(None)
-                    .take(self.probe_side_source.get_schema().len())
-                    .chain(cur_row.values());
-                if let Some(ret_chunk) = self
-                    .chunk_builder
-                    .append_one_row_from_datum_refs(datum_refs)?
-                {
-                    return Ok(Some(ret_chunk));
-                }
-            }
-            self.build_table.advance_row();
-        }
-        Ok(None)
-    }
-
-    fn do_probe_remaining_right_anti(&mut self) -> Result<Option<DataChunk>> {
-        while let Some(cur_row) = self.build_table.get_current_row_ref() {
-            if !self
-                .build_table
-                .is_build_matched(self.build_table.get_current_row_id())?
-            {
-                if let Some(ret_chunk) = self.chunk_builder.append_one_row_ref(cur_row)? {
-                    return Ok(Some(ret_chunk));
-                }
-            }
-            self.build_table.advance_row();
-        }
-        Ok(None)
-    }
 
+impl NestedLoopJoinExecutor {
     /// The layout be like:
     ///
     /// [ `left` chunk     |  `right` chunk     ]
@@ -576,23 +404,81 @@ impl NestedLoopJoinExecutor {
         Ok(data_chunk)
     }
 }
+
+/// Coalesces the output rows of the join into chunks, and fails the join once it outputs more
+/// rows than allowed.
+struct OutputBuilder {
+    chunk_builder: DataChunkBuilder,
+    output_rows: usize,
+    max_output_rows: Option<usize>,
+}
+
+impl OutputBuilder {
+    fn new(data_types: Vec<DataType>, max_output_rows: Option<usize>) -> Self {
+        Self {
+            chunk_builder: DataChunkBuilder::with_default_size(data_types),
+            output_rows: 0,
+            max_output_rows,
+        }
+    }
+
+    fn add_output_rows(&mut self, num_rows: usize) -> Result<()> {
+        self.output_rows += num_rows;
+        match self.max_output_rows {
+            Some(max_output_rows) if self.output_rows > max_output_rows => {
+                Err(ErrorCode::RowLimitExceeded(format!(
+                    "nested loop join producing more than {} rows",
+                    max_output_rows
+                ))
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Append the visible rows of `chunk`, and return the chunks filled up.
+    fn append_chunk(&mut self, chunk: DataChunk) -> Result<Vec<DataChunk>> {
+        let cardinality = chunk.cardinality();
+        if cardinality == 0 {
+            return Ok(vec![]);
+        }
+        self.add_output_rows(cardinality)?;
+
+        let mut outputs = vec![];
+        let mut remaining = Some(SlicedDataChunk::new_checked(chunk)?);
+        while let Some(sliced_chunk) = remaining.take() {
+            let (left_chunk, output) = self.chunk_builder.append_chunk(sliced_chunk)?;
+            remaining = left_chunk;
+            outputs.extend(output);
+        }
+        Ok(outputs)
+    }
+
+    fn consume_all(&mut self) -> Result<Option<DataChunk>> {
+        self.chunk_builder.consume_all()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
+    use assert_matches::assert_matches;
+    use futures::StreamExt;
     use risingwave_common::array::column::Column;
     use risingwave_common::array::*;
     use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::error::ErrorCode;
     use risingwave_common::types::{DataType, ScalarRefImpl};
-    use risingwave_common::util::chunk_coalesce::DataChunkBuilder;
     use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
     use risingwave_expr::expr::InputRefExpression;
     use risingwave_pb::expr::expr_node::Type;
 
-    use crate::executor::join::nested_loop_join::{NestedLoopJoinExecutor, RowLevelIter};
+    use crate::executor::join::nested_loop_join::NestedLoopJoinExecutor;
     use crate::executor::join::JoinType;
     use crate::executor::test_utils::{diff_executor_output, MockExecutor};
     use crate::executor::BoxedExecutor;
+    use crate::task::MemoryContext;
 
     /// Test combine two chunk into one.
     #[test]
@@ -631,26 +517,12 @@ mod tests {
         let probe_side_schema = Schema {
             fields: vec![Field::unnamed(DataType::Int32)],
         };
-        let probe_source = Box::new(MockExecutor::new(probe_side_schema.clone()));
-        let build_source = Box::new(MockExecutor::new(probe_side_schema.clone()));
-        // Note that only probe side schema of this executor is meaningful. All other fields are
-        // meaningless. They are just used to pass Rust checker.
-        let source = NestedLoopJoinExecutor {
-            join_expr: Box::new(InputRefExpression::new(DataType::Int32, 0)),
-            join_type: JoinType::Inner,
-            chunk_builder: DataChunkBuilder::with_default_size(probe_side_schema.data_types()),
-            schema: Schema { fields: vec![] },
-            last_chunk: None,
-            probe_side_schema: probe_side_schema.data_types(),
-            probe_side_source: RowLevelIter::new(probe_source),
-            build_table: RowLevelIter::new(build_source),
-            probe_remain_chunk_idx: 0,
-            probe_remain_row_idx: 0,
-            identity: "NestedLoopJoinExecutor2".to_string(),
-        };
-        let const_row_chunk = source
-            .convert_datum_refs_to_chunk(&row, 5, &probe_side_schema.data_types())
-            .unwrap();
+        let const_row_chunk = NestedLoopJoinExecutor::convert_datum_refs_to_chunk(
+            &row,
+            5,
+            &probe_side_schema.data_types(),
+        )
+        .unwrap();
         assert_eq!(const_row_chunk.capacity(), 5);
         assert_eq!(
             const_row_chunk.row_at(2).unwrap().0.value_at(0),
@@ -752,52 +624,25 @@ mod tests {
             Box::new(executor)
         }
 
-        fn create_join_executor(&self) -> BoxedExecutor {
-            let join_type = self.join_type;
-
-            let left_child = self.create_left_executor();
-            let right_child = self.create_right_executor();
-
-            // TODO(Bowen): Merge this with derive schema in Logical Join.
-            let fields = match self.join_type {
-                JoinType::LeftSemi => left_child.schema().fields.clone(),
-                JoinType::LeftAnti => left_child.schema().fields.clone(),
-                JoinType::RightSemi => right_child.schema().fields.clone(),
-                JoinType::RightAnti => right_child.schema().fields.clone(),
-                _ => left_child
-                    .schema()
-                    .fields
-                    .iter()
-                    .chain(right_child.schema().fields.iter())
-                    .cloned()
-                    .collect(),
-            };
-            let schema = Schema { fields };
-
-            let probe_side_schema = left_child.schema().data_types();
-
-            Box::new(NestedLoopJoinExecutor {
-                join_expr: new_binary_expr(
+        fn create_join_executor(&self, max_output_rows: Option<usize>) -> BoxedExecutor {
+            Box::new(NestedLoopJoinExecutor::new(
+                new_binary_expr(
                     Type::Equal,
                     DataType::Boolean,
                     Box::new(InputRefExpression::new(DataType::Int32, 0)),
                     Box::new(InputRefExpression::new(DataType::Int32, 2)),
                 ),
-                join_type,
-                schema: schema.clone(),
-                chunk_builder: DataChunkBuilder::with_default_size(schema.data_types()),
-                last_chunk: None,
-                probe_side_schema,
-                probe_side_source: RowLevelIter::new(left_child),
-                build_table: RowLevelIter::new(right_child),
-                probe_remain_chunk_idx: 0,
-                probe_remain_row_idx: 0,
-                identity: "NestedLoopJoinExecutor2".to_string(),
-            })
+                self.join_type,
+                self.create_left_executor(),
+                self.create_right_executor(),
+                max_output_rows,
+                MemoryContext::default(),
+                "NestedLoopJoinExecutor".to_string(),
+            ))
         }
 
         async fn do_test(&self, expected: DataChunk) {
-            let join_executor = self.create_join_executor();
+            let join_executor = self.create_join_executor(None);
             let mut expected_mock_exec = MockExecutor::new(join_executor.schema().clone());
             expected_mock_exec.add(expected);
             diff_executor_output(join_executor, Box::new(expected_mock_exec)).await;
@@ -829,19 +674,59 @@ mod tests {
 
         let expected_chunk = DataChunk::from_pretty(
             "i f   i F
-             1 6.1 . .
              2 8.4 2 6.1
              3 3.9 3 8.9
+             1 6.1 . .
              3 6.6 3 8.9
-             4 0.7 . .
              6 5.5 6 3.4
              6 5.6 6 3.4
-             8 7.0 8 3.5",
+             8 7.0 8 3.5
+             4 0.7 . .",
+        );
+
+        test_fixture.do_test(expected_chunk).await;
+    }
+
+    /// sql: select * from t1 right outer join t2 on t1.v1 = t2.v1
+    #[tokio::test]
+    async fn test_right_outer_join() {
+        let test_fixture = TestFixture::with_join_type(JoinType::RightOuter);
+
+        let expected_chunk = DataChunk::from_pretty(
+            "i f   i   F
+             2 8.4 2   6.1
+             3 3.9 3   8.9
+             3 6.6 3   8.9
+             6 5.5 6   3.4
+             6 5.6 6   3.4
+             8 7.0 8   3.5
+             . .   9   7.5
+             . .   10  .
+             . .   11  8
+             . .   12  .
+             . .   20  5.7
+             . .   30  9.6
+             . .   100 .
+             . .   200 8.18",
         );
 
         test_fixture.do_test(expected_chunk).await;
     }
 
+    #[tokio::test]
+    async fn test_max_output_rows() {
+        let test_fixture = TestFixture::with_join_type(JoinType::Inner);
+
+        let join_executor = test_fixture.create_join_executor(Some(6));
+        let mut stream = join_executor.execute();
+        assert_eq!(stream.next().await.unwrap().unwrap().cardinality(), 6);
+        assert!(stream.next().await.is_none());
+
+        let join_executor = test_fixture.create_join_executor(Some(5));
+        let err = join_executor.execute().next().await.unwrap().unwrap_err();
+        assert_matches!(err.inner(), ErrorCode::RowLimitExceeded(_));
+    }
+
     #[tokio::test]
     async fn test_left_semi_join() {
         let test_fixture = TestFixture::with_join_type(JoinType::LeftSemi);
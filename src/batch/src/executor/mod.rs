@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod delete;
+mod external_sort;
 mod filter;
 mod generate_series;
 mod generic_exchange;
@@ -27,6 +28,7 @@ mod order_by;
 mod project;
 mod row_seq_scan;
 mod sort_agg;
+mod spill;
 #[cfg(test)]
 pub mod test_utils;
 mod top_n;
@@ -50,8 +52,7 @@ pub use order_by::*;
 pub use project::*;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
-use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorContext, Result};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::PlanNode;
 pub use row_seq_scan::*;
@@ -145,13 +146,11 @@ impl<'a, C: Clone> ExecutorBuilder<'a, C> {
 
 impl<'a, C: BatchTaskContext> ExecutorBuilder<'a, C> {
     pub async fn build(&self) -> Result<BoxedExecutor> {
-        self.try_build().await.map_err(|e| {
-            InternalError(format!(
-                "[PlanNode: {:?}] Failed to build executor: {}",
+        self.try_build().await.with_context(|| {
+            format!(
+                "[PlanNode: {:?}] Failed to build executor",
                 self.plan_node.get_node_body(),
-                e,
-            ))
-            .into()
+            )
         })
     }
 
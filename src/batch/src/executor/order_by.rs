@@ -30,10 +30,12 @@ use risingwave_common::util::encoding_for_comparison::{encode_chunk, is_type_enc
 use risingwave_common::util::sort_util::{compare_two_row, HeapElem, OrderPair};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
+use crate::executor::external_sort::ExternalSorter;
+use crate::executor::spill::estimated_chunk_size;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::BatchTaskContext;
+use crate::task::{BatchTaskContext, MemoryContext, MemoryTracker};
 
 pub struct OrderByExecutor {
     child: Option<BoxedExecutor>,
@@ -48,6 +50,10 @@ pub struct OrderByExecutor {
     identity: String,
     chunk_size: usize,
     schema: Schema,
+    /// The max size of the input kept in memory. The input is sorted by `ExternalSorter` instead
+    /// once exceeded.
+    memory_quota: usize,
+    memory_context: MemoryContext,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -64,6 +70,8 @@ impl OrderByExecutor {
         disable_encoding: bool,
         identity: String,
         chunk_size: usize,
+        memory_quota: usize,
+        memory_context: MemoryContext,
     ) -> Self {
         let schema = child.schema().clone();
         Self {
@@ -79,6 +87,8 @@ impl OrderByExecutor {
             identity,
             chunk_size,
             schema,
+            memory_quota,
+            memory_context,
         }
     }
 }
@@ -114,6 +124,8 @@ impl BoxedExecutorBuilder for OrderByExecutor {
                 false,
                 source.plan_node().get_identity().clone(),
                 DEFAULT_CHUNK_BUFFER_SIZE,
+                source.context().sort_memory_quota(),
+                source.context().memory_context(),
             )));
         }
         Err(InternalError("OrderBy must have one child".to_string()).into())
@@ -172,10 +184,39 @@ impl OrderByExecutor {
         index
     }
 
-    async fn collect_child_data(&mut self) -> Result<()> {
+    /// Collects the input in memory, or returns an `ExternalSorter` fed with all the input if it
+    /// exceeds the memory quota.
+    async fn collect_child_data(
+        &mut self,
+        mem_tracker: &mut MemoryTracker,
+    ) -> Result<Option<ExternalSorter>> {
         let mut stream = self.child.take().unwrap().execute();
+        let mut memory_usage = 0;
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
+            let chunk_size = estimated_chunk_size(&chunk);
+            memory_usage += chunk_size;
+            if memory_usage > self.memory_quota {
+                mem_tracker.release_all();
+                let mut sorter = ExternalSorter::new(
+                    self.order_pairs.clone(),
+                    self.schema.data_types(),
+                    self.memory_quota,
+                    self.chunk_size,
+                    self.memory_context.new_tracker(),
+                );
+                self.sorted_indices.clear();
+                self.encoded_keys.clear();
+                for chunk in self.chunks.drain(..) {
+                    sorter.push_chunk(chunk)?;
+                }
+                sorter.push_chunk(chunk)?;
+                while let Some(chunk) = stream.next().await {
+                    sorter.push_chunk(chunk?)?;
+                }
+                return Ok(Some(sorter));
+            }
+            mem_tracker.reserve(chunk_size)?;
             if !self.disable_encoding && self.encodable {
                 self.encoded_keys
                     .push(encode_chunk(&chunk, self.order_pairs.clone()));
@@ -188,7 +229,7 @@ impl OrderByExecutor {
         for idx in 0..self.chunks.len() {
             self.push_heap_for_chunk(idx);
         }
-        Ok(())
+        Ok(None)
     }
 }
 
@@ -218,7 +259,14 @@ impl OrderByExecutor {
                 .all(is_type_encodable)
         }
 
-        self.collect_child_data().await?;
+        let mut mem_tracker = self.memory_context.new_tracker();
+        if let Some(sorter) = self.collect_child_data(&mut mem_tracker).await? {
+            #[for_await]
+            for chunk in sorter.into_stream() {
+                yield chunk?;
+            }
+            return Ok(());
+        }
 
         loop {
             let mut array_builders = self.schema().create_array_builders(self.chunk_size)?;
@@ -323,6 +371,8 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            usize::MAX,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Int32);
@@ -379,6 +429,8 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            usize::MAX,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Float32);
@@ -435,6 +487,8 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            usize::MAX,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Varchar);
@@ -452,6 +506,72 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_order_by_with_spill() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        let mut rows = vec![];
+        // With no memory quota, each chunk is spilled as a run, and the runs are more than a merge
+        // can take at once.
+        for i in 0..40 {
+            let mut pretty = "i I".to_string();
+            for j in 0..5 {
+                let v1 = (i * 5 + j) * 37 % 101;
+                let v0 = v1 % 7;
+                pretty.push_str(&format!("\n{} {}", v0, v1));
+                rows.push((Some(v0 as i32), Some(v1 as i64)));
+            }
+            mock_executor.add(DataChunk::from_pretty(&pretty));
+        }
+        rows.sort_by(|(l0, l1), (r0, r1)| r0.cmp(l0).then(l1.cmp(r1)));
+
+        let order_pairs = vec![
+            OrderPair {
+                column_idx: 0,
+                order_type: OrderType::Descending,
+            },
+            OrderPair {
+                column_idx: 1,
+                order_type: OrderType::Ascending,
+            },
+        ];
+        let order_by_executor = Box::new(OrderByExecutor::new(
+            Box::new(mock_executor),
+            vec![],
+            vec![],
+            vec![],
+            BinaryHeap::new(),
+            Arc::new(order_pairs),
+            vec![],
+            false,
+            false,
+            "OrderByExecutor2".to_string(),
+            16,
+            0,
+            MemoryContext::default(),
+        ));
+
+        let mut output = vec![];
+        let mut stream = order_by_executor.execute();
+        while let Some(res) = stream.next().await {
+            let res = res.unwrap();
+            assert!(res.cardinality() <= 16);
+            output.extend(
+                res.column_at(0)
+                    .array()
+                    .as_int32()
+                    .iter()
+                    .zip(res.column_at(1).array().as_int64().iter()),
+            );
+        }
+        assert_eq!(output, rows);
+    }
+
     // TODO: enable benches
 
     // fn benchmark_1e4(b: &mut Bencher, enable_encoding: bool) {
@@ -1,6 +1,3 @@
-use std::sync::Arc;
-
-use futures_async_stream::try_stream;
 // Copyright 2022 Singularity Data
 //
 // Licensed under the Apache License, Version 2.0 (the "License");
@@ -14,13 +11,22 @@ use futures_async_stream::try_stream;
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::ops::Bound;
+use std::sync::Arc;
+
+use futures_async_stream::try_stream;
 use itertools::Itertools;
-use risingwave_common::array::DataChunk;
-use risingwave_common::catalog::{ColumnDesc, Schema, TableId};
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::{ColumnDesc, OrderedColumnDesc, Schema, TableId};
 use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::Datum;
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_common::util::value_encoding::deserialize_datum;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::{scan_range, ScanRange};
 use risingwave_storage::table::cell_based_table::{CellBasedTable, CellBasedTableRowIter};
 use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
+use tokio::sync::mpsc;
 
 use crate::executor::monitor::BatchMetrics;
 use crate::executor::{
@@ -28,6 +34,9 @@ use crate::executor::{
 };
 use crate::task::BatchTaskContext;
 
+/// The number of chunks buffered for each sub-range of a parallel scan.
+const PARALLEL_SCAN_BUFFER_SIZE: usize = 4;
+
 /// Executor that scans data from row table
 pub struct RowSeqScanExecutor<S: StateStore> {
     primary: bool,
@@ -35,13 +44,15 @@ pub struct RowSeqScanExecutor<S: StateStore> {
     schema: Schema,
     identity: String,
     stats: Arc<BatchMetrics>,
-    row_iter: CellBasedTableRowIter<S>,
+    /// The iterators of adjacent pk ranges in order. If there are more than one, they are read
+    /// concurrently.
+    row_iters: Vec<CellBasedTableRowIter<S>>,
 }
 
 impl<S: StateStore> RowSeqScanExecutor<S> {
     pub fn new(
         schema: Schema,
-        row_iter: CellBasedTableRowIter<S>,
+        row_iters: Vec<CellBasedTableRowIter<S>>,
         chunk_size: usize,
         primary: bool,
         identity: String,
@@ -53,7 +64,7 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
             schema,
             identity,
             stats,
-            row_iter,
+            row_iters,
         }
     }
 
@@ -72,6 +83,44 @@ impl RowSeqScanExecutorBuilder {
     pub const DEFAULT_CHUNK_SIZE: usize = 1024;
 }
 
+/// Converts the scan range into the values of the pk prefix and the bounds of the pk column next
+/// to the prefix.
+fn get_pk_bounds(
+    scan_range: &ScanRange,
+    pk_descs: &[OrderedColumnDesc],
+) -> Result<(Row, (Bound<Datum>, Bound<Datum>))> {
+    let pk_prefix = scan_range
+        .eq_conds
+        .iter()
+        .zip_eq(&pk_descs[..scan_range.eq_conds.len()])
+        .map(|(value, desc)| deserialize_datum(value.as_slice(), &desc.column_desc.data_type))
+        .try_collect()?;
+
+    if scan_range.lower_bound.is_none() && scan_range.upper_bound.is_none() {
+        return Ok((Row(pk_prefix), (Bound::Unbounded, Bound::Unbounded)));
+    }
+    let data_type = &pk_descs[scan_range.eq_conds.len()].column_desc.data_type;
+    let to_bound = |bound: &scan_range::Bound| -> Result<Bound<Datum>> {
+        let datum = deserialize_datum(bound.value.as_slice(), data_type)?;
+        Ok(if bound.inclusive {
+            Bound::Included(datum)
+        } else {
+            Bound::Excluded(datum)
+        })
+    };
+    // Nulls don't satisfy any range predicate, and are excluded by the lower bound since they are
+    // regarded as less than any value.
+    let lower_bound = match &scan_range.lower_bound {
+        Some(bound) => to_bound(bound)?,
+        None => Bound::Excluded(None),
+    };
+    let upper_bound = match &scan_range.upper_bound {
+        Some(bound) => to_bound(bound)?,
+        None => Bound::Unbounded,
+    };
+    Ok((Row(pk_prefix), (lower_bound, upper_bound)))
+}
+
 #[async_trait::async_trait]
 impl BoxedExecutorBuilder for RowSeqScanExecutorBuilder {
     async fn new_boxed_executor<C: BatchTaskContext>(
@@ -90,17 +139,69 @@ impl BoxedExecutorBuilder for RowSeqScanExecutorBuilder {
             .iter()
             .map(|column_desc| ColumnDesc::from(column_desc.clone()))
             .collect_vec();
+        let pk_descs = seq_scan_node
+            .table_desc
+            .as_ref()
+            .unwrap()
+            .pk
+            .iter()
+            .map(|desc| OrderedColumnDesc::from(desc.clone()))
+            .collect_vec();
+        let pk_bounds = match &seq_scan_node.scan_range {
+            Some(scan_range) if !pk_descs.is_empty() => Some(get_pk_bounds(scan_range, &pk_descs)?),
+            _ => None,
+        };
         dispatch_state_store!(source.context().try_get_state_store()?, state_store, {
             let keyspace = Keyspace::table_root(state_store.clone(), &table_id);
             let storage_stats = state_store.stats();
             let batch_stats = source.context().stats();
-            let table = CellBasedTable::new_adhoc(keyspace, column_descs, storage_stats);
-            let iter = table.iter(source.epoch).await?;
+            let table = match pk_bounds {
+                Some(_) => {
+                    let pk_serializer =
+                        OrderedRowSerializer::new(pk_descs.iter().map(|desc| desc.order).collect());
+                    CellBasedTable::new(
+                        keyspace,
+                        column_descs,
+                        Some(pk_serializer),
+                        storage_stats,
+                        None,
+                    )
+                }
+                None => CellBasedTable::new_adhoc(keyspace, column_descs, storage_stats),
+            };
+            let primary = source.task_id.task_id == 0;
+            let parallelism = seq_scan_node.parallelism as usize;
+            let iters = if primary && parallelism > 1 {
+                let pk_range = match pk_bounds {
+                    Some((pk_prefix, next_col_bounds)) => {
+                        table.serialize_pk_bounds(pk_prefix, next_col_bounds)
+                    }
+                    None => (Bound::Unbounded, Bound::Unbounded),
+                };
+                let mut iters = vec![];
+                for range in table
+                    .split_pk_range(source.epoch, pk_range, parallelism)
+                    .await?
+                {
+                    iters.push(table.iter_with_pk_range(source.epoch, range).await?);
+                }
+                iters
+            } else {
+                let iter = match pk_bounds {
+                    Some((pk_prefix, next_col_bounds)) => {
+                        table
+                            .iter_with_pk_bounds(source.epoch, pk_prefix, next_col_bounds)
+                            .await?
+                    }
+                    None => table.iter(source.epoch).await?,
+                };
+                vec![iter]
+            };
             Ok(Box::new(RowSeqScanExecutor::new(
                 table.schema().clone(),
-                iter,
+                iters,
                 RowSeqScanExecutorBuilder::DEFAULT_CHUNK_SIZE,
-                source.task_id.task_id == 0,
+                primary,
                 source.plan_node().get_identity().clone(),
                 batch_stats,
             )))
@@ -125,12 +226,15 @@ impl<S: StateStore> Executor for RowSeqScanExecutor<S> {
 impl<S: StateStore> RowSeqScanExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(mut self: Box<Self>) {
-        if !self.should_ignore() {
+        if self.should_ignore() {
+            return Ok(());
+        }
+        if self.row_iters.len() == 1 {
+            let mut row_iter = self.row_iters.pop().unwrap();
             loop {
                 let timer = self.stats.row_seq_scan_next_duration.start_timer();
 
-                let chunk = self
-                    .row_iter
+                let chunk = row_iter
                     .collect_data_chunk(&self.schema, Some(self.chunk_size))
                     .await
                     .map_err(RwError::from)?;
@@ -142,6 +246,44 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
                     break;
                 }
             }
+        } else {
+            // Each range is read by a task of its own, while the chunks are yielded range by range
+            // to keep the pk order. The tasks quit once the receivers are dropped.
+            let receivers = std::mem::take(&mut self.row_iters)
+                .into_iter()
+                .map(|mut row_iter| {
+                    let (tx, rx) = mpsc::channel(PARALLEL_SCAN_BUFFER_SIZE);
+                    let schema = self.schema.clone();
+                    let chunk_size = self.chunk_size;
+                    tokio::spawn(async move {
+                        while let Some(chunk) = row_iter
+                            .collect_data_chunk(&schema, Some(chunk_size))
+                            .await
+                            .transpose()
+                        {
+                            let is_err = chunk.is_err();
+                            if tx.send(chunk).await.is_err() || is_err {
+                                break;
+                            }
+                        }
+                    });
+                    rx
+                })
+                .collect_vec();
+
+            for mut rx in receivers {
+                loop {
+                    let timer = self.stats.row_seq_scan_next_duration.start_timer();
+                    let chunk = rx.recv().await;
+                    timer.observe_duration();
+
+                    if let Some(chunk) = chunk {
+                        yield chunk.map_err(RwError::from)?
+                    } else {
+                        break;
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,87 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Temporary files on the local disk for executors whose inputs exceed their memory quota. Chunks
+//! are written one after another, each as its protobuf encoding prefixed with the length.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use futures_async_stream::try_stream;
+use prost::Message;
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::data::DataChunk as ProstDataChunk;
+
+/// Estimates the memory taken by the chunk with the size of its encoded form.
+pub(crate) fn estimated_chunk_size(data_chunk: &DataChunk) -> usize {
+    data_chunk.to_protobuf().encoded_len()
+}
+
+/// Writes chunks into a temporary file, which is removed once the file is closed.
+pub(crate) struct SpillFile {
+    file: BufWriter<File>,
+}
+
+impl SpillFile {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            file: BufWriter::new(tempfile::tempfile()?),
+        })
+    }
+
+    /// Writes the chunk, which must be compacted.
+    pub(crate) fn write_chunk(&mut self, data_chunk: &DataChunk) -> Result<()> {
+        let encoded = data_chunk.to_protobuf().encode_to_vec();
+        self.file.write_u32::<LittleEndian>(encoded.len() as u32)?;
+        self.file.write_all(&encoded)?;
+        Ok(())
+    }
+
+    /// Finishes writing and returns the reader of the chunks written, from the first one.
+    pub(crate) fn finish(self) -> Result<SpillReader> {
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(SpillReader {
+            file: BufReader::new(file),
+        })
+    }
+}
+
+/// Reads the chunks of a spill file back.
+pub(crate) struct SpillReader {
+    file: BufReader<File>,
+}
+
+impl SpillReader {
+    pub(crate) fn read_chunk(&mut self) -> Result<Option<DataChunk>> {
+        let len = match self.file.read_u32::<LittleEndian>() {
+            Ok(len) => len as usize,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut encoded = vec![0; len];
+        self.file.read_exact(&mut encoded)?;
+        let prost_chunk = ProstDataChunk::decode(encoded.as_slice())?;
+        Ok(Some(DataChunk::from_protobuf(&prost_chunk)?))
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    pub(crate) async fn into_stream(mut self) {
+        while let Some(data_chunk) = self.read_chunk()? {
+            yield data_chunk;
+        }
+    }
+}
@@ -17,6 +17,7 @@ use std::collections::BinaryHeap;
 use std::sync::Arc;
 use std::vec::Vec;
 
+use futures::StreamExt;
 use futures_async_stream::try_stream;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
@@ -26,10 +27,12 @@ use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
 use risingwave_common::util::sort_util::{HeapElem, OrderPair};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
+use crate::executor::external_sort::ExternalSorter;
+use crate::executor::spill::estimated_chunk_size;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::BatchTaskContext;
+use crate::task::{BatchTaskContext, MemoryContext};
 
 struct TopNHeap {
     order_pairs: Arc<Vec<OrderPair>>,
@@ -82,6 +85,12 @@ impl TopNHeap {
             None
         }
     }
+
+    /// Takes all rows out of the heap, in no particular order.
+    fn take_chunks(&mut self, chunk_size: usize) -> Result<Vec<DataChunk>> {
+        let chunks = self.min_heap.drain().map(|e| e.0.chunk).collect::<Vec<_>>();
+        DataChunk::rechunk(&chunks, chunk_size)
+    }
 }
 
 pub struct TopNExecutor {
@@ -90,6 +99,10 @@ pub struct TopNExecutor {
     identity: String,
     chunk_size: usize,
     offset: usize,
+    /// The max size of the rows kept in the heap. All the input is sorted by `ExternalSorter`
+    /// instead once exceeded, which only happens when `limit + offset` is large.
+    memory_quota: usize,
+    memory_context: MemoryContext,
 }
 
 #[async_trait::async_trait]
@@ -116,6 +129,8 @@ impl BoxedExecutorBuilder for TopNExecutor {
                 top_n_node.get_offset() as usize,
                 source.plan_node().get_identity().clone(),
                 DEFAULT_CHUNK_BUFFER_SIZE,
+                source.context().sort_memory_quota(),
+                source.context().memory_context(),
             )));
         }
         Err(InternalError("TopN must have one child".to_string()).into())
@@ -130,6 +145,8 @@ impl TopNExecutor {
         offset: usize,
         identity: String,
         chunk_size: usize,
+        memory_quota: usize,
+        memory_context: MemoryContext,
     ) -> Self {
         Self {
             top_n_heap: TopNHeap {
@@ -141,6 +158,8 @@ impl TopNExecutor {
             identity,
             chunk_size,
             offset,
+            memory_quota,
+            memory_context,
         }
     }
 }
@@ -162,10 +181,45 @@ impl Executor for TopNExecutor {
 impl TopNExecutor {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(mut self: Box<Self>) {
-        #[for_await]
-        for data_chunk in self.child.execute() {
+        let data_types = self.child.schema().data_types();
+        let mut stream = self.child.execute();
+        // The size of rows in the heap is estimated with the average size of input rows.
+        let mut input_size = 0;
+        let mut input_cardinality = 0;
+        let mut mem_tracker = self.memory_context.new_tracker();
+        while let Some(data_chunk) = stream.next().await {
             let data_chunk = data_chunk?;
+            input_size += estimated_chunk_size(&data_chunk);
+            input_cardinality += data_chunk.cardinality();
             self.top_n_heap.fit(data_chunk);
+
+            let heap_size = self.top_n_heap.min_heap.len() * input_size / input_cardinality.max(1);
+            if heap_size > self.memory_quota {
+                mem_tracker.release_all();
+                let limit = self.top_n_heap.size - self.offset;
+                let mut sorter = ExternalSorter::new(
+                    self.top_n_heap.order_pairs.clone(),
+                    data_types,
+                    self.memory_quota,
+                    self.chunk_size,
+                    self.memory_context.new_tracker(),
+                )
+                .with_limit(limit, self.offset);
+                for data_chunk in self.top_n_heap.take_chunks(self.chunk_size)? {
+                    sorter.push_chunk(data_chunk)?;
+                }
+                while let Some(data_chunk) = stream.next().await {
+                    sorter.push_chunk(data_chunk?)?;
+                }
+                #[for_await]
+                for data_chunk in sorter.into_stream() {
+                    yield data_chunk?;
+                }
+                return Ok(());
+            }
+            if heap_size > mem_tracker.reserved() {
+                mem_tracker.reserve(heap_size - mem_tracker.reserved())?;
+            }
         }
 
         if let Some(data_chunk) = self.top_n_heap.dump(self.offset) {
@@ -224,6 +278,8 @@ mod tests {
             1,
             "TopNExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            usize::MAX,
+            MemoryContext::default(),
         ));
         let fields = &top_n_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Int32);
@@ -245,4 +301,62 @@ mod tests {
         let res = stream.next().await;
         assert!(matches!(res, None));
     }
+
+    #[tokio::test]
+    async fn test_top_n_with_spill() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let mut mock_executor = MockExecutor::new(schema);
+        let mut rows = vec![];
+        for i in 0..40 {
+            let mut pretty = "i I".to_string();
+            for j in 0..5 {
+                let v1 = (i * 5 + j) * 37 % 101;
+                let v0 = v1 % 7;
+                pretty.push_str(&format!("\n{} {}", v0, v1));
+                rows.push((Some(v0 as i32), Some(v1 as i64)));
+            }
+            mock_executor.add(DataChunk::from_pretty(&pretty));
+        }
+        rows.sort_by(|(l0, l1), (r0, r1)| l0.cmp(r0).then(r1.cmp(l1)));
+
+        let order_pairs = vec![
+            OrderPair {
+                column_idx: 0,
+                order_type: OrderType::Ascending,
+            },
+            OrderPair {
+                column_idx: 1,
+                order_type: OrderType::Descending,
+            },
+        ];
+        let top_n_executor = Box::new(TopNExecutor::new(
+            Box::new(mock_executor),
+            order_pairs,
+            50,
+            10,
+            "TopNExecutor2".to_string(),
+            16,
+            0,
+            MemoryContext::default(),
+        ));
+
+        let mut output = vec![];
+        let mut stream = top_n_executor.execute();
+        while let Some(res) = stream.next().await {
+            let res = res.unwrap();
+            output.extend(
+                res.column_at(0)
+                    .array()
+                    .as_int32()
+                    .iter()
+                    .zip(res.column_at(1).array().as_int64().iter()),
+            );
+        }
+        assert_eq!(output, rows[10..60]);
+    }
 }
@@ -24,6 +24,7 @@ use risingwave_expr::expr::{build_from_prost, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_source::SourceManagerRef;
 
+use crate::executor::delete::coalesce_for_write;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
@@ -94,8 +95,8 @@ impl UpdateExecutor {
         let mut notifiers = Vec::new();
 
         #[for_await]
-        for data_chunk in self.child.execute() {
-            let data_chunk = data_chunk?.compact()?;
+        for data_chunk in coalesce_for_write(self.child) {
+            let data_chunk = data_chunk?;
             let len = data_chunk.cardinality();
 
             let updated_data_chunk = {
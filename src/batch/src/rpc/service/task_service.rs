@@ -15,6 +15,7 @@
 use std::convert::Into;
 use std::sync::Arc;
 
+use risingwave_common::util::tracing_context::TracingContext;
 use risingwave_pb::batch_plan::TaskOutputId;
 use risingwave_pb::task_service::task_service_server::TaskService;
 use risingwave_pb::task_service::{
@@ -24,6 +25,7 @@ use risingwave_pb::task_service::{
 };
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
+use tracing_futures::Instrument;
 
 use crate::rpc::service::exchange::GrpcExchangeWriter;
 use crate::task::{BatchEnvironment, BatchManager, BatchTaskExecution, ComputeNodeContext};
@@ -52,15 +54,24 @@ impl TaskService for BatchServiceImpl {
         request: Request<CreateTaskRequest>,
     ) -> Result<Response<CreateTaskResponse>, Status> {
         let req = request.into_inner();
+        let task_id = req.get_task_id().expect("no task id found");
+        let span =
+            TracingContext::from_protobuf(&req.tracing_context).attach(tracing::trace_span!(
+                "create_task",
+                task_id = task_id.task_id,
+                stage_id = task_id.stage_id,
+                query_id = %task_id.query_id,
+            ));
 
         let res = self
             .mgr
             .fire_task(
-                req.get_task_id().expect("no task id found"),
+                task_id,
                 req.get_plan().expect("no plan found").clone(),
                 req.epoch,
-                ComputeNodeContext::new(self.env.clone()),
+                ComputeNodeContext::new(self.env.clone(), &task_id.query_id),
             )
+            .instrument(span)
             .await;
         match res {
             Ok(_) => Ok(Response::new(CreateTaskResponse { status: None })),
@@ -124,7 +135,7 @@ impl TaskService for BatchServiceImpl {
         let task_id = req.get_task_id().expect("no task id found");
         let plan = req.get_plan().expect("no plan found").clone();
         let epoch = req.epoch;
-        let context = ComputeNodeContext::new(self.env.clone());
+        let context = ComputeNodeContext::new(self.env.clone(), &task_id.query_id);
         let task = BatchTaskExecution::new(task_id, plan, context, epoch)?;
         let task = Arc::new(task);
         if let Err(e) = task.clone().async_execute().await {
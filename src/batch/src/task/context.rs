@@ -21,7 +21,7 @@ use risingwave_source::SourceManagerRef;
 use risingwave_storage::StateStoreImpl;
 
 use crate::executor::BatchMetrics;
-use crate::task::{BatchEnvironment, TaskOutput, TaskOutputId};
+use crate::task::{BatchEnvironment, MemoryContext, TaskOutput, TaskOutputId};
 
 /// Context for batch task execution.
 ///
@@ -52,12 +52,22 @@ pub trait BatchTaskContext: Clone + Send + Sync + 'static {
     }
 
     fn stats(&self) -> Arc<BatchMetrics>;
+
+    /// The max size in bytes of the build side of a hash join kept in memory before spilling.
+    fn hash_join_memory_quota(&self) -> usize;
+
+    /// The max size in bytes of the input of a sort kept in memory before spilling.
+    fn sort_memory_quota(&self) -> usize;
+
+    /// The memory context of the query the task belongs to.
+    fn memory_context(&self) -> MemoryContext;
 }
 
 /// Batch task context on compute node.
 #[derive(Clone)]
 pub struct ComputeNodeContext {
     env: BatchEnvironment,
+    memory_context: MemoryContext,
 }
 
 impl BatchTaskContext for ComputeNodeContext {
@@ -82,6 +92,18 @@ impl BatchTaskContext for ComputeNodeContext {
     fn stats(&self) -> Arc<BatchMetrics> {
         self.env.stats()
     }
+
+    fn hash_join_memory_quota(&self) -> usize {
+        self.env.config().hash_join_memory_quota_mb << 20
+    }
+
+    fn sort_memory_quota(&self) -> usize {
+        self.env.config().sort_memory_quota_mb << 20
+    }
+
+    fn memory_context(&self) -> MemoryContext {
+        self.memory_context.clone()
+    }
 }
 
 impl ComputeNodeContext {
@@ -89,10 +111,17 @@ impl ComputeNodeContext {
     pub fn new_for_test() -> Self {
         Self {
             env: BatchEnvironment::for_test(),
+            memory_context: MemoryContext::default(),
         }
     }
 
-    pub fn new(env: BatchEnvironment) -> Self {
-        Self { env }
+    pub fn new(env: BatchEnvironment, query_id: &str) -> Self {
+        let memory_context = env
+            .task_manager()
+            .memory_context(query_id, env.config().query_memory_quota_mb << 20);
+        Self {
+            env,
+            memory_context,
+        }
     }
 }
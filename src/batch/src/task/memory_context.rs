@@ -0,0 +1,195 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory accounting of batch queries. Each query has a [`MemoryContext`] on every compute node it
+//! runs on, shared by all its tasks there. The executors holding large amounts of data, e.g., hash
+//! tables and sort buffers, reserve memory from it via their own [`MemoryTracker`], so that a query
+//! using more memory than the quota fails with an error instead of taking down the node.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+use risingwave_common::error::{ErrorCode, Result};
+
+#[derive(Debug, Default)]
+struct MemoryContextInner {
+    /// Bytes reserved by all trackers of the query.
+    used: AtomicUsize,
+    /// `0` for no limit.
+    quota: usize,
+}
+
+/// The memory used by a query on this node.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryContext {
+    inner: Arc<MemoryContextInner>,
+}
+
+impl MemoryContext {
+    /// Create a context allowing `quota` bytes in total. `0` means no limit.
+    pub fn new(quota: usize) -> Self {
+        Self {
+            inner: Arc::new(MemoryContextInner {
+                used: AtomicUsize::new(0),
+                quota,
+            }),
+        }
+    }
+
+    /// Bytes reserved by all trackers of the query.
+    pub fn used(&self) -> usize {
+        self.inner.used.load(Ordering::Relaxed)
+    }
+
+    /// `None` if the query may use any amount of memory.
+    pub fn quota(&self) -> Option<usize> {
+        (self.inner.quota > 0).then(|| self.inner.quota)
+    }
+
+    /// Create a tracker for an executor to reserve memory from the context.
+    pub fn new_tracker(&self) -> MemoryTracker {
+        MemoryTracker {
+            context: self.clone(),
+            reserved: 0,
+        }
+    }
+
+    fn try_reserve(&self, bytes: usize) -> Result<()> {
+        let used = self.inner.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        match self.quota() {
+            Some(quota) if used > quota => {
+                self.inner.used.fetch_sub(bytes, Ordering::Relaxed);
+                Err(ErrorCode::QueryOutOfMemory(format!(
+                    "failed to reserve {} bytes with {} of {} bytes used",
+                    bytes,
+                    used - bytes,
+                    quota
+                ))
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn release(&self, bytes: usize) {
+        self.inner.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// The memory reserved by one executor from the [`MemoryContext`] of its query. All of it is
+/// released when the tracker is dropped.
+#[derive(Debug)]
+pub struct MemoryTracker {
+    context: MemoryContext,
+    reserved: usize,
+}
+
+impl MemoryTracker {
+    /// Reserve `bytes` more, or fail the query if that exceeds its quota.
+    pub fn reserve(&mut self, bytes: usize) -> Result<()> {
+        self.context.try_reserve(bytes)?;
+        self.reserved += bytes;
+        Ok(())
+    }
+
+    /// Release `bytes` of the memory reserved before.
+    pub fn release(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.reserved);
+        self.context.release(bytes);
+        self.reserved -= bytes;
+    }
+
+    /// Release all memory reserved by the tracker.
+    pub fn release_all(&mut self) {
+        self.release(self.reserved);
+    }
+
+    pub fn reserved(&self) -> usize {
+        self.reserved
+    }
+}
+
+impl Drop for MemoryTracker {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+/// The memory contexts of the queries running on this node. A context lives as long as any task of
+/// its query holds it.
+#[derive(Debug, Default)]
+pub struct QueryMemoryContexts {
+    contexts: Mutex<HashMap<String, Weak<MemoryContextInner>>>,
+}
+
+impl QueryMemoryContexts {
+    /// Get the context of the query, creating it with the quota if no task of the query is running.
+    pub fn get_or_create(&self, query_id: &str, quota: usize) -> MemoryContext {
+        let mut contexts = self.contexts.lock();
+        contexts.retain(|_, context| context.strong_count() > 0);
+        if let Some(inner) = contexts.get(query_id).and_then(Weak::upgrade) {
+            return MemoryContext { inner };
+        }
+        let context = MemoryContext::new(quota);
+        contexts.insert(query_id.to_string(), Arc::downgrade(&context.inner));
+        context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_tracker() {
+        let context = MemoryContext::new(100);
+        let mut tracker1 = context.new_tracker();
+        let mut tracker2 = context.new_tracker();
+
+        tracker1.reserve(60).unwrap();
+        tracker2.reserve(40).unwrap();
+        assert_eq!(context.used(), 100);
+
+        let err = tracker2.reserve(1).unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::QueryOutOfMemory(_)));
+        assert_eq!(tracker2.reserved(), 40);
+        assert_eq!(context.used(), 100);
+
+        tracker1.release(20);
+        tracker2.reserve(10).unwrap();
+        assert_eq!(context.used(), 90);
+
+        drop(tracker2);
+        assert_eq!(context.used(), 40);
+        tracker1.release(1000);
+        assert_eq!(context.used(), 0);
+    }
+
+    #[test]
+    fn test_query_memory_contexts() {
+        let contexts = QueryMemoryContexts::default();
+        let context = contexts.get_or_create("q1", 100);
+        let mut tracker = contexts.get_or_create("q1", 100).new_tracker();
+        tracker.reserve(10).unwrap();
+        assert_eq!(context.used(), 10);
+        assert_eq!(contexts.get_or_create("q2", 100).used(), 0);
+
+        drop(tracker);
+        drop(context);
+        tracker = contexts.get_or_create("q1", 50).new_tracker();
+        assert_eq!(tracker.context.quota(), Some(50));
+    }
+}
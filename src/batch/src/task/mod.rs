@@ -14,6 +14,7 @@
 
 pub use context::*;
 pub use env::*;
+pub use memory_context::*;
 pub use task_::*;
 pub use task_manager::*;
 
@@ -23,5 +24,6 @@ mod context;
 mod env;
 mod fifo_channel;
 mod hash_shuffle_channel;
+mod memory_context;
 mod task_;
 mod task_manager;
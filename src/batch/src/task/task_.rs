@@ -19,6 +19,7 @@ use futures::StreamExt;
 use parking_lot::Mutex;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_expr::expr::WithEpoch;
 use risingwave_pb::batch_plan::{
     PlanFragment, TaskId as ProstTaskId, TaskOutputId as ProstOutputId,
 };
@@ -238,6 +239,14 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             .extend(receivers.into_iter().map(Some));
         let failure = self.failure.clone();
         let task_id = self.task_id.clone();
+        // Created out of the spawned tasks so that it's a child of the span of the caller, e.g.,
+        // the one following the `CreateTask` RPC.
+        let span = tracing::trace_span!(
+            "batch_execute",
+            task_id = ?task_id.task_id,
+            stage_id = ?task_id.stage_id,
+            query_id = ?task_id.query_id,
+        );
         tokio::spawn(async move {
             trace!("Executing plan [{:?}]", task_id);
             let mut sender = sender;
@@ -249,12 +258,7 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
                 // close it after task error has been set.
                 if let Err(e) = self
                     .try_execute(exec, &mut sender, shutdown_rx)
-                    .instrument(tracing::trace_span!(
-                        "batch_execute",
-                        task_id = ?task_id.task_id,
-                        stage_id = ?task_id.stage_id,
-                        query_id = ?task_id.query_id,
-                    ))
+                    .instrument(span)
                     .await
                 {
                     // Prints the entire backtrace of error.
@@ -277,7 +281,8 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
         sender: &mut ChanSenderImpl,
         mut shutdown_rx: Receiver<u64>,
     ) -> Result<()> {
-        let mut data_chunk_stream = root.execute();
+        // All the expressions of the task, e.g. `now()`, are evaluated against the epoch of it.
+        let mut data_chunk_stream = WithEpoch::new(self.epoch, root.execute(), |_| None);
         loop {
             tokio::select! {
                 // We prioritize abort signal over normal data chunks.
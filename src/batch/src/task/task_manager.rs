@@ -27,22 +27,34 @@ use tokio::sync::mpsc::Sender;
 use tonic::Status;
 
 use crate::rpc::service::exchange::GrpcExchangeWriter;
-use crate::task::{BatchTaskExecution, ComputeNodeContext, TaskId, TaskOutput, TaskOutputId};
+use crate::task::{
+    BatchTaskExecution, ComputeNodeContext, MemoryContext, QueryMemoryContexts, TaskId, TaskOutput,
+    TaskOutputId,
+};
 
 /// `BatchManager` is responsible for managing all batch tasks.
 #[derive(Clone)]
 pub struct BatchManager {
     /// Every task id has a corresponding task execution.
     tasks: Arc<Mutex<HashMap<TaskId, Arc<BatchTaskExecution<ComputeNodeContext>>>>>,
+    /// Memory contexts of the queries running on this node.
+    memory_contexts: Arc<QueryMemoryContexts>,
 }
 
 impl BatchManager {
     pub fn new() -> Self {
         BatchManager {
             tasks: Arc::new(Mutex::new(HashMap::new())),
+            memory_contexts: Arc::new(QueryMemoryContexts::default()),
         }
     }
 
+    /// Get the memory context shared by the tasks of the query on this node. `quota` is in bytes
+    /// and only used if the context is created.
+    pub fn memory_context(&self, query_id: &str, quota: usize) -> MemoryContext {
+        self.memory_contexts.get_or_create(query_id, quota)
+    }
+
     pub async fn fire_task(
         &self,
         tid: &ProstTaskId,
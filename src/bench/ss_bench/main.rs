@@ -52,12 +52,21 @@ pub(crate) struct Opts {
     #[clap(long, default_value_t = 256)]
     shared_buffer_capacity_mb: u32,
 
+    #[clap(long, default_value_t = 0)]
+    write_stall_l0_sst_count: u32,
+
     #[clap(long, default_value_t = 2)]
     share_buffers_sync_parallelism: u32,
 
     #[clap(long, default_value_t = 0.1)]
     bloom_false_positive: f64,
 
+    #[clap(long, default_value = "none")]
+    compression_algorithm: String,
+
+    #[clap(long, default_value_t = 4)]
+    compression_level: i32,
+
     #[clap(long, default_value_t = 0)]
     compact_level_after_write: u32,
 
@@ -142,8 +151,12 @@ async fn main() {
     println!("Configurations after preprocess:\n {:?}", &opts);
 
     let config = Arc::new(StorageConfig {
+        shared_buffer_threshold_mb: opts.shared_buffer_threshold_mb,
         shared_buffer_capacity_mb: opts.shared_buffer_capacity_mb,
+        write_stall_l0_sst_count: opts.write_stall_l0_sst_count,
         bloom_false_positive: opts.bloom_false_positive,
+        compression_algorithm: opts.compression_algorithm.clone(),
+        compression_level: opts.compression_level,
         sstable_size_mb: opts.table_size_mb,
         block_size_kb: opts.block_size_kb,
         share_buffers_sync_parallelism: opts.share_buffers_sync_parallelism,
@@ -27,7 +27,7 @@ async fn main() {
     let opts = risingwave_compactor::CompactorOpts::parse();
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(false, false);
+    risingwave_logging::init_risingwave_logger(None, false);
 
     risingwave_compactor::start(opts).await;
 }
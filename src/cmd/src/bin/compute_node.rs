@@ -27,7 +27,10 @@ async fn main() {
     let opts = risingwave_compute::ComputeNodeOpts::parse();
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(opts.enable_jaeger_tracing, false);
+    risingwave_logging::init_risingwave_logger(
+        opts.enable_jaeger_tracing.then(|| "compute"),
+        false,
+    );
 
     risingwave_compute::start(opts).await
 }
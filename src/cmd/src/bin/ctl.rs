@@ -27,7 +27,7 @@ async fn main() {
     let opts = risingwave_ctl::CliOpts::parse();
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(false, true);
+    risingwave_logging::init_risingwave_logger(None, true);
 
     risingwave_ctl::start(opts).await
 }
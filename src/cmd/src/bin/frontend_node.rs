@@ -27,7 +27,10 @@ async fn main() {
     let opts = risingwave_frontend::FrontendOpts::parse();
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(false, false);
+    risingwave_logging::init_risingwave_logger(
+        opts.enable_jaeger_tracing.then(|| "frontend"),
+        false,
+    );
 
     risingwave_frontend::start(opts).await
 }
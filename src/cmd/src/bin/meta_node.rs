@@ -27,7 +27,7 @@ async fn main() {
     let opts = risingwave_meta::MetaNodeOpts::parse();
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(false, false);
+    risingwave_logging::init_risingwave_logger(opts.enable_jaeger_tracing.then(|| "meta"), false);
 
     risingwave_meta::start(opts).await
 }
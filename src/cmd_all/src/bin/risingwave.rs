@@ -46,7 +46,10 @@ async fn main() {
                     let opts = risingwave_compute::ComputeNodeOpts::parse_from(args);
 
                     risingwave_logging::oneshot_common();
-                    risingwave_logging::init_risingwave_logger(opts.enable_jaeger_tracing, false);
+                    risingwave_logging::init_risingwave_logger(
+                        opts.enable_jaeger_tracing.then(|| "compute"),
+                        false,
+                    );
 
                     risingwave_compute::start(opts).await
                 })
@@ -65,7 +68,10 @@ async fn main() {
                     let opts = risingwave_meta::MetaNodeOpts::parse_from(args);
 
                     risingwave_logging::oneshot_common();
-                    risingwave_logging::init_risingwave_logger(false, false);
+                    risingwave_logging::init_risingwave_logger(
+                        opts.enable_jaeger_tracing.then(|| "meta"),
+                        false,
+                    );
 
                     risingwave_meta::start(opts).await
                 })
@@ -84,7 +90,10 @@ async fn main() {
                     let opts = risingwave_frontend::FrontendOpts::parse_from(args);
 
                     risingwave_logging::oneshot_common();
-                    risingwave_logging::init_risingwave_logger(false, false);
+                    risingwave_logging::init_risingwave_logger(
+                        opts.enable_jaeger_tracing.then(|| "frontend"),
+                        false,
+                    );
 
                     risingwave_frontend::start(opts).await
                 })
@@ -103,7 +112,7 @@ async fn main() {
                     let opts = risingwave_compactor::CompactorOpts::parse_from(args);
 
                     risingwave_logging::oneshot_common();
-                    risingwave_logging::init_risingwave_logger(false, false);
+                    risingwave_logging::init_risingwave_logger(None, false);
 
                     risingwave_compactor::start(opts).await
                 })
@@ -120,7 +129,7 @@ async fn main() {
 
                 let opts = risingwave_ctl::CliOpts::parse_from(args);
                 risingwave_logging::oneshot_common();
-                risingwave_logging::init_risingwave_logger(false, true);
+                risingwave_logging::init_risingwave_logger(None, true);
 
                 risingwave_ctl::start(opts).await
             })
@@ -145,15 +154,22 @@ async fn main() {
         if let Ok(target) = env::var("RW_NODE") {
             // RW_NODE=meta ./risingwave <args>
             (target, env::args().collect())
+        } else if env::args().len() == 1 && binary_name() == "risingwave" {
+            // ./risingwave, which starts the playground so that `cargo run --bin risingwave` just
+            // works
+            ("playground".to_string(), env::args().collect())
         } else {
             // ./meta-node <args>
-            let x = env::args().next().expect("cannot find argv[0]");
-            let x = x.rsplit('/').next().expect("cannot find binary name");
-            let target = x.to_string();
-            (target, env::args().collect())
+            (binary_name(), env::args().collect())
         }
     }
 
+    fn binary_name() -> String {
+        let x = env::args().next().expect("cannot find argv[0]");
+        let x = x.rsplit('/').next().expect("cannot find binary name");
+        x.to_string()
+    }
+
     let (target, args) = get_target(fns.keys().copied().collect());
 
     match fns.remove(target.as_str()) {
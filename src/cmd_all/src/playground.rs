@@ -22,6 +22,7 @@ use risedev::{
     CompactorService, ComputeNodeService, ConfigExpander, FrontendService, MetaNodeService,
     ServiceConfig,
 };
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 use tokio::signal;
@@ -54,7 +55,7 @@ pub async fn playground() -> Result<()> {
     eprintln!("launching playground");
 
     risingwave_logging::oneshot_common();
-    risingwave_logging::init_risingwave_logger(false, true);
+    risingwave_logging::init_risingwave_logger(None, true);
 
     let profile = if let Ok(profile) = std::env::var("PLAYGROUND_PROFILE") {
         profile.to_string()
@@ -117,6 +118,7 @@ pub async fn playground() -> Result<()> {
         }
     };
 
+    let mut frontend_address = None;
     for service in services {
         match service {
             RisingWaveService::Meta(mut opts) => {
@@ -141,6 +143,7 @@ pub async fn playground() -> Result<()> {
                 tracing::info!("starting frontend-node thread with cli args: {:?}", opts);
                 let opts = risingwave_frontend::FrontendOpts::parse_from(opts);
                 tracing::info!("opts: {:#?}", opts);
+                frontend_address = Some(opts.host.clone());
                 let _frontend_handle =
                     tokio::spawn(async move { risingwave_frontend::start(opts).await });
             }
@@ -155,6 +158,16 @@ pub async fn playground() -> Result<()> {
         }
     }
 
+    if let Some(address) = frontend_address {
+        let (host, port) = address
+            .rsplit_once(':')
+            .unwrap_or((address.as_str(), "4566"));
+        eprintln!(
+            "playground is starting, connect to it via:\n\n    psql -h {} -p {} -d {} -U {}\n",
+            host, port, DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER
+        );
+    }
+
     // TODO: should we join all handles?
     // Currently, not all services can be shutdown gracefully, just quit on Ctrl-C now.
     signal::ctrl_c().await.unwrap();
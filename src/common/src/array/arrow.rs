@@ -0,0 +1,325 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between the arrays of RisingWave and [Apache Arrow](https://arrow.apache.org), e.g.,
+//! to export the results of a query to data frame libraries.
+//!
+//! Decimals are exported as strings, as the values in a decimal column may have different scales,
+//! or be `NaN` or infinities. Intervals, structs and lists are not supported yet.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as ArrowArray, ArrayRef as ArrowArrayRef, BooleanArray, Date32Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, StringArray, Time64MicrosecondArray,
+    TimestampMicrosecondArray,
+};
+use arrow::datatypes::{
+    DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use chrono::{Datelike, Timelike};
+use itertools::Itertools;
+
+use super::column::Column;
+use super::{
+    ArrayBuilder, ArrayImpl, BoolArrayBuilder, DataChunk, F32ArrayBuilder, F64ArrayBuilder,
+    I16ArrayBuilder, I32ArrayBuilder, I64ArrayBuilder, NaiveDateArrayBuilder,
+    NaiveDateTimeArrayBuilder, NaiveTimeArrayBuilder, Utf8ArrayBuilder,
+};
+use crate::array::Array;
+use crate::catalog::{Field, Schema};
+use crate::error::{ErrorCode, Result, RwError};
+use crate::types::{
+    DataType, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
+    UNIX_EPOCH_DAYS,
+};
+
+/// The time zone of the exported `TIMESTAMP WITH TIME ZONE` values, which are stored in UTC.
+const UTC_TIME_ZONE: &str = "+00:00";
+
+impl From<ArrowError> for RwError {
+    fn from(e: ArrowError) -> Self {
+        ErrorCode::InternalError(format!("Arrow error: {}", e)).into()
+    }
+}
+
+fn not_supported(data_type: impl std::fmt::Debug) -> RwError {
+    ErrorCode::NotImplemented(
+        format!("conversion of {:?} between RisingWave and Arrow", data_type),
+        None.into(),
+    )
+    .into()
+}
+
+/// The Arrow type that the values of `data_type` are exported as.
+pub fn to_arrow_data_type(data_type: &DataType) -> Result<ArrowDataType> {
+    Ok(match data_type {
+        DataType::Boolean => ArrowDataType::Boolean,
+        DataType::Int16 => ArrowDataType::Int16,
+        DataType::Int32 => ArrowDataType::Int32,
+        DataType::Int64 => ArrowDataType::Int64,
+        DataType::Float32 => ArrowDataType::Float32,
+        DataType::Float64 => ArrowDataType::Float64,
+        DataType::Decimal | DataType::Varchar => ArrowDataType::Utf8,
+        DataType::Date => ArrowDataType::Date32,
+        DataType::Time => ArrowDataType::Time64(TimeUnit::Microsecond),
+        DataType::Timestamp => ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+        DataType::Timestampz => {
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(UTC_TIME_ZONE.to_string()))
+        }
+        DataType::Interval | DataType::Struct { .. } | DataType::List { .. } => {
+            return Err(not_supported(data_type))
+        }
+    })
+}
+
+/// The type that the values of the Arrow type `data_type` are imported as.
+pub fn from_arrow_data_type(data_type: &ArrowDataType) -> Result<DataType> {
+    Ok(match data_type {
+        ArrowDataType::Boolean => DataType::Boolean,
+        ArrowDataType::Int16 => DataType::Int16,
+        ArrowDataType::Int32 => DataType::Int32,
+        ArrowDataType::Int64 => DataType::Int64,
+        ArrowDataType::Float32 => DataType::Float32,
+        ArrowDataType::Float64 => DataType::Float64,
+        ArrowDataType::Utf8 => DataType::Varchar,
+        ArrowDataType::Date32 => DataType::Date,
+        ArrowDataType::Time64(TimeUnit::Microsecond) => DataType::Time,
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => DataType::Timestamp,
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => DataType::Timestampz,
+        _ => return Err(not_supported(data_type)),
+    })
+}
+
+pub fn to_arrow_schema(schema: &Schema) -> Result<ArrowSchema> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(ArrowField::new(
+                &field.name,
+                to_arrow_data_type(&field.data_type)?,
+                true,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ArrowSchema::new(fields))
+}
+
+pub fn from_arrow_schema(schema: &ArrowSchema) -> Result<Schema> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(Field::with_name(
+                from_arrow_data_type(field.data_type())?,
+                field.name(),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Schema::new(fields))
+}
+
+/// Converts `array`, which holds the values of `data_type`, to an Arrow array. The type is needed
+/// as some types share the same array, e.g., `TIMESTAMP WITH TIME ZONE` is stored in `I64Array`.
+pub fn to_arrow_array(array: &ArrayImpl, data_type: &DataType) -> Result<ArrowArrayRef> {
+    let array: ArrowArrayRef = match (array, data_type) {
+        (ArrayImpl::Bool(array), _) => Arc::new(BooleanArray::from_iter(array.iter())),
+        (ArrayImpl::Int16(array), _) => Arc::new(Int16Array::from_iter(array.iter())),
+        (ArrayImpl::Int32(array), _) => Arc::new(Int32Array::from_iter(array.iter())),
+        (ArrayImpl::Int64(array), DataType::Timestampz) => {
+            Arc::new(TimestampMicrosecondArray::from_opt_vec(
+                array.iter().collect(),
+                Some(UTC_TIME_ZONE.to_string()),
+            ))
+        }
+        (ArrayImpl::Int64(array), _) => Arc::new(Int64Array::from_iter(array.iter())),
+        (ArrayImpl::Float32(array), _) => Arc::new(Float32Array::from_iter(
+            array.iter().map(|v| v.map(|v| v.0)),
+        )),
+        (ArrayImpl::Float64(array), _) => Arc::new(Float64Array::from_iter(
+            array.iter().map(|v| v.map(|v| v.0)),
+        )),
+        (ArrayImpl::Utf8(array), _) => Arc::new(StringArray::from_iter(array.iter())),
+        (ArrayImpl::Decimal(array), _) => Arc::new(StringArray::from_iter(
+            array.iter().map(|v| v.map(|v| v.to_string())),
+        )),
+        (ArrayImpl::NaiveDate(array), _) => Arc::new(Date32Array::from_iter(
+            array
+                .iter()
+                .map(|v| v.map(|v| v.0.num_days_from_ce() - UNIX_EPOCH_DAYS)),
+        )),
+        (ArrayImpl::NaiveTime(array), _) => {
+            Arc::new(Time64MicrosecondArray::from_iter(array.iter().map(|v| {
+                v.map(|v| {
+                    v.0.num_seconds_from_midnight() as i64 * 1_000_000
+                        + v.0.nanosecond() as i64 / 1000
+                })
+            })))
+        }
+        (ArrayImpl::NaiveDateTime(array), _) => Arc::new(TimestampMicrosecondArray::from_iter(
+            array
+                .iter()
+                .map(|v| v.map(|v| v.0.timestamp_nanos() / 1000)),
+        )),
+        _ => return Err(not_supported(data_type)),
+    };
+    Ok(array)
+}
+
+/// Appends the values of an Arrow array of type `$arrow_array` to `$builder`, converting each of
+/// them with the fallible `$convert`.
+macro_rules! build_from_arrow {
+    ($array:expr, $arrow_array:ty, $builder:ty, $convert:expr) => {{
+        let array = $array
+            .as_any()
+            .downcast_ref::<$arrow_array>()
+            .ok_or_else(|| not_supported($array.data_type()))?;
+        let mut builder = <$builder>::new(array.len())?;
+        for value in array.iter() {
+            let value: Option<Result<_>> = value.map($convert);
+            builder.append(value.transpose()?)?;
+        }
+        ArrayImpl::from(builder.finish()?)
+    }};
+}
+
+/// Converts an Arrow array to an array of the type given by [`from_arrow_data_type`].
+pub fn from_arrow_array(array: &dyn ArrowArray) -> Result<ArrayImpl> {
+    let array = match array.data_type() {
+        ArrowDataType::Boolean => build_from_arrow!(array, BooleanArray, BoolArrayBuilder, Ok),
+        ArrowDataType::Int16 => build_from_arrow!(array, Int16Array, I16ArrayBuilder, Ok),
+        ArrowDataType::Int32 => build_from_arrow!(array, Int32Array, I32ArrayBuilder, Ok),
+        ArrowDataType::Int64 => build_from_arrow!(array, Int64Array, I64ArrayBuilder, Ok),
+        ArrowDataType::Float32 => {
+            build_from_arrow!(array, Float32Array, F32ArrayBuilder, |v| Ok(
+                OrderedF32::from(v)
+            ))
+        }
+        ArrowDataType::Float64 => {
+            build_from_arrow!(array, Float64Array, F64ArrayBuilder, |v| Ok(
+                OrderedF64::from(v)
+            ))
+        }
+        ArrowDataType::Utf8 => build_from_arrow!(array, StringArray, Utf8ArrayBuilder, Ok),
+        ArrowDataType::Date32 => {
+            build_from_arrow!(array, Date32Array, NaiveDateArrayBuilder, |v| {
+                NaiveDateWrapper::from_protobuf(v)
+            })
+        }
+        ArrowDataType::Time64(TimeUnit::Microsecond) => {
+            build_from_arrow!(array, Time64MicrosecondArray, NaiveTimeArrayBuilder, |v| {
+                NaiveTimeWrapper::from_protobuf(v)
+            })
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => {
+            build_from_arrow!(array, TimestampMicrosecondArray, I64ArrayBuilder, Ok)
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => build_from_arrow!(
+            array,
+            TimestampMicrosecondArray,
+            NaiveDateTimeArrayBuilder,
+            NaiveDateTimeWrapper::from_protobuf
+        ),
+        data_type => return Err(not_supported(data_type)),
+    };
+    Ok(array)
+}
+
+impl DataChunk {
+    /// Converts the visible rows of the chunk to an Arrow record batch, whose columns are named and
+    /// typed after `schema`.
+    pub fn to_record_batch(self, schema: &Schema) -> Result<RecordBatch> {
+        let chunk = self.compact()?;
+        let columns = chunk
+            .columns()
+            .iter()
+            .zip_eq(schema.fields())
+            .map(|(column, field)| to_arrow_array(column.array_ref(), &field.data_type))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(
+            Arc::new(to_arrow_schema(schema)?),
+            columns,
+        )?)
+    }
+
+    /// Converts an Arrow record batch to a chunk, whose schema is given by [`from_arrow_schema`].
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self> {
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|array| Ok(Column::new(Arc::new(from_arrow_array(array.as_ref())?))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DataChunk::new(columns, batch.num_rows()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{I64Array, Utf8Array};
+    use crate::types::Decimal;
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int64, "id"),
+            Field::with_name(DataType::Varchar, "name"),
+            Field::with_name(DataType::Timestampz, "created_at"),
+        ]);
+        let chunk = DataChunk::new(
+            vec![
+                Column::new(Arc::new(
+                    I64Array::from_slice(&[Some(1), None, Some(3)])
+                        .unwrap()
+                        .into(),
+                )),
+                Column::new(Arc::new(
+                    Utf8Array::from_slice(&[Some("a"), Some("b"), None])
+                        .unwrap()
+                        .into(),
+                )),
+                Column::new(Arc::new(
+                    I64Array::from_slice(&[Some(1_000_000), Some(2_000_000), None])
+                        .unwrap()
+                        .into(),
+                )),
+            ],
+            3,
+        );
+
+        let batch = chunk.clone().to_record_batch(&schema).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            batch.schema().field(2).data_type(),
+            &ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(UTC_TIME_ZONE.to_string()))
+        );
+        assert_eq!(from_arrow_schema(&batch.schema()).unwrap(), schema);
+
+        assert_eq!(DataChunk::from_record_batch(&batch).unwrap(), chunk);
+    }
+
+    #[test]
+    fn test_decimal_to_string() {
+        let array = ArrayImpl::from(
+            crate::array::DecimalArray::from_slice(&[Some(Decimal::from(12)), None]).unwrap(),
+        );
+        let array = to_arrow_array(&array, &DataType::Decimal).unwrap();
+        let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(array.value(0), "12");
+        assert!(array.is_null(1));
+    }
+}
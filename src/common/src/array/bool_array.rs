@@ -40,6 +40,11 @@ impl BoolArray {
         }
         builder.finish()
     }
+
+    /// Returns the values of all rows, including the null ones whose values are unspecified.
+    pub fn values(&self) -> &Bitmap {
+        &self.data
+    }
 }
 
 impl Array for BoolArray {
@@ -22,12 +22,11 @@ use risingwave_pb::data::DataChunk as ProstDataChunk;
 
 use crate::array::column::Column;
 use crate::array::data_chunk_iter::{Row, RowRef};
-use crate::array::{ArrayBuilderImpl, ArrayImpl};
+use crate::array::{Array, ArrayBuilderImpl, ArrayImpl};
 use crate::buffer::Bitmap;
 use crate::error::{Result, RwError};
 use crate::hash::HashCode;
 use crate::types::{DataType, NaiveDateTimeWrapper};
-use crate::util::hash_util::finalize_hashers;
 
 pub struct DataChunkBuilder {
     columns: Vec<Column>,
@@ -85,6 +84,10 @@ pub struct DataChunk {
     vis2: Vis,
 }
 
+/// A chunk is compacted by `compact_if_sparse` if the fraction of its visible rows is lower than
+/// this.
+pub const COMPACT_SELECTIVITY_THRESHOLD: f64 = 0.5;
+
 /// `Vis` is a visibility bitmap of rows. When all rows are visible, it is considered compact and
 /// is represented by a single cardinality number rather than that many of ones.
 #[derive(Clone, PartialEq)]
@@ -253,10 +256,13 @@ impl DataChunk {
     pub fn compact(self) -> Result<Self> {
         match &self.vis2 {
             Vis::Compact(_) => Ok(self),
+            // All rows are visible, so no need to copy the columns.
+            Vis::Bitmap(visibility) if visibility.num_high_bits() == visibility.len() => {
+                let cardinality = visibility.len();
+                Ok(Self::new(self.columns, cardinality))
+            }
             Vis::Bitmap(visibility) => {
-                let cardinality = visibility
-                    .iter()
-                    .fold(0, |vis_cnt, vis| vis_cnt + vis as usize);
+                let cardinality = visibility.num_high_bits();
                 let columns = self
                     .columns
                     .into_iter()
@@ -272,6 +278,24 @@ impl DataChunk {
         }
     }
 
+    /// The fraction of the visible rows.
+    pub fn selectivity(&self) -> f64 {
+        match &self.vis2 {
+            Vis::Bitmap(visibility) => visibility.density(),
+            Vis::Compact(_) => 1.0,
+        }
+    }
+
+    /// Compacts the chunk only if the invisible rows are more than the visible ones. Otherwise it's
+    /// cheaper for the consumer to skip the invisible rows than to copy all columns.
+    pub fn compact_if_sparse(self) -> Result<Self> {
+        if self.selectivity() < COMPACT_SELECTIVITY_THRESHOLD {
+            self.compact()
+        } else {
+            Ok(self)
+        }
+    }
+
     pub fn from_protobuf(proto: &ProstDataChunk) -> Result<Self> {
         if proto.columns.is_empty() {
             // Dummy chunk, we should deserialize cardinality
@@ -383,16 +407,64 @@ impl DataChunk {
         column_idxes: &[usize],
         hasher_builder: H,
     ) -> Result<Vec<HashCode>> {
-        let mut states = Vec::with_capacity(self.capacity());
-        states.resize_with(self.capacity(), || hasher_builder.build_hasher());
+        let mut hashes = vec![0; self.capacity()];
         for column_idx in column_idxes {
-            let array = self.column_at(*column_idx).array();
-            array.hash_vec(&mut states[..]);
+            self.column_at(*column_idx)
+                .array_ref()
+                .hash_column(&hasher_builder, &mut hashes);
         }
-        Ok(finalize_hashers(&mut states[..])
+        Ok(hashes.into_iter().map(HashCode::from).collect_vec())
+    }
+
+    /// If the given columns are all dictionary-encoded strings, groups the rows by their codes in
+    /// these columns, so that the rows in a group have the same values. Returns the group of each
+    /// row along with the first row of each group. Returns `None` if any column is not encoded, or
+    /// there are too many combinations of the codes.
+    pub fn group_by_dictionary_codes(
+        &self,
+        column_idxes: &[usize],
+    ) -> Option<(Vec<usize>, Vec<usize>)> {
+        if column_idxes.is_empty() {
+            return None;
+        }
+        let capacity = self.capacity();
+        // The combination of the codes of each row, where a null takes the code after the values.
+        let mut combinations = vec![0; capacity];
+        let mut num_combinations = 1;
+        for column_idx in column_idxes {
+            let array = match self.column_at(*column_idx).array_ref() {
+                ArrayImpl::Utf8(array) => array,
+                _ => return None,
+            };
+            let codes = array.codes()?;
+            let null_code = array.dictionary_len();
+            for (idx, combination) in combinations.iter_mut().enumerate() {
+                let code = if array.is_null(idx) {
+                    null_code
+                } else {
+                    codes[idx] as usize
+                };
+                *combination += code * num_combinations;
+            }
+            num_combinations *= null_code + 1;
+            if num_combinations > capacity {
+                return None;
+            }
+        }
+
+        let mut group_of_combination = vec![None; num_combinations];
+        let mut first_rows = vec![];
+        let groups = combinations
             .into_iter()
-            .map(|hash_code| hash_code.into())
-            .collect_vec())
+            .enumerate()
+            .map(|(idx, combination)| {
+                *group_of_combination[combination].get_or_insert_with(|| {
+                    first_rows.push(idx);
+                    first_rows.len() - 1
+                })
+            })
+            .collect();
+        Some((groups, first_rows))
     }
 
     /// Random access a tuple in a data chunk. Return in a row format.
@@ -675,6 +747,55 @@ mod tests {
         test_case(10, 10, 7);
     }
 
+    #[test]
+    fn test_hash_dictionary_encoded() {
+        use std::hash::{BuildHasher, Hasher};
+        use std::sync::Arc;
+
+        use itertools::Itertools;
+
+        use crate::hash::HashCode;
+        use crate::util::hash_util::{combine_hash, CRC32FastBuilder};
+
+        let strings = (0..100)
+            .map(|i| [Some("a"), None, Some("b")][i % 3])
+            .collect_vec();
+        let encoded = Utf8Array::from_slice(&strings).unwrap();
+        assert!(encoded.codes().is_some());
+        let chunk = DataChunk::new(
+            vec![
+                Column::new(Arc::new(encoded.into())),
+                Column::new(Arc::new(
+                    I64Array::from_slice(&(0..100).map(Some).collect_vec())
+                        .unwrap()
+                        .into(),
+                )),
+            ],
+            100,
+        );
+        let (groups, first_rows) = chunk.group_by_dictionary_codes(&[0]).unwrap();
+        assert_eq!(first_rows, vec![0, 1, 2]);
+        assert_eq!(groups[3..6], [0, 1, 2]);
+        assert!(chunk.group_by_dictionary_codes(&[0, 1]).is_none());
+
+        // Same hashes as computed row by row.
+        let hashes = chunk.get_hash_values(&[0, 1], CRC32FastBuilder).unwrap();
+        let expected = (0..100)
+            .map(|idx| {
+                (0..2).fold(0, |row_hash, column_idx| {
+                    let mut state = CRC32FastBuilder.build_hasher();
+                    chunk
+                        .column_at(column_idx)
+                        .array_ref()
+                        .hash_at(idx, &mut state);
+                    combine_hash(row_hash, state.finish())
+                })
+            })
+            .map(HashCode::from)
+            .collect_vec();
+        assert_eq!(hashes, expected);
+    }
+
     #[test]
     fn test_chunk_iter() {
         let num_of_columns: usize = 2;
@@ -697,6 +818,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compact_if_sparse() {
+        use itertools::Itertools;
+
+        let dense = DataChunk::from_pretty(
+            "I I
+             1 6
+             2 7 D
+             3 8
+             4 9",
+        );
+        assert!((dense.selectivity() - 0.75).abs() < f64::EPSILON);
+        let dense = dense.compact_if_sparse().unwrap();
+        assert_eq!(dense.capacity(), 4);
+        assert_eq!(
+            dense.rows().map(|row| row.index()).collect_vec(),
+            vec![0, 2, 3]
+        );
+
+        let sparse = DataChunk::from_pretty(
+            "I I
+             1 6 D
+             2 7
+             3 8 D
+             4 9 D",
+        );
+        let sparse = sparse.compact_if_sparse().unwrap();
+        assert!(sparse.visibility().is_none());
+        assert_eq!(sparse.capacity(), 1);
+        assert_eq!(sparse.row_at(0).unwrap().0.value_at(1), Some(7i64.into()));
+
+        // Compacting a chunk whose rows are all visible doesn't copy the columns.
+        let all_visible = DataChunk::from_pretty(
+            "I
+             1
+             2",
+        );
+        let all_visible = all_visible
+            .with_visibility(Bitmap::try_from(vec![true, true]).unwrap())
+            .compact()
+            .unwrap();
+        assert!(all_visible.visibility().is_none());
+        assert_eq!(all_visible.cardinality(), 2);
+    }
+
     #[test]
     fn test_to_pretty_string() {
         let chunk = DataChunk::new(
@@ -12,22 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::hash::{BuildHasher, Hash, Hasher};
+use std::hash::{BuildHasher, Hasher};
 use std::ops;
+use std::sync::Arc;
 
 use bytes::Buf;
 use itertools::Itertools;
 
 use super::column::Column;
-use crate::array::DataChunk;
+use crate::array::{hash_datum, DataChunk};
 use crate::error::{ErrorCode, Result as RwResult};
 use crate::hash::HashCode;
 use crate::types::{
     deserialize_datum_from, deserialize_datum_not_null_from, serialize_datum_into,
     serialize_datum_not_null_into, DataType, Datum, DatumRef, ToOwnedDatum,
 };
+use crate::util::hash_util::combine_hash;
 use crate::util::sort_util::OrderType;
-use crate::util::value_encoding::{deserialize_datum, serialize_datum};
+use crate::util::value_encoding::compact_row::{
+    deserialize_row, deserialize_row_into, serialize_compact_row,
+};
+use crate::util::value_encoding::serialize_datum;
 
 impl DataChunk {
     /// Get an iterator for visible rows.
@@ -87,6 +92,11 @@ impl<'a> RowRef<'a> {
         Self { chunk, idx }
     }
 
+    /// The index of the row in the chunk, counting the invisible rows.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
     pub fn value_at(&self, pos: usize) -> DatumRef<'_> {
         debug_assert!(self.idx < self.chunk.capacity());
         // for `RowRef`, the index is always in bound.
@@ -268,6 +278,15 @@ impl Row {
         Ok(vec)
     }
 
+    /// Serialize the row into the compact value encoding, where the nulls are recorded in a
+    /// bitmap after a format version byte. See [`crate::util::value_encoding::compact_row`].
+    ///
+    /// Storage keys should keep using [`Row::value_encode`], so that the entries already written
+    /// under them can still be found.
+    pub fn compact_value_encode(&self) -> RwResult<Vec<u8>> {
+        serialize_compact_row(&self.0)
+    }
+
     /// Return number of cells in the row.
     pub fn size(&self) -> usize {
         self.0.len()
@@ -277,28 +296,28 @@ impl Row {
         self.0.iter()
     }
 
-    /// Hash row data all in one
+    /// Hash row data all in one. Like [`DataChunk::get_hash_values`], each datum is hashed on its
+    /// own and the hashes are combined, so that a row has the same hash as in a chunk.
     pub fn hash_row<H>(&self, hash_builder: &H) -> HashCode
     where
         H: BuildHasher,
     {
-        let mut hasher = hash_builder.build_hasher();
-        for datum in &self.0 {
-            datum.hash(&mut hasher);
-        }
-        HashCode(hasher.finish())
+        HashCode(self.0.iter().fold(0, |hash, datum| {
+            combine_hash(hash, hash_one(datum, hash_builder))
+        }))
     }
 
-    /// Compute hash value of a row on corresponding indices.
+    /// Compute hash value of a row on corresponding indices, which is the same as the hash of the
+    /// row computed by [`DataChunk::get_hash_values`] on these columns.
     pub fn hash_by_indices<H>(&self, hash_indices: &[usize], hash_builder: &H) -> RwResult<HashCode>
     where
         H: BuildHasher,
     {
-        let mut hasher = hash_builder.build_hasher();
+        let mut hash = 0;
         for idx in hash_indices {
             let datum = self.0.get(*idx);
             match datum {
-                Some(datum) => datum.hash(&mut hasher),
+                Some(datum) => hash = combine_hash(hash, hash_one(datum, hash_builder)),
                 None => {
                     return Err(
                         ErrorCode::InternalError(format!("index {} out of row bound", idx)).into(),
@@ -306,19 +325,34 @@ impl Row {
                 }
             }
         }
-        Ok(HashCode(hasher.finish()))
+        Ok(HashCode(hash))
     }
 }
 
-/// Deserializer of the `Row`.
+/// Hashes a datum with a hasher of its own.
+fn hash_one<H: BuildHasher>(datum: &Datum, hash_builder: &H) -> u64 {
+    let mut state = hash_builder.build_hasher();
+    hash_datum(datum, &mut state);
+    state.finish()
+}
+
+/// Deserializer of the `Row`. The schema is shared, so a deserializer is cheap to clone and
+/// should be created once and reused for all the rows of the same schema.
+#[derive(Clone)]
 pub struct RowDeserializer {
-    data_types: Vec<DataType>,
+    data_types: Arc<[DataType]>,
 }
 
 impl RowDeserializer {
     /// Creates a new `RowDeserializer` with row schema.
-    pub fn new(schema: Vec<DataType>) -> Self {
-        RowDeserializer { data_types: schema }
+    pub fn new(schema: impl Into<Arc<[DataType]>>) -> Self {
+        RowDeserializer {
+            data_types: schema.into(),
+        }
+    }
+
+    pub fn data_types(&self) -> &[DataType] {
+        &self.data_types
     }
 
     /// Deserialize the row from a memcomparable bytes.
@@ -359,13 +393,15 @@ impl RowDeserializer {
         Ok(datum)
     }
 
-    /// Deserialize the row from a value encoding bytes.
-    pub fn value_decode(&self, mut data: impl Buf) -> RwResult<Row> {
-        let mut values = Vec::with_capacity(self.data_types.len());
-        for ty in &self.data_types {
-            values.push(deserialize_datum(&mut data, ty)?);
-        }
-        Ok(Row(values))
+    /// Deserialize the row from a value encoding bytes, in either the compact or the legacy format.
+    pub fn value_decode(&self, data: impl Buf) -> RwResult<Row> {
+        Ok(Row(deserialize_row(data, &self.data_types)?))
+    }
+
+    /// Same as [`Self::value_decode`], but decodes into `row` to reuse its buffer, e.g. when the
+    /// decoded rows are only inspected one by one.
+    pub fn value_decode_into(&self, data: impl Buf, row: &mut Row) -> RwResult<()> {
+        deserialize_row_into(data, &self.data_types, &mut row.0)
     }
 }
 
@@ -437,6 +473,30 @@ mod tests {
         assert_eq!(row, row1);
     }
 
+    #[test]
+    fn row_value_encode_decode_reuse() {
+        let de = RowDeserializer::new(vec![Ty::Int32, Ty::Varchar, Ty::Float64]);
+        let rows = [
+            Row(vec![
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Utf8("a".into())),
+                None,
+            ]),
+            Row(vec![None, None, Some(ScalarImpl::Float64(2.0.into()))]),
+        ];
+
+        let mut decoded = Row::default();
+        for row in &rows {
+            de.clone()
+                .value_decode_into(&row.compact_value_encode().unwrap()[..], &mut decoded)
+                .unwrap();
+            assert_eq!(&decoded, row);
+            de.value_decode_into(&row.value_encode().unwrap()[..], &mut decoded)
+                .unwrap();
+            assert_eq!(&decoded, row);
+        }
+    }
+
     #[test]
     fn test_hash_row() {
         let hash_builder = CRC32FastBuilder {};
@@ -468,4 +528,30 @@ mod tests {
         let row_default = Row::default();
         assert_eq!(row_default.hash_row(&hash_builder).0, 0);
     }
+
+    #[test]
+    fn test_hash_row_same_as_chunk() {
+        use crate::array::DataChunkTestExt;
+
+        let chunk = DataChunk::from_pretty(
+            "I i  F   T   TS
+             1 2  3.5 foo 2022-01-01T00:00:00
+             . 2  .   bar .
+             4 .  0.5 .   2022-07-01T12:00:00
+             . .  .   .   .",
+        );
+        let hash_builder = CRC32FastBuilder {};
+        let hash_indices = [0, 1, 2, 3, 4];
+        let hashes = chunk
+            .get_hash_values(&hash_indices, CRC32FastBuilder {})
+            .unwrap();
+        for (row, hash) in chunk.rows().zip_eq(hashes) {
+            let row = row.to_owned_row();
+            assert_eq!(
+                row.hash_by_indices(&hash_indices, &hash_builder).unwrap(),
+                hash
+            );
+            assert_eq!(row.hash_row(&hash_builder), hash);
+        }
+    }
 }
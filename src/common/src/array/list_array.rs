@@ -196,7 +196,9 @@ impl Array for ListArray {
 
     fn hash_at<H: std::hash::Hasher>(&self, idx: usize, state: &mut H) {
         if !self.is_null(idx) {
-            self.value.hash_at(idx, state)
+            for value_idx in self.offsets[idx]..self.offsets[idx + 1] {
+                self.value.hash_at(value_idx, state)
+            }
         } else {
             NULL_VAL_FOR_HASH.hash(state);
         }
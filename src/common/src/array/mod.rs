@@ -14,6 +14,7 @@
 
 //! `Array` defines all in-memory representations of vectorized execution framework.
 
+pub mod arrow;
 mod bool_array;
 mod chrono_array;
 pub mod column;
@@ -33,7 +34,7 @@ mod utf8_array;
 mod value_reader;
 
 use std::convert::From;
-use std::hash::Hasher;
+use std::hash::{BuildHasher, Hasher};
 use std::sync::Arc;
 
 pub use bool_array::{BoolArray, BoolArrayBuilder};
@@ -42,7 +43,7 @@ pub use chrono_array::{
     NaiveTimeArray, NaiveTimeArrayBuilder,
 };
 pub use column_proto_readers::*;
-pub use data_chunk::{DataChunk, DataChunkTestExt, Vis};
+pub use data_chunk::{DataChunk, DataChunkTestExt, Vis, COMPACT_SELECTIVITY_THRESHOLD};
 pub use data_chunk_iter::{Row, RowDeserializer, RowRef};
 pub use decimal_array::{DecimalArray, DecimalArrayBuilder};
 pub use interval_array::{IntervalArray, IntervalArrayBuilder};
@@ -60,6 +61,7 @@ use crate::buffer::Bitmap;
 use crate::error::ErrorCode::InternalError;
 use crate::error::{Result, RwError};
 use crate::types::*;
+use crate::util::hash_util::combine_hash;
 
 pub type I64Array = PrimitiveArray<i64>;
 pub type I32Array = PrimitiveArray<i32>;
@@ -76,6 +78,26 @@ pub type F32ArrayBuilder = PrimitiveArrayBuilder<OrderedF32>;
 /// The hash source for `None` values when hashing an item.
 static NULL_VAL_FOR_HASH: u32 = 0xfffffff0;
 
+/// Hashes a datum in the same way as [`Array::hash_at`] does for the array it belongs to, so that a
+/// row is hashed the same as it is in a chunk.
+pub fn hash_datum<H: Hasher>(datum: &Datum, state: &mut H) {
+    match datum {
+        None => std::hash::Hash::hash(&NULL_VAL_FOR_HASH, state),
+        Some(ScalarImpl::Utf8(s)) => state.write(s.as_bytes()),
+        Some(ScalarImpl::Decimal(d)) => std::hash::Hash::hash(&d.normalize(), state),
+        Some(ScalarImpl::Struct(s)) => s.fields().iter().for_each(|d| hash_datum(d, state)),
+        Some(ScalarImpl::List(l)) => l.values().iter().for_each(|d| hash_datum(d, state)),
+        Some(scalar) => std::hash::Hash::hash(scalar, state),
+    }
+}
+
+/// The hash of a null value, as computed by [`Array::hash_at`].
+fn null_hash<B: BuildHasher>(hash_builder: &B) -> u64 {
+    let mut state = hash_builder.build_hasher();
+    std::hash::Hash::hash(&NULL_VAL_FOR_HASH, &mut state);
+    state.finish()
+}
+
 /// A trait over all array builders.
 ///
 /// `ArrayBuilder` is a trait over all builders. You could build an array with
@@ -197,6 +219,17 @@ pub trait Array: std::fmt::Debug + Send + Sync + Sized + 'static + Into<ArrayImp
         }
     }
 
+    /// Hashes the value of each row with a hasher of its own, and combines it into the hash of the
+    /// row in `hashes`, so that the hashes of a chunk are computed column by column.
+    fn hash_column<B: BuildHasher>(&self, hash_builder: &B, hashes: &mut [u64]) {
+        assert_eq!(hashes.len(), self.len());
+        for (idx, hash) in hashes.iter_mut().enumerate() {
+            let mut state = hash_builder.build_hasher();
+            self.hash_at(idx, &mut state);
+            *hash = combine_hash(*hash, state.finish());
+        }
+    }
+
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -227,12 +260,10 @@ trait CompactableArray: Array {
 
 impl<A: Array> CompactableArray for A {
     fn compact(&self, visibility: &Bitmap, cardinality: usize) -> Result<Self> {
-        use itertools::Itertools;
+        assert_eq!(visibility.len(), self.len());
         let mut builder = A::Builder::with_meta(cardinality, self.array_meta())?;
-        for (elem, visible) in self.iter().zip_eq(visibility.iter()) {
-            if visible {
-                builder.append(elem)?;
-            }
+        for idx in visibility.iter_ones() {
+            builder.append(self.value_at(idx))?;
         }
         builder.finish()
     }
@@ -483,6 +514,12 @@ macro_rules! impl_array {
                 }
             }
 
+            pub fn hash_column<B: BuildHasher>(&self, hash_builder: &B, hashes: &mut [u64]) {
+                match self {
+                    $( Self::$variant_name(inner) => inner.hash_column(hash_builder, hashes), )*
+                }
+            }
+
             /// Select some elements from `Array` based on `visibility` bitmap.
             pub fn compact(&self, visibility: &Bitmap, cardinality: usize) -> Result<Self> {
                 match self {
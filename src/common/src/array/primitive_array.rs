@@ -13,14 +13,15 @@
 // limitations under the License.
 
 use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::io::Write;
 use std::mem::size_of;
 
+use itertools::Itertools;
 use risingwave_pb::data::buffer::CompressionType;
 use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer};
 
-use super::{Array, ArrayBuilder, ArrayIterator, NULL_VAL_FOR_HASH};
+use super::{null_hash, Array, ArrayBuilder, ArrayIterator, NULL_VAL_FOR_HASH};
 use crate::array::{ArrayBuilderImpl, ArrayImpl, ArrayMeta};
 use crate::buffer::{Bitmap, BitmapBuilder};
 use crate::error::Result;
@@ -29,6 +30,7 @@ use crate::types::interval::IntervalUnit;
 use crate::types::{
     NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, NativeType, Scalar, ScalarRef,
 };
+use crate::util::hash_util::combine_hash;
 
 /// Physical type of array items which have fixed size.
 pub trait PrimitiveArrayItemType
@@ -141,6 +143,11 @@ pub struct PrimitiveArray<T: PrimitiveArrayItemType> {
 }
 
 impl<T: PrimitiveArrayItemType> PrimitiveArray<T> {
+    pub fn new(bitmap: Bitmap, data: Vec<T>) -> Self {
+        assert_eq!(bitmap.len(), data.len());
+        Self { bitmap, data }
+    }
+
     pub fn from_slice(data: &[Option<T>]) -> Result<Self> {
         let mut builder = <Self as Array>::Builder::new(data.len())?;
         for i in data {
@@ -148,6 +155,11 @@ impl<T: PrimitiveArrayItemType> PrimitiveArray<T> {
         }
         builder.finish()
     }
+
+    /// Returns the values of all rows, including the null ones whose values are unspecified.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
 }
 
 impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
@@ -222,6 +234,24 @@ impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
         }
     }
 
+    fn hash_column<B: BuildHasher>(&self, hash_builder: &B, hashes: &mut [u64]) {
+        let null_hash = null_hash(hash_builder);
+        for ((hash, value), valid) in hashes
+            .iter_mut()
+            .zip_eq(&self.data)
+            .zip_eq(self.bitmap.iter())
+        {
+            let value_hash = if valid {
+                let mut state = hash_builder.build_hasher();
+                value.hash_wrapper(&mut state);
+                state.finish()
+            } else {
+                null_hash
+            };
+            *hash = combine_hash(*hash, value_hash);
+        }
+    }
+
     fn create_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
         T::create_array_builder(capacity)
     }
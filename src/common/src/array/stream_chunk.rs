@@ -21,11 +21,10 @@ use prost::DecodeError;
 use risingwave_pb::data::{Op as ProstOp, StreamChunk as ProstStreamChunk};
 
 use crate::array::column::Column;
-use crate::array::{ArrayBuilderImpl, DataChunk, Row, Vis};
+use crate::array::{ArrayBuilderImpl, DataChunk, Row, Vis, COMPACT_SELECTIVITY_THRESHOLD};
 use crate::buffer::Bitmap;
 use crate::error::{ErrorCode, Result, RwError};
 use crate::types::{DataType, NaiveDateTimeWrapper};
-use crate::util::hash_util::finalize_hashers;
 
 /// `Op` represents three operations in `StreamChunk`.
 ///
@@ -159,9 +158,11 @@ impl StreamChunk {
         let (ops, columns, visibility) = self.into_inner();
         let visibility = visibility.unwrap();
 
-        let cardinality = visibility
-            .iter()
-            .fold(0, |vis_cnt, vis| vis_cnt + vis as usize);
+        let cardinality = visibility.num_high_bits();
+        // All rows are visible, so no need to copy the columns.
+        if cardinality == visibility.len() {
+            return Ok(StreamChunk::new(ops, columns, None));
+        }
         let columns = columns
             .into_iter()
             .map(|col| {
@@ -171,15 +172,25 @@ impl StreamChunk {
                     .map(|array| Column::new(Arc::new(array)))
             })
             .collect::<Result<Vec<_>>>()?;
-        let mut new_ops = Vec::with_capacity(cardinality);
-        for (op, visible) in ops.into_iter().zip_eq(visibility.iter()) {
-            if visible {
-                new_ops.push(op);
-            }
-        }
+        let new_ops = visibility.iter_ones().map(|idx| ops[idx]).collect();
         Ok(StreamChunk::new(new_ops, columns, None))
     }
 
+    /// The fraction of the visible rows.
+    pub fn selectivity(&self) -> f64 {
+        self.data.selectivity()
+    }
+
+    /// Compacts the chunk only if the invisible rows are more than the visible ones. Otherwise it's
+    /// cheaper for the consumer to skip the invisible rows than to copy all columns.
+    pub fn compact_if_sparse(self) -> Result<Self> {
+        if self.selectivity() < COMPACT_SELECTIVITY_THRESHOLD {
+            self.compact()
+        } else {
+            Ok(self)
+        }
+    }
+
     pub fn into_parts(self) -> (DataChunk, Vec<Op>) {
         (self.data, self.ops)
     }
@@ -236,13 +247,13 @@ impl StreamChunk {
         keys: &[usize],
         hasher_builder: H,
     ) -> Result<Vec<u64>> {
-        let mut states = vec![];
-        states.resize_with(self.capacity(), || hasher_builder.build_hasher());
+        let mut hashes = vec![0; self.capacity()];
         for key in keys {
-            let array = self.columns()[*key].array();
-            array.hash_vec(&mut states[..]);
+            self.columns()[*key]
+                .array_ref()
+                .hash_column(&hasher_builder, &mut hashes);
         }
-        Ok(finalize_hashers(&mut states[..]))
+        Ok(hashes)
     }
 
     /// `to_pretty_string` returns a table-like text representation of the `StreamChunk`.
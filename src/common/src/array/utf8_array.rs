@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter;
 use std::mem::size_of;
 
@@ -20,17 +21,31 @@ use itertools::Itertools;
 use risingwave_pb::data::buffer::CompressionType;
 use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer};
 
-use super::{Array, ArrayBuilder, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH};
+use super::{null_hash, Array, ArrayBuilder, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH};
 use crate::array::ArrayBuilderImpl;
 use crate::buffer::{Bitmap, BitmapBuilder};
 use crate::error::Result;
+use crate::util::hash_util::combine_hash;
+
+/// Arrays shorter than this are never dictionary-encoded.
+const DICTIONARY_MIN_LEN: usize = 64;
+
+/// An array is dictionary-encoded if the number of its distinct values is at most `1 /
+/// DICTIONARY_MAX_DISTINCT_RATIO` of its length.
+const DICTIONARY_MAX_DISTINCT_RATIO: usize = 8;
 
 /// `Utf8Array` is a collection of Rust Utf8 `String`s.
+///
+/// When an array has only a few distinct values, it's dictionary-encoded when built: `offset` and
+/// `data` hold the distinct values, and `codes` the index of the value of each row. The encoding is
+/// transparent to the `Array` methods, while kernels can work on the codes with
+/// [`Utf8Array::codes`] to evaluate each distinct value only once.
 #[derive(Debug)]
 pub struct Utf8Array {
     offset: Vec<usize>,
     bitmap: Bitmap,
     data: Vec<u8>,
+    codes: Option<Vec<u32>>,
 }
 
 impl Array for Utf8Array {
@@ -41,8 +56,7 @@ impl Array for Utf8Array {
 
     fn value_at(&self, idx: usize) -> Option<&str> {
         if !self.is_null(idx) {
-            let data_slice = &self.data[self.offset[idx]..self.offset[idx + 1]];
-            Some(unsafe { std::str::from_utf8_unchecked(data_slice) })
+            Some(self.value_of_slot(self.slot_at(idx)))
         } else {
             None
         }
@@ -50,15 +64,17 @@ impl Array for Utf8Array {
 
     unsafe fn value_at_unchecked(&self, idx: usize) -> Option<&str> {
         if !self.is_null_unchecked(idx) {
-            let data_slice = &self.data[self.offset[idx]..self.offset[idx + 1]];
-            Some(std::str::from_utf8_unchecked(data_slice))
+            Some(self.value_of_slot(self.slot_at(idx)))
         } else {
             None
         }
     }
 
     fn len(&self) -> usize {
-        self.offset.len() - 1
+        match &self.codes {
+            Some(codes) => codes.len(),
+            None => self.offset.len() - 1,
+        }
     }
 
     fn iter(&self) -> ArrayIterator<'_, Self> {
@@ -66,6 +82,10 @@ impl Array for Utf8Array {
     }
 
     fn to_protobuf(&self) -> ProstArray {
+        // The encoding is not sent, as the receiver encodes the array again when building it.
+        if self.codes.is_some() {
+            return self.decode().to_protobuf();
+        }
         let offset_buffer = self
             .offset
             .iter()
@@ -120,13 +140,39 @@ impl Array for Utf8Array {
     #[inline(always)]
     fn hash_at<H: Hasher>(&self, idx: usize, state: &mut H) {
         if !self.is_null(idx) {
-            let data_slice = &self.data[self.offset[idx]..self.offset[idx + 1]];
-            state.write(data_slice);
+            state.write(self.value_of_slot(self.slot_at(idx)).as_bytes());
         } else {
             NULL_VAL_FOR_HASH.hash(state);
         }
     }
 
+    /// The distinct values of a dictionary-encoded array are hashed only once.
+    fn hash_column<B: BuildHasher>(&self, hash_builder: &B, hashes: &mut [u64]) {
+        assert_eq!(hashes.len(), self.len());
+        let null_hash = null_hash(hash_builder);
+        let hash_value = |value: &str| {
+            let mut state = hash_builder.build_hasher();
+            state.write(value.as_bytes());
+            state.finish()
+        };
+        let value_hashes = match &self.codes {
+            Some(_) => (0..self.dictionary_len())
+                .map(|slot| hash_value(self.value_of_slot(slot)))
+                .collect_vec(),
+            None => vec![],
+        };
+        for (idx, hash) in hashes.iter_mut().enumerate() {
+            let value_hash = if self.is_null(idx) {
+                null_hash
+            } else if let Some(codes) = &self.codes {
+                value_hashes[codes[idx] as usize]
+            } else {
+                hash_value(self.value_of_slot(idx))
+            };
+            *hash = combine_hash(*hash, value_hash);
+        }
+    }
+
     fn create_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
         let array_builder = Utf8ArrayBuilder::new(capacity)?;
         Ok(ArrayBuilderImpl::Utf8(array_builder))
@@ -141,6 +187,101 @@ impl Utf8Array {
         }
         builder.finish()
     }
+
+    /// The dictionary codes of the rows if the array is dictionary-encoded, which index the values
+    /// returned by [`Utf8Array::dictionary_value`]. The codes of null rows are meaningless.
+    pub fn codes(&self) -> Option<&[u32]> {
+        self.codes.as_deref()
+    }
+
+    /// The number of values in the dictionary, which are distinct from each other.
+    pub fn dictionary_len(&self) -> usize {
+        self.offset.len() - 1
+    }
+
+    pub fn dictionary_value(&self, code: u32) -> &str {
+        self.value_of_slot(code as usize)
+    }
+
+    /// The index of the value of the row `idx` in `offset`.
+    #[inline(always)]
+    fn slot_at(&self, idx: usize) -> usize {
+        match &self.codes {
+            Some(codes) => codes[idx] as usize,
+            None => idx,
+        }
+    }
+
+    #[inline(always)]
+    fn value_of_slot(&self, slot: usize) -> &str {
+        let data_slice = &self.data[self.offset[slot]..self.offset[slot + 1]];
+        // SAFETY: The data are always appended as `&str`.
+        unsafe { std::str::from_utf8_unchecked(data_slice) }
+    }
+
+    /// Encodes the array with a dictionary if it's long enough and has few distinct values.
+    fn dictionary_encode(self) -> Self {
+        match self.build_dictionary() {
+            Some((offset, data, codes)) => Self {
+                offset,
+                bitmap: self.bitmap,
+                data,
+                codes: Some(codes),
+            },
+            None => self,
+        }
+    }
+
+    /// Returns the offsets and data of the distinct values along with the codes of the rows, or
+    /// `None` if the array shouldn't be encoded.
+    fn build_dictionary(&self) -> Option<(Vec<usize>, Vec<u8>, Vec<u32>)> {
+        let len = self.len();
+        if self.codes.is_some() || len < DICTIONARY_MIN_LEN {
+            return None;
+        }
+        let max_distinct = len / DICTIONARY_MAX_DISTINCT_RATIO;
+        let mut dictionary = HashMap::with_capacity(max_distinct);
+        let mut offset = vec![0];
+        let mut data = vec![];
+        let mut codes = Vec::with_capacity(len);
+        for value in self.iter() {
+            let code = match value {
+                Some(value) => {
+                    let next_code = dictionary.len() as u32;
+                    *dictionary.entry(value).or_insert_with(|| {
+                        data.extend_from_slice(value.as_bytes());
+                        offset.push(data.len());
+                        next_code
+                    })
+                }
+                None => 0,
+            };
+            if dictionary.len() > max_distinct {
+                return None;
+            }
+            codes.push(code);
+        }
+        Some((offset, data, codes))
+    }
+
+    /// Returns the array without dictionary encoding.
+    fn decode(&self) -> Self {
+        let mut offset = Vec::with_capacity(self.len() + 1);
+        offset.push(0);
+        let mut data = vec![];
+        for value in self.iter() {
+            if let Some(value) = value {
+                data.extend_from_slice(value.as_bytes());
+            }
+            offset.push(data.len());
+        }
+        Self {
+            offset,
+            bitmap: self.bitmap.clone(),
+            data,
+            codes: None,
+        }
+    }
 }
 
 /// `Utf8ArrayBuilder` use `&str` to build an `Utf8Array`.
@@ -180,6 +321,12 @@ impl ArrayBuilder for Utf8ArrayBuilder {
     }
 
     fn append_array(&mut self, other: &Utf8Array) -> Result<()> {
+        if other.codes.is_some() {
+            for value in other.iter() {
+                self.append(value)?;
+            }
+            return Ok(());
+        }
         for bit in other.bitmap.iter() {
             self.bitmap.append(bit);
         }
@@ -192,15 +339,20 @@ impl ArrayBuilder for Utf8ArrayBuilder {
     }
 
     fn finish(self) -> Result<Utf8Array> {
-        Ok(Utf8Array {
+        Ok(self.finish_plain().dictionary_encode())
+    }
+}
+
+impl Utf8ArrayBuilder {
+    fn finish_plain(self) -> Utf8Array {
+        Utf8Array {
             bitmap: (self.bitmap).finish(),
             data: self.data,
             offset: self.offset,
-        })
+            codes: None,
+        }
     }
-}
 
-impl Utf8ArrayBuilder {
     pub fn writer(self) -> BytesWriter {
         BytesWriter { builder: self }
     }
@@ -376,6 +528,40 @@ mod tests {
         assert!(buffers.len() >= 2);
     }
 
+    #[test]
+    fn test_utf8_array_dictionary_encoding() {
+        let input = (0..100)
+            .map(|i| match i % 4 {
+                0 => None,
+                1 => Some("a"),
+                2 => Some("bb"),
+                _ => Some(""),
+            })
+            .collect_vec();
+        let array = Utf8Array::from_slice(&input).unwrap();
+        assert_eq!(array.dictionary_len(), 3);
+        assert_eq!(array.codes().unwrap().len(), 100);
+        assert_eq!(array.dictionary_value(array.codes().unwrap()[2]), "bb");
+        assert_eq!(array.len(), 100);
+        assert_eq!(input, array.iter().collect_vec());
+
+        // Sent without the encoding.
+        let prost = array.to_protobuf();
+        assert_eq!(prost.values[1].body, array.decode().data);
+
+        let mut builder = Utf8ArrayBuilder::new(0).unwrap();
+        builder.append(Some("c")).unwrap();
+        builder.append_array(&array).unwrap();
+        let appended = builder.finish().unwrap();
+        assert_eq!(appended.value_at(0), Some("c"));
+        assert_eq!(input, appended.iter().skip(1).collect_vec());
+
+        // Too many distinct values to be encoded.
+        let distinct = (0..100).map(|i| i.to_string()).collect_vec();
+        let input = distinct.iter().map(|s| Some(s.as_str())).collect_vec();
+        assert!(Utf8Array::from_slice(&input).unwrap().codes().is_none());
+    }
+
     #[test]
     fn test_utf8_array_hash() {
         use std::hash::BuildHasher;
@@ -157,13 +157,43 @@ impl Bitmap {
 
     /// Return the next set bit index on or after `bit_idx`.
     pub fn next_set_bit(&self, bit_idx: usize) -> Option<usize> {
-        for idx in bit_idx..self.len() {
-            // Since `self.len()` guards the range, we can safely call unsafe function here.
-            if unsafe { self.is_set_unchecked(idx) } {
-                return Some(idx);
+        if bit_idx >= self.num_bits {
+            return None;
+        }
+        // Skip a byte of unset bits at a time.
+        let mut byte_idx = bit_idx / 8;
+        let mut byte = self.bits[byte_idx] & (0xff << (bit_idx % 8));
+        loop {
+            if byte != 0 {
+                let idx = byte_idx * 8 + byte.trailing_zeros() as usize;
+                return (idx < self.num_bits).then(|| idx);
             }
+            byte_idx += 1;
+            if byte_idx * 8 >= self.num_bits {
+                return None;
+            }
+            byte = self.bits[byte_idx];
+        }
+    }
+
+    /// Iterates over the indices of the set bits, skipping a word of unset bits at a time, which is
+    /// much faster than [`Bitmap::iter`] on sparse bitmaps.
+    pub fn iter_ones(&self) -> BitmapOnesIter<'_> {
+        BitmapOnesIter {
+            bits: &self.bits[..(self.num_bits + 7) / 8],
+            num_bits: self.num_bits,
+            word_idx: 0,
+            word: load_word(&self.bits[..(self.num_bits + 7) / 8], 0),
+        }
+    }
+
+    /// The fraction of the set bits, which is 1 for an empty bitmap.
+    pub fn density(&self) -> f64 {
+        if self.num_bits == 0 {
+            1.0
+        } else {
+            self.num_high_bits as f64 / self.num_bits as f64
         }
-        None
     }
 
     pub fn num_high_bits(&self) -> usize {
@@ -279,12 +309,21 @@ impl<'a, 'b> BitOr<&'b Bitmap> for &'a Bitmap {
 impl TryFrom<&BoolArray> for Bitmap {
     type Error = RwError;
 
+    /// Null is treated as false. The bitmap is computed on the bytes of the values and the null
+    /// bitmap at once.
     fn try_from(bools: &BoolArray) -> Result<Bitmap> {
-        let mut builder = BitmapBuilder::default();
-        bools.iter().for_each(|e| {
-            builder.append(e.unwrap_or(false));
-        });
-        Ok(builder.finish())
+        let num_bits = bools.len();
+        let num_bytes = (num_bits + 7) / 8;
+        let mut bits: Vec<u8> = bools.values().bits[..num_bytes]
+            .iter()
+            .zip_eq(bools.null_bitmap().bits[..num_bytes].iter())
+            .map(|(&value, &valid)| value & valid)
+            .collect();
+        // Clear the bits beyond the length, which are not guaranteed to be zero.
+        if num_bits % 8 != 0 {
+            bits[num_bytes - 1] &= (1 << (num_bits % 8)) - 1;
+        }
+        Ok(Bitmap::from_bytes_with_num_bits(bits.into(), num_bits))
     }
 }
 
@@ -372,6 +411,46 @@ impl<'a> std::iter::Iterator for BitmapIter<'a> {
     }
 }
 
+/// Loads the `word_idx`-th 64 bits of `bits`, padded with zeros beyond the buffer.
+fn load_word(bits: &[u8], word_idx: usize) -> u64 {
+    let start = (word_idx * 8).min(bits.len());
+    let end = (start + 8).min(bits.len());
+    let mut word = [0u8; 8];
+    word[..end - start].copy_from_slice(&bits[start..end]);
+    u64::from_le_bytes(word)
+}
+
+pub struct BitmapOnesIter<'a> {
+    bits: &'a [u8],
+    num_bits: usize,
+    word_idx: usize,
+    /// The remaining set bits of the current word.
+    word: u64,
+}
+
+impl<'a> std::iter::Iterator for BitmapOnesIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.word != 0 {
+                let idx = self.word_idx * 64 + self.word.trailing_zeros() as usize;
+                if idx >= self.num_bits {
+                    return None;
+                }
+                // Clear the lowest set bit.
+                self.word &= self.word - 1;
+                return Some(idx);
+            }
+            self.word_idx += 1;
+            if self.word_idx * 8 >= self.bits.len() {
+                return None;
+            }
+            self.word = load_word(self.bits, self.word_idx);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -548,4 +627,21 @@ mod tests {
         let bm2 = (vec![false]).try_into().unwrap();
         assert_eq!(bm1, bm2);
     }
+
+    #[test]
+    fn test_bitmap_iter_ones() {
+        let bools = (0..150).map(|i| i % 7 == 0 || i == 149).collect_vec();
+        let bitmap = Bitmap::try_from(bools.clone()).unwrap();
+        let expected = (0..150).filter(|i| bools[*i]).collect_vec();
+        assert_eq!(bitmap.iter_ones().collect_vec(), expected);
+        assert_eq!(bitmap.next_set_bit(1), Some(7));
+        assert_eq!(bitmap.next_set_bit(141), Some(147));
+        assert_eq!(bitmap.next_set_bit(148), Some(149));
+        assert_eq!(bitmap.next_set_bit(150), None);
+
+        let empty = Bitmap::try_from(vec![false; 70]).unwrap();
+        assert_eq!(empty.iter_ones().count(), 0);
+        assert_eq!(empty.next_set_bit(0), None);
+        assert!((empty.density() - 0.0).abs() < f64::EPSILON);
+    }
 }
@@ -25,8 +25,11 @@ pub use schema::{test_utils as schema_test_utils, Field, Schema};
 pub const DEFAULT_DATABASE_NAME: &str = "dev";
 pub const DEFAULT_SCHEMA_NAME: &str = "dev";
 
+/// The super user created by default, which has no password.
 pub const DEFAULT_SUPPER_USER: &str = "risingwave";
-pub const DEFAULT_SUPPER_USER_PASSWORD: &str = "risingwave";
+
+/// The option of materialized views for the time-to-live of their states, in seconds.
+pub const PROPERTIES_TTL_KEY: &str = "ttl";
 
 pub type CatalogVersion = u64;
 
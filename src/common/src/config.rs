@@ -76,6 +76,20 @@ impl Default for ServerConfig {
 pub struct BatchConfig {
     // #[serde(default = "default::chunk_size")]
     // pub chunk_size: u32,
+    /// The max size of the build side of a hash join kept in memory. Both sides of the join are
+    /// spilled to the local disk once exceeded.
+    #[serde(default = "default::hash_join_memory_quota_mb")]
+    pub hash_join_memory_quota_mb: usize,
+
+    /// The max size of the input of a sort or a top-n kept in memory. Sorted runs of the input are
+    /// spilled to the local disk and merged at last once exceeded.
+    #[serde(default = "default::sort_memory_quota_mb")]
+    pub sort_memory_quota_mb: usize,
+
+    /// The max memory a query may use on a compute node, counting its hash tables and sort
+    /// buffers. The query fails once exceeded. `0` means no limit.
+    #[serde(default = "default::query_memory_quota_mb")]
+    pub query_memory_quota_mb: usize,
 }
 
 impl Default for BatchConfig {
@@ -91,6 +105,38 @@ pub struct StreamingConfig {
     // pub chunk_size: u32,
     #[serde(default = "default::checkpoint_interval_ms")]
     pub checkpoint_interval_ms: u32,
+
+    /// Every this many barriers is a checkpoint, at which the states are persisted and committed.
+    /// Barriers carrying a mutation are always checkpoints. It only bootstraps the system param
+    /// of the same name, which can be changed by `ALTER SYSTEM` later.
+    #[serde(default = "default::checkpoint_frequency")]
+    pub checkpoint_frequency: usize,
+
+    /// The max number of entries cached by all stream executors on a compute node, e.g., agg
+    /// states, join rows and top-n rows. Executors are asked to evict their caches once exceeded.
+    #[serde(default = "default::max_cached_entries")]
+    pub max_cached_entries: usize,
+
+    /// The max number of join keys cached by each side of a hash join, beyond which the least
+    /// recently used ones are evicted.
+    #[serde(default = "default::join_cache_size")]
+    pub join_cache_size: usize,
+
+    /// How materialize executors check the ops against the existing rows of the table, which is
+    /// one of `off`, `strict` (fail on inconsistent ops) and `lenient` (reconcile them).
+    #[serde(default = "default::materialize_sanity_check")]
+    pub materialize_sanity_check: String,
+
+    /// How project executors handle the rows on which the expressions fail, which is one of
+    /// `fail` (fail the job) and `null` (produce NULL for these rows and count the errors).
+    #[serde(default = "default::expr_error_policy")]
+    pub expr_error_policy: String,
+
+    /// Whether to record the async call tree of actors, which can be dumped with `risectl cluster
+    /// stack-trace` to diagnose stuck actors. It slows down the actors a bit, so it's off by
+    /// default.
+    #[serde(default = "default::async_stack_trace")]
+    pub async_stack_trace: bool,
 }
 
 impl Default for StreamingConfig {
@@ -115,6 +161,14 @@ pub struct StorageConfig {
     #[serde(default = "default::bloom_false_positive")]
     pub bloom_false_positive: f64,
 
+    /// Compression algorithm of SST blocks, which is one of `none`, `lz4` and `zstd`.
+    #[serde(default = "default::compression_algorithm")]
+    pub compression_algorithm: String,
+
+    /// Compression level of SST blocks, whose range depends on the compression algorithm.
+    #[serde(default = "default::compression_level")]
+    pub compression_level: i32,
+
     /// parallelism while syncing share buffers into L0 SST. Should NOT be 0.
     #[serde(default = "default::share_buffers_sync_parallelism")]
     pub share_buffers_sync_parallelism: u32,
@@ -124,14 +178,20 @@ pub struct StorageConfig {
     #[serde(default = "default::share_buffer_compaction_worker_threads_number")]
     pub share_buffer_compaction_worker_threads_number: u32,
 
-    // /// Size threshold to trigger shared buffer flush.
-    // #[serde(default = "default::shared_buffer_threshold")]
-    // pub shared_buffer_threshold: u32,
+    /// Size threshold to trigger a background flush of the shared buffer into L0 SSTs.
+    #[serde(default = "default::shared_buffer_threshold_mb")]
+    pub shared_buffer_threshold_mb: u32,
+
     /// Maximum shared buffer size, writes attempting to exceed the capacity will stall until there
     /// is enough space.
     #[serde(default = "default::shared_buffer_capacity_mb")]
     pub shared_buffer_capacity_mb: u32,
 
+    /// Writes will stall until compaction catches up once L0 holds this many SSTs. 0 means never
+    /// stall.
+    #[serde(default = "default::write_stall_l0_sst_count")]
+    pub write_stall_l0_sst_count: u32,
+
     /// Remote directory for storing data and metadata objects.
     #[serde(default = "default::data_directory")]
     pub data_directory: String,
@@ -217,6 +277,14 @@ mod default {
         0.01
     }
 
+    pub fn compression_algorithm() -> String {
+        "none".to_string()
+    }
+
+    pub fn compression_level() -> i32 {
+        4
+    }
+
     pub fn share_buffers_sync_parallelism() -> u32 {
         2
     }
@@ -225,15 +293,18 @@ mod default {
         2
     }
 
-    pub fn shared_buffer_threshold() -> u32 {
-        // 192MB
-        201326592
+    pub fn shared_buffer_threshold_mb() -> u32 {
+        192
     }
 
     pub fn shared_buffer_capacity_mb() -> u32 {
         1024
     }
 
+    pub fn write_stall_l0_sst_count() -> u32 {
+        512
+    }
+
     pub fn data_directory() -> String {
         "hummock_001".to_string()
     }
@@ -265,4 +336,40 @@ mod default {
     pub fn checkpoint_interval_ms() -> u32 {
         100
     }
+
+    pub fn checkpoint_frequency() -> usize {
+        1
+    }
+
+    pub fn max_cached_entries() -> usize {
+        1 << 22
+    }
+
+    pub fn join_cache_size() -> usize {
+        1 << 16
+    }
+
+    pub fn materialize_sanity_check() -> String {
+        "off".to_string()
+    }
+
+    pub fn expr_error_policy() -> String {
+        "fail".to_string()
+    }
+
+    pub fn async_stack_trace() -> bool {
+        false
+    }
+
+    pub fn hash_join_memory_quota_mb() -> usize {
+        512
+    }
+
+    pub fn sort_memory_quota_mb() -> usize {
+        512
+    }
+
+    pub fn query_memory_quota_mb() -> usize {
+        4096
+    }
 }
@@ -110,6 +110,14 @@ pub enum ErrorCode {
     CatalogError(BoxedError),
     #[error("Out of range")]
     NumericValueOutOfRange,
+    #[error("Can't cast {value} to {target_type}: {reason}")]
+    InvalidCast {
+        value: String,
+        target_type: String,
+        reason: String,
+    },
+    #[error("Can't cast {value} to {target_type}: out of range")]
+    CastOutOfRange { value: String, target_type: String },
     #[error("protocol error: {0}")]
     ProtocolError(String),
     #[error("Task not found")]
@@ -129,8 +137,25 @@ pub enum ErrorCode {
         config_entry: String,
         config_value: String,
     },
+    #[error("Unrecognized configuration parameter [{config_entry:?}]")]
+    UnrecognizedConfigurationParameter { config_entry: String },
     #[error("Invalid Parameter Value: {0}")]
     InvalidParameterValue(String),
+    #[error("Duplicate key value violates primary key constraint: {0}")]
+    UniqueViolation(String),
+    #[error("Null value in column \"{0}\" violates not-null constraint")]
+    NotNullViolation(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    /// The query is cancelled by the client or by `statement_timeout`.
+    #[error("canceling statement due to {0}")]
+    QueryCancelled(String),
+    #[error("out of memory for query: {0}")]
+    QueryOutOfMemory(String),
+    /// The query produces more rows than allowed by a session variable, e.g.,
+    /// `batch_nested_loop_join_max_rows`.
+    #[error("row limit exceeded: {0}")]
+    RowLimitExceeded(String),
 
     /// This error occurs when the meta node receives heartbeat from a previous removed worker
     /// node. Currently we don't support re-register, and the worker node need a full restart.
@@ -158,6 +183,8 @@ pub fn parse_error(msg: impl Into<String>) -> RwError {
 pub struct RwError {
     inner: Arc<ErrorCode>,
     backtrace: Arc<Backtrace>,
+    /// What was being done when the error occurred, from the innermost to the outermost.
+    context: Vec<String>,
 }
 
 impl From<RwError> for tonic::Status {
@@ -195,6 +222,19 @@ impl RwError {
     pub fn inner(&self) -> &ErrorCode {
         &self.inner
     }
+
+    /// The SQLSTATE code reported to the client, see [`ErrorCode::sqlstate`].
+    pub fn sqlstate(&self) -> &'static str {
+        self.inner.sqlstate()
+    }
+
+    /// Adds a message describing what was being done when the error occurred. It's prepended to
+    /// the error message, while the error code stays the same.
+    #[must_use]
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context.push(context.into());
+        self
+    }
 }
 
 impl From<ErrorCode> for RwError {
@@ -202,6 +242,7 @@ impl From<ErrorCode> for RwError {
         Self {
             inner: Arc::new(code),
             backtrace: Arc::new(Backtrace::capture()),
+            context: vec![],
         }
     }
 }
@@ -211,6 +252,7 @@ impl From<JoinError> for RwError {
         Self {
             inner: Arc::new(ErrorCode::InternalError(join_error.to_string())),
             backtrace: Arc::new(Backtrace::capture()),
+            context: vec![],
         }
     }
 }
@@ -256,7 +298,7 @@ impl Debug for RwError {
         write!(
             f,
             "{}\n{}",
-            self.inner,
+            self,
             // Use inner error's backtrace by default, otherwise use the generated one in `From`.
             self.inner.backtrace().unwrap_or(&*self.backtrace)
         )
@@ -265,6 +307,9 @@ impl Debug for RwError {
 
 impl Display for RwError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
         write!(f, "{}", self.inner)
     }
 }
@@ -308,9 +353,72 @@ impl ErrorCode {
             ErrorCode::UnknownWorker => 24,
             ErrorCode::ConnectorError(_) => 25,
             ErrorCode::InvalidParameterValue(_) => 26,
+            ErrorCode::UnrecognizedConfigurationParameter { .. } => 27,
+            ErrorCode::UniqueViolation(_) => 28,
+            ErrorCode::NotNullViolation(_) => 29,
+            ErrorCode::PermissionDenied(_) => 30,
+            ErrorCode::QueryCancelled(_) => 31,
+            ErrorCode::QueryOutOfMemory(_) => 32,
+            ErrorCode::InvalidCast { .. } => 33,
+            ErrorCode::CastOutOfRange { .. } => 34,
+            ErrorCode::RowLimitExceeded(_) => 35,
             ErrorCode::UnknownError(_) => 101,
         }
     }
+
+    /// The SQLSTATE code of the error, which is sent to the client in the `ErrorResponse`. See
+    /// <https://www.postgresql.org/docs/current/errcodes-appendix.html>.
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            // successful_completion
+            ErrorCode::OK => "00000",
+            // feature_not_supported
+            ErrorCode::NotImplemented(..) => "0A000",
+            // protocol_violation
+            ErrorCode::ProtocolError(_) => "08P01",
+            // numeric_value_out_of_range
+            ErrorCode::NumericValueOutOfRange | ErrorCode::CastOutOfRange { .. } => "22003",
+            // invalid_parameter_value
+            ErrorCode::InvalidParameterValue(_) | ErrorCode::InvalidConfigValue { .. } => "22023",
+            // invalid_text_representation
+            ErrorCode::InvalidInputSyntax(_) | ErrorCode::InvalidCast { .. } => "22P02",
+            // not_null_violation
+            ErrorCode::NotNullViolation(_) => "23502",
+            // unique_violation
+            ErrorCode::UniqueViolation(_) => "23505",
+            // syntax_error_or_access_rule_violation
+            ErrorCode::BindError(_) | ErrorCode::CatalogError(_) => "42000",
+            // insufficient_privilege
+            ErrorCode::PermissionDenied(_) => "42501",
+            // syntax_error
+            ErrorCode::ParseError(_) => "42601",
+            // undefined_object
+            ErrorCode::ItemNotFound(_) | ErrorCode::UnrecognizedConfigurationParameter { .. } => {
+                "42704"
+            }
+            // out_of_memory
+            ErrorCode::MemoryError { .. } | ErrorCode::QueryOutOfMemory(_) => "53200",
+            // program_limit_exceeded
+            ErrorCode::RowLimitExceeded(_) => "54000",
+            // query_canceled
+            ErrorCode::QueryCancelled(_) => "57014",
+            // io_error
+            ErrorCode::IoError(_) => "58030",
+            // internal_error
+            ErrorCode::InternalError(_)
+            | ErrorCode::ConnectorError(_)
+            | ErrorCode::ProstError(_)
+            | ErrorCode::StorageError(_)
+            | ErrorCode::StreamError(_)
+            | ErrorCode::TaskNotFound
+            | ErrorCode::MemComparableError(_)
+            | ErrorCode::ValueEncodingError(_)
+            | ErrorCode::MetaError(_)
+            | ErrorCode::UnknownWorker
+            | ErrorCode::Eof
+            | ErrorCode::UnknownError(_) => "XX000",
+        }
+    }
 }
 
 impl PartialEq for ErrorCode {
@@ -345,6 +453,23 @@ pub fn tonic_err(err: impl Into<RwError>) -> tonic::Status {
 
 pub type Result<T> = std::result::Result<T, RwError>;
 
+/// Adds context to the error of a result, see [`RwError::context`].
+pub trait ErrorContext<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    fn with_context<C: Into<String>>(self, func: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<RwError>> ErrorContext<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Into<String>>(self, func: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.into().context(func()))
+    }
+}
+
 #[macro_export]
 macro_rules! gen_error {
     ($error_code:expr) => {
@@ -567,6 +692,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_context() {
+        let result: Result<()> = Err(ErrorCode::ParseError("unexpected token".to_string()).into());
+        let err = result
+            .context("failed to parse the query")
+            .with_context(|| "failed to run the statement")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to run the statement: failed to parse the query: Parse error: unexpected token"
+        );
+        assert_eq!(err.sqlstate(), "42601");
+        assert_eq!(err.inner().get_code(), 7);
+    }
+
     #[test]
     fn test_ensure_eq() {
         fn ensure_a_equals_b() -> Result<()> {
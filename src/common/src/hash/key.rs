@@ -17,14 +17,17 @@ use std::default::Default;
 use std::fmt::Debug;
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::io::{Cursor, Read};
+use std::sync::Arc;
 
 use chrono::{Datelike, Timelike};
 use itertools::Itertools;
 
 use super::{VirtualNode, VIRTUAL_NODE_COUNT};
+use crate::array::column::Column;
 use crate::array::{
     Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, DataChunk, ListRef, Row, StructRef,
 };
+use crate::buffer::Bitmap;
 use crate::error::Result;
 use crate::types::{
     DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper,
@@ -97,6 +100,30 @@ pub trait HashKey: Clone + Debug + Hash + Eq + Sized + Send + Sync + 'static {
     type S: HashKeySerializer<K = Self>;
 
     fn build(column_idxes: &[usize], data_chunk: &DataChunk) -> Result<Vec<Self>> {
+        // Rows with the same dictionary codes have the same key, so only the first row of each
+        // group is serialized.
+        if let Some((groups, first_rows)) = data_chunk.group_by_dictionary_codes(column_idxes) {
+            if first_rows.len() * 2 <= data_chunk.capacity() {
+                let mut visibility = vec![false; data_chunk.capacity()];
+                first_rows.iter().for_each(|idx| visibility[*idx] = true);
+                let visibility = Bitmap::try_from(visibility)?;
+                let columns = column_idxes
+                    .iter()
+                    .map(|idx| {
+                        let array = data_chunk.column_at(*idx).array_ref();
+                        Ok(Column::new(Arc::new(
+                            array.compact(&visibility, first_rows.len())?,
+                        )))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let first_rows_chunk = DataChunk::new(columns, first_rows.len());
+                let keys = Self::build(&(0..column_idxes.len()).collect_vec(), &first_rows_chunk)?;
+                return Ok(groups
+                    .into_iter()
+                    .map(|group| keys[group].clone())
+                    .collect());
+            }
+        }
         let hash_codes = data_chunk.get_hash_values(column_idxes, CRC32FastBuilder)?;
         Self::build_from_hash_code(column_idxes, data_chunk, hash_codes)
     }
@@ -141,6 +168,9 @@ pub trait HashKey: Clone + Debug + Hash + Eq + Sized + Send + Sync + 'static {
     fn deserialize_to_builders(self, array_builders: &mut [ArrayBuilderImpl]) -> Result<()>;
 
     fn has_null(&self) -> bool;
+
+    /// The hash code the key is built with, from which its vnode is derived.
+    fn hash_code(&self) -> HashCode;
 }
 
 /// Designed for hash keys with at most `N` serialized bytes.
@@ -637,6 +667,10 @@ impl<const N: usize> HashKey for FixedSizeKey<N> {
     fn has_null(&self) -> bool {
         self.null_bitmap != 0xFF
     }
+
+    fn hash_code(&self) -> HashCode {
+        self.hash_code.into()
+    }
 }
 
 impl HashKey for SerializedKey {
@@ -653,6 +687,10 @@ impl HashKey for SerializedKey {
     fn has_null(&self) -> bool {
         self.has_null
     }
+
+    fn hash_code(&self) -> HashCode {
+        self.hash_code.into()
+    }
 }
 
 #[cfg(test)]
@@ -185,8 +185,8 @@ impl NaiveDateTimeWrapper {
     }
 
     pub fn from_protobuf(timestamp_micro: i64) -> Result<Self> {
-        let secs = timestamp_micro / 1_000_000;
-        let nsecs = (timestamp_micro % 1_000_000) as u32 * 1000;
+        let secs = timestamp_micro.div_euclid(1_000_000);
+        let nsecs = timestamp_micro.rem_euclid(1_000_000) as u32 * 1000;
         Self::with_secs_nsecs(secs, nsecs).map_err(|e| RwError::from(InternalError(e.to_string())))
     }
 }
@@ -0,0 +1,320 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Canonical conversions of datums to and from the protobuf constant values and JSON.
+//!
+//! In JSON, values are represented in the most natural way where it doesn't lose information:
+//! numbers for integers and finite floats, strings for decimals (to keep the precision), texts and
+//! temporal values, arrays for structs and lists, and an object of `months`, `days` and `ms` for
+//! intervals. Non-finite floats are strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use risingwave_pb::expr::ConstantValue;
+use serde_json::{json, Map, Number, Value};
+
+use super::{
+    DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper,
+    NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarImpl,
+};
+use crate::array::{ListValue, StructValue};
+use crate::error::ErrorCode::InternalError;
+use crate::error::Result;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Converts a datum into a protobuf constant value. A null is represented by no value at all.
+pub fn datum_to_protobuf(datum: &Datum) -> Option<ConstantValue> {
+    datum.as_ref().map(|scalar| ConstantValue {
+        body: scalar.to_protobuf(),
+    })
+}
+
+/// Converts a protobuf constant value of the given type into a datum.
+pub fn datum_from_protobuf(value: Option<&ConstantValue>, data_type: &DataType) -> Result<Datum> {
+    value
+        .map(|value| ScalarImpl::bytes_to_scalar(&value.body, &data_type.to_protobuf()))
+        .transpose()
+}
+
+/// Converts a datum into a JSON value.
+pub fn datum_to_json(datum: &Datum) -> Value {
+    let scalar = match datum {
+        Some(scalar) => scalar,
+        None => return Value::Null,
+    };
+    match scalar {
+        ScalarImpl::Int16(v) => json!(v),
+        ScalarImpl::Int32(v) => json!(v),
+        ScalarImpl::Int64(v) => json!(v),
+        ScalarImpl::Float32(v) => float_to_json(v.0 as f64),
+        ScalarImpl::Float64(v) => float_to_json(v.0),
+        ScalarImpl::Utf8(v) => json!(v),
+        ScalarImpl::Bool(v) => json!(v),
+        ScalarImpl::Decimal(v) => json!(v.to_string()),
+        ScalarImpl::Interval(v) => json!({
+            "months": v.get_months(),
+            "days": v.get_days(),
+            "ms": v.get_ms(),
+        }),
+        ScalarImpl::NaiveDate(v) => json!(v.0.format(DATE_FORMAT).to_string()),
+        ScalarImpl::NaiveDateTime(v) => json!(v.0.format(TIMESTAMP_FORMAT).to_string()),
+        ScalarImpl::NaiveTime(v) => json!(v.0.format(TIME_FORMAT).to_string()),
+        ScalarImpl::Struct(v) => Value::Array(v.fields().iter().map(datum_to_json).collect()),
+        ScalarImpl::List(v) => Value::Array(v.values().iter().map(datum_to_json).collect()),
+    }
+}
+
+fn float_to_json(v: f64) -> Value {
+    match Number::from_f64(v) {
+        Some(n) => Value::Number(n),
+        None if v.is_nan() => json!("NaN"),
+        None if v > 0.0 => json!("Infinity"),
+        None => json!("-Infinity"),
+    }
+}
+
+/// Converts a JSON value of the given type into a datum. It accepts exactly the output of
+/// [`datum_to_json`].
+pub fn datum_from_json(value: &Value, data_type: &DataType) -> Result<Datum> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let invalid = || {
+        InternalError(format!(
+            "Failed to convert JSON {} to {:?}",
+            value, data_type
+        ))
+    };
+    let scalar = match data_type {
+        DataType::Int16 => ScalarImpl::Int16(
+            value
+                .as_i64()
+                .and_then(|v| v.try_into().ok())
+                .ok_or_else(invalid)?,
+        ),
+        DataType::Int32 => ScalarImpl::Int32(
+            value
+                .as_i64()
+                .and_then(|v| v.try_into().ok())
+                .ok_or_else(invalid)?,
+        ),
+        DataType::Int64 | DataType::Timestampz => {
+            ScalarImpl::Int64(value.as_i64().ok_or_else(invalid)?)
+        }
+        DataType::Float32 => ScalarImpl::Float32(OrderedF32::from(
+            float_from_json(value).ok_or_else(invalid)? as f32,
+        )),
+        DataType::Float64 => ScalarImpl::Float64(OrderedF64::from(
+            float_from_json(value).ok_or_else(invalid)?,
+        )),
+        DataType::Varchar => ScalarImpl::Utf8(value.as_str().ok_or_else(invalid)?.to_string()),
+        DataType::Boolean => ScalarImpl::Bool(value.as_bool().ok_or_else(invalid)?),
+        DataType::Decimal => ScalarImpl::Decimal(
+            value
+                .as_str()
+                .and_then(|v| Decimal::from_str(v).ok())
+                .ok_or_else(invalid)?,
+        ),
+        DataType::Interval => {
+            let object: &Map<String, Value> = value.as_object().ok_or_else(invalid)?;
+            let field = |name: &str| object.get(name).and_then(Value::as_i64).ok_or_else(invalid);
+            ScalarImpl::Interval(IntervalUnit::new(
+                field("months")?.try_into().map_err(|_| invalid())?,
+                field("days")?.try_into().map_err(|_| invalid())?,
+                field("ms")?,
+            ))
+        }
+        DataType::Date => ScalarImpl::NaiveDate(NaiveDateWrapper::new(
+            value
+                .as_str()
+                .and_then(|v| NaiveDate::parse_from_str(v, DATE_FORMAT).ok())
+                .ok_or_else(invalid)?,
+        )),
+        DataType::Time => ScalarImpl::NaiveTime(NaiveTimeWrapper::new(
+            value
+                .as_str()
+                .and_then(|v| NaiveTime::parse_from_str(v, TIME_FORMAT).ok())
+                .ok_or_else(invalid)?,
+        )),
+        DataType::Timestamp => ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::new(
+            value
+                .as_str()
+                .and_then(|v| NaiveDateTime::parse_from_str(v, TIMESTAMP_FORMAT).ok())
+                .ok_or_else(invalid)?,
+        )),
+        DataType::Struct { fields } => {
+            let values = value.as_array().ok_or_else(invalid)?;
+            if values.len() != fields.len() {
+                return Err(invalid().into());
+            }
+            ScalarImpl::Struct(StructValue::new(
+                values
+                    .iter()
+                    .zip(fields.iter())
+                    .map(|(v, ty)| datum_from_json(v, ty))
+                    .collect::<Result<_>>()?,
+            ))
+        }
+        DataType::List { datatype } => ScalarImpl::List(ListValue::new(
+            value
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|v| datum_from_json(v, datatype))
+                .collect::<Result<_>>()?,
+        )),
+    };
+    Ok(Some(scalar))
+}
+
+fn float_from_json(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => match s.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn test_datums() -> Vec<(Datum, DataType)> {
+        let date = NaiveDate::from_ymd(1969, 7, 20);
+        let time = NaiveTime::from_hms_micro(20, 17, 40, 123456);
+        vec![
+            (None, DataType::Int32),
+            (Some(ScalarImpl::Int16(-7)), DataType::Int16),
+            (Some(ScalarImpl::Int32(i32::MAX)), DataType::Int32),
+            (Some(ScalarImpl::Int64(i64::MIN)), DataType::Int64),
+            (Some(ScalarImpl::Int64(1_600_000_000)), DataType::Timestampz),
+            (Some(ScalarImpl::Float32(1.5.into())), DataType::Float32),
+            (Some(ScalarImpl::Float64((-0.25).into())), DataType::Float64),
+            (
+                Some(ScalarImpl::Float64(f64::NAN.into())),
+                DataType::Float64,
+            ),
+            (
+                Some(ScalarImpl::Float64(f64::INFINITY.into())),
+                DataType::Float64,
+            ),
+            (
+                Some(ScalarImpl::Float32(f32::NEG_INFINITY.into())),
+                DataType::Float32,
+            ),
+            (
+                Some(ScalarImpl::Utf8("risingwave".into())),
+                DataType::Varchar,
+            ),
+            (Some(ScalarImpl::Bool(false)), DataType::Boolean),
+            (
+                Some(ScalarImpl::Decimal("-233.3000".parse().unwrap())),
+                DataType::Decimal,
+            ),
+            (Some(ScalarImpl::Decimal(Decimal::NaN)), DataType::Decimal),
+            (
+                Some(ScalarImpl::Decimal(Decimal::PositiveINF)),
+                DataType::Decimal,
+            ),
+            (
+                Some(ScalarImpl::Decimal(Decimal::NegativeINF)),
+                DataType::Decimal,
+            ),
+            (
+                Some(ScalarImpl::Interval(IntervalUnit::new(14, -3, 3_600_000))),
+                DataType::Interval,
+            ),
+            (
+                Some(ScalarImpl::NaiveDate(NaiveDateWrapper::new(date))),
+                DataType::Date,
+            ),
+            (
+                Some(ScalarImpl::NaiveTime(NaiveTimeWrapper::new(time))),
+                DataType::Time,
+            ),
+            (
+                Some(ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::new(
+                    date.and_time(time),
+                ))),
+                DataType::Timestamp,
+            ),
+            (
+                Some(ScalarImpl::Struct(StructValue::new(vec![
+                    Some(ScalarImpl::Int32(1)),
+                    None,
+                    Some(ScalarImpl::Utf8("a".into())),
+                ]))),
+                DataType::Struct {
+                    fields: Arc::new([DataType::Int32, DataType::Float64, DataType::Varchar]),
+                },
+            ),
+            (
+                Some(ScalarImpl::List(ListValue::new(vec![
+                    Some(ScalarImpl::Int64(1)),
+                    Some(ScalarImpl::Int64(2)),
+                ]))),
+                DataType::List {
+                    datatype: Box::new(DataType::Int64),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_datum_protobuf_roundtrip() {
+        for (datum, data_type) in test_datums() {
+            let value = datum_to_protobuf(&datum);
+            assert_eq!(
+                datum_from_protobuf(value.as_ref(), &data_type).unwrap(),
+                datum,
+                "{:?}",
+                data_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_datum_json_roundtrip() {
+        for (datum, data_type) in test_datums() {
+            let value = datum_to_json(&datum);
+            // Also go through the text form.
+            let value: Value = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(
+                datum_from_json(&value, &data_type).unwrap(),
+                datum,
+                "{:?}",
+                data_type
+            );
+        }
+
+        assert_eq!(
+            datum_to_json(&Some(ScalarImpl::Float32(f32::NAN.into()))),
+            json!("NaN")
+        );
+        assert_eq!(datum_to_json(&Some(ScalarImpl::Int32(3))), json!(3));
+        assert!(datum_from_json(&json!(70000), &DataType::Int16).is_err());
+        assert!(datum_from_json(&json!("3"), &DataType::Int32).is_err());
+    }
+}
@@ -26,6 +26,10 @@ use smallvec::SmallVec;
 use super::*;
 use crate::error::ErrorCode::IoError;
 
+/// Number of days in a month when converting between months and days, same as PG.
+const DAYS_PER_MONTH: i32 = 30;
+const MS_PER_DAY: i64 = 24 * 3600 * 1000;
+
 /// Every interval can be represented by a `IntervalUnit`.
 /// Note that the difference between Interval and Instant.
 /// For example, `5 yrs 1 month 25 days 23:22:57` is a interval (Can be interpreted by Interval Unit
@@ -174,6 +178,122 @@ impl IntervalUnit {
         Some(IntervalUnit { months, days, ms })
     }
 
+    /// Multiple [`IntervalUnit`] by a float with overflow check. The fractional part of months
+    /// and days is cascaded down to days and milliseconds respectively, as PG does.
+    pub fn checked_mul_float<I>(&self, rhs: I) -> Option<Self>
+    where
+        I: TryInto<OrderedF64>,
+    {
+        let rhs = rhs.try_into().ok()?.0;
+        self.checked_scale(|x| x * rhs)
+    }
+
+    /// Divides [`IntervalUnit`] by a float with overflow check. Returns [`None`] if `rhs` is zero.
+    /// The fractional part of each unit is cascaded down in the same way as
+    /// [`IntervalUnit::checked_mul_float`].
+    pub fn checked_div_float<I>(&self, rhs: I) -> Option<Self>
+    where
+        I: TryInto<OrderedF64>,
+    {
+        let rhs = rhs.try_into().ok()?.0;
+        if rhs == 0.0 {
+            return None;
+        }
+        self.checked_scale(|x| x / rhs)
+    }
+
+    fn checked_scale(&self, f: impl Fn(f64) -> f64) -> Option<Self> {
+        let months = f(self.months as f64);
+        let days = f(self.days as f64) + months.fract() * DAYS_PER_MONTH as f64;
+        let ms = f(self.ms as f64) + days.fract() * MS_PER_DAY as f64;
+
+        let months = months.trunc();
+        let days = days.trunc();
+        let ms = ms.round();
+        if !(i32::MIN as f64..=i32::MAX as f64).contains(&months)
+            || !(i32::MIN as f64..=i32::MAX as f64).contains(&days)
+            || !(i64::MIN as f64..=i64::MAX as f64).contains(&ms)
+        {
+            return None;
+        }
+
+        Some(IntervalUnit {
+            months: months as i32,
+            days: days as i32,
+            ms: ms as i64,
+        })
+    }
+
+    /// Adjusts the interval so that 24-hour time periods are represented as days, like PG's
+    /// `justify_hours`. Days and milliseconds are made to have the same sign.
+    pub fn justify_hours(&self) -> Option<Self> {
+        let mut days = self
+            .days
+            .checked_add((self.ms / MS_PER_DAY).try_into().ok()?)?;
+        let mut ms = self.ms % MS_PER_DAY;
+        if days > 0 && ms < 0 {
+            ms += MS_PER_DAY;
+            days -= 1;
+        } else if days < 0 && ms > 0 {
+            ms -= MS_PER_DAY;
+            days += 1;
+        }
+        Some(IntervalUnit {
+            months: self.months,
+            days,
+            ms,
+        })
+    }
+
+    /// Adjusts the interval so that 30-day time periods are represented as months, like PG's
+    /// `justify_days`. Months and days are made to have the same sign.
+    pub fn justify_days(&self) -> Option<Self> {
+        let mut months = self.months.checked_add(self.days / DAYS_PER_MONTH)?;
+        let mut days = self.days % DAYS_PER_MONTH;
+        if months > 0 && days < 0 {
+            days += DAYS_PER_MONTH;
+            months -= 1;
+        } else if months < 0 && days > 0 {
+            days -= DAYS_PER_MONTH;
+            months += 1;
+        }
+        Some(IntervalUnit {
+            months,
+            days,
+            ms: self.ms,
+        })
+    }
+
+    /// Adjusts the interval using both [`IntervalUnit::justify_hours`] and
+    /// [`IntervalUnit::justify_days`], with additional sign adjustments, like PG's
+    /// `justify_interval`.
+    pub fn justify_interval(&self) -> Option<Self> {
+        let mut days = self
+            .days
+            .checked_add((self.ms / MS_PER_DAY).try_into().ok()?)?;
+        let mut ms = self.ms % MS_PER_DAY;
+        let mut months = self.months.checked_add(days / DAYS_PER_MONTH)?;
+        days %= DAYS_PER_MONTH;
+
+        if months > 0 && (days < 0 || (days == 0 && ms < 0)) {
+            days += DAYS_PER_MONTH;
+            months -= 1;
+        } else if months < 0 && (days > 0 || (days == 0 && ms > 0)) {
+            days -= DAYS_PER_MONTH;
+            months += 1;
+        }
+
+        if days > 0 && ms < 0 {
+            ms += MS_PER_DAY;
+            days -= 1;
+        } else if days < 0 && ms > 0 {
+            ms -= MS_PER_DAY;
+            days += 1;
+        }
+
+        Some(IntervalUnit { months, days, ms })
+    }
+
     /// Performs an exact division, returns [`None`] if for any unit, lhs % rhs != 0.
     pub fn exact_div(&self, rhs: &Self) -> Option<i64> {
         let mut res = None;
@@ -308,6 +428,54 @@ mod tests {
         assert_eq!(interval.to_string(), "-1 years -2 mons 3 days 11:45:14.233");
     }
 
+    #[test]
+    fn test_mul_div_float() {
+        let interval = IntervalUnit::new(1, 1, 0);
+        assert_eq!(
+            interval.checked_mul_float(1.5f64).unwrap(),
+            IntervalUnit::new(1, 16, 12 * 3600 * 1000)
+        );
+        assert_eq!(
+            interval.checked_div_float(2i32).unwrap(),
+            IntervalUnit::new(0, 15, 12 * 3600 * 1000)
+        );
+        assert_eq!(interval.checked_div_float(0i32), None);
+        assert_eq!(
+            IntervalUnit::new(i32::MAX, 0, 0).checked_mul_float(2i32),
+            None
+        );
+    }
+
+    #[test]
+    fn test_justify() {
+        let day = 24 * 3600 * 1000;
+        let interval = IntervalUnit::new(0, 35, day + 3600 * 1000);
+        assert_eq!(
+            interval.justify_hours().unwrap(),
+            IntervalUnit::new(0, 36, 3600 * 1000)
+        );
+        assert_eq!(
+            interval.justify_days().unwrap(),
+            IntervalUnit::new(1, 5, day + 3600 * 1000)
+        );
+        assert_eq!(
+            interval.justify_interval().unwrap(),
+            IntervalUnit::new(1, 6, 3600 * 1000)
+        );
+        assert_eq!(
+            IntervalUnit::new(1, 0, -3600 * 1000)
+                .justify_interval()
+                .unwrap(),
+            IntervalUnit::new(0, 29, 23 * 3600 * 1000)
+        );
+        assert_eq!(
+            IntervalUnit::new(0, 1, -3600 * 1000)
+                .justify_hours()
+                .unwrap(),
+            IntervalUnit::new(0, 0, 23 * 3600 * 1000)
+        );
+    }
+
     #[test]
     fn test_exact_div() {
         let cases = [
@@ -33,6 +33,7 @@ use risingwave_pb::data::data_type::IntervalType::*;
 use risingwave_pb::data::data_type::{IntervalType, TypeName};
 pub use scalar_impl::*;
 mod chrono_wrapper;
+mod datum_serde;
 mod decimal;
 pub mod interval;
 
@@ -42,6 +43,7 @@ use chrono::{Datelike, Timelike};
 pub use chrono_wrapper::{
     NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, UNIX_EPOCH_DAYS,
 };
+pub use datum_serde::*;
 pub use decimal::Decimal;
 pub use interval::*;
 use itertools::Itertools;
@@ -711,9 +713,17 @@ impl ScalarImpl {
             ScalarImpl::Bool(v) => (*v as i8).to_be_bytes().to_vec(),
             ScalarImpl::Decimal(v) => v.to_string().as_bytes().to_vec(),
             ScalarImpl::Interval(v) => v.to_protobuf_owned(),
-            ScalarImpl::NaiveDate(_) => todo!(),
-            ScalarImpl::NaiveDateTime(_) => todo!(),
-            ScalarImpl::NaiveTime(_) => todo!(),
+            ScalarImpl::NaiveDate(v) => (v.0.num_days_from_ce() - UNIX_EPOCH_DAYS)
+                .to_be_bytes()
+                .to_vec(),
+            ScalarImpl::NaiveDateTime(v) => (v.0.timestamp() * 1_000_000
+                + v.0.timestamp_subsec_micros() as i64)
+                .to_be_bytes()
+                .to_vec(),
+            ScalarImpl::NaiveTime(v) => (v.0.num_seconds_from_midnight() as i64 * 1_000_000
+                + v.0.nanosecond() as i64 / 1000)
+                .to_be_bytes()
+                .to_vec(),
             ScalarImpl::Struct(v) => v.to_protobuf_owned(),
             ScalarImpl::List(v) => v.to_protobuf_owned(),
         };
@@ -766,6 +776,26 @@ impl ScalarImpl {
                     |e| InternalError(format!("Failed to deserialize decimal, reason: {:?}", e)),
                 )?)
             }
+            TypeName::Date => ScalarImpl::NaiveDate(NaiveDateWrapper::from_protobuf(
+                i32::from_be_bytes(b.as_slice().try_into().map_err(|e| {
+                    InternalError(format!("Failed to deserialize date, reason: {:?}", e))
+                })?),
+            )?),
+            TypeName::Time => ScalarImpl::NaiveTime(NaiveTimeWrapper::from_protobuf(
+                i64::from_be_bytes(b.as_slice().try_into().map_err(|e| {
+                    InternalError(format!("Failed to deserialize time, reason: {:?}", e))
+                })?),
+            )?),
+            TypeName::Timestamp => ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::from_protobuf(
+                i64::from_be_bytes(b.as_slice().try_into().map_err(|e| {
+                    InternalError(format!("Failed to deserialize timestamp, reason: {:?}", e))
+                })?),
+            )?),
+            TypeName::Timestampz => {
+                ScalarImpl::Int64(i64::from_be_bytes(b.as_slice().try_into().map_err(
+                    |e| InternalError(format!("Failed to deserialize timestampz, reason: {:?}", e)),
+                )?))
+            }
             TypeName::Interval => ScalarImpl::Interval(IntervalUnit::from_protobuf_bytes(
                 b,
                 data_type.get_interval_type()?,
@@ -894,4 +924,19 @@ mod tests {
         assert!(decoded_floats.is_sorted());
         assert_eq!(floats, decoded_floats);
     }
+
+    #[test]
+    fn test_timestamp_protobuf() {
+        // Out of the range of `i64` nanoseconds.
+        for (secs, nsecs) in [(32503680000, 123_456_000), (-1, 999_999_000)] {
+            let scalar = ScalarImpl::NaiveDateTime(
+                NaiveDateTimeWrapper::with_secs_nsecs(secs, nsecs).unwrap(),
+            );
+            let bytes = scalar.to_protobuf();
+            assert_eq!(
+                ScalarImpl::bytes_to_scalar(&bytes, &DataType::Timestamp.to_protobuf()).unwrap(),
+                scalar
+            );
+        }
+    }
 }
@@ -101,8 +101,23 @@ impl HostAddr {
     }
 }
 
+/// Returns whether `peer_addr` refers to the same process as `server_addr`. Loopback aliases
+/// (e.g. `localhost` and `127.0.0.1`) on the same port are considered the same, so that
+/// co-located actors always exchange data through in-memory channels instead of gRPC.
 pub fn is_local_address(server_addr: &HostAddr, peer_addr: &HostAddr) -> bool {
-    server_addr == peer_addr
+    if server_addr.port != peer_addr.port {
+        return false;
+    }
+    server_addr.host == peer_addr.host
+        || (is_loopback_host(&server_addr.host) && is_loopback_host(&peer_addr.host))
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.is_loopback() || ip.is_unspecified())
+            .unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -122,6 +137,10 @@ mod tests {
         check_local("some.host.in.k8s:3456", "some.host.in.k8s:3456", true);
         check_local("some.host.in.k8s:3456", "other.host.in.k8s:3456", false);
         check_local("some.host.in.k8s:3456", "some.host.in.k8s:4567", false);
+        check_local("localhost:3456", "127.0.0.1:3456", true);
+        check_local("0.0.0.0:3456", "127.0.0.1:3456", true);
+        check_local("localhost:3456", "127.0.0.1:4567", false);
+        check_local("10.11.12.13:3456", "127.0.0.1:3456", false);
     }
 
     #[test]
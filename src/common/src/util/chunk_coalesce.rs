@@ -134,6 +134,38 @@ impl DataChunkBuilder {
         Ok((returned_input_chunk, output_chunk))
     }
 
+    /// Appends all the visible rows of `chunk`, and returns the chunks of `batch_size` rows that
+    /// are filled up along the way. The remaining rows stay in the buffer until more rows come or
+    /// [`Self::consume_all`] is called.
+    ///
+    /// A chunk which already has at least half of `batch_size` rows is passed through without
+    /// being copied if nothing is buffered, so the output chunks are close to but not always
+    /// exactly of `batch_size` rows.
+    pub fn push_chunk(&mut self, chunk: DataChunk) -> Result<Vec<DataChunk>> {
+        let cardinality = chunk.cardinality();
+        if cardinality == 0 {
+            return Ok(vec![]);
+        }
+        if self.buffered_count == 0
+            && cardinality >= self.batch_size / 2
+            && cardinality <= self.batch_size
+        {
+            return Ok(vec![chunk.compact()?]);
+        }
+
+        let mut outputs = vec![];
+        let mut sliced_chunk = SlicedDataChunk::new_checked(chunk)?;
+        loop {
+            let (left, output) = self.append_chunk(sliced_chunk)?;
+            outputs.extend(output);
+            match left {
+                Some(left) => sliced_chunk = left,
+                None => break,
+            }
+        }
+        Ok(outputs)
+    }
+
     /// Returns all data in current buffer.
     ///
     /// If `buffered_count` is 0, `None` is returned.
@@ -356,6 +388,60 @@ mod tests {
         assert_eq!(0, builder.buffered_count());
     }
 
+    #[test]
+    fn test_push_chunk() {
+        let mut builder = DataChunkBuilder::new(vec![DataType::Int32, DataType::Int64], 4);
+
+        // Sparse chunks are coalesced.
+        let outputs = builder
+            .push_chunk(DataChunk::from_pretty(
+                "i I
+                 1 . D
+                 2 7
+                 3 8 D",
+            ))
+            .unwrap();
+        assert!(outputs.is_empty());
+        let outputs = builder
+            .push_chunk(DataChunk::from_pretty(
+                "i I
+                 4 .
+                 5 9
+                 6 . D
+                 7 1
+                 8 2
+                 9 3",
+            ))
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0],
+            DataChunk::from_pretty(
+                "i I
+                 2 7
+                 4 .
+                 5 9
+                 7 1"
+            )
+        );
+        assert_eq!(builder.buffered_count(), 2);
+        assert_eq!(builder.consume_all().unwrap().unwrap().cardinality(), 2);
+
+        // Chunks close to the batch size bypass the empty buffer.
+        let outputs = builder
+            .push_chunk(DataChunk::from_pretty(
+                "i I
+                 1 2
+                 3 4 D
+                 5 6
+                 7 8 D",
+            ))
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].capacity(), 2);
+        assert_eq!(builder.buffered_count(), 0);
+    }
+
     #[test]
     fn test_consume_all() {
         let mut builder = DataChunkBuilder::new(vec![DataType::Int32, DataType::Int64], 3);
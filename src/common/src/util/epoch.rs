@@ -59,6 +59,13 @@ impl Epoch {
         self.0 >> EPOCH_PHYSICAL_SHIFT_BITS
     }
 
+    /// Returns the epoch whose physical time is `relative_time_ms` earlier than this one.
+    #[must_use]
+    pub fn subtract_ms(&self, relative_time_ms: u64) -> Self {
+        let physical_time = self.physical_time().saturating_sub(relative_time_ms);
+        Epoch(physical_time << EPOCH_PHYSICAL_SHIFT_BITS)
+    }
+
     fn physical_now() -> u64 {
         UNIX_SINGULARITY_DATE_EPOCH
             .elapsed()
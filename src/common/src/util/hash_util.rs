@@ -21,6 +21,17 @@ pub fn finalize_hashers<H: Hasher>(hashers: &mut [H]) -> Vec<u64> {
         .collect::<Vec<u64>>();
 }
 
+/// Combines the hash of a value into the hash of the row it belongs to, in the way of
+/// `boost::hash_combine`.
+#[inline(always)]
+pub fn combine_hash(row_hash: u64, value_hash: u64) -> u64 {
+    row_hash
+        ^ value_hash
+            .wrapping_add(0x9e3779b97f4a7c15)
+            .wrapping_add(row_hash << 6)
+            .wrapping_add(row_hash >> 2)
+}
+
 pub struct CRC32FastBuilder;
 impl BuildHasher for CRC32FastBuilder {
     type Hasher = crc32fast::Hasher;
@@ -29,6 +29,7 @@ pub mod hash_util;
 pub mod ordered;
 pub mod prost;
 pub mod sort_util;
+pub mod tracing_context;
 #[macro_use]
 pub mod try_match;
 pub mod epoch;
@@ -21,7 +21,7 @@ use super::OrderedDatum::{NormalOrder, ReversedOrder};
 use super::OrderedRow;
 use crate::array::{ArrayImpl, Row, RowRef};
 use crate::catalog::ColumnId;
-use crate::error::Result;
+use crate::error::{ErrorCode, Result};
 use crate::types::{
     deserialize_datum_from, serialize_datum_into, serialize_datum_ref_into, DataType, Datum,
     DatumRef,
@@ -75,6 +75,18 @@ impl OrderedRowSerializer {
         Self { order_types }
     }
 
+    /// Returns the serializer of the first `len` columns, which serializes a prefix of the rows.
+    #[must_use]
+    pub fn prefix(&self, len: usize) -> Self {
+        Self {
+            order_types: self.order_types[..len].to_vec(),
+        }
+    }
+
+    pub fn order_types(&self) -> &[OrderType] {
+        &self.order_types
+    }
+
     pub fn serialize(&self, row: &Row, append_to: &mut Vec<u8>) {
         self.serialize_datums(row.values(), append_to)
     }
@@ -234,6 +246,15 @@ pub fn deserialize_column_id(bytes: &[u8]) -> Result<ColumnId> {
     Ok(column_id.into())
 }
 
+/// Splits a cell key into the encoded pk and the column id suffix, without copying the pk.
+pub fn split_cell_key(key: &[u8]) -> Result<(&[u8], ColumnId)> {
+    if key.len() < 4 {
+        return Err(ErrorCode::InternalError("corrupted key".to_owned()).into());
+    }
+    let (pk, column_id) = key.split_at(key.len() - 4);
+    Ok((pk, deserialize_column_id(column_id)?))
+}
+
 pub fn serialize_pk_and_column_id(pk_buf: &[u8], col_id: &ColumnId) -> Result<Vec<u8>> {
     Ok([pk_buf, serialize_column_id(col_id)?.as_slice()].concat())
 }
@@ -353,4 +374,41 @@ mod tests {
             OrderedRow::new(row3, &order_types)
         );
     }
+
+    #[test]
+    fn test_compare_encoded_rows() {
+        let order_types = vec![OrderType::Descending, OrderType::Ascending];
+        let serializer = OrderedRowSerializer::new(order_types.clone());
+        let rows = [
+            Row(vec![Some(Utf8("b".to_string())), Some(Int16(2))]),
+            Row(vec![Some(Utf8("b".to_string())), Some(Int16(1))]),
+            Row(vec![Some(Utf8("a".to_string())), None]),
+        ];
+        let encoded = rows
+            .iter()
+            .map(|row| {
+                let mut bytes = vec![];
+                serializer.serialize(row, &mut bytes);
+                bytes
+            })
+            .collect_vec();
+
+        for (i, j) in (0..rows.len()).cartesian_product(0..rows.len()) {
+            let lhs = OrderedRow::new(rows[i].clone(), &order_types);
+            let rhs = OrderedRow::new(rows[j].clone(), &order_types);
+            assert_eq!(encoded[i].cmp(&encoded[j]), lhs.cmp(&rhs));
+        }
+    }
+
+    #[test]
+    fn test_split_cell_key() {
+        let key = serialize_pk_and_column_id(b"pk", &ColumnId::new(5)).unwrap();
+        let (pk, column_id) = split_cell_key(&key).unwrap();
+        assert_eq!(pk, b"pk");
+        assert_eq!(column_id, ColumnId::new(5));
+
+        let key = serialize_pk_and_column_id(b"", &SENTINEL_CELL_ID).unwrap();
+        assert_eq!(split_cell_key(&key).unwrap(), (&b""[..], SENTINEL_CELL_ID));
+        assert!(split_cell_key(b"abc").is_err());
+    }
 }
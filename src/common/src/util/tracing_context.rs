@@ -0,0 +1,52 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Propagation of the tracing context across RPCs, so that the spans created on different nodes
+//! for the same query or barrier are connected in a single trace.
+
+use std::collections::HashMap;
+
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// The context of a trace, which can be carried by RPC requests as a string map. It's empty if
+/// tracing is not enabled on the sender.
+#[derive(Debug, Clone, Default)]
+pub struct TracingContext(opentelemetry::Context);
+
+impl TracingContext {
+    /// Captures the context of the current span.
+    pub fn from_current_span() -> Self {
+        Self(tracing::Span::current().context())
+    }
+
+    pub fn from_protobuf(fields: &HashMap<String, String>) -> Self {
+        Self(opentelemetry::global::get_text_map_propagator(
+            |propagator| propagator.extract(fields),
+        ))
+    }
+
+    pub fn to_protobuf(&self) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&self.0, &mut fields)
+        });
+        fields
+    }
+
+    /// Makes `span` a child of the span that this context is captured from.
+    pub fn attach(&self, span: tracing::Span) -> tracing::Span {
+        span.set_parent(self.0.clone());
+        span
+    }
+}
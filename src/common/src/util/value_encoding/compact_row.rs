@@ -0,0 +1,209 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A compact value encoding of rows. Instead of tagging every value with a null byte, a row is
+//! laid out as:
+//!
+//! ```text
+//! | version (1B) | null bitmap (ceil(n/8) B) | fixed-width section | var-len section |
+//! ```
+//!
+//! The bit `i` of the null bitmap (LSB first) is set if the `i`-th value is not null. Null values
+//! take no space in the following sections. The fixed-width section holds the non-null values of
+//! fixed-size types in column order, and the var-len section holds the length-prefixed non-null
+//! values of varchar, struct and list columns, also in column order.
+//!
+//! The leading version byte never collides with the null tag (`0` or `1`) which the legacy
+//! per-value encoding starts with, so both formats can be told apart when decoding.
+
+use bytes::{Buf, BufMut};
+
+use super::{deserialize_datum, deserialize_value, serialize_value};
+use crate::error::Result;
+use crate::types::{DataType, Datum, ScalarImpl};
+use crate::util::value_encoding::error::ValueEncodingError;
+
+/// The version byte of the compact row format.
+pub const COMPACT_ROW_FORMAT_VERSION: u8 = 2;
+
+/// Whether values of the type are stored in the var-len section.
+fn is_var_len(ty: &DataType) -> bool {
+    matches!(
+        ty,
+        DataType::Varchar | DataType::Struct { .. } | DataType::List { .. }
+    )
+}
+
+fn is_var_len_scalar(scalar: &ScalarImpl) -> bool {
+    matches!(
+        scalar,
+        ScalarImpl::Utf8(_) | ScalarImpl::Struct(_) | ScalarImpl::List(_)
+    )
+}
+
+/// Serialize the datums of a row into the compact row format.
+pub fn serialize_compact_row(datums: &[Datum]) -> Result<Vec<u8>> {
+    let bitmap_len = (datums.len() + 7) / 8;
+    let mut buf = Vec::with_capacity(1 + bitmap_len + datums.len() * 8);
+    buf.put_u8(COMPACT_ROW_FORMAT_VERSION);
+
+    let mut bitmap = vec![0u8; bitmap_len];
+    for (i, datum) in datums.iter().enumerate() {
+        if datum.is_some() {
+            bitmap[i / 8] |= 1 << (i % 8);
+        }
+    }
+    buf.put_slice(&bitmap);
+
+    let mut var_len_buf = vec![];
+    for scalar in datums.iter().flatten() {
+        if is_var_len_scalar(scalar) {
+            serialize_value(scalar.as_scalar_ref_impl(), &mut var_len_buf);
+        } else {
+            serialize_value(scalar.as_scalar_ref_impl(), &mut buf);
+        }
+    }
+    buf.extend(var_len_buf);
+    Ok(buf)
+}
+
+/// Deserialize a row in the compact row format into `datums`, which are all nulls. The version
+/// byte must have been consumed.
+fn deserialize_compact_row_body(
+    mut data: impl Buf,
+    types: &[DataType],
+    datums: &mut [Datum],
+) -> Result<()> {
+    let mut bitmap = vec![0u8; (types.len() + 7) / 8];
+    data.copy_to_slice(&mut bitmap);
+    let is_set = |i: usize| bitmap[i / 8] & (1 << (i % 8)) != 0;
+
+    for (i, ty) in types.iter().enumerate() {
+        if is_set(i) && !is_var_len(ty) {
+            datums[i] = deserialize_value(ty, &mut data)?;
+        }
+    }
+    for (i, ty) in types.iter().enumerate() {
+        if is_set(i) && is_var_len(ty) {
+            datums[i] = deserialize_value(ty, &mut data)?;
+        }
+    }
+    Ok(())
+}
+
+/// Deserialize a row of the given types, which may be either in the compact row format or in the
+/// legacy format where every value is prefixed with a null tag.
+///
+/// Only the bytes of the row are consumed, so the row can be followed by other data in `data`.
+pub fn deserialize_row(data: impl Buf, types: &[DataType]) -> Result<Vec<Datum>> {
+    let mut datums = Vec::with_capacity(types.len());
+    deserialize_row_into(data, types, &mut datums)?;
+    Ok(datums)
+}
+
+/// Same as [`deserialize_row`], but reuses the allocation of `datums`, whose previous contents are
+/// dropped.
+pub fn deserialize_row_into(
+    mut data: impl Buf,
+    types: &[DataType],
+    datums: &mut Vec<Datum>,
+) -> Result<()> {
+    datums.clear();
+    match data.chunk().first().copied() {
+        Some(COMPACT_ROW_FORMAT_VERSION) => {
+            data.advance(1);
+            datums.resize(types.len(), None);
+            deserialize_compact_row_body(data, types, datums)
+        }
+        // An empty row in the legacy format has no bytes at all.
+        _ if types.is_empty() => Ok(()),
+        Some(0 | 1) => {
+            for ty in types {
+                datums.push(deserialize_datum(&mut data, ty)?);
+            }
+            Ok(())
+        }
+        Some(tag) => Err(ValueEncodingError::InvalidTagEncoding(tag).into()),
+        None => Err(ValueEncodingError::UnexpectedEnd.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IntervalUnit;
+    use crate::util::value_encoding::serialize_datum;
+
+    fn test_types() -> Vec<DataType> {
+        vec![
+            DataType::Int32,
+            DataType::Varchar,
+            DataType::Float64,
+            DataType::Boolean,
+            DataType::Varchar,
+            DataType::Decimal,
+            DataType::Int16,
+            DataType::Int64,
+            DataType::Interval,
+        ]
+    }
+
+    fn test_row() -> Vec<Datum> {
+        vec![
+            Some(ScalarImpl::Int32(1)),
+            Some(ScalarImpl::Utf8("abc".into())),
+            None,
+            Some(ScalarImpl::Bool(true)),
+            None,
+            Some(ScalarImpl::Decimal("-233.3".parse().unwrap())),
+            Some(ScalarImpl::Int16(7)),
+            None,
+            Some(ScalarImpl::Interval(IntervalUnit::new(1, 2, 3))),
+        ]
+    }
+
+    #[test]
+    fn test_compact_row_roundtrip() {
+        let row = test_row();
+        let mut bytes = serialize_compact_row(&row).unwrap();
+        assert_eq!(bytes[0], COMPACT_ROW_FORMAT_VERSION);
+        // Trailing data must be left untouched.
+        bytes.extend(42u64.to_le_bytes());
+
+        let mut data = &bytes[..];
+        let decoded = deserialize_row(&mut data, &test_types()).unwrap();
+        assert_eq!(decoded, row);
+        assert_eq!(data.get_u64_le(), 42);
+
+        let all_null = vec![None; 3];
+        let bytes = serialize_compact_row(&all_null).unwrap();
+        assert_eq!(bytes.len(), 2);
+        let decoded = deserialize_row(&bytes[..], &vec![DataType::Int32; 3]).unwrap();
+        assert_eq!(decoded, all_null);
+    }
+
+    #[test]
+    fn test_legacy_row_compatibility() {
+        let row = test_row();
+        let mut bytes = vec![];
+        for datum in &row {
+            bytes.extend(serialize_datum(datum).unwrap());
+        }
+        assert!(serialize_compact_row(&row).unwrap().len() < bytes.len());
+        let decoded = deserialize_row(&bytes[..], &test_types()).unwrap();
+        assert_eq!(decoded, row);
+
+        assert!(deserialize_row(&[3u8][..], &[DataType::Int32]).is_err());
+    }
+}
@@ -28,4 +28,6 @@ pub enum ValueEncodingError {
     InvalidNaiveTimeEncoding(u32, u32),
     #[error("Invalid null tag value encoding: {0}")]
     InvalidTagEncoding(u8),
+    #[error("Unexpected end of value encoding")]
+    UnexpectedEnd,
 }
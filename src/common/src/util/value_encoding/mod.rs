@@ -24,6 +24,7 @@ use crate::types::{
     NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarImpl, ScalarRefImpl,
 };
 
+pub mod compact_row;
 pub mod error;
 use error::ValueEncodingError;
 
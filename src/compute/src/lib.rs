@@ -31,8 +31,10 @@
 #[macro_use]
 extern crate log;
 
+pub mod observer;
 pub mod rpc;
 pub mod server;
+pub mod shutdown;
 
 use clap::Parser;
 
@@ -70,6 +72,11 @@ pub struct ComputeNodeOpts {
     /// Enable reporting tracing information to jaeger
     #[clap(long)]
     pub enable_jaeger_tracing: bool,
+
+    /// The number of parallel units of this node, one of which is reserved for singleton
+    /// fragments. 0 means to use the default of the meta service.
+    #[clap(long, default_value = "0")]
+    pub worker_node_parallelism: usize,
 }
 
 use crate::server::compute_node_serve;
@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use risingwave_common::error::Result;
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+use risingwave_pb::meta::subscribe_response::Info;
+use risingwave_pb::meta::{SubscribeResponse, SystemParams};
+use risingwave_rpc_client::{MetaClient, NotificationStream};
+use risingwave_storage::hummock::local_version_manager::LocalVersionManager;
+use tokio::task::JoinHandle;
+
+/// `ObserverManager` is used to apply the system params notified by meta to the compute node.
+/// Call `start` to spawn a new asynchronous task which receives meta's notification.
+pub struct ObserverManager {
+    rx: Box<dyn NotificationStream>,
+    meta_client: MetaClient,
+    addr: HostAddr,
+    local_version_manager: Arc<LocalVersionManager>,
+}
+
+const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+impl ObserverManager {
+    pub async fn new(
+        meta_client: MetaClient,
+        addr: HostAddr,
+        local_version_manager: Arc<LocalVersionManager>,
+    ) -> Self {
+        let rx = meta_client
+            .subscribe(&addr, WorkerType::ComputeNode)
+            .await
+            .unwrap();
+        Self {
+            rx,
+            meta_client,
+            addr,
+            local_version_manager,
+        }
+    }
+
+    fn handle_notification(&self, resp: SubscribeResponse) {
+        // Other notifications to compute nodes are consumed elsewhere.
+        if let Some(Info::SystemParams(params)) = resp.info {
+            self.apply_system_params(&params);
+        }
+    }
+
+    fn apply_system_params(&self, params: &SystemParams) {
+        tracing::info!("Apply system params: {:?}", params);
+        self.local_version_manager
+            .set_compaction_worker_threads(params.compaction_worker_threads_number);
+    }
+
+    /// Meta sends no snapshot to compute nodes, so the current params are fetched after
+    /// subscribing, and no update in between is missed.
+    async fn sync_system_params(&self) -> Result<()> {
+        let params = self.meta_client.get_system_params().await?;
+        self.apply_system_params(&params);
+        Ok(())
+    }
+
+    /// `start` is used to spawn a new asynchronous task which receives meta's notification and
+    /// applies the system params.
+    pub async fn start(mut self) -> Result<JoinHandle<()>> {
+        self.sync_system_params().await?;
+        let handle = tokio::spawn(async move {
+            loop {
+                match self.rx.next().await {
+                    Ok(Some(resp)) => self.handle_notification(resp),
+                    Ok(None) => {
+                        tracing::error!("Stream of notification terminated.");
+                        self.re_subscribe().await;
+                    }
+                    Err(err) => {
+                        tracing::error!("Failed to receive notification: {}", err);
+                        self.re_subscribe().await;
+                    }
+                }
+            }
+        });
+        Ok(handle)
+    }
+
+    /// `re_subscribe` is used to re-subscribe to the meta's notification.
+    async fn re_subscribe(&mut self) {
+        loop {
+            match self
+                .meta_client
+                .subscribe(&self.addr, WorkerType::ComputeNode)
+                .await
+            {
+                Ok(rx) => {
+                    tracing::debug!("re-subscribe success");
+                    self.rx = rx;
+                    // The updates while unsubscribed are lost.
+                    if self.sync_system_params().await.is_ok() {
+                        break;
+                    }
+                }
+                Err(_) => {
+                    tokio::time::sleep(RE_SUBSCRIBE_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
@@ -24,7 +24,7 @@ use risingwave_pb::task_service::{
     GetDataRequest, GetDataResponse, GetStreamRequest, GetStreamResponse,
 };
 use risingwave_stream::executor::Message;
-use risingwave_stream::task::LocalStreamManager;
+use risingwave_stream::task::{BufferOccupancy, LocalStreamManager};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
@@ -78,7 +78,11 @@ impl ExchangeService for ExchangeServiceImpl {
         let req = request.into_inner();
         let up_down_ids = (req.up_fragment_id, req.down_fragment_id);
         let receiver = self.stream_mgr.take_receiver(up_down_ids)?;
-        match self.get_stream_impl(peer_addr, receiver, up_down_ids).await {
+        let occupancy = self.stream_mgr.buffer_occupancy(up_down_ids);
+        match self
+            .get_stream_impl(peer_addr, receiver, occupancy, up_down_ids)
+            .await
+        {
             Ok(resp) => Ok(resp),
             Err(e) => {
                 error!(
@@ -108,6 +112,7 @@ impl ExchangeServiceImpl {
         &self,
         peer_addr: SocketAddr,
         mut receiver: Receiver<Message>,
+        occupancy: BufferOccupancy,
         up_down_ids: (u32, u32),
     ) -> Result<Response<<Self as ExchangeService>::GetStreamStream>> {
         let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
@@ -122,6 +127,7 @@ impl ExchangeServiceImpl {
                     // the sender is closed, we close the receiver and stop forwarding message
                     None => break,
                     Some(msg) => {
+                        occupancy.dec();
                         let res = match msg.to_protobuf() {
                             Ok(stream_msg) => Ok(GetStreamResponse {
                                 message: Some(stream_msg),
@@ -17,22 +17,31 @@ use std::sync::Arc;
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{tonic_err, Result as RwResult};
+use risingwave_common::util::tracing_context::TracingContext;
 use risingwave_pb::catalog::Source;
 use risingwave_pb::stream_service::stream_service_server::StreamService;
 use risingwave_pb::stream_service::*;
 use risingwave_stream::executor::{Barrier, Epoch};
 use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
 use tonic::{Request, Response, Status};
+use tracing::Instrument;
+
+use crate::shutdown::GracefulShutdown;
 
 #[derive(Clone)]
 pub struct StreamServiceImpl {
     mgr: Arc<LocalStreamManager>,
     env: StreamEnvironment,
+    shutdown: Arc<GracefulShutdown>,
 }
 
 impl StreamServiceImpl {
-    pub fn new(mgr: Arc<LocalStreamManager>, env: StreamEnvironment) -> Self {
-        StreamServiceImpl { mgr, env }
+    pub fn new(
+        mgr: Arc<LocalStreamManager>,
+        env: StreamEnvironment,
+        shutdown: Arc<GracefulShutdown>,
+    ) -> Self {
+        StreamServiceImpl { mgr, env, shutdown }
     }
 }
 
@@ -101,7 +110,7 @@ impl StreamService for StreamServiceImpl {
     ) -> std::result::Result<Response<DropActorsResponse>, Status> {
         let req = request.into_inner();
         let actors = req.actor_ids;
-        self.mgr.drop_actor(&actors)?;
+        self.mgr.drop_actor(&actors, req.epoch).await?;
         Ok(Response::new(DropActorsResponse {
             request_id: req.request_id,
             status: None,
@@ -135,6 +144,11 @@ impl StreamService for StreamServiceImpl {
         let req = request.into_inner();
         let barrier =
             Barrier::from_protobuf(req.get_barrier().map_err(tonic_err)?).map_err(tonic_err)?;
+        // Actors poll under the span of the barrier, which follows the one of the meta node.
+        let span = TracingContext::from_protobuf(&req.tracing_context).attach(
+            tracing::trace_span!("inject_barrier", epoch = barrier.epoch.curr,),
+        );
+        let barrier = barrier.with_span(span.clone());
 
         let collect_result = self
             .mgr
@@ -142,8 +156,9 @@ impl StreamService for StreamServiceImpl {
                 &barrier,
                 req.actor_ids_to_send,
                 req.actor_ids_to_collect,
-                true,
+                barrier.checkpoint,
             )
+            .instrument(span)
             .await?;
 
         Ok(Response::new(InjectBarrierResponse {
@@ -154,6 +169,42 @@ impl StreamService for StreamServiceImpl {
         }))
     }
 
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_inflight_barrier(
+        &self,
+        _request: Request<GetInflightBarrierRequest>,
+    ) -> Result<Response<GetInflightBarrierResponse>, Status> {
+        let response = match self.mgr.inflight_barrier() {
+            Some(inflight) => GetInflightBarrierResponse {
+                status: None,
+                inflight: true,
+                epoch: inflight.epoch,
+                elapsed_ms: inflight.elapsed.as_millis() as u64,
+                remaining_actors: inflight
+                    .remaining_actors
+                    .into_iter()
+                    .map(|actor_id| get_inflight_barrier_response::RemainingActor {
+                        actor_id,
+                        description: self.mgr.describe_actor(actor_id).unwrap_or_default(),
+                    })
+                    .collect(),
+            },
+            None => GetInflightBarrierResponse::default(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn stack_trace(
+        &self,
+        _request: Request<StackTraceRequest>,
+    ) -> Result<Response<StackTraceResponse>, Status> {
+        Ok(Response::new(StackTraceResponse {
+            actor_traces: self.mgr.actor_stack_traces().into_iter().collect(),
+        }))
+    }
+
     #[cfg_attr(coverage, no_coverage)]
     async fn create_source(
         &self,
@@ -200,6 +251,21 @@ impl StreamService for StreamServiceImpl {
 
         Ok(Response::new(DropSourceResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn shutdown(
+        &self,
+        _request: Request<ShutdownRequest>,
+    ) -> std::result::Result<Response<ShutdownResponse>, Status> {
+        let committed_epoch = self.shutdown.run().await.map_err(|e| {
+            error!("failed to shut down gracefully {}", e);
+            tonic_err(e)
+        })?;
+        Ok(Response::new(ShutdownResponse {
+            status: None,
+            committed_epoch: committed_epoch.unwrap_or_default(),
+        }))
+    }
 }
 
 impl StreamServiceImpl {
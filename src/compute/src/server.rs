@@ -40,11 +40,16 @@ use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
+use crate::observer::observer_manager::ObserverManager;
 use crate::rpc::service::exchange_metrics::ExchangeServiceMetrics;
 use crate::rpc::service::exchange_service::ExchangeServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
+use crate::shutdown::GracefulShutdown;
 use crate::ComputeNodeOpts;
 
+/// Interval between two checks of the cache usage of the stream executors.
+const MEMORY_CHECK_INTERVAL_MS: u64 = 1000;
+
 fn load_config(opts: &ComputeNodeOpts) -> ComputeNodeConfig {
     risingwave_common::config::load_config(&opts.config_path)
 }
@@ -75,7 +80,11 @@ pub async fn compute_node_serve(
 
     // Register to the cluster. We're not ready to serve until activate is called.
     let worker_id = meta_client
-        .register(&client_addr, WorkerType::ComputeNode)
+        .register(
+            &client_addr,
+            WorkerType::ComputeNode,
+            opts.worker_node_parallelism,
+        )
         .await
         .unwrap();
     info!("Assigned worker node id {}", worker_id);
@@ -125,6 +134,15 @@ pub async fn compute_node_serve(
             sub_tasks.push((handle, shutdown_sender));
         }
         monitor_cache(storage.inner().sstable_store(), &registry).unwrap();
+
+        // Apply the system params notified by meta, e.g., the compaction worker threads.
+        let observer_manager = ObserverManager::new(
+            meta_client.clone(),
+            client_addr.clone(),
+            storage.inner().local_version_manager().clone(),
+        )
+        .await;
+        observer_manager.start().await.unwrap();
     }
 
     // Initialize the managers.
@@ -136,6 +154,11 @@ pub async fn compute_node_serve(
         config.streaming.clone(),
     ));
     let source_mgr = Arc::new(MemSourceManager::new(worker_id));
+    sub_tasks.push(
+        stream_mgr
+            .memory_manager()
+            .start(Duration::from_millis(MEMORY_CHECK_INTERVAL_MS)),
+    );
 
     // Initialize batch environment.
     let batch_config = Arc::new(config.batch.clone());
@@ -163,7 +186,15 @@ pub async fn compute_node_serve(
     let batch_srv = BatchServiceImpl::new(batch_mgr.clone(), batch_env);
     let exchange_srv =
         ExchangeServiceImpl::new(batch_mgr, stream_mgr.clone(), exchange_srv_metrics);
-    let stream_srv = StreamServiceImpl::new(stream_mgr, stream_env.clone());
+    // Stops the node after it's drained by a `Shutdown` request.
+    let (drained_send, mut drained_recv) = tokio::sync::oneshot::channel::<()>();
+    let graceful_shutdown = Arc::new(GracefulShutdown::new(
+        meta_client.clone(),
+        client_addr.clone(),
+        stream_mgr.clone(),
+        drained_send,
+    ));
+    let stream_srv = StreamServiceImpl::new(stream_mgr, stream_env.clone(), graceful_shutdown);
 
     let (shutdown_send, mut shutdown_recv) = tokio::sync::oneshot::channel::<()>();
     let join_handle = tokio::spawn(async move {
@@ -174,7 +205,7 @@ pub async fn compute_node_serve(
             .serve_with_shutdown(listen_addr, async move {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {},
-                    _ = &mut shutdown_recv => {
+                    _ = futures::future::select(&mut shutdown_recv, &mut drained_recv) => {
                         for (join_handle, shutdown_sender) in sub_tasks {
                             if let Err(err) = shutdown_sender.send(()) {
                                 tracing::warn!("Failed to send shutdown: {:?}", err);
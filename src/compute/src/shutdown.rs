@@ -0,0 +1,112 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::util::addr::HostAddr;
+use risingwave_rpc_client::MetaClient;
+use risingwave_stream::task::LocalStreamManager;
+use tokio::sync::oneshot::Sender;
+
+/// How long to wait for the in-flight barriers to be committed before giving up the shutdown.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shuts down the compute node without losing the uncommitted epochs, so that a rolling restart
+/// doesn't rely on recovery to replay them.
+pub struct GracefulShutdown {
+    meta_client: MetaClient,
+    client_addr: HostAddr,
+    stream_mgr: Arc<LocalStreamManager>,
+    /// Stops the gRPC server and the other services of the node once drained.
+    shutdown_sender: Mutex<Option<Sender<()>>>,
+}
+
+impl GracefulShutdown {
+    pub fn new(
+        meta_client: MetaClient,
+        client_addr: HostAddr,
+        stream_mgr: Arc<LocalStreamManager>,
+        shutdown_sender: Sender<()>,
+    ) -> Self {
+        Self {
+            meta_client,
+            client_addr,
+            stream_mgr,
+            shutdown_sender: Mutex::new(Some(shutdown_sender)),
+        }
+    }
+
+    /// Cordons the node so that no new actor is scheduled onto it, stops the sources, waits for the
+    /// in-flight barriers to be committed, unregisters from meta, which moves the actors off the
+    /// node, and finally stops the node. Returns the last committed epoch, if any.
+    ///
+    /// If any step fails, the node is uncordoned and its sources are resumed, so that it keeps
+    /// serving as before.
+    pub async fn run(&self) -> Result<Option<u64>> {
+        info!("Shutting down compute node {}", self.client_addr);
+        self.meta_client
+            .cordon_worker_node(self.client_addr.to_protobuf(), true)
+            .await?;
+
+        match self.drain_and_unregister().await {
+            Ok(committed_epoch) => {
+                if let Some(sender) = self.shutdown_sender.lock().take() {
+                    let _ = sender.send(());
+                }
+                Ok(committed_epoch)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to shut down compute node {}, resuming it: {}",
+                    self.client_addr, e
+                );
+                self.stream_mgr.resume_sources();
+                if let Err(e) = self
+                    .meta_client
+                    .cordon_worker_node(self.client_addr.to_protobuf(), false)
+                    .await
+                {
+                    error!(
+                        "Failed to uncordon compute node {}: {}",
+                        self.client_addr, e
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn drain_and_unregister(&self) -> Result<Option<u64>> {
+        let committed_epoch = tokio::time::timeout(DRAIN_TIMEOUT, self.stream_mgr.drain())
+            .await
+            .map_err(|_| {
+                RwError::from(ErrorCode::InternalError(format!(
+                    "compute node not drained in {:?}",
+                    DRAIN_TIMEOUT
+                )))
+            })??;
+        info!(
+            "Compute node {} drained, last committed epoch: {:?}",
+            self.client_addr, committed_epoch
+        );
+
+        self.meta_client
+            .unregister(self.client_addr.clone())
+            .await?;
+        Ok(committed_epoch)
+    }
+}
@@ -1,3 +1,4 @@
+use std::ops::Bound::Unbounded;
 use std::sync::Arc;
 
 // Copyright 2022 Singularity Data
@@ -84,7 +85,7 @@ async fn test_row_seq_scan() -> Result<()> {
 
     let executor = Box::new(RowSeqScanExecutor::new(
         table.schema().clone(),
-        table.iter(u64::MAX).await.unwrap(),
+        vec![table.iter(u64::MAX).await.unwrap()],
         1,
         true,
         "RowSeqScanExecutor2".to_string(),
@@ -138,3 +139,71 @@ async fn test_row_seq_scan() -> Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_parallel_row_seq_scan() -> Result<()> {
+    let memory_state_store = MemoryStateStore::new();
+    let keyspace = Keyspace::executor_root(memory_state_store.clone(), 0x42);
+
+    let column_descs = vec![
+        ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+        ColumnDesc::unnamed(ColumnId::from(1), DataType::Int64),
+    ];
+    let mut state = StateTable::new(
+        keyspace.clone(),
+        column_descs.clone(),
+        vec![OrderType::Ascending],
+        None,
+    );
+    let table = CellBasedTable::new_adhoc(
+        keyspace,
+        column_descs,
+        Arc::new(StateStoreMetrics::unused()),
+    );
+
+    for i in 0..1000_i32 {
+        state
+            .insert(
+                Row(vec![Some(i.into())]),
+                Row(vec![Some(i.into()), Some((i as i64 * 2).into())]),
+            )
+            .unwrap();
+    }
+    state.commit(0).await.unwrap();
+
+    let ranges = table
+        .split_pk_range(u64::MAX, (Unbounded, Unbounded), 4)
+        .await
+        .unwrap();
+    assert_eq!(ranges.len(), 4);
+    let mut iters = vec![];
+    for range in ranges {
+        iters.push(table.iter_with_pk_range(u64::MAX, range).await.unwrap());
+    }
+
+    let executor = Box::new(RowSeqScanExecutor::new(
+        table.schema().clone(),
+        iters,
+        100,
+        true,
+        "RowSeqScanExecutor2".to_string(),
+        Arc::new(BatchMetrics::unused()),
+    ));
+
+    // The rows of all ranges are returned exactly once and in the pk order.
+    let mut stream = executor.execute();
+    let mut pks = vec![];
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        pks.extend(
+            chunk
+                .column_at(0)
+                .array()
+                .as_int32()
+                .iter()
+                .map(Option::unwrap),
+        );
+    }
+    assert_eq!(pks, (0..1000).collect::<Vec<_>>());
+    Ok(())
+}
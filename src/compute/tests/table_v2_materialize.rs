@@ -143,6 +143,7 @@ async fn test_table_v2_materialize() -> Result<()> {
         barrier_rx,
         1,
         1,
+        1,
         "SourceExecutor".to_string(),
         Arc::new(StreamingMetrics::unused()),
         vec![],
@@ -207,7 +208,7 @@ async fn test_table_v2_materialize() -> Result<()> {
 
     let scan = Box::new(RowSeqScanExecutor::new(
         table.schema().clone(),
-        table.iter(u64::MAX).await?,
+        vec![table.iter(u64::MAX).await?],
         1024,
         true,
         "RowSeqExecutor2".to_string(),
@@ -266,7 +267,7 @@ async fn test_table_v2_materialize() -> Result<()> {
     // Scan the table again, we are able to get the data now!
     let scan = Box::new(RowSeqScanExecutor::new(
         table.schema().clone(),
-        table.iter(u64::MAX).await?,
+        vec![table.iter(u64::MAX).await?],
         1024,
         true,
         "RowSeqScanExecutor2".to_string(),
@@ -334,7 +335,7 @@ async fn test_table_v2_materialize() -> Result<()> {
     // Scan the table again, we are able to see the deletion now!
     let scan = Box::new(RowSeqScanExecutor::new(
         table.schema().clone(),
-        table.iter(u64::MAX).await?,
+        vec![table.iter(u64::MAX).await?],
         1024,
         true,
         "RowSeqScanExecutor2".to_string(),
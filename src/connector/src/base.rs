@@ -25,6 +25,9 @@ use crate::datagen::{
     DatagenProperties, DatagenSplit, DatagenSplitEnumerator, DatagenSplitReader, DATAGEN_CONNECTOR,
 };
 use crate::dummy_connector::DummySplitReader;
+use crate::filesystem::file::{
+    FileProperties, FileSplit, FileSplitEnumerator, FileSplitReader, FILE_CONNECTOR,
+};
 use crate::filesystem::s3::{S3Properties, S3_CONNECTOR};
 use crate::kafka::enumerator::KafkaSplitEnumerator;
 use crate::kafka::source::KafkaSplitReader;
@@ -74,6 +77,7 @@ pub enum SplitImpl {
     Kinesis(KinesisSplit),
     Nexmark(NexmarkSplit),
     Datagen(DatagenSplit),
+    File(FileSplit),
 }
 
 pub enum SplitReaderImpl {
@@ -83,6 +87,7 @@ pub enum SplitReaderImpl {
     Nexmark(Box<NexmarkSplitReader>),
     Pulsar(Box<PulsarSplitReader>),
     Datagen(Box<DatagenSplitReader>),
+    File(Box<FileSplitReader>),
 }
 
 pub enum SplitEnumeratorImpl {
@@ -91,6 +96,7 @@ pub enum SplitEnumeratorImpl {
     Kinesis(KinesisSplitEnumerator),
     Nexmark(NexmarkSplitEnumerator),
     Datagen(DatagenSplitEnumerator),
+    File(FileSplitEnumerator),
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -101,6 +107,7 @@ pub enum ConnectorProperties {
     Nexmark(Box<NexmarkProperties>),
     Datagen(DatagenProperties),
     S3(S3Properties),
+    File(FileProperties),
     Dummy(()),
 }
 
@@ -111,7 +118,8 @@ impl_connector_properties! {
     { Kinesis, KINESIS_CONNECTOR },
     { Nexmark, NEXMARK_CONNECTOR },
     { Datagen, DATAGEN_CONNECTOR },
-    { S3, S3_CONNECTOR }
+    { S3, S3_CONNECTOR },
+    { File, FILE_CONNECTOR }
 }
 
 impl_split_enumerator! {
@@ -120,7 +128,8 @@ impl_split_enumerator! {
     { Pulsar, PulsarSplitEnumerator },
     { Kinesis, KinesisSplitEnumerator },
     { Nexmark, NexmarkSplitEnumerator },
-    { Datagen, DatagenSplitEnumerator }
+    { Datagen, DatagenSplitEnumerator },
+    { File, FileSplitEnumerator }
 }
 
 impl_split! {
@@ -129,7 +138,8 @@ impl_split! {
     { Pulsar, PULSAR_CONNECTOR, PulsarSplit },
     { Kinesis, KINESIS_CONNECTOR, KinesisSplit },
     { Nexmark, NEXMARK_CONNECTOR, NexmarkSplit },
-    { Datagen, DATAGEN_CONNECTOR, DatagenSplit }
+    { Datagen, DATAGEN_CONNECTOR, DatagenSplit },
+    { File, FILE_CONNECTOR, FileSplit }
 }
 
 impl_split_reader! {
@@ -139,6 +149,7 @@ impl_split_reader! {
     { Kinesis, KinesisMultiSplitReader },
     { Nexmark, NexmarkSplitReader },
     { Datagen, DatagenSplitReader },
+    { File, FileSplitReader },
     { Dummy, DummySplitReader }
 }
 
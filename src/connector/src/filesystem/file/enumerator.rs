@@ -0,0 +1,48 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+
+use crate::base::SplitEnumerator;
+use crate::filesystem::file::store::FileStore;
+use crate::filesystem::file::{FileProperties, FileSplit};
+
+/// Lists the files under the location as splits. Since the splits are identified by their paths,
+/// only the files that are new to the source get assigned after each discovery.
+#[derive(Debug)]
+pub struct FileSplitEnumerator {
+    store: FileStore,
+}
+
+#[async_trait]
+impl SplitEnumerator for FileSplitEnumerator {
+    type Properties = FileProperties;
+    type Split = FileSplit;
+
+    async fn new(properties: FileProperties) -> anyhow::Result<FileSplitEnumerator> {
+        Ok(Self {
+            store: FileStore::new(&properties).await?,
+        })
+    }
+
+    async fn list_splits(&mut self) -> anyhow::Result<Vec<FileSplit>> {
+        Ok(self
+            .store
+            .list_files()
+            .await?
+            .into_iter()
+            .map(|path| FileSplit::new(path, 0))
+            .collect())
+    }
+}
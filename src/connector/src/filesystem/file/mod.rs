@@ -0,0 +1,70 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The file source reads the files under a local directory or an S3 prefix. Each file is a split,
+//! whose offset is the number of bytes consumed, and every line of it is a message. Files
+//! appearing later are picked up by the periodical split discovery.
+
+pub mod enumerator;
+pub mod reader;
+pub mod split;
+mod store;
+
+pub use enumerator::*;
+pub use reader::*;
+use serde::Deserialize;
+pub use split::*;
+
+pub const FILE_CONNECTOR: &str = "file";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FileProperties {
+    /// The directory to read, either `file:///path/to/dir` or `s3://bucket/prefix`.
+    #[serde(rename = "file.location")]
+    pub location: String,
+
+    /// The glob that the paths relative to the location must match, e.g. `*.csv`. All files are
+    /// read if not set.
+    #[serde(rename = "file.match_pattern", default)]
+    pub match_pattern: Option<String>,
+
+    /// Whether the first line of each file is a header to skip, either `true` or `false`.
+    #[serde(rename = "file.header", default)]
+    pub header: Option<String>,
+
+    #[serde(rename = "s3.region_name", default)]
+    pub region_name: Option<String>,
+
+    #[serde(rename = "s3.endpoint_url", default)]
+    pub endpoint_url: Option<String>,
+
+    #[serde(rename = "s3.credentials.access", default)]
+    pub access: Option<String>,
+
+    #[serde(rename = "s3.credentials.secret", default)]
+    pub secret: Option<String>,
+}
+
+impl FileProperties {
+    pub fn has_header(&self) -> anyhow::Result<bool> {
+        match self.header.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("false") => Ok(false),
+            Some("true") => Ok(true),
+            Some(other) => Err(anyhow::anyhow!(
+                "'file.header' must be either 'true' or 'false', got '{}'",
+                other
+            )),
+        }
+    }
+}
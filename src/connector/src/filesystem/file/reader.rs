@@ -0,0 +1,207 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::io::AsyncBufReadExt;
+
+use crate::base::{SourceMessage, SplitReader};
+use crate::filesystem::file::store::{FileReader, FileStore};
+use crate::filesystem::file::{FileProperties, FileSplit};
+use crate::{Column, ConnectorState, SplitMetaData};
+
+const MAX_LINES_PER_BATCH: usize = 1024;
+
+/// Reads the assigned files one after another, line by line.
+pub struct FileSplitReader {
+    store: FileStore,
+    has_header: bool,
+    splits: VecDeque<FileSplit>,
+    /// The file being read, whose offset is advanced with the lines read.
+    current: Option<(FileSplit, FileReader)>,
+}
+
+impl FileSplitReader {
+    async fn open(
+        store: &FileStore,
+        has_header: bool,
+        mut split: FileSplit,
+    ) -> Result<(FileSplit, FileReader)> {
+        let mut reader = store.open(&split.path, split.offset).await?;
+        if has_header && split.offset == 0 {
+            let mut header = vec![];
+            split.offset += reader.read_until(b'\n', &mut header).await? as u64;
+        }
+        Ok((split, reader))
+    }
+
+    /// Read a batch of lines from the current file. Empty lines are skipped. Returns `None` at the
+    /// end of the file.
+    async fn read_lines(
+        split: &mut FileSplit,
+        reader: &mut FileReader,
+    ) -> Result<Option<Vec<SourceMessage>>> {
+        let mut messages = vec![];
+        while messages.len() < MAX_LINES_PER_BATCH {
+            let mut line = vec![];
+            let read = reader.read_until(b'\n', &mut line).await?;
+            if read == 0 {
+                return Ok(if messages.is_empty() {
+                    None
+                } else {
+                    Some(messages)
+                });
+            }
+            split.offset += read as u64;
+            while matches!(line.last(), Some(b'\n' | b'\r')) {
+                line.pop();
+            }
+            if line.is_empty() {
+                continue;
+            }
+            messages.push(SourceMessage {
+                payload: Some(Bytes::from(line)),
+                offset: split.offset.to_string(),
+                split_id: split.id(),
+            });
+        }
+        Ok(Some(messages))
+    }
+}
+
+#[async_trait]
+impl SplitReader for FileSplitReader {
+    type Properties = FileProperties;
+
+    async fn new(
+        properties: FileProperties,
+        state: ConnectorState,
+        _columns: Option<Vec<Column>>,
+    ) -> Result<Self> {
+        let splits = state
+            .unwrap_or_default()
+            .into_iter()
+            .map(|split| {
+                split
+                    .into_file()
+                    .map_err(|split| anyhow!("split {:?} is not a file", split))
+            })
+            .collect::<Result<VecDeque<_>>>()?;
+        Ok(Self {
+            store: FileStore::new(&properties).await?,
+            has_header: properties.has_header()?,
+            splits,
+            current: None,
+        })
+    }
+
+    async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>> {
+        loop {
+            if self.current.is_none() {
+                let split = match self.splits.pop_front() {
+                    Some(split) => split,
+                    None => return Ok(None),
+                };
+                self.current = Some(Self::open(&self.store, self.has_header, split).await?);
+            }
+            let (split, reader) = self.current.as_mut().unwrap();
+            match Self::read_lines(split, reader).await? {
+                Some(messages) => return Ok(Some(messages)),
+                None => {
+                    log::info!("finished reading file {}", split.path);
+                    self.current = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::base::{SplitEnumerator, SplitImpl};
+    use crate::filesystem::file::FileSplitEnumerator;
+    use crate::ConnectorProperties;
+
+    fn properties(dir: &str, extra: &[(&str, &str)]) -> FileProperties {
+        let mut props: HashMap<String, String> = extra
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        props.insert("connector".to_string(), "file".to_string());
+        props.insert("file.location".to_string(), format!("file://{}", dir));
+        match ConnectorProperties::extract(props).unwrap() {
+            ConnectorProperties::File(props) => props,
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap().to_string();
+        std::fs::write(dir.path().join("1.csv"), "a,b\n1,x\n\n2,y\r\n3,z").unwrap();
+        std::fs::write(dir.path().join("2.csv"), "a,b\n4,w\n").unwrap();
+        std::fs::write(dir.path().join("ignored.json"), "{}\n").unwrap();
+
+        let props = properties(
+            &dir_path,
+            &[("file.match_pattern", "*.csv"), ("file.header", "true")],
+        );
+        let mut enumerator = FileSplitEnumerator::new(props.clone()).await.unwrap();
+        let splits = enumerator.list_splits().await.unwrap();
+        assert_eq!(
+            splits.iter().map(|s| s.id()).collect_vec(),
+            vec![format!("{}/1.csv", dir_path), format!("{}/2.csv", dir_path)]
+        );
+
+        let state = Some(splits.into_iter().map(SplitImpl::File).collect_vec());
+        let mut reader = FileSplitReader::new(props.clone(), state, None)
+            .await
+            .unwrap();
+        let mut messages = vec![];
+        while let Some(batch) = reader.next().await.unwrap() {
+            messages.extend(batch);
+        }
+        let payloads = messages
+            .iter()
+            .map(|m| String::from_utf8(m.payload.clone().unwrap().to_vec()).unwrap())
+            .collect_vec();
+        assert_eq!(payloads, vec!["1,x", "2,y", "3,z", "4,w"]);
+        assert_eq!(messages[1].offset, "14");
+
+        // Resume from the offset of the second line of the first file, skipping no header.
+        let split = SplitImpl::File(FileSplit::new(format!("{}/1.csv", dir_path), 0))
+            .update(messages[0].offset.clone());
+        let mut reader = FileSplitReader::new(props, Some(vec![split]), None)
+            .await
+            .unwrap();
+        let payloads = reader
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|m| String::from_utf8(m.payload.unwrap().to_vec()).unwrap())
+            .collect_vec();
+        assert_eq!(payloads, vec!["2,y", "3,z"]);
+    }
+}
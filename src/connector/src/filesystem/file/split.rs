@@ -0,0 +1,52 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::base::SplitMetaData;
+
+/// A file to read, identified by its path, e.g., `/data/1.csv` or `s3://bucket/prefix/1.csv`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FileSplit {
+    pub(crate) path: String,
+    /// The number of bytes already consumed.
+    pub(crate) offset: u64,
+}
+
+impl SplitMetaData for FileSplit {
+    fn id(&self) -> String {
+        self.path.clone()
+    }
+
+    fn encode_to_bytes(&self) -> Bytes {
+        Bytes::from(serde_json::to_string(self).unwrap())
+    }
+
+    fn restore_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| anyhow!(e))
+    }
+}
+
+impl FileSplit {
+    pub fn new(path: String, offset: u64) -> Self {
+        Self { path, offset }
+    }
+
+    /// The offset of a message is the position right after it, so reading resumes from there.
+    pub fn copy_with_offset(&self, start_offset: String) -> Self {
+        Self::new(self.path.clone(), start_offset.parse::<u64>().unwrap())
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use futures::TryStreamExt;
+use globset::{Glob, GlobMatcher};
+use http::Uri;
+use tokio::io::{AsyncBufRead, AsyncSeekExt, BufReader};
+use tokio_util::io::StreamReader;
+
+use crate::aws_utils::{
+    default_conn_config, s3_client, AwsConfigV2, AwsCredentialV2, EndpointWrapper,
+};
+use crate::filesystem::file::FileProperties;
+
+const LOCAL_SCHEME: &str = "file://";
+const S3_SCHEME: &str = "s3://";
+
+pub(crate) type FileReader = Pin<Box<dyn AsyncBufRead + Send>>;
+
+#[derive(Debug)]
+enum Location {
+    Local {
+        dir: String,
+    },
+    S3 {
+        client: aws_sdk_s3::Client,
+        bucket: String,
+        prefix: String,
+    },
+}
+
+/// Lists and opens the files under the location of a file source.
+#[derive(Debug)]
+pub(crate) struct FileStore {
+    location: Location,
+    matcher: Option<GlobMatcher>,
+}
+
+impl FileStore {
+    pub async fn new(properties: &FileProperties) -> Result<Self> {
+        let location = if let Some(dir) = properties.location.strip_prefix(LOCAL_SCHEME) {
+            Location::Local {
+                dir: dir.trim_end_matches('/').to_string(),
+            }
+        } else if let Some(path) = properties.location.strip_prefix(S3_SCHEME) {
+            let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+            let credential = match (&properties.access, &properties.secret) {
+                (Some(access_key), Some(secret_access)) => AwsCredentialV2::Static {
+                    access_key: access_key.clone(),
+                    secret_access: secret_access.clone(),
+                    session_token: None,
+                },
+                _ => AwsCredentialV2::None,
+            };
+            let endpoint = match &properties.endpoint_url {
+                Some(url) => Some(EndpointWrapper {
+                    uri: Uri::from_str(url)
+                        .map_err(|e| anyhow!("failed to parse url ({}): {}", url, e))?,
+                }),
+                None => None,
+            };
+            let config = AwsConfigV2 {
+                region: properties.region_name.clone(),
+                arn: None,
+                credential,
+                endpoint,
+            };
+            let sdk_config = config.load_config(None).await;
+            Location::S3 {
+                client: s3_client(&sdk_config, Some(default_conn_config())),
+                bucket: bucket.to_string(),
+                prefix: prefix.to_string(),
+            }
+        } else {
+            return Err(anyhow!(
+                "file location must start with {} or {}, got {}",
+                LOCAL_SCHEME,
+                S3_SCHEME,
+                properties.location
+            ));
+        };
+        let matcher = properties
+            .match_pattern
+            .as_ref()
+            .map(|pattern| Glob::new(pattern).map(|glob| glob.compile_matcher()))
+            .transpose()?;
+        Ok(Self { location, matcher })
+    }
+
+    fn is_match(&self, relative_path: &str) -> bool {
+        self.matcher
+            .as_ref()
+            .map_or(true, |matcher| matcher.is_match(relative_path))
+    }
+
+    /// List the paths of the files matching the pattern, in lexicographical order.
+    pub async fn list_files(&self) -> Result<Vec<String>> {
+        let mut paths = vec![];
+        match &self.location {
+            Location::Local { dir } => {
+                let mut entries = tokio::fs::read_dir(dir).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if !entry.file_type().await?.is_file() {
+                        continue;
+                    }
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if self.is_match(&name) {
+                        paths.push(format!("{}/{}", dir, name));
+                    }
+                }
+            }
+            Location::S3 {
+                client,
+                bucket,
+                prefix,
+            } => {
+                let mut continuation_token = None;
+                loop {
+                    let output = client
+                        .list_objects_v2()
+                        .bucket(bucket)
+                        .prefix(prefix)
+                        .set_continuation_token(continuation_token)
+                        .send()
+                        .await
+                        .map_err(|e| anyhow!("failed to list objects of {}: {}", bucket, e))?;
+                    for key in output
+                        .contents
+                        .unwrap_or_default()
+                        .into_iter()
+                        .flat_map(|o| o.key)
+                    {
+                        let relative_path = key[prefix.len()..].trim_start_matches('/');
+                        if !key.ends_with('/') && self.is_match(relative_path) {
+                            paths.push(format!("{}{}/{}", S3_SCHEME, bucket, key));
+                        }
+                    }
+                    if !output.is_truncated {
+                        break;
+                    }
+                    continuation_token = output.next_continuation_token;
+                }
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Open the file of `path` to read from `offset`.
+    pub async fn open(&self, path: &str, offset: u64) -> Result<FileReader> {
+        match &self.location {
+            Location::Local { .. } => {
+                let mut file = tokio::fs::File::open(path).await?;
+                file.seek(SeekFrom::Start(offset)).await?;
+                Ok(Box::pin(BufReader::new(file)))
+            }
+            Location::S3 { client, bucket, .. } => {
+                let key = path
+                    .strip_prefix(S3_SCHEME)
+                    .and_then(|path| path.strip_prefix(bucket.as_str()))
+                    .map(|key| key.trim_start_matches('/'))
+                    .ok_or_else(|| anyhow!("{} is not in bucket {}", path, bucket))?;
+                let size = client
+                    .head_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("failed to get the size of {}: {}", path, e))?
+                    .content_length;
+                // A range starting at the end is not satisfiable.
+                if offset >= size as u64 {
+                    return Ok(Box::pin(tokio::io::empty()));
+                }
+                let body = client
+                    .get_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .range(format!("bytes={}-", offset))
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("failed to get {}: {}", path, e))?
+                    .body;
+                Ok(Box::pin(StreamReader::new(body.map_err(|e| {
+                    std::io::Error::new(std::io::ErrorKind::Other, e)
+                }))))
+            }
+        }
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::time::Duration;
 
 use serde::Deserialize;
@@ -28,20 +29,64 @@ pub const KAFKA_CONNECTOR: &str = "kafka";
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct KafkaProperties {
-    #[serde(rename = "kafka.brokers")]
+    #[serde(rename = "kafka.brokers", alias = "properties.bootstrap.server")]
     pub brokers: String,
 
-    #[serde(rename = "kafka.topic")]
+    #[serde(rename = "kafka.topic", alias = "topic")]
     pub topic: String,
 
-    #[serde(rename = "kafka.scan.startup.mode")]
+    #[serde(rename = "kafka.scan.startup.mode", alias = "scan.startup.mode")]
     pub scan_startup_mode: Option<String>,
 
-    #[serde(rename = "kafka.time.offset")]
+    #[serde(rename = "kafka.time.offset", alias = "scan.startup.timestamp_millis")]
     pub time_offset: Option<String>,
 
-    #[serde(rename = "kafka.consumer.group")]
+    #[serde(rename = "kafka.consumer.group", alias = "properties.group.id")]
     pub consumer_group: Option<String>,
+
+    /// Other options of the Kafka client, prefixed by `properties.`, e.g.
+    /// `properties.security.protocol`. They're passed to the consumers as they are.
+    #[serde(flatten)]
+    pub client_properties: HashMap<String, String>,
+}
+
+impl KafkaProperties {
+    const CLIENT_PROPERTY_PREFIX: &'static str = "properties.";
+
+    /// The options to be set on the Kafka client, with the `properties.` prefix stripped.
+    pub fn client_properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.client_properties.iter().filter_map(|(key, value)| {
+            key.strip_prefix(Self::CLIENT_PROPERTY_PREFIX)
+                .map(|key| (key, value.as_str()))
+        })
+    }
 }
 
 const KAFKA_SYNC_CALL_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashmap;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_kafka_properties() {
+        let props: KafkaProperties = serde_json::from_value(serde_json::json!({
+            "topic": "t",
+            "properties.bootstrap.server": "127.0.0.1:29092",
+            "properties.group.id": "g",
+            "properties.security.protocol": "SASL_SSL",
+            "scan.startup.mode": "earliest",
+        }))
+        .unwrap();
+        assert_eq!(props.topic, "t");
+        assert_eq!(props.brokers, "127.0.0.1:29092");
+        assert_eq!(props.consumer_group.as_deref(), Some("g"));
+        assert_eq!(props.scan_startup_mode.as_deref(), Some("earliest"));
+        assert_eq!(
+            props.client_properties().collect::<HashMap<_, _>>(),
+            hashmap! { "security.protocol" => "SASL_SSL" }
+        );
+    }
+}
@@ -47,17 +47,21 @@ impl SplitReader for KafkaSplitReader {
     where
         Self: Sized,
     {
-        let bootstrap_servers = properties.brokers;
-
         let mut config = ClientConfig::new();
+        for (key, value) in properties.client_properties() {
+            config.set(key, value);
+        }
 
         // disable partition eof
         config.set("enable.partition.eof", "false");
+        // offsets are checkpointed in the state store by the source executor instead
         config.set("enable.auto.commit", "false");
         config.set("auto.offset.reset", "smallest");
-        config.set("bootstrap.servers", bootstrap_servers);
+        config.set("bootstrap.servers", &properties.brokers);
 
-        if config.get("group.id").is_none() {
+        if let Some(group) = &properties.consumer_group {
+            config.set("group.id", group);
+        } else if config.get("group.id").is_none() {
             config.set(
                 "group.id",
                 format!(
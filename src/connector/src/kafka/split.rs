@@ -55,12 +55,28 @@ impl KafkaSplit {
         }
     }
 
-    pub fn copy_with_offset(&self, start_offset: String) -> Self {
+    /// Returns the split to resume from after the message at `offset` has been consumed.
+    pub fn copy_with_offset(&self, offset: String) -> Self {
         Self::new(
             self.partition,
-            Some(start_offset.as_str().parse::<i64>().unwrap()),
+            Some(offset.as_str().parse::<i64>().unwrap() + 1),
             self.stop_offset,
             self.topic.clone(),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_after_consumed_offset() {
+        let split = KafkaSplit::new(0, Some(0), None, "t".to_string());
+        let resumed = split.copy_with_offset("41".to_string());
+        assert_eq!(resumed.start_offset, Some(42));
+
+        let restored = KafkaSplit::restore_from_bytes(&resumed.encode_to_bytes()).unwrap();
+        assert_eq!(restored, resumed);
+    }
+}
@@ -40,6 +40,8 @@ mod kafka;
 pub mod kinesis;
 mod nexmark;
 mod pulsar;
+pub mod schema_registry;
+pub mod sink;
 
 pub use base::*;
 
@@ -50,6 +52,7 @@ pub mod state;
 
 pub use base::ConnectorState;
 pub use datagen::DATAGEN_CONNECTOR;
+pub use filesystem::file::FILE_CONNECTOR;
 pub use kafka::KAFKA_CONNECTOR;
 pub use kinesis::KINESIS_CONNECTOR;
 pub use nexmark::NEXMARK_CONNECTOR;
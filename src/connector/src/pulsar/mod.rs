@@ -26,18 +26,18 @@ pub const PULSAR_CONNECTOR: &str = "pulsar";
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct PulsarProperties {
-    #[serde(rename = "pulsar.topic")]
+    #[serde(rename = "pulsar.topic", alias = "topic")]
     pub topic: String,
 
-    #[serde(rename = "pulsar.admin.url")]
+    #[serde(rename = "pulsar.admin.url", alias = "admin.url")]
     pub admin_url: String,
 
-    #[serde(rename = "pulsar.service.url")]
+    #[serde(rename = "pulsar.service.url", alias = "service.url")]
     pub service_url: String,
 
-    #[serde(rename = "pulsar.scan.startup.mode")]
+    #[serde(rename = "pulsar.scan.startup.mode", alias = "scan.startup.mode")]
     pub scan_startup_mode: Option<String>,
 
-    #[serde(rename = "pulsar.time.offset")]
+    #[serde(rename = "pulsar.time.offset", alias = "scan.startup.timestamp_millis")]
     pub time_offset: Option<String>,
 }
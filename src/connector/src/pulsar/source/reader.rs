@@ -91,36 +91,42 @@ impl PulsarSingleSplitReader {
             .await
             .map_err(|e| anyhow!(e))?;
 
+        // The consumer works like a Pulsar reader: the subscription is non-durable, so that no
+        // cursor is left on the broker. The position is tracked by the message ids checkpointed in
+        // the source state instead.
+        let options = match split.start_offset.clone() {
+            PulsarEnumeratorOffset::Earliest => {
+                ConsumerOptions::default().with_initial_position(InitialPosition::Earliest)
+            }
+            PulsarEnumeratorOffset::Latest => {
+                ConsumerOptions::default().with_initial_position(InitialPosition::Latest)
+            }
+            PulsarEnumeratorOffset::MessageId(m) => ConsumerOptions {
+                start_message_id: Some(parse_message_id(m.as_str())?),
+                ..Default::default()
+            },
+            PulsarEnumeratorOffset::Timestamp(_) => {
+                return Err(anyhow!(
+                    "starting pulsar source from a timestamp is not supported yet"
+                ));
+            }
+        };
+
         let builder: ConsumerBuilder<TokioExecutor> = pulsar
             .consumer()
             .with_topic(topic)
             .with_subscription_type(SubType::Exclusive)
             .with_subscription(format!(
-                "consumer-{}",
+                "reader-{}",
                 SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_micros()
-            ));
-
-        let builder = match split.start_offset.clone() {
-            PulsarEnumeratorOffset::Earliest => builder.with_options(
-                ConsumerOptions::default().with_initial_position(InitialPosition::Earliest),
-            ),
-            PulsarEnumeratorOffset::Latest => builder.with_options(
-                ConsumerOptions::default().with_initial_position(InitialPosition::Latest),
-            ),
-            PulsarEnumeratorOffset::MessageId(m) => builder.with_options(pulsar::ConsumerOptions {
+            ))
+            .with_options(ConsumerOptions {
                 durable: Some(false),
-                start_message_id: parse_message_id(m.as_str()).ok(),
-                ..Default::default()
-            }),
-
-            PulsarEnumeratorOffset::Timestamp(_) => {
-                // todo: implement
-                builder
-            }
-        };
+                ..options
+            });
 
         let consumer: Consumer<Vec<u8>, _> = builder.build().await.map_err(|e| anyhow!(e))?;
 
@@ -238,3 +244,23 @@ impl Drop for PulsarSplitReader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message_id() {
+        let id = parse_message_id("12:34:2:-1").unwrap();
+        assert_eq!((id.ledger_id, id.entry_id), (12, 34));
+        assert_eq!(id.partition, Some(2));
+        assert_eq!(id.batch_index, Some(-1));
+
+        let id = parse_message_id("12:34").unwrap();
+        assert_eq!(id.partition, None);
+        assert_eq!(id.batch_index, None);
+
+        assert!(parse_message_id("12").is_err());
+        assert!(parse_message_id("12:x").is_err());
+    }
+}
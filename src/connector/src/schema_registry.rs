@@ -0,0 +1,159 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A client of the Confluent Schema Registry, from which the schemas of the records encoded in the
+//! Confluent wire format are fetched.
+
+use anyhow::{anyhow, Result};
+use hyper::body::Buf;
+use hyper::{Client, Uri};
+use serde_derive::Deserialize;
+
+/// Magic byte leading every record in the Confluent wire format.
+pub const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// Length of the header of the Confluent wire format: the magic byte and a big-endian `i32`
+/// schema id.
+pub const CONFLUENT_HEADER_LEN: usize = 5;
+
+/// Splits a record in the Confluent wire format into the schema id and the encoded payload.
+pub fn extract_schema_id(data: &[u8]) -> Result<(i32, &[u8])> {
+    if data.len() < CONFLUENT_HEADER_LEN {
+        return Err(anyhow!(
+            "record of {} bytes is too short for the confluent wire format",
+            data.len()
+        ));
+    }
+    if data[0] != CONFLUENT_MAGIC_BYTE {
+        return Err(anyhow!("unknown magic byte {} of the record", data[0]));
+    }
+    let schema_id = i32::from_be_bytes(data[1..CONFLUENT_HEADER_LEN].try_into().unwrap());
+    Ok((schema_id, &data[CONFLUENT_HEADER_LEN..]))
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SchemaById {
+    pub schema: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SubjectVersion {
+    pub subject: String,
+    pub id: i32,
+    pub version: i32,
+    pub schema: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaRegistryClient {
+    base_path: String,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(base_path: &str) -> Self {
+        Self {
+            base_path: base_path.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Fetches the schema registered with the given id.
+    pub async fn get_schema_by_id(&self, id: i32) -> Result<SchemaById> {
+        self.get(&format!("schemas/ids/{}", id)).await
+    }
+
+    /// Fetches the latest version of the schema registered under the given subject.
+    pub async fn get_latest_schema(&self, subject: &str) -> Result<SubjectVersion> {
+        self.get(&format!("subjects/{}/versions/latest", subject))
+            .await
+    }
+
+    async fn get<T>(&self, api: &str) -> Result<T>
+    where
+        T: for<'a> serde::Deserialize<'a>,
+    {
+        let client = Client::new();
+        let url: Uri = format!("{}/{}", self.base_path, api).parse()?;
+        let res = client.get(url.clone()).await?;
+        let status = res.status();
+        let body = hyper::body::aggregate(res).await?;
+        if !status.is_success() {
+            let mut message = String::new();
+            std::io::Read::read_to_string(&mut body.reader(), &mut message)?;
+            return Err(anyhow!(
+                "schema registry request {} failed with {}: {}",
+                url,
+                status,
+                message
+            ));
+        }
+        let result: T = serde_json::from_reader(body.reader())?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn mock_server(web_path: &str, status: u16, body: &str) -> MockServer {
+        let mock_server = MockServer::start().await;
+        let response = ResponseTemplate::new(status)
+            .set_body_string(body)
+            .append_header("content-type", "application/vnd.schemaregistry.v1+json");
+
+        Mock::given(method("GET"))
+            .and(path(web_path))
+            .respond_with(response)
+            .mount(&mock_server)
+            .await;
+
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_get_schema() {
+        let server = mock_server(
+            "/subjects/t1-value/versions/latest",
+            200,
+            r#"{"subject":"t1-value","version":2,"id":7,"schema":"\"string\""}"#,
+        )
+        .await;
+        let client = SchemaRegistryClient::new(&(server.uri() + "/"));
+        let latest = client.get_latest_schema("t1-value").await.unwrap();
+        assert_eq!(latest.id, 7);
+        assert_eq!(latest.version, 2);
+        assert_eq!(latest.schema, "\"string\"");
+
+        let server = mock_server(
+            "/schemas/ids/8",
+            404,
+            r#"{"error_code":40403,"message":"Schema not found"}"#,
+        )
+        .await;
+        let client = SchemaRegistryClient::new(&server.uri());
+        let err = client.get_schema_by_id(8).await.unwrap_err();
+        assert!(err.to_string().contains("Schema not found"));
+    }
+
+    #[test]
+    fn test_extract_schema_id() {
+        let data = [0, 0, 0, 1, 2, 42];
+        assert_eq!(extract_schema_id(&data).unwrap(), (258, &[42][..]));
+        assert!(extract_schema_id(&[0, 0, 1]).is_err());
+        assert!(extract_schema_id(&[1, 0, 0, 0, 1]).is_err());
+    }
+}
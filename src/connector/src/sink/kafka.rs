@@ -0,0 +1,219 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use risingwave_common::array::{Op, RowRef, StreamChunk};
+use risingwave_common::catalog::Schema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::sink::{row_to_json, Sink, SinkFormat};
+
+pub const KAFKA_SINK: &str = "kafka";
+
+#[derive(Clone, Debug, Deserialize)]
+struct KafkaSinkProperties {
+    #[serde(rename = "kafka.brokers")]
+    brokers: String,
+
+    #[serde(rename = "kafka.topic")]
+    topic: String,
+
+    format: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct KafkaConfig {
+    pub brokers: String,
+    pub topic: String,
+    pub format: SinkFormat,
+}
+
+impl KafkaConfig {
+    pub fn from_hashmap(properties: HashMap<String, String>) -> Result<Self> {
+        let json_value = serde_json::to_value(properties).map_err(|e| anyhow!(e))?;
+        let properties: KafkaSinkProperties =
+            serde_json::from_value(json_value).map_err(|e| anyhow!(e.to_string()))?;
+        let format = match properties.format {
+            Some(format) => format.parse()?,
+            None => SinkFormat::AppendOnly,
+        };
+        Ok(Self {
+            brokers: properties.brokers,
+            topic: properties.topic,
+            format,
+        })
+    }
+}
+
+/// Writes the changes to a Kafka topic. Messages are sent asynchronously and only awaited on
+/// `commit`, which gives at-least-once delivery with respect to the checkpoints.
+pub struct KafkaSink {
+    config: KafkaConfig,
+    schema: Schema,
+    pk_indices: Vec<usize>,
+    producer: FutureProducer,
+    in_flight: Vec<DeliveryFuture>,
+}
+
+impl KafkaSink {
+    pub fn new(config: KafkaConfig, schema: Schema, pk_indices: Vec<usize>) -> Result<Self> {
+        if config.format != SinkFormat::AppendOnly && pk_indices.is_empty() {
+            return Err(anyhow!(
+                "sink format {:?} requires a primary key",
+                config.format
+            ));
+        }
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .create()
+            .map_err(|e| anyhow!("producer creation failed {}", e))?;
+        Ok(Self {
+            config,
+            schema,
+            pk_indices,
+            producer,
+            in_flight: vec![],
+        })
+    }
+
+    fn encode_key(&self, row: &RowRef<'_>) -> Vec<u8> {
+        row_to_json(row, &self.schema, self.pk_indices.iter().copied())
+            .to_string()
+            .into_bytes()
+    }
+
+    fn encode_value(&self, row: &RowRef<'_>) -> Value {
+        row_to_json(row, &self.schema, 0..self.schema.len())
+    }
+
+    async fn send(&mut self, key: Option<Vec<u8>>, payload: Option<Vec<u8>>) -> Result<()> {
+        loop {
+            let mut record = FutureRecord::<[u8], [u8]>::to(&self.config.topic);
+            if let Some(key) = &key {
+                record = record.key(key);
+            }
+            if let Some(payload) = &payload {
+                record = record.payload(payload);
+            }
+            match self.producer.send_result(record).map_err(|(e, _)| e) {
+                Ok(delivery) => {
+                    self.in_flight.push(delivery);
+                    return Ok(());
+                }
+                Err(KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull)) => {
+                    // The local queue of the producer is full. Wait for the in-flight messages
+                    // before retrying.
+                    self.commit().await?;
+                }
+                Err(e) => return Err(anyhow!("failed to send to kafka sink: {}", e)),
+            }
+        }
+    }
+
+    async fn write_append_only(&mut self, chunk: StreamChunk) -> Result<()> {
+        for (op, row) in chunk.rows() {
+            if op != Op::Insert {
+                return Err(anyhow!(
+                    "append-only kafka sink got a {:?}, use the upsert or debezium format for \
+                     materialized views with updates",
+                    op
+                ));
+            }
+            let payload = self.encode_value(&row).to_string().into_bytes();
+            self.send(None, Some(payload)).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_upsert(&mut self, chunk: StreamChunk) -> Result<()> {
+        // The key of the `UpdateDelete` waiting for its `UpdateInsert`.
+        let mut update_delete_key = None;
+        for (op, row) in chunk.rows() {
+            let key = self.encode_key(&row);
+            match op {
+                Op::Insert => {
+                    let payload = self.encode_value(&row).to_string().into_bytes();
+                    self.send(Some(key), Some(payload)).await?;
+                }
+                // A tombstone deletes the key.
+                Op::Delete => self.send(Some(key), None).await?,
+                Op::UpdateDelete => update_delete_key = Some(key),
+                Op::UpdateInsert => {
+                    // The primary key changed, so delete the old one.
+                    if let Some(old_key) = update_delete_key.take().filter(|k| k != &key) {
+                        self.send(Some(old_key), None).await?;
+                    }
+                    let payload = self.encode_value(&row).to_string().into_bytes();
+                    self.send(Some(key), Some(payload)).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_debezium(&mut self, chunk: StreamChunk) -> Result<()> {
+        let ts_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let mut update_before = None;
+        for (op, row) in chunk.rows() {
+            let value = self.encode_value(&row);
+            let event = match op {
+                Op::Insert => json!({"before": null, "after": value, "op": "c", "ts_ms": ts_ms}),
+                Op::Delete => json!({"before": value, "after": null, "op": "d", "ts_ms": ts_ms}),
+                Op::UpdateDelete => {
+                    update_before = Some(value);
+                    continue;
+                }
+                Op::UpdateInsert => {
+                    json!({"before": update_before.take(), "after": value, "op": "u", "ts_ms": ts_ms})
+                }
+            };
+            let key = self.encode_key(&row);
+            self.send(Some(key), Some(event.to_string().into_bytes()))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()> {
+        match self.config.format {
+            SinkFormat::AppendOnly => self.write_append_only(chunk).await,
+            SinkFormat::Upsert => self.write_upsert(chunk).await,
+            SinkFormat::Debezium => self.write_debezium(chunk).await,
+        }
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        for delivery in std::mem::take(&mut self.in_flight) {
+            delivery
+                .await
+                .map_err(|_| anyhow!("kafka sink delivery canceled"))?
+                .map_err(|(e, _)| anyhow!("failed to deliver to kafka sink: {}", e))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sinks deliver the changes of a materialized view to external systems.
+
+pub mod kafka;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use risingwave_common::array::{RowRef, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::datum_to_json;
+use serde_json::{Map, Value};
+
+use crate::sink::kafka::{KafkaConfig, KafkaSink, KAFKA_SINK};
+
+/// The property key of the connector of a sink.
+pub const SINK_CONNECTOR_KEY: &str = "connector";
+
+#[async_trait]
+pub trait Sink {
+    /// Writes the changes in a chunk to the sink. They may be buffered until the next `commit`.
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()>;
+
+    /// Returns after all the changes written so far are delivered. It's called on every barrier,
+    /// so that once a barrier is collected, the changes before it are delivered at least once.
+    async fn commit(&mut self) -> Result<()>;
+}
+
+/// How the changes are encoded into the messages of a sink.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SinkFormat {
+    /// Only inserts are accepted, each of which is encoded as a JSON object of the row.
+    AppendOnly,
+    /// The messages are keyed by the primary key, with the row as the value of inserts and
+    /// updates, and an empty value for deletes.
+    Upsert,
+    /// Each change is encoded as a Debezium JSON envelope with `before`, `after` and `op`.
+    Debezium,
+}
+
+impl FromStr for SinkFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "append_only" => Ok(Self::AppendOnly),
+            "upsert" => Ok(Self::Upsert),
+            "debezium" => Ok(Self::Debezium),
+            _ => Err(anyhow!(
+                "sink format '{}' is not supported, expected one of append_only, upsert and debezium",
+                format
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SinkConfig {
+    Kafka(KafkaConfig),
+}
+
+impl SinkConfig {
+    pub fn from_hashmap(mut properties: HashMap<String, String>) -> Result<Self> {
+        let connector = properties
+            .remove(SINK_CONNECTOR_KEY)
+            .ok_or_else(|| anyhow!("Must specify 'connector' in WITH clause"))?;
+        match connector.to_lowercase().as_str() {
+            KAFKA_SINK => Ok(SinkConfig::Kafka(KafkaConfig::from_hashmap(properties)?)),
+            _ => Err(anyhow!("sink connector '{}' is not supported", connector)),
+        }
+    }
+}
+
+pub enum SinkImpl {
+    Kafka(Box<KafkaSink>),
+}
+
+impl SinkImpl {
+    /// Creates a sink for the changes of the given schema, whose primary key is `pk_indices`.
+    pub async fn new(config: SinkConfig, schema: Schema, pk_indices: Vec<usize>) -> Result<Self> {
+        Ok(match config {
+            SinkConfig::Kafka(config) => {
+                SinkImpl::Kafka(Box::new(KafkaSink::new(config, schema, pk_indices)?))
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for SinkImpl {
+    async fn write_batch(&mut self, chunk: StreamChunk) -> Result<()> {
+        match self {
+            SinkImpl::Kafka(sink) => sink.write_batch(chunk).await,
+        }
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        match self {
+            SinkImpl::Kafka(sink) => sink.commit().await,
+        }
+    }
+}
+
+/// Encodes the columns at `indices` of a row as a JSON object keyed by the column names.
+pub fn row_to_json(
+    row: &RowRef<'_>,
+    schema: &Schema,
+    indices: impl IntoIterator<Item = usize>,
+) -> Value {
+    let mut map = Map::new();
+    for idx in indices {
+        map.insert(
+            schema.fields[idx].name.clone(),
+            datum_to_json(&row.value_at(idx).map(|scalar| scalar.into_scalar_impl())),
+        );
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_row_to_json() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int64, "id"),
+            Field::with_name(DataType::Float64, "price"),
+            Field::with_name(DataType::Varchar, "name"),
+        ]);
+        let chunk = StreamChunk::from_pretty(
+            " I F   T
+            + 1 2.5 abc
+            + 2 .   def",
+        );
+        let rows = chunk
+            .rows()
+            .map(|(_, row)| row_to_json(&row, &schema, 0..schema.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                json!({"id": 1, "price": 2.5, "name": "abc"}),
+                json!({"id": 2, "price": null, "name": "def"}),
+            ]
+        );
+        assert_eq!(
+            chunk
+                .rows()
+                .map(|(_, row)| row_to_json(&row, &schema, [0]))
+                .collect::<Vec<_>>(),
+            vec![json!({"id": 1}), json!({"id": 2})]
+        );
+    }
+
+    #[test]
+    fn test_sink_config() {
+        let config = SinkConfig::from_hashmap(HashMap::from([
+            ("connector".to_string(), "kafka".to_string()),
+            ("kafka.brokers".to_string(), "localhost:9092".to_string()),
+            ("kafka.topic".to_string(), "test".to_string()),
+            ("format".to_string(), "upsert".to_string()),
+        ]))
+        .unwrap();
+        let SinkConfig::Kafka(config) = config;
+        assert_eq!(config.format, SinkFormat::Upsert);
+
+        assert!(SinkConfig::from_hashmap(HashMap::from([(
+            "connector".to_string(),
+            "redis".to_string()
+        )]))
+        .is_err());
+    }
+}
@@ -64,12 +64,11 @@ impl<S: StateStore> SourceStateHandler<S> {
             // TODO should be a clear Error Code
             Err(anyhow!("states require not null"))
         } else {
-            let mut write_batch = self.keyspace.state_store().start_write_batch();
-            let mut local_batch = write_batch.prefixify(&self.keyspace);
+            let mut write_batch = self.keyspace.start_write_batch();
             states.iter().for_each(|state| {
                 let value = state.encode_to_bytes();
                 // TODO(Yuanxin): Implement value meta
-                local_batch.put(state.id(), StorageValue::new_default_put(value));
+                write_batch.put(state.id(), StorageValue::new_default_put(value));
             });
             // If an error is returned, the underlying state should be rollback
             let ingest_rs = write_batch.ingest(epoch).await;
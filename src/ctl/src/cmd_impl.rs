@@ -12,4 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod cluster;
 pub mod hummock;
+pub mod meta;
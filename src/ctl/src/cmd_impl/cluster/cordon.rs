@@ -0,0 +1,33 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::util::addr::HostAddr;
+
+use crate::common::MetaServiceOpts;
+
+/// Mark the compute node at `addr` as (un)schedulable.
+pub async fn cordon(addr: &str, unschedulable: bool) -> anyhow::Result<()> {
+    let host: HostAddr = addr.parse()?;
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    meta_client
+        .cordon_worker_node(host.to_protobuf(), unschedulable)
+        .await?;
+    if unschedulable {
+        println!("cordoned {}", addr);
+    } else {
+        println!("uncordoned {}", addr);
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+
+use crate::common::MetaServiceOpts;
+
+pub async fn list_nodes() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let nodes = meta_client
+        .list_all_nodes(WorkerType::ComputeNode, true)
+        .await?;
+    for node in nodes {
+        let host = node.host.as_ref().map(HostAddr::from);
+        println!(
+            "id: {}, host: {:?}, state: {:?}, parallel units: {}, unschedulable: {}",
+            node.id,
+            host,
+            node.state(),
+            node.parallel_units.len(),
+            node.unschedulable
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+use risingwave_pb::stream_service::ShutdownRequest;
+use risingwave_rpc_client::StreamClientPool;
+
+use crate::common::MetaServiceOpts;
+
+/// Gracefully shut down the compute node at `addr`, which exits after its actors are drained.
+pub async fn shutdown(addr: &str) -> anyhow::Result<()> {
+    let host: HostAddr = addr.parse()?;
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let node = meta_client
+        .list_all_nodes(WorkerType::ComputeNode, true)
+        .await?
+        .into_iter()
+        .find(|node| node.host.as_ref().map(HostAddr::from).as_ref() == Some(&host))
+        .ok_or_else(|| anyhow::anyhow!("compute node {} not found", addr))?;
+    let mut client = StreamClientPool::new().get(&node).await?;
+    let committed_epoch = client
+        .shutdown(ShutdownRequest {})
+        .await?
+        .into_inner()
+        .committed_epoch;
+    println!(
+        "shut down {}, last committed epoch: {}",
+        addr, committed_epoch
+    );
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+use risingwave_pb::stream_service::StackTraceRequest;
+use risingwave_rpc_client::StreamClientPool;
+
+use crate::common::MetaServiceOpts;
+
+/// Dump the async call tree of the actors on each compute node.
+pub async fn stack_trace() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let nodes = meta_client
+        .list_all_nodes(WorkerType::ComputeNode, true)
+        .await?;
+    let client_pool = StreamClientPool::new();
+    for node in nodes {
+        let host = node.host.as_ref().map(HostAddr::from);
+        let mut client = client_pool.get(&node).await?;
+        let actor_traces = client
+            .stack_trace(StackTraceRequest {})
+            .await?
+            .into_inner()
+            .actor_traces;
+        println!("--- compute node {}, host: {:?} ---", node.id, host);
+        if actor_traces.is_empty() {
+            println!("no actor traces, is `async_stack_trace` enabled in the streaming config?");
+        }
+        for trace in actor_traces
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+            .values()
+        {
+            print!("{}", trace);
+        }
+    }
+    Ok(())
+}
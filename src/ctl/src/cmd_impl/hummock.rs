@@ -16,3 +16,7 @@ mod list_version;
 pub use list_version::*;
 mod list_kv;
 pub use list_kv::*;
+mod list_ssts;
+pub use list_ssts::*;
+mod verify_ssts;
+pub use verify_ssts::*;
@@ -0,0 +1,34 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_rpc_client::HummockMetaClient;
+
+use crate::common::MetaServiceOpts;
+
+pub async fn list_ssts() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let version = meta_client.pin_version(u64::MAX).await?;
+    println!("version: {}", version.id);
+    for level in &version.levels {
+        for sst in &level.table_infos {
+            println!(
+                "level: {}, sst_id: {}, file_size: {}",
+                level.level_idx, sst.id, sst.file_size
+            );
+        }
+    }
+    meta_client.unpin_version(&[version.id]).await?;
+    Ok(())
+}
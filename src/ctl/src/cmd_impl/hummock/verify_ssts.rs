@@ -0,0 +1,53 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::HummockServiceOpts;
+
+/// Verifies the checksums of all SSTs in the latest Hummock version, or only the given ones.
+pub async fn verify_ssts(sst_ids: &[u64]) -> anyhow::Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let hummock = hummock_opts.create_hummock_store().await?;
+    let sst_ids = if sst_ids.is_empty() {
+        let version = hummock.inner().local_version_manager().get_pinned_version();
+        println!("version: {}", version.id());
+        version
+            .levels()
+            .iter()
+            .flat_map(|level| level.table_infos.iter().map(|sst| sst.id))
+            .collect()
+    } else {
+        sst_ids.to_vec()
+    };
+
+    let sstable_store = hummock.inner().sstable_store();
+    let mut corrupted_count = 0;
+    for sst_id in &sst_ids {
+        match sstable_store.verify_sstable(*sst_id).await {
+            Ok(()) => println!("sst_id: {}, ok", sst_id),
+            Err(e) => {
+                corrupted_count += 1;
+                println!("sst_id: {}, {}", sst_id, e);
+            }
+        }
+    }
+    println!(
+        "verified {} SSTs, {} corrupted",
+        sst_ids.len(),
+        corrupted_count
+    );
+    if corrupted_count > 0 {
+        anyhow::bail!("found {} corrupted SSTs", corrupted_count);
+    }
+    Ok(())
+}
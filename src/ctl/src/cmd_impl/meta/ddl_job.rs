@@ -0,0 +1,41 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::MetaServiceOpts;
+
+pub async fn list_ddl_jobs() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    for job in meta_client.list_ddl_jobs().await? {
+        print!(
+            "job_id: {}, name: {}, status: {:?}",
+            job.job_id,
+            job.name,
+            job.status()
+        );
+        if !job.error.is_empty() {
+            print!(", error: {}", job.error);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+pub async fn cancel_ddl_job(job_id: u32) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    meta_client.cancel_ddl_job(job_id).await?;
+    println!("cancelled ddl job {}", job_id);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::MetaServiceOpts;
+
+pub async fn list_jobs() -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let table_fragments = meta_client.list_table_fragments().await?;
+    for table_fragments in table_fragments {
+        let actor_count: usize = table_fragments
+            .fragments
+            .values()
+            .map(|fragment| fragment.actors.len())
+            .sum();
+        println!(
+            "table_id: {}, fragments: {}, actors: {}",
+            table_fragments.table_id,
+            table_fragments.fragments.len(),
+            actor_count
+        );
+    }
+    Ok(())
+}
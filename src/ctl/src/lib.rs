@@ -31,6 +31,12 @@ enum Commands {
     /// Commands for Hummock
     #[clap(subcommand)]
     Hummock(HummockCommands),
+    /// Commands for streaming jobs and barriers on meta node
+    #[clap(subcommand)]
+    Meta(MetaCommands),
+    /// Commands for cluster membership
+    #[clap(subcommand)]
+    Cluster(ClusterCommands),
 }
 
 #[derive(Subcommand)]
@@ -45,6 +51,41 @@ enum HummockCommands {
         #[clap(short, long = "table-id", default_value_t = u32::MAX)]
         tableid: u32,
     },
+    /// list all SSTs in the latest Hummock version
+    ListSsts,
+    /// verify the checksums of the given SSTs, or all SSTs in the latest Hummock version if none
+    /// is given
+    VerifySsts { sst_ids: Vec<u64> },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// list all streaming jobs (materialized views and sources)
+    ListJobs,
+    /// pause all streaming sources
+    Pause,
+    /// resume all streaming sources
+    Resume,
+    /// trigger a checkpoint and wait for it to be collected
+    Checkpoint,
+    /// list the ddl jobs creating streaming jobs in progress, and the ones finished recently
+    ListDdlJobs,
+    /// cancel a ddl job in progress, e.g. `cancel-ddl-job 1001`
+    CancelDdlJob { job_id: u32 },
+}
+
+#[derive(Subcommand)]
+enum ClusterCommands {
+    /// list all compute nodes
+    ListNodes,
+    /// mark a compute node as unschedulable, e.g. `cordon 127.0.0.1:5688`
+    Cordon { addr: String },
+    /// mark a compute node as schedulable again
+    Uncordon { addr: String },
+    /// drain the actors on a compute node and shut it down, e.g., before restarting it
+    Shutdown { addr: String },
+    /// dump what the actors on each compute node are awaiting, e.g., to find the stuck ones
+    StackTrace,
 }
 
 pub async fn start(opts: CliOpts) {
@@ -55,5 +96,34 @@ pub async fn start(opts: CliOpts) {
         Commands::Hummock(HummockCommands::ListKv { epoch, tableid }) => {
             cmd_impl::hummock::list_kv(*epoch, *tableid).await.unwrap()
         }
+        Commands::Hummock(HummockCommands::ListSsts) => {
+            cmd_impl::hummock::list_ssts().await.unwrap()
+        }
+        Commands::Hummock(HummockCommands::VerifySsts { sst_ids }) => {
+            cmd_impl::hummock::verify_ssts(sst_ids).await.unwrap()
+        }
+        Commands::Meta(MetaCommands::ListJobs) => cmd_impl::meta::list_jobs().await.unwrap(),
+        Commands::Meta(MetaCommands::Pause) => cmd_impl::meta::pause().await.unwrap(),
+        Commands::Meta(MetaCommands::Resume) => cmd_impl::meta::resume().await.unwrap(),
+        Commands::Meta(MetaCommands::Checkpoint) => cmd_impl::meta::checkpoint().await.unwrap(),
+        Commands::Meta(MetaCommands::ListDdlJobs) => cmd_impl::meta::list_ddl_jobs().await.unwrap(),
+        Commands::Meta(MetaCommands::CancelDdlJob { job_id }) => {
+            cmd_impl::meta::cancel_ddl_job(*job_id).await.unwrap()
+        }
+        Commands::Cluster(ClusterCommands::ListNodes) => {
+            cmd_impl::cluster::list_nodes().await.unwrap()
+        }
+        Commands::Cluster(ClusterCommands::Cordon { addr }) => {
+            cmd_impl::cluster::cordon(addr, true).await.unwrap()
+        }
+        Commands::Cluster(ClusterCommands::Uncordon { addr }) => {
+            cmd_impl::cluster::cordon(addr, false).await.unwrap()
+        }
+        Commands::Cluster(ClusterCommands::Shutdown { addr }) => {
+            cmd_impl::cluster::shutdown(addr).await.unwrap()
+        }
+        Commands::Cluster(ClusterCommands::StackTrace) => {
+            cmd_impl::cluster::stack_trace().await.unwrap()
+        }
     }
 }
@@ -28,6 +28,8 @@ pub enum AggKind {
     Avg,
     StringAgg,
     SingleValue,
+    StddevSamp,
+    VarPop,
 }
 
 impl std::fmt::Display for AggKind {
@@ -41,6 +43,8 @@ impl std::fmt::Display for AggKind {
             AggKind::Avg => write!(f, "avg"),
             AggKind::StringAgg => write!(f, "string_agg"),
             AggKind::SingleValue => write!(f, "single_value"),
+            AggKind::StddevSamp => write!(f, "stddev_samp"),
+            AggKind::VarPop => write!(f, "var_pop"),
         }
     }
 }
@@ -57,6 +61,8 @@ impl TryFrom<Type> for AggKind {
             Type::Count => Ok(AggKind::Count),
             Type::StringAgg => Ok(AggKind::StringAgg),
             Type::SingleValue => Ok(AggKind::SingleValue),
+            Type::StddevSamp => Ok(AggKind::StddevSamp),
+            Type::VarPop => Ok(AggKind::VarPop),
             _ => Err(ErrorCode::InternalError("Unrecognized agg.".into()).into()),
         }
     }
@@ -72,6 +78,8 @@ impl AggKind {
             Self::Count => Type::Count,
             Self::StringAgg => Type::StringAgg,
             Self::SingleValue => Type::SingleValue,
+            Self::StddevSamp => Type::StddevSamp,
+            Self::VarPop => Type::VarPop,
             Self::RowCount => {
                 panic!("cannot convert RowCount to prost, TODO: remove RowCount from AggKind")
             }
@@ -19,6 +19,10 @@ use risingwave_common::array::{
 use risingwave_common::types::*;
 use risingwave_pb::expr::expr_node::Type;
 
+use crate::expr::expr_binary_primitive::{
+    is_primitive_kernel_type, new_primitive_arithmetic_expr, new_primitive_cmp_expr,
+};
+use crate::expr::expr_binary_utf8::BinaryUtf8PredicateExpression;
 use crate::expr::template::BinaryExpression;
 use crate::expr::BoxedExpression;
 use crate::for_all_cmp_variants;
@@ -113,14 +117,12 @@ macro_rules! gen_cmp_impl {
 macro_rules! gen_binary_expr_cmp {
     ($macro:ident, $general_f:ident, $op:ident, $l:expr, $r:expr, $ret:expr) => {
         match ($l.return_type(), $r.return_type()) {
-            (DataType::Varchar, DataType::Varchar) => {
-                Box::new(BinaryExpression::<Utf8Array, Utf8Array, BoolArray, _>::new(
-                    $l,
-                    $r,
-                    $ret,
-                    gen_str_cmp($op),
-                ))
-            }
+            (DataType::Varchar, DataType::Varchar) => Box::new(BinaryUtf8PredicateExpression::new(
+                $l,
+                $r,
+                $ret,
+                gen_str_cmp($op),
+            )),
             (DataType::Struct { fields: _ }, DataType::Struct { fields: _ }) => Box::new(
                 BinaryExpression::<StructArray, StructArray, BoolArray, _>::new(
                     $l,
@@ -137,6 +139,9 @@ macro_rules! gen_binary_expr_cmp {
                     gen_list_cmp($op),
                 ))
             }
+            (l_type, r_type) if l_type == r_type && is_primitive_kernel_type(&l_type) => {
+                new_primitive_cmp_expr($l, $r, $ret, $op)
+            }
             _ => {
                 for_all_cmp_variants! {$macro, $l, $r, $ret, $general_f}
             }
@@ -254,6 +259,13 @@ pub fn new_binary_expr(
         Type::LessThanOrEqual => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_le, LE, l, r, ret}
         }
+        Type::Add | Type::Subtract | Type::Multiply
+            if l.return_type() == r.return_type()
+                && ret == l.return_type()
+                && is_primitive_kernel_type(&ret) =>
+        {
+            new_primitive_arithmetic_expr(expr_type, l, r, ret)
+        }
         Type::Add => {
             gen_binary_expr_atm! {
                 gen_atm_impl,
@@ -294,6 +306,12 @@ pub fn new_binary_expr(
                     { int16, interval, interval, int_interval_mul },
                     { int32, interval, interval, int_interval_mul },
                     { int64, interval, interval, int_interval_mul },
+                    { interval, float32, interval, interval_float_mul },
+                    { interval, float64, interval, interval_float_mul },
+                    { interval, decimal, interval, interval_float_mul },
+                    { float32, interval, interval, float_interval_mul },
+                    { float64, interval, interval, float_interval_mul },
+                    { decimal, interval, interval, float_interval_mul },
                 },
             }
         }
@@ -303,6 +321,12 @@ pub fn new_binary_expr(
                 l, r, ret,
                 general_div,
                 {
+                    { interval, int16, interval, interval_float_div },
+                    { interval, int32, interval, interval_float_div },
+                    { interval, int64, interval, interval_float_div },
+                    { interval, float32, interval, interval_float_div },
+                    { interval, float64, interval, interval_float_div },
+                    { interval, decimal, interval, interval_float_div },
                 },
             }
         }
@@ -371,7 +395,7 @@ pub fn new_like_default(
     expr_ia2: BoxedExpression,
     return_type: DataType,
 ) -> BoxedExpression {
-    Box::new(BinaryExpression::<Utf8Array, Utf8Array, BoolArray, _>::new(
+    Box::new(BinaryUtf8PredicateExpression::new(
         expr_ia1,
         expr_ia2,
         return_type,
@@ -0,0 +1,375 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparisons and arithmetics on two primitive arrays of the same type, evaluated by the kernels
+//! in [`crate::vector_op::primitive_kernel`] instead of row by row.
+
+use std::fmt;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayImpl, ArrayRef, BoolArray, DataChunk, PrimitiveArray,
+    PrimitiveArrayItemType, Row,
+};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::ErrorCode::{InternalError, NumericValueOutOfRange};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, OrderedF32, OrderedF64, Scalar};
+use risingwave_pb::expr::expr_node::Type;
+
+use crate::expr::{BoxedExpression, Expression};
+use crate::vector_op::cmp::Comparison;
+use crate::vector_op::primitive_kernel::{
+    arithmetic_slices, compare_slices, gen_float_arithmetic, gen_int_arithmetic, gen_primitive_cmp,
+};
+
+/// Whether the comparisons or arithmetics between two values of `data_type` can be evaluated by
+/// the primitive kernels.
+pub fn is_primitive_kernel_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64
+    )
+}
+
+/// Creates the comparison between two expressions of the same type, which must satisfy
+/// [`is_primitive_kernel_type`].
+pub fn new_primitive_cmp_expr(
+    l: BoxedExpression,
+    r: BoxedExpression,
+    ret: DataType,
+    op: Comparison,
+) -> BoxedExpression {
+    match l.return_type() {
+        DataType::Int16 => Box::new(PrimitiveCmpExpression::<i16>::new(l, r, ret, op)),
+        DataType::Int32 => Box::new(PrimitiveCmpExpression::<i32>::new(l, r, ret, op)),
+        DataType::Int64 => Box::new(PrimitiveCmpExpression::<i64>::new(l, r, ret, op)),
+        DataType::Float32 => Box::new(PrimitiveCmpExpression::<OrderedF32>::new(l, r, ret, op)),
+        DataType::Float64 => Box::new(PrimitiveCmpExpression::<OrderedF64>::new(l, r, ret, op)),
+        other => unreachable!("{:?} is not supported by the primitive kernels", other),
+    }
+}
+
+/// Creates the addition, subtraction or multiplication between two expressions of the same type,
+/// which must satisfy [`is_primitive_kernel_type`].
+pub fn new_primitive_arithmetic_expr(
+    op: Type,
+    l: BoxedExpression,
+    r: BoxedExpression,
+    ret: DataType,
+) -> BoxedExpression {
+    match l.return_type() {
+        DataType::Int16 => Box::new(PrimitiveArithmeticExpression::new(
+            l,
+            r,
+            ret,
+            gen_int_arithmetic::<i16>(op),
+        )),
+        DataType::Int32 => Box::new(PrimitiveArithmeticExpression::new(
+            l,
+            r,
+            ret,
+            gen_int_arithmetic::<i32>(op),
+        )),
+        DataType::Int64 => Box::new(PrimitiveArithmeticExpression::new(
+            l,
+            r,
+            ret,
+            gen_int_arithmetic::<i64>(op),
+        )),
+        DataType::Float32 => Box::new(PrimitiveArithmeticExpression::new(
+            l,
+            r,
+            ret,
+            gen_float_arithmetic::<OrderedF32>(op),
+        )),
+        DataType::Float64 => Box::new(PrimitiveArithmeticExpression::new(
+            l,
+            r,
+            ret,
+            gen_float_arithmetic::<OrderedF64>(op),
+        )),
+        other => unreachable!("{:?} is not supported by the primitive kernels", other),
+    }
+}
+
+/// Returns the visibility of the rows of the input arrays, or `None` if the inputs have been
+/// compacted, e.g., by `InputRefExpression`.
+fn input_visibility<'a>(data_chunk: &'a DataChunk, input_len: usize) -> Option<&'a Bitmap> {
+    data_chunk
+        .get_visibility_ref()
+        .filter(|visibility| visibility.len() == input_len)
+}
+
+/// Keeps the visible rows of `array` only, as the outputs of expressions are compacted.
+fn compact<A: Array>(array: A, visibility: Option<&Bitmap>) -> Result<A> {
+    match visibility {
+        Some(visibility) => {
+            let mut builder = A::Builder::new(visibility.num_high_bits())?;
+            for (value, visible) in array.iter().zip_eq(visibility.iter()) {
+                if visible {
+                    builder.append(value)?;
+                }
+            }
+            builder.finish()
+        }
+        None => Ok(array),
+    }
+}
+
+fn as_primitive_array<T: PrimitiveArrayItemType>(array: &ArrayImpl) -> Result<&PrimitiveArray<T>> {
+    T::try_into_array_ref(array).ok_or_else(|| {
+        InternalError(format!(
+            "expect {} array, got {}",
+            std::any::type_name::<T>(),
+            array.get_ident()
+        ))
+        .into()
+    })
+}
+
+/// Evaluates the inputs of a binary expression on the values of type `T` of a row, returning
+/// `None` if either of them is null.
+fn eval_row_inputs<T: PrimitiveArrayItemType>(
+    expr_ia1: &BoxedExpression,
+    expr_ia2: &BoxedExpression,
+    row: &Row,
+) -> Result<Option<(T, T)>> {
+    let datum_ia1 = expr_ia1.eval_row(row)?;
+    let datum_ia2 = expr_ia2.eval_row(row)?;
+    match (datum_ia1, datum_ia2) {
+        (Some(v_ia1), Some(v_ia2)) => Ok(Some((T::try_from(v_ia1)?, T::try_from(v_ia2)?))),
+        _ => Ok(None),
+    }
+}
+
+/// Compares two primitive arrays of type `T`, producing the bitmap of the results directly.
+pub struct PrimitiveCmpExpression<T> {
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+    op: Comparison,
+    func: fn(T, T) -> bool,
+}
+
+impl<T> fmt::Debug for PrimitiveCmpExpression<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrimitiveCmpExpression")
+            .field("type", &std::any::type_name::<T>())
+            .field("op", &self.op)
+            .field("expr_ia1", &self.expr_ia1)
+            .field("expr_ia2", &self.expr_ia2)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<T: PrimitiveArrayItemType> PrimitiveCmpExpression<T> {
+    pub fn new(
+        expr_ia1: BoxedExpression,
+        expr_ia2: BoxedExpression,
+        return_type: DataType,
+        op: Comparison,
+    ) -> Self {
+        Self {
+            expr_ia1,
+            expr_ia2,
+            return_type,
+            op,
+            func: gen_primitive_cmp(op),
+        }
+    }
+}
+
+impl<T: PrimitiveArrayItemType> Expression for PrimitiveCmpExpression<T> {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, data_chunk: &DataChunk) -> Result<ArrayRef> {
+        let ret_ia1 = self.expr_ia1.eval(data_chunk)?;
+        let ret_ia2 = self.expr_ia2.eval(data_chunk)?;
+        let arr_ia1 = as_primitive_array::<T>(&ret_ia1)?;
+        let arr_ia2 = as_primitive_array::<T>(&ret_ia2)?;
+
+        let values = compare_slices(arr_ia1.as_slice(), arr_ia2.as_slice(), self.func);
+        let bitmap = (arr_ia1.null_bitmap() & arr_ia2.null_bitmap())?;
+        let output_array = compact(
+            BoolArray::new(bitmap, values),
+            input_visibility(data_chunk, arr_ia1.len()),
+        )?;
+        Ok(Arc::new(output_array.into()))
+    }
+
+    fn eval_row(&self, row: &Row) -> Result<Datum> {
+        Ok(eval_row_inputs::<T>(&self.expr_ia1, &self.expr_ia2, row)?
+            .map(|(v_ia1, v_ia2)| (self.func)(v_ia1, v_ia2).to_scalar_value()))
+    }
+}
+
+/// Adds, subtracts or multiplies two primitive arrays of type `T`. `func` returns the wrapped
+/// result and whether it overflowed.
+pub struct PrimitiveArithmeticExpression<T> {
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+    func: fn(T, T) -> (T, bool),
+}
+
+impl<T> fmt::Debug for PrimitiveArithmeticExpression<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrimitiveArithmeticExpression")
+            .field("type", &std::any::type_name::<T>())
+            .field("expr_ia1", &self.expr_ia1)
+            .field("expr_ia2", &self.expr_ia2)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<T: PrimitiveArrayItemType> PrimitiveArithmeticExpression<T> {
+    pub fn new(
+        expr_ia1: BoxedExpression,
+        expr_ia2: BoxedExpression,
+        return_type: DataType,
+        func: fn(T, T) -> (T, bool),
+    ) -> Self {
+        Self {
+            expr_ia1,
+            expr_ia2,
+            return_type,
+            func,
+        }
+    }
+}
+
+impl<T: PrimitiveArrayItemType> Expression for PrimitiveArithmeticExpression<T> {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, data_chunk: &DataChunk) -> Result<ArrayRef> {
+        let ret_ia1 = self.expr_ia1.eval(data_chunk)?;
+        let ret_ia2 = self.expr_ia2.eval(data_chunk)?;
+        let arr_ia1 = as_primitive_array::<T>(&ret_ia1)?;
+        let arr_ia2 = as_primitive_array::<T>(&ret_ia2)?;
+
+        let bitmap = (arr_ia1.null_bitmap() & arr_ia2.null_bitmap())?;
+        let visibility = input_visibility(data_chunk, arr_ia1.len());
+        // Overflows on invisible rows are not errors.
+        let valid = match visibility {
+            Some(visibility) => (&bitmap & visibility)?,
+            None => bitmap.clone(),
+        };
+        let values = arithmetic_slices(arr_ia1.as_slice(), arr_ia2.as_slice(), &valid, self.func)?;
+        let output_array = compact(PrimitiveArray::new(bitmap, values), visibility)?;
+        Ok(Arc::new(T::erase_array_type(output_array)))
+    }
+
+    fn eval_row(&self, row: &Row) -> Result<Datum> {
+        match eval_row_inputs::<T>(&self.expr_ia1, &self.expr_ia2, row)? {
+            Some((v_ia1, v_ia2)) => match (self.func)(v_ia1, v_ia2) {
+                (value, false) => Ok(Some(value.to_scalar_value())),
+                (_, true) => Err(RwError::from(NumericValueOutOfRange)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::{I32Array, I64Array};
+
+    use super::*;
+    use crate::expr::InputRefExpression;
+    use crate::vector_op::cmp::general_lt;
+
+    fn new_input_chunk(data_type: &DataType) -> (Vec<Option<i64>>, Vec<Option<i64>>, DataChunk) {
+        let lhs = (0..100)
+            .map(|i| if i % 7 == 0 { None } else { Some(i) })
+            .collect::<Vec<_>>();
+        let rhs = (0..100)
+            .map(|i| if i % 11 == 0 { None } else { Some(100 - i) })
+            .collect::<Vec<_>>();
+        let columns = match data_type {
+            DataType::Int32 => [&lhs, &rhs]
+                .iter()
+                .map(|values| {
+                    let values = values
+                        .iter()
+                        .map(|v| v.map(|v| v as i32))
+                        .collect::<Vec<_>>();
+                    Column::new(Arc::new(I32Array::from_slice(&values).unwrap().into()))
+                })
+                .collect(),
+            _ => [&lhs, &rhs]
+                .iter()
+                .map(|values| Column::new(Arc::new(I64Array::from_slice(values).unwrap().into())))
+                .collect(),
+        };
+        (lhs, rhs, DataChunk::new(columns, 100))
+    }
+
+    #[test]
+    fn test_primitive_cmp() {
+        let (lhs, rhs, chunk) = new_input_chunk(&DataType::Int32);
+        let expr = new_primitive_cmp_expr(
+            InputRefExpression::new(DataType::Int32, 0).boxed(),
+            InputRefExpression::new(DataType::Int32, 1).boxed(),
+            DataType::Boolean,
+            Comparison::Lt,
+        );
+        let result = expr.eval(&chunk).unwrap();
+        let result: &BoolArray = result.as_ref().into();
+        assert_eq!(result.len(), 100);
+        for ((l, r), result) in lhs.iter().zip_eq(rhs.iter()).zip_eq(result.iter()) {
+            let expected = l
+                .zip(*r)
+                .map(|(l, r)| general_lt::<i64, i64, i64>(l, r).unwrap());
+            assert_eq!(result, expected);
+        }
+
+        let row = Row::new(vec![Some(1i32.into()), Some(2i32.into())]);
+        assert_eq!(expr.eval_row(&row).unwrap(), Some(true.into()));
+
+        // Only the visible rows are returned.
+        let visibility = Bitmap::try_from((0..100).map(|i| i % 2 == 0).collect_vec()).unwrap();
+        let result = expr.eval(&chunk.with_visibility(visibility)).unwrap();
+        assert_eq!(result.len(), 50);
+    }
+
+    #[test]
+    fn test_primitive_arithmetic() {
+        let (lhs, rhs, chunk) = new_input_chunk(&DataType::Int64);
+        let expr = new_primitive_arithmetic_expr(
+            Type::Multiply,
+            InputRefExpression::new(DataType::Int64, 0).boxed(),
+            InputRefExpression::new(DataType::Int64, 1).boxed(),
+            DataType::Int64,
+        );
+        let result = expr.eval(&chunk).unwrap();
+        let result: &I64Array = result.as_ref().into();
+        for ((l, r), result) in lhs.iter().zip_eq(rhs.iter()).zip_eq(result.iter()) {
+            assert_eq!(result, l.zip(*r).map(|(l, r)| l * r));
+        }
+
+        let row = Row::new(vec![Some(i64::MAX.into()), Some(2i64.into())]);
+        assert!(expr.eval_row(&row).is_err());
+        let row = Row::new(vec![None, Some(2i64.into())]);
+        assert_eq!(expr.eval_row(&row).unwrap(), None);
+    }
+}
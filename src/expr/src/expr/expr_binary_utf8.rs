@@ -0,0 +1,183 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Predicates on two strings, such as comparisons and `LIKE`, which work on the dictionary codes
+//! when the strings are dictionary-encoded.
+
+use std::fmt;
+use std::sync::Arc;
+
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayRef, BoolArrayBuilder, DataChunk, Row, Utf8Array,
+};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+
+use crate::expr::{BoxedExpression, Expression};
+
+/// Evaluates `func` on two strings. When both inputs are dictionary-encoded, e.g., a column of low
+/// cardinality compared with a literal, `func` is evaluated once for each pair of dictionary values
+/// that occurs, and the results are looked up by the codes of the rows.
+pub struct BinaryUtf8PredicateExpression<F> {
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+    func: F,
+}
+
+impl<F> fmt::Debug for BinaryUtf8PredicateExpression<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BinaryUtf8PredicateExpression")
+            .field("func", &std::any::type_name::<F>())
+            .field("expr_ia1", &self.expr_ia1)
+            .field("expr_ia2", &self.expr_ia2)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<F> BinaryUtf8PredicateExpression<F>
+where
+    F: Fn(&str, &str) -> Result<bool> + Sync + Send,
+{
+    pub fn new(
+        expr_ia1: BoxedExpression,
+        expr_ia2: BoxedExpression,
+        return_type: DataType,
+        func: F,
+    ) -> Self {
+        Self {
+            expr_ia1,
+            expr_ia2,
+            return_type,
+            func,
+        }
+    }
+}
+
+impl<F> Expression for BinaryUtf8PredicateExpression<F>
+where
+    F: Fn(&str, &str) -> Result<bool> + Sync + Send,
+{
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, data_chunk: &DataChunk) -> Result<ArrayRef> {
+        let ret_ia1 = self.expr_ia1.eval(data_chunk)?;
+        let ret_ia2 = self.expr_ia2.eval(data_chunk)?;
+        let arr_ia1: &Utf8Array = ret_ia1.as_ref().into();
+        let arr_ia2: &Utf8Array = ret_ia2.as_ref().into();
+
+        // The results of the pairs of dictionary values, indexed by `code_ia1 * len_ia2 +
+        // code_ia2`. Only used if there are fewer pairs than rows.
+        let len_ia2 = arr_ia2.dictionary_len();
+        let mut codes = match (arr_ia1.codes(), arr_ia2.codes()) {
+            (Some(codes_ia1), Some(codes_ia2))
+                if arr_ia1.dictionary_len() * len_ia2 <= data_chunk.capacity() =>
+            {
+                let results = vec![None; arr_ia1.dictionary_len() * len_ia2];
+                Some((codes_ia1, codes_ia2, results))
+            }
+            _ => None,
+        };
+
+        let visibility = data_chunk.get_visibility_ref();
+        let mut output_array = BoolArrayBuilder::new(data_chunk.capacity())?;
+        for idx in 0..data_chunk.capacity() {
+            if let Some(visibility) = visibility {
+                if !visibility.is_set(idx)? {
+                    continue;
+                }
+            }
+            let output = match (arr_ia1.value_at(idx), arr_ia2.value_at(idx)) {
+                (Some(v_ia1), Some(v_ia2)) => Some(match &mut codes {
+                    Some((codes_ia1, codes_ia2, results)) => {
+                        let pair = codes_ia1[idx] as usize * len_ia2 + codes_ia2[idx] as usize;
+                        match results[pair] {
+                            Some(result) => result,
+                            None => *results[pair].insert((self.func)(v_ia1, v_ia2)?),
+                        }
+                    }
+                    None => (self.func)(v_ia1, v_ia2)?,
+                }),
+                _ => None,
+            };
+            output_array.append(output)?;
+        }
+        Ok(Arc::new(output_array.finish()?.into()))
+    }
+
+    fn eval_row(&self, row: &Row) -> Result<Datum> {
+        let datum_ia1 = self.expr_ia1.eval_row(row)?;
+        let datum_ia2 = self.expr_ia2.eval_row(row)?;
+        match (datum_ia1, datum_ia2) {
+            (Some(ScalarImpl::Utf8(v_ia1)), Some(ScalarImpl::Utf8(v_ia2))) => {
+                Ok(Some(ScalarImpl::Bool((self.func)(&v_ia1, &v_ia2)?)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::BoolArray;
+    use risingwave_common::types::Scalar;
+
+    use super::*;
+    use crate::expr::{InputRefExpression, LiteralExpression};
+    use crate::vector_op::cmp::{gen_str_cmp, Comparison};
+    use crate::vector_op::like::like_default;
+
+    #[test]
+    fn test_dictionary_encoded_predicate() {
+        let values = (0..100)
+            .map(|i| match i % 4 {
+                0 => None,
+                1 => Some("apple"),
+                2 => Some("banana"),
+                _ => Some("cherry"),
+            })
+            .collect::<Vec<_>>();
+        let array = Utf8Array::from_slice(&values).unwrap();
+        assert!(array.codes().is_some());
+        let chunk = DataChunk::new(vec![Column::new(Arc::new(array.into()))], values.len());
+
+        let new_expr = |literal: &str, func: fn(&str, &str) -> Result<bool>| {
+            BinaryUtf8PredicateExpression::new(
+                InputRefExpression::new(DataType::Varchar, 0).boxed(),
+                LiteralExpression::new(
+                    DataType::Varchar,
+                    Some(literal.to_string().to_scalar_value()),
+                )
+                .boxed(),
+                DataType::Boolean,
+                func,
+            )
+        };
+        let check = |expr: BinaryUtf8PredicateExpression<_>, expected: fn(&str) -> bool| {
+            let result = expr.eval(&chunk).unwrap();
+            let result: &BoolArray = result.as_ref().into();
+            for (value, result) in values.iter().zip(result.iter()) {
+                assert_eq!((*value).map(expected), result);
+            }
+        };
+        check(new_expr("banana", gen_str_cmp(Comparison::Lt)), |v| {
+            v < "banana"
+        });
+        check(new_expr("%an%", like_default), |v| v.contains("an"));
+    }
+}
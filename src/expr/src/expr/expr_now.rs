@@ -0,0 +1,213 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use risingwave_common::array::{ArrayRef, DataChunk, Row};
+use risingwave_common::ensure;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::epoch::{Epoch, INVALID_EPOCH};
+use risingwave_pb::expr::expr_node::Type;
+use risingwave_pb::expr::ExprNode;
+use tokio_stream::Stream;
+
+use crate::expr::{Expression, LiteralExpression};
+
+thread_local! {
+    /// The epoch `now()` is evaluated against on this thread. See [`with_epoch`].
+    static EVAL_EPOCH: Cell<Option<Epoch>> = Cell::new(None);
+}
+
+/// Restores the previous evaluation epoch on drop, so that nested or panicking evaluations
+/// don't leak the epoch to later ones.
+struct EpochGuard(Option<Epoch>);
+
+impl Drop for EpochGuard {
+    fn drop(&mut self) {
+        EVAL_EPOCH.with(|e| e.set(self.0));
+    }
+}
+
+/// Evaluates `f` with `now()` fixed to the physical time of `epoch`.
+///
+/// This makes `now()` a constant within an epoch: all rows of a batch query see the time of its
+/// snapshot, and all rows between two barriers of a streaming job see the time of the barrier that
+/// started the epoch. So the results are reproducible when the data of an epoch is replayed after
+/// recovery.
+pub fn with_epoch<R>(epoch: u64, f: impl FnOnce() -> R) -> R {
+    let epoch = (epoch != INVALID_EPOCH).then(|| Epoch(epoch));
+    let _guard = EpochGuard(EVAL_EPOCH.with(|e| e.replace(epoch)));
+    f()
+}
+
+/// A stream that polls `inner` inside [`with_epoch`], so that every expression evaluated by the
+/// executor producing `inner`, whatever it is, sees the epoch. `next_epoch` is called on each item
+/// and returns the epoch of the items after it, if the item starts a new epoch.
+///
+/// Executors are wrapped with it where they're built, instead of each of them passing the epoch
+/// to its expressions.
+pub struct WithEpoch<S, F> {
+    inner: S,
+    epoch: u64,
+    next_epoch: F,
+}
+
+impl<S, F> WithEpoch<S, F>
+where
+    S: Stream + Unpin,
+    F: FnMut(&S::Item) -> Option<u64> + Unpin,
+{
+    pub fn new(epoch: u64, inner: S, next_epoch: F) -> Self {
+        Self {
+            inner,
+            epoch,
+            next_epoch,
+        }
+    }
+}
+
+impl<S, F> Stream for WithEpoch<S, F>
+where
+    S: Stream + Unpin,
+    F: FnMut(&S::Item) -> Option<u64> + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let inner = &mut this.inner;
+        let poll = with_epoch(this.epoch, || Pin::new(inner).poll_next(cx));
+        if let Poll::Ready(Some(item)) = &poll {
+            if let Some(epoch) = (this.next_epoch)(item) {
+                this.epoch = epoch;
+            }
+        }
+        poll
+    }
+}
+
+/// `now()`, or `proctime()`, returns the current time as a timestamp. It falls back to the wall
+/// clock if it's evaluated outside of [`with_epoch`].
+#[derive(Debug)]
+pub struct NowExpression {
+    return_type: DataType,
+}
+
+impl NowExpression {
+    pub fn new() -> Self {
+        Self {
+            return_type: DataType::Timestamp,
+        }
+    }
+
+    fn now(&self) -> Datum {
+        let time = EVAL_EPOCH
+            .with(Cell::get)
+            .map_or_else(SystemTime::now, |epoch| epoch.as_system_time());
+        let duration = time
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock set earlier than unix epoch!");
+        let timestamp = NaiveDateTimeWrapper::with_secs_nsecs(
+            duration.as_secs() as i64,
+            duration.subsec_nanos(),
+        )
+        .unwrap();
+        Some(ScalarImpl::NaiveDateTime(timestamp))
+    }
+}
+
+impl Default for NowExpression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Expression for NowExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        LiteralExpression::new(self.return_type(), self.now()).eval(input)
+    }
+
+    fn eval_row(&self, _input: &Row) -> Result<Datum> {
+        Ok(self.now())
+    }
+}
+
+impl<'a> TryFrom<&'a ExprNode> for NowExpression {
+    type Error = RwError;
+
+    fn try_from(prost: &'a ExprNode) -> Result<Self> {
+        ensure!(prost.get_expr_type()? == Type::Now);
+        ensure!(DataType::from(prost.get_return_type()?) == DataType::Timestamp);
+        Ok(Self::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, ArrayImpl};
+
+    use super::*;
+
+    #[test]
+    fn test_now_with_epoch() {
+        let epoch = Epoch::now();
+        let expected = epoch.as_system_time();
+        let chunk = DataChunk::new_dummy(3);
+        let expr = NowExpression::new();
+
+        let array = with_epoch(epoch.0, || expr.eval(&chunk)).unwrap();
+        let array = match array.as_ref() {
+            ArrayImpl::NaiveDateTime(array) => array,
+            _ => panic!("expect a timestamp array"),
+        };
+        let values = array.iter().collect::<Vec<_>>();
+        assert_eq!(values.len(), 3);
+        assert!(values.iter().all(|v| *v == values[0]));
+
+        let expected = expected.duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(
+            values[0].unwrap().0.timestamp_millis(),
+            expected.as_millis() as i64
+        );
+
+        // The same epoch always gives the same time.
+        let row = with_epoch(epoch.0, || expr.eval_row(&Row(vec![]))).unwrap();
+        assert_eq!(row, Some(ScalarImpl::NaiveDateTime(values[0].unwrap())));
+
+        // The epoch is only effective inside `with_epoch`.
+        assert!(EVAL_EPOCH.with(Cell::get).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_with_epoch() {
+        use tokio_stream::StreamExt;
+
+        // Each item is the epoch it starts, if any.
+        let inner = tokio_stream::iter([None, Some(1), None, Some(2), None])
+            .map(|epoch| (epoch, EVAL_EPOCH.with(Cell::get).map(|e| e.0)));
+        let stream = WithEpoch::new(INVALID_EPOCH, Box::pin(inner), |(epoch, _)| *epoch);
+
+        let seen = stream.map(|(_, seen)| seen).collect::<Vec<_>>().await;
+        assert_eq!(seen, vec![None, None, Some(1), Some(1), Some(2)]);
+    }
+}
@@ -23,7 +23,9 @@ use super::template::{UnaryBytesExpression, UnaryExpression};
 use crate::expr::expr_is_null::{IsNotNullExpression, IsNullExpression};
 use crate::expr::template::UnaryNullableExpression;
 use crate::expr::BoxedExpression;
-use crate::vector_op::arithmetic_op::{decimal_abs, general_abs, general_neg};
+use crate::vector_op::arithmetic_op::{
+    decimal_abs, general_abs, general_neg, justify_days, justify_hours, justify_interval, sqrt_f64,
+};
 use crate::vector_op::ascii::ascii;
 use crate::vector_op::cast::*;
 use crate::vector_op::cmp::{is_false, is_not_false, is_not_true, is_true};
@@ -302,6 +304,11 @@ pub fn new_unary_expr(
                 }
             }
         }
+        (ProstType::Sqrt, _, _) => Box::new(UnaryExpression::<F64Array, F64Array, _>::new(
+            child_expr,
+            return_type,
+            sqrt_f64,
+        )),
         (ProstType::Ceil, _, _) => {
             gen_round_expr! {"Ceil", child_expr, return_type, ceil_f64, ceil_decimal}
         }
@@ -311,6 +318,27 @@ pub fn new_unary_expr(
         (ProstType::Round, _, _) => {
             gen_round_expr! {"Ceil", child_expr, return_type, round_f64, round_decimal}
         }
+        (ProstType::JustifyHours, _, _) => {
+            Box::new(UnaryExpression::<IntervalArray, IntervalArray, _>::new(
+                child_expr,
+                return_type,
+                justify_hours,
+            ))
+        }
+        (ProstType::JustifyDays, _, _) => {
+            Box::new(UnaryExpression::<IntervalArray, IntervalArray, _>::new(
+                child_expr,
+                return_type,
+                justify_days,
+            ))
+        }
+        (ProstType::JustifyInterval, _, _) => {
+            Box::new(UnaryExpression::<IntervalArray, IntervalArray, _>::new(
+                child_expr,
+                return_type,
+                justify_interval,
+            ))
+        }
         (expr, ret, child) => {
             return Err(ErrorCode::NotImplemented(format!(
                 "The expression {:?}({:?}) ->{:?} using vectorized expression framework is not supported yet.",
@@ -19,6 +19,8 @@ mod expr_array;
 mod expr_binary_bytes;
 pub mod expr_binary_nonnull;
 pub mod expr_binary_nullable;
+mod expr_binary_primitive;
+mod expr_binary_utf8;
 mod expr_case;
 mod expr_coalesce;
 mod expr_concat_ws;
@@ -27,6 +29,7 @@ mod expr_in;
 mod expr_input_ref;
 mod expr_is_null;
 mod expr_literal;
+mod expr_now;
 mod expr_ternary_bytes;
 pub mod expr_unary;
 mod template;
@@ -38,6 +41,7 @@ use std::sync::Arc;
 pub use agg::AggKind;
 pub use expr_input_ref::InputRefExpression;
 pub use expr_literal::*;
+pub use expr_now::{with_epoch, NowExpression, WithEpoch};
 use risingwave_common::array::{ArrayRef, DataChunk, Row};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
@@ -80,7 +84,8 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
 
     match prost.get_expr_type()? {
         Cast | Upper | Lower | Md5 | Not | IsTrue | IsNotTrue | IsFalse | IsNotFalse | IsNull
-        | IsNotNull | Neg | Ascii | Abs | Ceil | Floor | Round => build_unary_expr_prost(prost),
+        | IsNotNull | Neg | Ascii | Abs | Sqrt | Ceil | Floor | Round | JustifyHours
+        | JustifyDays | JustifyInterval => build_unary_expr_prost(prost),
         Equal | NotEqual | LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual | Add
         | Subtract | Multiply | Divide | Modulus | Extract | RoundDigit | TumbleStart
         | Position => build_binary_expr_prost(prost),
@@ -103,6 +108,7 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         In => build_in_expr(prost),
         Field => FieldExpression::try_from(prost).map(Expression::boxed),
         Array => ArrayExpression::try_from(prost).map(Expression::boxed),
+        Now => NowExpression::try_from(prost).map(Expression::boxed),
         _ => Err(InternalError(format!(
             "Unsupported expression type: {:?}",
             prost.get_expr_type()
@@ -0,0 +1,216 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayImpl, ArrayRef, DataChunk, I32Array, I64Array, IntervalArray,
+    NaiveDateTimeArray,
+};
+use risingwave_common::error::ErrorCode::{InternalError, InvalidParameterValue};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{CheckedAdd, DataType, Scalar};
+
+use super::{expect_args, BoxedTableFunction, TableFunction};
+use crate::expr::{BoxedExpression, Expression};
+
+/// Generates the values from `start` to `stop`, inclusive, with a step size of `step`. The series
+/// counts down if `step` is negative.
+#[derive(Debug)]
+pub struct GenerateSeries<T: Array, S: Array> {
+    start: BoxedExpression,
+    stop: BoxedExpression,
+    step: BoxedExpression,
+    return_type: DataType,
+    _phantom: PhantomData<(T, S)>,
+}
+
+impl<T, S> GenerateSeries<T, S>
+where
+    T: Array,
+    S: Array,
+    T::OwnedItem: PartialOrd<T::OwnedItem>,
+    T::OwnedItem: for<'a> CheckedAdd<S::RefItem<'a>>,
+    S::OwnedItem: PartialOrd<S::OwnedItem> + Default,
+{
+    fn new(
+        start: BoxedExpression,
+        stop: BoxedExpression,
+        step: BoxedExpression,
+        return_type: DataType,
+    ) -> Self {
+        Self {
+            start,
+            stop,
+            step,
+            return_type,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn eval_row(
+        &self,
+        start: T::RefItem<'_>,
+        stop: T::RefItem<'_>,
+        step: S::RefItem<'_>,
+    ) -> Result<T> {
+        let increasing = match step.to_owned_scalar().partial_cmp(&S::OwnedItem::default()) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Less) => false,
+            _ => {
+                return Err(RwError::from(InvalidParameterValue(
+                    "step size cannot equal zero".to_string(),
+                )))
+            }
+        };
+        let stop = stop.to_owned_scalar();
+        let mut builder = T::Builder::new(0)?;
+        let mut cur = start.to_owned_scalar();
+        while if increasing { cur <= stop } else { cur >= stop } {
+            builder.append(Some(cur.as_scalar_ref()))?;
+            // The series ends where the next value overflows, e.g., when `stop` is the maximum.
+            match cur.checked_add(step) {
+                Ok(next) => cur = next,
+                Err(_) => break,
+            }
+        }
+        builder.finish()
+    }
+}
+
+impl<T, S> TableFunction for GenerateSeries<T, S>
+where
+    T: Array,
+    S: Array,
+    T::OwnedItem: PartialOrd<T::OwnedItem>,
+    T::OwnedItem: for<'a> CheckedAdd<S::RefItem<'a>>,
+    S::OwnedItem: PartialOrd<S::OwnedItem> + Default,
+    for<'a> &'a T: From<&'a ArrayImpl>,
+    for<'a> &'a S: From<&'a ArrayImpl>,
+{
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<Vec<ArrayRef>> {
+        let start = self.start.eval(input)?;
+        let stop = self.stop.eval(input)?;
+        let step = self.step.eval(input)?;
+        let start: &T = start.as_ref().into();
+        let stop: &T = stop.as_ref().into();
+        let step: &S = step.as_ref().into();
+
+        (0..input.capacity())
+            .map(|i| {
+                let array = match (start.value_at(i), stop.value_at(i), step.value_at(i)) {
+                    (Some(start), Some(stop), Some(step)) => self.eval_row(start, stop, step)?,
+                    _ => T::Builder::new(0)?.finish()?,
+                };
+                Ok(Arc::new(array.into()))
+            })
+            .collect()
+    }
+}
+
+pub fn new_generate_series(
+    args: Vec<BoxedExpression>,
+    return_type: DataType,
+) -> Result<BoxedTableFunction> {
+    let [start, stop, step] = expect_args("generate_series", args)?;
+    match (
+        start.return_type(),
+        stop.return_type(),
+        step.return_type(),
+        &return_type,
+    ) {
+        (DataType::Int32, DataType::Int32, DataType::Int32, DataType::Int32) => {
+            Ok(GenerateSeries::<I32Array, I32Array>::new(start, stop, step, return_type).boxed())
+        }
+        (DataType::Int64, DataType::Int64, DataType::Int64, DataType::Int64) => {
+            Ok(GenerateSeries::<I64Array, I64Array>::new(start, stop, step, return_type).boxed())
+        }
+        (DataType::Timestamp, DataType::Timestamp, DataType::Interval, DataType::Timestamp) => {
+            Ok(GenerateSeries::<NaiveDateTimeArray, IntervalArray>::new(
+                start,
+                stop,
+                step,
+                return_type,
+            )
+            .boxed())
+        }
+        (start, stop, step, _) => Err(RwError::from(InternalError(format!(
+            "generate_series({:?}, {:?}, {:?}) is not supported",
+            start, stop, step
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+
+    use super::*;
+    use crate::expr::InputRefExpression;
+
+    fn new_int32_series() -> BoxedTableFunction {
+        let input_ref = |i| InputRefExpression::new(DataType::Int32, i).boxed();
+        new_generate_series(
+            vec![input_ref(0), input_ref(1), input_ref(2)],
+            DataType::Int32,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generate_series() {
+        let chunk = DataChunk::from_pretty(
+            "i i i
+             1 3 1
+             2 6 2
+             5 4 1
+             1 . 1
+             5 1 -2
+             1 3 -1
+             2147483646 2147483647 1",
+        );
+        let outputs = new_int32_series().eval(&chunk).unwrap();
+
+        let expected: [&[i32]; 7] = [
+            &[1, 2, 3],
+            &[2, 4, 6],
+            &[],
+            &[],
+            &[5, 3, 1],
+            &[],
+            &[2147483646, 2147483647],
+        ];
+        assert_eq!(outputs.len(), expected.len());
+        for (output, expected) in outputs.iter().zip_eq(expected) {
+            let values = output.as_int32().iter().flatten().collect_vec();
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    fn test_generate_series_zero_step() {
+        let chunk = DataChunk::from_pretty(
+            "i i i
+             1 3 0",
+        );
+        assert!(new_int32_series().eval(&chunk).is_err());
+    }
+}
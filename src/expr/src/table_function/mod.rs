@@ -0,0 +1,112 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Table functions, a.k.a. set-returning functions, which produce zero or more rows for each input
+//! row, e.g., `unnest(array)` and `generate_series(start, stop, step)`.
+
+use risingwave_common::array::{ArrayRef, DataChunk};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_pb::expr::project_set_select_item::SelectItem;
+use risingwave_pb::expr::table_function::Type;
+use risingwave_pb::expr::{
+    ProjectSetSelectItem as ProjectSetSelectItemProst, TableFunction as TableFunctionProst,
+};
+
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression, Expression};
+
+mod generate_series;
+mod unnest;
+
+pub use generate_series::*;
+pub use unnest::*;
+
+/// Instance of a table function.
+pub trait TableFunction: std::fmt::Debug + Sync + Send {
+    fn return_type(&self) -> DataType;
+
+    /// Evaluate the table function on each row of `input`, returning one array of output values
+    /// for each row. Rows with null arguments produce empty arrays.
+    fn eval(&self, input: &DataChunk) -> Result<Vec<ArrayRef>>;
+
+    fn boxed(self) -> BoxedTableFunction
+    where
+        Self: Sized + Send + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+pub type BoxedTableFunction = Box<dyn TableFunction>;
+
+pub fn build_table_function_from_prost(prost: &TableFunctionProst) -> Result<BoxedTableFunction> {
+    let return_type = DataType::from(prost.get_return_type()?);
+    let args = prost
+        .get_args()
+        .iter()
+        .map(expr_build_from_prost)
+        .collect::<Result<Vec<_>>>()?;
+
+    match prost.get_function_type()? {
+        Type::GenerateSeries => new_generate_series(args, return_type),
+        Type::Unnest => new_unnest(args, return_type),
+        Type::Invalid => Err(RwError::from(InternalError(
+            "invalid table function type".to_string(),
+        ))),
+    }
+}
+
+/// An item of the select list of `ProjectSet`, either evaluated to a single value or expanded to a
+/// set of values for each input row.
+#[derive(Debug)]
+pub enum ProjectSetSelectItem {
+    Expr(BoxedExpression),
+    TableFunction(BoxedTableFunction),
+}
+
+impl ProjectSetSelectItem {
+    pub fn from_prost(prost: &ProjectSetSelectItemProst) -> Result<Self> {
+        match prost.select_item.as_ref() {
+            Some(SelectItem::Expr(expr)) => Ok(Self::Expr(expr_build_from_prost(expr)?)),
+            Some(SelectItem::TableFunction(table_function)) => Ok(Self::TableFunction(
+                build_table_function_from_prost(table_function)?,
+            )),
+            None => Err(RwError::from(InternalError(
+                "missing select item of project set".to_string(),
+            ))),
+        }
+    }
+
+    pub fn return_type(&self) -> DataType {
+        match self {
+            Self::Expr(expr) => expr.return_type(),
+            Self::TableFunction(table_function) => table_function.return_type(),
+        }
+    }
+}
+
+fn expect_args<const N: usize>(
+    name: &str,
+    args: Vec<BoxedExpression>,
+) -> Result<[BoxedExpression; N]> {
+    args.try_into().map_err(|args: Vec<_>| {
+        RwError::from(InternalError(format!(
+            "{} expects {} arguments, got {}",
+            name,
+            N,
+            args.len()
+        )))
+    })
+}
@@ -0,0 +1,65 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::array::{Array, ArrayRef, DataChunk};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
+
+use super::{expect_args, BoxedTableFunction, TableFunction};
+use crate::expr::{BoxedExpression, Expression};
+
+/// Expands an array into a set of rows, one for each element.
+#[derive(Debug)]
+pub struct Unnest {
+    list: BoxedExpression,
+    return_type: DataType,
+}
+
+impl TableFunction for Unnest {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<Vec<ArrayRef>> {
+        let list = self.list.eval(input)?;
+        let list = list.as_list();
+
+        (0..input.capacity())
+            .map(|i| {
+                let values = list.value_at(i).map(|l| l.values_ref()).unwrap_or_default();
+                let mut builder = self.return_type.create_array_builder(values.len())?;
+                for value in values {
+                    builder.append_datum_ref(value)?;
+                }
+                Ok(Arc::new(builder.finish()?))
+            })
+            .collect()
+    }
+}
+
+pub fn new_unnest(args: Vec<BoxedExpression>, return_type: DataType) -> Result<BoxedTableFunction> {
+    let [list] = expect_args("unnest", args)?;
+    match list.return_type() {
+        DataType::List { datatype } if *datatype == return_type => {
+            Ok(Unnest { list, return_type }.boxed())
+        }
+        other => Err(RwError::from(InternalError(format!(
+            "unnest of {:?} can't return {:?}",
+            other, return_type
+        )))),
+    }
+}
@@ -17,11 +17,13 @@ use std::convert::TryInto;
 use std::fmt::Debug;
 
 use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub, Signed};
-use risingwave_common::error::ErrorCode::{InternalError, NumericValueOutOfRange};
+use risingwave_common::error::ErrorCode::{
+    InternalError, InvalidParameterValue, NumericValueOutOfRange,
+};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{
     CheckedAdd as NaiveDateTimeCheckedAdd, Decimal, IntervalUnit, NaiveDateTimeWrapper,
-    NaiveDateWrapper,
+    NaiveDateWrapper, OrderedF64,
 };
 
 use super::cast::date_to_timestamp;
@@ -112,6 +114,16 @@ pub fn decimal_abs(decimal: Decimal) -> Result<Decimal> {
     Ok(Decimal::abs(&decimal).unwrap())
 }
 
+#[inline(always)]
+pub fn sqrt_f64(input: OrderedF64) -> Result<OrderedF64> {
+    if input.0 < 0.0 {
+        return Err(RwError::from(InvalidParameterValue(
+            "cannot take square root of a negative number".to_string(),
+        )));
+    }
+    Ok(input.0.sqrt().into())
+}
+
 #[inline(always)]
 pub fn general_atm<T1, T2, T3, F>(l: T1, r: T2, atm: F) -> Result<T3>
 where
@@ -216,13 +228,60 @@ where
     interval_int_mul::<T2, T1, T3>(r, l)
 }
 
+#[inline(always)]
+pub fn interval_float_mul<T1, T2, T3>(l: IntervalUnit, r: T2) -> Result<IntervalUnit>
+where
+    T2: TryInto<OrderedF64> + Debug,
+{
+    l.checked_mul_float(r)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn float_interval_mul<T1, T2, T3>(l: T1, r: IntervalUnit) -> Result<IntervalUnit>
+where
+    T1: TryInto<OrderedF64> + Debug,
+{
+    interval_float_mul::<T2, T1, T3>(r, l)
+}
+
+#[inline(always)]
+pub fn interval_float_div<T1, T2, T3>(l: IntervalUnit, r: T2) -> Result<IntervalUnit>
+where
+    T2: TryInto<OrderedF64> + Debug,
+{
+    l.checked_div_float(r)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn justify_hours(interval: IntervalUnit) -> Result<IntervalUnit> {
+    interval
+        .justify_hours()
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn justify_days(interval: IntervalUnit) -> Result<IntervalUnit> {
+    interval
+        .justify_days()
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn justify_interval(interval: IntervalUnit) -> Result<IntervalUnit> {
+    interval
+        .justify_interval()
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use risingwave_common::types::Decimal;
+    use risingwave_common::types::{Decimal, IntervalUnit, OrderedF64};
 
-    use crate::vector_op::arithmetic_op::general_add;
+    use crate::vector_op::arithmetic_op::*;
 
     #[test]
     fn test() {
@@ -231,4 +290,31 @@ mod tests {
             Decimal::from_str("2").unwrap()
         );
     }
+
+    #[test]
+    fn test_interval_float() {
+        let interval = IntervalUnit::new(1, 1, 0);
+        assert_eq!(
+            interval_float_mul::<IntervalUnit, OrderedF64, IntervalUnit>(interval, 2.0.into())
+                .unwrap(),
+            IntervalUnit::new(2, 2, 0)
+        );
+        assert_eq!(
+            interval_float_div::<IntervalUnit, i32, IntervalUnit>(interval, 2).unwrap(),
+            IntervalUnit::new(0, 15, 12 * 3600 * 1000)
+        );
+        assert!(interval_float_div::<IntervalUnit, i32, IntervalUnit>(interval, 0).is_err());
+        assert_eq!(
+            justify_days(IntervalUnit::new(0, 31, 0)).unwrap(),
+            IntervalUnit::new(1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_sqrt() {
+        assert_eq!(sqrt_f64(4.0.into()).unwrap(), OrderedF64::from(2.0));
+        assert_eq!(sqrt_f64(0.0.into()).unwrap(), OrderedF64::from(0.0));
+        assert!(sqrt_f64(f64::NAN.into()).unwrap().0.is_nan());
+        assert!(sqrt_f64((-1.0).into()).is_err());
+    }
 }
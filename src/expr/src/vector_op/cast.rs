@@ -18,7 +18,7 @@ use std::str::FromStr;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use num_traits::ToPrimitive;
-use risingwave_common::error::ErrorCode::{InternalError, InvalidInputSyntax};
+use risingwave_common::error::ErrorCode::{CastOutOfRange, InvalidCast, InvalidInputSyntax};
 use risingwave_common::error::{parse_error, Result, RwError};
 use risingwave_common::types::{
     Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
@@ -108,12 +108,11 @@ where
     <T as FromStr>::Err: std::fmt::Display,
 {
     elem.parse().map_err(|e| {
-        RwError::from(InternalError(format!(
-            "Can't cast {:?} to {:?}: {}",
-            elem,
-            type_name::<T>(),
-            e
-        )))
+        RwError::from(InvalidCast {
+            value: format!("{:?}", elem),
+            target_type: type_name::<T>().to_string(),
+            reason: e.to_string(),
+        })
     })
 }
 
@@ -144,11 +143,10 @@ macro_rules! define_cast_to_primitive {
             {
                 elem.[<to_ $ty>]()
                     .ok_or_else(|| {
-                        RwError::from(InternalError(format!(
-                            "Can't cast {:?} to {}",
-                            elem,
-                            std::any::type_name::<$ty>()
-                        )))
+                        RwError::from(CastOutOfRange {
+                            value: format!("{:?}", elem),
+                            target_type: std::any::type_name::<$ty>().to_string(),
+                        })
                     })
                     .map(Into::into)
             }
@@ -186,12 +184,11 @@ where
     <T1 as TryInto<T2>>::Error: std::fmt::Display,
 {
     elem.try_into().map_err(|e| {
-        RwError::from(InternalError(format!(
-            "Can't cast {:?} to {:?}: {}",
-            &elem,
-            type_name::<T2>(),
-            e
-        )))
+        RwError::from(InvalidCast {
+            value: format!("{:?}", elem),
+            target_type: type_name::<T2>().to_string(),
+            reason: e.to_string(),
+        })
     })
 }
 
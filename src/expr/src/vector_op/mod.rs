@@ -25,6 +25,7 @@ pub mod lower;
 pub mod ltrim;
 pub mod md5;
 pub mod position;
+pub mod primitive_kernel;
 pub mod replace;
 pub mod round;
 pub mod rtrim;
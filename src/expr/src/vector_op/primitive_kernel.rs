@@ -0,0 +1,177 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison and arithmetic kernels on the values of primitive arrays. They work on whole slices
+//! and pack the results into bitmaps a word at a time, so that the loops have no branches and can
+//! be vectorized by the compiler. Nulls are handled separately by the callers on the bitmaps.
+
+use std::ops::{Add, Mul, Sub};
+
+use num_traits::ops::overflowing::{OverflowingAdd, OverflowingMul, OverflowingSub};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::ErrorCode::NumericValueOutOfRange;
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::expr::expr_node::Type;
+
+use super::cmp::Comparison;
+
+/// The number of values processed at a time, i.e., the bits of a word of the result bitmap.
+const LANES: usize = u64::BITS as usize;
+
+/// Reads the `word_idx`-th word of `bits`, padding it with zeros if it's beyond the buffer.
+#[inline(always)]
+fn word_at(bits: &[u8], word_idx: usize) -> u64 {
+    let start = word_idx * 8;
+    let mut word = [0u8; 8];
+    if start < bits.len() {
+        let bytes = &bits[start..bits.len().min(start + 8)];
+        word[..bytes.len()].copy_from_slice(bytes);
+    }
+    u64::from_le_bytes(word)
+}
+
+/// Evaluates `op` on each pair of values of `l` and `r` and packs the results into a bitmap.
+pub fn compare_slices<T, F>(l: &[T], r: &[T], op: F) -> Bitmap
+where
+    T: Copy,
+    F: Fn(T, T) -> bool,
+{
+    assert_eq!(l.len(), r.len());
+    let num_bits = l.len();
+    let mut bits = Vec::with_capacity((num_bits + 7) / 8);
+
+    let l_chunks = l.chunks_exact(LANES);
+    let r_chunks = r.chunks_exact(LANES);
+    let (l_remainder, r_remainder) = (l_chunks.remainder(), r_chunks.remainder());
+    for (l, r) in l_chunks.zip(r_chunks) {
+        let mut word = 0u64;
+        for i in 0..LANES {
+            word |= (op(l[i], r[i]) as u64) << i;
+        }
+        bits.extend_from_slice(&word.to_le_bytes());
+    }
+
+    let mut word = 0u64;
+    for (i, (&l, &r)) in l_remainder.iter().zip(r_remainder).enumerate() {
+        word |= (op(l, r) as u64) << i;
+    }
+    bits.extend_from_slice(&word.to_le_bytes()[..(l_remainder.len() + 7) / 8]);
+
+    Bitmap::from_bytes_with_num_bits(bits.into(), num_bits)
+}
+
+/// Evaluates `op` on each pair of values of `l` and `r`. `op` returns the wrapped result and
+/// whether it overflowed, and an overflow on any row set in `valid` is reported as an error.
+pub fn arithmetic_slices<T, F>(l: &[T], r: &[T], valid: &Bitmap, op: F) -> Result<Vec<T>>
+where
+    T: Copy + Default,
+    F: Fn(T, T) -> (T, bool),
+{
+    assert_eq!(l.len(), r.len());
+    assert_eq!(l.len(), valid.len());
+    let mut results = vec![T::default(); l.len()];
+
+    for (word_idx, ((l, r), results)) in l
+        .chunks(LANES)
+        .zip(r.chunks(LANES))
+        .zip(results.chunks_mut(LANES))
+        .enumerate()
+    {
+        let mut overflows = 0u64;
+        for (i, ((&l, &r), result)) in l.iter().zip(r).zip(results.iter_mut()).enumerate() {
+            let (value, overflow) = op(l, r);
+            *result = value;
+            overflows |= (overflow as u64) << i;
+        }
+        if overflows & word_at(&valid.bits, word_idx) != 0 {
+            return Err(RwError::from(NumericValueOutOfRange));
+        }
+    }
+    Ok(results)
+}
+
+#[inline(always)]
+pub fn gen_primitive_cmp<T: PartialOrd>(op: Comparison) -> fn(T, T) -> bool {
+    match op {
+        Comparison::Eq => |l, r| l == r,
+        Comparison::Ne => |l, r| l != r,
+        Comparison::Lt => |l, r| l < r,
+        Comparison::Gt => |l, r| l > r,
+        Comparison::Le => |l, r| l <= r,
+        Comparison::Ge => |l, r| l >= r,
+    }
+}
+
+/// Generates the arithmetic function of `op` on integers, which reports overflows.
+pub fn gen_int_arithmetic<T>(op: Type) -> fn(T, T) -> (T, bool)
+where
+    T: OverflowingAdd + OverflowingSub + OverflowingMul,
+{
+    match op {
+        Type::Add => |l: T, r: T| l.overflowing_add(&r),
+        Type::Subtract => |l: T, r: T| l.overflowing_sub(&r),
+        Type::Multiply => |l: T, r: T| l.overflowing_mul(&r),
+        _ => unreachable!("{:?} is not supported by the arithmetic kernels", op),
+    }
+}
+
+/// Generates the arithmetic function of `op` on floats, which never overflows.
+pub fn gen_float_arithmetic<T>(op: Type) -> fn(T, T) -> (T, bool)
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    match op {
+        Type::Add => |l: T, r: T| (l + r, false),
+        Type::Subtract => |l: T, r: T| (l - r, false),
+        Type::Multiply => |l: T, r: T| (l * r, false),
+        _ => unreachable!("{:?} is not supported by the arithmetic kernels", op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_slices() {
+        let l = (0..150).collect::<Vec<i32>>();
+        let r = (0..150).rev().collect::<Vec<i32>>();
+        let bitmap = compare_slices(&l, &r, gen_primitive_cmp(Comparison::Lt));
+        assert_eq!(bitmap.len(), 150);
+        assert_eq!(bitmap.bits.len(), 19);
+        assert_eq!(bitmap.num_high_bits(), 75);
+        for (i, bit) in bitmap.iter().enumerate() {
+            assert_eq!(bit, l[i] < r[i]);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_slices() {
+        let l = vec![i16::MAX; 100];
+        let r = (0..100).map(|i| (i == 70) as i16).collect::<Vec<_>>();
+        let add = gen_int_arithmetic::<i16>(Type::Add);
+
+        // The row that overflows is null.
+        let valid = Bitmap::try_from((0..100).map(|i| i != 70).collect::<Vec<_>>()).unwrap();
+        let results = arithmetic_slices(&l, &r, &valid, add).unwrap();
+        assert_eq!(results[0], i16::MAX);
+
+        let valid = Bitmap::try_from(vec![true; 100]).unwrap();
+        assert!(arithmetic_slices(&l, &r, &valid, add).is_err());
+
+        let mul = gen_float_arithmetic::<f64>(Type::Multiply);
+        let results = arithmetic_slices(&[1.5, 2.0], &[2.0, -1.0], &Bitmap::new(2).unwrap(), mul);
+        assert_eq!(results.unwrap(), vec![3.0, -2.0]);
+    }
+}
@@ -16,7 +16,7 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::{BinaryOperator, Expr};
 
 use crate::binder::Binder;
-use crate::expr::{ExprImpl, ExprType, FunctionCall};
+use crate::expr::{Expr as _, ExprImpl, ExprType, FunctionCall};
 
 impl Binder {
     pub(super) fn bind_binary_op(
@@ -25,8 +25,16 @@ impl Binder {
         op: BinaryOperator,
         right: Expr,
     ) -> Result<ExprImpl> {
-        let bound_left = self.bind_expr(left)?;
-        let bound_right = self.bind_expr(right)?;
+        // A parameter on either side takes the type of the other side.
+        let (bound_left, bound_right) = if matches!(left, Expr::Parameter { .. }) {
+            let bound_right = self.bind_expr(right)?;
+            self.infer_param_type(&left, &bound_right.return_type())?;
+            (self.bind_expr(left)?, bound_right)
+        } else {
+            let bound_left = self.bind_expr(left)?;
+            self.infer_param_type(&right, &bound_left.return_type())?;
+            (bound_left, self.bind_expr(right)?)
+        };
         let func_type = match op {
             BinaryOperator::Plus => ExprType::Add,
             BinaryOperator::Minus => ExprType::Subtract,
@@ -42,6 +42,8 @@ impl Binder {
                 "avg" => Some(AggKind::Avg),
                 "string_agg" => Some(AggKind::StringAgg),
                 "single_value" => Some(AggKind::SingleValue),
+                "stddev_samp" => Some(AggKind::StddevSamp),
+                "var_pop" => Some(AggKind::VarPop),
                 _ => None,
             };
             if let Some(kind) = agg_kind {
@@ -62,6 +64,10 @@ impl Binder {
                 "rtrim" => ExprType::Rtrim,
                 "md5" => ExprType::Md5,
                 "to_char" => ExprType::ToChar,
+                "justify_hours" => ExprType::JustifyHours,
+                "justify_days" => ExprType::JustifyDays,
+                "justify_interval" => ExprType::JustifyInterval,
+                "now" | "proctime" => ExprType::Now,
                 "nullif" => {
                     inputs = Self::rewrite_nullif_to_case_when(inputs)?;
                     ExprType::Case
@@ -86,6 +92,7 @@ impl Binder {
                     ExprType::Floor
                 }
                 "abs" => ExprType::Abs,
+                "sqrt" => ExprType::Sqrt,
                 "booleq" => {
                     inputs = Self::rewrite_two_bool_inputs(inputs)?;
                     ExprType::Equal
@@ -27,6 +27,7 @@ use crate::expr::{Expr as _, ExprImpl, ExprType, FunctionCall, SubqueryKind};
 mod binary_op;
 mod column;
 mod function;
+mod parameter;
 mod subquery;
 mod value;
 
@@ -35,6 +36,7 @@ impl Binder {
         match expr {
             // literal
             Expr::Value(v) => Ok(ExprImpl::Literal(Box::new(self.bind_value(v)?))),
+            Expr::Parameter { index } => self.bind_parameter(index),
             Expr::TypedString { data_type, value } => {
                 let s: ExprImpl = self.bind_string(value)?.into();
                 s.cast_explicit(bind_data_type(&data_type)?)
@@ -133,6 +135,7 @@ impl Binder {
         let mut bound_expr_list = vec![left.clone()];
         let mut non_const_exprs = vec![];
         for elem in list {
+            self.infer_param_type(&elem, &left.return_type())?;
             let expr = self.bind_expr(elem)?;
             match expr.is_const() {
                 true => bound_expr_list.push(expr),
@@ -254,6 +257,8 @@ impl Binder {
         high: Expr,
     ) -> Result<ExprImpl> {
         let expr = self.bind_expr(expr)?;
+        self.infer_param_type(&low, &expr.return_type())?;
+        self.infer_param_type(&high, &expr.return_type())?;
         let low = self.bind_expr(low)?;
         let high = self.bind_expr(high)?;
 
@@ -339,8 +344,9 @@ impl Binder {
     }
 
     pub(super) fn bind_cast(&mut self, expr: Expr, data_type: AstDataType) -> Result<ExprImpl> {
-        self.bind_expr(expr)?
-            .cast_explicit(bind_data_type(&data_type)?)
+        let data_type = bind_data_type(&data_type)?;
+        self.infer_param_type(&expr, &data_type)?;
+        self.bind_expr(expr)?.cast_explicit(data_type)
     }
 }
 
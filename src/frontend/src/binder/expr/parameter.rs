@@ -0,0 +1,65 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::Expr;
+
+use crate::binder::Binder;
+use crate::expr::{ExprImpl, Parameter};
+
+impl Binder {
+    /// Binds the parameter `$index` of a prepared statement. The actual value is substituted in
+    /// when the statement is executed. A parameter whose type is neither specified nor inferred so
+    /// far is taken as `varchar`, like an unknown literal in PG.
+    pub(super) fn bind_parameter(&mut self, index: u64) -> Result<ExprImpl> {
+        let data_type = self
+            .param_type_mut(index)?
+            .get_or_insert(DataType::Varchar)
+            .clone();
+        Ok(Parameter::new(index, data_type).into())
+    }
+
+    /// Infers the type of `expr` from the context it's used in, if it's a parameter whose type is
+    /// still unknown. This must be called before `expr` is bound.
+    pub(super) fn infer_param_type(&mut self, expr: &Expr, data_type: &DataType) -> Result<()> {
+        match expr {
+            Expr::Parameter { index } => {
+                let param_type = self.param_type_mut(*index)?;
+                if param_type.is_none() {
+                    *param_type = Some(data_type.clone());
+                }
+                Ok(())
+            }
+            Expr::Nested(expr) => self.infer_param_type(expr, data_type),
+            _ => Ok(()),
+        }
+    }
+
+    fn param_type_mut(&mut self, index: u64) -> Result<&mut Option<DataType>> {
+        let param_types = match &mut self.param_types {
+            Some(param_types) if index > 0 => param_types,
+            _ => {
+                return Err(
+                    ErrorCode::BindError(format!("there is no parameter ${}", index)).into(),
+                )
+            }
+        };
+        let index = index as usize - 1;
+        if index >= param_types.len() {
+            param_types.resize(index + 1, None);
+        }
+        Ok(&mut param_types[index])
+    }
+}
@@ -16,11 +16,25 @@ use itertools::Itertools;
 use risingwave_common::array::StructValue;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::{DataType, ScalarImpl};
-use risingwave_sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
+use risingwave_pb::plan_common::ConflictBehavior;
+use risingwave_sqlparser::ast::{Expr, Ident, ObjectName, OnInsert, Query, SetExpr};
+use risingwave_sqlparser::parser::Parser;
+use risingwave_sqlparser::tokenizer::Tokenizer;
 
 use super::{BoundQuery, BoundSetExpr};
 use crate::binder::{Binder, BoundTableSource};
-use crate::expr::{Expr, ExprImpl, InputRef, Literal};
+use crate::catalog::table_catalog::TableCatalog;
+use crate::expr::{Expr as _, ExprImpl, InputRef, Literal};
+
+/// Parses the DEFAULT expression of a column stored in the catalog.
+fn parse_default_expr(default_expr: &str) -> Result<Expr> {
+    let tokens = Tokenizer::new(default_expr)
+        .tokenize()
+        .map_err(|e| ErrorCode::InternalError(e.to_string()))?;
+    Parser::new(tokens)
+        .parse_expr()
+        .map_err(|e| ErrorCode::InternalError(e.to_string()).into())
+}
 
 #[derive(Debug)]
 pub struct BoundInsert {
@@ -32,21 +46,53 @@ pub struct BoundInsert {
     /// Used as part of an extra `Project` when the column types of `source` query does not match
     /// `table_source`. This does not include a simple `VALUE`. See comments in code for details.
     pub cast_exprs: Vec<ExprImpl>,
+
+    /// Set if the inserted rows should be checked against the existing rows of the materialized
+    /// table, with either `IgnoreConflict` or `ErrorOnConflict`.
+    pub conflict_check: Option<(TableCatalog, ConflictBehavior)>,
+
+    /// Indices of the columns of `table_source` which must not be null.
+    pub not_null_columns: Vec<usize>,
 }
 
 impl Binder {
     pub(super) fn bind_insert(
         &mut self,
         source_name: ObjectName,
-        _columns: Vec<Ident>,
+        columns: Vec<Ident>,
         source: Query,
+        on: Option<OnInsert>,
     ) -> Result<BoundInsert> {
-        let table_source = self.bind_table_source(source_name)?;
+        let table_source = self.bind_table_source(source_name.clone())?;
+        let conflict_check = self.bind_conflict_check(source_name, &table_source, on)?;
 
-        let expected_types = table_source
+        // The columns that the `source` query provides values for, in the order of its output.
+        let target_columns = self.bind_insert_columns(&table_source, columns)?;
+        // The omitted columns are filled with their defaults, which are bound before the `source`
+        // so that they can't see any columns.
+        let defaults = table_source
             .columns
             .iter()
-            .map(|c| c.data_type.clone())
+            .zip_eq(&table_source.default_exprs)
+            .enumerate()
+            .map(|(i, (column, default_expr))| {
+                if target_columns.contains(&i) {
+                    return Ok(None);
+                }
+                let expr = match default_expr {
+                    Some(default_expr) => self.bind_column_default(
+                        parse_default_expr(default_expr)?,
+                        &column.data_type,
+                    )?,
+                    None => Literal::new(None, column.data_type.clone()).into(),
+                };
+                Ok(Some(expr))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let expected_types: Vec<DataType> = target_columns
+            .iter()
+            .map(|i| table_source.columns[*i].data_type.clone())
             .collect();
 
         // When the column types of `source` query does not match `expected_types`, casting is
@@ -80,7 +126,7 @@ impl Binder {
                 offset: None,
                 fetch: None,
             } if order.is_empty() => {
-                let values = self.bind_values(values, Some(expected_types))?;
+                let values = self.bind_values(values, Some(expected_types.clone()))?;
                 let body = BoundSetExpr::Values(values.into());
                 (
                     BoundQuery {
@@ -99,7 +145,7 @@ impl Binder {
                 let cast_exprs = match expected_types == actual_types {
                     true => vec![],
                     false => Self::cast_on_insert(
-                        expected_types,
+                        expected_types.clone(),
                         actual_types
                             .into_iter()
                             .enumerate()
@@ -111,15 +157,131 @@ impl Binder {
             }
         };
 
+        // Reorder the output of `source` to the columns of the table, and fill in the omitted ones.
+        let all_in_order = target_columns
+            .iter()
+            .copied()
+            .eq(0..table_source.columns.len());
+        let cast_exprs = if all_in_order {
+            cast_exprs
+        } else {
+            let provided = if cast_exprs.is_empty() {
+                expected_types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, t)| InputRef::new(i, t).into())
+                    .collect()
+            } else {
+                cast_exprs
+            };
+            defaults
+                .into_iter()
+                .enumerate()
+                .map(|(i, default)| match default {
+                    Some(default) => default,
+                    None => {
+                        let pos = target_columns.iter().position(|idx| *idx == i).unwrap();
+                        provided[pos].clone()
+                    }
+                })
+                .collect()
+        };
+
+        let not_null_columns = table_source
+            .not_null
+            .iter()
+            .positions(|not_null| *not_null)
+            .collect();
+
         let insert = BoundInsert {
             table_source,
             source,
             cast_exprs,
+            conflict_check,
+            not_null_columns,
         };
 
         Ok(insert)
     }
 
+    /// Resolves the column list of INSERT into the indices of the columns of the table. All columns
+    /// are targeted in order if the list is omitted.
+    fn bind_insert_columns(
+        &self,
+        table_source: &BoundTableSource,
+        columns: Vec<Ident>,
+    ) -> Result<Vec<usize>> {
+        if columns.is_empty() {
+            return Ok((0..table_source.columns.len()).collect());
+        }
+        let mut target_columns = Vec::with_capacity(columns.len());
+        for column in columns {
+            let idx = table_source
+                .columns
+                .iter()
+                .position(|c| c.name == column.value)
+                .ok_or_else(|| {
+                    ErrorCode::BindError(format!(
+                        "column \"{}\" of table \"{}\" does not exist",
+                        column.value, table_source.name
+                    ))
+                })?;
+            if target_columns.contains(&idx) {
+                return Err(ErrorCode::BindError(format!(
+                    "column \"{}\" specified more than once",
+                    column.value
+                ))
+                .into());
+            }
+            target_columns.push(idx);
+        }
+        Ok(target_columns)
+    }
+
+    /// Binds the DEFAULT expression of a column, which can't refer to any columns, aggregates or
+    /// subqueries, and casts it to the type of the column.
+    pub(crate) fn bind_column_default(
+        &mut self,
+        expr: Expr,
+        data_type: &DataType,
+    ) -> Result<ExprImpl> {
+        let bound = self.bind_expr(expr)?;
+        if bound.has_agg_call() || bound.has_subquery() {
+            return Err(ErrorCode::BindError(
+                "cannot use aggregate functions or subqueries in DEFAULT expression".to_string(),
+            )
+            .into());
+        }
+        bound.cast_assign(data_type.clone())
+    }
+
+    /// Resolves how the inserted rows are checked against the primary key of the table. Rows
+    /// overwriting existing ones are handled by the materialize executor, so no check is needed.
+    fn bind_conflict_check(
+        &self,
+        source_name: ObjectName,
+        table_source: &BoundTableSource,
+        on: Option<OnInsert>,
+    ) -> Result<Option<(TableCatalog, ConflictBehavior)>> {
+        let behavior = match (table_source.conflict_behavior, on) {
+            // The hidden row id is used as pk, so there won't be any conflicts.
+            (ConflictBehavior::NoCheck, _) => return Ok(None),
+            (_, Some(OnInsert::DoNothing)) => ConflictBehavior::IgnoreConflict,
+            (_, Some(on)) => {
+                return Err(ErrorCode::NotImplemented(format!("INSERT{}", on), None.into()).into())
+            }
+            (ConflictBehavior::Overwrite, None) => return Ok(None),
+            (behavior, None) => behavior,
+        };
+
+        let (schema_name, table_name) = self.resolve_relation_name(source_name)?;
+        let table = self
+            .catalog
+            .get_table_by_name(&self.db_name, &schema_name, &table_name)?
+            .clone();
+        Ok(Some((table, behavior)))
+    }
+
     /// Cast a list of `exprs` to corresponding `expected_types` IN ASSIGNMENT CONTEXT. Make sure
     /// you understand the difference of implicit, assignment and explicit cast before reusing it.
     pub(super) fn cast_on_insert(
@@ -14,7 +14,9 @@
 
 use std::collections::HashMap;
 
+use risingwave_common::catalog::DEFAULT_SCHEMA_NAME;
 use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{Statement, TableAlias};
 
 pub mod bind_context;
@@ -35,8 +37,8 @@ pub use delete::BoundDelete;
 pub use insert::BoundInsert;
 pub use query::BoundQuery;
 pub use relation::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundTableSource,
-    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundTableSource, BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
 pub use select::BoundSelect;
 pub use set_expr::BoundSetExpr;
@@ -60,6 +62,12 @@ pub struct Binder {
     next_subquery_id: usize,
     /// Map the cte's name to its Relation::Subquery.
     cte_to_relation: HashMap<String, (BoundQuery, TableAlias)>,
+    /// The types of the parameters `$1`, `$2`, ... of a prepared statement. `None` for those
+    /// neither specified by the client nor inferred yet. Parameters are not allowed if the
+    /// statement is not prepared, i.e., the binder is not created by `new_with_param_types`.
+    param_types: Option<Vec<Option<DataType>>>,
+    /// Schemas to look up unqualified relation names in, in order.
+    search_path: Vec<String>,
 }
 
 impl Binder {
@@ -71,6 +79,28 @@ impl Binder {
             upper_contexts: vec![],
             next_subquery_id: 0,
             cte_to_relation: HashMap::new(),
+            param_types: None,
+            search_path: vec![DEFAULT_SCHEMA_NAME.to_string()],
+        }
+    }
+
+    /// Sets the schemas to look up unqualified relation names in, usually the `search_path` of
+    /// the session.
+    #[must_use]
+    pub fn with_search_path(mut self, search_path: Vec<String>) -> Self {
+        self.search_path = search_path;
+        self
+    }
+
+    /// Creates a binder for a prepared statement, whose parameters are of the given types.
+    pub fn new_with_param_types(
+        catalog: CatalogReadGuard,
+        db_name: String,
+        param_types: Vec<Option<DataType>>,
+    ) -> Binder {
+        Binder {
+            param_types: Some(param_types),
+            ..Self::new(catalog, db_name)
         }
     }
 
@@ -79,6 +109,16 @@ impl Binder {
         self.bind_statement(stmt)
     }
 
+    /// The types of the parameters after binding. Those neither specified nor used in a context
+    /// that implies their types are `varchar`.
+    pub fn export_param_types(&self) -> Vec<DataType> {
+        self.param_types
+            .iter()
+            .flatten()
+            .map(|t| t.clone().unwrap_or(DataType::Varchar))
+            .collect()
+    }
+
     fn push_context(&mut self) {
         let new_context = std::mem::take(&mut self.context);
         self.upper_contexts.push(new_context);
@@ -30,7 +30,7 @@ mod window_table_function;
 pub use generate_series::BoundGenerateSeriesFunction;
 pub use join::BoundJoin;
 pub use subquery::BoundSubquery;
-pub use table_or_source::{BoundBaseTable, BoundSource, BoundTableSource};
+pub use table_or_source::{BoundBaseTable, BoundSource, BoundSystemTable, BoundTableSource};
 pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKind};
 
 /// A validated item that refers to a table-like entity, including base table, subquery, join, etc.
@@ -39,6 +39,7 @@ pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKin
 pub enum Relation {
     Source(Box<BoundSource>),
     BaseTable(Box<BoundBaseTable>),
+    SystemTable(Box<BoundSystemTable>),
     Subquery(Box<BoundSubquery>),
     Join(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
@@ -71,6 +72,30 @@ impl Binder {
         Self::resolve_double_name(name.0, "empty table name", DEFAULT_SCHEMA_NAME)
     }
 
+    /// Like [`Self::resolve_table_name`], but an unqualified name is resolved to the first schema
+    /// of the search path that contains such a relation, or the first schema if none does.
+    pub(super) fn resolve_relation_name(&self, name: ObjectName) -> Result<(String, String)> {
+        if name.0.len() != 1 {
+            return Self::resolve_table_name(name);
+        }
+        let (_, table_name) = Self::resolve_table_name(name)?;
+        let schema_name = self
+            .search_path
+            .iter()
+            .find(|schema_name| {
+                self.catalog
+                    .get_schema_by_name(&self.db_name, schema_name)
+                    .map_or(false, |schema| {
+                        schema.get_table_by_name(&table_name).is_some()
+                            || schema.get_source_by_name(&table_name).is_some()
+                    })
+            })
+            .or_else(|| self.search_path.first())
+            .map_or(DEFAULT_SCHEMA_NAME, String::as_str)
+            .to_string();
+        Ok((schema_name, table_name))
+    }
+
     /// return the ( `database_name`, `schema_name`)
     pub fn resolve_schema_name(
         default_db_name: &str,
@@ -93,6 +118,20 @@ impl Binder {
         Ok(database_name)
     }
 
+    /// return the `user_name`
+    pub fn resolve_user_name(name: ObjectName) -> Result<String> {
+        let mut identifiers = name.0;
+        if identifiers.len() > 1 {
+            return Err(internal_error("user name must contain 1 argument"));
+        }
+        let user_name = identifiers
+            .pop()
+            .ok_or_else(|| internal_error("empty user name"))?
+            .value;
+
+        Ok(user_name)
+    }
+
     /// Fill the [`BindContext`](super::BindContext) for table.
     pub(super) fn bind_context(
         &mut self,
@@ -157,7 +196,7 @@ impl Binder {
         match table_factor {
             TableFactor::Table { name, alias, args } => {
                 if args.is_empty() {
-                    let (schema_name, table_name) = Self::resolve_table_name(name)?;
+                    let (schema_name, table_name) = self.resolve_relation_name(name)?;
                     if let Some(bound_query) = self.cte_to_relation.get(&table_name) {
                         let (query, alias) = bound_query.clone();
                         self.bind_context(
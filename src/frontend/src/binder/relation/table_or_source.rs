@@ -16,10 +16,12 @@ use std::sync::Arc;
 
 use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::plan_common::ConflictBehavior;
 use risingwave_sqlparser::ast::{ObjectName, TableAlias};
 
 use crate::binder::{Binder, Relation};
 use crate::catalog::source_catalog::SourceCatalog;
+use crate::catalog::system_catalog::{SystemTableKind, SYSTEM_SCHEMA_NAME};
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{CatalogError, TableId};
 
@@ -37,6 +39,11 @@ pub struct BoundTableSource {
     pub name: String,       // explain-only
     pub source_id: TableId, // TODO: refactor to source id
     pub columns: Vec<ColumnDesc>,
+    /// Whether each of `columns` rejects NULL values.
+    pub not_null: Vec<bool>,
+    /// The SQL text of the DEFAULT expression of each of `columns`.
+    pub default_exprs: Vec<Option<String>>,
+    pub conflict_behavior: ConflictBehavior,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +51,11 @@ pub struct BoundSource {
     pub catalog: SourceCatalog,
 }
 
+#[derive(Debug, Clone)]
+pub struct BoundSystemTable {
+    pub kind: SystemTableKind,
+}
+
 impl From<&SourceCatalog> for BoundSource {
     fn from(s: &SourceCatalog) -> Self {
         Self { catalog: s.clone() }
@@ -57,6 +69,9 @@ impl Binder {
         table_name: &str,
         alias: Option<TableAlias>,
     ) -> Result<Relation> {
+        if schema_name == SYSTEM_SCHEMA_NAME {
+            return self.bind_system_table(table_name, alias);
+        }
         if schema_name == "pg_catalog" {
             // TODO: support pg_catalog.
             return Err(ErrorCode::NotImplemented(
@@ -112,6 +127,25 @@ impl Binder {
         Ok(ret)
     }
 
+    fn bind_system_table(
+        &mut self,
+        table_name: &str,
+        alias: Option<TableAlias>,
+    ) -> Result<Relation> {
+        let kind = SystemTableKind::from_name(table_name).ok_or_else(|| {
+            RwError::from(CatalogError::NotFound(
+                "system table",
+                table_name.to_string(),
+            ))
+        })?;
+        self.bind_context(
+            kind.columns().into_iter().map(|field| (false, field)),
+            table_name.to_string(),
+            alias,
+        )?;
+        Ok(Relation::SystemTable(Box::new(BoundSystemTable { kind })))
+    }
+
     fn resolve_table_indexes(
         &mut self,
         schema_name: &str,
@@ -159,19 +193,20 @@ impl Binder {
     }
 
     pub(crate) fn bind_table_source(&mut self, name: ObjectName) -> Result<BoundTableSource> {
-        let (schema_name, source_name) = Self::resolve_table_name(name)?;
+        let (schema_name, source_name) = self.resolve_relation_name(name)?;
         let source = self
             .catalog
             .get_source_by_name(&self.db_name, &schema_name, &source_name)?;
 
         let source_id = TableId::new(source.id);
 
-        let columns = source
-            .columns
-            .iter()
-            .filter(|c| !c.is_hidden)
+        let visible_columns = source.columns.iter().filter(|c| !c.is_hidden);
+        let columns = visible_columns
+            .clone()
             .map(|c| c.column_desc.clone())
             .collect();
+        let not_null = visible_columns.clone().map(|c| c.not_null).collect();
+        let default_exprs = visible_columns.map(|c| c.default_expr.clone()).collect();
 
         // Note(bugen): do not bind context here.
 
@@ -179,6 +214,9 @@ impl Binder {
             name: source_name,
             source_id,
             columns,
+            not_null,
+            default_exprs,
+            conflict_behavior: source.conflict_behavior,
         })
     }
 }
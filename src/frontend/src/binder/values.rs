@@ -50,7 +50,18 @@ impl Binder {
         let vec2d = values.0;
         let mut bound = vec2d
             .into_iter()
-            .map(|vec| vec.into_iter().map(|expr| self.bind_expr(expr)).collect())
+            .map(|vec| {
+                vec.into_iter()
+                    .enumerate()
+                    .map(|(i, expr)| {
+                        // Parameters take the types of the columns they're inserted into.
+                        if let Some(data_type) = expected_types.as_ref().and_then(|t| t.get(i)) {
+                            self.infer_param_type(&expr, data_type)?;
+                        }
+                        self.bind_expr(expr)
+                    })
+                    .collect()
+            })
             .collect::<Result<Vec<Vec<_>>>>()?;
         self.context.clause = None;
 
@@ -20,8 +20,10 @@ use risingwave_common::catalog::{CatalogVersion, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
 use risingwave_rpc_client::MetaClient;
 use tokio::sync::watch::Receiver;
@@ -68,11 +70,30 @@ pub trait CatalogWriter: Send + Sync {
 
     async fn create_source(&self, source: ProstSource) -> Result<()>;
 
-    async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()>;
+    async fn create_sink(&self, sink: ProstSink, graph: StreamFragmentGraph) -> Result<()>;
 
-    async fn drop_materialized_view(&self, table_id: TableId) -> Result<()>;
+    /// Drops a materialized source, and the relations depending on it if `cascade` is set.
+    async fn drop_materialized_source(
+        &self,
+        source_id: u32,
+        table_id: TableId,
+        cascade: bool,
+    ) -> Result<()>;
+
+    /// Drops a materialized view, and the relations depending on it if `cascade` is set.
+    async fn drop_materialized_view(&self, table_id: TableId, cascade: bool) -> Result<()>;
+
+    /// Drops a source, and the relations depending on it if `cascade` is set.
+    async fn drop_source(&self, source_id: u32, cascade: bool) -> Result<()>;
 
-    async fn drop_source(&self, source_id: u32) -> Result<()>;
+    async fn drop_sink(&self, sink_id: u32) -> Result<()>;
+
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        source_version: u64,
+        column: ProstColumnCatalog,
+    ) -> Result<()>;
 
     async fn drop_database(&self, database_id: u32) -> Result<()>;
 
@@ -141,21 +162,52 @@ impl CatalogWriter for CatalogWriterImpl {
         self.wait_version(version).await
     }
 
-    async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()> {
+    async fn create_sink(&self, sink: ProstSink, graph: StreamFragmentGraph) -> Result<()> {
+        let (_id, version) = self.meta_client.create_sink(sink, graph).await?;
+        self.wait_version(version).await
+    }
+
+    async fn drop_materialized_source(
+        &self,
+        source_id: u32,
+        table_id: TableId,
+        cascade: bool,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .drop_materialized_source(source_id, table_id, cascade)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn drop_materialized_view(&self, table_id: TableId, cascade: bool) -> Result<()> {
         let version = self
             .meta_client
-            .drop_materialized_source(source_id, table_id)
+            .drop_materialized_view(table_id, cascade)
             .await?;
         self.wait_version(version).await
     }
 
-    async fn drop_materialized_view(&self, table_id: TableId) -> Result<()> {
-        let version = self.meta_client.drop_materialized_view(table_id).await?;
+    async fn drop_source(&self, source_id: u32, cascade: bool) -> Result<()> {
+        let version = self.meta_client.drop_source(source_id, cascade).await?;
         self.wait_version(version).await
     }
 
-    async fn drop_source(&self, source_id: u32) -> Result<()> {
-        let version = self.meta_client.drop_source(source_id).await?;
+    async fn drop_sink(&self, sink_id: u32) -> Result<()> {
+        let version = self.meta_client.drop_sink(sink_id).await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        source_version: u64,
+        column: ProstColumnCatalog,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_source_add_column(source_id, source_version, column)
+            .await?;
         self.wait_version(version).await
     }
 
@@ -24,6 +24,10 @@ use super::row_id_column_desc;
 pub struct ColumnCatalog {
     pub column_desc: ColumnDesc,
     pub is_hidden: bool,
+    /// Whether the column rejects NULL values.
+    pub not_null: bool,
+    /// The SQL text of the DEFAULT expression of the column.
+    pub default_expr: Option<String>,
 }
 
 impl ColumnCatalog {
@@ -47,11 +51,23 @@ impl ColumnCatalog {
         self.column_desc.name.as_ref()
     }
 
+    /// Get the column catalog's not null.
+    pub fn not_null(&self) -> bool {
+        self.not_null
+    }
+
+    /// Get the SQL text of the column's default expression.
+    pub fn default_expr(&self) -> Option<&str> {
+        self.default_expr.as_deref()
+    }
+
     /// Convert column catalog to proto
     pub fn to_protobuf(&self) -> ProstColumnCatalog {
         ProstColumnCatalog {
             column_desc: Some(self.column_desc.to_protobuf()),
             is_hidden: self.is_hidden,
+            not_null: self.not_null,
+            default_expr: self.default_expr.clone().unwrap_or_default(),
         }
     }
 
@@ -60,6 +76,8 @@ impl ColumnCatalog {
         Self {
             column_desc: row_id_column_desc(),
             is_hidden: true,
+            not_null: false,
+            default_expr: None,
         }
     }
 
@@ -77,6 +95,8 @@ impl From<ProstColumnCatalog> for ColumnCatalog {
         Self {
             column_desc: prost.column_desc.unwrap().into(),
             is_hidden: prost.is_hidden,
+            not_null: prost.not_null,
+            default_expr: Some(prost.default_expr).filter(|expr| !expr.is_empty()),
         }
     }
 }
@@ -14,6 +14,7 @@
 use itertools::Itertools;
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::Source as ProstSource;
+use risingwave_pb::plan_common::ConflictBehavior;
 use risingwave_pb::stream_plan::source_node::SourceType;
 
 use super::column_catalog::ColumnCatalog;
@@ -27,6 +28,14 @@ pub struct SourceCatalog {
     pub columns: Vec<ColumnCatalog>,
     pub pk_col_ids: Vec<ColumnId>,
     pub source_type: SourceType,
+    /// How rows with an existing pk are handled. Only set for tables with a user-defined pk.
+    pub conflict_behavior: ConflictBehavior,
+    /// Whether the source only emits inserts. Tables are never append-only since they accept
+    /// `DELETE` and `UPDATE`.
+    pub append_only: bool,
+    /// The version of the columns, increased by each `ALTER SOURCE ADD COLUMN`. Always 0 for
+    /// tables.
+    pub version: u64,
 }
 
 impl SourceCatalog {
@@ -43,6 +52,8 @@ impl SourceCatalog {
                     .map(|c| ColumnCatalog {
                         column_desc: c,
                         is_hidden: col.is_hidden,
+                        not_null: col.not_null,
+                        default_expr: col.default_expr.clone(),
                     })
                     .collect_vec(),
             )
@@ -56,23 +67,38 @@ impl From<&ProstSource> for SourceCatalog {
     fn from(prost: &ProstSource) -> Self {
         let id = prost.id;
         let name = prost.name.clone();
-        let (source_type, prost_columns, pk_col_ids) = match &prost.info {
-            Some(Info::StreamSource(source)) => (
-                SourceType::Source,
-                source.columns.clone(),
-                source
-                    .pk_column_ids
-                    .iter()
-                    .map(|id| ColumnId::new(*id))
-                    .collect(),
-            ),
-            Some(Info::TableSource(source)) => (
-                SourceType::Table,
-                source.columns.clone(),
-                vec![TABLE_SOURCE_PK_COLID],
-            ),
-            None => unreachable!(),
-        };
+        let (source_type, prost_columns, pk_col_ids, conflict_behavior, append_only, version) =
+            match &prost.info {
+                Some(Info::StreamSource(source)) => (
+                    SourceType::Source,
+                    source.columns.clone(),
+                    source
+                        .pk_column_ids
+                        .iter()
+                        .map(|id| ColumnId::new(*id))
+                        .collect(),
+                    ConflictBehavior::NoCheck,
+                    source.append_only,
+                    source.version,
+                ),
+                Some(Info::TableSource(source)) => (
+                    SourceType::Table,
+                    source.columns.clone(),
+                    if source.pk_column_ids.is_empty() {
+                        vec![TABLE_SOURCE_PK_COLID]
+                    } else {
+                        source
+                            .pk_column_ids
+                            .iter()
+                            .map(|id| ColumnId::new(*id))
+                            .collect()
+                    },
+                    source.get_conflict_behavior().unwrap(),
+                    false,
+                    0,
+                ),
+                None => unreachable!(),
+            };
         let columns = prost_columns.into_iter().map(ColumnCatalog::from).collect();
         Self {
             id,
@@ -80,6 +106,9 @@ impl From<&ProstSource> for SourceCatalog {
             columns,
             pk_col_ids,
             source_type,
+            conflict_behavior,
+            append_only,
+            version,
         }
     }
 }
@@ -0,0 +1,100 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! System tables, which expose the states of the frontend node rather than the data in storage.
+//! They are evaluated when a query referencing them is planned.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use risingwave_common::catalog::Field;
+use risingwave_common::types::{DataType, Datum, NaiveDateTimeWrapper, ScalarImpl};
+
+use crate::session::SessionImpl;
+
+/// The schema of the system tables.
+pub const SYSTEM_SCHEMA_NAME: &str = "rw_catalog";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemTableKind {
+    /// The latest statements run on this frontend node. Only superusers see the ones of the other
+    /// users.
+    QueryLog,
+}
+
+impl SystemTableKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rw_query_log" => Some(Self::QueryLog),
+            _ => None,
+        }
+    }
+
+    pub fn columns(&self) -> Vec<Field> {
+        match self {
+            Self::QueryLog => vec![
+                Field::with_name(DataType::Int32, "session_id"),
+                Field::with_name(DataType::Varchar, "user_name"),
+                Field::with_name(DataType::Varchar, "statement"),
+                Field::with_name(DataType::Timestamp, "start_time"),
+                Field::with_name(DataType::Float64, "planning_time_ms"),
+                Field::with_name(DataType::Float64, "execution_time_ms"),
+                Field::with_name(DataType::Int64, "row_count"),
+                Field::with_name(DataType::Varchar, "error"),
+            ],
+        }
+    }
+
+    /// The current rows of the table visible to the user of `session`, in the order of
+    /// [`Self::columns`].
+    pub fn rows(&self, session: &SessionImpl) -> Vec<Vec<Datum>> {
+        let is_superuser = session
+            .env()
+            .user_info_reader()
+            .read_guard()
+            .get_user_by_name(session.user_name())
+            .map_or(false, |user| user.is_supper);
+        match self {
+            Self::QueryLog => session
+                .env()
+                .query_log()
+                .records()
+                .into_iter()
+                .filter(|record| is_superuser || record.user_name == session.user_name())
+                .map(|record| {
+                    let start_time = record.start_time.duration_since(UNIX_EPOCH).unwrap();
+                    vec![
+                        Some(ScalarImpl::Int32(record.session_id)),
+                        Some(ScalarImpl::Utf8(record.user_name)),
+                        Some(ScalarImpl::Utf8(record.statement)),
+                        Some(ScalarImpl::NaiveDateTime(
+                            NaiveDateTimeWrapper::with_secs_nsecs(
+                                start_time.as_secs() as i64,
+                                start_time.subsec_nanos(),
+                            )
+                            .unwrap(),
+                        )),
+                        record.planning_time.map(duration_ms),
+                        Some(duration_ms(record.execution_time)),
+                        Some(ScalarImpl::Int64(record.rows)),
+                        record.error.map(ScalarImpl::Utf8),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+fn duration_ms(duration: Duration) -> ScalarImpl {
+    ScalarImpl::Float64((duration.as_secs_f64() * 1000.0).into())
+}
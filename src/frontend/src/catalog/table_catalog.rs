@@ -123,6 +123,7 @@ impl TableCatalog {
                 .iter()
                 .map(|k| *k as i32)
                 .collect_vec(),
+            properties: HashMap::new(),
         }
     }
 }
@@ -219,6 +220,7 @@ mod tests {
                 ProstColumnCatalog {
                     column_desc: Some((&row_id_column_desc()).into()),
                     is_hidden: true,
+                    ..Default::default()
                 },
                 ProstColumnCatalog {
                     column_desc: Some(ProstColumnDesc::new_struct(
@@ -239,6 +241,7 @@ mod tests {
                         ],
                     )),
                     is_hidden: false,
+                    ..Default::default()
                 },
             ],
             order_column_ids: vec![0],
@@ -246,6 +249,7 @@ mod tests {
             orders: vec![OrderType::Ascending.to_prost() as i32],
             dependent_relations: vec![],
             distribution_keys: vec![],
+            properties: Default::default(),
             optional_associated_source_id: OptionalAssociatedSourceId::AssociatedSourceId(233)
                 .into(),
         }
@@ -285,7 +289,9 @@ mod tests {
                             ],
                             type_name: ".test.Country".to_string()
                         },
-                        is_hidden: false
+                        is_hidden: false,
+                        not_null: false,
+                        default_expr: None,
                     }
                 ],
                 pks: vec![0],
@@ -14,14 +14,96 @@
 
 //! Contains configurations that could be accessed via "set" command.
 
-use risingwave_common::error::ErrorCode::InvalidConfigValue;
-use risingwave_common::error::RwError;
+use std::collections::BTreeMap;
+use std::num::{NonZeroU32, NonZeroU64};
+use std::time::Duration;
+
+use chrono::FixedOffset;
+use itertools::Itertools;
+use risingwave_common::catalog::DEFAULT_SCHEMA_NAME;
+use risingwave_common::error::ErrorCode::{InvalidConfigValue, UnrecognizedConfigurationParameter};
+use risingwave_common::error::{Result, RwError};
 
 use crate::config::QueryMode::{Distributed, Local};
 
-pub static QUERY_MODE: &str = "query_mode";
+/// If `RW_IMPLICIT_FLUSH` is on, then every INSERT/UPDATE/DELETE statement will block
+/// until the entire dataflow is refreshed. In other words, every related table & MV will
+/// be able to see the write.
+pub const IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
+/// Force the planner to generate delta joins for inner joins. For testing purpose only.
+pub const DELTA_JOIN: &str = "RW_FORCE_DELTA_JOIN";
+pub const QUERY_MODE: &str = "query_mode";
+pub const TIMEZONE: &str = "timezone";
+pub const EXTRA_FLOAT_DIGITS: &str = "extra_float_digits";
+pub const SEARCH_PATH: &str = "search_path";
+/// Number of parallel tasks each non-root stage of a distributed batch query is split into.
+/// `0` means one task per worker node.
+pub const BATCH_PARALLELISM: &str = "batch_parallelism";
+/// Cancel any statement that runs longer than this. A plain number is in milliseconds, and `0`
+/// disables the timeout.
+pub const STATEMENT_TIMEOUT: &str = "statement_timeout";
+/// Fail any batch query whose nested loop join produces more rows than this. `0` means no limit.
+pub const BATCH_NESTED_LOOP_JOIN_MAX_ROWS: &str = "batch_nested_loop_join_max_rows";
+/// Number of sub-ranges each task of a batch table scan splits its key range into and reads
+/// concurrently. `1` scans the range sequentially.
+pub const BATCH_SCAN_PARALLELISM: &str = "batch_scan_parallelism";
+/// Log every statement that runs at least this long. A plain number is in milliseconds, `0` logs
+/// all statements and `-1` disables the logging.
+pub const LOG_MIN_DURATION_STATEMENT: &str = "log_min_duration_statement";
+/// Number of branches each streaming hash aggregation runs in within an actor, whose input is
+/// partitioned among them by the group keys. `1` runs it as a whole.
+pub const STREAMING_LOCAL_PARALLELISM: &str = "streaming_local_parallelism";
 
-#[derive(Debug, Clone)]
+/// Names and descriptions of all session variables, in the order of `SHOW ALL`.
+const VARIABLES: [(&str, &str); 12] = [
+    (
+        IMPLICIT_FLUSH,
+        "If enabled, every INSERT/UPDATE/DELETE waits until the dataflow is refreshed.",
+    ),
+    (
+        DELTA_JOIN,
+        "Force the planner to use delta joins. For testing purpose only.",
+    ),
+    (
+        QUERY_MODE,
+        "Sets the execution mode of batch queries: local or distributed.",
+    ),
+    (TIMEZONE, "Sets the time zone of the session."),
+    (
+        EXTRA_FLOAT_DIGITS,
+        "Sets the number of digits displayed for floating-point values.",
+    ),
+    (
+        SEARCH_PATH,
+        "Sets the schema search order for names that are not schema-qualified.",
+    ),
+    (
+        BATCH_PARALLELISM,
+        "Sets the parallelism of distributed batch queries. 0 means one task per worker.",
+    ),
+    (
+        STATEMENT_TIMEOUT,
+        "Sets the maximum allowed duration of any statement. 0 disables the timeout.",
+    ),
+    (
+        BATCH_NESTED_LOOP_JOIN_MAX_ROWS,
+        "Sets the maximum number of rows a nested loop join may produce. 0 means no limit.",
+    ),
+    (
+        BATCH_SCAN_PARALLELISM,
+        "Sets the number of key ranges each task of a table scan reads concurrently.",
+    ),
+    (
+        LOG_MIN_DURATION_STATEMENT,
+        "Sets the minimum duration of a statement to be logged. -1 disables the logging.",
+    ),
+    (
+        STREAMING_LOCAL_PARALLELISM,
+        "Sets the number of parallel branches of a streaming hash aggregation in each actor.",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QueryMode {
     Local,
     Distributed,
@@ -37,25 +119,423 @@ impl Default for QueryMode {
 impl<'a> TryFrom<&'a str> for QueryMode {
     type Error = RwError;
 
-    fn try_from(s: &'a str) -> Result<Self, RwError> {
+    fn try_from(s: &'a str) -> Result<Self> {
         if s.eq_ignore_ascii_case("local") {
             Ok(Local)
         } else if s.eq_ignore_ascii_case("distributed") {
             Ok(Distributed)
         } else {
-            Err(InvalidConfigValue {
-                config_entry: QUERY_MODE.to_string(),
-                config_value: s.to_string(),
-            })?
+            Err(invalid_value(QUERY_MODE, s))
+        }
+    }
+}
+
+impl std::fmt::Display for QueryMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Local => write!(f, "local"),
+            Distributed => write!(f, "distributed"),
+        }
+    }
+}
+
+/// A session variable as displayed by `SHOW ALL`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableInfo {
+    pub name: String,
+    pub setting: String,
+    pub description: String,
+}
+
+/// Typed values of all the session variables. Variable names are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct ConfigMap {
+    implicit_flush: bool,
+    delta_join: bool,
+    query_mode: QueryMode,
+    timezone: String,
+    extra_float_digits: i32,
+    search_path: Vec<String>,
+    batch_parallelism: Option<NonZeroU64>,
+    /// In milliseconds. `0` for no timeout.
+    statement_timeout: u64,
+    batch_nested_loop_join_max_rows: Option<NonZeroU64>,
+    batch_scan_parallelism: NonZeroU32,
+    /// In milliseconds. `None` if no statement is logged.
+    log_min_duration_statement: Option<u64>,
+    streaming_local_parallelism: NonZeroU32,
+    /// Variables that are not recognized, e.g., those of postgres or older versions that are set
+    /// by clients or scripts. They are accepted and shown as is, but have no effect.
+    unrecognized: BTreeMap<String, String>,
+}
+
+impl Default for ConfigMap {
+    fn default() -> Self {
+        Self {
+            implicit_flush: false,
+            delta_join: false,
+            query_mode: QueryMode::default(),
+            timezone: "UTC".to_string(),
+            extra_float_digits: 1,
+            search_path: vec![DEFAULT_SCHEMA_NAME.to_string()],
+            batch_parallelism: None,
+            statement_timeout: 0,
+            batch_nested_loop_join_max_rows: None,
+            batch_scan_parallelism: NonZeroU32::new(1).unwrap(),
+            log_min_duration_statement: None,
+            streaming_local_parallelism: NonZeroU32::new(1).unwrap(),
+            unrecognized: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConfigMap {
+    /// Whether `key` names a variable known to the system. Others can still be set, but have no
+    /// effect.
+    pub fn is_recognized(key: &str) -> bool {
+        Self::resolve_key(key).is_ok()
+    }
+
+    /// Set a variable. `values` holds more than one element only for list variables, e.g.
+    /// `SET search_path = a, b`.
+    pub fn set(&mut self, key: &str, values: &[String]) -> Result<()> {
+        let key = match Self::resolve_key(key) {
+            Ok(key) => key,
+            Err(_) => {
+                self.unrecognized
+                    .insert(key.to_ascii_lowercase(), values.join(", "));
+                return Ok(());
+            }
+        };
+        let single = || -> Result<&str> {
+            match values {
+                [value] => Ok(value),
+                _ => Err(invalid_value(key, &values.join(", "))),
+            }
+        };
+
+        match key {
+            IMPLICIT_FLUSH => self.implicit_flush = parse_bool(key, single()?)?,
+            DELTA_JOIN => self.delta_join = parse_bool(key, single()?)?,
+            QUERY_MODE => self.query_mode = single()?.try_into()?,
+            TIMEZONE => self.timezone = parse_timezone(single()?)?,
+            EXTRA_FLOAT_DIGITS => {
+                let value = single()?;
+                self.extra_float_digits = value
+                    .parse()
+                    .ok()
+                    .filter(|digits| (-15..=3).contains(digits))
+                    .ok_or_else(|| invalid_value(key, value))?;
+            }
+            SEARCH_PATH => {
+                if values.is_empty() || values.iter().any(|schema| schema.is_empty()) {
+                    return Err(invalid_value(key, &values.join(", ")));
+                }
+                self.search_path = values.to_vec();
+            }
+            BATCH_PARALLELISM => {
+                let value = single()?;
+                self.batch_parallelism = value
+                    .parse::<u64>()
+                    .map_err(|_| invalid_value(key, value))?
+                    .try_into()
+                    .ok();
+            }
+            STATEMENT_TIMEOUT => self.statement_timeout = parse_duration_ms(key, single()?)?,
+            BATCH_NESTED_LOOP_JOIN_MAX_ROWS => {
+                let value = single()?;
+                self.batch_nested_loop_join_max_rows = value
+                    .parse::<u64>()
+                    .map_err(|_| invalid_value(key, value))?
+                    .try_into()
+                    .ok();
+            }
+            BATCH_SCAN_PARALLELISM => {
+                let value = single()?;
+                self.batch_scan_parallelism =
+                    value.parse().map_err(|_| invalid_value(key, value))?;
+            }
+            LOG_MIN_DURATION_STATEMENT => {
+                let value = single()?;
+                self.log_min_duration_statement = match value.trim() {
+                    "-1" => None,
+                    _ => Some(parse_duration_ms(key, value)?),
+                };
+            }
+            STREAMING_LOCAL_PARALLELISM => {
+                let value = single()?;
+                self.streaming_local_parallelism =
+                    value.parse().map_err(|_| invalid_value(key, value))?;
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Reset a variable to its default value. `ALL` resets every variable.
+    pub fn reset(&mut self, key: &str) -> Result<()> {
+        if key.eq_ignore_ascii_case("all") {
+            *self = Self::default();
+            return Ok(());
         }
+
+        let key = match Self::resolve_key(key) {
+            Ok(key) => key,
+            Err(err) => {
+                return match self.unrecognized.remove(&key.to_ascii_lowercase()) {
+                    Some(_) => Ok(()),
+                    None => Err(err),
+                };
+            }
+        };
+        let default = Self::default();
+        match key {
+            IMPLICIT_FLUSH => self.implicit_flush = default.implicit_flush,
+            DELTA_JOIN => self.delta_join = default.delta_join,
+            QUERY_MODE => self.query_mode = default.query_mode,
+            TIMEZONE => self.timezone = default.timezone,
+            EXTRA_FLOAT_DIGITS => self.extra_float_digits = default.extra_float_digits,
+            SEARCH_PATH => self.search_path = default.search_path,
+            BATCH_PARALLELISM => self.batch_parallelism = default.batch_parallelism,
+            STATEMENT_TIMEOUT => self.statement_timeout = default.statement_timeout,
+            BATCH_NESTED_LOOP_JOIN_MAX_ROWS => {
+                self.batch_nested_loop_join_max_rows = default.batch_nested_loop_join_max_rows
+            }
+            BATCH_SCAN_PARALLELISM => self.batch_scan_parallelism = default.batch_scan_parallelism,
+            LOG_MIN_DURATION_STATEMENT => {
+                self.log_min_duration_statement = default.log_min_duration_statement
+            }
+            STREAMING_LOCAL_PARALLELISM => {
+                self.streaming_local_parallelism = default.streaming_local_parallelism
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Get the display value of a variable.
+    pub fn get(&self, key: &str) -> Result<String> {
+        let key = match Self::resolve_key(key) {
+            Ok(key) => key,
+            Err(err) => {
+                return self
+                    .unrecognized
+                    .get(&key.to_ascii_lowercase())
+                    .cloned()
+                    .ok_or(err);
+            }
+        };
+        let value = match key {
+            IMPLICIT_FLUSH => self.implicit_flush.to_string(),
+            DELTA_JOIN => self.delta_join.to_string(),
+            QUERY_MODE => self.query_mode.to_string(),
+            TIMEZONE => self.timezone.clone(),
+            EXTRA_FLOAT_DIGITS => self.extra_float_digits.to_string(),
+            SEARCH_PATH => self.search_path.iter().join(", "),
+            BATCH_PARALLELISM => self
+                .batch_parallelism
+                .map_or(0, NonZeroU64::get)
+                .to_string(),
+            STATEMENT_TIMEOUT => format_duration_ms(self.statement_timeout),
+            BATCH_NESTED_LOOP_JOIN_MAX_ROWS => self
+                .batch_nested_loop_join_max_rows
+                .map_or(0, NonZeroU64::get)
+                .to_string(),
+            BATCH_SCAN_PARALLELISM => self.batch_scan_parallelism.to_string(),
+            LOG_MIN_DURATION_STATEMENT => self
+                .log_min_duration_statement
+                .map_or_else(|| "-1".to_string(), format_duration_ms),
+            STREAMING_LOCAL_PARALLELISM => self.streaming_local_parallelism.to_string(),
+            _ => unreachable!(),
+        };
+        Ok(value)
+    }
+
+    /// List all the variables with their current values.
+    pub fn show_all(&self) -> Vec<VariableInfo> {
+        VARIABLES
+            .iter()
+            .map(|(name, description)| VariableInfo {
+                name: name.to_string(),
+                setting: self.get(name).unwrap(),
+                description: description.to_string(),
+            })
+            .collect()
+    }
+
+    pub fn implicit_flush(&self) -> bool {
+        self.implicit_flush
+    }
+
+    pub fn delta_join(&self) -> bool {
+        self.delta_join
+    }
+
+    pub fn query_mode(&self) -> QueryMode {
+        self.query_mode
+    }
+
+    pub fn timezone(&self) -> &str {
+        &self.timezone
+    }
+
+    /// The offset of the session time zone from UTC.
+    pub fn timezone_offset(&self) -> FixedOffset {
+        parse_timezone_offset(&self.timezone).expect("time zone is validated on set")
+    }
+
+    pub fn extra_float_digits(&self) -> i32 {
+        self.extra_float_digits
+    }
+
+    pub fn search_path(&self) -> &[String] {
+        &self.search_path
+    }
+
+    pub fn batch_parallelism(&self) -> Option<NonZeroU64> {
+        self.batch_parallelism
+    }
+
+    /// `None` if statements never time out.
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        (self.statement_timeout > 0).then(|| Duration::from_millis(self.statement_timeout))
+    }
+
+    /// `None` if nested loop joins may produce any number of rows.
+    pub fn batch_nested_loop_join_max_rows(&self) -> Option<NonZeroU64> {
+        self.batch_nested_loop_join_max_rows
+    }
+
+    pub fn batch_scan_parallelism(&self) -> NonZeroU32 {
+        self.batch_scan_parallelism
+    }
+
+    pub fn streaming_local_parallelism(&self) -> NonZeroU32 {
+        self.streaming_local_parallelism
+    }
+
+    /// `None` if no statement is logged for its duration.
+    pub fn log_min_duration_statement(&self) -> Option<Duration> {
+        self.log_min_duration_statement.map(Duration::from_millis)
+    }
+
+    /// Map a user-provided name to the canonical variable name.
+    fn resolve_key(key: &str) -> Result<&'static str> {
+        VARIABLES
+            .iter()
+            .map(|(name, _)| *name)
+            .find(|name| name.eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                UnrecognizedConfigurationParameter {
+                    config_entry: key.to_string(),
+                }
+                .into()
+            })
+    }
+}
+
+fn invalid_value(key: &str, value: &str) -> RwError {
+    InvalidConfigValue {
+        config_entry: key.to_string(),
+        config_value: value.to_string(),
+    }
+    .into()
+}
+
+/// Parse a boolean the way postgres does, e.g. `true`, `off`, `1`.
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(invalid_value(key, value)),
     }
 }
 
+/// Units of durations, from the largest to the smallest, with their lengths in milliseconds.
+const DURATION_UNITS: [(&str, u64); 5] = [
+    ("d", 24 * 60 * 60 * 1000),
+    ("h", 60 * 60 * 1000),
+    ("min", 60 * 1000),
+    ("s", 1000),
+    ("ms", 1),
+];
+
+/// Parse a duration like `1500`, `30s` or `5 min` into milliseconds. A plain number is in
+/// milliseconds.
+fn parse_duration_ms(key: &str, value: &str) -> Result<u64> {
+    let trimmed = value.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| invalid_value(key, value))?;
+    let unit_ms = match unit.trim() {
+        "" => 1,
+        unit => DURATION_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, ms)| *ms)
+            .ok_or_else(|| invalid_value(key, value))?,
+    };
+    number
+        .checked_mul(unit_ms)
+        .ok_or_else(|| invalid_value(key, value))
+}
+
+/// Display a duration in the largest unit that divides it, like postgres does.
+fn format_duration_ms(ms: u64) -> String {
+    if ms == 0 {
+        return "0".to_string();
+    }
+    let (unit, unit_ms) = DURATION_UNITS
+        .iter()
+        .find(|(_, unit_ms)| ms % unit_ms == 0)
+        .unwrap();
+    format!("{}{}", ms / unit_ms, unit)
+}
+
+/// Only `UTC` (and its aliases) and fixed offsets like `+08:00` are accepted for now.
+fn parse_timezone(value: &str) -> Result<String> {
+    if is_utc_alias(value) {
+        return Ok("UTC".to_string());
+    }
+    match parse_timezone_offset(value) {
+        Some(_) => Ok(value.to_string()),
+        None => Err(invalid_value(TIMEZONE, value)),
+    }
+}
+
+fn is_utc_alias(value: &str) -> bool {
+    ["utc", "gmt", "z"].contains(&value.to_ascii_lowercase().as_str())
+}
+
+/// Parse a time zone accepted by [`parse_timezone`] into its offset from UTC.
+fn parse_timezone_offset(value: &str) -> Option<FixedOffset> {
+    if is_utc_alias(value) {
+        return Some(FixedOffset::east(0));
+    }
+    let sign = match value.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let offset = &value[1..];
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let hours = hours.parse::<i32>().ok()?;
+    let minutes = minutes.parse::<i32>().ok()?;
+    if !(0..=15).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
 
-    use crate::config::QueryMode;
+    use super::*;
 
     #[test]
     fn parse_query_mode() {
@@ -65,4 +545,130 @@ mod tests {
         assert_matches!("diStributed".try_into().unwrap(), QueryMode::Distributed);
         assert!(QueryMode::try_from("ab").is_err());
     }
+
+    fn set(config: &mut ConfigMap, key: &str, value: &str) -> Result<()> {
+        config.set(key, &[value.to_string()])
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut config = ConfigMap::default();
+
+        set(&mut config, "QUERY_MODE", "local").unwrap();
+        assert_eq!(config.query_mode(), QueryMode::Local);
+        assert_eq!(config.get(QUERY_MODE).unwrap(), "local");
+
+        set(&mut config, "rw_implicit_flush", "on").unwrap();
+        assert!(config.implicit_flush());
+        assert!(set(&mut config, IMPLICIT_FLUSH, "maybe").is_err());
+
+        set(&mut config, EXTRA_FLOAT_DIGITS, "-3").unwrap();
+        assert_eq!(config.extra_float_digits(), -3);
+        assert!(set(&mut config, EXTRA_FLOAT_DIGITS, "4").is_err());
+
+        set(&mut config, TIMEZONE, "gmt").unwrap();
+        assert_eq!(config.timezone(), "UTC");
+        assert_eq!(config.timezone_offset(), FixedOffset::east(0));
+        set(&mut config, TIMEZONE, "+08:00").unwrap();
+        assert_eq!(config.timezone(), "+08:00");
+        assert_eq!(config.timezone_offset(), FixedOffset::east(8 * 3600));
+        set(&mut config, TIMEZONE, "-03:30").unwrap();
+        assert_eq!(
+            config.timezone_offset(),
+            FixedOffset::west(3 * 3600 + 30 * 60)
+        );
+        assert!(set(&mut config, TIMEZONE, "Mars/Olympus").is_err());
+
+        config
+            .set(SEARCH_PATH, &["s1".to_string(), "public".to_string()])
+            .unwrap();
+        assert_eq!(config.get(SEARCH_PATH).unwrap(), "s1, public");
+
+        set(&mut config, BATCH_PARALLELISM, "4").unwrap();
+        assert_eq!(config.batch_parallelism().unwrap().get(), 4);
+        set(&mut config, BATCH_PARALLELISM, "0").unwrap();
+        assert_eq!(config.batch_parallelism(), None);
+        assert!(set(&mut config, BATCH_PARALLELISM, "-1").is_err());
+
+        assert_eq!(config.statement_timeout(), None);
+        set(&mut config, STATEMENT_TIMEOUT, "1500").unwrap();
+        assert_eq!(
+            config.statement_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(config.get(STATEMENT_TIMEOUT).unwrap(), "1500ms");
+        set(&mut config, STATEMENT_TIMEOUT, "2 min").unwrap();
+        assert_eq!(config.statement_timeout(), Some(Duration::from_secs(120)));
+        assert_eq!(config.get(STATEMENT_TIMEOUT).unwrap(), "2min");
+        set(&mut config, STATEMENT_TIMEOUT, "0").unwrap();
+        assert_eq!(config.statement_timeout(), None);
+        assert!(set(&mut config, STATEMENT_TIMEOUT, "10 years").is_err());
+        assert!(set(&mut config, STATEMENT_TIMEOUT, "-1").is_err());
+
+        set(&mut config, BATCH_NESTED_LOOP_JOIN_MAX_ROWS, "1000").unwrap();
+        assert_eq!(
+            config.batch_nested_loop_join_max_rows().unwrap().get(),
+            1000
+        );
+        set(&mut config, BATCH_NESTED_LOOP_JOIN_MAX_ROWS, "0").unwrap();
+        assert_eq!(config.batch_nested_loop_join_max_rows(), None);
+        assert!(set(&mut config, BATCH_NESTED_LOOP_JOIN_MAX_ROWS, "many").is_err());
+
+        assert_eq!(config.batch_scan_parallelism().get(), 1);
+        set(&mut config, BATCH_SCAN_PARALLELISM, "8").unwrap();
+        assert_eq!(config.batch_scan_parallelism().get(), 8);
+        assert!(set(&mut config, BATCH_SCAN_PARALLELISM, "0").is_err());
+
+        assert_eq!(config.log_min_duration_statement(), None);
+        assert_eq!(config.get(LOG_MIN_DURATION_STATEMENT).unwrap(), "-1");
+        set(&mut config, LOG_MIN_DURATION_STATEMENT, "250ms").unwrap();
+        assert_eq!(
+            config.log_min_duration_statement(),
+            Some(Duration::from_millis(250))
+        );
+        set(&mut config, LOG_MIN_DURATION_STATEMENT, "0").unwrap();
+        assert_eq!(config.log_min_duration_statement(), Some(Duration::ZERO));
+        set(&mut config, LOG_MIN_DURATION_STATEMENT, "-1").unwrap();
+        assert_eq!(config.log_min_duration_statement(), None);
+        assert!(set(&mut config, LOG_MIN_DURATION_STATEMENT, "-2").is_err());
+
+        assert_eq!(config.streaming_local_parallelism().get(), 1);
+        set(&mut config, STREAMING_LOCAL_PARALLELISM, "4").unwrap();
+        assert_eq!(config.streaming_local_parallelism().get(), 4);
+        assert!(set(&mut config, STREAMING_LOCAL_PARALLELISM, "0").is_err());
+
+        // Unrecognized variables are kept as is.
+        assert!(!ConfigMap::is_recognized(
+            "enable_new_subquery_batch_planner"
+        ));
+        set(&mut config, "Enable_New_Subquery_Batch_Planner", "on").unwrap();
+        assert_eq!(
+            config.get("enable_new_subquery_batch_planner").unwrap(),
+            "on"
+        );
+        assert!(config.get("no_such_variable").is_err());
+        assert!(set(&mut config, EXTRA_FLOAT_DIGITS, "no").is_err());
+        // A failed `SET` leaves the old value untouched.
+        assert_eq!(config.extra_float_digits(), -3);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut config = ConfigMap::default();
+        set(&mut config, QUERY_MODE, "local").unwrap();
+        set(&mut config, EXTRA_FLOAT_DIGITS, "0").unwrap();
+
+        config.reset("query_mode").unwrap();
+        assert_eq!(config.query_mode(), QueryMode::Distributed);
+        assert_eq!(config.extra_float_digits(), 0);
+
+        config.reset("ALL").unwrap();
+        assert_eq!(config.extra_float_digits(), 1);
+        assert!(config.reset("no_such_variable").is_err());
+        set(&mut config, "no_such_variable", "1").unwrap();
+        config.reset("no_such_variable").unwrap();
+        assert!(config.get("no_such_variable").is_err());
+
+        assert_eq!(config.show_all().len(), VARIABLES.len());
+    }
 }
@@ -82,6 +82,18 @@ impl AggCall {
             },
             (AggKind::Avg, _) => return invalid(),
 
+            // StddevSamp, VarPop
+            (AggKind::StddevSamp | AggKind::VarPop, [input]) => match input {
+                DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Decimal
+                | DataType::Float32
+                | DataType::Float64 => DataType::Float64,
+                _ => return invalid(),
+            },
+            (AggKind::StddevSamp | AggKind::VarPop, _) => return invalid(),
+
             // Sum
             (AggKind::Sum, [input]) => match input {
                 DataType::Int16 => DataType::Int64,
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_common::types::{datum_to_protobuf, DataType, Datum, ScalarImpl};
 use risingwave_pb::expr::expr_node::RexNode;
 
 use super::Expr;
@@ -74,12 +74,7 @@ impl Expr for Literal {
 
 /// Convert a literal value (datum) into protobuf.
 fn literal_to_protobuf(d: &Datum) -> Option<RexNode> {
-    let Some(d) = d.as_ref() else {
-        return None;
-    };
-    use risingwave_pb::expr::*;
-    let body = d.to_protobuf();
-    Some(RexNode::Constant(ConstantValue { body }))
+    datum_to_protobuf(d).map(RexNode::Constant)
 }
 
 #[cfg(test)]
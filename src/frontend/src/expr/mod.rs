@@ -25,6 +25,7 @@ mod correlated_input_ref;
 mod function_call;
 mod input_ref;
 mod literal;
+mod parameter;
 mod subquery;
 
 mod expr_rewriter;
@@ -37,6 +38,7 @@ pub use correlated_input_ref::CorrelatedInputRef;
 pub use function_call::FunctionCall;
 pub use input_ref::{as_alias_display, input_ref_to_column_indices, InputRef, InputRefDisplay};
 pub use literal::Literal;
+pub use parameter::Parameter;
 pub use subquery::{Subquery, SubqueryKind};
 
 pub type ExprType = risingwave_pb::expr::expr_node::Type;
@@ -64,6 +66,7 @@ pub enum ExprImpl {
     FunctionCall(Box<FunctionCall>),
     AggCall(Box<AggCall>),
     Subquery(Box<Subquery>),
+    Parameter(Box<Parameter>),
 }
 
 impl ExprImpl {
@@ -148,7 +151,7 @@ macro_rules! impl_has_variant {
     };
 }
 
-impl_has_variant! {InputRef, Literal, FunctionCall, AggCall, Subquery}
+impl_has_variant! {InputRef, Literal, FunctionCall, AggCall, Subquery, Parameter}
 
 impl ExprImpl {
     // We need to traverse inside subqueries.
@@ -192,7 +195,9 @@ impl ExprImpl {
 
     /// Checks whether this is a constant expr that can be evaluated over a dummy chunk.
     /// Equivalent to `!has_input_ref && !has_agg_call && !has_subquery &&
-    /// !has_correlated_input_ref` but checks them in one pass.
+    /// !has_correlated_input_ref` but checks them in one pass. `now()` is not a constant as it
+    /// depends on the epoch it's evaluated in. Parameters are constants, as they're substituted
+    /// with their values before evaluation.
     pub fn is_const(&self) -> bool {
         struct Has {
             has: bool,
@@ -200,7 +205,10 @@ impl ExprImpl {
         impl ExprVisitor for Has {
             fn visit_expr(&mut self, expr: &ExprImpl) {
                 match expr {
-                    ExprImpl::Literal(_inner) => {}
+                    ExprImpl::Literal(_) | ExprImpl::Parameter(_) => {}
+                    ExprImpl::FunctionCall(inner) if inner.get_expr_type() == ExprType::Now => {
+                        self.has = true
+                    }
                     ExprImpl::FunctionCall(inner) => self.visit_function_call(inner),
                     _ => self.has = true,
                 }
@@ -221,6 +229,7 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(expr) => expr.return_type(),
             ExprImpl::Subquery(expr) => expr.return_type(),
             ExprImpl::CorrelatedInputRef(expr) => expr.return_type(),
+            ExprImpl::Parameter(expr) => expr.return_type(),
         }
     }
 
@@ -232,6 +241,7 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(e) => e.to_expr_proto(),
             ExprImpl::Subquery(e) => e.to_expr_proto(),
             ExprImpl::CorrelatedInputRef(e) => e.to_expr_proto(),
+            ExprImpl::Parameter(e) => e.to_expr_proto(),
         }
     }
 }
@@ -272,6 +282,12 @@ impl From<CorrelatedInputRef> for ExprImpl {
     }
 }
 
+impl From<Parameter> for ExprImpl {
+    fn from(parameter: Parameter) -> Self {
+        ExprImpl::Parameter(Box::new(parameter))
+    }
+}
+
 impl From<Condition> for ExprImpl {
     fn from(c: Condition) -> Self {
         merge_expr_by_binary(
@@ -297,6 +313,7 @@ impl std::fmt::Debug for ExprImpl {
                 Self::CorrelatedInputRef(arg0) => {
                     f.debug_tuple("CorrelatedInputRef").field(arg0).finish()
                 }
+                Self::Parameter(arg0) => f.debug_tuple("Parameter").field(arg0).finish(),
             };
         }
         match self {
@@ -306,6 +323,7 @@ impl std::fmt::Debug for ExprImpl {
             Self::AggCall(x) => write!(f, "{:?}", x),
             Self::Subquery(x) => write!(f, "{:?}", x),
             Self::CorrelatedInputRef(x) => write!(f, "{:?}", x),
+            Self::Parameter(x) => write!(f, "{:?}", x),
         }
     }
 }
@@ -0,0 +1,71 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::fmt;
+
+use risingwave_common::types::DataType;
+use risingwave_pb::expr::expr_node::RexNode;
+use risingwave_pb::expr::{ExprNode, ParameterExpr};
+
+use super::{Expr, ExprType};
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+/// A parameter `$index` of a prepared statement, whose value is unknown until the statement is
+/// executed. Plans with parameters are cached and reused for every execution, with the parameters
+/// substituted in the serialized plan.
+///
+/// `index` starts from 1.
+pub struct Parameter {
+    index: u64,
+    data_type: DataType,
+}
+
+impl Parameter {
+    pub fn new(index: u64, data_type: DataType) -> Self {
+        Parameter { index, data_type }
+    }
+
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl Expr for Parameter {
+    fn return_type(&self) -> DataType {
+        self.data_type.clone()
+    }
+
+    fn to_expr_proto(&self) -> ExprNode {
+        ExprNode {
+            expr_type: ExprType::Parameter as i32,
+            return_type: Some(self.return_type().to_protobuf()),
+            rex_node: Some(RexNode::Parameter(ParameterExpr {
+                index: self.index as u32,
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("Parameter")
+                .field("index", &self.index)
+                .field("data_type", &self.data_type)
+                .finish()
+        } else {
+            write!(f, "${}:{:?}", self.index, self.data_type)
+        }
+    }
+}
@@ -256,6 +256,8 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         T::Decimal,
     );
 
+    map.insert(FuncSign::new(E::Sqrt, vec![T::Float64]), T::Float64);
+
     build_round_funcs(&mut map, E::Round);
     build_round_funcs(&mut map, E::Ceil);
     build_round_funcs(&mut map, E::Floor);
@@ -286,6 +288,11 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         map.insert(FuncSign::new(E::Divide, vec![T::Interval, t]), T::Interval);
     }
 
+    for e in [E::JustifyHours, E::JustifyDays, E::JustifyInterval] {
+        map.insert(FuncSign::new(e, vec![T::Interval]), T::Interval);
+    }
+    map.insert(FuncSign::new(E::Now, vec![]), T::Timestamp);
+
     for t in [T::Timestamp, T::Time, T::Date] {
         map.insert(FuncSign::new(E::Extract, vec![T::Varchar, t]), T::Decimal);
     }
@@ -0,0 +1,275 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An [Arrow Flight](https://arrow.apache.org/docs/format/Flight.html) endpoint to export the
+//! results of queries in Arrow, so that they can be pulled into data frames without converting
+//! each value to text like pgwire does.
+//!
+//! The ticket of `DoGet` is the SQL of the query. The user is given by the `authorization` header
+//! of basic authentication, and the database by the `database` header.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow_flight::flight_descriptor::DescriptorType;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
+    HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use futures::{stream, Stream, StreamExt};
+use pgwire::pg_server::{Session, SessionManager};
+use risingwave_common::array::arrow::to_arrow_schema;
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
+use risingwave_common::error::tonic_err;
+use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::Parser;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::handler::query::{describe_query_schema, handle_query_for_chunks};
+use crate::session::{SessionImpl, SessionManagerImpl};
+
+type BoxedFlightStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+pub struct FlightServiceImpl {
+    session_mgr: Arc<SessionManagerImpl>,
+}
+
+impl FlightServiceImpl {
+    pub fn new(session_mgr: Arc<SessionManagerImpl>) -> Self {
+        Self { session_mgr }
+    }
+
+    /// Opens a session for the user given in the headers of the request.
+    fn connect(&self, metadata: &MetadataMap) -> Result<Arc<SessionImpl>, Status> {
+        let database = match metadata.get("database") {
+            Some(database) => database
+                .to_str()
+                .map_err(|_| Status::invalid_argument("invalid database header"))?,
+            None => DEFAULT_DATABASE_NAME,
+        };
+        let (user_name, password) = match metadata.get("authorization") {
+            Some(authorization) => parse_basic_auth(
+                authorization
+                    .to_str()
+                    .map_err(|_| Status::unauthenticated("invalid authorization header"))?,
+            )?,
+            None => (DEFAULT_SUPPER_USER.to_string(), String::new()),
+        };
+        let session = self
+            .session_mgr
+            .connect(database, &user_name)
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        // A Flight query can't be cancelled by a `CancelRequest` of pgwire, so the session doesn't
+        // have to be tracked.
+        self.session_mgr.end_session(&session);
+        if !session
+            .user_authenticator()
+            .authenticate_plaintext(&user_name, &password)
+        {
+            return Err(Status::unauthenticated(format!(
+                "password authentication failed for user {}",
+                user_name
+            )));
+        }
+        Ok(session)
+    }
+}
+
+/// Parses the user name and password of `Basic <base64 of user:password>`.
+fn parse_basic_auth(authorization: &str) -> Result<(String, String), Status> {
+    let credentials = authorization
+        .strip_prefix("Basic ")
+        .and_then(|encoded| base64::decode(encoded).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or_else(|| Status::unauthenticated("expect basic authentication"))?;
+    let (user_name, password) = credentials.split_once(':').unwrap_or((&credentials, ""));
+    Ok((user_name.to_string(), password.to_string()))
+}
+
+/// Parses the query carried by a ticket or a descriptor.
+fn parse_query(sql: &[u8]) -> Result<Statement, Status> {
+    let sql =
+        std::str::from_utf8(sql).map_err(|_| Status::invalid_argument("the query is not UTF-8"))?;
+    let mut stmts = Parser::parse_sql(sql).map_err(|e| Status::invalid_argument(e.to_string()))?;
+    if stmts.len() != 1 {
+        return Err(Status::invalid_argument("expect exactly one query"));
+    }
+    Ok(stmts.swap_remove(0))
+}
+
+fn descriptor_query(descriptor: &FlightDescriptor) -> Result<Statement, Status> {
+    if descriptor.r#type != DescriptorType::Cmd as i32 {
+        return Err(Status::invalid_argument(
+            "expect a command descriptor carrying the query",
+        ));
+    }
+    parse_query(&descriptor.cmd)
+}
+
+#[async_trait::async_trait]
+impl FlightService for FlightServiceImpl {
+    type DoActionStream = BoxedFlightStream<arrow_flight::Result>;
+    type DoExchangeStream = BoxedFlightStream<FlightData>;
+    type DoGetStream = BoxedFlightStream<FlightData>;
+    type DoPutStream = BoxedFlightStream<PutResult>;
+    type HandshakeStream = BoxedFlightStream<HandshakeResponse>;
+    type ListActionsStream = BoxedFlightStream<ActionType>;
+    type ListFlightsStream = BoxedFlightStream<FlightInfo>;
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        let session = self.connect(request.metadata())?;
+        let stmt = descriptor_query(request.get_ref())?;
+        let schema = describe_query_schema(session, stmt).map_err(tonic_err)?;
+        let schema = to_arrow_schema(&schema).map_err(tonic_err)?;
+        Ok(Response::new(
+            SchemaAsIpc::new(&schema, &IpcWriteOptions::default()).into(),
+        ))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.connect(request.metadata())?;
+        let descriptor = request.into_inner();
+        let stmt = descriptor_query(&descriptor)?;
+        let schema = describe_query_schema(session, stmt).map_err(tonic_err)?;
+        let schema = to_arrow_schema(&schema).map_err(tonic_err)?;
+        let IpcMessage(schema) =
+            IpcMessage::try_from(SchemaAsIpc::new(&schema, &IpcWriteOptions::default()))
+                .map_err(|e| Status::internal(e.to_string()))?;
+        // The results are fetched from this node with the query as the ticket.
+        let endpoint = FlightEndpoint {
+            ticket: Some(Ticket {
+                ticket: descriptor.cmd.clone(),
+            }),
+            location: vec![],
+        };
+        Ok(Response::new(FlightInfo {
+            schema,
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![endpoint],
+            total_records: -1,
+            total_bytes: -1,
+        }))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let session = self.connect(request.metadata())?;
+        let stmt = parse_query(&request.get_ref().ticket)?;
+        let (schema, data_stream) = handle_query_for_chunks(session, stmt)
+            .await
+            .map_err(tonic_err)?;
+
+        let options = IpcWriteOptions::default();
+        let arrow_schema = to_arrow_schema(&schema).map_err(tonic_err)?;
+        let schema_data: FlightData = SchemaAsIpc::new(&arrow_schema, &options).into();
+        let batch_data = data_stream.map(move |chunk| -> Result<FlightData, Status> {
+            let batch = chunk.and_then(|chunk| chunk.to_record_batch(&schema));
+            // No dictionary is used, as strings are not dictionary-encoded in the batches.
+            let (_, batch_data) =
+                flight_data_from_arrow_batch(&batch.map_err(tonic_err)?, &options);
+            Ok(batch_data)
+        });
+        Ok(Response::new(
+            stream::once(async { Ok(schema_data) })
+                .chain(batch_data)
+                .boxed(),
+        ))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented(
+            "handshake is not supported, authenticate with the authorization header instead",
+        ))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights is not supported"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put is not supported"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action is not supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Ok(Response::new(stream::empty().boxed()))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange is not supported"))
+    }
+}
+
+/// Serves Arrow Flight at `listen_addr` until the process exits.
+pub async fn flight_serve(
+    listen_addr: SocketAddr,
+    session_mgr: Arc<SessionManagerImpl>,
+) -> Result<(), tonic::transport::Error> {
+    tracing::info!("Arrow Flight serving at {}", listen_addr);
+    tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(FlightServiceImpl::new(
+            session_mgr,
+        )))
+        .serve(listen_addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_basic_auth() {
+        let header = format!("Basic {}", base64::encode("user:p:wd"));
+        assert_eq!(
+            parse_basic_auth(&header).unwrap(),
+            ("user".to_string(), "p:wd".to_string())
+        );
+        assert!(parse_basic_auth("Bearer token").is_err());
+    }
+}
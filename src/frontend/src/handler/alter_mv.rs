@@ -0,0 +1,66 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_sqlparser::ast::{AlterMaterializedViewOperation, ObjectName};
+
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+
+pub(super) async fn handle_alter_mv(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    operation: AlterMaterializedViewOperation,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+
+    let table_id = {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+
+        // If associated source is `Some`, then it is a actually a materialized source / table v2.
+        if table.associated_source_id().is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "cannot alter the parallelism of a table".to_owned(),
+            )));
+        }
+
+        // If is index on is `Some`, then it is a actually an index.
+        if table.is_index_on.is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "cannot alter the parallelism of an index".to_owned(),
+            )));
+        }
+        table.id()
+    };
+
+    match operation {
+        AlterMaterializedViewOperation::SetParallelism { parallelism } => {
+            let parallelism = u32::try_from(parallelism).map_err(|_| {
+                ErrorCode::InvalidInputSyntax(format!("parallelism {} is too large", parallelism))
+            })?;
+            session
+                .env()
+                .meta_client()
+                .alter_parallelism(table_id, parallelism)
+                .await?;
+        }
+    }
+
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
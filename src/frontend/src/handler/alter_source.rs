@@ -0,0 +1,152 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::ColumnId;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
+use risingwave_pb::stream_plan::source_node::SourceType;
+use risingwave_sqlparser::ast::{AlterSourceOperation, ObjectName};
+
+use super::create_table::bind_sql_column;
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+
+/// Adds a column to a source. The records without the field get NULL in the new column. The
+/// materialized views already reading the source can't pick up the column, so meta rejects the
+/// sources being read.
+pub async fn handle_alter_source(
+    context: OptimizerContext,
+    name: ObjectName,
+    operation: AlterSourceOperation,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, source_name) = Binder::resolve_table_name(name)?;
+
+    let catalog_reader = session.env().catalog_reader();
+    let source = catalog_reader
+        .read_guard()
+        .get_source_by_name(session.database(), &schema_name, &source_name)?
+        .clone();
+    if source.source_type == SourceType::Table {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+            "Use `ALTER TABLE` to alter a table.".to_owned(),
+        )));
+    }
+    let is_materialized = catalog_reader
+        .read_guard()
+        .get_table_by_name(session.database(), &schema_name, &source_name)
+        .is_ok();
+    if is_materialized {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+            "Materialized source \"{}\" can't be altered.",
+            source_name
+        ))));
+    }
+
+    let AlterSourceOperation::AddColumn { column_def } = operation;
+    if source
+        .columns
+        .iter()
+        .any(|c| c.name() == column_def.name.value)
+    {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+            "column \"{}\" of source \"{}\" already exists",
+            column_def.name.value, source_name
+        ))));
+    }
+    let column_id = source
+        .columns
+        .iter()
+        .map(|c| c.column_id().get_id())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let column = ProstColumnCatalog {
+        column_desc: Some(bind_sql_column(column_def, ColumnId::new(column_id))?.to_protobuf()),
+        is_hidden: false,
+        ..Default::default()
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .alter_source_add_column(source.id, source.version, column)
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::ALTER_SOURCE))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{ColumnId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::types::DataType;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_source_add_column() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE SOURCE s (v1 INT) ROW FORMAT JSON")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("ALTER SOURCE s ADD COLUMN v2 VARCHAR")
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        assert_eq!(source.version, 1);
+        let columns = source
+            .columns
+            .iter()
+            .map(|c| (c.name().to_string(), c.column_id(), c.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns[1..],
+            [
+                ("v1".to_string(), ColumnId::new(1), DataType::Int32),
+                ("v2".to_string(), ColumnId::new(2), DataType::Varchar),
+            ]
+        );
+
+        assert_eq!(
+            frontend
+                .run_sql("ALTER SOURCE s ADD COLUMN v1 INT")
+                .await
+                .unwrap_err()
+                .to_string(),
+            "Invalid input syntax: column \"v1\" of source \"s\" already exists"
+        );
+
+        frontend
+            .run_sql("CREATE MATERIALIZED SOURCE ms (v INT) ROW FORMAT JSON")
+            .await
+            .unwrap();
+        assert_eq!(
+            frontend
+                .run_sql("ALTER SOURCE ms ADD COLUMN v2 INT")
+                .await
+                .unwrap_err()
+                .to_string(),
+            "Invalid input syntax: Materialized source \"ms\" can't be altered."
+        );
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::{InternalError, PermissionDenied};
+use risingwave_common::error::Result;
+use risingwave_sqlparser::ast::{Ident, SetVariableValue, Value};
+
+use crate::session::OptimizerContext;
+
+pub(super) async fn handle_alter_system(
+    context: OptimizerContext,
+    param: Ident,
+    value: SetVariableValue,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    {
+        let user_reader = session.env().user_info_reader().read_guard();
+        let session_user = user_reader
+            .get_user_by_name(session.user_name())
+            .ok_or_else(|| InternalError("Session user is invalid".to_string()))?;
+        if !session_user.is_supper {
+            return Err(
+                PermissionDenied("Only superusers can alter system params".to_string()).into(),
+            );
+        }
+    }
+
+    let value = match value {
+        SetVariableValue::Literal(Value::SingleQuotedString(s)) => s,
+        _ => value.to_string(),
+    };
+    session
+        .env()
+        .meta_client()
+        .set_system_param(param.value, value)
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::ALTER_SYSTEM))
+}
@@ -12,18 +12,68 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::PROPERTIES_TTL_KEY;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query};
+use risingwave_sqlparser::ast::{ObjectName, Query, SqlOption, Value};
 
-use crate::binder::{Binder, BoundSetExpr};
+use crate::binder::{Binder, BoundQuery, BoundSetExpr};
 use crate::optimizer::property::RequiredDist;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
 
+/// Check that every output column of the query has a name, as it will become a column of the
+/// created relation.
+pub(crate) fn check_column_aliases(query: &BoundQuery) -> Result<()> {
+    if let BoundSetExpr::Select(select) = &query.body {
+        // `InputRef`'s alias will be implicitly assigned in `bind_project`.
+        // For other expressions, we require the user to explicitly assign an alias.
+        if select.aliases.iter().any(Option::is_none) {
+            return Err(ErrorCode::BindError(
+                "An alias must be specified for an expression".to_string(),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Binds the `WITH` options of CREATE MATERIALIZED VIEW to the properties of the table. Only
+/// `ttl`, the time-to-live of the states in seconds, is supported so far.
+fn bind_mv_properties(with_options: &[SqlOption]) -> Result<HashMap<String, String>> {
+    with_options
+        .iter()
+        .map(|option| {
+            let name = option.name.value.to_lowercase();
+            if name != PROPERTIES_TTL_KEY {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "unsupported option of materialized view: {}",
+                    option.name
+                ))
+                .into());
+            }
+            let ttl = match &option.value {
+                Value::Number(value, _) | Value::SingleQuotedString(value) => {
+                    value.parse::<u32>().ok()
+                }
+                _ => None,
+            }
+            .ok_or_else(|| {
+                ErrorCode::InvalidParameterValue(format!(
+                    "{} must be a non-negative integer of seconds, got {}",
+                    PROPERTIES_TTL_KEY, option.value
+                ))
+            })?;
+            Ok((name, ttl.to_string()))
+        })
+        .collect()
+}
+
 /// Generate create MV plan, return plan and mv table info.
 pub fn gen_create_mv_plan(
     session: &SessionImpl,
@@ -42,20 +92,12 @@ pub fn gen_create_mv_plan(
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
-        );
+        )
+        .with_search_path(session.config().search_path().to_vec());
         binder.bind_query(*query)?
     };
 
-    if let BoundSetExpr::Select(select) = &bound.body {
-        // `InputRef`'s alias will be implicitly assigned in `bind_project`.
-        // For other expressions, we require the user to explicitly assign an alias.
-        if select.aliases.iter().any(Option::is_none) {
-            return Err(ErrorCode::BindError(
-                "An alias must be specified for an expression".to_string(),
-            )
-            .into());
-        }
-    }
+    check_column_aliases(&bound)?;
 
     let mut plan_root = Planner::new(context).plan_query(bound)?;
     plan_root.set_required_dist(RequiredDist::Any);
@@ -70,11 +112,14 @@ pub async fn handle_create_mv(
     context: OptimizerContext,
     name: ObjectName,
     query: Box<Query>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    let properties = bind_mv_properties(&with_options)?;
 
     let (table, graph) = {
-        let (plan, table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        let (plan, mut table) = gen_create_mv_plan(&session, context.into(), query, name)?;
+        table.properties = properties;
         let stream_plan = plan.to_stream_prost();
         let graph = StreamFragmenter::build_graph(stream_plan);
 
@@ -196,4 +241,29 @@ pub mod tests {
             "Bind error: An alias must be specified for an expression"
         );
     }
+
+    #[tokio::test]
+    async fn test_create_mv_with_ttl() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("create table t(x int)").await.unwrap();
+
+        frontend
+            .run_sql("create materialized view mv1 with (ttl = 3600) as select x from t")
+            .await
+            .unwrap();
+
+        let sql = "create materialized view mv2 with (ttl = 'abc') as select x from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: ttl must be a non-negative integer of seconds, got 'abc'"
+        );
+
+        let sql = "create materialized view mv2 with (retention = 3600) as select x from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: unsupported option of materialized view: retention"
+        );
+    }
 }
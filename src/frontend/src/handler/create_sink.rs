@@ -0,0 +1,189 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::catalog::Sink as ProstSink;
+use risingwave_sqlparser::ast::{
+    CreateSinkStatement, ObjectName, Query, Select, SelectItem, SetExpr, TableFactor,
+    TableWithJoins,
+};
+
+use super::create_source::handle_source_with_properties;
+use crate::binder::Binder;
+use crate::optimizer::property::RequiredDist;
+use crate::optimizer::PlanRef;
+use crate::planner::Planner;
+use crate::session::OptimizerContext;
+use crate::stream_fragmenter::StreamFragmenter;
+
+/// Builds `SELECT * FROM <mv>`, the query whose changes are written to the sink.
+fn select_all_from(materialized_view: ObjectName) -> Query {
+    let select = Select {
+        distinct: false,
+        projection: vec![SelectItem::Wildcard],
+        from: vec![TableWithJoins {
+            relation: TableFactor::Table {
+                name: materialized_view,
+                alias: None,
+                args: vec![],
+            },
+            joins: vec![],
+        }],
+        lateral_views: vec![],
+        selection: None,
+        group_by: vec![],
+        having: None,
+    };
+    Query {
+        with: None,
+        body: SetExpr::Select(Box::new(select)),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+    }
+}
+
+pub async fn handle_create_sink(
+    context: OptimizerContext,
+    stmt: CreateSinkStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+
+    let (schema_name, sink_name) = Binder::resolve_table_name(stmt.sink_name.clone())?;
+    let (mv_schema_name, mv_name) = Binder::resolve_table_name(stmt.materialized_view.clone())?;
+    let properties = handle_source_with_properties(stmt.with_properties.0)?;
+    // The other properties are validated by the connector when the sink is built.
+    if !properties.contains_key("connector") {
+        return Err(ErrorCode::InvalidParameterValue(
+            "Must specify 'connector' in WITH clause".to_string(),
+        )
+        .into());
+    }
+
+    let (database_id, schema_id, associated_table_id) = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let schema = catalog_reader.get_schema_by_name(session.database(), &schema_name)?;
+        if schema.get_sink_by_name(&sink_name).is_some() {
+            if stmt.if_not_exists {
+                return Ok(PgResponse::empty_result_with_notice(
+                    StatementType::CREATE_SINK,
+                    format!("sink {} exists, skipping", sink_name),
+                ));
+            }
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "sink {} already exists",
+                sink_name
+            ))
+            .into());
+        }
+        let database_id = catalog_reader
+            .get_database_by_name(session.database())?
+            .id();
+        let table =
+            catalog_reader.get_table_by_name(session.database(), &mv_schema_name, &mv_name)?;
+        if table.associated_source_id.is_some() || table.is_index_on.is_some() {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "{} is not a materialized view",
+                mv_name
+            ))
+            .into());
+        }
+        (database_id, schema.id(), table.id)
+    };
+
+    let graph = {
+        let bound = {
+            let mut binder = Binder::new(
+                session.env().catalog_reader().read_guard(),
+                session.database().to_string(),
+            )
+            .with_search_path(session.config().search_path().to_vec());
+            binder.bind_query(select_all_from(stmt.materialized_view))?
+        };
+        let mut plan_root = Planner::new(context.into()).plan_query(bound)?;
+        plan_root.set_required_dist(RequiredDist::Any);
+        let sink = plan_root.gen_create_sink_plan(associated_table_id, properties.clone())?;
+        let plan: PlanRef = sink.into();
+        let stream_plan = plan.to_stream_prost();
+        StreamFragmenter::build_graph(stream_plan)
+    };
+
+    let sink = ProstSink {
+        id: 0,
+        schema_id,
+        database_id,
+        name: sink_name,
+        associated_table_id: associated_table_id.table_id,
+        properties,
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer.create_sink(sink, graph).await?;
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_SINK))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_sink_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 INT)")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("CREATE MATERIALIZED VIEW mv AS SELECT v1, v2 FROM t")
+            .await
+            .unwrap();
+
+        let sql = "CREATE SINK snk FROM mv WITH (connector = 'kafka', 'kafka.brokers' = 'localhost:9092', 'kafka.topic' = 'test', format = 'upsert')";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let sink = catalog_reader
+            .get_sink_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "snk")
+            .unwrap();
+        let mv = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv")
+            .unwrap();
+        assert_eq!(sink.associated_table_id, mv.id);
+        assert_eq!(sink.properties["format"], "upsert");
+        drop(catalog_reader);
+
+        // The connector must be specified.
+        assert!(frontend
+            .run_sql("CREATE SINK snk2 FROM mv WITH ('kafka.topic' = 'test')")
+            .await
+            .is_err());
+        // Sinks can only be created from materialized views.
+        assert!(frontend
+            .run_sql("CREATE SINK snk2 FROM t WITH (connector = 'kafka')")
+            .await
+            .is_err());
+
+        frontend.run_sql("DROP SINK snk").await.unwrap();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        assert!(catalog_reader
+            .get_sink_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "snk")
+            .is_err());
+    }
+}
@@ -16,7 +16,7 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::ErrorCode::ProtocolError;
+use risingwave_common::error::ErrorCode::{self, ProtocolError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, StreamSourceInfo};
@@ -26,7 +26,7 @@ use risingwave_sqlparser::ast::{
     CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema, SqlOption, Value,
 };
 
-use super::create_table::{bind_sql_columns, gen_materialized_source_plan};
+use super::create_table::{bind_sql_columns, bind_sql_pk, gen_materialized_source_plan};
 use crate::binder::Binder;
 use crate::catalog::column_catalog::ColumnCatalog;
 use crate::session::{OptimizerContext, SessionImpl};
@@ -64,11 +64,14 @@ fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstCol
         .map(|col| ProstColumnCatalog {
             column_desc: Some(col),
             is_hidden: false,
+            ..Default::default()
         })
         .collect_vec())
 }
 
-fn handle_source_with_properties(options: Vec<SqlOption>) -> Result<HashMap<String, String>> {
+pub(crate) fn handle_source_with_properties(
+    options: Vec<SqlOption>,
+) -> Result<HashMap<String, String>> {
     options
         .into_iter()
         .map(|x| match x.value {
@@ -96,6 +99,7 @@ pub async fn handle_create_source(
                 row_id_index: 0,
                 columns,
                 pk_column_ids: vec![0],
+                append_only: true,
             }
         }
         SourceSchema::Json => StreamSourceInfo {
@@ -105,7 +109,46 @@ pub async fn handle_create_source(
             row_id_index: 0,
             columns: bind_sql_columns(stmt.columns)?,
             pk_column_ids: vec![0],
+            append_only: true,
         },
+        SourceSchema::Avro(avro_schema) => StreamSourceInfo {
+            properties: handle_source_with_properties(stmt.with_properties.0)?,
+            row_format: RowFormatType::Avro as i32,
+            row_schema_location: avro_schema.row_schema_location.0.clone(),
+            row_id_index: 0,
+            columns: bind_sql_columns(stmt.columns)?,
+            pk_column_ids: vec![0],
+            append_only: true,
+        },
+        SourceSchema::Csv => StreamSourceInfo {
+            properties: handle_source_with_properties(stmt.with_properties.0)?,
+            row_format: RowFormatType::Csv as i32,
+            row_schema_location: "".to_string(),
+            row_id_index: 0,
+            columns: bind_sql_columns(stmt.columns)?,
+            pk_column_ids: vec![0],
+            append_only: true,
+        },
+        SourceSchema::DebeziumJson => {
+            // The changelog is applied by the primary key, so it must be declared.
+            let columns = bind_sql_columns(stmt.columns.clone())?;
+            let pk_column_ids = bind_sql_pk(&stmt.columns, &stmt.constraints, &columns)?;
+            if pk_column_ids.is_empty() {
+                return Err(ErrorCode::InvalidInputSyntax(
+                    "source with ROW FORMAT DEBEZIUM_JSON requires a primary key".to_string(),
+                )
+                .into());
+            }
+            StreamSourceInfo {
+                properties: handle_source_with_properties(stmt.with_properties.0)?,
+                row_format: RowFormatType::DebeziumJson as i32,
+                row_schema_location: "".to_string(),
+                row_id_index: 0,
+                columns,
+                pk_column_ids,
+                append_only: false,
+            }
+        }
     };
 
     let session = context.session_ctx.clone();
@@ -133,7 +176,7 @@ pub async fn handle_create_source(
 pub mod tests {
     use std::collections::HashMap;
 
-    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::catalog::{ColumnId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
 
     use crate::catalog::row_id_column_name;
@@ -161,6 +204,7 @@ pub mod tests {
             .unwrap()
             .clone();
         assert_eq!(source.name, "t");
+        assert!(source.append_only);
 
         // Only check stream source
         let catalogs = source.columns;
@@ -193,4 +237,29 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_debezium_json_source() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = r#"CREATE MATERIALIZED SOURCE t (id INT PRIMARY KEY, name VARCHAR)
+    WITH ('kafka.topic' = 'dbserver1.inventory.products', 'kafka.servers' = 'localhost:1001')
+    ROW FORMAT DEBEZIUM_JSON"#;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        assert!(!source.append_only);
+        assert_eq!(source.pk_col_ids, vec![ColumnId::new(1)]);
+
+        let sql = r#"CREATE SOURCE t2 (id INT, name VARCHAR)
+    WITH ('kafka.topic' = 'dbserver1.inventory.products', 'kafka.servers' = 'localhost:1001')
+    ROW FORMAT DEBEZIUM_JSON"#;
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert!(err.to_string().contains("requires a primary key"));
+    }
 }
@@ -12,20 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::Result;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field};
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
-use risingwave_pb::plan_common::ColumnCatalog;
-use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName};
+use risingwave_pb::plan_common::{ColumnCatalog, ConflictBehavior};
+use risingwave_sqlparser::ast::{
+    ColumnDef, ColumnOption, DataType as AstDataType, ObjectName, SqlOption, TableConstraint, Value,
+};
 
 use super::create_source::make_prost_source;
 use crate::binder::expr::{bind_data_type, bind_struct_field};
+use crate::binder::Binder;
 use crate::catalog::{check_valid_column_name, row_id_column_desc};
 use crate::optimizer::plan_node::{LogicalSource, StreamSource};
 use crate::optimizer::property::{Order, RequiredDist};
@@ -43,35 +47,233 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         column_descs.push(row_id_column_desc());
         // Then user columns.
         for (i, column) in columns.into_iter().enumerate() {
-            check_valid_column_name(&column.name.value)?;
-            let field_descs = if let AstDataType::Struct(fields) = &column.data_type {
-                fields
-                    .iter()
-                    .map(bind_struct_field)
-                    .collect::<Result<Vec<_>>>()?
-            } else {
-                vec![]
-            };
-            column_descs.push(ColumnDesc {
-                data_type: bind_data_type(&column.data_type)?,
-                column_id: ColumnId::new((i + 1) as i32),
-                name: column.name.value,
-                field_descs,
-                type_name: "".to_string(),
-            });
+            column_descs.push(bind_sql_column(column, ColumnId::new((i + 1) as i32))?);
         }
         column_descs
     };
 
-    let columns_catalog = column_descs
+    Ok(column_descs_to_catalog(column_descs))
+}
+
+/// Binds a column declared in CREATE or ALTER statement with the given column id.
+pub(crate) fn bind_sql_column(column: ColumnDef, column_id: ColumnId) -> Result<ColumnDesc> {
+    check_valid_column_name(&column.name.value)?;
+    let field_descs = if let AstDataType::Struct(fields) = &column.data_type {
+        fields
+            .iter()
+            .map(bind_struct_field)
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        vec![]
+    };
+    Ok(ColumnDesc {
+        data_type: bind_data_type(&column.data_type)?,
+        column_id,
+        name: column.name.value,
+        field_descs,
+        type_name: "".to_string(),
+    })
+}
+
+/// Derives the column schemas of the table from the output fields of a query, for
+/// `CREATE TABLE AS`.
+pub fn bind_query_columns(fields: &[Field]) -> Result<Vec<ColumnCatalog>> {
+    let mut column_descs = Vec::with_capacity(fields.len() + 1);
+    column_descs.push(row_id_column_desc());
+    let mut names = HashSet::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        check_valid_column_name(&field.name)?;
+        if !names.insert(field.name.as_str()) {
+            return Err(ErrorCode::BindError(format!(
+                "column \"{}\" specified more than once",
+                field.name
+            ))
+            .into());
+        }
+        column_descs.push(ColumnDesc {
+            column_id: ColumnId::new((i + 1) as i32),
+            ..ColumnDesc::from_field_without_column_id(field)
+        });
+    }
+    Ok(column_descs_to_catalog(column_descs))
+}
+
+/// Binds the `NULL`, `NOT NULL` and `DEFAULT` options of the columns declared in CREATE TABLE into
+/// `column_catalogs`, whose first column is the hidden row id. Columns of the primary key are
+/// implicitly not null.
+fn bind_sql_column_options(
+    session: &SessionImpl,
+    columns: &[ColumnDef],
+    pk_column_ids: &[i32],
+    column_catalogs: &mut [ColumnCatalog],
+) -> Result<()> {
+    let mut binder = Binder::new(
+        session.env().catalog_reader().read_guard(),
+        session.database().to_string(),
+    )
+    .with_search_path(session.config().search_path().to_vec());
+    for (column, catalog) in columns.iter().zip_eq(column_catalogs.iter_mut().skip(1)) {
+        let column_id = catalog.column_desc.as_ref().unwrap().column_id;
+        let mut nullable = None;
+        for option in &column.options {
+            match &option.option {
+                ColumnOption::Null | ColumnOption::NotNull => {
+                    let is_nullable = matches!(option.option, ColumnOption::Null);
+                    if nullable.map_or(false, |nullable| nullable != is_nullable) {
+                        return Err(ErrorCode::InvalidInputSyntax(format!(
+                            "conflicting NULL/NOT NULL declarations for column \"{}\"",
+                            column.name.value
+                        ))
+                        .into());
+                    }
+                    nullable = Some(is_nullable);
+                }
+                ColumnOption::Default(expr) => {
+                    if !catalog.default_expr.is_empty() {
+                        return Err(ErrorCode::InvalidInputSyntax(format!(
+                            "multiple default values specified for column \"{}\"",
+                            column.name.value
+                        ))
+                        .into());
+                    }
+                    binder
+                        .bind_column_default(expr.clone(), &bind_data_type(&column.data_type)?)?;
+                    catalog.default_expr = expr.to_string();
+                }
+                _ => {}
+            }
+        }
+        let is_pk = pk_column_ids.contains(&column_id);
+        if is_pk && nullable == Some(true) {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "primary key column \"{}\" can't be declared NULL",
+                column.name.value
+            ))
+            .into());
+        }
+        catalog.not_null = is_pk || nullable == Some(false);
+    }
+    Ok(())
+}
+
+/// The option in the WITH clause of CREATE TABLE which specifies how to handle a row whose
+/// primary key already exists.
+const ON_CONFLICT_OPTION: &str = "on_conflict";
+
+/// Binds the `PRIMARY KEY` declared in CREATE TABLE, either as a column option or as a table
+/// constraint, into column ids. Returns an empty vector if there's none, in which case the hidden
+/// row id column is used as the pk.
+pub(crate) fn bind_sql_pk(
+    columns: &[ColumnDef],
+    constraints: &[TableConstraint],
+    column_catalogs: &[ColumnCatalog],
+) -> Result<Vec<i32>> {
+    let mut pk_names = vec![];
+    for column in columns {
+        for option in &column.options {
+            if let ColumnOption::Unique { is_primary: true } = option.option {
+                if !pk_names.is_empty() {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "multiple primary keys for a table are not allowed".to_string(),
+                    )
+                    .into());
+                }
+                pk_names.push(column.name.value.clone());
+            }
+        }
+    }
+    for constraint in constraints {
+        if let TableConstraint::Unique {
+            columns,
+            is_primary: true,
+            ..
+        } = constraint
+        {
+            if !pk_names.is_empty() {
+                return Err(ErrorCode::InvalidInputSyntax(
+                    "multiple primary keys for a table are not allowed".to_string(),
+                )
+                .into());
+            }
+            pk_names.extend(columns.iter().map(|c| c.value.clone()));
+        }
+    }
+
+    let mut pk_column_ids = Vec::with_capacity(pk_names.len());
+    for name in pk_names {
+        let column_desc = column_catalogs
+            .iter()
+            .filter(|c| !c.is_hidden)
+            .map(|c| c.column_desc.as_ref().unwrap())
+            .find(|c| c.name == name)
+            .ok_or_else(|| {
+                ErrorCode::InvalidInputSyntax(format!(
+                    "column \"{}\" named in key does not exist",
+                    name
+                ))
+            })?;
+        if pk_column_ids.contains(&column_desc.column_id) {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "column \"{}\" appears twice in primary key constraint",
+                name
+            ))
+            .into());
+        }
+        pk_column_ids.push(column_desc.column_id);
+    }
+    Ok(pk_column_ids)
+}
+
+/// Binds the `on_conflict` option of CREATE TABLE, which must be one of `error` (the default),
+/// `overwrite` and `ignore`. Only tables with a user-defined primary key can have conflicts.
+fn bind_conflict_behavior(with_options: &[SqlOption], has_pk: bool) -> Result<ConflictBehavior> {
+    let option = with_options
+        .iter()
+        .find(|option| option.name.value.eq_ignore_ascii_case(ON_CONFLICT_OPTION));
+    let behavior = match option.map(|option| &option.value) {
+        None => ConflictBehavior::ErrorOnConflict,
+        Some(Value::SingleQuotedString(value)) => match value.to_lowercase().as_str() {
+            "error" => ConflictBehavior::ErrorOnConflict,
+            "overwrite" => ConflictBehavior::Overwrite,
+            "ignore" => ConflictBehavior::IgnoreConflict,
+            _ => {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "{} must be one of 'error', 'overwrite' and 'ignore', got '{}'",
+                    ON_CONFLICT_OPTION, value
+                ))
+                .into())
+            }
+        },
+        Some(value) => {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} only supports single quoted string value, got {}",
+                ON_CONFLICT_OPTION, value
+            ))
+            .into())
+        }
+    };
+
+    match (has_pk, option.is_some()) {
+        (true, _) => Ok(behavior),
+        (false, false) => Ok(ConflictBehavior::NoCheck),
+        (false, true) => Err(ErrorCode::InvalidInputSyntax(format!(
+            "{} requires the table to have a primary key",
+            ON_CONFLICT_OPTION
+        ))
+        .into()),
+    }
+}
+
+fn column_descs_to_catalog(column_descs: Vec<ColumnDesc>) -> Vec<ColumnCatalog> {
+    column_descs
         .into_iter()
         .enumerate()
         .map(|(i, c)| ColumnCatalog {
             column_desc: c.to_protobuf().into(),
             is_hidden: i == 0, // the row id column is hidden
+            ..Default::default()
         })
-        .collect_vec();
-    Ok(columns_catalog)
+        .collect_vec()
 }
 
 pub(crate) fn gen_create_table_plan(
@@ -79,14 +281,34 @@ pub(crate) fn gen_create_table_plan(
     context: OptimizerContextRef,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    with_options: Vec<SqlOption>,
 ) -> Result<(PlanRef, ProstSource, ProstTable)> {
-    let source = make_prost_source(
+    let mut column_catalogs = bind_sql_columns(columns.clone())?;
+    let pk_column_ids = bind_sql_pk(&columns, &constraints, &column_catalogs)?;
+    bind_sql_column_options(session, &columns, &pk_column_ids, &mut column_catalogs)?;
+    let conflict_behavior = bind_conflict_behavior(&with_options, !pk_column_ids.is_empty())?;
+    gen_create_table_plan_with_columns(
         session,
+        context,
         table_name,
-        Info::TableSource(TableSourceInfo {
-            columns: bind_sql_columns(columns)?,
-        }),
-    )?;
+        TableSourceInfo {
+            columns: column_catalogs,
+            pk_column_ids,
+            conflict_behavior: conflict_behavior as i32,
+        },
+    )
+}
+
+/// Generates the plan of CREATE TABLE with bound columns. The hidden row id column is used as the
+/// pk unless `pk_column_ids` of the table source is set.
+pub(crate) fn gen_create_table_plan_with_columns(
+    session: &SessionImpl,
+    context: OptimizerContextRef,
+    table_name: ObjectName,
+    table_source: TableSourceInfo,
+) -> Result<(PlanRef, ProstSource, ProstTable)> {
+    let source = make_prost_source(session, table_name, Info::TableSource(table_source))?;
     let (plan, table) = gen_materialized_source_plan(context, source.clone())?;
     Ok((plan, source, table))
 }
@@ -97,6 +319,10 @@ pub(crate) fn gen_materialized_source_plan(
     context: OptimizerContextRef,
     source: ProstSource,
 ) -> Result<(PlanRef, ProstTable)> {
+    let conflict_behavior = match &source.info {
+        Some(Info::TableSource(table_source)) => table_source.get_conflict_behavior()?,
+        _ => ConflictBehavior::NoCheck,
+    };
     let materialize = {
         // Manually assemble the materialization plan for the table.
         let source_node: PlanRef =
@@ -115,6 +341,7 @@ pub(crate) fn gen_materialized_source_plan(
             out_names,
         )
         .gen_create_mv_plan(source.name.clone())?
+        .with_conflict_behavior(conflict_behavior)
     };
     let table = materialize
         .table()
@@ -127,12 +354,20 @@ pub async fn handle_create_table(
     context: OptimizerContext,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
 
     let (graph, source, table) = {
-        let (plan, source, table) =
-            gen_create_table_plan(&session, context.into(), table_name.clone(), columns)?;
+        let (plan, source, table) = gen_create_table_plan(
+            &session,
+            context.into(),
+            table_name.clone(),
+            columns,
+            constraints,
+            with_options,
+        )?;
         let plan = plan.to_stream_prost();
         let graph = StreamFragmenter::build_graph(plan);
 
@@ -160,6 +395,7 @@ mod tests {
     use itertools::Itertools;
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
+    use risingwave_pb::plan_common::ConflictBehavior;
 
     use crate::catalog::row_id_column_name;
     use crate::test_utils::LocalFrontend;
@@ -212,4 +448,79 @@ mod tests {
 
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_table_with_pk() {
+        let sql = "create table t (v1 int, v2 int, v3 int, primary key (v2, v1)) \
+                   with (on_conflict = 'ignore')";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let source = catalog_reader
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        assert_eq!(source.pk_col_ids, vec![2.into(), 1.into()]);
+        assert_eq!(source.conflict_behavior, ConflictBehavior::IgnoreConflict);
+
+        let table = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        assert_eq!(table.pks, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_column_options() {
+        let sql = "create table t (v1 int primary key, v2 int not null, v3 int default 1 + 1, \
+                   v4 int null)";
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let source = catalog_reader
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        let options = source
+            .columns
+            .iter()
+            .filter(|c| !c.is_hidden)
+            .map(|c| (c.name(), c.not_null(), c.default_expr()))
+            .collect_vec();
+        assert_eq!(
+            options,
+            vec![
+                ("v1", true, None),
+                ("v2", true, None),
+                ("v3", false, Some("1 + 1")),
+                ("v4", false, None),
+            ]
+        );
+
+        for sql in [
+            "create table t2 (v1 int null not null)",
+            "create table t2 (v1 int default 1 default 2)",
+            "create table t2 (v1 int default v2, v2 int)",
+            "create table t2 (v1 int default count(*))",
+            "create table t2 (v1 int default true)",
+        ] {
+            assert!(frontend.run_sql(sql).await.is_err(), "{}", sql);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_invalid_pk() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        for sql in [
+            "create table t (v1 int primary key, v2 int primary key)",
+            "create table t (v1 int primary key, v2 int, primary key (v2))",
+            "create table t (v1 int, primary key (v2))",
+            "create table t (v1 int, primary key (v1, v1))",
+            "create table t (v1 int) with (on_conflict = 'ignore')",
+            "create table t (v1 int primary key) with (on_conflict = 'replace')",
+        ] {
+            assert!(frontend.run_sql(sql).await.is_err(), "{}", sql);
+        }
+    }
 }
@@ -0,0 +1,145 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::Result;
+use risingwave_pb::catalog::TableSourceInfo;
+use risingwave_sqlparser::ast::{ColumnDef, ObjectName, Query, Statement};
+
+use super::create_mv::check_column_aliases;
+use super::create_table::{
+    bind_query_columns, bind_sql_columns, gen_create_table_plan_with_columns,
+};
+use super::dml::handle_dml;
+use super::drop_table::handle_drop_table;
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+use crate::stream_fragmenter::StreamFragmenter;
+
+/// Handles `CREATE TABLE <name> [(<columns>)] AS <query>`: creates the table, then inserts the
+/// result of the query into it. If no columns are declared, the schema is derived from the query.
+/// Completes with `SELECT <number of rows inserted>` like Postgres.
+pub async fn handle_create_table_as(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    columns: Vec<ColumnDef>,
+    query: Box<Query>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+
+    let columns = if columns.is_empty() {
+        let bound = {
+            let mut binder = Binder::new(
+                session.env().catalog_reader().read_guard(),
+                session.database().to_string(),
+            )
+            .with_search_path(session.config().search_path().to_vec());
+            binder.bind_query(*query.clone())?
+        };
+        check_column_aliases(&bound)?;
+        bind_query_columns(bound.schema().fields())?
+    } else {
+        bind_sql_columns(columns)?
+    };
+
+    let (graph, source, table) = {
+        let (plan, source, table) = gen_create_table_plan_with_columns(
+            &session,
+            context.into(),
+            table_name.clone(),
+            TableSourceInfo {
+                columns,
+                ..Default::default()
+            },
+        )?;
+        let plan = plan.to_stream_prost();
+        let graph = StreamFragmenter::build_graph(plan);
+
+        (graph, source, table)
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .create_materialized_source(source, table, graph)
+        .await?;
+
+    let insert = Statement::Insert {
+        table_name: table_name.clone(),
+        columns: vec![],
+        source: query,
+        on: None,
+    };
+    match handle_dml(OptimizerContext::new(session.clone()), insert).await {
+        Ok(res) => Ok(PgResponse::new(
+            StatementType::CREATE_TABLE_AS,
+            res.get_effected_rows_cnt(),
+            vec![],
+            vec![],
+        )),
+        Err(e) => {
+            // Don't leave an empty table behind if the query fails.
+            if let Err(drop_err) =
+                handle_drop_table(OptimizerContext::new(session), table_name.clone(), None).await
+            {
+                tracing::warn!(
+                    "failed to drop table {} after CREATE TABLE AS failed: {}",
+                    table_name,
+                    drop_err
+                );
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_table_as_requires_alias() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        assert!(frontend
+            .run_sql("create table t2 as select v1 + v2 from t")
+            .await
+            .is_err());
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        assert!(catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t2")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_table_as_duplicate_column() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        assert!(frontend
+            .run_sql("create table t2 as select v1, v2 as v1 from t")
+            .await
+            .is_err());
+    }
+}
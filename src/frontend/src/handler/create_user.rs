@@ -0,0 +1,127 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::ErrorCode::{InternalError, PermissionDenied};
+use risingwave_common::error::Result;
+use risingwave_pb::user::UserInfo;
+use risingwave_sqlparser::ast::{CreateUserOption, CreateUserStatement};
+
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+use crate::user::user_authentication::encrypted_password;
+
+fn make_prost_user_info(name: String, options: &[CreateUserOption]) -> UserInfo {
+    // Same as Postgres, a user can login but is not a superuser by default.
+    let mut user_info = UserInfo {
+        name,
+        can_login: true,
+        ..Default::default()
+    };
+    for option in options {
+        match option {
+            CreateUserOption::SuperUser => user_info.is_supper = true,
+            CreateUserOption::NoSuperUser => user_info.is_supper = false,
+            CreateUserOption::CreateDB => user_info.can_create_db = true,
+            CreateUserOption::NoCreateDB => user_info.can_create_db = false,
+            CreateUserOption::Login => user_info.can_login = true,
+            CreateUserOption::NoLogin => user_info.can_login = false,
+            CreateUserOption::Password(Some(password)) => {
+                user_info.auth_info = encrypted_password(&user_info.name, &password.0);
+            }
+            CreateUserOption::Password(None) => user_info.auth_info = None,
+        }
+    }
+    user_info
+}
+
+pub async fn handle_create_user(
+    context: OptimizerContext,
+    stmt: CreateUserStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let user_name = Binder::resolve_user_name(stmt.user_name)?;
+    let user_info = make_prost_user_info(user_name, &stmt.with_options.0);
+
+    {
+        let user_reader = session.env().user_info_reader().read_guard();
+        let session_user = user_reader
+            .get_user_by_name(session.user_name())
+            .ok_or_else(|| InternalError("Session user is invalid".to_string()))?;
+        if !session_user.is_supper {
+            return Err(PermissionDenied("Only superusers can create users".to_string()).into());
+        }
+        if user_reader.get_user_by_name(&user_info.name).is_some() {
+            return Err(PermissionDenied(format!("User {} already exists", user_info.name)).into());
+        }
+    }
+
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer.create_user(user_info).await?;
+    Ok(PgResponse::empty_result(StatementType::CREATE_USER))
+}
+
+#[cfg(test)]
+mod tests {
+    use pgwire::pg_auth::md5_hash_password;
+    use risingwave_pb::user::auth_info::EncryptionType;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_user() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let user_info_reader = session.env().user_info_reader();
+
+        // Passwords hashed by MD5 are stored as they are.
+        let md5_password = md5_hash_password("user", "pwd");
+        frontend
+            .run_sql(format!(
+                "CREATE USER user WITH SUPERUSER CREATEDB PASSWORD '{}'",
+                md5_password
+            ))
+            .await
+            .unwrap();
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user")
+            .cloned()
+            .unwrap();
+        assert!(user_info.is_supper);
+        assert!(user_info.can_create_db);
+        assert!(user_info.can_login);
+        let auth_info = user_info.auth_info.unwrap();
+        assert_eq!(auth_info.encryption_type(), EncryptionType::Md5);
+        assert_eq!(auth_info.encrypted_value, md5_password.into_bytes());
+
+        frontend
+            .run_sql("CREATE USER user2 NOLOGIN PASSWORD 'pwd'")
+            .await
+            .unwrap();
+        let user_info = user_info_reader
+            .read_guard()
+            .get_user_by_name("user2")
+            .cloned()
+            .unwrap();
+        assert!(!user_info.is_supper);
+        assert!(!user_info.can_login);
+        assert_eq!(
+            user_info.auth_info.unwrap().encrypted_value,
+            md5_hash_password("user2", "pwd").into_bytes()
+        );
+
+        assert!(frontend.run_sql("CREATE USER user").await.is_err());
+    }
+}
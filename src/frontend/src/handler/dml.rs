@@ -13,21 +13,21 @@
 // limitations under the License.
 
 use futures_async_stream::for_await;
+use itertools::Itertools;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
+use pgwire::types::Row;
+use risingwave_common::error::{Result, RwError};
 use risingwave_sqlparser::ast::Statement;
 
-use crate::binder::Binder;
-use crate::handler::util::{to_pg_field, to_pg_rows};
+use crate::binder::{Binder, BoundSetExpr, BoundStatement};
+use crate::config::QueryMode;
+use crate::handler::query::{execute_stream, gen_batch_query};
+use crate::handler::util::{to_pg_field, to_pg_rows, PgValueFormat};
 use crate::planner::Planner;
 use crate::scheduler::{ExecutionContext, ExecutionContextRef};
 use crate::session::{OptimizerContext, SessionImpl};
 
-/// If `RW_IMPLICIT_FLUSH` is on, then every INSERT/UPDATE/DELETE statement will block
-/// until the entire dataflow is refreshed. In other words, every related table & MV will
-/// be able to see the write.
-pub static IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
-
 pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
@@ -36,31 +36,36 @@ pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<Pg
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
-        );
+        )
+        .with_search_path(session.config().search_path().to_vec());
         binder.bind(stmt)?
     };
 
-    let (plan, pg_descs) = {
-        // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-        let root = Planner::new(context.into()).plan(bound)?;
-        let pg_descs = root.schema().fields().iter().map(to_pg_field).collect();
-        let plan = root.gen_batch_query_plan()?;
-
-        (plan.to_batch_prost(), pg_descs)
+    // The source query of `INSERT ... SELECT` may need several stages, e.g., for joins or
+    // aggregations, while the other statements run in a single task.
+    let insert_select = matches!(
+        &bound,
+        BoundStatement::Insert(insert) if !matches!(insert.source.body, BoundSetExpr::Values(_))
+    );
+    let format = PgValueFormat::from_config(&session.config());
+    let (rows, pg_descs) = if insert_select {
+        let (query, pg_descs) = gen_batch_query(context, bound, QueryMode::Distributed)?;
+        let type_oids = pg_descs.iter().map(|d| d.get_type_oid()).collect_vec();
+        let rows = session
+            .run_cancellable(async {
+                let mut rows = vec![];
+                #[for_await]
+                for chunk in execute_stream(session.clone(), query, QueryMode::Distributed) {
+                    rows.extend(to_pg_rows(chunk?, &type_oids, &format));
+                }
+                Ok::<_, RwError>(rows)
+            })
+            .await?;
+        (rows, pg_descs)
+    } else {
+        handle_single_task_dml(context, bound, format).await?
     };
 
-    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
-    let query_manager = execution_context.session().env().query_manager().clone();
-
-    let mut rows = vec![];
-    #[for_await]
-    for chunk in query_manager
-        .schedule_single(execution_context, plan)
-        .await?
-    {
-        rows.extend(to_pg_rows(chunk?));
-    }
-
     let rows_count = match stmt_type {
         // TODO(renjie): We need a better solution for this.
         StatementType::INSERT | StatementType::DELETE | StatementType::UPDATE => {
@@ -75,15 +80,52 @@ pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<Pg
     };
 
     // Implicitly flush the writes.
-    if let Some(flag) = session.get_config(IMPLICIT_FLUSH) {
-        if flag.is_set(false) {
-            flush_for_write(&session, stmt_type).await?;
-        }
+    let implicit_flush = session.config().implicit_flush();
+    if implicit_flush {
+        flush_for_write(&session, stmt_type).await?;
     }
 
     Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
 }
 
+/// Runs a DML statement whose plan fits in a single task on a random compute node.
+async fn handle_single_task_dml(
+    context: OptimizerContext,
+    bound: BoundStatement,
+    format: PgValueFormat,
+) -> Result<(Vec<Row>, Vec<PgFieldDescriptor>)> {
+    let session = context.session_ctx.clone();
+    let (plan, pg_descs) = {
+        // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
+        let root = Planner::new(context.into()).plan(bound)?;
+        let pg_descs: Vec<PgFieldDescriptor> =
+            root.schema().fields().iter().map(to_pg_field).collect();
+        let plan = root.gen_batch_query_plan()?;
+
+        (plan.to_batch_prost(), pg_descs)
+    };
+    let type_oids = pg_descs.iter().map(|d| d.get_type_oid()).collect_vec();
+
+    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    let query_manager = execution_context.session().env().query_manager().clone();
+
+    let rows = session
+        .run_cancellable(async {
+            let mut rows = vec![];
+            #[for_await]
+            for chunk in query_manager
+                .schedule_single(execution_context, plan)
+                .await?
+            {
+                rows.extend(to_pg_rows(chunk?, &type_oids, &format));
+            }
+            Ok::<_, RwError>(rows)
+        })
+        .await?;
+
+    Ok((rows, pg_descs))
+}
+
 async fn flush_for_write(session: &SessionImpl, stmt_type: StatementType) -> Result<()> {
     match stmt_type {
         StatementType::INSERT | StatementType::DELETE | StatementType::UPDATE => {
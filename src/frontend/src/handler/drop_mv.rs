@@ -14,7 +14,7 @@
 
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_sqlparser::ast::ObjectName;
+use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
 use crate::binder::Binder;
 use crate::handler::drop_table::check_source;
@@ -23,6 +23,7 @@ use crate::session::OptimizerContext;
 pub async fn handle_drop_mv(
     context: OptimizerContext,
     table_name: ObjectName,
+    mode: Option<DropMode>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
     let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
@@ -52,7 +53,9 @@ pub async fn handle_drop_mv(
     };
 
     let catalog_writer = session.env().catalog_writer();
-    catalog_writer.drop_materialized_view(table_id).await?;
+    catalog_writer
+        .drop_materialized_view(table_id, mode == Some(DropMode::Cascade))
+        .await?;
 
     Ok(PgResponse::empty_result(
         StatementType::DROP_MATERIALIZED_VIEW,
@@ -15,12 +15,17 @@
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::stream_plan::source_node::SourceType;
-use risingwave_sqlparser::ast::ObjectName;
+use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
 use crate::binder::Binder;
 use crate::session::OptimizerContext;
 
-pub async fn handle_drop_source(context: OptimizerContext, name: ObjectName) -> Result<PgResponse> {
+pub async fn handle_drop_source(
+    context: OptimizerContext,
+    name: ObjectName,
+    mode: Option<DropMode>,
+) -> Result<PgResponse> {
+    let cascade = mode == Some(DropMode::Cascade);
     let session = context.session_ctx;
     let (schema_name, source_name) = Binder::resolve_table_name(name)?;
 
@@ -46,10 +51,10 @@ pub async fn handle_drop_source(context: OptimizerContext, name: ObjectName) ->
             if let Some(table) = table {
                 // Dropping a materialized source.
                 catalog_writer
-                    .drop_materialized_source(source.id, table.id)
+                    .drop_materialized_source(source.id, table.id, cascade)
                     .await?;
             } else {
-                catalog_writer.drop_source(source.id).await?;
+                catalog_writer.drop_source(source.id, cascade).await?;
             }
         }
     }
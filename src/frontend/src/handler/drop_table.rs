@@ -17,7 +17,7 @@ use std::sync::Arc;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::stream_plan::source_node::SourceType;
-use risingwave_sqlparser::ast::ObjectName;
+use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
 use crate::binder::Binder;
 use crate::catalog::catalog_service::CatalogReader;
@@ -43,6 +43,7 @@ pub fn check_source(
 pub async fn handle_drop_table(
     context: OptimizerContext,
     table_name: ObjectName,
+    mode: Option<DropMode>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
     let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
@@ -68,7 +69,11 @@ pub async fn handle_drop_table(
 
     let catalog_writer = session.env().catalog_writer();
     catalog_writer
-        .drop_materialized_source(source_id.table_id(), table_id)
+        .drop_materialized_source(
+            source_id.table_id(),
+            table_id,
+            mode == Some(DropMode::Cascade),
+        )
         .await?;
 
     Ok(PgResponse::empty_result(StatementType::DROP_TABLE))
@@ -42,8 +42,22 @@ pub(super) fn handle_explain(
             ..
         } => gen_create_mv_plan(&*session, planner.ctx(), query, name)?.0,
 
-        Statement::CreateTable { name, columns, .. } => {
-            gen_create_table_plan(&*session, planner.ctx(), name, columns)?.0
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            gen_create_table_plan(
+                &*session,
+                planner.ctx(),
+                name,
+                columns,
+                constraints,
+                with_options,
+            )?
+            .0
         }
 
         stmt => {
@@ -51,7 +65,8 @@ pub(super) fn handle_explain(
                 let mut binder = Binder::new(
                     session.env().catalog_reader().read_guard(),
                     session.database().to_string(),
-                );
+                )
+                .with_search_path(session.config().search_path().to_vec());
                 binder.bind(stmt)?
             };
             let logical = planner.plan(bound)?;
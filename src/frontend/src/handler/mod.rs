@@ -21,22 +21,31 @@ use risingwave_sqlparser::ast::{DropStatement, ObjectType, Statement};
 
 use crate::session::{OptimizerContext, SessionImpl};
 
+mod alter_mv;
+mod alter_source;
+mod alter_system;
 mod create_database;
 pub mod create_index;
 pub mod create_mv;
 mod create_schema;
+pub mod create_sink;
 pub mod create_source;
 pub mod create_table;
+mod create_table_as;
+mod create_user;
 mod describe;
 pub mod dml;
 mod drop_database;
 mod drop_index;
 pub mod drop_mv;
 mod drop_schema;
+pub mod drop_sink;
 pub mod drop_source;
 pub mod drop_table;
+mod drop_user;
 mod explain;
 mod flush;
+pub mod prepared_statement;
 #[allow(dead_code)]
 pub mod query;
 mod set;
@@ -53,8 +62,21 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             is_materialized,
             stmt,
         } => create_source::handle_create_source(context, is_materialized, stmt).await,
-        Statement::CreateTable { name, columns, .. } => {
-            create_table::handle_create_table(context, name, columns).await
+        Statement::CreateTable {
+            name,
+            columns,
+            query: Some(query),
+            ..
+        } => create_table_as::handle_create_table_as(context, name, columns, query).await,
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            create_table::handle_create_table(context, name, columns, constraints, with_options)
+                .await
         }
         Statement::CreateDatabase {
             db_name,
@@ -66,6 +88,17 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             if_not_exists,
             ..
         } => create_schema::handle_create_schema(context, schema_name, if_not_exists).await,
+        Statement::CreateSink { stmt } => create_sink::handle_create_sink(context, stmt).await,
+        Statement::CreateUser(stmt) => create_user::handle_create_user(context, stmt).await,
+        Statement::AlterSource { name, operation } => {
+            alter_source::handle_alter_source(context, name, operation).await
+        }
+        Statement::AlterMaterializedView { name, operation } => {
+            alter_mv::handle_alter_mv(context, name, operation).await
+        }
+        Statement::AlterSystem { param, value } => {
+            alter_system::handle_alter_system(context, param, value).await
+        }
         Statement::Describe { name } => describe::handle_describe(context, name).await,
         Statement::ShowObjects(show_object) => show::handle_show_object(context, show_object).await,
         Statement::Drop(DropStatement {
@@ -74,10 +107,17 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             if_exists,
             drop_mode,
         }) => match object_type {
-            ObjectType::Table => drop_table::handle_drop_table(context, object_name).await,
-            ObjectType::MaterializedView => drop_mv::handle_drop_mv(context, object_name).await,
+            ObjectType::Table => {
+                drop_table::handle_drop_table(context, object_name, drop_mode.into()).await
+            }
+            ObjectType::MaterializedView => {
+                drop_mv::handle_drop_mv(context, object_name, drop_mode.into()).await
+            }
             ObjectType::Index => drop_index::handle_drop_index(context, object_name).await,
-            ObjectType::Source => drop_source::handle_drop_source(context, object_name).await,
+            ObjectType::Source => {
+                drop_source::handle_drop_source(context, object_name, drop_mode.into()).await
+            }
+            ObjectType::Sink => drop_sink::handle_drop_sink(context, object_name).await,
             ObjectType::Database => {
                 drop_database::handle_drop_database(
                     context,
@@ -91,6 +131,9 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
                 drop_schema::handle_drop_schema(context, object_name, if_exists, drop_mode.into())
                     .await
             }
+            ObjectType::User => {
+                drop_user::handle_drop_user(context, object_name, if_exists, drop_mode.into()).await
+            }
             _ => Err(
                 ErrorCode::InvalidInputSyntax(format!("DROP {} is unsupported", object_type))
                     .into(),
@@ -105,14 +148,17 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             or_replace: false,
             name,
             query,
+            with_options,
             ..
-        } => create_mv::handle_create_mv(context, name, query).await,
+        } => create_mv::handle_create_mv(context, name, query, with_options).await,
         Statement::Flush => flush::handle_flush(context).await,
         Statement::SetVariable {
             local: _,
             variable,
             value,
         } => set::handle_set(context, variable, value),
+        Statement::ResetVariable { variable } => set::handle_reset(context, variable),
+        Statement::ShowVariable { variable } => show::handle_show_variable(context, variable),
         Statement::CreateIndex {
             name,
             table_name,
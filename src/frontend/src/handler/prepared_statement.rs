@@ -0,0 +1,207 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, ScalarImpl, ToOwnedDatum};
+use risingwave_expr::expr::build_from_prost;
+use risingwave_pb::expr::ExprNode;
+use risingwave_sqlparser::ast::Statement;
+
+use super::query::{execute_query, gen_batch_query};
+use super::util::{to_pg_field, PgValueFormat};
+use crate::binder::{Binder, BoundStatement};
+use crate::expr::{Expr, ExprImpl, Literal};
+use crate::scheduler::{CachedPlan, PlanContext};
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// Describes a statement of the extended query protocol, whose parameters are of `param_types`
+/// if specified. Returns the types of all parameters, and the fields of the rows it returns.
+///
+/// Only the DML statements and queries are bound, which infers the types of their parameters. The
+/// rows returned by the other statements are unknown until they are executed.
+pub fn describe_statement(
+    session: &SessionImpl,
+    stmt: Statement,
+    param_types: Vec<Option<DataType>>,
+) -> Result<(Vec<DataType>, Option<Vec<PgFieldDescriptor>>)> {
+    match stmt {
+        Statement::Query(_)
+        | Statement::Insert { .. }
+        | Statement::Delete { .. }
+        | Statement::Update { .. } => {
+            let mut binder = Binder::new_with_param_types(
+                session.env().catalog_reader().read_guard(),
+                session.database().to_string(),
+                param_types,
+            )
+            .with_search_path(session.config().search_path().to_vec());
+            let fields = match binder.bind(stmt)? {
+                BoundStatement::Query(query) => query
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(to_pg_field)
+                    .collect_vec(),
+                _ => vec![],
+            };
+            Ok((binder.export_param_types(), Some(fields)))
+        }
+        _ => {
+            let param_types = param_types
+                .into_iter()
+                .map(|t| t.unwrap_or(DataType::Varchar))
+                .collect();
+            Ok((param_types, None))
+        }
+    }
+}
+
+/// Executes a prepared query with its parameters bound to `params`, the values in the text
+/// format. The plan is cached by the query string, and reused by the following executions as long
+/// as the catalog, the parameter types and the configurations affecting planning are unchanged.
+pub async fn handle_prepared_query(
+    context: OptimizerContext,
+    sql: &str,
+    stmt: Statement,
+    param_types: Vec<DataType>,
+    params: Vec<Option<String>>,
+    result_format_codes: Vec<i16>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let params = params
+        .into_iter()
+        .zip_eq(param_types.iter())
+        .map(|(value, data_type)| param_to_expr_proto(value, data_type.clone()))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Subblock to make sure the catalog guard and the `PlanRef`s are dropped before `await` below.
+    let plan = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let plan_context = PlanContext {
+            param_types,
+            catalog_version: catalog_reader.version(),
+            query_mode: session.config().query_mode(),
+            batch_parallelism: session.config().batch_parallelism(),
+            search_path: session.config().search_path().to_vec(),
+        };
+        match session.plan_cache().get(sql, &plan_context) {
+            Some(plan) => plan,
+            None => {
+                let mut binder = Binder::new_with_param_types(
+                    catalog_reader,
+                    session.database().to_string(),
+                    plan_context.param_types.iter().cloned().map(Some).collect(),
+                )
+                .with_search_path(plan_context.search_path.clone());
+                let bound = binder.bind(stmt)?;
+                drop(binder);
+                let (query, pg_descs) = gen_batch_query(context, bound, plan_context.query_mode)?;
+                session.plan_cache().insert(
+                    sql.to_string(),
+                    CachedPlan {
+                        context: plan_context,
+                        query,
+                        pg_descs,
+                    },
+                )
+            }
+        }
+    };
+
+    let query = plan.query.bind_params(&params)?;
+    let format =
+        PgValueFormat::from_config(&session.config()).with_result_format_codes(result_format_codes);
+    execute_query(
+        session,
+        StatementType::SELECT,
+        query,
+        plan.pg_descs.clone(),
+        plan.context.query_mode,
+        format,
+    )
+    .await
+}
+
+/// Converts the value of a parameter in the text format into a constant of its type.
+fn param_to_expr_proto(value: Option<String>, data_type: DataType) -> Result<ExprNode> {
+    let datum = match value {
+        Some(value) => {
+            let text: ExprImpl =
+                Literal::new(Some(ScalarImpl::Utf8(value)), DataType::Varchar).into();
+            let expr = build_from_prost(&text.cast_explicit(data_type.clone())?.to_expr_proto())?;
+            expr.eval(&DataChunk::new_dummy(1))?
+                .value_at(0)
+                .to_owned_datum()
+        }
+        None => None,
+    };
+    Ok(Literal::new(datum, data_type).to_expr_proto())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::DataType;
+    use risingwave_sqlparser::parser::Parser;
+
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_describe_statement() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 varchar, v3 double)")
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let describe = |sql: &str, param_types| {
+            let stmt = Parser::parse_sql(sql).unwrap().remove(0);
+            describe_statement(&session, stmt, param_types).unwrap()
+        };
+
+        let (param_types, fields) = describe(
+            "select v1 + $2, v2 from t where v3 > $1 and v2 = $3",
+            vec![None, Some(DataType::Int64)],
+        );
+        assert_eq!(
+            param_types,
+            vec![DataType::Float64, DataType::Int64, DataType::Varchar]
+        );
+        let fields = fields.unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].get_name(), "v2");
+
+        let (param_types, fields) = describe("insert into t (v3, v1) values ($1, $2)", vec![]);
+        assert_eq!(param_types, vec![DataType::Float64, DataType::Int32]);
+        assert!(fields.unwrap().is_empty());
+
+        let (param_types, fields) = describe("update t set v1 = $1 where v2 in ($2)", vec![]);
+        assert_eq!(param_types, vec![DataType::Int32, DataType::Varchar]);
+        assert!(fields.unwrap().is_empty());
+
+        let (param_types, fields) = describe("select $1::bigint, $2", vec![]);
+        assert_eq!(param_types, vec![DataType::Int64, DataType::Varchar]);
+        assert_eq!(fields.unwrap().len(), 2);
+
+        let (_, fields) = describe("show tables", vec![]);
+        assert!(fields.is_none());
+
+        // Parameters are only allowed in prepared statements.
+        assert!(frontend.run_sql("select $1").await.is_err());
+    }
+}
@@ -12,23 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use futures_async_stream::for_await;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_async_stream::{for_await, try_stream};
+use itertools::Itertools;
 use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::pg_server::BoxedError;
 use risingwave_batch::executor::BoxedDataChunkStream;
-use risingwave_common::error::Result;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_sqlparser::ast::Statement;
 use tracing::info;
 
 use crate::binder::{Binder, BoundStatement};
 use crate::config::QueryMode;
-use crate::handler::util::{to_pg_field, to_pg_rows};
+use crate::handler::util::{to_pg_field, to_pg_rows, PgValueFormat};
 use crate::planner::Planner;
-use crate::scheduler::plan_fragmenter::BatchPlanFragmenter;
+use crate::scheduler::plan_fragmenter::{BatchPlanFragmenter, Query};
 use crate::scheduler::{ExecutionContext, ExecutionContextRef, LocalQueryExecution};
-use crate::session::OptimizerContext;
-
-pub static QUERY_MODE: &str = "query_mode";
+use crate::session::{OptimizerContext, SessionImpl};
 
 pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
@@ -38,32 +43,15 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
-        );
+        )
+        .with_search_path(session.config().search_path().to_vec());
         binder.bind(stmt)?
     };
 
-    let query_mode = session
-        .get_config(QUERY_MODE)
-        .map(|entry| entry.get_val(QueryMode::default()))
-        .unwrap_or_default();
-
-    let (data_stream, pg_descs) = match query_mode {
-        QueryMode::Local => local_execute(context, bound).await?,
-        QueryMode::Distributed => distribute_execute(context, bound).await?,
-    };
-
-    let mut rows = vec![];
-    #[for_await]
-    for chunk in data_stream {
-        rows.extend(to_pg_rows(chunk?));
-    }
-
-    let rows_count = match stmt_type {
-        StatementType::SELECT => rows.len() as i32,
-        _ => unreachable!(),
-    };
-
-    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
+    let query_mode = session.config().query_mode();
+    let (query, pg_descs) = gen_batch_query(context, bound, query_mode)?;
+    let format = PgValueFormat::from_config(&session.config());
+    execute_query(session, stmt_type, query, pg_descs, query_mode, format).await
 }
 
 fn to_statement_type(stmt: &Statement) -> StatementType {
@@ -75,76 +63,162 @@ fn to_statement_type(stmt: &Statement) -> StatementType {
     }
 }
 
-async fn distribute_execute(
-    context: OptimizerContext,
-    stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
-    let session = context.session_ctx.clone();
-    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
-        let root = Planner::new(context.into()).plan(stmt)?;
-
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
-
-        let plan = root.gen_batch_query_plan()?;
-
-        info!(
-            "Generated distributed plan: {:?}",
-            plan.explain_to_string()?
-        );
-
-        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
-        let query = plan_fragmenter.split(plan)?;
-        info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+/// Runs a query, and returns the schema of the results along with the stream of them. Unlike
+/// [`handle_query`], the results are kept in chunks, e.g., to be exported in Arrow.
+pub async fn handle_query_for_chunks(
+    session: Arc<SessionImpl>,
+    stmt: Statement,
+) -> Result<(Schema, BoxedDataChunkStream)> {
+    let (query, schema, query_mode) = plan_query(session.clone(), stmt)?;
+    let data_stream =
+        session.cancellable_stream(execute_stream(session.clone(), query, query_mode));
+    Ok((schema, data_stream))
+}
+
+/// Plans a query without running it, and returns the schema of the results.
+pub fn describe_query_schema(session: Arc<SessionImpl>, stmt: Statement) -> Result<Schema> {
+    let (_, schema, _) = plan_query(session, stmt)?;
+    Ok(schema)
+}
+
+fn plan_query(session: Arc<SessionImpl>, stmt: Statement) -> Result<(Query, Schema, QueryMode)> {
+    if !matches!(stmt, Statement::Query(_)) {
+        return Err(ErrorCode::InvalidInputSyntax(format!("expect a query, got {}", stmt)).into());
+    }
+    let bound = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        )
+        .with_search_path(session.config().search_path().to_vec());
+        binder.bind(stmt)?
     };
+    let query_mode = session.config().query_mode();
+    let (query, schema) =
+        gen_batch_query_with_schema(OptimizerContext::new(session), bound, query_mode)?;
+    Ok((query, schema, query_mode))
+}
 
-    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
-    let query_manager = execution_context.session().env().query_manager().clone();
-    Ok((
-        Box::pin(query_manager.schedule(execution_context, query).await?),
-        pg_descs,
-    ))
+/// Plans a bound query and splits the plan into stages. Returns the stages and the fields of the
+/// rows returned by the query.
+pub(super) fn gen_batch_query(
+    context: OptimizerContext,
+    stmt: BoundStatement,
+    query_mode: QueryMode,
+) -> Result<(Query, Vec<PgFieldDescriptor>)> {
+    let (query, schema) = gen_batch_query_with_schema(context, stmt, query_mode)?;
+    let pg_descs = schema
+        .fields()
+        .iter()
+        .map(to_pg_field)
+        .collect::<Vec<PgFieldDescriptor>>();
+    Ok((query, pg_descs))
 }
 
-async fn local_execute(
+/// Like [`gen_batch_query`], but returns the schema of the results instead.
+fn gen_batch_query_with_schema(
     context: OptimizerContext,
     stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
+    query_mode: QueryMode,
+) -> Result<(Query, Schema)> {
+    let _span = tracing::trace_span!("gen_batch_query", %query_mode).entered();
     let session = context.session_ctx.clone();
+    let root = Planner::new(context.into()).plan(stmt)?;
+    let schema = root.schema().clone();
+
+    let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
+    let query = match query_mode {
+        QueryMode::Local => {
+            let plan = root.gen_batch_local_plan()?;
+
+            info!(
+                "Generated local execution plan: {:?}",
+                plan.explain_to_string()?
+            );
+
+            plan_fragmenter.split(plan)?
+        }
+        QueryMode::Distributed => {
+            let plan = root.gen_batch_query_plan()?;
+
+            info!(
+                "Generated distributed plan: {:?}",
+                plan.explain_to_string()?
+            );
+
+            plan_fragmenter
+                .with_parallelism(session.config().batch_parallelism())
+                .split(plan)?
+        }
+    };
+    info!("Generated query after plan fragmenter: {:?}", &query);
+    Ok((query, schema))
+}
 
-    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
-        let root = Planner::new(context.into()).plan(stmt)?;
-
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
-
-        let plan = root.gen_batch_local_plan()?;
-
-        info!(
-            "Generated local execution plan: {:?}",
-            plan.explain_to_string()?
-        );
+/// Executes the stages of a query. The rows are streamed to the client as the query runs.
+pub(super) async fn execute_query(
+    session: Arc<SessionImpl>,
+    stmt_type: StatementType,
+    query: Query,
+    pg_descs: Vec<PgFieldDescriptor>,
+    query_mode: QueryMode,
+    format: PgValueFormat,
+) -> Result<PgResponse> {
+    let type_oids = pg_descs.iter().map(|d| d.get_type_oid()).collect_vec();
+    let data_stream =
+        session.cancellable_stream(execute_stream(session.clone(), query, query_mode));
+    let rows_stream = data_stream
+        .map(move |chunk| {
+            chunk
+                .map(|chunk| to_pg_rows(chunk, &type_oids, &format))
+                .map_err(BoxedError::from)
+        })
+        .boxed();
+
+    Ok(PgResponse::new_for_stream(
+        stmt_type,
+        0,
+        rows_stream,
+        pg_descs,
+    ))
+}
 
-        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
-        let query = plan_fragmenter.split(plan)?;
-        info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+/// Schedules the query once the stream is polled, and yields the chunks of its results.
+#[try_stream(boxed, ok = DataChunk, error = RwError)]
+pub(super) async fn execute_stream(session: Arc<SessionImpl>, query: Query, query_mode: QueryMode) {
+    let query_mode_label = query_mode.to_string();
+    let metrics = session.env().frontend_metrics();
+    metrics
+        .query_counter
+        .with_label_values(&[&query_mode_label])
+        .inc();
+    // Observed once the results are all returned, or the query fails or is cancelled.
+    let _timer = metrics
+        .query_latency
+        .with_label_values(&[&query_mode_label])
+        .start_timer();
+
+    let data_stream: BoxedDataChunkStream = match query_mode {
+        QueryMode::Local => {
+            let hummock_snapshot_manager = session.env().hummock_snapshot_manager().clone();
+
+            let execution_context: ExecutionContextRef =
+                ExecutionContext::new(session.clone()).into();
+            // TODO: Passing sql here
+            let execution =
+                LocalQueryExecution::new(query, hummock_snapshot_manager, execution_context, "");
+            Box::pin(execution.run())
+        }
+        QueryMode::Distributed => {
+            let execution_context: ExecutionContextRef =
+                ExecutionContext::new(session.clone()).into();
+            let query_manager = execution_context.session().env().query_manager().clone();
+            Box::pin(query_manager.schedule(execution_context, query).await?)
+        }
     };
 
-    let hummock_snapshot_manager = session.env().hummock_snapshot_manager().clone();
-
-    // TODO: Passing sql here
-    let execution = LocalQueryExecution::new(query, hummock_snapshot_manager, "");
-    Ok((Box::pin(execution.run()), pg_descs))
+    #[for_await]
+    for chunk in data_stream {
+        yield chunk?;
+    }
 }
@@ -14,8 +14,9 @@
 
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
-use risingwave_sqlparser::ast::{Ident, SetVariableValue};
+use risingwave_sqlparser::ast::{Ident, SetVariableValue, Value};
 
+use crate::config::ConfigMap;
 use crate::session::OptimizerContext;
 
 pub(super) fn handle_set(
@@ -23,16 +24,42 @@ pub(super) fn handle_set(
     name: Ident,
     value: Vec<SetVariableValue>,
 ) -> Result<PgResponse> {
-    let string_val = to_string(&value[0]);
-    // Currently store the config variable simply as String -> ConfigEntry(String).
-    // In future we can add converter/parser to make the API more robust.
-    context.session_ctx.set_config(&name.value, &string_val);
+    // `SET x = DEFAULT` is the same as `RESET x`.
+    let is_default = matches!(
+        value.as_slice(),
+        [SetVariableValue::Ident(ident)] if ident.value.eq_ignore_ascii_case("default")
+    );
+    if is_default {
+        return handle_reset(context, name);
+    }
+
+    let string_vals = value.iter().map(to_string).collect::<Vec<_>>();
+    context.session_ctx.set_config(&name.value, &string_vals)?;
+
+    if ConfigMap::is_recognized(&name.value) {
+        Ok(PgResponse::empty_result(StatementType::SET_OPTION))
+    } else {
+        Ok(PgResponse::empty_result_with_notice(
+            StatementType::SET_OPTION,
+            format!(
+                "unrecognized configuration parameter \"{}\" is ignored",
+                name.value
+            ),
+        ))
+    }
+}
+
+pub(super) fn handle_reset(context: OptimizerContext, name: Ident) -> Result<PgResponse> {
+    context.session_ctx.reset_config(&name.value)?;
 
     Ok(PgResponse::empty_result(StatementType::SET_OPTION))
 }
 
 /// Convert any set variable to String.
-/// For example, TRUE -> "TRUE", 1 -> "1".
+/// For example, TRUE -> "true", 1 -> "1", 'UTC' -> "UTC".
 fn to_string(value: &SetVariableValue) -> String {
-    format!("{}", value)
+    match value {
+        SetVariableValue::Literal(Value::SingleQuotedString(s)) => s.clone(),
+        _ => format!("{}", value),
+    }
 }
@@ -21,6 +21,7 @@ use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::{Ident, ObjectName, ShowObject};
 
 use crate::binder::Binder;
+use crate::config::TIMEZONE;
 use crate::handler::util::col_descs_to_rows;
 use crate::session::{OptimizerContext, SessionImpl};
 
@@ -116,6 +117,51 @@ pub async fn handle_show_object(
     ))
 }
 
+pub fn handle_show_variable(context: OptimizerContext, variable: Vec<Ident>) -> Result<PgResponse> {
+    let config = context.session_ctx.config();
+    let name = variable.iter().map(|v| v.value.as_str()).join(" ");
+
+    if name.eq_ignore_ascii_case("all") {
+        let rows = config
+            .show_all()
+            .into_iter()
+            .map(|info| {
+                Row::new(vec![
+                    Some(info.name),
+                    Some(info.setting),
+                    Some(info.description),
+                ])
+            })
+            .collect_vec();
+
+        return Ok(PgResponse::new(
+            StatementType::SHOW_COMMAND,
+            rows.len() as i32,
+            rows,
+            vec![
+                PgFieldDescriptor::new("Name".to_owned(), TypeOid::Varchar),
+                PgFieldDescriptor::new("Setting".to_owned(), TypeOid::Varchar),
+                PgFieldDescriptor::new("Description".to_owned(), TypeOid::Varchar),
+            ],
+        ));
+    }
+
+    // `SHOW TIME ZONE` is an alias for `SHOW timezone`.
+    let name = if name.eq_ignore_ascii_case("time zone") {
+        TIMEZONE.to_string()
+    } else {
+        name
+    };
+    let row = Row::new(vec![Some(config.get(&name)?)]);
+
+    Ok(PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        1,
+        vec![row],
+        vec![PgFieldDescriptor::new(name, TypeOid::Varchar)],
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -192,4 +238,20 @@ mod tests {
 
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_show_variable() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let rows = frontend.query_formatted_result("SHOW query_mode").await;
+        assert_eq!(rows, vec!["Row([Some(\"distributed\")])".to_string()]);
+
+        let rows = frontend.query_formatted_result("SHOW TIME ZONE").await;
+        assert_eq!(rows, vec!["Row([Some(\"UTC\")])".to_string()]);
+
+        let rows = frontend.query_formatted_result("SHOW ALL").await;
+        assert_eq!(rows.len(), 7);
+
+        assert!(frontend.run_sql("SHOW no_such_variable").await.is_err());
+    }
 }
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::LowerExp;
+use std::str::FromStr;
+
+use chrono::{FixedOffset, NaiveDateTime, TimeZone};
 use itertools::Itertools;
 use num_traits::Float;
 use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
@@ -20,17 +24,103 @@ use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::{ColumnDesc, Field};
 use risingwave_common::types::{DataType, ScalarRefImpl};
 
+use crate::config::ConfigMap;
+
+/// The session variables that decide how values are formatted for the client.
+#[derive(Clone, Debug)]
+pub struct PgValueFormat {
+    pub extra_float_digits: i32,
+    /// `timestamptz` values are displayed in this time zone.
+    pub timezone: FixedOffset,
+    /// The formats requested for the columns by the client, following the convention of the Bind
+    /// message: no codes for all in text, a single code for all columns, or one code per column.
+    pub result_format_codes: Vec<i16>,
+}
+
+impl PgValueFormat {
+    pub fn from_config(config: &ConfigMap) -> Self {
+        Self {
+            extra_float_digits: config.extra_float_digits(),
+            timezone: config.timezone_offset(),
+            result_format_codes: vec![],
+        }
+    }
+
+    pub fn with_result_format_codes(self, result_format_codes: Vec<i16>) -> Self {
+        Self {
+            result_format_codes,
+            ..self
+        }
+    }
+
+    /// Whether the `column` is sent in the binary format.
+    fn is_binary(&self, column: usize) -> bool {
+        match self.result_format_codes.as_slice() {
+            [] => false,
+            [code] => *code == 1,
+            codes => codes.get(column) == Some(&1),
+        }
+    }
+}
+
+impl Default for PgValueFormat {
+    fn default() -> Self {
+        Self::from_config(&ConfigMap::default())
+    }
+}
+
 /// Format scalars according to postgres convention.
-fn pg_value_format(d: ScalarRefImpl) -> String {
-    match d {
-        ScalarRefImpl::Bool(b) => if b { "t" } else { "f" }.to_string(),
-        ScalarRefImpl::Float32(v) => pg_float_format(v),
-        ScalarRefImpl::Float64(v) => pg_float_format(v),
+fn pg_value_format(d: ScalarRefImpl, type_oid: TypeOid, format: &PgValueFormat) -> String {
+    match (d, type_oid) {
+        (ScalarRefImpl::Bool(b), _) => if b { "t" } else { "f" }.to_string(),
+        (ScalarRefImpl::Float32(v), _) => {
+            pg_float_format(v.0, f32::DIGITS, format.extra_float_digits)
+        }
+        (ScalarRefImpl::Float64(v), _) => {
+            pg_float_format(v.0, f64::DIGITS, format.extra_float_digits)
+        }
+        (ScalarRefImpl::Int64(v), TypeOid::Timestampz) => pg_timestampz_format(v, format.timezone),
         _ => d.to_string(),
     }
 }
 
-fn pg_float_format<T: Float + ToString>(v: T) -> String {
+/// `timestamptz` values are stored as microseconds since the UNIX epoch, and displayed in the
+/// session time zone like Postgres, e.g. `2022-06-01 08:00:00.5+08`: the fractional seconds are
+/// trimmed of trailing zeros, and the minutes of the offset are omitted if zero.
+fn pg_timestampz_format(micros: i64, timezone: FixedOffset) -> String {
+    let subsec_micros = micros.rem_euclid(1_000_000);
+    let utc =
+        NaiveDateTime::from_timestamp(micros.div_euclid(1_000_000), (subsec_micros * 1000) as u32);
+    let mut text = timezone
+        .from_utc_datetime(&utc)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    if subsec_micros != 0 {
+        let fraction = format!("{:06}", subsec_micros);
+        text.push('.');
+        text.push_str(fraction.trim_end_matches('0'));
+    }
+
+    let offset = timezone.local_minus_utc();
+    text.push(if offset < 0 { '-' } else { '+' });
+    let offset = offset.abs();
+    text.push_str(&format!("{:02}", offset / 3600));
+    if offset % 3600 != 0 {
+        text.push_str(&format!(":{:02}", offset % 3600 / 60));
+    }
+    if offset % 60 != 0 {
+        text.push_str(&format!(":{:02}", offset % 60));
+    }
+    text
+}
+
+/// Same as postgres, a positive `extra_float_digits` outputs the shortest precise representation,
+/// otherwise the value is rounded to `digits + extra_float_digits` significant digits.
+fn pg_float_format<T: Float + ToString + FromStr + LowerExp>(
+    v: T,
+    digits: u32,
+    extra_float_digits: i32,
+) -> String {
     if v.is_infinite() {
         if v.is_sign_positive() {
             "Infinity"
@@ -40,18 +130,39 @@ fn pg_float_format<T: Float + ToString>(v: T) -> String {
         .to_string()
     } else if v.is_nan() {
         "NaN".to_string()
-    } else {
+    } else if extra_float_digits > 0 {
         v.to_string()
+    } else {
+        let precision = (digits as i32 + extra_float_digits).max(1) as usize;
+        // Round in scientific notation, then print the rounded value in the shortest form.
+        let rounded = format!("{:.*e}", precision - 1, v);
+        rounded
+            .parse::<T>()
+            .map_or(rounded, |rounded| rounded.to_string())
     }
 }
 
-pub fn to_pg_rows(chunk: DataChunk) -> Vec<Row> {
+/// Convert the rows of `chunk` to texts. `type_oids` are the types of the columns sent to the
+/// client. The columns sent in the binary format are converted to the plain texts of the values,
+/// e.g., the microseconds of `timestamptz`, from which pgwire encodes the binary exactly, rather
+/// than to the texts displayed in the session.
+pub fn to_pg_rows(chunk: DataChunk, type_oids: &[TypeOid], format: &PgValueFormat) -> Vec<Row> {
     chunk
         .rows()
         .map(|r| {
             Row::new(
                 r.values()
-                    .map(|data| data.map(pg_value_format))
+                    .zip_eq(type_oids)
+                    .enumerate()
+                    .map(|(i, (data, type_oid))| {
+                        data.map(|d| {
+                            if format.is_binary(i) {
+                                d.to_string()
+                            } else {
+                                pg_value_format(d, *type_oid, format)
+                            }
+                        })
+                    })
                     .collect_vec(),
             )
         })
@@ -103,6 +214,23 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
     }
 }
 
+pub fn type_oid_to_data_type(type_oid: TypeOid) -> DataType {
+    match type_oid {
+        TypeOid::Boolean => DataType::Boolean,
+        TypeOid::BigInt => DataType::Int64,
+        TypeOid::SmallInt => DataType::Int16,
+        TypeOid::Int => DataType::Int32,
+        TypeOid::Float4 => DataType::Float32,
+        TypeOid::Float8 => DataType::Float64,
+        TypeOid::CharArray | TypeOid::Varchar => DataType::Varchar,
+        TypeOid::Date => DataType::Date,
+        TypeOid::Time => DataType::Time,
+        TypeOid::Timestamp => DataType::Timestamp,
+        TypeOid::Timestampz => DataType::Timestampz,
+        TypeOid::Decimal => DataType::Decimal,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use risingwave_common::array::*;
@@ -129,7 +257,13 @@ mod tests {
              3 7 7.01 vvv
              4 . .    .  ",
         );
-        let rows = to_pg_rows(chunk);
+        let type_oids = [
+            TypeOid::Int,
+            TypeOid::BigInt,
+            TypeOid::Float8,
+            TypeOid::Varchar,
+        ];
+        let rows = to_pg_rows(chunk, &type_oids, &PgValueFormat::default());
         let expected = vec![
             vec![
                 Some("1".to_string()),
@@ -154,11 +288,34 @@ mod tests {
         assert_eq!(vec, expected);
     }
 
+    #[test]
+    fn test_to_pg_rows_binary() {
+        let chunk = DataChunk::from_pretty(
+            "I I
+             1500000 1500000",
+        );
+        let type_oids = [TypeOid::Timestampz, TypeOid::Timestampz];
+        let format = PgValueFormat {
+            timezone: FixedOffset::east(8 * 3600),
+            ..Default::default()
+        }
+        .with_result_format_codes(vec![0, 1]);
+        let rows = to_pg_rows(chunk, &type_oids, &format);
+        assert_eq!(
+            rows[0].values(),
+            &[
+                Some("1970-01-01 08:00:01.5+08".to_string()),
+                Some("1500000".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_value_format() {
         use ScalarRefImpl as S;
 
-        let f = pg_value_format;
+        let format = PgValueFormat::default();
+        let f = |d| pg_value_format(d, TypeOid::Float8, &format);
         assert_eq!(&f(S::Float32(1_f32.into())), "1");
         assert_eq!(&f(S::Float32(f32::NAN.into())), "NaN");
         assert_eq!(&f(S::Float64(f64::NAN.into())), "NaN");
@@ -166,7 +323,62 @@ mod tests {
         assert_eq!(&f(S::Float32(f32::NEG_INFINITY.into())), "-Infinity");
         assert_eq!(&f(S::Float64(f64::INFINITY.into())), "Infinity");
         assert_eq!(&f(S::Float64(f64::NEG_INFINITY.into())), "-Infinity");
+        assert_eq!(
+            &f(S::Float64(0.30000000000000004.into())),
+            "0.30000000000000004"
+        );
+        let f = |d| pg_value_format(d, TypeOid::Boolean, &format);
         assert_eq!(&f(S::Bool(true)), "t");
         assert_eq!(&f(S::Bool(false)), "f");
     }
+
+    #[test]
+    fn test_extra_float_digits() {
+        use ScalarRefImpl as S;
+
+        let f = |d, extra_float_digits| {
+            let format = PgValueFormat {
+                extra_float_digits,
+                ..Default::default()
+            };
+            pg_value_format(d, TypeOid::Float8, &format)
+        };
+        assert_eq!(&f(S::Float64(0.30000000000000004.into()), 0), "0.3");
+        assert_eq!(&f(S::Float64(1234.5678.into()), -12), "1230");
+        assert_eq!(&f(S::Float32(0.1_f32.into()), 0), "0.1");
+        assert_eq!(&f(S::Float64(f64::NAN.into()), -15), "NaN");
+    }
+
+    #[test]
+    fn test_timestampz_format() {
+        let f = |micros, timezone| {
+            let format = PgValueFormat {
+                timezone,
+                ..Default::default()
+            };
+            pg_value_format(ScalarRefImpl::Int64(micros), TypeOid::Timestampz, &format)
+        };
+        assert_eq!(&f(0, FixedOffset::east(0)), "1970-01-01 00:00:00+00");
+        assert_eq!(
+            &f(1_500_000, FixedOffset::east(8 * 3600)),
+            "1970-01-01 08:00:01.5+08"
+        );
+        assert_eq!(
+            &f(-1, FixedOffset::west(3600)),
+            "1969-12-31 22:59:59.999999-01"
+        );
+        assert_eq!(
+            &f(0, FixedOffset::east(5 * 3600 + 30 * 60)),
+            "1970-01-01 05:30:00+05:30"
+        );
+        // The type decides whether an integer is a timestamp.
+        assert_eq!(
+            &pg_value_format(
+                ScalarRefImpl::Int64(0),
+                TypeOid::BigInt,
+                &Default::default()
+            ),
+            "0"
+        );
+    }
 }
@@ -38,7 +38,9 @@
 pub mod catalog;
 pub mod binder;
 pub mod expr;
+pub mod flight;
 pub mod handler;
+pub mod monitor;
 pub mod observer;
 pub mod optimizer;
 pub mod planner;
@@ -46,6 +48,7 @@ pub mod planner;
 mod scheduler;
 pub mod session;
 pub mod stream_fragmenter;
+pub mod user;
 pub mod utils;
 extern crate log;
 mod meta_client;
@@ -61,6 +64,7 @@ use std::sync::Arc;
 
 use clap::Parser;
 use pgwire::pg_server::pg_serve;
+use pgwire::pg_ssl::SslConfig;
 use session::SessionManagerImpl;
 
 #[derive(Parser, Clone, Debug)]
@@ -83,6 +87,54 @@ pub struct FrontendOpts {
     /// No given `config_path` means to use default config.
     #[clap(long, default_value = "")]
     pub config_path: String,
+
+    /// Path of the PEM file of the server certificate. SSL connections are accepted if it's given
+    /// along with `ssl_key`.
+    #[clap(long)]
+    pub ssl_cert: Option<String>,
+
+    /// Path of the PEM file of the private key of the server certificate.
+    #[clap(long)]
+    pub ssl_key: Option<String>,
+
+    /// Reject the clients that don't ask for SSL.
+    #[clap(long)]
+    pub require_ssl: bool,
+
+    #[clap(long, default_value = "127.0.0.1:2222")]
+    pub prometheus_listener_addr: String,
+
+    #[clap(long, default_value = "0")]
+    pub metrics_level: u32,
+
+    /// Enable reporting tracing information to jaeger
+    #[clap(long)]
+    pub enable_jaeger_tracing: bool,
+
+    /// Serve Arrow Flight at this address to export query results in Arrow, e.g.,
+    /// `127.0.0.1:4567`. Disabled if not given.
+    #[clap(long)]
+    pub flight_listen_addr: Option<String>,
+}
+
+impl FrontendOpts {
+    fn ssl_config(&self) -> Option<SslConfig> {
+        match (&self.ssl_cert, &self.ssl_key) {
+            (Some(cert_path), Some(key_path)) => Some(SslConfig {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                require_ssl: self.require_ssl,
+            }),
+            (None, None) => {
+                assert!(
+                    !self.require_ssl,
+                    "--require-ssl needs --ssl-cert and --ssl-key"
+                );
+                None
+            }
+            _ => panic!("--ssl-cert and --ssl-key must be given together"),
+        }
+    }
 }
 
 impl Default for FrontendOpts {
@@ -94,5 +146,16 @@ impl Default for FrontendOpts {
 /// Start frontend
 pub async fn start(opts: FrontendOpts) {
     let session_mgr = Arc::new(SessionManagerImpl::new(&opts).await.unwrap());
-    pg_serve(&opts.host, session_mgr).await.unwrap();
+    if let Some(flight_listen_addr) = &opts.flight_listen_addr {
+        let flight_listen_addr = flight_listen_addr.parse().unwrap();
+        let session_mgr = session_mgr.clone();
+        tokio::spawn(async move {
+            flight::flight_serve(flight_listen_addr, session_mgr)
+                .await
+                .unwrap()
+        });
+    }
+    pg_serve(&opts.host, session_mgr, opts.ssl_config())
+        .await
+        .unwrap();
 }
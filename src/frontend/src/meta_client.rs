@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::TableId;
 use risingwave_common::error::Result;
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 
@@ -27,6 +28,10 @@ pub trait FrontendMetaClient: Send + Sync {
     async fn flush(&self) -> Result<()>;
 
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()>;
+
+    async fn set_system_param(&self, param: String, value: String) -> Result<()>;
+
+    async fn alter_parallelism(&self, table_id: TableId, parallelism: u32) -> Result<()>;
 }
 
 pub struct FrontendMetaClientImpl(pub MetaClient);
@@ -44,4 +49,12 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()> {
         self.0.unpin_snapshot(&[epoch]).await
     }
+
+    async fn set_system_param(&self, param: String, value: String) -> Result<()> {
+        self.0.set_system_param(param, value).await
+    }
+
+    async fn alter_parallelism(&self, table_id: TableId, parallelism: u32) -> Result<()> {
+        self.0.alter_parallelism(table_id, parallelism).await
+    }
 }
@@ -0,0 +1,238 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use parking_lot::Mutex;
+
+/// The number of latest statements kept in the query log of a frontend node.
+pub const QUERY_LOG_CAPACITY: usize = 1000;
+
+/// A statement run on this frontend node.
+#[derive(Clone, Debug)]
+pub struct QueryLogRecord {
+    /// The process id of the session running the statement.
+    pub session_id: i32,
+    pub user_name: String,
+    pub statement: String,
+    pub start_time: SystemTime,
+    /// The time spent until the execution of a query starts. `None` for other statements, which
+    /// are planned and executed in one go.
+    pub planning_time: Option<Duration>,
+    pub execution_time: Duration,
+    /// The number of rows returned by a query, or affected by a command.
+    pub rows: i64,
+    pub error: Option<String>,
+}
+
+/// Keeps the latest statements run on this frontend node in a ring buffer.
+#[derive(Debug)]
+pub struct QueryLog {
+    capacity: usize,
+    records: Mutex<VecDeque<QueryLogRecord>>,
+}
+
+impl Default for QueryLog {
+    fn default() -> Self {
+        Self::new(QUERY_LOG_CAPACITY)
+    }
+}
+
+impl QueryLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Add a record, evicting the oldest one if the log is full.
+    pub fn push(&self, record: QueryLogRecord) {
+        let mut records = self.records.lock();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// All the records, from the oldest to the latest.
+    pub fn records(&self) -> Vec<QueryLogRecord> {
+        self.records.lock().iter().cloned().collect()
+    }
+}
+
+/// Replaces the string literal after each `PASSWORD` keyword, e.g., of `CREATE USER`, so that no
+/// password is kept in the log.
+fn redact_passwords(statement: &str) -> String {
+    const KEYWORD: &str = "password";
+    // The offsets are the same in the lowercase statement as the ASCII letters keep their lengths.
+    let lowercase = statement.to_ascii_lowercase();
+    let mut redacted = String::with_capacity(statement.len());
+    let mut pos = 0;
+    while let Some(found) = lowercase[pos..].find(KEYWORD) {
+        let keyword_end = pos + found + KEYWORD.len();
+        redacted.push_str(&statement[pos..keyword_end]);
+        pos = keyword_end;
+
+        let rest = &statement[pos..];
+        let quote = rest.len() - rest.trim_start().len();
+        if !rest[quote..].starts_with('\'') {
+            continue;
+        }
+        // Find the closing quote, where a doubled quote is an escaped one.
+        let literal = &rest[quote + 1..];
+        let mut literal_len = literal.len();
+        let mut chars = literal.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '\'' && chars.next_if(|(_, c)| *c == '\'').is_none() {
+                literal_len = i + 1;
+                break;
+            }
+        }
+        redacted.push_str(&rest[..quote]);
+        redacted.push_str("'********'");
+        pos += quote + 1 + literal_len;
+    }
+    redacted.push_str(&statement[pos..]);
+    redacted
+}
+
+/// A statement being run. It's added to the query log once dropped, by when the results of the
+/// statement are all returned, or it fails or gets cancelled. If it runs at least as long as
+/// `log_min_duration_statement`, it's also written to the server log.
+pub struct QueryLogEntry {
+    log: Arc<QueryLog>,
+    min_duration: Option<Duration>,
+    record: QueryLogRecord,
+    start: Instant,
+    execution_start: Instant,
+}
+
+impl QueryLogEntry {
+    pub fn new(
+        log: Arc<QueryLog>,
+        min_duration: Option<Duration>,
+        session_id: i32,
+        user_name: String,
+        statement: String,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            log,
+            min_duration,
+            record: QueryLogRecord {
+                session_id,
+                user_name,
+                statement: redact_passwords(&statement),
+                start_time: SystemTime::now(),
+                planning_time: None,
+                execution_time: Duration::ZERO,
+                rows: 0,
+                error: None,
+            },
+            start: now,
+            execution_start: now,
+        }
+    }
+
+    /// Mark the end of planning, for a query whose results are streamed once it's planned.
+    pub fn finish_planning(&mut self) {
+        self.execution_start = Instant::now();
+        self.record.planning_time = Some(self.execution_start - self.start);
+    }
+
+    pub fn add_rows(&mut self, rows: i64) {
+        self.record.rows += rows;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.record.error = Some(error);
+    }
+}
+
+impl Drop for QueryLogEntry {
+    fn drop(&mut self) {
+        self.record.execution_time = self.execution_start.elapsed();
+        let duration = self.start.elapsed();
+        if let Some(min_duration) = self.min_duration && duration >= min_duration {
+            tracing::info!(
+                "duration: {:.3} ms, rows: {}, statement: {}",
+                duration.as_secs_f64() * 1000.0,
+                self.record.rows,
+                self.record.statement
+            );
+        }
+        self.log.push(self.record.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_log() {
+        let log = Arc::new(QueryLog::new(2));
+        for i in 0..3 {
+            let mut entry = QueryLogEntry::new(
+                log.clone(),
+                Some(Duration::ZERO),
+                1,
+                "root".to_string(),
+                format!("SELECT {}", i),
+            );
+            entry.finish_planning();
+            entry.add_rows(1);
+        }
+        let mut entry = QueryLogEntry::new(
+            log.clone(),
+            None,
+            1,
+            "root".to_string(),
+            "SELEC".to_string(),
+        );
+        entry.set_error("syntax error".to_string());
+        drop(entry);
+
+        let records = log.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].statement, "SELECT 2");
+        assert_eq!(records[0].rows, 1);
+        assert!(records[0].planning_time.is_some());
+        assert_eq!(records[1].planning_time, None);
+        assert_eq!(records[1].error.as_deref(), Some("syntax error"));
+    }
+
+    #[test]
+    fn test_redact_passwords() {
+        assert_eq!(
+            redact_passwords("CREATE USER u WITH PASSWORD 'it''s secret' LOGIN"),
+            "CREATE USER u WITH PASSWORD '********' LOGIN"
+        );
+        assert_eq!(
+            redact_passwords("alter user u with password'x'"),
+            "alter user u with password'********'"
+        );
+        assert_eq!(
+            redact_passwords("CREATE USER u PASSWORD NULL"),
+            "CREATE USER u PASSWORD NULL"
+        );
+        assert_eq!(
+            redact_passwords("SELECT * FROM t WHERE password = 'x'"),
+            "SELECT * FROM t WHERE password = 'x'"
+        );
+    }
+}
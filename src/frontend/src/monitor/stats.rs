@@ -0,0 +1,55 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, HistogramVec, IntCounterVec, Registry,
+};
+
+pub struct FrontendMetrics {
+    pub registry: Registry,
+    pub query_counter: IntCounterVec,
+    pub query_latency: HistogramVec,
+}
+
+impl FrontendMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let query_counter = register_int_counter_vec_with_registry!(
+            "frontend_query_counter",
+            "Total number of queries that have been issued, by query mode",
+            &["query_mode"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "frontend_query_latency",
+            "Time between a query is scheduled and all its results are returned, by query mode",
+            exponential_buckets(0.001, 2.0, 20).unwrap()
+        );
+        let query_latency =
+            register_histogram_vec_with_registry!(opts, &["query_mode"], registry).unwrap();
+
+        Self {
+            registry,
+            query_counter,
+            query_latency,
+        }
+    }
+
+    /// Create a new `FrontendMetrics` instance used in tests or other places.
+    pub fn unused() -> Self {
+        Self::new(Registry::new())
+    }
+}
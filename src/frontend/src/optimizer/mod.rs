@@ -22,6 +22,8 @@ mod plan_rewriter;
 mod plan_visitor;
 mod rule;
 
+use std::collections::HashMap;
+
 use fixedbitset::FixedBitSet;
 use itertools::Itertools as _;
 use property::Order;
@@ -29,7 +31,7 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 
 use self::heuristic::{ApplyOrder, HeuristicOptimizer};
-use self::plan_node::{BatchProject, Convention, LogicalProject, StreamMaterialize};
+use self::plan_node::{BatchProject, Convention, LogicalProject, StreamMaterialize, StreamSink};
 use self::property::RequiredDist;
 use self::rule::*;
 use crate::catalog::TableId;
@@ -248,6 +250,22 @@ impl PlanRoot {
             heuristic_optimizer.optimize(plan)
         };
 
+        // Run hash aggregations in parallel branches within each actor.
+        let local_parallelism = plan
+            .ctx()
+            .inner()
+            .session_ctx
+            .config()
+            .streaming_local_parallelism()
+            .get() as usize;
+        let plan = if local_parallelism > 1 {
+            let rules = vec![LocalExchangeRule::create(local_parallelism)];
+            let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::BottomUp, rules);
+            heuristic_optimizer.optimize(plan)
+        } else {
+            plan
+        };
+
         Ok(plan)
     }
 
@@ -264,6 +282,21 @@ impl PlanRoot {
         )
     }
 
+    /// Optimize and generate a create sink plan, which writes the changes of the query to the
+    /// sink.
+    pub fn gen_create_sink_plan(
+        &mut self,
+        associated_table_id: TableId,
+        properties: HashMap<String, String>,
+    ) -> Result<StreamSink> {
+        let stream_plan = self.gen_stream_plan()?;
+        Ok(StreamSink::new(
+            stream_plan,
+            associated_table_id,
+            properties,
+        ))
+    }
+
     /// Optimize and generate a create index plan.
     pub fn gen_create_index_plan(
         &mut self,
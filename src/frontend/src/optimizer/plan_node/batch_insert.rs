@@ -17,11 +17,12 @@ use std::fmt;
 use risingwave_common::error::Result;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::InsertNode;
-use risingwave_pb::plan_common::TableRefId;
+use risingwave_pb::expr::InputRefExpr;
+use risingwave_pb::plan_common::{ColumnOrder, ConflictBehavior, TableRefId};
 
 use super::{LogicalInsert, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch};
 use crate::optimizer::plan_node::{PlanBase, ToLocalBatch};
-use crate::optimizer::property::{Distribution, Order};
+use crate::optimizer::property::{Distribution, Order, RequiredDist};
 
 /// `BatchInsert` implements [`LogicalInsert`]
 #[derive(Debug, Clone)]
@@ -64,21 +65,59 @@ impl_plan_tree_node_for_unary! { BatchInsert }
 
 impl ToDistributedBatch for BatchInsert {
     fn to_distributed(&self) -> Result<PlanRef> {
-        let new_input = self.input().to_distributed()?;
+        // All rows are written by a single task, which assigns the row ids.
+        let new_input = RequiredDist::single()
+            .enforce_if_not_satisfies(self.input().to_distributed()?, Order::any())?;
         Ok(self.clone_with_input(new_input).into())
     }
 }
 
 impl ToBatchProst for BatchInsert {
     fn to_batch_prost_body(&self) -> NodeBody {
-        NodeBody::Insert(InsertNode {
+        let mut node = InsertNode {
             table_source_ref_id: TableRefId {
                 table_id: self.logical.source_id().table_id() as i32,
                 ..Default::default()
             }
             .into(),
             column_ids: vec![], // unused
-        })
+            conflict_behavior: ConflictBehavior::NoCheck as i32,
+            not_null_column_indices: self
+                .logical
+                .not_null_columns()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            ..Default::default()
+        };
+        if let Some((table, behavior)) = self.logical.conflict_check() {
+            node.table_id = table.id().table_id();
+            node.column_descs = table
+                .columns()
+                .iter()
+                .map(|col| col.column_desc.to_protobuf())
+                .collect();
+            node.pk_orders = table
+                .order_desc()
+                .iter()
+                .map(|order| {
+                    let idx = table
+                        .columns()
+                        .iter()
+                        .position(|col| col.column_desc.column_id == order.column_desc.column_id)
+                        .unwrap();
+                    ColumnOrder {
+                        order_type: order.order.to_prost() as i32,
+                        input_ref: Some(InputRefExpr {
+                            column_idx: idx as i32,
+                        }),
+                        return_type: Some(order.column_desc.data_type.to_protobuf()),
+                    }
+                })
+                .collect();
+            node.conflict_behavior = *behavior as i32;
+        }
+        NodeBody::Insert(node)
     }
 }
 
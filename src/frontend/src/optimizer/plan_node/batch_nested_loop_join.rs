@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fmt;
+use std::num::NonZeroU64;
 
 use risingwave_common::error::Result;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
@@ -92,9 +93,18 @@ impl ToDistributedBatch for BatchNestedLoopJoin {
 
 impl ToBatchProst for BatchNestedLoopJoin {
     fn to_batch_prost_body(&self) -> NodeBody {
+        let max_output_rows = self
+            .base
+            .ctx
+            .inner()
+            .session_ctx
+            .config()
+            .batch_nested_loop_join_max_rows()
+            .map_or(0, NonZeroU64::get);
         NodeBody::NestedLoopJoin(NestedLoopJoinNode {
             join_type: self.logical.join_type() as i32,
             join_cond: Some(ExprImpl::from(self.logical.on().clone()).to_expr_proto()),
+            max_output_rows,
         })
     }
 }
@@ -17,34 +17,50 @@ use std::fmt;
 use risingwave_common::error::Result;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::RowSeqScanNode;
-use risingwave_pb::plan_common::{CellBasedTableDesc, ColumnDesc as ProstColumnDesc};
+use risingwave_pb::plan_common::{
+    CellBasedTableDesc, ColumnDesc as ProstColumnDesc, OrderedColumnDesc as ProstOrderedColumnDesc,
+};
 
 use super::{PlanBase, PlanRef, ToBatchProst, ToDistributedBatch};
 use crate::optimizer::plan_node::{LogicalScan, ToLocalBatch};
 use crate::optimizer::property::{Distribution, Order};
+use crate::utils::ScanRange;
 
 /// `BatchSeqScan` implements [`super::LogicalScan`] to scan from a row-oriented table
 #[derive(Debug, Clone)]
 pub struct BatchSeqScan {
     pub base: PlanBase,
     logical: LogicalScan,
+    scan_range: ScanRange,
 }
 
 impl BatchSeqScan {
-    pub fn new_inner(logical: LogicalScan, dist: Distribution) -> Self {
+    pub fn new_inner(logical: LogicalScan, dist: Distribution, scan_range: ScanRange) -> Self {
         let ctx = logical.base.ctx.clone();
         // TODO: derive from input
         let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
 
-        Self { base, logical }
+        Self {
+            base,
+            logical,
+            scan_range,
+        }
     }
 
     pub fn new(logical: LogicalScan) -> Self {
-        Self::new_inner(logical, Distribution::Single)
+        Self::new_inner(logical, Distribution::Single, ScanRange::full_table_scan())
     }
 
-    pub fn with_dist(logical: LogicalScan) -> Self {
-        Self::new_inner(logical, Distribution::SomeShard)
+    pub fn new_with_scan_range(logical: LogicalScan, scan_range: ScanRange) -> Self {
+        Self::new_inner(logical, Distribution::Single, scan_range)
+    }
+
+    fn clone_with_dist(&self) -> Self {
+        Self::new_inner(
+            self.logical.clone(),
+            Distribution::SomeShard,
+            self.scan_range.clone(),
+        )
     }
 
     /// Get a reference to the batch seq scan's logical.
@@ -52,24 +68,40 @@ impl BatchSeqScan {
     pub fn logical(&self) -> &LogicalScan {
         &self.logical
     }
+
+    /// Get a reference to the batch seq scan's scan range.
+    #[must_use]
+    pub fn scan_range(&self) -> &ScanRange {
+        &self.scan_range
+    }
 }
 
 impl_plan_tree_node_for_leaf! { BatchSeqScan }
 
 impl fmt::Display for BatchSeqScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "BatchScan {{ table: {}, columns: [{}] }}",
-            self.logical.table_name(),
-            self.logical.column_names().join(", ")
-        )
+        if self.scan_range.is_full_table_scan() {
+            write!(
+                f,
+                "BatchScan {{ table: {}, columns: [{}] }}",
+                self.logical.table_name(),
+                self.logical.column_names().join(", ")
+            )
+        } else {
+            write!(
+                f,
+                "BatchScan {{ table: {}, columns: [{}], scan_range: {} }}",
+                self.logical.table_name(),
+                self.logical.column_names().join(", "),
+                self.scan_range
+            )
+        }
     }
 }
 
 impl ToDistributedBatch for BatchSeqScan {
     fn to_distributed(&self) -> Result<PlanRef> {
-        Ok(Self::with_dist(self.logical.clone()).into())
+        Ok(self.clone_with_dist().into())
     }
 }
 
@@ -82,18 +114,44 @@ impl ToBatchProst for BatchSeqScan {
             .map(ProstColumnDesc::from)
             .collect();
 
+        let pk = self
+            .logical
+            .table_desc()
+            .order_desc
+            .iter()
+            .map(|desc| ProstOrderedColumnDesc {
+                column_desc: Some(desc.column_desc.to_protobuf()),
+                order: desc.order.to_prost() as i32,
+            })
+            .collect();
+
+        let parallelism = self
+            .base
+            .ctx
+            .inner()
+            .session_ctx
+            .config()
+            .batch_scan_parallelism()
+            .get();
+
         NodeBody::RowSeqScan(RowSeqScanNode {
             table_desc: Some(CellBasedTableDesc {
                 table_id: self.logical.table_desc().table_id.into(),
-                pk: vec![], // TODO:
+                pk,
             }),
             column_descs,
+            scan_range: if self.scan_range.is_full_table_scan() {
+                None
+            } else {
+                Some(self.scan_range.to_protobuf())
+            },
+            parallelism,
         })
     }
 }
 
 impl ToLocalBatch for BatchSeqScan {
     fn to_local(&self) -> Result<PlanRef> {
-        Ok(Self::with_dist(self.logical.clone()).into())
+        Ok(self.clone_with_dist().into())
     }
 }
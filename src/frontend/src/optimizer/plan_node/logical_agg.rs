@@ -19,7 +19,7 @@ use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, ScalarImpl};
 use risingwave_expr::expr::AggKind;
 use risingwave_pb::expr::AggCall as ProstAggCall;
 
@@ -27,7 +27,9 @@ use super::{
     BatchHashAgg, BatchSimpleAgg, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
     PredicatePushdown, StreamHashAgg, StreamSimpleAgg, ToBatch, ToStream,
 };
-use crate::expr::{AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef};
+use crate::expr::{
+    AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef, Literal,
+};
 use crate::optimizer::plan_node::{gen_filter_and_pushdown, LogicalProject};
 use crate::optimizer::property::RequiredDist;
 use crate::utils::{ColIndexMapping, Condition, Substitute};
@@ -73,7 +75,9 @@ impl PlanAggCall {
             | AggKind::Max
             | AggKind::Avg
             | AggKind::StringAgg
-            | AggKind::SingleValue => self.agg_kind.clone(),
+            | AggKind::SingleValue
+            | AggKind::StddevSamp
+            | AggKind::VarPop => self.agg_kind.clone(),
 
             AggKind::Count | AggKind::RowCount | AggKind::Sum => AggKind::Sum,
         };
@@ -161,6 +165,125 @@ impl ExprHandler {
         }
         Ok(rewritten_expr)
     }
+
+    /// Projects `inputs` of an agg call, reusing the existing projections of the same
+    /// expressions, and returns the references to them.
+    fn project_inputs(&mut self, inputs: Vec<ExprImpl>) -> Vec<InputRef> {
+        let mut index = self.project.len();
+        let mut input_refs = vec![];
+        self.project.extend(inputs.into_iter().filter(|expr| {
+            if let Some(idx) = self.expr_index.get(expr) {
+                input_refs.push(InputRef::new(*idx, expr.return_type()));
+                false
+            } else {
+                self.expr_index.insert(expr.clone(), index);
+                input_refs.push(InputRef::new(index, expr.return_type()));
+                index += 1;
+                true
+            }
+        }));
+        input_refs
+    }
+
+    /// Adds a non-distinct agg call on `input` and returns the reference to its result. An
+    /// identical agg call added before is reused.
+    fn push_unary_agg_call(&mut self, agg_kind: AggKind, input: ExprImpl) -> ExprImpl {
+        let return_type = AggCall::infer_return_type(&agg_kind, &[input.return_type()]).unwrap();
+        let inputs = self.project_inputs(vec![input]);
+        let index = match self.agg_calls.iter().position(|agg_call| {
+            agg_call.agg_kind == agg_kind && !agg_call.distinct && agg_call.inputs == inputs
+        }) {
+            Some(index) => index,
+            None => {
+                self.agg_calls.push(PlanAggCall {
+                    agg_kind,
+                    return_type: return_type.clone(),
+                    inputs,
+                    distinct: false,
+                });
+                self.agg_calls.len() - 1
+            }
+        };
+        InputRef::new(self.group_key_len + index, return_type).into()
+    }
+
+    /// Rewrites `var_pop(x)` and `stddev_samp(x)` into sums and counts, which support retraction
+    /// in streaming:
+    ///
+    /// - `var_pop(x) = (sum(x * x) - sum(x) * sum(x) / count(x)) / count(x)`
+    /// - `stddev_samp(x) = sqrt((sum(x * x) - sum(x) * sum(x) / count(x)) / (count(x) - 1))`
+    ///
+    /// `x` is cast to `double precision` in advance. The sum of squared deviations is clamped to
+    /// 0 to get rid of negative rounding errors. `var_pop` is null for no rows and `stddev_samp`
+    /// is null for less than 2 rows, rather than the `NaN` of dividing by a zero count, which
+    /// happens when all the rows are retracted in streaming.
+    fn rewrite_variance(&mut self, agg_kind: AggKind, input: ExprImpl) -> ExprImpl {
+        let call = |func_type: ExprType, inputs: Vec<ExprImpl>| {
+            ExprImpl::from(FunctionCall::new(func_type, inputs).unwrap())
+        };
+        let float64 = |v: f64| ExprImpl::from(Literal::new(Some(v.into()), DataType::Float64));
+        let int64 =
+            |v: i64| ExprImpl::from(Literal::new(Some(ScalarImpl::Int64(v)), DataType::Int64));
+        let null = || ExprImpl::from(Literal::new(None, DataType::Float64));
+
+        let input = input.cast_implicit(DataType::Float64).unwrap();
+        let squared = call(ExprType::Multiply, vec![input.clone(), input.clone()]);
+        let sum_of_squares = self.push_unary_agg_call(AggKind::Sum, squared);
+        let sum = self.push_unary_agg_call(AggKind::Sum, input.clone());
+        let count = self.push_unary_agg_call(AggKind::Count, input);
+        let count_float64 = count.clone().cast_implicit(DataType::Float64).unwrap();
+
+        let deviation = call(
+            ExprType::Subtract,
+            vec![
+                sum_of_squares,
+                call(
+                    ExprType::Divide,
+                    vec![
+                        call(ExprType::Multiply, vec![sum.clone(), sum]),
+                        count_float64.clone(),
+                    ],
+                ),
+            ],
+        );
+        let deviation = call(
+            ExprType::Case,
+            vec![
+                call(ExprType::LessThan, vec![deviation.clone(), float64(0.0)]),
+                float64(0.0),
+                deviation,
+            ],
+        );
+
+        match agg_kind {
+            AggKind::VarPop => call(
+                ExprType::Case,
+                vec![
+                    call(ExprType::Equal, vec![count, int64(0)]),
+                    null(),
+                    call(ExprType::Divide, vec![deviation, count_float64]),
+                ],
+            ),
+            AggKind::StddevSamp => {
+                let variance = call(
+                    ExprType::Divide,
+                    vec![
+                        deviation,
+                        call(ExprType::Subtract, vec![count_float64, float64(1.0)]),
+                    ],
+                );
+                call(
+                    ExprType::Case,
+                    vec![
+                        call(ExprType::LessThanOrEqual, vec![count, int64(1)]),
+                        null(),
+                        call(ExprType::Sqrt, vec![variance]),
+                    ],
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl ExprRewriter for ExprHandler {
@@ -169,6 +292,8 @@ impl ExprRewriter for ExprHandler {
     /// 2. add a `PlanAggCall` to agg;
     /// 3. rewrite it as an `InputRef` to the agg result in select list.
     ///
+    /// `avg`, `var_pop` and `stddev_samp` are rewritten into expressions of multiple agg calls.
+    ///
     /// Note that the rewriter does not traverse into inputs of agg calls.
     fn rewrite_agg_call(&mut self, agg_call: AggCall) -> ExprImpl {
         let return_type = agg_call.return_type();
@@ -183,19 +308,20 @@ impl ExprRewriter for ExprHandler {
             }
         }
 
-        let mut index = self.project.len();
-        let mut input_refs = vec![];
-        self.project.extend(inputs.into_iter().filter(|expr| {
-            if let Some(idx) = self.expr_index.get(expr) {
-                input_refs.push(InputRef::new(*idx, expr.return_type()));
-                false
-            } else {
-                self.expr_index.insert(expr.clone(), index);
-                input_refs.push(InputRef::new(index, expr.return_type()));
-                index += 1;
-                true
+        if matches!(agg_kind, AggKind::StddevSamp | AggKind::VarPop) {
+            if distinct {
+                self.error = Some(ErrorCode::NotImplemented(
+                    format!("{} with DISTINCT", agg_kind),
+                    None.into(),
+                ));
+                return AggCall::new(agg_kind, inputs, distinct).unwrap().into();
             }
-        }));
+            assert_eq!(inputs.len(), 1);
+            let input = inputs.into_iter().next().unwrap();
+            return self.rewrite_variance(agg_kind, input);
+        }
+
+        let input_refs = self.project_inputs(inputs);
 
         if agg_kind == AggKind::Avg {
             assert_eq!(input_refs.len(), 1);
@@ -757,6 +883,31 @@ mod tests {
             assert_eq!(group_keys, vec![0]);
         }
 
+        // Test case: select var_pop(v1), stddev_samp(v1) from test;
+        {
+            let var_pop_v1 =
+                AggCall::new(AggKind::VarPop, vec![input_ref_1.clone().into()], false).unwrap();
+            let stddev_samp_v1 =
+                AggCall::new(AggKind::StddevSamp, vec![input_ref_1.clone().into()], false).unwrap();
+            let select_exprs = vec![var_pop_v1.into(), stddev_samp_v1.into()];
+
+            let (exprs, agg_calls, group_keys) = gen_internal_value(select_exprs, vec![]);
+
+            assert_eq!(exprs.len(), 2);
+            assert_eq!(exprs[0].return_type(), DataType::Float64);
+            assert_eq!(exprs[1].return_type(), DataType::Float64);
+
+            // sum(v1 * v1), sum(v1) and count(v1), which are shared by both of them.
+            let agg_kinds = agg_calls.iter().map(|c| c.agg_kind.clone()).collect_vec();
+            assert_eq!(agg_kinds, [AggKind::Sum, AggKind::Sum, AggKind::Count]);
+            let agg_inputs = agg_calls
+                .iter()
+                .flat_map(|c| input_ref_to_column_indices(&c.inputs))
+                .collect_vec();
+            assert_eq!(agg_inputs, vec![0, 1, 1]);
+            assert!(group_keys.is_empty());
+        }
+
         // Test case: select v2, min(v1 * v3) from test group by v2;
         {
             let v1_mult_v3 = FunctionCall::new(
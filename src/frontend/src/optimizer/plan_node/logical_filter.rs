@@ -22,7 +22,7 @@ use super::{
     PredicatePushdown, ToBatch, ToStream,
 };
 use crate::expr::{assert_input_ref, ExprImpl};
-use crate::optimizer::plan_node::{BatchFilter, StreamFilter};
+use crate::optimizer::plan_node::{BatchFilter, BatchSeqScan, StreamFilter};
 use crate::risingwave_common::error::Result;
 use crate::utils::{ColIndexMapping, Condition};
 
@@ -152,6 +152,22 @@ impl PredicatePushdown for LogicalFilter {
 
 impl ToBatch for LogicalFilter {
     fn to_batch(&self) -> Result<PlanRef> {
+        // Turn the comparisons on the storage key of a scan into the range it scans.
+        if let Some(logical_scan) = self.input().as_logical_scan() {
+            let (scan_range, predicate) = self
+                .predicate()
+                .clone()
+                .split_to_scan_range(&logical_scan.order_col_indices());
+            if !scan_range.is_full_table_scan() {
+                let scan: PlanRef =
+                    BatchSeqScan::new_with_scan_range(logical_scan.clone(), scan_range).into();
+                if predicate.always_true() {
+                    return Ok(scan);
+                }
+                return Ok(BatchFilter::new(LogicalFilter::new(scan, predicate)).into());
+            }
+        }
+
         let new_input = self.input().to_batch()?;
         let new_logical = self.clone_with_input(new_input);
         Ok(BatchFilter::new(new_logical).into())
@@ -17,11 +17,13 @@ use std::fmt;
 use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::Result;
 use risingwave_common::types::DataType;
+use risingwave_pb::plan_common::ConflictBehavior;
 
 use super::{
     gen_filter_and_pushdown, BatchInsert, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
     PredicatePushdown, ToBatch, ToStream,
 };
+use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::TableId;
 use crate::utils::Condition;
 
@@ -35,11 +37,22 @@ pub struct LogicalInsert {
     table_source_name: String, // explain-only
     source_id: TableId,        // TODO: use SourceId
     input: PlanRef,
+    /// The materialized table to check the pk of inserted rows against, and how to handle the
+    /// conflicting rows.
+    conflict_check: Option<(TableCatalog, ConflictBehavior)>,
+    /// Indices of the input columns which must not be null.
+    not_null_columns: Vec<usize>,
 }
 
 impl LogicalInsert {
     /// Create a [`LogicalInsert`] node. Used internally by optimizer.
-    pub fn new(input: PlanRef, table_source_name: String, source_id: TableId) -> Self {
+    pub fn new(
+        input: PlanRef,
+        table_source_name: String,
+        source_id: TableId,
+        conflict_check: Option<(TableCatalog, ConflictBehavior)>,
+        not_null_columns: Vec<usize>,
+    ) -> Self {
         let ctx = input.ctx();
         let schema = Schema::new(vec![Field::unnamed(DataType::Int64)]);
         let base = PlanBase::new_logical(ctx, schema, vec![]);
@@ -48,16 +61,42 @@ impl LogicalInsert {
             table_source_name,
             source_id,
             input,
+            conflict_check,
+            not_null_columns,
         }
     }
 
     /// Create a [`LogicalInsert`] node. Used by planner.
-    pub fn create(input: PlanRef, table_source_name: String, source_id: TableId) -> Result<Self> {
-        Ok(Self::new(input, table_source_name, source_id))
+    pub fn create(
+        input: PlanRef,
+        table_source_name: String,
+        source_id: TableId,
+        conflict_check: Option<(TableCatalog, ConflictBehavior)>,
+        not_null_columns: Vec<usize>,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            input,
+            table_source_name,
+            source_id,
+            conflict_check,
+            not_null_columns,
+        ))
     }
 
     pub(super) fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
-        write!(f, "{} {{ table: {} }}", name, self.table_source_name)
+        match &self.conflict_check {
+            Some((_, ConflictBehavior::IgnoreConflict)) => write!(
+                f,
+                "{} {{ table: {}, on_conflict: ignore }}",
+                name, self.table_source_name
+            ),
+            Some((_, ConflictBehavior::ErrorOnConflict)) => write!(
+                f,
+                "{} {{ table: {}, on_conflict: error }}",
+                name, self.table_source_name
+            ),
+            _ => write!(f, "{} {{ table: {} }}", name, self.table_source_name),
+        }
     }
 
     /// Get the logical insert's source id.
@@ -65,6 +104,18 @@ impl LogicalInsert {
     pub fn source_id(&self) -> TableId {
         self.source_id
     }
+
+    /// Get the materialized table and the behavior of the pk conflict check, if any.
+    #[must_use]
+    pub fn conflict_check(&self) -> Option<&(TableCatalog, ConflictBehavior)> {
+        self.conflict_check.as_ref()
+    }
+
+    /// Get the indices of the input columns which must not be null.
+    #[must_use]
+    pub fn not_null_columns(&self) -> &[usize] {
+        &self.not_null_columns
+    }
 }
 
 impl PlanTreeNodeUnary for LogicalInsert {
@@ -73,7 +124,13 @@ impl PlanTreeNodeUnary for LogicalInsert {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        Self::new(input, self.table_source_name.clone(), self.source_id)
+        Self::new(
+            input,
+            self.table_source_name.clone(),
+            self.source_id,
+            self.conflict_check.clone(),
+            self.not_null_columns.clone(),
+        )
     }
 }
 
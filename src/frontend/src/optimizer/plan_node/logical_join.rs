@@ -22,7 +22,7 @@ use risingwave_pb::plan_common::JoinType;
 
 use super::{
     ColPrunable, CollectInputRef, LogicalProject, PlanBase, PlanRef, PlanTreeNodeBinary,
-    PredicatePushdown, StreamHashJoin, ToBatch, ToStream,
+    PlanTreeNodeUnary, PredicatePushdown, StreamHashJoin, ToBatch, ToStream,
 };
 use crate::expr::{ExprImpl, ExprType};
 use crate::optimizer::plan_node::{
@@ -655,6 +655,20 @@ impl PredicatePushdown for LogicalJoin {
     }
 }
 
+/// The exact number of rows `plan` outputs, if it's known at planning time.
+fn known_row_count(plan: &PlanRef) -> Option<u64> {
+    if let Some(values) = plan.as_logical_values() {
+        Some(values.rows().len() as u64)
+    } else if let Some(project) = plan.as_logical_project() {
+        known_row_count(&project.input())
+    } else if let Some(agg) = plan.as_logical_agg() {
+        // A simple agg always outputs one row.
+        agg.group_keys().is_empty().then(|| 1)
+    } else {
+        None
+    }
+}
+
 impl ToBatch for LogicalJoin {
     fn to_batch(&self) -> Result<PlanRef> {
         let predicate = EqJoinPredicate::create(
@@ -663,6 +677,38 @@ impl ToBatch for LogicalJoin {
             self.on.clone(),
         );
 
+        // Nested loop join is only the fallback for joins without any equal condition, and it
+        // can't do full outer joins.
+        if !predicate.has_eq() && self.join_type == JoinType::FullOuter {
+            return Err(RwError::from(ErrorCode::NotImplemented(
+                "full outer join without any equal join condition".to_string(),
+                None.into(),
+            )));
+        }
+
+        // A cross join whose inputs have known sizes fails early if it's sure to produce more
+        // rows than allowed, instead of after computing that many rows.
+        let max_rows = self
+            .base
+            .ctx
+            .inner()
+            .session_ctx
+            .config()
+            .batch_nested_loop_join_max_rows();
+        if self.join_type == JoinType::Inner
+            && self.on.always_true()
+            && let Some(max_rows) = max_rows
+            && let Some(left_rows) = known_row_count(&self.left)
+            && let Some(right_rows) = known_row_count(&self.right)
+            && left_rows.saturating_mul(right_rows) > max_rows.get()
+        {
+            return Err(RwError::from(ErrorCode::RowLimitExceeded(format!(
+                "cross join producing {} rows, more than batch_nested_loop_join_max_rows {}",
+                left_rows.saturating_mul(right_rows),
+                max_rows
+            ))));
+        }
+
         let left = self.left().to_batch()?;
         let right = self.right().to_batch()?;
         let logical_join = self.clone_with_left_right(left, right);
@@ -832,8 +878,9 @@ mod tests {
     use risingwave_pb::expr::expr_node::Type;
 
     use super::*;
+    use crate::config::BATCH_NESTED_LOOP_JOIN_MAX_ROWS;
     use crate::expr::{assert_eq_input_ref, FunctionCall, InputRef, Literal};
-    use crate::optimizer::plan_node::{LogicalValues, PlanTreeNodeUnary};
+    use crate::optimizer::plan_node::LogicalValues;
     use crate::session::OptimizerContext;
 
     /// Pruning
@@ -1300,4 +1347,95 @@ mod tests {
         let right = right.as_logical_values().unwrap();
         assert_eq!(right.schema().fields(), &fields[3..4]);
     }
+
+    /// Full outer joins without any equal condition can't be done by a nested loop join.
+    #[tokio::test]
+    async fn test_full_outer_join_without_eq_cond_to_batch() {
+        let ty = DataType::Int32;
+        let ctx = OptimizerContext::mock().await;
+        let fields: Vec<Field> = (1..3)
+            .map(|i| Field::with_name(ty.clone(), format!("v{}", i)))
+            .collect();
+        let left = LogicalValues::new(
+            vec![],
+            Schema {
+                fields: fields[0..1].to_vec(),
+            },
+            ctx.clone(),
+        );
+        let right = LogicalValues::new(
+            vec![],
+            Schema {
+                fields: fields[1..2].to_vec(),
+            },
+            ctx,
+        );
+        let on: ExprImpl = ExprImpl::FunctionCall(Box::new(
+            FunctionCall::new(
+                Type::LessThan,
+                vec![
+                    ExprImpl::InputRef(Box::new(InputRef::new(0, ty.clone()))),
+                    ExprImpl::InputRef(Box::new(InputRef::new(1, ty))),
+                ],
+            )
+            .unwrap(),
+        ));
+
+        let join = LogicalJoin::new(
+            left.clone().into(),
+            right.clone().into(),
+            JoinType::FullOuter,
+            Condition::with_expr(on.clone()),
+        );
+        assert!(join.to_batch().is_err());
+
+        let join = LogicalJoin::new(
+            left.into(),
+            right.into(),
+            JoinType::LeftOuter,
+            Condition::with_expr(on),
+        );
+        let plan = join.to_batch().unwrap();
+        assert!(plan.as_batch_nested_loop_join().is_some());
+    }
+
+    /// A cross join fails at planning if it's sure to produce more rows than allowed.
+    #[tokio::test]
+    async fn test_cross_join_exceeding_max_rows_to_batch() {
+        let ty = DataType::Int32;
+        let ctx = OptimizerContext::mock().await;
+        let values = |name: &str| {
+            let row = vec![ExprImpl::from(Literal::new(
+                Datum::Some(1_i32.into()),
+                ty.clone(),
+            ))];
+            LogicalValues::new(
+                vec![row.clone(), row],
+                Schema {
+                    fields: vec![Field::with_name(ty.clone(), name)],
+                },
+                ctx.clone(),
+            )
+        };
+        let join = LogicalJoin::new(
+            values("v1").into(),
+            values("v2").into(),
+            JoinType::Inner,
+            Condition::true_cond(),
+        );
+
+        ctx.inner()
+            .session_ctx
+            .set_config(BATCH_NESTED_LOOP_JOIN_MAX_ROWS, &["3".to_string()])
+            .unwrap();
+        let err = join.to_batch().unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::RowLimitExceeded(_)));
+
+        ctx.inner()
+            .session_ctx
+            .set_config(BATCH_NESTED_LOOP_JOIN_MAX_ROWS, &["4".to_string()])
+            .unwrap();
+        let plan = join.to_batch().unwrap();
+        assert!(plan.as_batch_nested_loop_join().is_some());
+    }
 }
@@ -152,6 +152,22 @@ impl LogicalScan {
             .collect()
     }
 
+    /// The indices of the columns of the storage key among the output columns, in the order of
+    /// the key. Stops at the first key column not output.
+    pub fn order_col_indices(&self) -> Vec<usize> {
+        let id_to_op_idx = self
+            .required_col_idx
+            .iter()
+            .enumerate()
+            .map(|(op_idx, tb_idx)| (self.table_desc.columns[*tb_idx].column_id, op_idx))
+            .collect::<HashMap<_, _>>();
+        self.table_desc
+            .order_desc
+            .iter()
+            .map_while(|desc| id_to_op_idx.get(&desc.column_desc.column_id).copied())
+            .collect()
+    }
+
     pub fn to_index_scan(&self, index_name: &str, index: &Rc<TableDesc>) -> LogicalScan {
         let mut new_required_col_idx = Vec::with_capacity(self.required_col_idx.len());
         let all_columns = index
@@ -245,9 +245,11 @@ mod stream_hash_agg;
 mod stream_hash_join;
 mod stream_hop_window;
 mod stream_index_scan;
+mod stream_local_exchange;
 mod stream_materialize;
 mod stream_project;
 mod stream_simple_agg;
+mod stream_sink;
 mod stream_source;
 mod stream_table_scan;
 mod stream_topn;
@@ -292,9 +294,11 @@ pub use stream_hash_agg::StreamHashAgg;
 pub use stream_hash_join::StreamHashJoin;
 pub use stream_hop_window::StreamHopWindow;
 pub use stream_index_scan::StreamIndexScan;
+pub use stream_local_exchange::StreamLocalExchange;
 pub use stream_materialize::StreamMaterialize;
 pub use stream_project::StreamProject;
 pub use stream_simple_agg::StreamSimpleAgg;
+pub use stream_sink::StreamSink;
 pub use stream_source::StreamSource;
 pub use stream_table_scan::StreamTableScan;
 pub use stream_topn::StreamTopN;
@@ -365,6 +369,8 @@ macro_rules! for_all_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, Sink }
+            , { Stream, LocalExchange }
         }
     };
 }
@@ -443,6 +449,8 @@ macro_rules! for_stream_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, Sink }
+            , { Stream, LocalExchange }
         }
     };
 }
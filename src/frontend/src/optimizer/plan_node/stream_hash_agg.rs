@@ -99,7 +99,7 @@ impl ToStreamProst for StreamHashAgg {
                 .map(PlanAggCall::to_protobuf)
                 .collect_vec(),
             table_ids: vec![],
-            append_only: self.append_only(),
+            append_only: self.input().append_only(),
         })
     }
 }
@@ -43,8 +43,6 @@ pub struct StreamHashJoin {
     is_delta: bool,
 }
 
-pub static DELTA_JOIN: &str = "RW_FORCE_DELTA_JOIN";
-
 impl StreamHashJoin {
     pub fn new(logical: LogicalJoin, eq_join_predicate: EqJoinPredicate) -> Self {
         let ctx = logical.base.ctx.clone();
@@ -61,11 +59,7 @@ impl StreamHashJoin {
                 .composite(&logical.i2o_col_mapping()),
         );
 
-        let force_delta = if let Some(config) = ctx.inner().session_ctx.get_config(DELTA_JOIN) {
-            config.is_set(false)
-        } else {
-            false
-        };
+        let force_delta = ctx.inner().session_ctx.config().delta_join();
 
         // TODO: derive from input
         let base = PlanBase::new_stream(
@@ -0,0 +1,86 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::LocalExchangeNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::expr::InputRefDisplay;
+
+/// `StreamLocalExchange` runs its input, a stateful operator, in several branches within each
+/// actor. The input of the operator is partitioned among the branches by `keys`, so the output is
+/// the same as the operator's.
+#[derive(Debug, Clone)]
+pub struct StreamLocalExchange {
+    pub base: PlanBase,
+    input: PlanRef,
+    /// Indices of the partition keys in the input of the operator.
+    keys: Vec<usize>,
+    parallelism: usize,
+}
+
+impl StreamLocalExchange {
+    pub fn new(input: PlanRef, keys: Vec<usize>, parallelism: usize) -> Self {
+        assert!(parallelism > 1, "local exchange requires multiple branches");
+        let ctx = input.ctx();
+        let base = PlanBase::new_stream(
+            ctx,
+            input.schema().clone(),
+            input.pk_indices().to_vec(),
+            input.distribution().clone(),
+            input.append_only(),
+        );
+        StreamLocalExchange {
+            base,
+            input,
+            keys,
+            parallelism,
+        }
+    }
+}
+
+impl fmt::Display for StreamLocalExchange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamLocalExchange")
+            .field(
+                "keys",
+                &self.keys.iter().copied().map(InputRefDisplay).collect_vec(),
+            )
+            .field("parallelism", &self.parallelism)
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for StreamLocalExchange {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.keys.clone(), self.parallelism)
+    }
+}
+impl_plan_tree_node_for_unary! { StreamLocalExchange }
+
+impl ToStreamProst for StreamLocalExchange {
+    fn to_stream_prost_body(&self) -> NodeBody {
+        NodeBody::LocalExchange(LocalExchangeNode {
+            key_indices: self.keys.iter().map(|idx| *idx as u32).collect(),
+            parallelism: self.parallelism as u32,
+        })
+    }
+}
@@ -22,7 +22,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::expr::InputRefExpr;
-use risingwave_pb::plan_common::ColumnOrder;
+use risingwave_pb::plan_common::{ColumnOrder, ConflictBehavior};
 use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
 
 use super::{PlanRef, PlanTreeNodeUnary, ToStreamProst};
@@ -39,6 +39,8 @@ pub struct StreamMaterialize {
     /// Child of Materialize plan
     input: PlanRef,
     table: TableCatalog,
+    /// How to handle a row whose pk already exists. Only set for tables with a user-defined pk.
+    conflict_behavior: ConflictBehavior,
 }
 
 impl StreamMaterialize {
@@ -62,7 +64,20 @@ impl StreamMaterialize {
     #[must_use]
     pub fn new(input: PlanRef, table: TableCatalog) -> Self {
         let base = Self::derive_plan_base(&input).unwrap();
-        Self { base, input, table }
+        Self {
+            base,
+            input,
+            table,
+            conflict_behavior: ConflictBehavior::NoCheck,
+        }
+    }
+
+    #[must_use]
+    pub fn with_conflict_behavior(self, conflict_behavior: ConflictBehavior) -> Self {
+        Self {
+            conflict_behavior,
+            ..self
+        }
     }
 
     /// Create a materialize node.
@@ -113,6 +128,8 @@ impl StreamMaterialize {
                 let mut c = ColumnCatalog {
                     column_desc: ColumnDesc::from_field_without_column_id(field),
                     is_hidden: !user_cols.contains(i),
+                    not_null: false,
+                    default_expr: None,
                 };
                 c.column_desc.name = if !c.is_hidden {
                     out_name_iter.next().unwrap()
@@ -168,7 +185,12 @@ impl StreamMaterialize {
             distribution_keys: base.dist.dist_column_indices().to_vec(),
         };
 
-        Ok(Self { base, input, table })
+        Ok(Self {
+            base,
+            input,
+            table,
+            conflict_behavior: ConflictBehavior::NoCheck,
+        })
     }
 
     /// Get a reference to the stream materialize's table.
@@ -232,7 +254,8 @@ impl PlanTreeNodeUnary for StreamMaterialize {
     }
 
     fn clone_with_input(&self, input: PlanRef) -> Self {
-        let new = Self::new(input, self.table().clone());
+        let new =
+            Self::new(input, self.table().clone()).with_conflict_behavior(self.conflict_behavior);
         assert_eq!(new.plan_base().schema, self.plan_base().schema);
         assert_eq!(new.plan_base().pk_indices, self.plan_base().pk_indices);
         new
@@ -278,6 +301,7 @@ impl ToStreamProst for StreamMaterialize {
                 .iter()
                 .map(|idx| *idx as u32)
                 .collect_vec(),
+            conflict_behavior: self.conflict_behavior as i32,
         })
     }
 }
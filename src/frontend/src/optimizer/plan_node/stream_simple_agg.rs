@@ -86,7 +86,7 @@ impl ToStreamProst for StreamSimpleAgg {
                 .map(|idx| *idx as u32)
                 .collect_vec(),
             table_ids: vec![],
-            append_only: self.append_only(),
+            append_only: self.input().append_only(),
         })
     }
 }
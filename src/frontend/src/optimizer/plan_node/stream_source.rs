@@ -35,7 +35,7 @@ impl StreamSource {
             logical.schema().clone(),
             logical.pk_indices().to_vec(),
             Distribution::SomeShard,
-            false, // TODO: determine the `append-only` field of source
+            logical.source_catalog.append_only,
         );
         Self { base, logical }
     }
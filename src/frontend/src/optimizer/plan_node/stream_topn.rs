@@ -49,9 +49,15 @@ impl StreamTopN {
 
 impl fmt::Display for StreamTopN {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = if self.input().append_only() {
+            "StreamAppendOnlyTopN"
+        } else {
+            "StreamTopN"
+        };
         write!(
             f,
-            "StreamTopN {{ order: {}, limit: {}, offset: {} }}",
+            "{} {{ order: {}, limit: {}, offset: {} }}",
+            name,
             self.logical.topn_order(),
             self.logical.limit(),
             self.logical.offset(),
@@ -87,11 +93,84 @@ impl ToStreamProst for StreamTopN {
                 return_type: Some(self.input().schema()[f.index].data_type().to_protobuf()),
             })
             .collect();
-        ProstStreamNode::TopN(TopNNode {
+        let top_n_node = TopNNode {
             column_orders,
             limit: self.logical.limit() as u64,
             offset: self.logical.offset() as u64,
             distribution_keys: vec![], // TODO: seems unnecessary
-        })
+        };
+        // Without retractions from the input, the rows out of the top n range will never be needed
+        // again, so a cheaper executor can be used.
+        if self.input().append_only() {
+            ProstStreamNode::AppendOnlyTopN(top_n_node)
+        } else {
+            ProstStreamNode::TopN(top_n_node)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use risingwave_common::catalog::ColumnDesc;
+    use risingwave_common::types::DataType;
+    use risingwave_pb::plan_common::ConflictBehavior;
+    use risingwave_pb::stream_plan::source_node::SourceType;
+
+    use super::*;
+    use crate::catalog::column_catalog::ColumnCatalog;
+    use crate::catalog::source_catalog::SourceCatalog;
+    use crate::optimizer::plan_node::{LogicalSource, StreamExchange, StreamSource};
+    use crate::optimizer::property::{FieldOrder, Order};
+    use crate::session::OptimizerContext;
+
+    async fn top_n_on_source(append_only: bool) -> StreamTopN {
+        let ctx = OptimizerContext::mock().await;
+        let source_catalog = SourceCatalog {
+            id: 1,
+            name: "s".to_string(),
+            columns: vec![
+                ColumnCatalog::row_id_column(),
+                ColumnCatalog {
+                    column_desc: ColumnDesc::new_atomic(DataType::Int32, "v", 1),
+                    is_hidden: false,
+                    not_null: false,
+                    default_expr: None,
+                },
+            ],
+            pk_col_ids: vec![0.into()],
+            source_type: SourceType::Source,
+            conflict_behavior: ConflictBehavior::NoCheck,
+            append_only,
+            version: 0,
+        };
+        let source = StreamSource::new(LogicalSource::new(Rc::new(source_catalog), ctx));
+        let input = StreamExchange::new(source.into(), Distribution::Single);
+        StreamTopN::new(LogicalTopN::new(
+            input.into(),
+            3,
+            0,
+            Order::new(vec![FieldOrder::ascending(1)]),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_append_only_top_n() {
+        let top_n = top_n_on_source(true).await;
+        assert!(top_n
+            .to_string()
+            .starts_with("StreamAppendOnlyTopN { order: "));
+        assert!(matches!(
+            top_n.to_stream_prost_body(),
+            ProstStreamNode::AppendOnlyTopN(_)
+        ));
+
+        let top_n = top_n_on_source(false).await;
+        assert!(top_n.to_string().starts_with("StreamTopN { order: "));
+        assert!(matches!(
+            top_n.to_stream_prost_body(),
+            ProstStreamNode::TopN(_)
+        ));
     }
 }
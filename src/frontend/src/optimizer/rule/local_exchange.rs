@@ -0,0 +1,35 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::super::plan_node::*;
+use super::{BoxedRule, Rule};
+
+/// Runs the streaming hash aggregations in several branches within each actor, partitioned by
+/// their group keys.
+pub struct LocalExchangeRule {
+    parallelism: usize,
+}
+
+impl Rule for LocalExchangeRule {
+    fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
+        let keys = plan.as_stream_hash_agg()?.distribution_keys().to_vec();
+        Some(StreamLocalExchange::new(plan, keys, self.parallelism).into())
+    }
+}
+
+impl LocalExchangeRule {
+    pub fn create(parallelism: usize) -> BoxedRule {
+        Box::new(Self { parallelism })
+    }
+}
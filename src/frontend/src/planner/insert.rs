@@ -32,6 +32,8 @@ impl Planner {
             input,
             insert.table_source.name,
             insert.table_source.source_id,
+            insert.conflict_check,
+            insert.not_null_columns,
         )?
         .into();
         let order = Order::any().clone();
@@ -20,13 +20,13 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::ScalarImpl;
 
 use crate::binder::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundWindowTableFunction,
-    Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
-use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
+use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef, Literal};
 use crate::optimizer::plan_node::{
     LogicalGenerateSeries, LogicalHopWindow, LogicalJoin, LogicalProject, LogicalScan,
-    LogicalSource, PlanRef,
+    LogicalSource, LogicalValues, PlanRef,
 };
 use crate::planner::Planner;
 
@@ -34,6 +34,7 @@ impl Planner {
     pub(super) fn plan_relation(&mut self, relation: Relation) -> Result<PlanRef> {
         match relation {
             Relation::BaseTable(t) => self.plan_base_table(*t),
+            Relation::SystemTable(t) => self.plan_system_table(*t),
             // TODO: order is ignored in the subquery
             Relation::Subquery(q) => Ok(self.plan_query(q.query)?.as_subplan()),
             Relation::Join(join) => self.plan_join(*join),
@@ -56,6 +57,23 @@ impl Planner {
         )
     }
 
+    /// A system table is planned as the values of its rows at the moment.
+    pub(super) fn plan_system_table(&mut self, system_table: BoundSystemTable) -> Result<PlanRef> {
+        let schema = Schema::new(system_table.kind.columns());
+        let rows = system_table
+            .kind
+            .rows(&self.ctx().inner().session_ctx)
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip_eq(schema.fields())
+                    .map(|(datum, field)| Literal::new(datum, field.data_type()).into())
+                    .collect()
+            })
+            .collect();
+        Ok(LogicalValues::create(rows, schema, self.ctx()))
+    }
+
     pub(super) fn plan_source(&mut self, source: BoundSource) -> Result<PlanRef> {
         Ok(LogicalSource::new(Rc::new(source.catalog), self.ctx()).into())
     }
@@ -17,13 +17,13 @@ use std::mem::swap;
 use std::sync::Arc;
 
 use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::batch_plan::{TaskId as TaskIdProst, TaskOutputId as TaskOutputIdProst};
 use risingwave_rpc_client::ComputeClientPoolRef;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::{oneshot, RwLock};
 use tokio::task::JoinHandle;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 use super::stage::StageEvent;
 use crate::scheduler::distributed::query::QueryMessage::Stage;
@@ -60,7 +60,7 @@ enum QueryState {
 
     /// Running
     Running {
-        _msg_sender: Sender<QueryMessage>,
+        msg_sender: Sender<QueryMessage>,
         _task_handle: JoinHandle<Result<()>>,
     },
 
@@ -169,13 +169,17 @@ impl QueryExecution {
                 root_stage_receiver,
             } => {
                 let msg_sender = runner.msg_sender.clone();
-                let task_handle = tokio::spawn(async move {
-                    let query_id = runner.query.query_id.clone();
-                    runner.run().await.map_err(|e| {
-                        error!("Query {:?} failed, reason: {:?}", query_id, e);
-                        e
-                    })
-                });
+                let span = tracing::trace_span!("query", query_id = %self.query.query_id.id);
+                let task_handle = tokio::spawn(
+                    async move {
+                        let query_id = runner.query.query_id.clone();
+                        runner.run().await.map_err(|e| {
+                            error!("Query {:?} failed, reason: {:?}", query_id, e);
+                            e
+                        })
+                    }
+                    .instrument(span),
+                );
 
                 let root_stage = root_stage_receiver.await.map_err(|e| {
                     InternalError(format!("Starting query execution failed: {:?}", e))
@@ -187,7 +191,7 @@ impl QueryExecution {
                 );
 
                 *state = QueryState::Running {
-                    _msg_sender: msg_sender,
+                    msg_sender,
                     _task_handle: task_handle,
                 };
 
@@ -203,8 +207,20 @@ impl QueryExecution {
 
     /// Cancel execution of this query.
     #[allow(unused)]
-    pub async fn abort(&mut self) -> Result<()> {
-        todo!()
+    pub async fn abort(&self) -> Result<()> {
+        let state = self.state.read().await;
+        match &*state {
+            QueryState::Running { msg_sender, .. } => {
+                msg_sender.send(QueryMessage::Stop).await.map_err(|e| {
+                    InternalError(format!(
+                        "Failed to stop query {:?}, reason: {:?}",
+                        self.query.query_id, e
+                    ))
+                    .into()
+                })
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -213,21 +229,15 @@ impl QueryRunner {
         // Start leaf stages.
         let leaf_stages = self.query.leaf_stages();
         for stage_id in &leaf_stages {
-            // TODO: We should not return error here, we should abort query.
             info!(
                 "Starting query stage: {:?}-{:?}",
                 self.query.query_id, stage_id
             );
-            self.stage_executions
-                .get(stage_id)
-                .as_ref()
-                .unwrap()
-                .start()
-                .await
-                .map_err(|e| {
-                    error!("Failed to start stage: {}, reason: {:?}", stage_id, e);
-                    e
-                })?;
+            if let Err(e) = self.get_stage_execution_unchecked(stage_id).start().await {
+                error!("Failed to start stage: {}, reason: {:?}", stage_id, e);
+                self.fail(e).await;
+                return Ok(());
+            }
             info!(
                 "Query stage {:?}-{:?} started.",
                 self.query.query_id, stage_id
@@ -255,29 +265,38 @@ impl QueryRunner {
                         // iterator have been created, thus they all successfully pinned a
                         // HummockVersion. So we can now unpin their epoch.
                         info!("Query {:?} has scheduled all of its stages that have table scan (iterator creation).", self.query.query_id);
-                        self.hummock_snapshot_manager
-                            .clone()
-                            .unpin_snapshot(self.epoch, self.query.query_id());
+                        if let Err(e) = self
+                            .hummock_snapshot_manager
+                            .unpin_snapshot(self.epoch, self.query.query_id())
+                            .await
+                        {
+                            warn!(
+                                "Failed to unpin snapshot of query {:?}, reason: {}",
+                                self.query.query_id, e
+                            );
+                        }
                     }
 
                     if self.scheduled_stages_count == self.stage_executions.len() {
                         // Now all stages have been scheduled, send root stage info.
                         self.send_root_stage_info().await;
                     } else {
+                        let mut start_error = None;
                         for parent in self.query.get_parents(&stage_id) {
                             if self.all_children_scheduled(parent).await {
-                                self.get_stage_execution_unchecked(parent)
-                                    .start()
-                                    .await
-                                    .map_err(|e| {
-                                        error!(
-                                            "Failed to start stage: {}, reason: {:?}",
-                                            stage_id, e
-                                        );
-                                        e
-                                    })?;
+                                if let Err(e) =
+                                    self.get_stage_execution_unchecked(parent).start().await
+                                {
+                                    error!("Failed to start stage: {}, reason: {:?}", parent, e);
+                                    start_error = Some(e);
+                                    break;
+                                }
                             }
                         }
+                        if let Some(e) = start_error {
+                            self.fail(e).await;
+                            break;
+                        }
                     }
                 }
                 Stage(StageEvent::Failed { id, reason }) => {
@@ -286,22 +305,13 @@ impl QueryRunner {
                         self.query.query_id, id, reason
                     );
 
-                    // Consume sender here.
-                    let mut tmp_sender = None;
-                    swap(&mut self.root_stage_sender, &mut tmp_sender);
-                    // It's possible we receive stage failed event message multi times and the
-                    // sender has been consumed in first failed event.
-                    if let Some(sender) = tmp_sender {
-                        if let Err(e) = sender.send(Err(reason)) {
-                            warn!("Query execution dropped: {:?}", e);
-                        } else {
-                            debug!(
-                                "Root stage failure event for {:?} sent.",
-                                self.query.query_id
-                            );
-                        }
-                    }
-                    // TODO: We should can cancel all scheduled stages here.
+                    self.fail(reason).await;
+                    break;
+                }
+                QueryMessage::Stop => {
+                    info!("Stopping query {:?}.", self.query.query_id);
+                    self.stop_all_stages().await;
+                    break;
                 }
                 _ => {
                     return Err(ErrorCode::NotImplemented(
@@ -317,6 +327,36 @@ impl QueryRunner {
         Ok(())
     }
 
+    /// Fails the query with `reason`. The reason is reported to the waiting `QueryExecution` if the
+    /// root stage hasn't been sent, and all stages are stopped so that the tasks already scheduled
+    /// on compute nodes are aborted.
+    async fn fail(&mut self, reason: RwError) {
+        // It's possible that the root stage info has been sent before some stage fails, in which
+        // case the result fetcher gets the error from the aborted tasks instead.
+        if let Some(sender) = self.root_stage_sender.take() {
+            if let Err(e) = sender.send(Err(reason)) {
+                warn!("Query execution dropped: {:?}", e);
+            } else {
+                debug!(
+                    "Root stage failure event for {:?} sent.",
+                    self.query.query_id
+                );
+            }
+        }
+        self.stop_all_stages().await;
+    }
+
+    async fn stop_all_stages(&self) {
+        for (stage_id, stage_execution) in self.stage_executions.iter() {
+            if let Err(e) = stage_execution.stop().await {
+                warn!(
+                    "Failed to stop stage {:?}-{:?}, reason: {}",
+                    self.query.query_id, stage_id, e
+                );
+            }
+        }
+    }
+
     async fn send_root_stage_info(&mut self) {
         let root_task_status = self.stage_executions[&self.query.root_stage_id()]
             .get_task_status_unchecked(ROOT_TASK_ID);
@@ -340,7 +380,8 @@ impl QueryRunner {
             root_task_output_id,
             root_task_status.task_host_unchecked(),
             self.compute_client_pool.clone(),
-        );
+        )
+        .with_query_msg_sender(self.msg_sender.clone());
 
         // Consume sender here.
         let mut tmp_sender = None;
@@ -517,6 +558,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(0, 0),
+            unschedulable: false,
         };
         let worker2 = WorkerNode {
             id: 1,
@@ -527,6 +569,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(8, 1),
+            unschedulable: false,
         };
         let worker3 = WorkerNode {
             id: 2,
@@ -537,6 +580,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(16, 2),
+            unschedulable: false,
         };
         let workers = vec![worker1, worker2, worker3];
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(workers));
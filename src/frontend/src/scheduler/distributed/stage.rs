@@ -31,7 +31,7 @@ use tokio::spawn;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{error, info, warn, Instrument};
 use uuid::Uuid;
 use StageEvent::Failed;
 
@@ -171,14 +171,22 @@ impl StageExecution {
                     state: self.state.clone(),
                     compute_client_pool: self.compute_client_pool.clone(),
                 };
-                let handle = spawn(async move {
-                    if let Err(e) = runner.run().await {
-                        error!("Stage failed: {}", e);
-                        Err(e)
-                    } else {
-                        Ok(())
+                let span = tracing::trace_span!(
+                    "stage",
+                    query_id = %self.stage.query_id.id,
+                    stage_id = self.stage.id,
+                );
+                let handle = spawn(
+                    async move {
+                        if let Err(e) = runner.run().await {
+                            error!("Stage failed: {}", e);
+                            Err(e)
+                        } else {
+                            Ok(())
+                        }
                     }
-                });
+                    .instrument(span),
+                );
 
                 *s = StageState::Started { sender, handle };
                 Ok(())
@@ -195,8 +203,38 @@ impl StageExecution {
         }
     }
 
+    /// Stops execution of this stage, and aborts all tasks that have been scheduled.
     pub async fn stop(&self) -> Result<()> {
-        todo!()
+        {
+            let mut s = self.state.write().await;
+            if let StageState::Started { handle, .. } = &*s {
+                // Don't schedule the remaining tasks.
+                handle.abort();
+            }
+            *s = StageState::Failed;
+        }
+
+        for (task_id, status_holder) in self.tasks.iter() {
+            let Some(location) = status_holder.get_status().location.clone() else {
+                continue;
+            };
+            let task_id_prost = TaskIdProst {
+                query_id: self.stage.query_id.id.clone(),
+                stage_id: self.stage.id,
+                task_id: *task_id,
+            };
+            let compute_client = self
+                .compute_client_pool
+                .get_client_for_addr((&location).into())
+                .await?;
+            if let Err(e) = compute_client.abort_task(task_id_prost).await {
+                warn!(
+                    "Failed to abort task {:?}-{:?}-{:?}, reason: {}",
+                    self.stage.query_id, self.stage.id, task_id, e
+                );
+            }
+        }
+        Ok(())
     }
 
     pub async fn is_scheduled(&self) -> bool {
@@ -242,7 +280,7 @@ impl StageRunner {
                 "Stage {:?}-{:?} failed to schedule tasks, error: {}",
                 self.stage.query_id, self.stage.id, e
             );
-            // TODO: We should cancel all scheduled tasks
+            // The query runner stops all stages on the failure, which aborts the tasks scheduled.
             self.send_event(QueryMessage::Stage(Failed {
                 id: self.stage.id,
                 reason: e,
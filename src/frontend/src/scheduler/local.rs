@@ -12,13 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Local execution for batch query.
+//! Local execution for batch query. The plan runs as a single task without being scheduled stage
+//! by stage, which saves the scheduling overhead of short queries like point lookups. It runs in
+//! the frontend if it doesn't read any tables, and on a compute node otherwise since the frontend
+//! has no access to the state store.
 
 use futures_async_stream::try_stream;
 use risingwave_batch::executor::ExecutorBuilder;
 use risingwave_batch::task::TaskId;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::{internal_error, Result, RwError};
+use risingwave_expr::expr::WithEpoch;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::{PlanFragment, PlanNode as PlanNodeProst, TaskId as TaskIdProst};
 use tracing::debug;
 use uuid::Uuid;
@@ -26,24 +31,27 @@ use uuid::Uuid;
 use crate::optimizer::plan_node::PlanNodeType;
 use crate::scheduler::plan_fragmenter::{ExecutionPlanNode, Query};
 use crate::scheduler::task_context::FrontendBatchTaskContext;
-use crate::scheduler::{DataChunkStream, HummockSnapshotManagerRef};
+use crate::scheduler::{DataChunkStream, ExecutionContextRef, HummockSnapshotManagerRef};
 
 pub struct LocalQueryExecution {
     sql: String,
     query: Query,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    context: ExecutionContextRef,
 }
 
 impl LocalQueryExecution {
     pub fn new<S: Into<String>>(
         query: Query,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
+        context: ExecutionContextRef,
         sql: S,
     ) -> Self {
         Self {
             sql: sql.into(),
             query,
             hummock_snapshot_manager,
+            context,
         }
     }
 
@@ -55,6 +63,19 @@ impl LocalQueryExecution {
         );
 
         let plan_fragment = self.create_plan_fragment()?;
+        let plan_node = plan_fragment.root.unwrap();
+
+        if Self::reads_table(&plan_node) {
+            let query_manager = self.context.session().env().query_manager().clone();
+            #[for_await]
+            for chunk in query_manager
+                .schedule_single(self.context.clone(), plan_node)
+                .await?
+            {
+                yield chunk?;
+            }
+            return Ok(());
+        }
 
         let context = FrontendBatchTaskContext::default();
 
@@ -67,16 +88,22 @@ impl LocalQueryExecution {
         };
 
         let epoch = self.hummock_snapshot_manager.get_epoch(query_id).await?;
-        let plan_node = plan_fragment.root.unwrap();
         let executor = ExecutorBuilder::new(&plan_node, &task_id, context, epoch);
         let executor = executor.build().await?;
 
+        // All the expressions of the query, e.g. `now()`, are evaluated against the epoch of it.
         #[for_await]
-        for chunk in executor.execute() {
+        for chunk in WithEpoch::new(epoch, executor.execute(), |_| None) {
             yield chunk?;
         }
     }
 
+    /// Whether the plan scans any table, which can only be done on compute nodes.
+    fn reads_table(plan_node: &PlanNodeProst) -> bool {
+        matches!(plan_node.node_body, Some(NodeBody::RowSeqScan(_)))
+            || plan_node.children.iter().any(Self::reads_table)
+    }
+
     /// Convert query to plan fragment.
     ///
     /// We can convert a query to plan fragment since in local execution mode, there are at most
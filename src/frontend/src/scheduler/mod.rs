@@ -19,6 +19,8 @@ use crate::session::SessionImpl;
 mod distributed;
 mod hummock_snapshot_manager;
 pub use hummock_snapshot_manager::*;
+mod plan_cache;
+pub use plan_cache::*;
 pub mod plan_fragmenter;
 mod query_manager;
 pub use query_manager::*;
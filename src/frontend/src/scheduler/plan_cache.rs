@@ -0,0 +1,88 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
+use risingwave_common::catalog::CatalogVersion;
+use risingwave_common::types::DataType;
+
+use crate::config::QueryMode;
+use crate::scheduler::plan_fragmenter::Query;
+
+/// The maximum number of plans cached by a session.
+pub const PLAN_CACHE_CAPACITY: usize = 256;
+
+/// Everything a plan depends on besides the statement itself. A cached plan can only be reused if
+/// none of them has changed since it's planned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanContext {
+    pub param_types: Vec<DataType>,
+    pub catalog_version: CatalogVersion,
+    pub query_mode: QueryMode,
+    pub batch_parallelism: Option<NonZeroU64>,
+    /// Unqualified relation names are resolved with it.
+    pub search_path: Vec<String>,
+}
+
+/// The plan of a prepared query, with its parameters not bound yet. See
+/// [`Query::bind_params`].
+#[derive(Debug)]
+pub struct CachedPlan {
+    pub context: PlanContext,
+    pub query: Query,
+    pub pg_descs: Vec<PgFieldDescriptor>,
+}
+
+/// Caches the plans of prepared queries by the query strings, so that executing a prepared query
+/// repeatedly doesn't plan it every time.
+#[derive(Default)]
+pub struct PlanCache {
+    plans: Mutex<HashMap<String, Arc<CachedPlan>>>,
+}
+
+impl PlanCache {
+    /// Gets the plan of `sql` if it's cached in the same context.
+    pub fn get(&self, sql: &str, context: &PlanContext) -> Option<Arc<CachedPlan>> {
+        self.plans
+            .lock()
+            .get(sql)
+            .filter(|plan| &plan.context == context)
+            .cloned()
+    }
+
+    /// Caches the plan of `sql`, replacing the stale one if any. If the cache is full, an
+    /// arbitrary plan is evicted.
+    pub fn insert(&self, sql: String, plan: CachedPlan) -> Arc<CachedPlan> {
+        let plan = Arc::new(plan);
+        let mut plans = self.plans.lock();
+        if plans.len() >= PLAN_CACHE_CAPACITY && !plans.contains_key(&sql) {
+            let evicted = plans.keys().next().cloned().unwrap();
+            plans.remove(&evicted);
+        }
+        plans.insert(sql, plan.clone());
+        plan
+    }
+
+    pub fn len(&self) -> usize {
+        self.plans.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plans.lock().is_empty()
+    }
+}
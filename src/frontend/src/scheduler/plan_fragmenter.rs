@@ -14,11 +14,14 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
+use std::num::NonZeroU64;
 use std::sync::Arc;
 
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::{ExchangeInfo, PlanNode};
+use risingwave_pb::expr::expr_node::RexNode;
+use risingwave_pb::expr::ExprNode;
 use risingwave_pb::plan_common::Field as FieldProst;
 use uuid::Uuid;
 
@@ -74,6 +77,67 @@ impl ExecutionPlanNode {
     pub fn node_type(&self) -> PlanNodeType {
         self.plan_node_type
     }
+
+    /// See [`Query::bind_params`].
+    fn bind_params(&self, params: &[ExprNode]) -> Result<ExecutionPlanNode> {
+        let mut node = self.node.clone();
+        let exprs: Vec<&mut ExprNode> = match &mut node {
+            NodeBody::Project(project) => project.select_list.iter_mut().collect(),
+            NodeBody::Filter(filter) => filter.search_condition.iter_mut().collect(),
+            NodeBody::Update(update) => update.exprs.iter_mut().collect(),
+            NodeBody::Values(values) => values
+                .tuples
+                .iter_mut()
+                .flat_map(|tuple| tuple.cells.iter_mut())
+                .collect(),
+            NodeBody::NestedLoopJoin(join) => join.join_cond.iter_mut().collect(),
+            NodeBody::HashJoin(join) => join.condition.iter_mut().collect(),
+            NodeBody::SortAgg(agg) => agg.group_keys.iter_mut().collect(),
+            NodeBody::GenerateSeries(series) => series
+                .start
+                .iter_mut()
+                .chain(series.stop.iter_mut())
+                .chain(series.step.iter_mut())
+                .collect(),
+            _ => vec![],
+        };
+        for expr in exprs {
+            bind_params_in_expr(expr, params)?;
+        }
+
+        Ok(Self {
+            plan_node_id: self.plan_node_id,
+            plan_node_type: self.plan_node_type,
+            node,
+            schema: self.schema.clone(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.bind_params(params).map(Arc::new))
+                .collect::<Result<_>>()?,
+            stage_id: self.stage_id,
+        })
+    }
+}
+
+fn bind_params_in_expr(expr: &mut ExprNode, params: &[ExprNode]) -> Result<()> {
+    match &mut expr.rex_node {
+        Some(RexNode::Parameter(param)) => {
+            let index = param.index;
+            *expr = (index as usize)
+                .checked_sub(1)
+                .and_then(|i| params.get(i))
+                .ok_or_else(|| ErrorCode::BindError(format!("there is no parameter ${}", index)))?
+                .clone();
+        }
+        Some(RexNode::FuncCall(func_call)) => {
+            for child in &mut func_call.children {
+                bind_params_in_expr(child, params)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
 }
 
 /// `BatchPlanFragmenter` splits a query plan into fragments.
@@ -82,6 +146,8 @@ pub struct BatchPlanFragmenter {
     stage_graph_builder: StageGraphBuilder,
     next_stage_id: u32,
     worker_node_manager: WorkerNodeManagerRef,
+    /// Parallelism of non-root stages. Defaults to the number of worker nodes if not set.
+    parallelism: Option<NonZeroU64>,
 }
 
 impl Default for QueryId {
@@ -99,8 +165,16 @@ impl BatchPlanFragmenter {
             stage_graph_builder: StageGraphBuilder::new(),
             next_stage_id: 0,
             worker_node_manager,
+            parallelism: None,
         }
     }
+
+    /// Override the parallelism of non-root stages, e.g. by the `batch_parallelism` session
+    /// variable.
+    pub fn with_parallelism(mut self, parallelism: Option<NonZeroU64>) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
 }
 
 /// Contains the connection info of each stage.
@@ -146,6 +220,38 @@ impl Query {
             .map(|(id, _)| *id)
             .collect::<Vec<_>>()
     }
+
+    /// Creates a new query with the parameters of a prepared statement substituted by `params`,
+    /// where `params[i]` is the constant expression of the parameter `$i+1`. The query planned for
+    /// a prepared statement can be reused in this way without planning it again.
+    pub fn bind_params(&self, params: &[ExprNode]) -> Result<Query> {
+        let query_id = QueryId::default();
+        let stages = self
+            .stage_graph
+            .stages
+            .iter()
+            .map(|(id, stage)| {
+                let stage = QueryStage {
+                    query_id: query_id.clone(),
+                    id: *id,
+                    root: Arc::new(stage.root.bind_params(params)?),
+                    exchange_info: stage.exchange_info.clone(),
+                    parallelism: stage.parallelism,
+                    has_table_scan: stage.has_table_scan,
+                };
+                Ok((*id, Arc::new(stage)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Query {
+            query_id,
+            stage_graph: StageGraph {
+                root_stage_id: self.stage_graph.root_stage_id,
+                stages,
+                child_edges: self.stage_graph.child_edges.clone(),
+                parent_edges: self.stage_graph.parent_edges.clone(),
+            },
+        })
+    }
 }
 
 /// Fragment part of `Query`.
@@ -317,7 +423,10 @@ impl BatchPlanFragmenter {
         self.next_stage_id += 1;
         let parallelism = match parent_parallelism {
             // Non-root node
-            Some(_) => self.worker_node_manager.worker_node_count(),
+            Some(_) => match self.parallelism {
+                Some(parallelism) => parallelism.get() as usize,
+                None => self.worker_node_manager.worker_node_count(),
+            },
             // Root node.
             None => 1,
         };
@@ -396,21 +505,26 @@ impl BatchPlanFragmenter {
 #[cfg(test)]
 mod tests {
     use std::collections::{HashMap, HashSet};
+    use std::num::NonZeroU64;
     use std::rc::Rc;
     use std::sync::Arc;
 
     use risingwave_common::catalog::{ColumnDesc, TableDesc};
     use risingwave_common::types::DataType;
     use risingwave_pb::batch_plan::plan_node::NodeBody;
+    use risingwave_pb::batch_plan::values_node::ExprTuple;
+    use risingwave_pb::batch_plan::{FilterNode, ValuesNode};
     use risingwave_pb::common::{
         HostAddress, ParallelUnit, ParallelUnitType, WorkerNode, WorkerType,
     };
+    use risingwave_pb::expr::expr_node::RexNode;
     use risingwave_pb::plan_common::JoinType;
 
-    use crate::expr::InputRef;
+    use super::ExecutionPlanNode;
+    use crate::expr::{Expr, ExprImpl, ExprType, FunctionCall, InputRef, Parameter};
     use crate::optimizer::plan_node::{
         BatchExchange, BatchHashJoin, BatchSeqScan, EqJoinPredicate, LogicalJoin, LogicalScan,
-        PlanNodeType,
+        PlanNodeId, PlanNodeType,
     };
     use crate::optimizer::property::{Distribution, Order};
     use crate::optimizer::PlanRef;
@@ -522,6 +636,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(0, 0),
+            unschedulable: false,
         };
         let worker2 = WorkerNode {
             id: 1,
@@ -532,6 +647,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(8, 1),
+            unschedulable: false,
         };
         let worker3 = WorkerNode {
             id: 2,
@@ -542,11 +658,12 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(16, 2),
+            unschedulable: false,
         };
         let workers = vec![worker1, worker2, worker3];
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(workers));
         // Break the plan node into fragments.
-        let fragmenter = BatchPlanFragmenter::new(worker_node_manager);
+        let fragmenter = BatchPlanFragmenter::new(worker_node_manager.clone());
         let query = fragmenter.split(batch_exchange_node3.clone()).unwrap();
 
         assert_eq!(query.stage_graph.root_stage_id, 0);
@@ -619,6 +736,73 @@ mod tests {
         assert_eq!(scan_node2.root.node_type(), PlanNodeType::BatchSeqScan);
         assert_eq!(scan_node2.root.stage_id, None);
         assert_eq!(0, scan_node2.root.children.len());
+
+        // Parallelism of non-root stages can be overridden.
+        let fragmenter =
+            BatchPlanFragmenter::new(worker_node_manager).with_parallelism(NonZeroU64::new(5));
+        let query = fragmenter.split(batch_exchange_node3).unwrap();
+        assert_eq!(query.stage_graph.stages[&0].parallelism, 1);
+        assert_eq!(query.stage_graph.stages[&1].parallelism, 5);
+    }
+
+    #[test]
+    fn test_bind_params() {
+        let param = |index| Parameter::new(index, DataType::Int32).into();
+        let condition: ExprImpl = FunctionCall::new(
+            ExprType::Equal,
+            vec![InputRef::new(0, DataType::Int32).into(), param(2)],
+        )
+        .unwrap()
+        .into();
+        let values = ExecutionPlanNode {
+            plan_node_id: PlanNodeId(0),
+            plan_node_type: PlanNodeType::BatchValues,
+            node: NodeBody::Values(ValuesNode {
+                tuples: vec![ExprTuple {
+                    cells: vec![param(1).to_expr_proto()],
+                }],
+                ..Default::default()
+            }),
+            schema: vec![],
+            children: vec![],
+            stage_id: None,
+        };
+        let filter = ExecutionPlanNode {
+            plan_node_id: PlanNodeId(1),
+            plan_node_type: PlanNodeType::BatchFilter,
+            node: NodeBody::Filter(FilterNode {
+                search_condition: Some(condition.to_expr_proto()),
+            }),
+            schema: vec![],
+            children: vec![Arc::new(values)],
+            stage_id: None,
+        };
+
+        let params = [ExprImpl::literal_int(10), ExprImpl::literal_int(20)]
+            .map(|e| e.to_expr_proto())
+            .to_vec();
+        let bound = filter.bind_params(&params).unwrap();
+        let NodeBody::Filter(filter_node) = &bound.node else {
+            panic!("expect filter node");
+        };
+        let RexNode::FuncCall(func_call) = filter_node
+            .search_condition
+            .as_ref()
+            .unwrap()
+            .rex_node
+            .as_ref()
+            .unwrap()
+        else {
+            panic!("expect function call");
+        };
+        assert_eq!(func_call.children[1], params[1]);
+        let NodeBody::Values(values_node) = &bound.children[0].node else {
+            panic!("expect values node");
+        };
+        assert_eq!(values_node.tuples[0].cells[0], params[0]);
+
+        // All parameters must be bound.
+        assert!(filter.bind_params(&params[..1]).is_err());
     }
 
     fn generate_parallel_units(start_id: u32, node_id: u32) -> Vec<ParallelUnit> {
@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use futures::Stream;
 use futures_async_stream::try_stream;
-use log::debug;
+use log::{debug, warn};
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::batch_plan::{PlanNode as BatchPlanProst, TaskId, TaskOutputId};
@@ -25,10 +25,11 @@ use risingwave_pb::common::HostAddress;
 use risingwave_rpc_client::{
     ComputeClient, ComputeClientPool, ComputeClientPoolRef, ExchangeSource,
 };
+use tokio::sync::mpsc::Sender;
 use uuid::Uuid;
 
 use super::HummockSnapshotManagerRef;
-use crate::scheduler::distributed::QueryExecution;
+use crate::scheduler::distributed::{QueryExecution, QueryMessage};
 use crate::scheduler::plan_fragmenter::{Query, QueryId};
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
 use crate::scheduler::ExecutionContextRef;
@@ -43,6 +44,49 @@ pub struct QueryResultFetcher {
     task_output_id: TaskOutputId,
     task_host: HostAddress,
     compute_client_pool: ComputeClientPoolRef,
+
+    /// Used to stop the query if the results are not fully fetched. `None` for a single task
+    /// scheduled by [`QueryManager::schedule_single`], which is aborted directly.
+    query_msg_sender: Option<Sender<QueryMessage>>,
+}
+
+/// Stops the query when the result stream is dropped before it's exhausted, e.g., when the query
+/// is cancelled or times out.
+struct StopQueryGuard {
+    query_msg_sender: Option<Sender<QueryMessage>>,
+    task_output_id: TaskOutputId,
+    task_host: HostAddress,
+    compute_client_pool: ComputeClientPoolRef,
+    finished: bool,
+}
+
+impl Drop for StopQueryGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Some(sender) = self.query_msg_sender.take() {
+            if let Err(e) = sender.try_send(QueryMessage::Stop) {
+                warn!("Failed to stop query: {:?}", e);
+            }
+            return;
+        }
+        let task_id = self.task_output_id.get_task_id().unwrap().clone();
+        let task_host = self.task_host.clone();
+        let compute_client_pool = self.compute_client_pool.clone();
+        tokio::spawn(async move {
+            let result = async {
+                compute_client_pool
+                    .get_client_for_addr((&task_host).into())
+                    .await?
+                    .abort_task(task_id.clone())
+                    .await
+            };
+            if let Err(e) = result.await {
+                warn!("Failed to abort task {:?}: {}", task_id, e);
+            }
+        });
+    }
 }
 
 /// Manages execution of batch queries.
@@ -68,7 +112,7 @@ impl QueryManager {
 
     /// Schedule query to single node.
     ///
-    /// This is kept for dml only.
+    /// Used for DML and the local execution of queries reading tables.
     pub async fn schedule_single(
         &self,
         _context: ExecutionContextRef,
@@ -169,15 +213,29 @@ impl QueryResultFetcher {
             task_output_id,
             task_host,
             compute_client_pool,
+            query_msg_sender: None,
         }
     }
 
+    #[must_use]
+    pub fn with_query_msg_sender(mut self, query_msg_sender: Sender<QueryMessage>) -> Self {
+        self.query_msg_sender = Some(query_msg_sender);
+        self
+    }
+
     #[try_stream(ok = DataChunk, error = RwError)]
     async fn run(self) {
         debug!(
             "Starting to run query result fetcher, task output id: {:?}, task_host: {:?}",
             self.task_output_id, self.task_host
         );
+        let mut stop_guard = StopQueryGuard {
+            query_msg_sender: self.query_msg_sender,
+            task_output_id: self.task_output_id.clone(),
+            task_host: self.task_host.clone(),
+            compute_client_pool: self.compute_client_pool.clone(),
+            finished: false,
+        };
         let compute_client = self
             .compute_client_pool
             .get_client_for_addr((&self.task_host).into())
@@ -187,6 +245,7 @@ impl QueryResultFetcher {
         while let Some(chunk) = source.take_data().await? {
             yield chunk;
         }
+        stop_guard.finished = true;
 
         let epoch = self.epoch;
     }
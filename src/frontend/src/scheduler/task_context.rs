@@ -15,14 +15,25 @@
 use std::sync::Arc;
 
 use risingwave_batch::executor::BatchMetrics;
-use risingwave_batch::task::{BatchTaskContext, TaskId, TaskOutput, TaskOutputId};
+use risingwave_batch::task::{BatchTaskContext, MemoryContext, TaskId, TaskOutput, TaskOutputId};
+use risingwave_common::config::BatchConfig;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_source::SourceManagerRef;
 
 /// Batch task execution context in frontend.
-#[derive(Clone, Default)]
-pub struct FrontendBatchTaskContext {}
+#[derive(Clone)]
+pub struct FrontendBatchTaskContext {
+    memory_context: MemoryContext,
+}
+
+impl Default for FrontendBatchTaskContext {
+    fn default() -> Self {
+        Self {
+            memory_context: MemoryContext::new(BatchConfig::default().query_memory_quota_mb << 20),
+        }
+    }
+}
 
 impl BatchTaskContext for FrontendBatchTaskContext {
     fn get_task_output(&self, _task_output_id: TaskOutputId) -> Result<TaskOutput> {
@@ -44,4 +55,16 @@ impl BatchTaskContext for FrontendBatchTaskContext {
     fn stats(&self) -> Arc<BatchMetrics> {
         todo!()
     }
+
+    fn hash_join_memory_quota(&self) -> usize {
+        BatchConfig::default().hash_join_memory_quota_mb << 20
+    }
+
+    fn sort_memory_quota(&self) -> usize {
+        BatchConfig::default().sort_memory_quota_mb << 20
+    }
+
+    fn memory_context(&self) -> MemoryContext {
+        self.memory_context.clone()
+    }
 }
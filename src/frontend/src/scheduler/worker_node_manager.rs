@@ -99,6 +99,7 @@ mod tests {
                 host: Some(HostAddr::try_from("127.0.0.1:1234").unwrap().to_protobuf()),
                 state: worker_node::State::Running as i32,
                 parallel_units: vec![],
+                unschedulable: false,
             },
             WorkerNode {
                 id: 2,
@@ -106,6 +107,7 @@ mod tests {
                 host: Some(HostAddr::try_from("127.0.0.1:1235").unwrap().to_protobuf()),
                 state: worker_node::State::Running as i32,
                 parallel_units: vec![],
+                unschedulable: false,
             },
         ];
         worker_nodes
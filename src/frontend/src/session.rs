@@ -14,34 +14,55 @@
 
 use std::collections::HashMap;
 use std::fmt::Formatter;
+use std::future::Future;
 use std::marker::Sync;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use parking_lot::RwLock;
+use futures::future::{pending, BoxFuture};
+use futures::{stream, FutureExt, StreamExt, TryStreamExt};
+use futures_async_stream::try_stream;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use pgwire::error::PgError;
+use pgwire::pg_auth::UserAuthenticator;
+use pgwire::pg_extended::{PgPortal, StatementDescription};
+use pgwire::pg_field_descriptor::TypeOid;
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
+use pgwire::pg_server::{BoxedError, Session, SessionId, SessionManager};
+use risingwave_batch::executor::BoxedDataChunkStream;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::DEFAULT_SUPPER_USER;
 use risingwave_common::config::FrontendConfig;
-use risingwave_common::error::{Result, RwError};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::service::MetricsManager;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::common::WorkerType;
 use risingwave_rpc_client::{ComputeClientPool, MetaClient};
-use risingwave_sqlparser::parser::Parser;
+use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::{Parser, ParserError};
 use tokio::sync::oneshot::Sender;
-use tokio::sync::watch;
+use tokio::sync::{oneshot, watch};
 use tokio::task::JoinHandle;
 
 use crate::catalog::catalog_service::{CatalogReader, CatalogWriter, CatalogWriterImpl};
 use crate::catalog::root_catalog::Catalog;
-use crate::handler::dml::IMPLICIT_FLUSH;
+use crate::config::ConfigMap;
 use crate::handler::handle;
+use crate::handler::prepared_statement::{describe_statement, handle_prepared_query};
+use crate::handler::util::{data_type_to_type_oid, type_oid_to_data_type};
 use crate::meta_client::{FrontendMetaClient, FrontendMetaClientImpl};
+use crate::monitor::{FrontendMetrics, QueryLog, QueryLogEntry};
 use crate::observer::observer_manager::ObserverManager;
 use crate::optimizer::plan_node::PlanNodeId;
 use crate::scheduler::worker_node_manager::{WorkerNodeManager, WorkerNodeManagerRef};
-use crate::scheduler::{HummockSnapshotManager, HummockSnapshotManagerRef, QueryManager};
+use crate::scheduler::{
+    HummockSnapshotManager, HummockSnapshotManagerRef, PlanCache, QueryManager,
+};
+use crate::user::user_authentication::user_authenticator;
+use crate::user::user_manager::UserInfoManager;
+use crate::user::user_service::{UserInfoReader, UserInfoWriter, UserInfoWriterImpl};
 use crate::FrontendOpts;
 
 pub struct OptimizerContext {
@@ -127,6 +148,10 @@ pub struct FrontendEnv {
     worker_node_manager: WorkerNodeManagerRef,
     query_manager: QueryManager,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    user_info_writer: Arc<dyn UserInfoWriter>,
+    user_info_reader: UserInfoReader,
+    frontend_metrics: Arc<FrontendMetrics>,
+    query_log: Arc<QueryLog>,
 }
 
 impl FrontendEnv {
@@ -138,11 +163,14 @@ impl FrontendEnv {
     }
 
     pub fn mock() -> Self {
-        use crate::test_utils::{MockCatalogWriter, MockFrontendMetaClient};
+        use crate::test_utils::{MockCatalogWriter, MockFrontendMetaClient, MockUserInfoWriter};
 
         let catalog = Arc::new(RwLock::new(Catalog::default()));
         let catalog_writer = Arc::new(MockCatalogWriter::new(catalog.clone()));
         let catalog_reader = CatalogReader::new(catalog);
+        let user_info_manager = Arc::new(RwLock::new(UserInfoManager::default()));
+        let user_info_writer = Arc::new(MockUserInfoWriter::new(user_info_manager.clone()));
+        let user_info_reader = UserInfoReader::new(user_info_manager);
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(vec![]));
         let meta_client = Arc::new(MockFrontendMetaClient {});
         let hummock_snapshot_manager = Arc::new(HummockSnapshotManager::new(meta_client.clone()));
@@ -159,6 +187,10 @@ impl FrontendEnv {
             worker_node_manager,
             query_manager,
             hummock_snapshot_manager,
+            user_info_writer,
+            user_info_reader,
+            frontend_metrics: Arc::new(FrontendMetrics::unused()),
+            query_log: Arc::new(QueryLog::default()),
         }
     }
 
@@ -177,7 +209,7 @@ impl FrontendEnv {
             .unwrap();
         // Register in meta by calling `AddWorkerNode` RPC.
         meta_client
-            .register(&frontend_address, WorkerType::Frontend)
+            .register(&frontend_address, WorkerType::Frontend, 0)
             .await?;
 
         let (heartbeat_join_handle, heartbeat_shutdown_sender) = MetaClient::start_heartbeat_loop(
@@ -193,6 +225,16 @@ impl FrontendEnv {
         ));
         let catalog_reader = CatalogReader::new(catalog.clone());
 
+        // Users are synced through the same notification stream as the catalog, but waited on
+        // with their own watch channel.
+        let (user_info_updated_tx, user_info_updated_rx) = watch::channel(0);
+        let user_info_manager = Arc::new(RwLock::new(UserInfoManager::default()));
+        let user_info_writer = Arc::new(UserInfoWriterImpl::new(
+            meta_client.clone(),
+            user_info_updated_rx,
+        ));
+        let user_info_reader = UserInfoReader::new(user_info_manager.clone());
+
         let worker_node_manager = Arc::new(WorkerNodeManager::new(meta_client.clone()).await?);
 
         let frontend_meta_client = Arc::new(FrontendMetaClientImpl(meta_client.clone()));
@@ -212,10 +254,21 @@ impl FrontendEnv {
             catalog,
             catalog_updated_tx,
             hummock_snapshot_manager.clone(),
+            user_info_manager,
+            user_info_updated_tx,
         )
         .await;
         let observer_join_handle = observer_manager.start().await?;
 
+        let registry = prometheus::Registry::new();
+        let frontend_metrics = Arc::new(FrontendMetrics::new(registry.clone()));
+        if opts.metrics_level > 0 {
+            MetricsManager::boot_metrics_service(
+                opts.prometheus_listener_addr.clone(),
+                Arc::new(registry),
+            );
+        }
+
         meta_client.activate(&frontend_address).await?;
 
         Ok((
@@ -226,6 +279,10 @@ impl FrontendEnv {
                 meta_client: frontend_meta_client,
                 query_manager,
                 hummock_snapshot_manager,
+                user_info_writer,
+                user_info_reader,
+                frontend_metrics,
+                query_log: Arc::new(QueryLog::default()),
             },
             observer_join_handle,
             heartbeat_join_handle,
@@ -243,6 +300,16 @@ impl FrontendEnv {
         &self.catalog_reader
     }
 
+    /// Get a reference to the frontend env's user info writer.
+    pub fn user_info_writer(&self) -> &dyn UserInfoWriter {
+        &*self.user_info_writer
+    }
+
+    /// Get a reference to the frontend env's user info reader.
+    pub fn user_info_reader(&self) -> &UserInfoReader {
+        &self.user_info_reader
+    }
+
     pub fn worker_node_manager(&self) -> &WorkerNodeManager {
         &*self.worker_node_manager
     }
@@ -266,44 +333,49 @@ impl FrontendEnv {
     pub fn hummock_snapshot_manager(&self) -> &HummockSnapshotManagerRef {
         &self.hummock_snapshot_manager
     }
+
+    pub fn frontend_metrics(&self) -> &FrontendMetrics {
+        &self.frontend_metrics
+    }
+
+    pub fn query_log(&self) -> &QueryLog {
+        &self.query_log
+    }
 }
 
 pub struct SessionImpl {
     env: FrontendEnv,
     database: String,
+    user_name: String,
+    /// How the user of this session is authenticated when connecting.
+    user_authenticator: UserAuthenticator,
+    /// Identifies this session in `CancelRequest`s.
+    id: SessionId,
     /// Stores the value of configurations.
-    config_map: RwLock<HashMap<String, ConfigEntry>>,
-}
-
-#[derive(Clone)]
-pub struct ConfigEntry {
-    str_val: String,
-}
-
-impl ConfigEntry {
-    pub fn new(str_val: String) -> Self {
-        ConfigEntry { str_val }
-    }
-
-    /// Only used for boolean configurations.
-    pub fn is_set(&self, default: bool) -> bool {
-        self.str_val.parse().unwrap_or(default)
-    }
-
-    pub fn get_val<V>(&self, default: V) -> V
-    where
-        for<'a> V: TryFrom<&'a str, Error = RwError>,
-    {
-        V::try_from(&self.str_val).unwrap_or(default)
-    }
+    config_map: RwLock<ConfigMap>,
+    /// The plans of the prepared queries executed in this session.
+    plan_cache: PlanCache,
+    /// Notified to cancel the running query. `None` if no query is running.
+    current_query_cancel_flag: Mutex<Option<oneshot::Sender<()>>>,
 }
 
 impl SessionImpl {
-    pub fn new(env: FrontendEnv, database: String) -> Self {
+    pub fn new(
+        env: FrontendEnv,
+        database: String,
+        user_name: String,
+        user_authenticator: UserAuthenticator,
+        id: SessionId,
+    ) -> Self {
         Self {
             env,
             database,
-            config_map: Self::init_config_map(),
+            user_name,
+            user_authenticator,
+            id,
+            config_map: Default::default(),
+            plan_cache: Default::default(),
+            current_query_cancel_flag: Mutex::new(None),
         }
     }
 
@@ -312,7 +384,12 @@ impl SessionImpl {
         Self {
             env: FrontendEnv::mock(),
             database: "dev".to_string(),
-            config_map: Self::init_config_map(),
+            user_name: DEFAULT_SUPPER_USER.to_string(),
+            user_authenticator: UserAuthenticator::None,
+            id: (0, 0),
+            config_map: Default::default(),
+            plan_cache: Default::default(),
+            current_query_cancel_flag: Mutex::new(None),
         }
     }
 
@@ -324,28 +401,186 @@ impl SessionImpl {
         &self.database
     }
 
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
     /// Set configuration values in this session.
-    /// For example, `set_config("RW_IMPLICIT_FLUSH", true)` will implicit flush for every inserts.
-    pub fn set_config(&self, key: &str, val: &str) {
-        self.config_map
-            .write()
-            .insert(key.to_string(), ConfigEntry::new(val.to_string()));
+    /// For example, `set_config("RW_IMPLICIT_FLUSH", &["true"])` will implicit flush for every
+    /// inserts.
+    pub fn set_config(&self, key: &str, values: &[String]) -> Result<()> {
+        self.config_map.write().set(key, values)
+    }
+
+    /// Reset a configuration value in this session to its default.
+    pub fn reset_config(&self, key: &str) -> Result<()> {
+        self.config_map.write().reset(key)
     }
 
     /// Get configuration values in this session.
-    pub fn get_config(&self, key: &str) -> Option<ConfigEntry> {
-        let reader = self.config_map.read();
-        reader.get(key).cloned()
+    pub fn config(&self) -> RwLockReadGuard<'_, ConfigMap> {
+        self.config_map.read()
+    }
+
+    pub fn plan_cache(&self) -> &PlanCache {
+        &self.plan_cache
+    }
+
+    /// Registers a new query of this session. The returned future resolves to the error to fail
+    /// the query with, once it's cancelled by [`Self::cancel_current_query`] or runs longer than
+    /// `statement_timeout`.
+    fn start_query(&self) -> BoxFuture<'static, RwError> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        *self.current_query_cancel_flag.lock() = Some(cancel_tx);
+        let statement_timeout = self.config().statement_timeout();
+        async move {
+            let timeout = async move {
+                match statement_timeout {
+                    Some(timeout) => tokio::time::sleep(timeout).await,
+                    None => pending().await,
+                }
+            };
+            let reason = tokio::select! {
+                Ok(()) = cancel_rx => "user request",
+                _ = timeout => "statement timeout",
+            };
+            RwError::from(ErrorCode::QueryCancelled(reason.to_string()))
+        }
+        .boxed()
     }
 
-    fn init_config_map() -> RwLock<HashMap<String, ConfigEntry>> {
-        let mut map = HashMap::new();
-        // FIXME: May need better init way + default config.
-        map.insert(
-            IMPLICIT_FLUSH.to_string(),
-            ConfigEntry::new("false".to_string()),
-        );
-        RwLock::new(map)
+    /// Runs `query` as the current query of this session. It fails once the query is cancelled
+    /// by [`Self::cancel_current_query`] or runs longer than `statement_timeout`, in which case
+    /// `query` is dropped to stop its execution.
+    pub async fn run_cancellable<T>(&self, query: impl Future<Output = Result<T>>) -> Result<T> {
+        let cancelled = self.start_query();
+        let result = tokio::select! {
+            result = query => result,
+            err = cancelled => Err(err),
+        };
+        self.current_query_cancel_flag.lock().take();
+        result
+    }
+
+    /// Like [`Self::run_cancellable`], but for a query whose results are streamed to the client.
+    /// The stream fails once the query is cancelled, and is dropped by the caller then.
+    pub fn cancellable_stream(&self, data_stream: BoxedDataChunkStream) -> BoxedDataChunkStream {
+        cancellable_stream(data_stream, self.start_query())
+    }
+
+    /// Cancels the running query of this session, if any.
+    pub fn cancel_current_query(&self) {
+        if let Some(cancel_tx) = self.current_query_cancel_flag.lock().take() {
+            let _ = cancel_tx.send(());
+        }
+    }
+
+    fn query_log_entry(&self, sql: &str) -> QueryLogEntry {
+        QueryLogEntry::new(
+            self.env.query_log.clone(),
+            self.config().log_min_duration_statement(),
+            self.id.0,
+            self.user_name.clone(),
+            sql.to_string(),
+        )
+    }
+
+    async fn run_statement_inner(
+        self: Arc<Self>,
+        sql: &str,
+    ) -> std::result::Result<PgResponse, BoxedError> {
+        // Parse sql.
+        let mut stmts = Parser::parse_sql(sql).map_err(|e| {
+            tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
+            e
+        })?;
+        // With pgwire, there would be at most 1 statement in the vec.
+        assert!(stmts.len() <= 1);
+        if stmts.is_empty() {
+            return Ok(PgResponse::new(
+                pgwire::pg_response::StatementType::EMPTY,
+                0,
+                vec![],
+                vec![],
+            ));
+        }
+        let stmt = stmts.swap_remove(0);
+        let rsp = handle(self, stmt).await.map_err(|e| {
+            tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
+            e
+        })?;
+        Ok(rsp)
+    }
+}
+
+/// Attaches the SQLSTATE code of the error, so that the client gets it in the `ErrorResponse`.
+fn with_sqlstate(e: BoxedError) -> BoxedError {
+    if let Some(rw_error) = e.downcast_ref::<RwError>() {
+        Box::new(PgError::new(rw_error.sqlstate(), e))
+    } else if e.is::<ParserError>() {
+        // syntax_error
+        Box::new(PgError::new("42601", e))
+    } else {
+        e
+    }
+}
+
+/// Attaches the SQLSTATE codes to the error of `rsp`, and to the errors in its rows if it's a
+/// query. See [`with_sqlstate`].
+fn response_with_sqlstate(
+    rsp: std::result::Result<PgResponse, BoxedError>,
+) -> std::result::Result<PgResponse, BoxedError> {
+    let mut rsp = rsp.map_err(with_sqlstate)?;
+    if rsp.is_query() {
+        let values_stream = std::mem::replace(rsp.values_stream(), stream::empty().boxed());
+        *rsp.values_stream() = values_stream.map_err(with_sqlstate).boxed();
+    }
+    Ok(rsp)
+}
+
+/// Adds the statement of `log_entry` to the query log once the results in `rsp` are all returned.
+fn log_response(
+    mut log_entry: QueryLogEntry,
+    rsp: std::result::Result<PgResponse, BoxedError>,
+) -> std::result::Result<PgResponse, BoxedError> {
+    match rsp {
+        // The rows of a query are produced as the response is consumed.
+        Ok(mut rsp) if rsp.is_query() => {
+            log_entry.finish_planning();
+            let values_stream = std::mem::replace(rsp.values_stream(), stream::empty().boxed());
+            *rsp.values_stream() = values_stream
+                .inspect(move |rows| match rows {
+                    Ok(rows) => log_entry.add_rows(rows.len() as i64),
+                    Err(e) => log_entry.set_error(e.to_string()),
+                })
+                .boxed();
+            Ok(rsp)
+        }
+        Ok(rsp) => {
+            log_entry.add_rows(rsp.get_effected_rows_cnt() as i64);
+            Ok(rsp)
+        }
+        Err(e) => {
+            log_entry.set_error(e.to_string());
+            Err(e)
+        }
+    }
+}
+
+#[try_stream(boxed, ok = DataChunk, error = RwError)]
+async fn cancellable_stream(
+    mut data_stream: BoxedDataChunkStream,
+    mut cancelled: BoxFuture<'static, RwError>,
+) {
+    loop {
+        let chunk = tokio::select! {
+            chunk = data_stream.next() => chunk,
+            err = &mut cancelled => return Err(err),
+        };
+        match chunk {
+            Some(chunk) => yield chunk?,
+            None => break,
+        }
     }
 }
 
@@ -354,13 +589,62 @@ pub struct SessionManagerImpl {
     observer_join_handle: JoinHandle<()>,
     heartbeat_join_handle: JoinHandle<()>,
     _heartbeat_shutdown_sender: Sender<()>,
+    /// The sessions of all open connections, by which `CancelRequest`s are dispatched.
+    sessions: Mutex<HashMap<SessionId, Arc<SessionImpl>>>,
+    next_process_id: AtomicI32,
 }
 
 impl SessionManager for SessionManagerImpl {
     type Session = SessionImpl;
 
-    fn connect(&self, database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
-        Ok(SessionImpl::new(self.env.clone(), database.to_string()).into())
+    fn connect(
+        &self,
+        database: &str,
+        user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+        let user_authenticator = {
+            let user_reader = self.env.user_info_reader().read_guard();
+            let user = user_reader.get_user_by_name(user_name).ok_or_else(|| {
+                RwError::from(ErrorCode::PermissionDenied(format!(
+                    "User {} does not exist",
+                    user_name
+                )))
+            })?;
+            if !user.can_login {
+                return Err(RwError::from(ErrorCode::PermissionDenied(format!(
+                    "User {} is not allowed to login",
+                    user_name
+                )))
+                .into());
+            }
+            user_authenticator(user.auth_info.as_ref())?
+        };
+        let id = (
+            self.next_process_id.fetch_add(1, Ordering::Relaxed),
+            rand::random(),
+        );
+        let session: Arc<SessionImpl> = SessionImpl::new(
+            self.env.clone(),
+            database.to_string(),
+            user_name.to_string(),
+            user_authenticator,
+            id,
+        )
+        .into();
+        self.sessions.lock().insert(id, session.clone());
+        Ok(session)
+    }
+
+    fn cancel_queries_in_session(&self, session_id: SessionId) {
+        let session = self.sessions.lock().get(&session_id).cloned();
+        match session {
+            Some(session) => session.cancel_current_query(),
+            None => tracing::info!("no session to cancel for {:?}", session_id),
+        }
+    }
+
+    fn end_session(&self, session: &Self::Session) {
+        self.sessions.lock().remove(&session.id());
     }
 }
 
@@ -373,6 +657,8 @@ impl SessionManagerImpl {
             observer_join_handle: join_handle,
             heartbeat_join_handle,
             _heartbeat_shutdown_sender: heartbeat_shutdown_sender,
+            sessions: Mutex::new(HashMap::new()),
+            next_process_id: AtomicI32::new(1),
         })
     }
 
@@ -385,43 +671,180 @@ impl SessionManagerImpl {
 
 #[async_trait::async_trait]
 impl Session for SessionImpl {
+    fn user_authenticator(&self) -> &UserAuthenticator {
+        &self.user_authenticator
+    }
+
+    fn id(&self) -> SessionId {
+        self.id
+    }
+
     async fn run_statement(
         self: Arc<Self>,
         sql: &str,
     ) -> std::result::Result<PgResponse, BoxedError> {
-        // Parse sql.
-        let mut stmts = Parser::parse_sql(sql).map_err(|e| {
+        let log_entry = self.query_log_entry(sql);
+        let rsp = self.run_statement_inner(sql).await;
+        response_with_sqlstate(log_response(log_entry, rsp))
+    }
+
+    fn split_statements(&self, sql: &str) -> std::result::Result<Vec<String>, BoxedError> {
+        let stmts = Parser::parse_sql(sql).map_err(|e| {
             tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
-            e
+            with_sqlstate(e.into())
         })?;
+        // Keep the original text of a single statement, which is the common case.
+        if stmts.len() <= 1 {
+            return Ok(vec![sql.to_string()]);
+        }
+        Ok(stmts.iter().map(|stmt| stmt.to_string()).collect())
+    }
+
+    fn describe_statement(
+        self: Arc<Self>,
+        sql: &str,
+        param_types: Vec<Option<TypeOid>>,
+    ) -> std::result::Result<StatementDescription, BoxedError> {
+        let mut stmts = Parser::parse_sql(sql).map_err(|e| with_sqlstate(e.into()))?;
         // With pgwire, there would be at most 1 statement in the vec.
         assert!(stmts.len() <= 1);
         if stmts.is_empty() {
-            return Ok(PgResponse::new(
-                pgwire::pg_response::StatementType::EMPTY,
-                0,
-                vec![],
-                vec![],
-            ));
+            return Ok(StatementDescription {
+                param_types: vec![],
+                row_description: Some(vec![]),
+            });
         }
-        let stmt = stmts.swap_remove(0);
-        let rsp = handle(self, stmt).await.map_err(|e| {
+        let param_types = param_types
+            .into_iter()
+            .map(|t| t.map(type_oid_to_data_type))
+            .collect();
+        let (param_types, row_description) =
+            describe_statement(&self, stmts.swap_remove(0), param_types).map_err(|e| {
+                tracing::error!("failed to describe sql:\n{}:\n{}", sql, e);
+                with_sqlstate(e.into())
+            })?;
+        Ok(StatementDescription {
+            param_types: param_types.into_iter().map(data_type_to_type_oid).collect(),
+            row_description,
+        })
+    }
+
+    async fn run_portal(
+        self: Arc<Self>,
+        portal: &PgPortal,
+    ) -> std::result::Result<PgResponse, BoxedError> {
+        let sql = portal.statement();
+        let mut stmts = Parser::parse_sql(sql).map_err(|e| with_sqlstate(e.into()))?;
+        // Only the plans of queries are cached. The other statements are run with the parameters
+        // substituted into the query string.
+        if stmts.len() != 1 || !matches!(stmts[0], Statement::Query(_)) {
+            return self.run_statement(portal.query_string()).await;
+        }
+        let param_types = portal
+            .param_types()
+            .iter()
+            .map(|t| type_oid_to_data_type(*t))
+            .collect();
+        let log_entry = self.query_log_entry(portal.query_string());
+        let rsp = handle_prepared_query(
+            OptimizerContext::new(self),
+            sql,
+            stmts.swap_remove(0),
+            param_types,
+            portal.params().to_vec(),
+            portal.result_format_codes().to_vec(),
+        )
+        .await
+        .map_err(|e| {
             tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
-            e
-        })?;
-        Ok(rsp)
+            e.into()
+        });
+        response_with_sqlstate(log_response(log_entry, rsp))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
     use assert_impl::assert_impl;
+    use futures::future::pending;
+    use futures::{stream, StreamExt};
+    use pgwire::error::sqlstate_of;
+    use pgwire::pg_server::{BoxedError, Session};
+    use risingwave_common::error::{ErrorCode, Result, RwError};
+    use risingwave_sqlparser::parser::Parser;
 
-    use crate::session::OptimizerContextRef;
+    use crate::config::STATEMENT_TIMEOUT;
+    use crate::session::{with_sqlstate, OptimizerContextRef, SessionImpl};
 
     #[test]
     fn check_query_context_ref() {
         assert_impl!(Send: OptimizerContextRef);
         assert_impl!(!Sync: OptimizerContextRef);
     }
+
+    #[test]
+    fn test_split_statements() {
+        let session = SessionImpl::mock();
+        let stmts = session
+            .split_statements("CREATE TABLE t (v INT); INSERT INTO t VALUES (1);")
+            .unwrap();
+        assert_eq!(
+            stmts,
+            vec!["CREATE TABLE t (v INT)", "INSERT INTO t VALUES (1)"]
+        );
+        assert_eq!(session.split_statements(";").unwrap(), vec![";"]);
+        assert!(session.split_statements("SELECT 1; SELEC 2").is_err());
+    }
+
+    #[test]
+    fn test_with_sqlstate() {
+        let sqlstate = |e: BoxedError| sqlstate_of(&with_sqlstate(e));
+        let denied = RwError::from(ErrorCode::PermissionDenied("no".to_string()));
+        assert_eq!(sqlstate(denied.into()), "42501");
+        assert_eq!(
+            sqlstate(Parser::parse_sql("SELEC 1").unwrap_err().into()),
+            "42601"
+        );
+        assert_eq!(sqlstate("oops".into()), "XX000");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_query() {
+        let session = Arc::new(SessionImpl::mock());
+        assert_eq!(session.run_cancellable(async { Ok(1) }).await.unwrap(), 1);
+
+        let canceller = session.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel_current_query();
+        });
+        let err = session
+            .run_cancellable(pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "canceling statement due to user request");
+
+        session
+            .set_config(STATEMENT_TIMEOUT, &["10".to_string()])
+            .unwrap();
+        let err = session
+            .run_cancellable(pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "canceling statement due to statement timeout"
+        );
+
+        // A streamed query fails in the middle of its results.
+        let mut data_stream = session.cancellable_stream(stream::pending().boxed());
+        let err = data_stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "canceling statement due to statement timeout"
+        );
+    }
 }
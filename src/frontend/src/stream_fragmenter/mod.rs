@@ -24,6 +24,7 @@ use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::Result;
 use risingwave_pb::plan_common::JoinType;
+use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::{
     DispatchStrategy, DispatcherType, ExchangeNode, FragmentType,
     StreamFragmentGraph as StreamFragmentGraphProto, StreamNode,
@@ -92,9 +93,34 @@ impl StreamFragmenter {
         insert_exchange_flag: bool,
     ) -> Result<StreamNode> {
         let mut inputs = vec![];
+        let is_exchange = matches!(stream_node.node_body, Some(NodeBody::Exchange(_)));
 
         for child_node in stream_node.input {
             let input = match child_node.get_node_body()? {
+                // Put a stream source into a fragment of its own, so that its actors can be shared
+                // by the materialized views created later on the same source.
+                NodeBody::Source(source)
+                    if source.source_type == SourceType::Source as i32
+                        && !is_exchange
+                        && !child_node.fields.is_empty() =>
+                {
+                    let strategy = DispatchStrategy {
+                        r#type: DispatcherType::NoShuffle.into(),
+                        column_indices: vec![],
+                    };
+                    let append_only = child_node.append_only;
+                    StreamNode {
+                        pk_indices: child_node.pk_indices.clone(),
+                        fields: child_node.fields.clone(),
+                        node_body: Some(NodeBody::Exchange(ExchangeNode {
+                            strategy: Some(strategy),
+                        })),
+                        operator_id: state.gen_operator_id() as u64,
+                        input: vec![child_node],
+                        identity: "Exchange (NoShuffle)".to_string(),
+                        append_only,
+                    }
+                }
                 // For stateful operators, set `exchange_flag = true`. If it's already true, force
                 // add an exchange.
                 NodeBody::HashAgg(_)
@@ -185,10 +211,12 @@ impl StreamFragmenter {
         match stream_node.get_node_body()? {
             NodeBody::Source(_) => current_fragment.fragment_type = FragmentType::Source,
 
-            NodeBody::Materialize(_) => current_fragment.fragment_type = FragmentType::Sink,
+            NodeBody::Materialize(_) | NodeBody::Sink(_) => {
+                current_fragment.fragment_type = FragmentType::Sink
+            }
 
             // TODO: Force singleton for TopN as a workaround. We should implement two phase TopN.
-            NodeBody::TopN(_) => current_fragment.is_singleton = true,
+            NodeBody::TopN(_) | NodeBody::AppendOnlyTopN(_) => current_fragment.is_singleton = true,
 
             NodeBody::Chain(ref node) => {
                 // memorize table id for later use
@@ -17,16 +17,24 @@ use std::io::Write;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use futures_async_stream::for_await;
 use parking_lot::RwLock;
+use pgwire::pg_auth::UserAuthenticator;
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
-use risingwave_common::catalog::{TableId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+use pgwire::pg_server::{BoxedError, Session, SessionId, SessionManager};
+use risingwave_common::catalog::{
+    TableId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, DEFAULT_SUPPER_USER,
+};
 use risingwave_common::error::Result;
+use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::UserInfo;
 use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
 use tempfile::{Builder, NamedTempFile};
@@ -39,6 +47,8 @@ use crate::meta_client::FrontendMetaClient;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
 use crate::session::{FrontendEnv, OptimizerContext, SessionImpl};
+use crate::user::user_manager::UserInfoManager;
+use crate::user::user_service::UserInfoWriter;
 use crate::FrontendOpts;
 
 /// An embedded frontend without starting meta and without starting frontend as a tcp server.
@@ -50,9 +60,17 @@ pub struct LocalFrontend {
 impl SessionManager for LocalFrontend {
     type Session = SessionImpl;
 
-    fn connect(&self, _database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+    fn connect(
+        &self,
+        _database: &str,
+        _user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
         Ok(self.session_ref())
     }
+
+    fn cancel_queries_in_session(&self, _session_id: SessionId) {}
+
+    fn end_session(&self, _session: &Self::Session) {}
 }
 
 impl LocalFrontend {
@@ -70,12 +88,15 @@ impl LocalFrontend {
     }
 
     pub async fn query_formatted_result(&self, sql: impl Into<String>) -> Vec<String> {
-        self.run_sql(sql)
-            .await
-            .unwrap()
-            .iter()
-            .map(|row| format!("{:?}", row))
-            .collect()
+        let mut rsp = self.run_sql(sql).await.unwrap();
+        let mut res = vec![];
+        #[for_await]
+        for rows in rsp.values_stream() {
+            for row in rows.unwrap() {
+                res.push(format!("{:?}", row));
+            }
+        }
+        res
     }
 
     /// Convert a sql (must be an `Query`) into an unoptimized batch plan.
@@ -89,7 +110,8 @@ impl LocalFrontend {
                 let mut binder = Binder::new(
                     session.env().catalog_reader().read_guard(),
                     session.database().to_string(),
-                );
+                )
+                .with_search_path(session.config().search_path().to_vec());
                 binder.bind(Statement::Query(query.clone()))?
             };
             Planner::new(OptimizerContext::new(session).into())
@@ -105,6 +127,9 @@ impl LocalFrontend {
         Arc::new(SessionImpl::new(
             self.env.clone(),
             DEFAULT_DATABASE_NAME.to_string(),
+            DEFAULT_SUPPER_USER.to_string(),
+            UserAuthenticator::None,
+            (0, 0),
         ))
     }
 }
@@ -114,6 +139,7 @@ pub struct MockCatalogWriter {
     id: AtomicU32,
     table_id_to_schema_id: RwLock<HashMap<u32, SchemaId>>,
     schema_id_to_database_id: RwLock<HashMap<u32, DatabaseId>>,
+    sources: RwLock<HashMap<u32, ProstSource>>,
 }
 
 #[async_trait::async_trait]
@@ -165,7 +191,19 @@ impl CatalogWriter for MockCatalogWriter {
         self.create_source_inner(source).map(|_| ())
     }
 
-    async fn drop_materialized_source(&self, source_id: u32, table_id: TableId) -> Result<()> {
+    async fn create_sink(&self, mut sink: ProstSink, _graph: StreamFragmentGraph) -> Result<()> {
+        sink.id = self.gen_id();
+        self.catalog.write().create_sink(&sink);
+        self.add_table_or_source_id(sink.id, sink.schema_id, sink.database_id);
+        Ok(())
+    }
+
+    async fn drop_materialized_source(
+        &self,
+        source_id: u32,
+        table_id: TableId,
+        _cascade: bool,
+    ) -> Result<()> {
         let (database_id, schema_id) = self.drop_table_or_source_id(source_id);
         self.drop_table_or_source_id(table_id.table_id);
         self.catalog
@@ -174,14 +212,41 @@ impl CatalogWriter for MockCatalogWriter {
         self.catalog
             .write()
             .drop_source(database_id, schema_id, source_id);
+        self.sources.write().remove(&source_id);
         Ok(())
     }
 
-    async fn drop_source(&self, source_id: u32) -> Result<()> {
+    async fn drop_source(&self, source_id: u32, _cascade: bool) -> Result<()> {
         let (database_id, schema_id) = self.drop_table_or_source_id(source_id);
         self.catalog
             .write()
             .drop_source(database_id, schema_id, source_id);
+        self.sources.write().remove(&source_id);
+        Ok(())
+    }
+
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        source_version: u64,
+        column: ProstColumnCatalog,
+    ) -> Result<()> {
+        let mut sources = self.sources.write();
+        let source = sources.get_mut(&source_id).unwrap();
+        if let Some(Info::StreamSource(info)) = source.info.as_mut() {
+            assert_eq!(info.version, source_version);
+            info.columns.push(column);
+            info.version += 1;
+        }
+        self.catalog.write().update_source(source);
+        Ok(())
+    }
+
+    async fn drop_sink(&self, sink_id: u32) -> Result<()> {
+        let (database_id, schema_id) = self.drop_table_or_source_id(sink_id);
+        self.catalog
+            .write()
+            .drop_sink(database_id, schema_id, sink_id);
         Ok(())
     }
 
@@ -196,7 +261,7 @@ impl CatalogWriter for MockCatalogWriter {
         Ok(())
     }
 
-    async fn drop_materialized_view(&self, table_id: TableId) -> Result<()> {
+    async fn drop_materialized_view(&self, table_id: TableId, _cascade: bool) -> Result<()> {
         let (database_id, schema_id) = self.drop_table_or_source_id(table_id.table_id);
         self.catalog
             .write()
@@ -223,6 +288,7 @@ impl MockCatalogWriter {
             id: AtomicU32::new(0),
             table_id_to_schema_id: Default::default(),
             schema_id_to_database_id: RwLock::new(map),
+            sources: Default::default(),
         }
     }
 
@@ -263,6 +329,7 @@ impl MockCatalogWriter {
         source.id = self.gen_id();
         self.catalog.write().create_source(source.clone());
         self.add_table_or_source_id(source.id, source.schema_id, source.database_id);
+        self.sources.write().insert(source.id, source.clone());
         Ok(source.id)
     }
 
@@ -275,6 +342,36 @@ impl MockCatalogWriter {
     }
 }
 
+pub struct MockUserInfoWriter {
+    user_info: Arc<RwLock<UserInfoManager>>,
+}
+
+#[async_trait::async_trait]
+impl UserInfoWriter for MockUserInfoWriter {
+    async fn create_user(&self, user: UserInfo) -> Result<()> {
+        self.user_info.write().create_user(user);
+        Ok(())
+    }
+
+    async fn drop_user(&self, user_name: &str) -> Result<()> {
+        self.user_info.write().drop_user(user_name);
+        Ok(())
+    }
+}
+
+impl MockUserInfoWriter {
+    pub fn new(user_info: Arc<RwLock<UserInfoManager>>) -> Self {
+        user_info.write().create_user(UserInfo {
+            name: DEFAULT_SUPPER_USER.to_string(),
+            is_supper: true,
+            can_create_db: true,
+            can_login: true,
+            ..Default::default()
+        });
+        Self { user_info }
+    }
+}
+
 pub struct MockFrontendMetaClient {}
 
 #[async_trait::async_trait]
@@ -290,6 +387,14 @@ impl FrontendMetaClient for MockFrontendMetaClient {
     async fn unpin_snapshot(&self, _epoch: u64) -> Result<()> {
         Ok(())
     }
+
+    async fn set_system_param(&self, _param: String, _value: String) -> Result<()> {
+        Ok(())
+    }
+
+    async fn alter_parallelism(&self, _table_id: TableId, _parallelism: u32) -> Result<()> {
+        Ok(())
+    }
 }
 pub static PROTO_FILE_DATA: &str = r#"
     syntax = "proto3";
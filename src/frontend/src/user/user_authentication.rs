@@ -0,0 +1,93 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_auth::{is_md5_hashed, md5_hash_password, ScramVerifier, UserAuthenticator};
+use rand::Rng;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::auth_info::EncryptionType;
+use risingwave_pb::user::AuthInfo;
+
+/// Encrypts the password given in `CREATE USER`. Passwords already hashed by MD5 or given as a
+/// SCRAM-SHA-256 verifier are stored as they are, and the others are hashed by MD5. An empty
+/// password means no password.
+pub fn encrypted_password(user_name: &str, password: &str) -> Option<AuthInfo> {
+    if password.is_empty() {
+        return None;
+    }
+    let (encryption_type, encrypted_value) = if is_md5_hashed(password) {
+        (EncryptionType::Md5, password.to_string())
+    } else if ScramVerifier::parse(password).is_some() {
+        (EncryptionType::Sha256, password.to_string())
+    } else {
+        (EncryptionType::Md5, md5_hash_password(user_name, password))
+    };
+    Some(AuthInfo {
+        encryption_type: encryption_type as i32,
+        encrypted_value: encrypted_value.into_bytes(),
+    })
+}
+
+/// Decides how a user is authenticated by its stored password.
+pub fn user_authenticator(auth_info: Option<&AuthInfo>) -> Result<UserAuthenticator> {
+    let Some(auth_info) = auth_info else {
+        return Ok(UserAuthenticator::None);
+    };
+    let invalid = || ErrorCode::InternalError("invalid password stored for the user".to_string());
+    let authenticator = match auth_info.encryption_type() {
+        EncryptionType::Plaintext => {
+            UserAuthenticator::ClearText(auth_info.encrypted_value.clone())
+        }
+        EncryptionType::Md5 => UserAuthenticator::Md5WithSalt {
+            encrypted_password: auth_info.encrypted_value.clone(),
+            salt: rand::thread_rng().gen(),
+        },
+        EncryptionType::Sha256 => {
+            let verifier = std::str::from_utf8(&auth_info.encrypted_value)
+                .ok()
+                .and_then(ScramVerifier::parse)
+                .ok_or_else(invalid)?;
+            UserAuthenticator::ScramSha256(verifier)
+        }
+        EncryptionType::Unknown => return Err(invalid().into()),
+    };
+    Ok(authenticator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_password() {
+        assert!(encrypted_password("user", "").is_none());
+
+        let auth_info = encrypted_password("user", "pwd").unwrap();
+        assert_eq!(auth_info.encryption_type(), EncryptionType::Md5);
+        let md5_password = md5_hash_password("user", "pwd");
+        assert_eq!(auth_info.encrypted_value, md5_password.as_bytes());
+        // Hashed passwords are stored as they are.
+        assert_eq!(
+            encrypted_password("user", &md5_password).unwrap(),
+            auth_info
+        );
+
+        let verifier = ScramVerifier::new("pwd").to_string();
+        let auth_info = encrypted_password("user", &verifier).unwrap();
+        assert_eq!(auth_info.encryption_type(), EncryptionType::Sha256);
+        assert!(matches!(
+            user_authenticator(Some(&auth_info)).unwrap(),
+            UserAuthenticator::ScramSha256(_)
+        ));
+    }
+}
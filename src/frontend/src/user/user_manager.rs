@@ -0,0 +1,62 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use risingwave_pb::user::UserInfo;
+
+use super::UserInfoVersion;
+
+/// `UserInfoManager` holds the users synced from meta. Like
+/// [`crate::catalog::root_catalog::Catalog`],
+/// only [`crate::observer::observer_manager::ObserverManager`] writes to it.
+#[derive(Default)]
+pub struct UserInfoManager {
+    version: UserInfoVersion,
+    user_by_name: HashMap<String, UserInfo>,
+}
+
+impl UserInfoManager {
+    pub fn get_user_by_name(&self, user_name: &str) -> Option<&UserInfo> {
+        self.user_by_name.get(user_name)
+    }
+
+    pub fn create_user(&mut self, user_info: UserInfo) {
+        self.user_by_name
+            .try_insert(user_info.name.clone(), user_info)
+            .unwrap();
+    }
+
+    pub fn drop_user(&mut self, user_name: &str) {
+        self.user_by_name.remove(user_name).unwrap();
+    }
+
+    pub fn update_user(&mut self, user_info: UserInfo) {
+        self.user_by_name.insert(user_info.name.clone(), user_info);
+    }
+
+    pub fn clear(&mut self) {
+        self.user_by_name.clear();
+    }
+
+    /// Get the user info manager's version.
+    pub fn version(&self) -> UserInfoVersion {
+        self.version
+    }
+
+    /// Set the user info manager's version.
+    pub fn set_version(&mut self, version: UserInfoVersion) {
+        self.version = version;
+    }
+}
@@ -14,14 +14,17 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::ops::Bound;
 
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
+use risingwave_common::types::ScalarImpl;
 
 use crate::expr::{
     factorization_expr, fold_boolean_constant, push_down_not, to_conjunctions,
-    try_get_bool_constant, ExprImpl, ExprRewriter, ExprType, ExprVisitor, InputRef,
+    try_get_bool_constant, Expr, ExprImpl, ExprRewriter, ExprType, ExprVisitor, InputRef,
 };
+use crate::utils::ScanRange;
 
 #[derive(Debug, Clone)]
 pub struct Condition {
@@ -264,6 +267,103 @@ impl Condition {
         })
     }
 
+    /// Returns `(expr_type, value)` if the expression compares the column `col_idx` with a
+    /// non-null constant of the same type, i.e., `col <expr_type> value`.
+    fn as_comparison_with_const(expr: &ExprImpl, col_idx: usize) -> Option<(ExprType, ScalarImpl)> {
+        let function_call = match expr {
+            ExprImpl::FunctionCall(function_call) => function_call,
+            _ => return None,
+        };
+        let expr_type = function_call.get_expr_type();
+        if !matches!(
+            expr_type,
+            ExprType::Equal
+                | ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ) {
+            return None;
+        }
+        let (input_ref, literal, expr_type) = match function_call.inputs() {
+            [ExprImpl::InputRef(input_ref), ExprImpl::Literal(literal)] => {
+                (input_ref, literal, expr_type)
+            }
+            // Flip `value <op> col` into `col <op'> value`.
+            [ExprImpl::Literal(literal), ExprImpl::InputRef(input_ref)] => {
+                let expr_type = match expr_type {
+                    ExprType::LessThan => ExprType::GreaterThan,
+                    ExprType::LessThanOrEqual => ExprType::GreaterThanOrEqual,
+                    ExprType::GreaterThan => ExprType::LessThan,
+                    ExprType::GreaterThanOrEqual => ExprType::LessThanOrEqual,
+                    expr_type => expr_type,
+                };
+                (input_ref, literal, expr_type)
+            }
+            _ => return None,
+        };
+        if input_ref.index() != col_idx || input_ref.return_type() != literal.return_type() {
+            return None;
+        }
+        literal.get_data().clone().map(|value| (expr_type, value))
+    }
+
+    /// Splits the condition into the scan range of a table scan and the remaining condition.
+    /// `order_col_indices` are the columns of the storage key of the table, in the order of the
+    /// key. The scan range fixes the values of the leading key columns compared with constants by
+    /// `=`, and bounds the next one with the `<`, `<=`, `>` and `>=` comparisons on it.
+    #[must_use]
+    pub fn split_to_scan_range(self, order_col_indices: &[usize]) -> (ScanRange, Self) {
+        let mut scan_range = ScanRange::full_table_scan();
+        let mut others = self.conjunctions;
+
+        for &col_idx in order_col_indices {
+            let eq_cond = others.iter().position(|expr| {
+                matches!(
+                    Self::as_comparison_with_const(expr, col_idx),
+                    Some((ExprType::Equal, _))
+                )
+            });
+            if let Some(i) = eq_cond {
+                let (_, value) =
+                    Self::as_comparison_with_const(&others.remove(i), col_idx).unwrap();
+                scan_range.eq_conds.push(value);
+                continue;
+            }
+
+            // Intersect all ranges on the column.
+            let (mut lower_bound, mut upper_bound) = (Bound::Unbounded, Bound::Unbounded);
+            others.retain(|expr| match Self::as_comparison_with_const(expr, col_idx) {
+                Some((ExprType::GreaterThan, value)) => {
+                    lower_bound = tighter_bound(lower_bound.clone(), Bound::Excluded(value), true);
+                    false
+                }
+                Some((ExprType::GreaterThanOrEqual, value)) => {
+                    lower_bound = tighter_bound(lower_bound.clone(), Bound::Included(value), true);
+                    false
+                }
+                Some((ExprType::LessThan, value)) => {
+                    upper_bound = tighter_bound(upper_bound.clone(), Bound::Excluded(value), false);
+                    false
+                }
+                Some((ExprType::LessThanOrEqual, value)) => {
+                    upper_bound = tighter_bound(upper_bound.clone(), Bound::Included(value), false);
+                    false
+                }
+                _ => true,
+            });
+            scan_range.range = (lower_bound, upper_bound);
+            break;
+        }
+
+        (
+            scan_range,
+            Condition {
+                conjunctions: others,
+            },
+        )
+    }
+
     #[must_use]
     pub fn rewrite_expr(self, rewriter: &mut impl ExprRewriter) -> Self {
         Self {
@@ -332,6 +432,33 @@ impl Condition {
     }
 }
 
+/// Returns the tighter one of two lower bounds if `is_lower`, or of two upper bounds otherwise.
+fn tighter_bound(
+    lhs: Bound<ScalarImpl>,
+    rhs: Bound<ScalarImpl>,
+    is_lower: bool,
+) -> Bound<ScalarImpl> {
+    let value_of = |bound: &Bound<ScalarImpl>| match bound {
+        Bound::Included(value) | Bound::Excluded(value) => Some(value.clone()),
+        Bound::Unbounded => None,
+    };
+    let (lhs_value, rhs_value) = match (value_of(&lhs), value_of(&rhs)) {
+        (None, _) => return rhs,
+        (_, None) => return lhs,
+        (Some(lhs_value), Some(rhs_value)) => (lhs_value, rhs_value),
+    };
+    match lhs_value.cmp(&rhs_value) {
+        // An excluded bound is tighter than an included one of the same value.
+        std::cmp::Ordering::Equal => match lhs {
+            Bound::Excluded(_) => lhs,
+            _ => rhs,
+        },
+        std::cmp::Ordering::Greater if is_lower => lhs,
+        std::cmp::Ordering::Less if !is_lower => lhs,
+        _ => rhs,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::Rng;
@@ -396,4 +523,43 @@ mod tests {
         assert_eq!(res.1.conjunctions, vec![right]);
         assert_eq!(res.2.conjunctions, vec![other]);
     }
+
+    #[test]
+    fn test_split_to_scan_range() {
+        let ty = DataType::Int32;
+        let compare = |expr_type, lhs: ExprImpl, rhs: ExprImpl| -> ExprImpl {
+            FunctionCall::new(expr_type, vec![lhs, rhs]).unwrap().into()
+        };
+        let col = |idx| -> ExprImpl { InputRef::new(idx, ty.clone()).into() };
+
+        let not_in_range = compare(ExprType::NotEqual, col(0), ExprImpl::literal_int(4));
+        let after_range = compare(ExprType::Equal, col(2), ExprImpl::literal_int(1));
+        let cond = Condition {
+            conjunctions: vec![
+                compare(
+                    ExprType::GreaterThanOrEqual,
+                    col(0),
+                    ExprImpl::literal_int(3),
+                ),
+                not_in_range.clone(),
+                compare(ExprType::Equal, ExprImpl::literal_int(5), col(1)),
+                after_range.clone(),
+                compare(ExprType::GreaterThan, col(0), ExprImpl::literal_int(3)),
+                compare(ExprType::GreaterThan, ExprImpl::literal_int(10), col(0)),
+            ],
+        };
+
+        let (scan_range, others) = cond.split_to_scan_range(&[1, 0, 2]);
+        assert_eq!(
+            scan_range,
+            ScanRange {
+                eq_conds: vec![ScalarImpl::Int32(5)],
+                range: (
+                    Bound::Excluded(ScalarImpl::Int32(3)),
+                    Bound::Excluded(ScalarImpl::Int32(10))
+                ),
+            }
+        );
+        assert_eq!(others.conjunctions, vec![not_in_range, after_range]);
+    }
 }
@@ -0,0 +1,78 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+use itertools::Itertools;
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::value_encoding::serialize_datum;
+use risingwave_pb::batch_plan::{scan_range, ScanRange as ScanRangeProst};
+
+/// The range of pks a scan reads. The first pk columns are fixed to `eq_conds`, and the pk column
+/// next to them is bounded by `range`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanRange {
+    pub eq_conds: Vec<ScalarImpl>,
+    pub range: (Bound<ScalarImpl>, Bound<ScalarImpl>),
+}
+
+impl ScanRange {
+    pub fn full_table_scan() -> Self {
+        Self {
+            eq_conds: vec![],
+            range: (Bound::Unbounded, Bound::Unbounded),
+        }
+    }
+
+    pub fn is_full_table_scan(&self) -> bool {
+        self.eq_conds.is_empty() && self.range == (Bound::Unbounded, Bound::Unbounded)
+    }
+
+    pub fn to_protobuf(&self) -> ScanRangeProst {
+        let encode = |value: &ScalarImpl| serialize_datum(&Some(value.clone())).unwrap();
+        let encode_bound = |bound: Bound<&ScalarImpl>| match bound {
+            Bound::Included(value) => Some(scan_range::Bound {
+                value: encode(value),
+                inclusive: true,
+            }),
+            Bound::Excluded(value) => Some(scan_range::Bound {
+                value: encode(value),
+                inclusive: false,
+            }),
+            Bound::Unbounded => None,
+        };
+        ScanRangeProst {
+            eq_conds: self.eq_conds.iter().map(encode).collect(),
+            lower_bound: encode_bound(self.range.start_bound()),
+            upper_bound: encode_bound(self.range.end_bound()),
+        }
+    }
+}
+
+impl fmt::Display for ScanRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.eq_conds.iter().join(", "))?;
+        match self.range.start_bound() {
+            Bound::Included(value) => write!(f, " [{}", value)?,
+            Bound::Excluded(value) => write!(f, " ({}", value)?,
+            Bound::Unbounded => write!(f, " (-inf")?,
+        }
+        match self.range.end_bound() {
+            Bound::Included(value) => write!(f, ", {}]", value),
+            Bound::Excluded(value) => write!(f, ", {})", value),
+            Bound::Unbounded => write!(f, ", +inf)"),
+        }
+    }
+}
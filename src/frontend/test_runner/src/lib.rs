@@ -178,7 +178,7 @@ impl TestCase {
 
         if let Some(ref config_map) = self.with_config_map {
             for (key, val) in config_map {
-                session.set_config(key, val);
+                session.set_config(key, &[val.to_owned()])?;
             }
         }
 
@@ -263,8 +263,21 @@ impl TestCase {
                     }
                     result = Some(ret);
                 }
-                Statement::CreateTable { name, columns, .. } => {
-                    create_table::handle_create_table(context, name, columns).await?;
+                Statement::CreateTable {
+                    name,
+                    columns,
+                    constraints,
+                    with_options,
+                    ..
+                } => {
+                    create_table::handle_create_table(
+                        context,
+                        name,
+                        columns,
+                        constraints,
+                        with_options,
+                    )
+                    .await?;
                 }
                 Statement::CreateSource {
                     is_materialized,
@@ -291,7 +304,12 @@ impl TestCase {
                     create_mv::handle_create_mv(context, name, query).await?;
                 }
                 Statement::Drop(drop_statement) => {
-                    drop_table::handle_drop_table(context, drop_statement.object_name).await?;
+                    drop_table::handle_drop_table(
+                        context,
+                        drop_statement.object_name,
+                        drop_statement.drop_mode.into(),
+                    )
+                    .await?;
                 }
                 _ => return Err(anyhow!("Unsupported statement type")),
             }
@@ -20,7 +20,12 @@ use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_common::util::epoch::Epoch;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation;
-use risingwave_pb::data::{AddMutation, DispatcherMutation, NothingMutation, StopMutation};
+use risingwave_pb::data::{
+    AddMutation, DispatcherHashMapping, DispatcherMutation, NothingMutation, PauseMutation,
+    ResumeMutation, SourceChangeSplit, SourceChangeSplitMutation, StopMutation,
+    UpdateVnodeBitmapMutation,
+};
+use risingwave_pb::stream_plan::StreamSourceState;
 use risingwave_pb::stream_service::DropActorsRequest;
 use risingwave_rpc_client::StreamClientPoolRef;
 use uuid::Uuid;
@@ -56,11 +61,41 @@ pub enum Command {
     /// collected.
     /// After the barrier is collected, these newly created actors will be marked as `Created`. And
     /// it adds the table fragments info to meta store.
+    ///
+    /// The source jobs split off from the materialized view to be shared, if any, are created
+    /// along with it.
     CreateMaterializedView {
         table_fragments: TableFragments,
+        source_job_ids: Vec<TableId>,
         table_sink_map: HashMap<TableId, Vec<ActorId>>,
         dispatches: HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>,
     },
+
+    /// `UpdateVnodeBitmaps` command generates a `UpdateVnodeBitmap` barrier to reassign the vnodes
+    /// owned by the actors of the given materialized view, on which the stateful executors evict
+    /// the cached states of the vnodes moved out, and the hash dispatchers routing to the actors
+    /// switch to the new mappings.
+    ///
+    /// After the barrier is collected, the new vnode bitmaps and mappings are persisted in the
+    /// meta store.
+    UpdateVnodeBitmaps {
+        table_id: TableId,
+        actor_vnode_bitmaps: HashMap<ActorId, Vec<u8>>,
+        dispatcher_hash_mappings: HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+    },
+
+    /// `SourceChangeSplit` command generates a `Splits` barrier to assign the splits to the stream
+    /// source actors, on which the actors start reading the splits they don't have yet.
+    ///
+    /// After the barrier is collected, the new split assignment is persisted in the meta store.
+    SourceChangeSplit(HashMap<ActorId, StreamSourceState>),
+
+    /// `MigrateActors` command moves the actors off the cordoned compute nodes, e.g., before they
+    /// leave the cluster. Instead of sending a barrier, the barrier manager runs a recovery, which
+    /// rebuilds the actors on the idle parallel units from the latest committed epoch.
+    ///
+    /// It's notified as collected and finished once the recovery succeeds.
+    MigrateActors,
 }
 
 impl Command {
@@ -68,12 +103,30 @@ impl Command {
         Self::Plain(Mutation::Nothing(NothingMutation {}))
     }
 
-    pub fn creating_table_id(&self) -> Option<TableId> {
+    pub fn pause() -> Self {
+        Self::Plain(Mutation::Pause(PauseMutation {}))
+    }
+
+    pub fn resume() -> Self {
+        Self::Plain(Mutation::Resume(ResumeMutation {}))
+    }
+
+    /// Whether the barrier of this command must be a checkpoint. Only the periodic barriers
+    /// without any mutation may skip persisting the states.
+    pub fn need_checkpoint(&self) -> bool {
+        !matches!(self, Command::Plain(Mutation::Nothing(_)))
+    }
+
+    pub fn creating_table_ids(&self) -> Vec<TableId> {
         match self {
             Command::CreateMaterializedView {
-                table_fragments, ..
-            } => Some(table_fragments.table_id()),
-            _ => None,
+                table_fragments,
+                source_job_ids,
+                ..
+            } => std::iter::once(table_fragments.table_id())
+                .chain(source_job_ids.iter().copied())
+                .collect(),
+            _ => vec![],
         }
     }
 }
@@ -92,6 +145,9 @@ pub struct CommandContext<'a, S> {
     pub prev_epoch: &'a Epoch,
     pub curr_epoch: &'a Epoch,
 
+    /// Whether the states are persisted and committed at this barrier.
+    pub checkpoint: bool,
+
     command: Command,
 }
 
@@ -102,6 +158,7 @@ impl<'a, S> CommandContext<'a, S> {
         info: &'a BarrierActorInfo,
         prev_epoch: &'a Epoch,
         curr_epoch: &'a Epoch,
+        checkpoint: bool,
         command: Command,
     ) -> Self {
         Self {
@@ -110,6 +167,7 @@ impl<'a, S> CommandContext<'a, S> {
             info,
             prev_epoch,
             curr_epoch,
+            checkpoint,
             command,
         }
     }
@@ -124,6 +182,8 @@ where
         let mutation = match &self.command {
             Command::Plain(mutation) => mutation.clone(),
 
+            Command::MigrateActors => Mutation::Nothing(NothingMutation {}),
+
             Command::DropMaterializedView(table_id) => {
                 let actors = self.fragment_manager.get_table_actor_ids(table_id).await?;
                 Mutation::Stop(StopMutation { actors })
@@ -142,6 +202,37 @@ where
                     .collect();
                 Mutation::Add(AddMutation { mutations })
             }
+
+            Command::UpdateVnodeBitmaps {
+                actor_vnode_bitmaps,
+                dispatcher_hash_mappings,
+                ..
+            } => Mutation::UpdateVnodeBitmap(UpdateVnodeBitmapMutation {
+                actor_vnode_bitmaps: actor_vnode_bitmaps.clone(),
+                dispatcher_hash_mappings: dispatcher_hash_mappings
+                    .iter()
+                    .map(
+                        |(&(actor_id, dispatcher_id), hash_mapping)| DispatcherHashMapping {
+                            actor_id,
+                            dispatcher_id,
+                            hash_mapping: hash_mapping.clone(),
+                        },
+                    )
+                    .collect(),
+            }),
+
+            Command::SourceChangeSplit(actor_splits) => {
+                Mutation::Splits(SourceChangeSplitMutation {
+                    mutations: actor_splits
+                        .iter()
+                        .map(|(&actor_id, splits)| SourceChangeSplit {
+                            actor_id,
+                            split_type: splits.split_type.clone(),
+                            source_splits: splits.stream_source_splits.clone(),
+                        })
+                        .collect(),
+                })
+            }
         };
 
         Ok(mutation)
@@ -160,10 +251,25 @@ where
         }
     }
 
+    /// For `DropMaterializedView`, returns the actors to be stopped. For other commands, returns an
+    /// empty set.
+    pub async fn actors_to_stop(&self) -> Result<HashSet<ActorId>> {
+        match &self.command {
+            Command::DropMaterializedView(table_id) => Ok(self
+                .fragment_manager
+                .get_table_actor_ids(table_id)
+                .await?
+                .into_iter()
+                .collect()),
+
+            _ => Ok(Default::default()),
+        }
+    }
+
     /// Do some stuffs after barriers are collected, for the given command.
     pub async fn post_collect(&self) -> Result<()> {
         match &self.command {
-            Command::Plain(_) => {}
+            Command::Plain(_) | Command::MigrateActors => {}
 
             Command::DropMaterializedView(table_id) => {
                 // Tell compute nodes to drop actors.
@@ -177,6 +283,7 @@ where
                         let request = DropActorsRequest {
                             request_id,
                             actor_ids: actors.to_owned(),
+                            epoch: self.curr_epoch.0,
                         };
                         client.drop_actors(request).await.to_rw_result()?;
 
@@ -192,9 +299,16 @@ where
 
             Command::CreateMaterializedView {
                 table_fragments,
+                source_job_ids,
                 dispatches,
                 table_sink_map,
             } => {
+                for source_job_id in source_job_ids {
+                    self.fragment_manager
+                        .finish_create_table_fragments(source_job_id, &[])
+                        .await?;
+                }
+
                 let mut dependent_table_actors = Vec::with_capacity(table_sink_map.len());
                 for (table_id, actors) in table_sink_map {
                     let downstream_actors = dispatches
@@ -219,6 +333,26 @@ where
                     )
                     .await?;
             }
+
+            Command::UpdateVnodeBitmaps {
+                table_id,
+                actor_vnode_bitmaps,
+                dispatcher_hash_mappings,
+            } => {
+                self.fragment_manager
+                    .update_actor_vnode_bitmaps(
+                        table_id,
+                        actor_vnode_bitmaps,
+                        dispatcher_hash_mappings,
+                    )
+                    .await?;
+            }
+
+            Command::SourceChangeSplit(actor_splits) => {
+                self.fragment_manager
+                    .update_actor_splits(actor_splits)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -22,21 +22,25 @@ use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_common::util::epoch::INVALID_EPOCH;
+use risingwave_common::util::tracing_context::TracingContext;
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::data::Barrier;
 use risingwave_pb::stream_service::{InjectBarrierRequest, InjectBarrierResponse};
+use serde::Serialize;
 use smallvec::SmallVec;
 use tokio::sync::oneshot::{Receiver, Sender};
 use tokio::sync::{oneshot, watch, RwLock};
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 use uuid::Uuid;
 
 pub use self::command::Command;
 use self::command::CommandContext;
 use self::info::BarrierActorInfo;
 use self::notifier::Notifier;
+pub use self::progress::CreateMviewProgressInfo;
 use self::progress::CreateMviewProgressTracker;
 use crate::cluster::{ClusterManagerRef, META_NODE_ID};
 use crate::hummock::HummockManagerRef;
@@ -130,6 +134,17 @@ impl ScheduledBarriers {
     }
 }
 
+/// A snapshot of the status of [`crate::barrier::GlobalBarrierManager`], exposed for inspection.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BarrierManagerStatus {
+    /// The epoch of the last collected barrier.
+    pub prev_epoch: u64,
+    /// The number of scheduled barriers waiting to be sent.
+    pub scheduled_barriers: usize,
+    /// The progress of the mviews being created.
+    pub create_mview_progress: Vec<CreateMviewProgressInfo>,
+}
+
 /// [`crate::barrier::GlobalBarrierManager`] sends barriers to all registered compute nodes and
 /// collect them, with monotonic increasing epoch numbers. On compute nodes, `LocalBarrierManager`
 /// in `risingwave_stream` crate will serve these requests and dispatch them to source actors.
@@ -140,9 +155,6 @@ impl ScheduledBarriers {
 /// barrier manager and meta store, some actions like "drop materialized view" or "create mv on mv"
 /// must be done in barrier manager transactional using [`Command`].
 pub struct GlobalBarrierManager<S: MetaStore> {
-    /// The maximal interval for sending a barrier.
-    interval: Duration,
-
     /// Enable recovery or not when failover.
     enable_recovery: bool,
 
@@ -159,6 +171,9 @@ pub struct GlobalBarrierManager<S: MetaStore> {
 
     metrics: Arc<MetaMetrics>,
 
+    /// Updated after each barrier is collected.
+    status: parking_lot::Mutex<BarrierManagerStatus>,
+
     env: MetaSrvEnv<S>,
 }
 
@@ -176,15 +191,12 @@ where
         metrics: Arc<MetaMetrics>,
     ) -> Self {
         let enable_recovery = env.opts.enable_recovery;
-        let interval = env.opts.checkpoint_interval;
         tracing::info!(
-            "Starting barrier manager with: interval={:?}, enable_recovery={}",
-            interval,
+            "Starting barrier manager with: enable_recovery={}",
             enable_recovery
         );
 
         Self {
-            interval,
             enable_recovery,
             cluster_manager,
             catalog_manager,
@@ -192,6 +204,7 @@ where
             scheduled_barriers: ScheduledBarriers::new(),
             hummock_manager,
             metrics,
+            status: Default::default(),
             env,
         }
     }
@@ -225,10 +238,16 @@ where
             }
             state.prev_epoch = new_epoch;
             state.update(self.env.meta_store()).await.unwrap();
+            self.update_status(&state, &tracker);
         }
 
-        let mut min_interval = tokio::time::interval(self.interval);
+        // The maximal interval for sending a barrier, which follows the system param.
+        let mut params_rx = self.env.system_param_manager().watch_params();
+        let mut interval = Duration::from_millis(params_rx.borrow().barrier_interval_ms as u64);
+        tracing::info!("Barrier interval: {:?}", interval);
+        let mut min_interval = tokio::time::interval(interval);
         min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut barriers_since_checkpoint = 0;
         loop {
             tokio::select! {
                 biased;
@@ -241,10 +260,42 @@ where
                 _ = self.scheduled_barriers.wait_one() => {}
                 // Wait for the minimal interval,
                 _ = min_interval.tick() => {},
+                // The barrier interval may be changed.
+                Ok(()) = params_rx.changed() => {
+                    let new_interval =
+                        Duration::from_millis(params_rx.borrow().barrier_interval_ms as u64);
+                    if new_interval != interval {
+                        tracing::info!("Barrier interval is changed to {:?}", new_interval);
+                        interval = new_interval;
+                        min_interval = tokio::time::interval(interval);
+                        min_interval
+                            .set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    }
+                    continue;
+                }
             }
             // Get a barrier to send.
             let (command, notifiers) = self.scheduled_barriers.pop_or_default().await;
-            let info = self.resolve_actor_info(command.creating_table_id()).await;
+            if let Command::MigrateActors = command {
+                let mut notifiers = notifiers;
+                notifiers.iter_mut().for_each(Notifier::notify_to_send);
+                let (new_epoch, actors_to_track, create_mview_progress) =
+                    self.recovery(state.prev_epoch.next()).await;
+                tracker = CreateMviewProgressTracker::default(); // Reset progress tracker
+                barriers_since_checkpoint = 0;
+                tracker.add(new_epoch, actors_to_track, vec![]);
+                for progress in create_mview_progress {
+                    tracker.update(progress);
+                }
+                state.prev_epoch = new_epoch;
+                notifiers.iter_mut().for_each(Notifier::notify_collected);
+                notifiers.into_iter().for_each(Notifier::notify_finished);
+
+                state.update(self.env.meta_store()).await.unwrap();
+                self.update_status(&state, &tracker);
+                continue;
+            }
+            let info = self.resolve_actor_info(&command.creating_table_ids()).await;
             // When there's no actors exist in the cluster, we don't need to send the barrier. This
             // is an advance optimization. Besides if another barrier comes immediately,
             // it may send a same epoch and fail the epoch check.
@@ -256,18 +307,31 @@ where
             }
             let new_epoch = state.prev_epoch.next();
             assert!(new_epoch > state.prev_epoch);
+            // Every `checkpoint_frequency` barriers is a checkpoint, which follows the system
+            // param. Those waiting for the barrier also expect its effects to be committed once
+            // collected.
+            barriers_since_checkpoint += 1;
+            let checkpoint = command.need_checkpoint()
+                || !notifiers.is_empty()
+                || barriers_since_checkpoint >= params_rx.borrow().checkpoint_frequency;
             let command_ctx = CommandContext::new(
                 self.fragment_manager.clone(),
                 self.env.stream_client_pool_ref(),
                 &info,
                 &state.prev_epoch,
                 &new_epoch,
+                checkpoint,
                 command,
             );
 
+            // The chains stopped by this barrier will never report their progress. The table may
+            // not exist, in which case there's nothing to stop.
+            let actors_to_stop = command_ctx.actors_to_stop().await.unwrap_or_default();
+
             let mut notifiers = notifiers;
             notifiers.iter_mut().for_each(Notifier::notify_to_send);
-            match self.run_inner(&command_ctx).await {
+            let span = tracing::trace_span!("barrier", epoch = new_epoch.0, checkpoint);
+            match self.run_inner(&command_ctx).instrument(span).await {
                 Ok(responses) => {
                     // Notify about collected first.
                     notifiers.iter_mut().for_each(Notifier::notify_collected);
@@ -278,8 +342,12 @@ where
                     for progress in responses.into_iter().flat_map(|r| r.create_mview_progress) {
                         tracker.update(progress);
                     }
+                    tracker.cancel(&actors_to_stop);
 
                     state.prev_epoch = new_epoch;
+                    if checkpoint {
+                        barriers_since_checkpoint = 0;
+                    }
                 }
                 Err(e) => {
                     notifiers
@@ -290,6 +358,7 @@ where
                         let (new_epoch, actors_to_track, create_mview_progress) =
                             self.recovery(new_epoch).await;
                         tracker = CreateMviewProgressTracker::default(); // Reset progress tracker
+                        barriers_since_checkpoint = 0;
                         tracker.add(new_epoch, actors_to_track, vec![]);
                         for progress in create_mview_progress {
                             tracker.update(progress);
@@ -303,9 +372,23 @@ where
             }
 
             state.update(self.env.meta_store()).await.unwrap();
+            self.update_status(&state, &tracker);
         }
     }
 
+    fn update_status(&self, state: &BarrierManagerState, tracker: &CreateMviewProgressTracker) {
+        let mut status = self.status.lock();
+        status.prev_epoch = state.prev_epoch.0;
+        status.create_mview_progress = tracker.progress_infos();
+    }
+
+    /// Returns the status of the barrier manager.
+    pub async fn status(&self) -> BarrierManagerStatus {
+        let mut status = self.status.lock().clone();
+        status.scheduled_barriers = self.scheduled_barriers.buffer.read().await.len();
+        status
+    }
+
     /// Running a scheduled command.
     async fn run_inner<'a>(
         &self,
@@ -315,8 +398,8 @@ where
 
         // Wait for all barriers collected
         let result = self.inject_barrier(command_context).await;
-        // Commit this epoch to Hummock
-        if command_context.prev_epoch.0 != INVALID_EPOCH {
+        // Commit this epoch to Hummock, together with the epochs since the last checkpoint.
+        if command_context.checkpoint && command_context.prev_epoch.0 != INVALID_EPOCH {
             match &result {
                 Ok(resps) => {
                     // We must ensure all epochs are committed in ascending order, because
@@ -354,6 +437,7 @@ where
     ) -> Result<Vec<InjectBarrierResponse>> {
         let mutation = command_context.to_mutation().await?;
         let info = command_context.info;
+        let tracing_context = TracingContext::from_current_span().to_protobuf();
 
         let collect_futures = info.node_map.iter().filter_map(|(node_id, node)| {
             let actor_ids_to_send = info.actor_ids_to_send(node_id).collect_vec();
@@ -366,14 +450,15 @@ where
             } else {
                 let mutation = mutation.clone();
                 let request_id = Uuid::new_v4().to_string();
+                let tracing_context = tracing_context.clone();
                 let barrier = Barrier {
                     epoch: Some(risingwave_pb::data::Epoch {
                         curr: command_context.curr_epoch.0,
                         prev: command_context.prev_epoch.0,
                     }),
                     mutation: Some(mutation),
-                    // TODO(chi): add distributed tracing
                     span: vec![],
+                    checkpoint: command_context.checkpoint,
                 };
 
                 async move {
@@ -384,6 +469,7 @@ where
                         barrier: Some(barrier),
                         actor_ids_to_send,
                         actor_ids_to_collect,
+                        tracing_context,
                     };
                     tracing::trace!(
                         target: "events::meta::barrier::inject_barrier",
@@ -405,14 +491,14 @@ where
     }
 
     /// Resolve actor information from cluster and fragment manager.
-    async fn resolve_actor_info(&self, creating_table_id: Option<TableId>) -> BarrierActorInfo {
+    async fn resolve_actor_info(&self, creating_table_ids: &[TableId]) -> BarrierActorInfo {
         let all_nodes = self
             .cluster_manager
             .list_worker_node(WorkerType::ComputeNode, Some(Running))
             .await;
         let all_actor_infos = self
             .fragment_manager
-            .load_all_actors(creating_table_id)
+            .load_all_actors(creating_table_ids)
             .await;
         BarrierActorInfo::resolve(all_nodes, all_actor_infos)
     }
@@ -474,15 +560,14 @@ where
                 .hummock_manager
                 .pin_snapshot(META_NODE_ID, HummockEpoch::MAX)
                 .await?;
-            finish_rx.await.unwrap(); // Wait for this command to be finished.
+            let finished = finish_rx.await.unwrap(); // Wait for this command to be finished.
             self.hummock_manager
                 .unpin_snapshot(META_NODE_ID, [snapshot])
                 .await?;
+            finished
         } else {
-            finish_rx.await.unwrap(); // Wait for this command to be finished.
+            finish_rx.await.unwrap() // Wait for this command to be finished.
         }
-
-        Ok(())
     }
 
     /// Wait for the next barrier to collect. Note that the barrier flowing in our stream graph is
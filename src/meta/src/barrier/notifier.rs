@@ -24,8 +24,8 @@ pub(super) struct Notifier {
     /// Get notified when scheduled barrier is collected or failed.
     pub collected: Option<oneshot::Sender<Result<()>>>,
 
-    /// Get notified when scheduled barrier is finished.
-    pub finished: Option<oneshot::Sender<()>>,
+    /// Get notified when scheduled barrier is finished or failed.
+    pub finished: Option<oneshot::Sender<Result<()>>>,
 }
 
 impl Notifier {
@@ -57,7 +57,15 @@ impl Notifier {
     /// However for creating MV, this is only called when all `Chain` report it finished.
     pub fn notify_finished(self) {
         if let Some(tx) = self.finished {
-            tx.send(()).ok();
+            tx.send(Ok(())).ok();
+        }
+    }
+
+    /// Notify when we failed to finish a barrier, e.g., the mview being created is dropped before
+    /// all `Chain` report finished. This function consumes `self`.
+    pub fn notify_finish_failed(self, err: RwError) {
+        if let Some(tx) = self.finished {
+            tx.send(Err(err)).ok();
         }
     }
 }
@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, RwError};
 use risingwave_common::util::epoch::Epoch;
 use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress;
+use serde::Serialize;
 
 use super::notifier::Notifier;
 use crate::model::ActorId;
@@ -66,6 +68,13 @@ impl Progress {
         }
     }
 
+    /// Stop tracking `actor`, which is dropped.
+    fn remove(&mut self, actor: ActorId) {
+        if let Some(ChainState::Done) = self.states.remove(&actor) {
+            self.done_count -= 1;
+        }
+    }
+
     /// Returns whether all chains are done.
     fn is_done(&self) -> bool {
         self.done_count == self.states.len()
@@ -78,6 +87,17 @@ impl Progress {
     }
 }
 
+/// The progress of a creating mview, exposed for inspection.
+#[derive(Clone, Debug, Serialize)]
+pub struct CreateMviewProgressInfo {
+    /// The epoch of the barrier creating the mview.
+    pub ddl_epoch: u64,
+    /// The number of actors containing the chain nodes.
+    pub chain_actors: usize,
+    /// The number of actors whose chain nodes have finished backfilling.
+    pub done_chain_actors: usize,
+}
+
 /// Track the progress of all creating mviews. When creation is done, `notify_finished` will be
 /// called on registered notifiers.
 #[derive(Default)]
@@ -117,6 +137,19 @@ impl CreateMviewProgressTracker {
         assert!(old.is_none());
     }
 
+    /// Returns the progress of all creating mviews in the order of their DDL epochs.
+    pub fn progress_infos(&self) -> Vec<CreateMviewProgressInfo> {
+        self.progress_map
+            .iter()
+            .map(|(epoch, (progress, _))| CreateMviewProgressInfo {
+                ddl_epoch: epoch.0,
+                chain_actors: progress.states.len(),
+                done_chain_actors: progress.done_count,
+            })
+            .sorted_by_key(|info| info.ddl_epoch)
+            .collect()
+    }
+
     /// Update the progress of `actor` according to the Prost struct. If all actors in this MV have
     /// finished, `notify_finished` will be called on registered notifiers.
     pub fn update(&mut self, progress: CreateMviewProgress) {
@@ -151,4 +184,38 @@ impl CreateMviewProgressTracker {
             Entry::Vacant(_) => unreachable!(),
         }
     }
+
+    /// Stop tracking the progress of the chains in `actors`, which are dropped. If all chains of a
+    /// create-mview DDL are dropped, i.e., the mview is dropped before being created,
+    /// `notify_finish_failed` will be called on its registered notifiers.
+    pub fn cancel(&mut self, actors: &HashSet<ActorId>) {
+        let epochs = actors
+            .iter()
+            .filter_map(|actor| self.actor_map.remove(actor))
+            .collect::<HashSet<_>>();
+
+        for epoch in epochs {
+            let Entry::Occupied(mut o) = self.progress_map.entry(epoch) else {
+                unreachable!()
+            };
+            let progress = &mut o.get_mut().0;
+            actors.iter().for_each(|&actor| progress.remove(actor));
+
+            if progress.states.is_empty() {
+                tracing::debug!("creating mview with epoch {} is cancelled", epoch);
+                let notifiers = o.remove().1;
+                notifiers.into_iter().for_each(|notifier| {
+                    notifier.notify_finish_failed(RwError::from(ErrorCode::InternalError(
+                        "the materialized view is dropped before being created".to_string(),
+                    )))
+                });
+            } else if progress.is_done() {
+                for actor in o.get().0.actors() {
+                    self.actor_map.remove(&actor);
+                }
+                let notifiers = o.remove().1;
+                notifiers.into_iter().for_each(Notifier::notify_finished);
+            }
+        }
+    }
 }
@@ -12,15 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::Map;
 use std::time::Duration;
 
 use futures::future::try_join_all;
+use itertools::Itertools;
 use log::{debug, error};
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_common::util::epoch::Epoch;
-use risingwave_pb::common::ActorInfo;
+use risingwave_pb::common::worker_node::State::Running;
+use risingwave_pb::common::{ActorInfo, ParallelUnit, WorkerType};
 use risingwave_pb::data::Epoch as ProstEpoch;
 use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress;
 use risingwave_pb::stream_service::{
@@ -33,6 +35,7 @@ use uuid::Uuid;
 use crate::barrier::command::CommandContext;
 use crate::barrier::info::BarrierActorInfo;
 use crate::barrier::{Command, GlobalBarrierManager};
+use crate::cluster::ParallelUnitId;
 use crate::model::ActorId;
 use crate::storage::MetaStore;
 
@@ -63,7 +66,13 @@ where
         debug!("recovery start!");
         let retry_strategy = Self::get_retry_strategy();
         let (new_epoch, responses) = tokio_retry::Retry::spawn(retry_strategy, || async {
-            let info = self.resolve_actor_info(None).await;
+            // Move the actors on the lost compute nodes to the surviving ones.
+            if let Err(err) = self.migrate_actors().await {
+                error!("migrate_actors failed: {}", err);
+                return Err(err);
+            }
+
+            let info = self.resolve_actor_info(&[]).await;
             let mut new_epoch = prev_epoch.next();
 
             // Reset all compute nodes, stop and drop existing actors.
@@ -95,6 +104,7 @@ where
                 &info,
                 &prev_epoch,
                 &new_epoch,
+                true,
                 Command::checkpoint(),
             );
 
@@ -126,6 +136,81 @@ where
         );
     }
 
+    /// Move the actors off the cordoned compute nodes, so that they can leave the cluster without
+    /// failing the next barrier. It's a planned recovery on which the actors are rebuilt on idle
+    /// parallel units, and fails without touching any actor if there aren't enough of them.
+    pub async fn migrate_cordoned_actors(&self) -> Result<()> {
+        if !self.enable_recovery {
+            return Err(RwError::from(ErrorCode::InternalError(
+                "recovery is disabled, can't migrate the actors".to_string(),
+            )));
+        }
+        if self.migration_plan().await?.is_empty() {
+            return Ok(());
+        }
+        self.run_command(Command::MigrateActors).await
+    }
+
+    /// Migrate the actors on the compute nodes which have been removed from the cluster, e.g., for
+    /// missing heartbeats, to the surviving ones.
+    async fn migrate_actors(&self) -> Result<()> {
+        let migrate_map = self.migration_plan().await?;
+        if migrate_map.is_empty() {
+            return Ok(());
+        }
+        self.fragment_manager.migrate_actors(&migrate_map).await
+    }
+
+    /// Map each parallel unit that has actors but is no longer schedulable to a distinct alive
+    /// parallel unit of the same type that has no actors yet. All actors on a lost parallel unit go
+    /// to the same parallel unit, so the actors required to be on the same node as their upstreams
+    /// still are, and no two lost parallel units are merged into one. Fails if there aren't enough
+    /// idle parallel units to take them.
+    async fn migration_plan(&self) -> Result<HashMap<ParallelUnitId, ParallelUnit>> {
+        let alive_parallel_units = self
+            .cluster_manager
+            .list_worker_node(WorkerType::ComputeNode, Some(Running))
+            .await
+            .into_iter()
+            .filter(|worker| !worker.unschedulable)
+            .flat_map(|worker| worker.parallel_units)
+            .collect_vec();
+        let alive_ids: HashSet<_> = alive_parallel_units.iter().map(|unit| unit.id).collect();
+        let used_parallel_units = self.fragment_manager.all_actor_parallel_units().await;
+        let expired_parallel_units = used_parallel_units
+            .values()
+            .filter(|unit| !alive_ids.contains(&unit.id))
+            .sorted_by_key(|unit| unit.id)
+            .collect_vec();
+
+        let mut idle_parallel_units = alive_parallel_units
+            .into_iter()
+            .filter(|unit| !used_parallel_units.contains_key(&unit.id))
+            .sorted_by_key(|unit| unit.id)
+            .collect_vec();
+        let mut migrate_map = HashMap::new();
+        for expired in expired_parallel_units {
+            let position = idle_parallel_units
+                .iter()
+                .position(|unit| unit.r#type == expired.r#type)
+                .ok_or_else(|| {
+                    RwError::from(ErrorCode::InternalError(format!(
+                        "no idle parallel unit to migrate the actors on parallel unit {} to, wait \
+                         for online.",
+                        expired.id
+                    )))
+                })?;
+            let target = idle_parallel_units.remove(position);
+            debug!(
+                "migrate actors from parallel unit {} on worker {} to {} on worker {}",
+                expired.id, expired.worker_node_id, target.id, target.worker_node_id
+            );
+            migrate_map.insert(expired.id, target);
+        }
+
+        Ok(migrate_map)
+    }
+
     /// Sync all sources in compute nodes, the local source manager in compute nodes may be dirty
     /// already.
     async fn sync_sources(&self, info: &BarrierActorInfo) -> Result<()> {
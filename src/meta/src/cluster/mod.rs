@@ -14,7 +14,7 @@
 
 use std::cmp;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Add;
 use std::sync::Arc;
@@ -99,6 +99,7 @@ where
         &self,
         host_address: HostAddress,
         r#type: WorkerType,
+        worker_node_parallelism: usize,
     ) -> Result<(WorkerNode, bool)> {
         let mut core = self.core.write().await;
         match core.get_worker_by_host(host_address.clone()) {
@@ -113,7 +114,19 @@ where
 
                 // Generate parallel units.
                 let parallel_units = if r#type == WorkerType::ComputeNode {
-                    self.generate_cn_parallel_units(DEFAULT_WORK_NODE_PARALLEL_DEGREE, worker_id)
+                    let parallel_degree = match worker_node_parallelism {
+                        0 => DEFAULT_WORK_NODE_PARALLEL_DEGREE,
+                        // At least one single parallel unit and one hash parallel unit.
+                        n if n < 2 => {
+                            return Err(ErrorCode::InvalidParameterValue(format!(
+                                "worker node parallelism {} is less than 2",
+                                n
+                            ))
+                            .into())
+                        }
+                        n => n,
+                    };
+                    self.generate_cn_parallel_units(parallel_degree, worker_id)
                         .await?
                 } else {
                     vec![]
@@ -126,6 +139,7 @@ where
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    unschedulable: false,
                 };
 
                 let worker = Worker::from_protobuf(worker_node.clone());
@@ -192,6 +206,26 @@ where
         Ok(())
     }
 
+    /// Marks the worker as (un)schedulable. Actors already running on a cordoned worker are kept,
+    /// but no new actors will be scheduled onto it.
+    pub async fn set_worker_unschedulable(
+        &self,
+        host_address: HostAddress,
+        unschedulable: bool,
+    ) -> Result<()> {
+        let mut core = self.core.write().await;
+        let mut worker = core.get_worker_by_host_checked(host_address)?;
+        if worker.worker_node.unschedulable == unschedulable {
+            return Ok(());
+        }
+        worker.worker_node.unschedulable = unschedulable;
+        worker.insert(self.env.meta_store()).await?;
+
+        core.update_worker_node(worker);
+
+        Ok(())
+    }
+
     /// Invoked when it receives a heartbeat from a worker node.
     pub async fn heartbeat(&self, worker_id: WorkerId) -> Result<()> {
         tracing::trace!(target: "events::meta::server_heartbeat", worker_id = worker_id, "receive heartbeat");
@@ -302,6 +336,16 @@ where
         core.list_parallel_units(parallel_unit_type)
     }
 
+    /// Same as [`ClusterManager::list_parallel_units`], but excludes parallel units on cordoned
+    /// workers. Used when scheduling new actors.
+    pub async fn list_schedulable_parallel_units(
+        &self,
+        parallel_unit_type: Option<ParallelUnitType>,
+    ) -> Vec<ParallelUnit> {
+        let core = self.core.read().await;
+        core.list_schedulable_parallel_units(parallel_unit_type)
+    }
+
     pub async fn get_parallel_unit_count(
         &self,
         parallel_unit_type: Option<ParallelUnitType>,
@@ -341,6 +385,10 @@ where
     pub async fn get_worker_by_id(&self, worker_id: WorkerId) -> Option<Worker> {
         self.core.read().await.get_worker_by_id(worker_id)
     }
+
+    pub async fn get_worker_by_host(&self, host_address: HostAddress) -> Option<Worker> {
+        self.core.read().await.get_worker_by_host(host_address)
+    }
 }
 
 pub struct ClusterManagerCore {
@@ -469,6 +517,22 @@ impl ClusterManagerCore {
         }
     }
 
+    fn list_schedulable_parallel_units(
+        &self,
+        parallel_unit_type: Option<ParallelUnitType>,
+    ) -> Vec<ParallelUnit> {
+        let unschedulable_workers: HashSet<WorkerId> = self
+            .workers
+            .values()
+            .filter(|worker| worker.worker_node.unschedulable)
+            .map(|worker| worker.worker_id())
+            .collect();
+        self.list_parallel_units(parallel_unit_type)
+            .into_iter()
+            .filter(|parallel_unit| !unschedulable_workers.contains(&parallel_unit.worker_node_id))
+            .collect()
+    }
+
     fn get_parallel_unit_count(&self, parallel_unit_type: Option<ParallelUnitType>) -> usize {
         match parallel_unit_type {
             Some(ParallelUnitType::Single) => self.single_parallel_units.len(),
@@ -519,7 +583,7 @@ mod tests {
                 port: 5000 + i as i32,
             };
             let (worker_node, _) = cluster_manager
-                .add_worker_node(fake_host_address, WorkerType::ComputeNode)
+                .add_worker_node(fake_host_address, WorkerType::ComputeNode, 0)
                 .await
                 .unwrap();
             worker_nodes.push(worker_node);
@@ -545,6 +609,90 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_worker_node_parallelism() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager = ClusterManager::new(env, Duration::new(0, 0)).await?;
+
+        for (i, parallelism) in [0, 8].into_iter().enumerate() {
+            let fake_host_address = HostAddress {
+                host: "localhost".to_string(),
+                port: 5000 + i as i32,
+            };
+            cluster_manager
+                .add_worker_node(fake_host_address, WorkerType::ComputeNode, parallelism)
+                .await?;
+        }
+        assert_cluster_manager(
+            &cluster_manager,
+            2,
+            DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1 + 7,
+        )
+        .await;
+
+        // A compute node must own at least one hash parallel unit.
+        let fake_host_address = HostAddress {
+            host: "localhost".to_string(),
+            port: 5002,
+        };
+        assert!(cluster_manager
+            .add_worker_node(fake_host_address, WorkerType::ComputeNode, 1)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cordon_worker_node() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager = ClusterManager::new(env, Duration::new(0, 0)).await?;
+
+        let mut hosts = Vec::new();
+        for i in 0..2 {
+            let fake_host_address = HostAddress {
+                host: "localhost".to_string(),
+                port: 5000 + i,
+            };
+            cluster_manager
+                .add_worker_node(fake_host_address.clone(), WorkerType::ComputeNode, 0)
+                .await?;
+            hosts.push(fake_host_address);
+        }
+
+        cluster_manager
+            .set_worker_unschedulable(hosts[0].clone(), true)
+            .await?;
+        let hash_parallel_units = cluster_manager
+            .list_schedulable_parallel_units(Some(ParallelUnitType::Hash))
+            .await;
+        assert_eq!(
+            hash_parallel_units.len(),
+            DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1
+        );
+        // Cordoned workers still own their parallel units.
+        assert_eq!(
+            cluster_manager
+                .list_parallel_units(Some(ParallelUnitType::Hash))
+                .await
+                .len(),
+            (DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1) * 2
+        );
+
+        cluster_manager
+            .set_worker_unschedulable(hosts[0].clone(), false)
+            .await?;
+        let hash_parallel_units = cluster_manager
+            .list_schedulable_parallel_units(Some(ParallelUnitType::Hash))
+            .await;
+        assert_eq!(
+            hash_parallel_units.len(),
+            (DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1) * 2
+        );
+
+        Ok(())
+    }
+
     async fn assert_cluster_manager(
         cluster_manager: &ClusterManager<MemStore>,
         single_parallel_count: usize,
@@ -571,7 +719,7 @@ mod tests {
             port: 2,
         };
         let (_worker_node_2, _) = cluster_manager
-            .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+            .add_worker_node(fake_host_address_2, WorkerType::ComputeNode, 0)
             .await
             .unwrap();
         // Two live nodes
@@ -27,7 +27,10 @@ use tower_http::add_extension::AddExtensionLayer;
 use tower_http::cors::{self, CorsLayer};
 use tower_http::services::ServeDir;
 
+use crate::barrier::BarrierManagerRef;
 use crate::cluster::ClusterManagerRef;
+use crate::hummock::HummockManagerRef;
+use crate::manager::DdlJobManagerRef;
 use crate::storage::MetaStore;
 use crate::stream::FragmentManagerRef;
 
@@ -36,6 +39,9 @@ pub struct DashboardService<S: MetaStore> {
     pub dashboard_addr: SocketAddr,
     pub cluster_manager: ClusterManagerRef<S>,
     pub fragment_manager: FragmentManagerRef<S>,
+    pub barrier_manager: BarrierManagerRef<S>,
+    pub hummock_manager: HummockManagerRef<S>,
+    pub ddl_job_manager: DdlJobManagerRef,
 
     // TODO: replace with catalog manager.
     pub meta_store: Arc<S>,
@@ -47,11 +53,14 @@ mod handlers {
     use axum::Json;
     use risingwave_pb::catalog::Table;
     use risingwave_pb::common::WorkerNode;
+    use risingwave_pb::ddl_service::DdlJob;
     use risingwave_pb::meta::ActorLocation;
     use risingwave_pb::stream_plan::StreamActor;
+    use serde::Serialize;
     use serde_json::json;
 
     use super::*;
+    use crate::barrier::BarrierManagerStatus;
 
     pub struct DashboardError(anyhow::Error);
     pub type Result<T> = std::result::Result<T, DashboardError>;
@@ -135,6 +144,55 @@ mod handlers {
 
         Ok(Json(table_fragments))
     }
+
+    pub async fn get_barrier_status<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<BarrierManagerStatus>> {
+        Ok(Json(srv.barrier_manager.status().await))
+    }
+
+    pub async fn list_ddl_jobs<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<Vec<DdlJob>>> {
+        Ok(Json(srv.ddl_job_manager.list_jobs()))
+    }
+
+    #[derive(Serialize)]
+    pub struct LevelStats {
+        level_idx: u32,
+        sst_count: usize,
+        total_file_size: u64,
+    }
+
+    #[derive(Serialize)]
+    pub struct HummockVersionStats {
+        id: u64,
+        max_committed_epoch: u64,
+        safe_epoch: u64,
+        levels: Vec<LevelStats>,
+    }
+
+    pub async fn get_hummock_version_stats<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<HummockVersionStats>> {
+        let version = srv.hummock_manager.get_current_version().await;
+        let levels = version
+            .levels
+            .iter()
+            .map(|level| LevelStats {
+                level_idx: level.level_idx,
+                sst_count: level.table_infos.len(),
+                total_file_size: level.table_infos.iter().map(|sst| sst.file_size).sum(),
+            })
+            .collect();
+
+        Ok(Json(HummockVersionStats {
+            id: version.id,
+            max_committed_epoch: version.max_committed_epoch,
+            safe_epoch: version.safe_epoch,
+            levels,
+        }))
+    }
 }
 
 impl<S> DashboardService<S>
@@ -150,6 +208,9 @@ where
             .route("/actors", get(list_actors::<S>))
             .route("/fragments", get(list_table_fragments::<S>))
             .route("/materialized_views", get(list_materialized_views::<S>))
+            .route("/barrier", get(get_barrier_status::<S>))
+            .route("/ddl_jobs", get(list_ddl_jobs::<S>))
+            .route("/hummock/version", get(get_hummock_version_stats::<S>))
             .layer(
                 ServiceBuilder::new()
                     .layer(AddExtensionLayer::new(srv.clone()))
@@ -225,6 +225,7 @@ impl CompactStatus {
             // TODO: fill with compaction group info
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            table_options: Default::default(),
         };
         self.next_compact_task_id += 1;
         Some(compact_task)
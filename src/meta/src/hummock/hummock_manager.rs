@@ -41,7 +41,9 @@ use crate::cluster::{ClusterManagerRef, META_NODE_ID};
 use crate::hummock::compaction::{CompactStatus, CompactionConfig};
 use crate::hummock::compaction_scheduler::CompactionRequestChannelRef;
 use crate::hummock::error::{Error, Result};
-use crate::hummock::metrics_utils::{trigger_commit_stat, trigger_rw_stat, trigger_sst_stat};
+use crate::hummock::metrics_utils::{
+    trigger_commit_stat, trigger_pin_unpin_version_state, trigger_rw_stat, trigger_sst_stat,
+};
 use crate::hummock::model::{
     sstable_id_info, CurrentHummockVersionId, HummockPinnedSnapshotExt, HummockPinnedVersionExt,
     INVALID_TIMESTAMP,
@@ -327,6 +329,7 @@ where
         if !already_pinned {
             context_pinned_version.pin_version(version_id);
             commit_multi_var!(self, Some(context_id), context_pinned_version)?;
+            trigger_pin_unpin_version_state(&self.metrics, &versioning.pinned_versions);
         } else {
             abort_multi_var!(context_pinned_version);
         }
@@ -359,6 +362,7 @@ where
             context_pinned_version.unpin_version(*pinned_version_id);
         }
         commit_multi_var!(self, Some(context_id), context_pinned_version)?;
+        trigger_pin_unpin_version_state(&self.metrics, &versioning_guard.pinned_versions);
 
         #[cfg(test)]
         {
@@ -489,20 +493,27 @@ where
                         .flat_map(|v| v.snapshot_id.clone())
                         .fold(max_committed_epoch, std::cmp::min)
                 };
+                let table_ids = compact_task
+                    .input_ssts
+                    .iter()
+                    .flat_map(|level| {
+                        level
+                            .table_infos
+                            .iter()
+                            .flat_map(|sst_info| {
+                                sst_info.vnode_bitmaps.iter().map(|bitmap| bitmap.table_id)
+                            })
+                            .collect_vec()
+                    })
+                    .collect::<HashSet<u32>>();
+                for table_id in &table_ids {
+                    if let Some(table_option) =
+                        self.env.table_option_manager().get_table_option(table_id)
+                    {
+                        compact_task.table_options.insert(*table_id, table_option);
+                    }
+                }
                 if compact_task.target_level != 0 {
-                    let table_ids = compact_task
-                        .input_ssts
-                        .iter()
-                        .flat_map(|level| {
-                            level
-                                .table_infos
-                                .iter()
-                                .flat_map(|sst_info| {
-                                    sst_info.vnode_bitmaps.iter().map(|bitmap| bitmap.table_id)
-                                })
-                                .collect_vec()
-                        })
-                        .collect::<HashSet<u32>>();
                     compact_task.vnode_mappings.reserve_exact(table_ids.len());
                     for table_id in table_ids {
                         if let Some(vnode_mapping) = self
@@ -867,6 +878,7 @@ where
                 pinned_versions,
                 pinned_snapshots
             )?;
+            trigger_pin_unpin_version_state(&self.metrics, &versioning.pinned_versions);
         } else {
             abort_multi_var!(
                 compact_status,
@@ -966,7 +978,23 @@ where
         let pinned_versions_ref = &versioning.pinned_versions;
         let mut hummock_versions = VarTransaction::new(&mut versioning.hummock_versions);
         let mut stale_sstables = VarTransaction::new(&mut versioning.stale_sstables);
+        let current_version_id = versioning.current_version_id.id();
         for version_id in version_ids {
+            if *version_id == current_version_id {
+                return Err(Error::InternalError(format!(
+                    "Version {} is the current version and shouldn't be deleted",
+                    version_id
+                )));
+            }
+            if let Some(version_pin) = pinned_versions_ref
+                .values()
+                .find(|version_pin| version_pin.version_id.contains(version_id))
+            {
+                return Err(Error::InternalError(format!(
+                    "Version {} is still pinned by context {}",
+                    version_id, version_pin.context_id
+                )));
+            }
             if hummock_versions.remove(version_id).is_none() {
                 continue;
             }
@@ -979,13 +1007,6 @@ where
                 }
             }
             stale_sstables.remove(version_id);
-
-            for version_pin in pinned_versions_ref.values() {
-                assert!(
-                    !version_pin.version_id.contains(version_id),
-                    "version still referenced shouldn't be deleted."
-                );
-            }
         }
         commit_multi_var!(self, None, hummock_versions, stale_sstables)?;
 
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::time::Duration;
 
 use itertools::Itertools;
@@ -24,7 +25,7 @@ use risingwave_hummock_sdk::{
 use risingwave_pb::common::{HostAddress, ParallelUnitType, WorkerType};
 use risingwave_pb::hummock::{
     HummockPinnedSnapshot, HummockPinnedVersion, HummockSnapshot, HummockVersion,
-    HummockVersionRefId,
+    HummockVersionRefId, TableOption,
 };
 
 use crate::hummock::error::Error;
@@ -125,6 +126,8 @@ async fn test_hummock_compaction_task() {
         env.hash_mapping_manager()
             .set_fragment_state_table(1, table_id as u32);
     }
+    env.table_option_manager()
+        .set_table_option(1, TableOption { ttl: 60 });
 
     // No compaction task available.
     let task = hummock_manager.get_compact_task().await.unwrap();
@@ -175,6 +178,11 @@ async fn test_hummock_compaction_task() {
     // one of them overlaps with the previous SST. So there will be one more relational tables
     // (for vnode mapping) than SSTs.
     assert_eq!(compact_task.get_vnode_mappings().len(), sst_num + 1);
+    // Only the tables with options are present.
+    assert_eq!(
+        compact_task.table_options,
+        HashMap::from([(1, TableOption { ttl: 60 })])
+    );
 
     // Cancel the task and succeed.
     compact_task.task_status = false;
@@ -389,7 +397,7 @@ async fn test_release_context_resource() {
         port: 2,
     };
     let (worker_node_2, _) = cluster_manager
-        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode, 0)
         .await
         .unwrap();
     let context_id_2 = worker_node_2.id;
@@ -504,7 +512,7 @@ async fn test_hummock_manager_basic() {
         port: 2,
     };
     let (worker_node_2, _) = cluster_manager
-        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode)
+        .add_worker_node(fake_host_address_2, WorkerType::ComputeNode, 0)
         .await
         .unwrap();
     let context_id_2 = worker_node_2.id;
@@ -586,6 +594,11 @@ async fn test_hummock_manager_basic() {
         .is_empty());
 
     // test delete_version
+    // The current version is pinned and shouldn't be deleted.
+    assert!(hummock_manager
+        .delete_versions(&[FIRST_VERSION_ID + 1])
+        .await
+        .is_err());
     hummock_manager
         .delete_versions(&[FIRST_VERSION_ID])
         .await
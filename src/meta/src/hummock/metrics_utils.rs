@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::atomic::Ordering;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use itertools::enumerate;
 use prometheus::Histogram;
 use prost::Message;
-use risingwave_pb::hummock::{CompactMetrics, HummockVersion, TableSetStatistics};
+use risingwave_hummock_sdk::{HummockContextId, INVALID_VERSION_ID};
+use risingwave_pb::hummock::{
+    CompactMetrics, HummockPinnedVersion, HummockVersion, TableSetStatistics,
+};
 
 use crate::hummock::compaction::CompactStatus;
 use crate::rpc::metrics::MetaMetrics;
@@ -32,6 +36,21 @@ pub fn trigger_commit_stat(metrics: &MetaMetrics, current_version: &HummockVersi
         .set(current_version.encoded_len() as i64);
 }
 
+pub fn trigger_pin_unpin_version_state(
+    metrics: &MetaMetrics,
+    pinned_versions: &BTreeMap<HummockContextId, HummockPinnedVersion>,
+) {
+    let min_pinned_version_id = pinned_versions
+        .values()
+        .flat_map(|pinned_version| pinned_version.version_id.iter())
+        .min()
+        .cloned()
+        .unwrap_or(INVALID_VERSION_ID);
+    metrics
+        .min_pinned_version_id
+        .set(min_pinned_version_id as i64);
+}
+
 pub fn trigger_sst_stat(
     metrics: &MetaMetrics,
     compact_status: &CompactStatus,
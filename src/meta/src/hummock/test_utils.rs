@@ -172,7 +172,7 @@ pub async fn setup_compute_env(
         port,
     };
     let (worker_node, _) = cluster_manager
-        .add_worker_node(fake_host_address, WorkerType::ComputeNode)
+        .add_worker_node(fake_host_address, WorkerType::ComputeNode, 0)
         .await
         .unwrap();
     (env, hummock_manager, cluster_manager, worker_node)
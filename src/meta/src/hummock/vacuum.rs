@@ -21,7 +21,7 @@ use risingwave_common::error::Result;
 use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::hummock::VacuumTask;
 
-use crate::hummock::model::INVALID_TIMESTAMP;
+use crate::hummock::model::{get_timestamp_now, INVALID_TIMESTAMP};
 use crate::hummock::{CompactorManager, HummockManagerRef};
 use crate::storage::MetaStore;
 
@@ -33,6 +33,8 @@ use crate::storage::MetaStore;
 ///   `SstableIdInfo::meta_delete_timestamp` and delete asynchronously.
 /// - 4.2 Or if step 3 didn't happen after some time, the SST is delete as orphan data
 ///   asynchronously.
+/// - 5 The SST file is deleted from object store after a grace period since step 4, in case it's
+///   still being read by stale readers.
 pub struct VacuumTrigger<S: MetaStore> {
     hummock_manager: HummockManagerRef<S>,
     /// Use the CompactorManager to dispatch VacuumTask.
@@ -124,9 +126,13 @@ where
     ///   `id_create_timestamp`. Its `meta_delete_timestamp` field will then be set.
     /// - SST marked for deletion. The SST is marked for deletion by `vacuum_tracked_data`, that's
     ///   to say `meta_delete_timestamp` is set.
+    ///
+    /// Either type of SSTs is only deleted after it has been marked for at least
+    /// `stale_sst_retention_interval`.
     pub async fn vacuum_sst_data(
         &self,
         orphan_sst_retention_interval: Duration,
+        stale_sst_retention_interval: Duration,
     ) -> risingwave_common::error::Result<Vec<HummockSSTableId>> {
         // Select SSTs to delete.
         let ssts_to_delete = {
@@ -143,13 +149,17 @@ where
                 self.hummock_manager
                     .mark_orphan_ssts(orphan_sst_retention_interval)
                     .await?;
+                let now = get_timestamp_now();
                 let ssts_to_delete = self
                     .hummock_manager
                     .list_sstable_id_infos(None)
                     .await?
                     .into_iter()
                     .filter(|sstable_id_info| {
-                        sstable_id_info.meta_delete_timestamp != INVALID_TIMESTAMP
+                        let marked_at = sstable_id_info.meta_delete_timestamp;
+                        marked_at != INVALID_TIMESTAMP
+                            && now >= marked_at
+                            && now - marked_at >= stale_sst_retention_interval.as_secs()
                     })
                     .map(|sstable_id_info| sstable_id_info.id)
                     .collect_vec();
@@ -308,7 +318,7 @@ mod tests {
         hummock_manager.get_new_table_id().await.unwrap();
         // 2. no expired SST id.
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(60),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(60), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -316,7 +326,7 @@ mod tests {
         );
         // 3. 2 expired SST id but no vacuum node.
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -324,15 +334,17 @@ mod tests {
         );
         let _receiver = compactor_manager.add_compactor(0);
         // 4. 2 expired SST ids.
-        let sst_ids = VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0))
-            .await
-            .unwrap();
+        let sst_ids =
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0), Duration::ZERO)
+                .await
+                .unwrap();
         assert_eq!(sst_ids.len(), 2);
         // 5. got the same 2 expired sst ids because the previous pending SST ids are not
         // reported.
-        let sst_ids_2 = VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0))
-            .await
-            .unwrap();
+        let sst_ids_2 =
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0), Duration::ZERO)
+                .await
+                .unwrap();
         assert_eq!(sst_ids, sst_ids_2);
         // 6. report the previous pending SST ids to indicate their success.
         vacuum
@@ -342,7 +354,7 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(0), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -373,9 +385,22 @@ mod tests {
             3
         );
 
+        // test_table is marked for deletion, but still in the grace period.
+        assert_eq!(
+            VacuumTrigger::vacuum_sst_data(
+                &vacuum,
+                Duration::from_secs(600),
+                Duration::from_secs(600)
+            )
+            .await
+            .unwrap()
+            .len(),
+            0
+        );
+
         // Found test_table is marked for deletion.
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -384,7 +409,7 @@ mod tests {
 
         // The vacuum task is not reported yet.
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -406,7 +431,7 @@ mod tests {
 
         // test_table is already reported.
         assert_eq!(
-            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600),)
+            VacuumTrigger::vacuum_sst_data(&vacuum, Duration::from_secs(600), Duration::ZERO)
                 .await
                 .unwrap()
                 .len(),
@@ -78,6 +78,10 @@ pub struct MetaNodeOpts {
     #[clap(long, default_value_t = String::from(""))]
     etcd_endpoints: String,
 
+    /// The state store url, which is fixed once the cluster is bootstrapped.
+    #[clap(long, default_value = "hummock+memory")]
+    state_store: String,
+
     /// Maximum allowed heartbeat interval in ms.
     #[clap(long, default_value = "60000")]
     max_heartbeat_interval: u32,
@@ -93,6 +97,10 @@ pub struct MetaNodeOpts {
     /// e2e tests.
     #[clap(long)]
     disable_recovery: bool,
+
+    /// Enable reporting tracing information to jaeger
+    #[clap(long)]
+    pub enable_jaeger_tracing: bool,
 }
 
 fn load_config(opts: &MetaNodeOpts) -> ComputeNodeConfig {
@@ -130,6 +138,12 @@ pub async fn start(opts: MetaNodeOpts) {
         MetaOpts {
             enable_recovery: !opts.disable_recovery,
             checkpoint_interval,
+            checkpoint_frequency: compute_config.streaming.checkpoint_frequency,
+            state_store: opts.state_store,
+            block_cache_capacity_mb: compute_config.storage.block_cache_capacity_mb,
+            compaction_worker_threads_number: compute_config
+                .storage
+                .share_buffer_compaction_worker_threads_number,
         },
     )
     .await
@@ -22,9 +22,11 @@ use risingwave_common::catalog::{CatalogVersion, DEFAULT_DATABASE_NAME, DEFAULT_
 use risingwave_common::ensure;
 use risingwave_common::error::ErrorCode::{CatalogError, InternalError};
 use risingwave_common::error::{Result, RwError};
+use risingwave_pb::catalog::source::Info as SourceInfo;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
-use risingwave_pb::catalog::{Database, Schema, Source, Table};
+use risingwave_pb::catalog::{Database, Schema, Sink, Source, Table};
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::plan_common::{ColumnCatalog, RowFormatType};
 use tokio::sync::{Mutex, MutexGuard};
 
 use super::IdCategory;
@@ -36,9 +38,24 @@ pub type DatabaseId = u32;
 pub type SchemaId = u32;
 pub type TableId = u32;
 pub type SourceId = u32;
+pub type SinkId = u32;
 pub type RelationId = u32;
 
-pub type Catalog = (Vec<Database>, Vec<Schema>, Vec<Table>, Vec<Source>);
+/// A relation depending on others, which has to be dropped before them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DependentRelation {
+    /// A materialized view or an index.
+    Table(TableId),
+    Sink(SinkId),
+}
+
+pub type Catalog = (
+    Vec<Database>,
+    Vec<Schema>,
+    Vec<Table>,
+    Vec<Source>,
+    Vec<Sink>,
+);
 
 pub struct CatalogManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
@@ -274,7 +291,8 @@ where
             match core.get_ref_count(table_id) {
                 Some(ref_count) => Err(CatalogError(
                     anyhow!(
-                        "Fail to delete table `{}` because {} other relation(s) depend on it.",
+                        "Fail to delete table `{}` because {} other relation(s) depend on it. \
+                         Use CASCADE to drop them as well.",
                         table.name,
                         ref_count
                     )
@@ -379,7 +397,8 @@ where
             match core.get_ref_count(source_id) {
                 Some(ref_count) => Err(CatalogError(
                     anyhow!(
-                        "Fail to delete source `{}` because {} other relation(s) depend on it.",
+                        "Fail to delete source `{}` because {} other relation(s) depend on it. \
+                         Use CASCADE to drop them as well.",
                         source.name,
                         ref_count
                     )
@@ -406,6 +425,172 @@ where
         }
     }
 
+    /// Appends `column` to the columns of a source and bumps the version of them. The running
+    /// materialized views are not altered along with the source, so it can't be read by any of
+    /// them, including the one of a materialized source.
+    pub async fn alter_source_add_column(
+        &self,
+        source_id: SourceId,
+        source_version: u64,
+        column: ColumnCatalog,
+    ) -> Result<(Source, CatalogVersion)> {
+        let core = self.core.lock().await;
+        let mut source = Source::select(self.env.meta_store(), &source_id)
+            .await?
+            .ok_or_else(|| RwError::from(InternalError("source doesn't exist".to_string())))?;
+        if let Some(ref_count) = core.get_ref_count(source_id) {
+            return Err(CatalogError(
+                anyhow!(
+                    "Fail to alter source `{}` because {} other relation(s) depend on it. \
+                     Running materialized views can't pick up new columns, drop them first.",
+                    source.name,
+                    ref_count
+                )
+                .into(),
+            )
+            .into());
+        }
+        let is_materialized = Table::list(self.env.meta_store())
+            .await?
+            .iter()
+            .any(|table| {
+                table.optional_associated_source_id
+                    == Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id))
+            });
+        if is_materialized {
+            return Err(CatalogError(
+                anyhow!("Fail to alter materialized source `{}`.", source.name).into(),
+            )
+            .into());
+        }
+
+        let name = source.name.clone();
+        let info = match source.info.as_mut() {
+            Some(SourceInfo::StreamSource(info)) => info,
+            _ => {
+                return Err(CatalogError(anyhow!("`{}` is not a source.", name).into()).into());
+            }
+        };
+        if info.row_format == RowFormatType::Protobuf as i32 {
+            return Err(CatalogError(
+                anyhow!(
+                    "Fail to alter source `{}` whose columns come from the protobuf schema.",
+                    name
+                )
+                .into(),
+            )
+            .into());
+        }
+        if info.version != source_version {
+            return Err(CatalogError(
+                anyhow!(
+                    "Fail to alter source `{}` because it has been altered concurrently.",
+                    name
+                )
+                .into(),
+            )
+            .into());
+        }
+        let column_desc = column.get_column_desc()?;
+        if info.columns.iter().any(|c| {
+            let desc = c.column_desc.as_ref().unwrap();
+            desc.name == column_desc.name || desc.column_id == column_desc.column_id
+        }) {
+            return Err(CatalogError(
+                anyhow!(
+                    "Fail to alter source `{}` because column `{}` already exists.",
+                    name,
+                    column_desc.name
+                )
+                .into(),
+            )
+            .into());
+        }
+        info.columns.push(column);
+        info.version += 1;
+
+        source.insert(self.env.meta_store()).await?;
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::Source(source.clone()))
+            .await;
+
+        Ok((source, version))
+    }
+
+    pub async fn start_create_sink_procedure(&self, sink: &Sink) -> Result<()> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && !core.has_in_progress_creation(&key) {
+            core.mark_creating(&key);
+            core.increase_ref_count(sink.associated_table_id);
+            Ok(())
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exists or in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn finish_create_sink_procedure(&self, sink: &Sink) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && core.has_in_progress_creation(&key) {
+            core.unmark_creating(&key);
+            sink.insert(self.env.meta_store()).await?;
+            core.add_sink(sink);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Add, Info::Sink(sink.to_owned()))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exist or not in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn cancel_create_sink_procedure(&self, sink: &Sink) -> Result<()> {
+        let mut core = self.core.lock().await;
+        let key = (sink.database_id, sink.schema_id, sink.name.clone());
+        if !core.has_sink(sink) && core.has_in_progress_creation(&key) {
+            core.unmark_creating(&key);
+            core.decrease_ref_count(sink.associated_table_id);
+            Ok(())
+        } else {
+            Err(RwError::from(InternalError(
+                "sink already exist or not in creating procedure".to_string(),
+            )))
+        }
+    }
+
+    pub async fn drop_sink(&self, sink_id: SinkId) -> Result<CatalogVersion> {
+        let mut core = self.core.lock().await;
+        let sink = Sink::select(self.env.meta_store(), &sink_id).await?;
+        if let Some(sink) = sink {
+            Sink::delete(self.env.meta_store(), &sink_id).await?;
+            core.drop_sink(&sink);
+            core.decrease_ref_count(sink.associated_table_id);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Delete, Info::Sink(sink))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(InternalError(
+                "sink doesn't exist".to_string(),
+            )))
+        }
+    }
+
     pub async fn start_create_materialized_source_procedure(
         &self,
         source: &Source,
@@ -522,7 +707,8 @@ where
                 if let Some(ref_count) = core.get_ref_count(mview_id) {
                     return Err(CatalogError(
                         anyhow!(
-                            "Fail to delete table `{}` because {} other relation(s) depend on it.",
+                            "Fail to delete table `{}` because {} other relation(s) depend on it. \
+                             Use CASCADE to drop them as well.",
                             mview.name,
                             ref_count
                         )
@@ -533,7 +719,8 @@ where
                 if let Some(ref_count) = core.get_ref_count(source_id) {
                     return Err(CatalogError(
                         anyhow!(
-                            "Fail to delete source `{}` because {} other relation(s) depend on it.",
+                            "Fail to delete source `{}` because {} other relation(s) depend on it. \
+                             Use CASCADE to drop them as well.",
                             source.name,
                             ref_count
                         )
@@ -571,6 +758,103 @@ where
         }
     }
 
+    /// Drops the relations depending on `relation_id` directly or indirectly for a cascading drop.
+    /// They're deleted from the catalog in one transaction, under the same lock as listing them.
+    /// Returns them in the order that they can be torn down one by one, i.e., each relation comes
+    /// before the ones it depends on.
+    pub async fn drop_dependents(&self, relation_id: RelationId) -> Result<Vec<DependentRelation>> {
+        let mut core = self.core.lock().await;
+        let mut tables: HashMap<TableId, Table> = Table::list(self.env.meta_store())
+            .await?
+            .into_iter()
+            .map(|table| (table.id, table))
+            .collect();
+        let mut sinks: HashMap<SinkId, Sink> = Sink::list(self.env.meta_store())
+            .await?
+            .into_iter()
+            .map(|sink| (sink.id, sink))
+            .collect();
+
+        let mut direct_dependents: HashMap<RelationId, Vec<DependentRelation>> = HashMap::new();
+        for table in tables.values() {
+            for &dependent_relation_id in &table.dependent_relations {
+                direct_dependents
+                    .entry(dependent_relation_id)
+                    .or_default()
+                    .push(DependentRelation::Table(table.id));
+            }
+        }
+        for sink in sinks.values() {
+            direct_dependents
+                .entry(sink.associated_table_id)
+                .or_default()
+                .push(DependentRelation::Sink(sink.id));
+        }
+
+        // A post-order traversal, which puts a relation after all its dependents.
+        fn visit(
+            relation_id: RelationId,
+            direct_dependents: &HashMap<RelationId, Vec<DependentRelation>>,
+            visited: &mut HashSet<DependentRelation>,
+            dependents: &mut Vec<DependentRelation>,
+        ) {
+            for &dependent in direct_dependents.get(&relation_id).into_iter().flatten() {
+                if visited.insert(dependent) {
+                    if let DependentRelation::Table(table_id) = dependent {
+                        visit(table_id, direct_dependents, visited, dependents);
+                    }
+                    dependents.push(dependent);
+                }
+            }
+        }
+        let mut dependents = vec![];
+        visit(
+            relation_id,
+            &direct_dependents,
+            &mut HashSet::new(),
+            &mut dependents,
+        );
+
+        let mut transaction = Transaction::default();
+        for dependent in &dependents {
+            match dependent {
+                DependentRelation::Table(table_id) => {
+                    tables[table_id].delete_in_transaction(&mut transaction)?
+                }
+                DependentRelation::Sink(sink_id) => {
+                    sinks[sink_id].delete_in_transaction(&mut transaction)?
+                }
+            }
+        }
+        core.env.meta_store().txn(transaction).await?;
+
+        for &dependent in &dependents {
+            match dependent {
+                DependentRelation::Table(table_id) => {
+                    let table = tables.remove(&table_id).unwrap();
+                    core.drop_table(&table);
+                    for &dependent_relation_id in &table.dependent_relations {
+                        core.decrease_ref_count(dependent_relation_id);
+                    }
+                    self.env
+                        .notification_manager()
+                        .notify_frontend(Operation::Delete, Info::Table(table))
+                        .await;
+                }
+                DependentRelation::Sink(sink_id) => {
+                    let sink = sinks.remove(&sink_id).unwrap();
+                    core.drop_sink(&sink);
+                    core.decrease_ref_count(sink.associated_table_id);
+                    self.env
+                        .notification_manager()
+                        .notify_frontend(Operation::Delete, Info::Sink(sink))
+                        .await;
+                }
+            }
+        }
+        Ok(dependents)
+    }
+
     pub async fn list_tables(
         &self,
         database_id: DatabaseId,
@@ -604,6 +888,7 @@ type DatabaseKey = String;
 type SchemaKey = (DatabaseId, String);
 type TableKey = (DatabaseId, SchemaId, String);
 type SourceKey = (DatabaseId, SchemaId, String);
+type SinkKey = (DatabaseId, SchemaId, String);
 type RelationKey = (DatabaseId, SchemaId, String);
 
 /// [`CatalogManagerCore`] caches meta catalog information and maintains dependent relationship
@@ -616,6 +901,8 @@ pub struct CatalogManagerCore<S: MetaStore> {
     schemas: HashSet<SchemaKey>,
     /// Cached source key information.
     sources: HashSet<SourceKey>,
+    /// Cached sink key information.
+    sinks: HashSet<SinkKey>,
     /// Cached table key information.
     tables: HashSet<TableKey>,
     /// Relation refer count mapping.
@@ -634,6 +921,7 @@ where
         let schemas = Schema::list(env.meta_store()).await?;
         let sources = Source::list(env.meta_store()).await?;
         let tables = Table::list(env.meta_store()).await?;
+        let sinks = Sink::list(env.meta_store()).await?;
 
         let mut relation_ref_count = HashMap::new();
 
@@ -650,10 +938,16 @@ where
         );
         let tables = HashSet::from_iter(tables.into_iter().map(|table| {
             for depend_relation_id in &table.dependent_relations {
-                relation_ref_count.entry(*depend_relation_id).or_insert(0);
+                *relation_ref_count.entry(*depend_relation_id).or_insert(0) += 1;
             }
             (table.database_id, table.schema_id, table.name)
         }));
+        let sinks = HashSet::from_iter(sinks.into_iter().map(|sink| {
+            *relation_ref_count
+                .entry(sink.associated_table_id)
+                .or_insert(0) += 1;
+            (sink.database_id, sink.schema_id, sink.name)
+        }));
 
         let in_progress_creation_tracker = HashSet::new();
 
@@ -662,6 +956,7 @@ where
             databases,
             schemas,
             sources,
+            sinks,
             tables,
             relation_ref_count,
             in_progress_creation_tracker,
@@ -674,6 +969,7 @@ where
             Schema::list(self.env.meta_store()).await?,
             Table::list(self.env.meta_store()).await?,
             Source::list(self.env.meta_store()).await?,
+            Sink::list(self.env.meta_store()).await?,
         ))
     }
 
@@ -738,6 +1034,21 @@ where
             .remove(&(source.database_id, source.schema_id, source.name.clone()))
     }
 
+    fn has_sink(&self, sink: &Sink) -> bool {
+        self.sinks
+            .contains(&(sink.database_id, sink.schema_id, sink.name.clone()))
+    }
+
+    fn add_sink(&mut self, sink: &Sink) {
+        self.sinks
+            .insert((sink.database_id, sink.schema_id, sink.name.clone()));
+    }
+
+    fn drop_sink(&mut self, sink: &Sink) -> bool {
+        self.sinks
+            .remove(&(sink.database_id, sink.schema_id, sink.name.clone()))
+    }
+
     pub async fn get_source(&self, id: SourceId) -> Result<Option<Source>> {
         Source::select(self.env.meta_store(), &id).await
     }
@@ -0,0 +1,278 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::ddl_service::ddl_job::Status;
+use risingwave_pb::ddl_service::DdlJob;
+use tokio::sync::{oneshot, OwnedMutexGuard};
+
+use super::TableId;
+
+pub type DdlJobManagerRef = Arc<DdlJobManager>;
+
+/// The number of finished jobs kept for inspection.
+const MAX_FINISHED_DDL_JOBS: usize = 16;
+
+struct DdlJobEntry {
+    job: DdlJob,
+    /// Fired to cancel the job while it's pending.
+    cancel_tx: Option<oneshot::Sender<()>>,
+    /// Set once the actors of the job are being dropped for cancellation.
+    cancelling: bool,
+    /// Set once the job is about to be created, after which it can't be cancelled.
+    committed: bool,
+}
+
+#[derive(Default)]
+struct DdlJobManagerCore {
+    in_progress: BTreeMap<TableId, DdlJobEntry>,
+    finished: VecDeque<DdlJob>,
+}
+
+/// `DdlJobManager` serializes the DDL jobs creating streaming jobs, so that the actors of at most
+/// one job are being built at any time, and tracks the status of the jobs.
+#[derive(Default)]
+pub struct DdlJobManager {
+    ddl_lock: Arc<tokio::sync::Mutex<()>>,
+    core: Mutex<DdlJobManagerCore>,
+}
+
+impl DdlJobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a job and wait for the jobs submitted earlier to finish. The job stays in building
+    /// status until the returned guard is finished or dropped.
+    pub async fn start_job(self: &Arc<Self>, job_id: TableId, name: &str) -> Result<DdlJobGuard> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let old = self.core.lock().in_progress.insert(
+            job_id,
+            DdlJobEntry {
+                job: DdlJob {
+                    job_id,
+                    name: name.to_string(),
+                    status: Status::Pending as i32,
+                    error: String::new(),
+                },
+                cancel_tx: Some(cancel_tx),
+                cancelling: false,
+                committed: false,
+            },
+        );
+        assert!(old.is_none(), "ddl job {} already exists", job_id);
+        // Clean up the job if it's dropped while pending.
+        let mut guard = DdlJobGuard {
+            manager: self.clone(),
+            job_id,
+            _permit: None,
+            finished: false,
+        };
+
+        let permit = tokio::select! {
+            permit = self.ddl_lock.clone().lock_owned() => Some(permit),
+            _ = cancel_rx => None,
+        };
+        let cancelled = {
+            let mut core = self.core.lock();
+            let entry = core.in_progress.get_mut(&job_id).unwrap();
+            // The job may also be cancelled right after acquiring the lock.
+            if permit.is_some() && entry.cancel_tx.take().is_some() {
+                entry.job.status = Status::Building as i32;
+                false
+            } else {
+                true
+            }
+        };
+
+        guard._permit = permit;
+        if cancelled {
+            guard.finish(Err(cancelled_error()))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    /// List the jobs in progress in the order of their ids, followed by the ones finished recently.
+    pub fn list_jobs(&self) -> Vec<DdlJob> {
+        let core = self.core.lock();
+        core.in_progress
+            .values()
+            .map(|entry| entry.job.clone())
+            .chain(core.finished.iter().cloned())
+            .collect()
+    }
+
+    /// Cancel the job if it's pending. Returns `false` if it's building, which can only be
+    /// cancelled by dropping its actors after [`DdlJobManager::mark_cancelling`].
+    pub fn cancel_pending_job(&self, job_id: TableId) -> Result<bool> {
+        let mut core = self.core.lock();
+        let entry = Self::get_cancellable(&mut core, job_id)?;
+        match entry.cancel_tx.take() {
+            Some(cancel_tx) => {
+                cancel_tx.send(()).ok();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Mark the building job as being cancelled, after which it will fail instead of being
+    /// created.
+    pub fn mark_cancelling(&self, job_id: TableId) -> Result<()> {
+        let mut core = self.core.lock();
+        let entry = Self::get_cancellable(&mut core, job_id)?;
+        entry.cancelling = true;
+        Ok(())
+    }
+
+    fn get_cancellable(core: &mut DdlJobManagerCore, job_id: TableId) -> Result<&mut DdlJobEntry> {
+        match core.in_progress.get_mut(&job_id) {
+            Some(entry) if !entry.committed && !entry.cancelling => Ok(entry),
+            Some(_) => Err(RwError::from(ErrorCode::InternalError(format!(
+                "ddl job {} is already being created or cancelled",
+                job_id
+            )))),
+            None => Err(RwError::from(ErrorCode::ItemNotFound(format!(
+                "ddl job {} is not in progress",
+                job_id
+            )))),
+        }
+    }
+
+    fn commit_job(&self, job_id: TableId) -> Result<()> {
+        let mut core = self.core.lock();
+        let entry = core.in_progress.get_mut(&job_id).unwrap();
+        if entry.cancelling {
+            return Err(cancelled_error());
+        }
+        entry.committed = true;
+        Ok(())
+    }
+
+    fn finish_job(&self, job_id: TableId, error: Option<String>) {
+        let mut core = self.core.lock();
+        let mut job = core.in_progress.remove(&job_id).unwrap().job;
+        match error {
+            Some(error) => {
+                tracing::warn!("ddl job {} failed: {}", job_id, error);
+                job.status = Status::Failed as i32;
+                job.error = error;
+            }
+            None => job.status = Status::Created as i32,
+        }
+        if core.finished.len() == MAX_FINISHED_DDL_JOBS {
+            core.finished.pop_front();
+        }
+        core.finished.push_back(job);
+    }
+}
+
+fn cancelled_error() -> RwError {
+    ErrorCode::QueryCancelled("user request".to_string()).into()
+}
+
+/// The guard of a building job, which holds the DDL lock until it's finished or dropped. A job
+/// dropped without being finished is considered failed.
+pub struct DdlJobGuard {
+    manager: DdlJobManagerRef,
+    job_id: TableId,
+    _permit: Option<OwnedMutexGuard<()>>,
+    finished: bool,
+}
+
+impl DdlJobGuard {
+    /// Called once the streaming job is built and before it's created in catalog. Fails if the job
+    /// is being cancelled.
+    pub fn commit(&self) -> Result<()> {
+        self.manager.commit_job(self.job_id)
+    }
+
+    /// Record the result of the job and release the DDL lock.
+    pub fn finish<T>(mut self, result: Result<T>) -> Result<T> {
+        self.finished = true;
+        self.manager
+            .finish_job(self.job_id, result.as_ref().err().map(ToString::to_string));
+        result
+    }
+}
+
+impl Drop for DdlJobGuard {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.manager
+                .finish_job(self.job_id, Some("the job is aborted".to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ddl_job_manager() {
+        let manager = Arc::new(DdlJobManager::new());
+
+        let job_1 = manager.start_job(1, "mv1").await.unwrap();
+        let pending = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.start_job(2, "mv2").await.map(|_| ()) })
+        };
+        while manager.list_jobs().len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        let statuses = manager
+            .list_jobs()
+            .iter()
+            .map(|job| (job.job_id, job.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, vec![(1, Status::Building), (2, Status::Pending)]);
+
+        // Cancel the pending job.
+        assert!(manager.cancel_pending_job(2).unwrap());
+        assert!(pending.await.unwrap().is_err());
+        assert!(manager.cancel_pending_job(2).is_err());
+
+        // Cancel the building job.
+        assert!(!manager.cancel_pending_job(1).unwrap());
+        manager.mark_cancelling(1).unwrap();
+        assert!(manager.mark_cancelling(1).is_err());
+        assert!(job_1.commit().is_err());
+        drop(job_1);
+
+        let job_3 = manager.start_job(3, "mv3").await.unwrap();
+        job_3.commit().unwrap();
+        assert!(manager.cancel_pending_job(3).is_err());
+        job_3.finish(Ok(())).unwrap();
+
+        let statuses = manager
+            .list_jobs()
+            .iter()
+            .map(|job| (job.job_id, job.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (2, Status::Failed),
+                (1, Status::Failed),
+                (3, Status::Created)
+            ]
+        );
+    }
+}
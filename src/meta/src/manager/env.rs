@@ -16,9 +16,13 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
+use risingwave_pb::meta::SystemParams;
 use risingwave_rpc_client::{StreamClientPool, StreamClientPoolRef};
 
-use super::{HashMappingManager, HashMappingManagerRef};
+use super::{
+    HashMappingManager, HashMappingManagerRef, SystemParamManager, SystemParamManagerRef,
+    TableOptionManager, TableOptionManagerRef,
+};
 use crate::manager::{
     IdGeneratorManager, IdGeneratorManagerRef, NotificationManager, NotificationManagerRef,
 };
@@ -45,6 +49,12 @@ where
     /// hash mapping manager.
     hash_mapping_manager: HashMappingManagerRef,
 
+    /// table option manager.
+    table_option_manager: TableOptionManagerRef,
+
+    /// system param manager.
+    system_param_manager: SystemParamManagerRef<S>,
+
     /// stream client pool memorization.
     stream_client_pool: StreamClientPoolRef,
 
@@ -55,7 +65,31 @@ where
 /// Options shared by all meta service instances
 pub struct MetaOpts {
     pub enable_recovery: bool,
+    /// Initial value of the mutable system param `barrier_interval_ms`.
     pub checkpoint_interval: Duration,
+    /// Initial value of the mutable system param `checkpoint_frequency`.
+    pub checkpoint_frequency: usize,
+
+    /// Initial value of the immutable system param `state_store`.
+    pub state_store: String,
+    /// Initial value of the immutable system param `block_cache_capacity_mb`.
+    pub block_cache_capacity_mb: usize,
+    /// Initial value of the mutable system param `compaction_worker_threads_number`.
+    pub compaction_worker_threads_number: u32,
+}
+
+impl MetaOpts {
+    /// The system params to bootstrap the cluster with. They're ignored if the cluster has been
+    /// bootstrapped before.
+    pub fn init_system_params(&self) -> SystemParams {
+        SystemParams {
+            barrier_interval_ms: self.checkpoint_interval.as_millis() as u32,
+            block_cache_capacity_mb: self.block_cache_capacity_mb as u64,
+            state_store: self.state_store.clone(),
+            compaction_worker_threads_number: self.compaction_worker_threads_number,
+            checkpoint_frequency: self.checkpoint_frequency.max(1) as u64,
+        }
+    }
 }
 
 impl Default for MetaOpts {
@@ -63,6 +97,10 @@ impl Default for MetaOpts {
         Self {
             enable_recovery: false,
             checkpoint_interval: Duration::from_millis(100),
+            checkpoint_frequency: 1,
+            state_store: "hummock+memory".to_string(),
+            block_cache_capacity_mb: 256,
+            compaction_worker_threads_number: 2,
         }
     }
 }
@@ -77,12 +115,24 @@ where
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let notification_manager = Arc::new(NotificationManager::new());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
+        let table_option_manager = Arc::new(TableOptionManager::new());
+        let system_param_manager = Arc::new(
+            SystemParamManager::new(
+                meta_store.clone(),
+                notification_manager.clone(),
+                opts.init_system_params(),
+            )
+            .await
+            .unwrap(),
+        );
 
         Self {
             id_gen_manager,
             meta_store,
             notification_manager,
             hash_mapping_manager,
+            table_option_manager,
+            system_param_manager,
             stream_client_pool,
             opts: opts.into(),
         }
@@ -120,6 +170,22 @@ where
         self.hash_mapping_manager.deref()
     }
 
+    pub fn table_option_manager_ref(&self) -> TableOptionManagerRef {
+        self.table_option_manager.clone()
+    }
+
+    pub fn table_option_manager(&self) -> &TableOptionManager {
+        self.table_option_manager.deref()
+    }
+
+    pub fn system_param_manager_ref(&self) -> SystemParamManagerRef<S> {
+        self.system_param_manager.clone()
+    }
+
+    pub fn system_param_manager(&self) -> &SystemParamManager<S> {
+        self.system_param_manager.deref()
+    }
+
     pub fn stream_client_pool_ref(&self) -> StreamClientPoolRef {
         self.stream_client_pool.clone()
     }
@@ -139,14 +205,27 @@ impl MetaSrvEnv<MemStore> {
         let notification_manager = Arc::new(NotificationManager::new());
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
+        let table_option_manager = Arc::new(TableOptionManager::new());
+        let opts = MetaOpts::default();
+        let system_param_manager = Arc::new(
+            SystemParamManager::new(
+                meta_store.clone(),
+                notification_manager.clone(),
+                opts.init_system_params(),
+            )
+            .await
+            .unwrap(),
+        );
 
         Self {
             id_gen_manager,
             meta_store,
             notification_manager,
             hash_mapping_manager,
+            table_option_manager,
+            system_param_manager,
             stream_client_pool,
-            opts: MetaOpts::default().into(),
+            opts: opts.into(),
         }
     }
 }
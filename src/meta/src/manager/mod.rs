@@ -13,15 +13,21 @@
 // limitations under the License.
 
 mod catalog;
+mod ddl_job;
 mod env;
 mod hash_mapping;
 mod id;
 mod notification;
+mod system_param;
+mod table_option;
 mod user;
 
 pub use catalog::*;
+pub use ddl_job::*;
 pub use env::*;
 pub use hash_mapping::*;
 pub use id::*;
 pub use notification::*;
+pub use system_param::*;
+pub use table_option::*;
 pub use user::*;
@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use risingwave_pb::common::WorkerNode;
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
-use risingwave_pb::meta::SubscribeResponse;
+use risingwave_pb::meta::{MetaSnapshot, SubscribeResponse};
 use tokio::sync::mpsc::{self, UnboundedSender};
 use tokio::sync::{oneshot, Mutex};
 use tonic::Status;
@@ -155,12 +155,29 @@ impl NotificationManager {
         core_guard.frontend_senders.remove(&worker_key);
     }
 
+    /// Sends the snapshot to a newly subscribed frontend and registers its sender. Both are done
+    /// under the lock of the core, so that the frontend receives exactly the notifications after
+    /// the snapshot, i.e., the ones of larger versions.
     pub async fn insert_frontend_sender(
         &self,
         worker_key: WorkerKey,
         sender: UnboundedSender<Notification>,
+        snapshot: MetaSnapshot,
     ) {
         let mut core_guard = self.core.lock().await;
+        if let Err(err) = sender.send(Ok(SubscribeResponse {
+            status: None,
+            operation: Operation::Snapshot as i32,
+            info: Some(Info::Snapshot(snapshot)),
+            version: core_guard.current_version,
+        })) {
+            tracing::warn!(
+                "Failed to send snapshot to frontend {:?}: {}",
+                worker_key,
+                err
+            );
+            return;
+        }
         core_guard.frontend_senders.insert(worker_key, sender);
     }
 
@@ -212,18 +229,23 @@ impl NotificationManagerCore {
         }
     }
 
+    /// Send a `SubscribeResponse` to frontends. The senders whose subscriptions are gone are
+    /// removed, and the frontends will get a new snapshot once they subscribe again.
     async fn notify_frontend(&mut self, operation: Operation, info: &Info) -> NotificationVersion {
         self.current_version += 1;
-        for (worker_key, sender) in &self.frontend_senders {
+        let version = self.current_version;
+        self.frontend_senders.retain(|worker_key, sender| {
             if let Err(err) = sender.send(Ok(SubscribeResponse {
                 status: None,
                 operation: operation as i32,
                 info: Some(info.clone()),
-                version: self.current_version,
+                version,
             })) {
                 tracing::warn!("Failed to notify frontend {:?}: {}", worker_key, err);
+                return false;
             }
-        }
+            true
+        });
 
         self.current_version
     }
@@ -231,17 +253,67 @@ impl NotificationManagerCore {
     /// Send a `SubscribeResponse` to backend.
     async fn notify_compute(&mut self, operation: Operation, info: &Info) -> NotificationVersion {
         self.current_version += 1;
-        for (worker_key, sender) in &self.compute_senders {
+        let version = self.current_version;
+        self.compute_senders.retain(|worker_key, sender| {
             if let Err(err) = sender.send(Ok(SubscribeResponse {
                 status: None,
                 operation: operation as i32,
                 info: Some(info.clone()),
-                version: self.current_version,
+                version,
             })) {
                 tracing::warn!("Failed to notify compute {:?}: {}", worker_key, err);
+                return false;
             }
-        }
+            true
+        });
 
         self.current_version
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::common::HostAddress;
+
+    use super::*;
+
+    fn worker_key(port: i32) -> WorkerKey {
+        WorkerKey(HostAddress {
+            host: "127.0.0.1".to_string(),
+            port,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_frontend_subscription() {
+        let manager = NotificationManager::new();
+        let (tx1, mut rx1) = mpsc::unbounded_channel();
+        manager
+            .insert_frontend_sender(worker_key(1), tx1, MetaSnapshot::default())
+            .await;
+        let version = manager
+            .notify_frontend(Operation::Add, Info::Database(Default::default()))
+            .await;
+
+        // The snapshot is followed by the notifications of larger versions.
+        let snapshot = rx1.recv().await.unwrap().unwrap();
+        assert_eq!(snapshot.operation(), Operation::Snapshot);
+        assert!(snapshot.version < version);
+        assert_eq!(rx1.recv().await.unwrap().unwrap().version, version);
+
+        // A frontend subscribing later gets a snapshot of the latest version.
+        let (tx2, mut rx2) = mpsc::unbounded_channel();
+        manager
+            .insert_frontend_sender(worker_key(2), tx2, MetaSnapshot::default())
+            .await;
+        assert_eq!(rx2.recv().await.unwrap().unwrap().version, version);
+
+        // The sender of a gone subscription is removed on the next notification.
+        drop(rx2);
+        let version = manager
+            .notify_frontend(Operation::Delete, Info::Database(Default::default()))
+            .await;
+        assert_eq!(rx1.recv().await.unwrap().unwrap().version, version);
+        assert_eq!(manager.core.lock().await.frontend_senders.len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::error::ErrorCode::InvalidParameterValue;
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::meta::SystemParams;
+use tokio::sync::{watch, Mutex, MutexGuard};
+
+use crate::manager::NotificationManagerRef;
+use crate::model::{MetadataModel, SYSTEM_PARAMS_KEY};
+use crate::storage::MetaStore;
+
+pub type SystemParamManagerRef<S> = Arc<SystemParamManager<S>>;
+
+/// `SystemParamManager` maintains the cluster-wide system parameters. They're persisted in the meta
+/// store when the cluster is bootstrapped, and the values stored take precedence over the ones
+/// given on later boots. Only the mutable parameters can be changed afterwards, of which every
+/// change is notified to the workers and the local subscribers.
+pub struct SystemParamManager<S: MetaStore> {
+    meta_store: Arc<S>,
+    notification_manager: NotificationManagerRef,
+    core: Mutex<SystemParams>,
+    /// Local subscribers inside meta, e.g., the barrier manager, watch the params through it.
+    params_tx: watch::Sender<SystemParams>,
+    params_rx: watch::Receiver<SystemParams>,
+}
+
+impl<S: MetaStore> SystemParamManager<S> {
+    pub async fn new(
+        meta_store: Arc<S>,
+        notification_manager: NotificationManagerRef,
+        init_params: SystemParams,
+    ) -> Result<Self> {
+        let params = match SystemParams::select(&*meta_store, &SYSTEM_PARAMS_KEY.to_string())
+            .await?
+        {
+            Some(params) => {
+                if params.block_cache_capacity_mb != init_params.block_cache_capacity_mb
+                    || params.state_store != init_params.state_store
+                {
+                    tracing::warn!(
+                        "Immutable system params differ from the ones of the cluster, use the latter: {:?}",
+                        params
+                    );
+                }
+                params
+            }
+            None => {
+                init_params.insert(&*meta_store).await?;
+                init_params
+            }
+        };
+        let (params_tx, params_rx) = watch::channel(params.clone());
+
+        Ok(Self {
+            meta_store,
+            notification_manager,
+            core: Mutex::new(params),
+            params_tx,
+            params_rx,
+        })
+    }
+
+    /// Used in `NotificationService::subscribe`.
+    /// Need to pay attention to the order of acquiring locks to prevent deadlock problems.
+    pub async fn get_params_guard(&self) -> MutexGuard<'_, SystemParams> {
+        self.core.lock().await
+    }
+
+    pub async fn get_params(&self) -> SystemParams {
+        self.core.lock().await.clone()
+    }
+
+    /// Watch the changes of the params.
+    pub fn watch_params(&self) -> watch::Receiver<SystemParams> {
+        self.params_rx.clone()
+    }
+
+    /// Set the mutable param `name` to `value`, and notify the workers if it succeeds.
+    pub async fn set_param(&self, name: &str, value: &str) -> Result<SystemParams> {
+        let mut core = self.core.lock().await;
+        let mut params = core.clone();
+        set_param(&mut params, name, value)?;
+        params.insert(&*self.meta_store).await?;
+        *core = params.clone();
+
+        // Never fails since `params_rx` is held.
+        self.params_tx.send(params.clone()).unwrap();
+        self.notification_manager
+            .notify_frontend(Operation::Update, Info::SystemParams(params.clone()))
+            .await;
+        self.notification_manager
+            .notify_compute(Operation::Update, Info::SystemParams(params.clone()))
+            .await;
+
+        Ok(params)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        RwError::from(InvalidParameterValue(format!(
+            "invalid value {} for system param {}",
+            value, name
+        )))
+    })
+}
+
+/// Validate the param and apply it to `params`.
+fn set_param(params: &mut SystemParams, name: &str, value: &str) -> Result<()> {
+    match name {
+        "barrier_interval_ms" => {
+            let interval: u32 = parse_value(name, value)?;
+            if interval == 0 {
+                return Err(RwError::from(InvalidParameterValue(
+                    "barrier_interval_ms should be positive".to_string(),
+                )));
+            }
+            params.barrier_interval_ms = interval;
+        }
+        "checkpoint_frequency" => {
+            let frequency: u64 = parse_value(name, value)?;
+            if frequency == 0 {
+                return Err(RwError::from(InvalidParameterValue(
+                    "checkpoint_frequency should be positive".to_string(),
+                )));
+            }
+            params.checkpoint_frequency = frequency;
+        }
+        "compaction_worker_threads_number" => {
+            params.compaction_worker_threads_number = parse_value(name, value)?;
+        }
+        "block_cache_capacity_mb" | "state_store" => {
+            return Err(RwError::from(InvalidParameterValue(format!(
+                "system param {} is immutable",
+                name
+            ))));
+        }
+        _ => {
+            return Err(RwError::from(InvalidParameterValue(format!(
+                "unrecognized system param {}",
+                name
+            ))));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::NotificationManager;
+    use crate::storage::MemStore;
+
+    fn init_params() -> SystemParams {
+        SystemParams {
+            barrier_interval_ms: 100,
+            block_cache_capacity_mb: 256,
+            state_store: "hummock+memory".to_string(),
+            compaction_worker_threads_number: 2,
+            checkpoint_frequency: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_params() -> Result<()> {
+        let meta_store = Arc::new(MemStore::default());
+        let notification_manager = Arc::new(NotificationManager::new());
+        let manager = SystemParamManager::new(
+            meta_store.clone(),
+            notification_manager.clone(),
+            init_params(),
+        )
+        .await?;
+        let mut rx = manager.watch_params();
+
+        let params = manager.set_param("barrier_interval_ms", "500").await?;
+        assert_eq!(params.barrier_interval_ms, 500);
+        rx.changed().await.unwrap();
+        assert_eq!(rx.borrow().barrier_interval_ms, 500);
+
+        assert!(manager.set_param("barrier_interval_ms", "0").await.is_err());
+        assert!(manager
+            .set_param("barrier_interval_ms", "abc")
+            .await
+            .is_err());
+        assert!(manager
+            .set_param("state_store", "hummock+minio")
+            .await
+            .is_err());
+        assert!(manager.set_param("no_such_param", "1").await.is_err());
+        assert!(manager
+            .set_param("checkpoint_frequency", "0")
+            .await
+            .is_err());
+        let params = manager.set_param("checkpoint_frequency", "10").await?;
+        assert_eq!(params.checkpoint_frequency, 10);
+        assert_eq!(manager.get_params().await.barrier_interval_ms, 500);
+
+        // The persisted params take precedence over the initial ones on restart.
+        let manager = SystemParamManager::new(
+            meta_store,
+            notification_manager,
+            SystemParams {
+                state_store: "hummock+minio".to_string(),
+                ..init_params()
+            },
+        )
+        .await?;
+        let params = manager.get_params().await;
+        assert_eq!(params.barrier_interval_ms, 500);
+        assert_eq!(params.state_store, "hummock+memory");
+
+        Ok(())
+    }
+}
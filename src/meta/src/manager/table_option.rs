@@ -0,0 +1,44 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_pb::hummock::TableOption;
+
+use super::TableId;
+
+pub type TableOptionManagerRef = Arc<TableOptionManager>;
+
+/// `TableOptionManager` maintains the options of state tables, which are sent to compactors along
+/// with the compaction tasks involving the tables.
+#[derive(Default)]
+pub struct TableOptionManager {
+    table_options: Mutex<HashMap<TableId, TableOption>>,
+}
+
+impl TableOptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_table_option(&self, table_id: TableId, table_option: TableOption) {
+        self.table_options.lock().insert(table_id, table_option);
+    }
+
+    pub fn get_table_option(&self, table_id: &TableId) -> Option<TableOption> {
+        self.table_options.lock().get(table_id).cloned()
+    }
+}
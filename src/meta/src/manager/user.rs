@@ -13,14 +13,15 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use risingwave_common::catalog::{DEFAULT_SUPPER_USER, DEFAULT_SUPPER_USER_PASSWORD};
+use risingwave_common::catalog::{CatalogVersion, DEFAULT_SUPPER_USER};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
-use risingwave_pb::user::auth_info::EncryptionType;
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use risingwave_pb::user::grant_privilege::{PrivilegeWithGrantOption, Target};
-use risingwave_pb::user::{AuthInfo, GrantPrivilege, UserInfo};
-use tokio::sync::Mutex;
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
+use tokio::sync::{Mutex, MutexGuard};
 
 use crate::manager::MetaSrvEnv;
 use crate::model::{MetadataModel, Transactional};
@@ -29,9 +30,12 @@ use crate::storage::{MetaStore, Transaction};
 /// We use `UserName` as the key for the user info.
 type UserName = String;
 
+pub type UserManagerRef<S> = Arc<UserManager<S>>;
+
 /// `UserManager` managers the user info, including authentication and privileges. It only responds
 /// to manager the user info and some basic validation. Other authorization relate to the current
-/// session user should be done in Frontend before passing to Meta.
+/// session user should be done in Frontend before passing to Meta. Every change is notified to the
+/// frontends.
 pub struct UserManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     core: Mutex<HashMap<UserName, UserInfo>>,
@@ -58,10 +62,6 @@ impl<S: MetaStore> UserManager<S> {
                 is_supper: true,
                 can_create_db: true,
                 can_login: true,
-                auth_info: Some(AuthInfo {
-                    encryption_type: EncryptionType::Plaintext as i32,
-                    encrypted_value: Vec::from(DEFAULT_SUPPER_USER_PASSWORD.as_bytes()),
-                }),
                 ..Default::default()
             };
 
@@ -72,12 +72,25 @@ impl<S: MetaStore> UserManager<S> {
         Ok(())
     }
 
+    /// Used in `NotificationService::subscribe`.
+    /// Need to pay attention to the order of acquiring locks to prevent deadlock problems.
+    pub async fn get_user_core_guard(&self) -> MutexGuard<'_, HashMap<UserName, UserInfo>> {
+        self.core.lock().await
+    }
+
     pub async fn list_users(&self) -> Result<Vec<UserInfo>> {
         let core = self.core.lock().await;
         Ok(core.values().cloned().collect())
     }
 
-    pub async fn create_user(&self, user: &UserInfo) -> Result<()> {
+    async fn notify_frontend(&self, operation: Operation, user: UserInfo) -> CatalogVersion {
+        self.env
+            .notification_manager()
+            .notify_frontend(operation, Info::User(user))
+            .await
+    }
+
+    pub async fn create_user(&self, user: &UserInfo) -> Result<CatalogVersion> {
         let mut core = self.core.lock().await;
         if core.contains_key(&user.name) {
             return Err(RwError::from(InternalError(format!(
@@ -87,8 +100,9 @@ impl<S: MetaStore> UserManager<S> {
         }
         user.insert(self.env.meta_store()).await?;
         core.insert(user.name.clone(), user.clone());
-        // TODO: add notification support.
-        Ok(())
+
+        let version = self.notify_frontend(Operation::Add, user.clone()).await;
+        Ok(version)
     }
 
     pub async fn get_user(&self, user_name: &UserName) -> Result<UserInfo> {
@@ -99,7 +113,7 @@ impl<S: MetaStore> UserManager<S> {
             .ok_or_else(|| RwError::from(InternalError(format!("User {} not found", user_name))))
     }
 
-    pub async fn drop_user(&self, user_name: &UserName) -> Result<()> {
+    pub async fn drop_user(&self, user_name: &UserName) -> Result<CatalogVersion> {
         let mut core = self.core.lock().await;
         if !core.contains_key(user_name) {
             return Err(RwError::from(InternalError(format!(
@@ -122,10 +136,10 @@ impl<S: MetaStore> UserManager<S> {
 
         // TODO: add more check, like whether he owns any database/schema/table/source.
         UserInfo::delete(self.env.meta_store(), user_name).await?;
-        core.remove(user_name);
+        let user = core.remove(user_name).unwrap();
 
-        // TODO: add notification support.
-        Ok(())
+        let version = self.notify_frontend(Operation::Delete, user).await;
+        Ok(version)
     }
 }
 
@@ -162,7 +176,7 @@ impl<S: MetaStore> UserManager<S> {
         &self,
         user_name: &UserName,
         new_grant_privileges: &[GrantPrivilege],
-    ) -> Result<()> {
+    ) -> Result<CatalogVersion> {
         let mut core = self.core.lock().await;
         let mut user = core
             .get(user_name)
@@ -189,8 +203,10 @@ impl<S: MetaStore> UserManager<S> {
         });
 
         user.insert(self.env.meta_store()).await?;
-        core.insert(user_name.clone(), user);
-        Ok(())
+        core.insert(user_name.clone(), user.clone());
+
+        let version = self.notify_frontend(Operation::Update, user).await;
+        Ok(version)
     }
 
     // Revoke privilege from target.
@@ -232,7 +248,7 @@ impl<S: MetaStore> UserManager<S> {
         user_name: &UserName,
         revoke_grant_privileges: &[GrantPrivilege],
         revoke_grant_option: bool,
-    ) -> Result<()> {
+    ) -> Result<CatalogVersion> {
         let mut core = self.core.lock().await;
         let mut user = core
             .get(user_name)
@@ -269,8 +285,10 @@ impl<S: MetaStore> UserManager<S> {
         }
 
         user.insert(self.env.meta_store()).await?;
-        core.insert(user_name.clone(), user);
-        Ok(())
+        core.insert(user_name.clone(), user.clone());
+
+        let version = self.notify_frontend(Operation::Update, user).await;
+        Ok(version)
     }
 
     /// `release_privileges` removes the privileges with given target from all users, it will be
@@ -291,7 +309,8 @@ impl<S: MetaStore> UserManager<S> {
         }
         self.env.meta_store().txn(transaction).await?;
         for user in users_need_update {
-            core.insert(user.name.clone(), user);
+            core.insert(user.name.clone(), user.clone());
+            self.notify_frontend(Operation::Update, user).await;
         }
 
         Ok(())
@@ -16,6 +16,7 @@ mod barrier;
 mod catalog;
 mod cluster;
 mod stream;
+mod system_param;
 mod user;
 
 use std::collections::BTreeMap;
@@ -28,6 +29,7 @@ pub use cluster::*;
 use prost::Message;
 use risingwave_common::error::Result;
 pub use stream::*;
+pub use system_param::*;
 pub use user::*;
 
 use crate::storage::{self, MetaStore, Transaction};
@@ -12,18 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::hash::{VIRTUAL_NODE_COUNT, VNODE_BITMAP_LEN};
+use risingwave_common::util::bit_util::set_bit;
+use risingwave_common::util::compress::compress_data;
+use risingwave_pb::common::{ParallelUnit, ParallelUnitMapping};
+use risingwave_pb::hummock::TableOption;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::{FragmentType, StreamActor, StreamNode};
+use risingwave_pb::stream_plan::{
+    ActorMapping, DispatcherType, FragmentType, SourceNode, StreamActor, StreamNode,
+    StreamSourceState,
+};
 
-use super::{ActorId, FragmentId};
+use super::{ActorId, DispatcherId, FragmentId};
 use crate::cluster::{ParallelUnitId, WorkerId};
 use crate::manager::SourceId;
 use crate::model::MetadataModel;
@@ -45,6 +53,9 @@ pub struct TableFragments {
 
     /// The status of actors
     actor_status: BTreeMap<ActorId, ActorStatus>,
+
+    /// The options applied to the state tables.
+    table_option: TableOption,
 }
 
 impl MetadataModel for TableFragments {
@@ -60,6 +71,7 @@ impl MetadataModel for TableFragments {
             table_id: self.table_id.table_id(),
             fragments: self.fragments.clone().into_iter().collect(),
             actor_status: self.actor_status.clone().into_iter().collect(),
+            table_option: Some(self.table_option.clone()),
         }
     }
 
@@ -68,6 +80,7 @@ impl MetadataModel for TableFragments {
             table_id: TableId::new(prost.table_id),
             fragments: prost.fragments.into_iter().collect(),
             actor_status: prost.actor_status.into_iter().collect(),
+            table_option: prost.table_option.unwrap_or_default(),
         }
     }
 
@@ -77,11 +90,19 @@ impl MetadataModel for TableFragments {
 }
 
 impl TableFragments {
-    pub fn new(table_id: TableId, fragments: BTreeMap<FragmentId, Fragment>) -> Self {
+    pub fn new(table_id: TableId, mut fragments: BTreeMap<FragmentId, Fragment>) -> Self {
+        // Tag the actors with the table they belong to, so that their metrics can be grouped by it.
+        for actor in fragments
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+        {
+            actor.table_id = table_id.table_id();
+        }
         Self {
             table_id,
             fragments,
             actor_status: BTreeMap::default(),
+            table_option: TableOption::default(),
         }
     }
 
@@ -94,6 +115,15 @@ impl TableFragments {
         self.actor_status = actor_status;
     }
 
+    /// Set the options applied to the state tables.
+    pub fn set_table_option(&mut self, table_option: TableOption) {
+        self.table_option = table_option;
+    }
+
+    pub fn table_option(&self) -> &TableOption {
+        &self.table_option
+    }
+
     /// Returns the table id.
     pub fn table_id(&self) -> TableId {
         self.table_id
@@ -106,6 +136,275 @@ impl TableFragments {
         }
     }
 
+    /// Whether all actors are running, i.e., the materialized view has been created.
+    pub fn is_created(&self) -> bool {
+        self.actor_status
+            .values()
+            .all(|status| status.state == ActorState::Running as i32)
+    }
+
+    /// Update the splits assigned to the given stream source actors.
+    pub fn update_actor_splits(&mut self, actor_splits: &HashMap<ActorId, StreamSourceState>) {
+        for fragment in self.fragments.values_mut() {
+            for actor in &mut fragment.actors {
+                if let Some(splits) = actor_splits.get(&actor.actor_id) {
+                    if let Some(source) =
+                        Self::stream_source_node_mut(actor.nodes.as_mut().unwrap())
+                    {
+                        source.stream_source_state = Some(splits.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves the actors on the parallel units in `migrate_map` to the mapped ones, which are also
+    /// substituted in the vnode mappings of the fragments. Returns whether any actor is moved.
+    pub fn migrate_actors(&mut self, migrate_map: &HashMap<ParallelUnitId, ParallelUnit>) -> bool {
+        let mut migrated = false;
+        for actor_status in self.actor_status.values_mut() {
+            let parallel_unit = actor_status.parallel_unit.as_mut().unwrap();
+            if let Some(new_parallel_unit) = migrate_map.get(&parallel_unit.id) {
+                *parallel_unit = new_parallel_unit.clone();
+                migrated = true;
+            }
+        }
+        if migrated {
+            for fragment in self.fragments.values_mut() {
+                if let Some(vnode_mapping) = fragment.vnode_mapping.as_mut() {
+                    for parallel_unit_id in &mut vnode_mapping.data {
+                        if let Some(new_parallel_unit) = migrate_map.get(parallel_unit_id) {
+                            *parallel_unit_id = new_parallel_unit.id;
+                        }
+                    }
+                }
+            }
+        }
+        migrated
+    }
+
+    /// Returns the parallel units that the actors are on.
+    pub fn actor_parallel_units(&self) -> impl Iterator<Item = &ParallelUnit> + '_ {
+        self.actor_status
+            .values()
+            .map(|actor_status| actor_status.get_parallel_unit().unwrap())
+    }
+
+    /// Update the vnode bitmaps of the given actors, and the mappings of the hash dispatchers
+    /// routing to them. The vnode mappings of the fragments are rebuilt from the hash mappings and
+    /// the locations of the actors, and returned.
+    pub fn update_vnode_bitmaps(
+        &mut self,
+        actor_vnode_bitmaps: &HashMap<ActorId, Vec<u8>>,
+        dispatcher_hash_mappings: &HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+    ) -> HashMap<FragmentId, Vec<ParallelUnitId>> {
+        let actor_fragment_ids = self.actor_fragment_ids();
+        let mut fragment_vnode_mappings = HashMap::new();
+        for fragment in self.fragments.values_mut() {
+            for actor in &mut fragment.actors {
+                if let Some(bitmap) = actor_vnode_bitmaps.get(&actor.actor_id) {
+                    actor.vnode_bitmap = bitmap.clone();
+                }
+                for dispatcher in &mut actor.dispatcher {
+                    let hash_mapping = match dispatcher_hash_mappings
+                        .get(&(actor.actor_id, dispatcher.dispatcher_id))
+                    {
+                        Some(hash_mapping) => hash_mapping,
+                        None => continue,
+                    };
+                    let (original_indices, data) = compress_data(hash_mapping);
+                    dispatcher.hash_mapping = Some(ActorMapping {
+                        original_indices,
+                        data,
+                    });
+
+                    let vnode_mapping = hash_mapping
+                        .iter()
+                        .map(|actor_id| self.actor_status[actor_id].get_parallel_unit().unwrap().id)
+                        .collect_vec();
+                    fragment_vnode_mappings
+                        .insert(actor_fragment_ids[&hash_mapping[0]], vnode_mapping);
+                }
+            }
+        }
+
+        for (fragment_id, vnode_mapping) in &fragment_vnode_mappings {
+            let (original_indices, data) = compress_data(vnode_mapping);
+            let fragment = self.fragments.get_mut(fragment_id).unwrap();
+            fragment.vnode_mapping = Some(ParallelUnitMapping {
+                original_indices,
+                data,
+                ..fragment.vnode_mapping.clone().unwrap_or_default()
+            });
+        }
+        fragment_vnode_mappings
+    }
+
+    /// Reassigns the vnodes of the fragments that hash dispatchers route to, so that each of them
+    /// is spread over its first `parallelism` actors in contiguous ranges. The other actors keep
+    /// running without vnodes, so no data will be routed to them.
+    ///
+    /// Returns the new vnode bitmaps of the actors, and the new mappings of the hash dispatchers
+    /// routing to them keyed by the upstream actor and the dispatcher.
+    #[allow(clippy::type_complexity)]
+    pub fn reassign_vnodes(
+        &self,
+        parallelism: usize,
+    ) -> Result<(
+        HashMap<ActorId, Vec<u8>>,
+        HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+    )> {
+        if parallelism == 0 {
+            return Err(ErrorCode::InvalidParameterValue(
+                "parallelism must be positive".to_string(),
+            )
+            .into());
+        }
+
+        // Find out the fragments that hash dispatchers route to.
+        let actor_fragment_ids = self.actor_fragment_ids();
+        let hash_dispatchers = self
+            .fragments
+            .values()
+            .flat_map(|fragment| &fragment.actors)
+            .flat_map(|actor| {
+                actor
+                    .dispatcher
+                    .iter()
+                    .filter(|dispatcher| dispatcher.r#type == DispatcherType::Hash as i32)
+                    .map(move |dispatcher| (actor.actor_id, dispatcher))
+            })
+            .collect_vec();
+        let target_fragment_ids: BTreeSet<_> = hash_dispatchers
+            .iter()
+            .map(|(_, dispatcher)| actor_fragment_ids[&dispatcher.downstream_actor_id[0]])
+            .collect();
+        if target_fragment_ids.is_empty() {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "materialized view {} has no hash distributed fragment to rescale",
+                self.table_id
+            ))
+            .into());
+        }
+
+        let mut actor_vnode_bitmaps = HashMap::new();
+        let mut fragment_hash_mappings = HashMap::new();
+        for fragment_id in target_fragment_ids {
+            let fragment = &self.fragments[&fragment_id];
+            for actor in &fragment.actors {
+                if let Some(node) = Self::find_unscalable_node(actor.get_nodes()?) {
+                    return Err(ErrorCode::NotImplemented(
+                        format!("rescaling {}", node.identity),
+                        None.into(),
+                    )
+                    .into());
+                }
+            }
+            if parallelism > fragment.actors.len() {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "parallelism {} exceeds the {} actors of fragment {}",
+                    parallelism,
+                    fragment.actors.len(),
+                    fragment_id
+                ))
+                .into());
+            }
+
+            let actor_ids = fragment
+                .actors
+                .iter()
+                .map(|actor| actor.actor_id)
+                .sorted()
+                .collect_vec();
+            let hash_mapping = (0..VIRTUAL_NODE_COUNT)
+                .map(|vnode| actor_ids[vnode * parallelism / VIRTUAL_NODE_COUNT])
+                .collect_vec();
+            for actor_id in &actor_ids {
+                actor_vnode_bitmaps.insert(*actor_id, vec![0; VNODE_BITMAP_LEN]);
+            }
+            for (vnode, actor_id) in hash_mapping.iter().enumerate() {
+                set_bit(actor_vnode_bitmaps.get_mut(actor_id).unwrap(), vnode);
+            }
+            fragment_hash_mappings.insert(fragment_id, hash_mapping);
+        }
+
+        let dispatcher_hash_mappings = hash_dispatchers
+            .into_iter()
+            .map(|(actor_id, dispatcher)| {
+                let fragment_id = actor_fragment_ids[&dispatcher.downstream_actor_id[0]];
+                (
+                    (actor_id, dispatcher.dispatcher_id),
+                    fragment_hash_mappings[&fragment_id].clone(),
+                )
+            })
+            .collect();
+
+        Ok((actor_vnode_bitmaps, dispatcher_hash_mappings))
+    }
+
+    /// Returns the fragment id of each actor.
+    fn actor_fragment_ids(&self) -> HashMap<ActorId, FragmentId> {
+        self.fragments
+            .values()
+            .flat_map(|fragment| {
+                fragment
+                    .actors
+                    .iter()
+                    .map(|actor| (actor.actor_id, fragment.fragment_id))
+            })
+            .collect()
+    }
+
+    /// Returns the first node whose executor can't follow vnode reassignment yet, either because
+    /// it caches states of other vnodes or because its actor is colocated with the upstream.
+    fn find_unscalable_node(stream_node: &StreamNode) -> Option<&StreamNode> {
+        if matches!(
+            stream_node.node_body,
+            Some(
+                NodeBody::HashJoin(_)
+                    | NodeBody::TopN(_)
+                    | NodeBody::AppendOnlyTopN(_)
+                    | NodeBody::Lookup(_)
+                    | NodeBody::Arrange(_)
+                    | NodeBody::LookupUnion(_)
+                    | NodeBody::DeltaIndexJoin(_)
+                    | NodeBody::Chain(_)
+                    | NodeBody::BatchPlan(_)
+                    | NodeBody::LocalExchange(_)
+                    | NodeBody::Source(_)
+            )
+        ) {
+            return Some(stream_node);
+        }
+
+        stream_node
+            .input
+            .iter()
+            .find_map(Self::find_unscalable_node)
+    }
+
+    /// Returns the splits assigned to the stream source actors of each fragment that reads from a
+    /// source, along with the source id.
+    pub fn stream_source_fragments(
+        &self,
+    ) -> Vec<(SourceId, BTreeMap<ActorId, Option<StreamSourceState>>)> {
+        let mut source_fragments = vec![];
+        for fragment in self.fragments.values() {
+            let mut source_id = None;
+            let mut actor_splits = BTreeMap::new();
+            for actor in &fragment.actors {
+                if let Some(source) = Self::stream_source_node(actor.nodes.as_ref().unwrap()) {
+                    source_id = Some(source.table_ref_id.as_ref().unwrap().table_id as SourceId);
+                    actor_splits.insert(actor.actor_id, source.stream_source_state.clone());
+                }
+            }
+            if let Some(source_id) = source_id {
+                source_fragments.push((source_id, actor_splits));
+            }
+        }
+        source_fragments
+    }
+
     /// Returns actor ids associated with this table.
     pub fn actor_ids(&self) -> Vec<ActorId> {
         self.fragments
@@ -155,6 +454,29 @@ impl TableFragments {
         false
     }
 
+    fn stream_source_node(stream_node: &StreamNode) -> Option<&SourceNode> {
+        if let Some(NodeBody::Source(s)) = stream_node.node_body.as_ref() {
+            if s.source_type == SourceType::Source as i32 {
+                return Some(s);
+            }
+        }
+
+        stream_node.input.iter().find_map(Self::stream_source_node)
+    }
+
+    fn stream_source_node_mut(stream_node: &mut StreamNode) -> Option<&mut SourceNode> {
+        if let Some(NodeBody::Source(s)) = stream_node.node_body.as_mut() {
+            if s.source_type == SourceType::Source as i32 {
+                return Some(s);
+            }
+        }
+
+        stream_node
+            .input
+            .iter_mut()
+            .find_map(Self::stream_source_node_mut)
+    }
+
     pub fn fetch_stream_source_id(stream_node: &StreamNode) -> Option<SourceId> {
         if let Some(NodeBody::Source(s)) = stream_node.node_body.as_ref() {
             if s.source_type == SourceType::Source as i32 {
@@ -185,28 +507,6 @@ impl TableFragments {
             .collect()
     }
 
-    /// Resolve dependent table
-    fn resolve_dependent_table(stream_node: &StreamNode, table_ids: &mut HashSet<TableId>) {
-        if let Some(NodeBody::Chain(chain)) = stream_node.node_body.as_ref() {
-            table_ids.insert(TableId::from(&chain.table_ref_id));
-        }
-
-        for child in &stream_node.input {
-            Self::resolve_dependent_table(child, table_ids);
-        }
-    }
-
-    /// Returns dependent table ids.
-    pub fn dependent_table_ids(&self) -> HashSet<TableId> {
-        let mut table_ids = HashSet::new();
-        self.fragments.values().for_each(|fragment| {
-            let actor = &fragment.actors[0];
-            Self::resolve_dependent_table(actor.nodes.as_ref().unwrap(), &mut table_ids);
-        });
-
-        table_ids
-    }
-
     /// Returns states of actors group by node id.
     pub fn node_actor_states(&self) -> BTreeMap<WorkerId, Vec<(ActorId, ActorState)>> {
         let mut map = BTreeMap::default();
@@ -288,6 +588,80 @@ impl TableFragments {
             .collect()
     }
 
+    /// Whether this is a source job, which only consists of a source fragment broadcasting the
+    /// messages of a stream source to the materialized views on it through the cross-MV
+    /// dispatcher.
+    pub fn is_source_job(&self) -> bool {
+        self.fragments.len() == 1
+            && self.fragments.values().all(|fragment| {
+                fragment.fragment_type == FragmentType::Source as i32
+                    && fragment.actors.iter().all(|actor| {
+                        matches!(
+                            actor.nodes.as_ref().unwrap().node_body.as_ref(),
+                            Some(NodeBody::Source(s)) if s.source_type == SourceType::Source as i32
+                        ) && matches!(
+                            actor.dispatcher.as_slice(),
+                            [dispatcher] if dispatcher.dispatcher_id == 0
+                                && dispatcher.r#type == DispatcherType::Broadcast as i32
+                        )
+                    })
+            })
+    }
+
+    /// Whether this is a source job with no materialized view reading from it any more.
+    pub fn is_orphaned_source_job(&self) -> bool {
+        self.is_source_job()
+            && self
+                .actors()
+                .iter()
+                .all(|actor| actor.dispatcher[0].downstream_actor_id.is_empty())
+    }
+
+    /// Returns the actors grouped by node id, if this is a running source job reading the same
+    /// stream source with the same columns as `source_node`.
+    pub fn shared_source_node_actor_ids(
+        &self,
+        source_node: &SourceNode,
+    ) -> Option<BTreeMap<WorkerId, Vec<ActorId>>> {
+        if !self.is_source_job() {
+            return None;
+        }
+        let is_shareable = |actor: &StreamActor| {
+            let reads_same_source = matches!(
+                actor.nodes.as_ref().unwrap().node_body.as_ref(),
+                Some(NodeBody::Source(s)) if s.table_ref_id == source_node.table_ref_id
+                    && s.column_ids == source_node.column_ids
+            );
+            reads_same_source
+                && self.actor_status[&actor.actor_id].state == ActorState::Running as i32
+        };
+        if !self.actors().iter().all(is_shareable) {
+            return None;
+        }
+
+        Some(self.node_actor_ids())
+    }
+
+    /// Splits the fragment off into a new table fragments with the given table id, together with
+    /// the status of its actors.
+    pub fn split_off_fragment(&mut self, fragment_id: FragmentId, table_id: TableId) -> Self {
+        let fragment = self.fragments.remove(&fragment_id).unwrap();
+        let actor_status = fragment
+            .actors
+            .iter()
+            .map(|actor| {
+                (
+                    actor.actor_id,
+                    self.actor_status.remove(&actor.actor_id).unwrap(),
+                )
+            })
+            .collect();
+
+        let mut table_fragments = Self::new(table_id, BTreeMap::from([(fragment_id, fragment)]));
+        table_fragments.set_actor_status(actor_status);
+        table_fragments
+    }
+
     /// Generate toplogical order of fragments. If `index(a) < index(b)` in vec, then a is the
     /// downstream of b.
     pub fn generate_topological_order(&self) -> Vec<FragmentId> {
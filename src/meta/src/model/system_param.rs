@@ -0,0 +1,45 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::meta::SystemParams;
+
+use crate::model::MetadataModel;
+
+/// Column family name for system params.
+const SYSTEM_PARAMS_CF_NAME: &str = "cf/system_params";
+
+/// The key of the single `SystemParams` record.
+pub const SYSTEM_PARAMS_KEY: &str = "system_params";
+
+/// `SystemParams` stores the cluster-wide system parameters.
+impl MetadataModel for SystemParams {
+    type KeyType = String;
+    type ProstType = SystemParams;
+
+    fn cf_name() -> String {
+        SYSTEM_PARAMS_CF_NAME.to_string()
+    }
+
+    fn to_protobuf(&self) -> Self::ProstType {
+        self.clone()
+    }
+
+    fn from_protobuf(prost: Self::ProstType) -> Self {
+        prost
+    }
+
+    fn key(&self) -> risingwave_common::error::Result<Self::KeyType> {
+        Ok(SYSTEM_PARAMS_KEY.to_string())
+    }
+}
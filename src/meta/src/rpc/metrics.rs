@@ -58,6 +58,8 @@ pub struct MetaMetrics {
     pub level_compact_frequency: IntCounterVec,
     /// hummock version size
     pub version_size: IntGauge,
+    /// min version id pinned by any context
+    pub min_pinned_version_id: IntGauge,
 }
 
 impl MetaMetrics {
@@ -171,6 +173,13 @@ impl MetaMetrics {
         let version_size =
             register_int_gauge_with_registry!("version_size", "version size", registry).unwrap();
 
+        let min_pinned_version_id = register_int_gauge_with_registry!(
+            "storage_min_pinned_version_id",
+            "min version id pinned by any context",
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
 
@@ -189,6 +198,7 @@ impl MetaMetrics {
             level_compact_write_sstn,
             level_compact_frequency,
             version_size,
+            min_pinned_version_id,
         }
     }
 
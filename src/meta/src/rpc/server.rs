@@ -25,6 +25,7 @@ use risingwave_pb::meta::cluster_service_server::ClusterServiceServer;
 use risingwave_pb::meta::heartbeat_service_server::HeartbeatServiceServer;
 use risingwave_pb::meta::notification_service_server::NotificationServiceServer;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerServiceServer;
+use risingwave_pb::meta::system_params_service_server::SystemParamsServiceServer;
 use risingwave_pb::user::user_service_server::UserServiceServer;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
@@ -37,12 +38,13 @@ use crate::cluster::ClusterManager;
 use crate::dashboard::DashboardService;
 use crate::hummock;
 use crate::hummock::CompactionScheduler;
-use crate::manager::{CatalogManager, MetaOpts, MetaSrvEnv, UserManager};
+use crate::manager::{CatalogManager, DdlJobManager, MetaOpts, MetaSrvEnv, UserManager};
 use crate::rpc::metrics::MetaMetrics;
 use crate::rpc::service::cluster_service::ClusterServiceImpl;
 use crate::rpc::service::heartbeat_service::HeartbeatServiceImpl;
 use crate::rpc::service::hummock_service::HummockServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
+use crate::rpc::service::system_params_service::SystemParamsServiceImpl;
 use crate::rpc::service::user_service::UserServiceImpl;
 use crate::storage::{EtcdMetaStore, MemStore, MetaStore};
 use crate::stream::{FragmentManager, GlobalStreamManager, SourceManager};
@@ -127,19 +129,8 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .unwrap(),
     );
 
-    if let Some(dashboard_addr) = dashboard_addr {
-        let dashboard_service = DashboardService {
-            dashboard_addr,
-            cluster_manager: cluster_manager.clone(),
-            fragment_manager: fragment_manager.clone(),
-            meta_store: env.meta_store_ref(),
-        };
-        // TODO: join dashboard service back to local thread.
-        tokio::spawn(dashboard_service.serve(ui_path));
-    }
-
     let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await.unwrap());
-    let user_manager = UserManager::new(env.clone()).await.unwrap();
+    let user_manager = Arc::new(UserManager::new(env.clone()).await.unwrap());
 
     let barrier_manager = Arc::new(GlobalBarrierManager::new(
         env.clone(),
@@ -149,6 +140,21 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         hummock_manager.clone(),
         meta_metrics.clone(),
     ));
+    let ddl_job_manager = Arc::new(DdlJobManager::new());
+
+    if let Some(dashboard_addr) = dashboard_addr {
+        let dashboard_service = DashboardService {
+            dashboard_addr,
+            cluster_manager: cluster_manager.clone(),
+            fragment_manager: fragment_manager.clone(),
+            barrier_manager: barrier_manager.clone(),
+            hummock_manager: hummock_manager.clone(),
+            ddl_job_manager: ddl_job_manager.clone(),
+            meta_store: env.meta_store_ref(),
+        };
+        // TODO: join dashboard service back to local thread.
+        tokio::spawn(dashboard_service.serve(ui_path));
+    }
 
     let source_manager = Arc::new(
         SourceManager::new(
@@ -156,6 +162,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             cluster_manager.clone(),
             barrier_manager.clone(),
             catalog_manager.clone(),
+            fragment_manager.clone(),
         )
         .await
         .unwrap(),
@@ -197,10 +204,13 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         source_manager,
         cluster_manager.clone(),
         fragment_manager.clone(),
+        ddl_job_manager,
     );
-    let user_srv = UserServiceImpl::<S>::new(catalog_manager.clone(), user_manager);
-    let cluster_srv = ClusterServiceImpl::<S>::new(cluster_manager.clone());
-    let stream_srv = StreamServiceImpl::<S>::new(stream_manager);
+    let user_srv = UserServiceImpl::<S>::new(catalog_manager.clone(), user_manager.clone());
+    let system_params_srv = SystemParamsServiceImpl::<S>::new(env.system_param_manager_ref());
+    let cluster_srv =
+        ClusterServiceImpl::<S>::new(cluster_manager.clone(), barrier_manager.clone());
+    let stream_srv = StreamServiceImpl::<S>::new(stream_manager, fragment_manager.clone());
     let hummock_srv = HummockServiceImpl::new(
         hummock_manager.clone(),
         compactor_manager.clone(),
@@ -208,7 +218,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     );
     let notification_manager = env.notification_manager_ref();
     let notification_srv =
-        NotificationServiceImpl::new(env, catalog_manager, cluster_manager.clone());
+        NotificationServiceImpl::new(env, catalog_manager, cluster_manager.clone(), user_manager);
 
     if let Some(prometheus_addr) = prometheus_addr {
         meta_metrics.boot_metrics_service(prometheus_addr);
@@ -244,6 +254,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .add_service(NotificationServiceServer::new(notification_srv))
             .add_service(DdlServiceServer::new(ddl_srv))
             .add_service(UserServiceServer::new(user_srv))
+            .add_service(SystemParamsServiceServer::new(system_params_srv))
             .serve_with_shutdown(addr, async move {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {},
@@ -14,28 +14,37 @@
 
 use risingwave_common::error::tonic_err;
 use risingwave_common::try_match_expand;
+use risingwave_pb::common::WorkerType;
 use risingwave_pb::meta::cluster_service_server::ClusterService;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
-    AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, ListAllNodesRequest,
-    ListAllNodesResponse,
+    AddWorkerNodeResponse, CordonWorkerNodeRequest, CordonWorkerNodeResponse,
+    DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, ListAllNodesRequest, ListAllNodesResponse,
 };
 use tonic::{Request, Response, Status};
 
+use crate::barrier::BarrierManagerRef;
 use crate::cluster::ClusterManagerRef;
 use crate::storage::MetaStore;
 
 #[derive(Clone)]
 pub struct ClusterServiceImpl<S: MetaStore> {
     cluster_manager: ClusterManagerRef<S>,
+    barrier_manager: BarrierManagerRef<S>,
 }
 
 impl<S> ClusterServiceImpl<S>
 where
     S: MetaStore,
 {
-    pub fn new(cluster_manager: ClusterManagerRef<S>) -> Self {
-        ClusterServiceImpl { cluster_manager }
+    pub fn new(
+        cluster_manager: ClusterManagerRef<S>,
+        barrier_manager: BarrierManagerRef<S>,
+    ) -> Self {
+        ClusterServiceImpl {
+            cluster_manager,
+            barrier_manager,
+        }
     }
 }
 
@@ -53,7 +62,7 @@ where
         let host = try_match_expand!(req.host, Some, "AddWorkerNodeRequest::host is empty")?;
         let (worker_node, _added) = self
             .cluster_manager
-            .add_worker_node(host, worker_type)
+            .add_worker_node(host, worker_type, req.worker_node_parallelism as usize)
             .await?;
         Ok(Response::new(AddWorkerNodeResponse {
             status: None,
@@ -76,7 +85,14 @@ where
         request: Request<DeleteWorkerNodeRequest>,
     ) -> Result<Response<DeleteWorkerNodeResponse>, Status> {
         let req = request.into_inner();
-        let host = try_match_expand!(req.host, Some, "ActivateWorkerNodeRequest::host is empty")?;
+        let host = try_match_expand!(req.host, Some, "DeleteWorkerNodeRequest::host is empty")?;
+        // Move the actors off a cordoned compute node before it leaves, otherwise the next barrier
+        // fails on it.
+        if let Some(worker) = self.cluster_manager.get_worker_by_host(host.clone()).await {
+            if worker.worker_type() == WorkerType::ComputeNode && worker.worker_node.unschedulable {
+                self.barrier_manager.migrate_cordoned_actors().await?;
+            }
+        }
         self.cluster_manager.delete_worker_node(host).await?;
         Ok(Response::new(DeleteWorkerNodeResponse { status: None }))
     }
@@ -101,4 +117,16 @@ where
             nodes: node_list,
         }))
     }
+
+    async fn cordon_worker_node(
+        &self,
+        request: Request<CordonWorkerNodeRequest>,
+    ) -> Result<Response<CordonWorkerNodeResponse>, Status> {
+        let req = request.into_inner();
+        let host = try_match_expand!(req.host, Some, "CordonWorkerNodeRequest::host is empty")?;
+        self.cluster_manager
+            .set_worker_unschedulable(host, req.unschedulable)
+            .await?;
+        Ok(Response::new(CordonWorkerNodeResponse { status: None }))
+    }
 }
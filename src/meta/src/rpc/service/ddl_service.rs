@@ -14,20 +14,24 @@
 
 use std::collections::HashSet;
 
-use risingwave_common::catalog::CatalogVersion;
-use risingwave_common::error::{tonic_err, Result as RwResult};
+use risingwave_common::catalog::{CatalogVersion, PROPERTIES_TTL_KEY};
+use risingwave_common::error::{tonic_err, ErrorCode, Result as RwResult};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::*;
 use risingwave_pb::common::ParallelUnitType;
 use risingwave_pb::ddl_service::ddl_service_server::DdlService;
 use risingwave_pb::ddl_service::*;
+use risingwave_pb::hummock::TableOption;
 use risingwave_pb::plan_common::TableRefId;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{StreamFragmentGraph, StreamNode};
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
-use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SourceId, TableId};
+use crate::manager::{
+    CatalogManagerRef, DdlJobGuard, DdlJobManagerRef, DependentRelation, IdCategory, MetaSrvEnv,
+    RelationId, SourceId, TableId,
+};
 use crate::model::TableFragments;
 use crate::storage::MetaStore;
 use crate::stream::{
@@ -43,6 +47,7 @@ pub struct DdlServiceImpl<S: MetaStore> {
     source_manager: SourceManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
     fragment_manager: FragmentManagerRef<S>,
+    ddl_job_manager: DdlJobManagerRef,
 }
 
 impl<S> DdlServiceImpl<S>
@@ -56,6 +61,7 @@ where
         source_manager: SourceManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         fragment_manager: FragmentManagerRef<S>,
+        ddl_job_manager: DdlJobManagerRef,
     ) -> Self {
         Self {
             env,
@@ -64,6 +70,7 @@ where
             source_manager,
             cluster_manager,
             fragment_manager,
+            ddl_job_manager,
         }
     }
 }
@@ -203,7 +210,13 @@ where
         &self,
         request: Request<DropSourceRequest>,
     ) -> Result<Response<DropSourceResponse>, Status> {
-        let source_id = request.into_inner().source_id;
+        let request = request.into_inner();
+        let source_id = request.source_id;
+
+        // 0. Drop the relations depending on the source if it's a cascading drop.
+        if request.cascade {
+            self.drop_dependents(source_id).await.map_err(tonic_err)?;
+        }
 
         // 1. Drop source in catalog. Ref count will be checked.
         let version = self
@@ -224,6 +237,37 @@ where
         }))
     }
 
+    async fn alter_source_add_column(
+        &self,
+        request: Request<AlterSourceAddColumnRequest>,
+    ) -> Result<Response<AlterSourceAddColumnResponse>, Status> {
+        let req = request.into_inner();
+        let column = req.get_column().map_err(tonic_err)?.clone();
+
+        // 1. Add the column in catalog. Sources read by materialized views will be rejected.
+        let (source, version) = self
+            .catalog_manager
+            .alter_source_add_column(req.source_id, req.source_version, column)
+            .await
+            .map_err(tonic_err)?;
+
+        // 2. Recreate the source on compute nodes with the new columns. It's not being read by
+        // any actor, so nothing is interrupted.
+        self.source_manager
+            .drop_source(source.id)
+            .await
+            .map_err(tonic_err)?;
+        self.source_manager
+            .create_source(&source)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(AlterSourceAddColumnResponse {
+            status: None,
+            version,
+        }))
+    }
+
     async fn create_materialized_view(
         &self,
         request: Request<CreateMaterializedViewRequest>,
@@ -241,67 +285,16 @@ where
             .map_err(tonic_err)? as u32;
         mview.id = id;
 
-        // 1. Resolve the dependent relations.
-        {
-            // TODO: distinguish SourceId and TableId
-            fn resolve_dependent_relations(
-                stream_node: &StreamNode,
-                dependent_relations: &mut HashSet<TableId>,
-            ) -> RwResult<()> {
-                match stream_node.node_body.as_ref().unwrap() {
-                    NodeBody::Source(source_node) => {
-                        dependent_relations.insert(source_node.get_table_ref_id()?.table_id as u32);
-                    }
-                    NodeBody::Chain(chain_node) => {
-                        dependent_relations.insert(chain_node.get_table_ref_id()?.table_id as u32);
-                    }
-                    _ => {}
-                }
-                for child in &stream_node.input {
-                    resolve_dependent_relations(child, dependent_relations)?;
-                }
-                Ok(())
-            }
-
-            let mut dependent_relations = Default::default();
-            for fragment in fragment_graph.fragments.values() {
-                resolve_dependent_relations(
-                    fragment.node.as_ref().unwrap(),
-                    &mut dependent_relations,
-                )
-                .map_err(tonic_err)?;
-            }
-            assert!(
-                !dependent_relations.is_empty(),
-                "there should be at lease 1 dependent relation when creating materialized view"
-            );
-            mview.dependent_relations = dependent_relations.into_iter().collect();
-        }
-
-        // 2. Mark current mview as "creating" and add reference count to dependent relations.
-        self.catalog_manager
-            .start_create_table_procedure(&mview)
-            .await
-            .map_err(tonic_err)?;
-
-        // 3. Create mview in stream manager. The id in stream node will be filled.
-        if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, id, None)
-            .await
-        {
-            self.catalog_manager
-                .cancel_create_table_procedure(&mview)
-                .await
-                .map_err(tonic_err)?;
-            return Err(e.into());
-        }
-
-        // 4. Finally, update the catalog.
-        let version = self
-            .catalog_manager
-            .finish_create_table_procedure(&mview)
+        // Wait for the DDL jobs submitted earlier to finish, and then create the mview.
+        let job = self
+            .ddl_job_manager
+            .start_job(id, &mview.name)
             .await
             .map_err(tonic_err)?;
+        let result = self
+            .create_materialized_view_inner(mview, fragment_graph, &job)
+            .await;
+        let version = job.finish(result).map_err(tonic_err)?;
 
         Ok(Response::new(CreateMaterializedViewResponse {
             status: None,
@@ -316,7 +309,14 @@ where
     ) -> Result<Response<DropMaterializedViewResponse>, Status> {
         use risingwave_common::catalog::TableId;
 
-        let table_id = request.into_inner().table_id;
+        let request = request.into_inner();
+        let table_id = request.table_id;
+
+        // 0. Drop the relations depending on the mview if it's a cascading drop.
+        if request.cascade {
+            self.drop_dependents(table_id).await.map_err(tonic_err)?;
+        }
+
         // 1. Drop table in catalog. Ref count will be checked.
         let version = self
             .catalog_manager
@@ -341,14 +341,37 @@ where
         request: Request<CreateMaterializedSourceRequest>,
     ) -> Result<Response<CreateMaterializedSourceResponse>, Status> {
         let request = request.into_inner();
-        let source = request.source.unwrap();
-        let mview = request.materialized_view.unwrap();
+        let mut source = request.source.unwrap();
+        let mut mview = request.materialized_view.unwrap();
         let fragment_graph = request.fragment_graph.unwrap();
 
-        let (source_id, table_id, version) = self
-            .create_materialized_source_inner(source, mview, fragment_graph)
+        // Generate source id and mview id.
+        let source_id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Table }>() // TODO: use source category
+            .await
+            .map_err(tonic_err)? as u32;
+        source.id = source_id;
+        let table_id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Table }>()
+            .await
+            .map_err(tonic_err)? as u32;
+        mview.id = table_id;
+
+        // Wait for the DDL jobs submitted earlier to finish, and then create the materialized
+        // source.
+        let job = self
+            .ddl_job_manager
+            .start_job(table_id, &mview.name)
             .await
             .map_err(tonic_err)?;
+        let result = self
+            .create_materialized_source_inner(source, mview, fragment_graph, &job)
+            .await;
+        let version = job.finish(result).map_err(tonic_err)?;
 
         Ok(Response::new(CreateMaterializedSourceResponse {
             status: None,
@@ -366,6 +389,11 @@ where
         let source_id = request.source_id;
         let table_id = request.table_id;
 
+        if request.cascade {
+            self.drop_dependents(table_id).await.map_err(tonic_err)?;
+            self.drop_dependents(source_id).await.map_err(tonic_err)?;
+        }
+
         let version = self
             .drop_materialized_source_inner(source_id, table_id)
             .await
@@ -376,6 +404,130 @@ where
             version,
         }))
     }
+
+    async fn create_sink(
+        &self,
+        request: Request<CreateSinkRequest>,
+    ) -> Result<Response<CreateSinkResponse>, Status> {
+        let req = request.into_inner();
+        let mut sink = req.get_sink().map_err(tonic_err)?.clone();
+        let fragment_graph = req.get_fragment_graph().map_err(tonic_err)?.clone();
+
+        // 0. Generate an id from sink. It shares the id space with tables, as the fragments of the
+        // sink are registered under it.
+        let id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Table }>()
+            .await
+            .map_err(tonic_err)? as u32;
+        sink.id = id;
+
+        // Wait for the DDL jobs submitted earlier to finish, and then create the sink.
+        let job = self
+            .ddl_job_manager
+            .start_job(id, &sink.name)
+            .await
+            .map_err(tonic_err)?;
+        let result = self.create_sink_inner(sink, fragment_graph, &job).await;
+        let version = job.finish(result).map_err(tonic_err)?;
+
+        Ok(Response::new(CreateSinkResponse {
+            status: None,
+            sink_id: id,
+            version,
+        }))
+    }
+
+    async fn drop_sink(
+        &self,
+        request: Request<DropSinkRequest>,
+    ) -> Result<Response<DropSinkResponse>, Status> {
+        use risingwave_common::catalog::TableId;
+
+        let sink_id = request.into_inner().sink_id;
+        // 1. Drop sink in catalog.
+        let version = self
+            .catalog_manager
+            .drop_sink(sink_id)
+            .await
+            .map_err(tonic_err)?;
+
+        // 2. Drop the actors of the sink in stream manager.
+        self.stream_manager
+            .drop_materialized_view(&TableId::new(sink_id))
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(DropSinkResponse {
+            status: None,
+            version,
+        }))
+    }
+
+    async fn list_ddl_jobs(
+        &self,
+        _request: Request<ListDdlJobsRequest>,
+    ) -> Result<Response<ListDdlJobsResponse>, Status> {
+        Ok(Response::new(ListDdlJobsResponse {
+            status: None,
+            jobs: self.ddl_job_manager.list_jobs(),
+        }))
+    }
+
+    async fn cancel_ddl_job(
+        &self,
+        request: Request<CancelDdlJobRequest>,
+    ) -> Result<Response<CancelDdlJobResponse>, Status> {
+        use risingwave_common::catalog::TableId;
+
+        let job_id = request.into_inner().job_id;
+
+        // 1. A pending job is cancelled right away.
+        if self
+            .ddl_job_manager
+            .cancel_pending_job(job_id)
+            .map_err(tonic_err)?
+        {
+            return Ok(Response::new(CancelDdlJobResponse { status: None }));
+        }
+
+        // 2. A building job can only be cancelled once its actors are running, as they are
+        // dropped in the same way as a materialized view. The job will fail then, and clean up its
+        // catalog.
+        let table_id = TableId::new(job_id);
+        if self
+            .fragment_manager
+            .is_table_fragments_created(&table_id)
+            .await
+            != Some(true)
+        {
+            return Err(tonic_err(ErrorCode::InternalError(format!(
+                "the actors of ddl job {} are still being built, please try again later",
+                job_id
+            ))));
+        }
+        self.ddl_job_manager
+            .mark_cancelling(job_id)
+            .map_err(tonic_err)?;
+        self.stream_manager
+            .drop_materialized_view(&table_id)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(CancelDdlJobResponse { status: None }))
+    }
+}
+
+/// Extracts the options applied to the state tables from the properties of the materialized view.
+fn table_option_of(mview: &Table) -> RwResult<TableOption> {
+    let ttl = match mview.properties.get(PROPERTIES_TTL_KEY) {
+        Some(ttl) => ttl.parse().map_err(|_| {
+            ErrorCode::InvalidParameterValue(format!("invalid {}: {}", PROPERTIES_TTL_KEY, ttl))
+        })?,
+        None => 0,
+    };
+    Ok(TableOption { ttl })
 }
 
 impl<S> DdlServiceImpl<S>
@@ -385,13 +537,11 @@ where
     async fn create_mview_on_compute_node(
         &self,
         mut fragment_graph: StreamFragmentGraph,
-        id: TableId,
+        mview: &Table,
         affiliated_source: Option<Source>,
     ) -> RwResult<()> {
         use risingwave_common::catalog::TableId;
 
-        use crate::stream::CreateMaterializedViewContext;
-
         // Fill in the correct mview id for stream node.
         fn fill_mview_id(stream_node: &mut StreamNode, mview_id: TableId) -> usize {
             let mut mview_count = 0;
@@ -406,7 +556,7 @@ where
             mview_count
         }
 
-        let mview_id = TableId::new(id);
+        let mview_id = TableId::new(mview.id);
         let mut mview_count = 0;
         for fragment in fragment_graph.fragments.values_mut() {
             mview_count += fill_mview_id(fragment.node.as_mut().unwrap(), mview_id);
@@ -417,6 +567,26 @@ where
             "require exactly 1 materialize node when creating materialized view"
         );
 
+        self.create_stream_job_on_compute_node(
+            fragment_graph,
+            mview_id,
+            table_option_of(mview)?,
+            affiliated_source,
+        )
+        .await
+    }
+
+    /// Builds the actors of the fragment graph and creates them on compute nodes. The fragments are
+    /// registered under `job_id`, which is the id of the materialized view or the sink.
+    async fn create_stream_job_on_compute_node(
+        &self,
+        fragment_graph: StreamFragmentGraph,
+        job_id: risingwave_common::catalog::TableId,
+        table_option: TableOption,
+        affiliated_source: Option<Source>,
+    ) -> RwResult<()> {
+        use crate::stream::CreateMaterializedViewContext;
+
         // Resolve fragments.
         let parallel_degree = self
             .cluster_manager
@@ -434,7 +604,8 @@ where
             &mut ctx,
         )
         .await?;
-        let table_fragments = TableFragments::new(mview_id, graph);
+        let mut table_fragments = TableFragments::new(job_id, graph);
+        table_fragments.set_table_option(table_option);
 
         // Create on compute node.
         self.stream_manager
@@ -444,19 +615,116 @@ where
         Ok(())
     }
 
+    async fn create_materialized_view_inner(
+        &self,
+        mut mview: Table,
+        fragment_graph: StreamFragmentGraph,
+        job: &DdlJobGuard,
+    ) -> RwResult<CatalogVersion> {
+        // 1. Resolve the dependent relations.
+        {
+            // TODO: distinguish SourceId and TableId
+            fn resolve_dependent_relations(
+                stream_node: &StreamNode,
+                dependent_relations: &mut HashSet<TableId>,
+            ) -> RwResult<()> {
+                match stream_node.node_body.as_ref().unwrap() {
+                    NodeBody::Source(source_node) => {
+                        dependent_relations.insert(source_node.get_table_ref_id()?.table_id as u32);
+                    }
+                    NodeBody::Chain(chain_node) => {
+                        dependent_relations.insert(chain_node.get_table_ref_id()?.table_id as u32);
+                    }
+                    _ => {}
+                }
+                for child in &stream_node.input {
+                    resolve_dependent_relations(child, dependent_relations)?;
+                }
+                Ok(())
+            }
+
+            let mut dependent_relations = Default::default();
+            for fragment in fragment_graph.fragments.values() {
+                resolve_dependent_relations(
+                    fragment.node.as_ref().unwrap(),
+                    &mut dependent_relations,
+                )?;
+            }
+            assert!(
+                !dependent_relations.is_empty(),
+                "there should be at lease 1 dependent relation when creating materialized view"
+            );
+            mview.dependent_relations = dependent_relations.into_iter().collect();
+        }
+
+        // 2. Mark current mview as "creating" and add reference count to dependent relations.
+        self.catalog_manager
+            .start_create_table_procedure(&mview)
+            .await?;
+
+        // 3. Create mview in stream manager. The id in stream node will be filled. It fails if the
+        // job is cancelled meanwhile.
+        if let Err(e) = self
+            .create_mview_on_compute_node(fragment_graph, &mview, None)
+            .await
+            .and_then(|()| job.commit())
+        {
+            self.catalog_manager
+                .cancel_create_table_procedure(&mview)
+                .await?;
+            return Err(e);
+        }
+
+        // 4. Finally, update the catalog.
+        self.catalog_manager
+            .finish_create_table_procedure(&mview)
+            .await
+    }
+
+    async fn create_sink_inner(
+        &self,
+        sink: Sink,
+        fragment_graph: StreamFragmentGraph,
+        job: &DdlJobGuard,
+    ) -> RwResult<CatalogVersion> {
+        use risingwave_common::catalog::TableId;
+
+        // 1. Mark current sink as "creating" and add reference count to the materialized view.
+        self.catalog_manager
+            .start_create_sink_procedure(&sink)
+            .await?;
+
+        // 2. Create the sink in stream manager. It fails if the job is cancelled meanwhile.
+        if let Err(e) = self
+            .create_stream_job_on_compute_node(
+                fragment_graph,
+                TableId::new(sink.id),
+                TableOption::default(),
+                None,
+            )
+            .await
+            .and_then(|()| job.commit())
+        {
+            self.catalog_manager
+                .cancel_create_sink_procedure(&sink)
+                .await?;
+            return Err(e);
+        }
+
+        // 3. Finally, update the catalog.
+        self.catalog_manager
+            .finish_create_sink_procedure(&sink)
+            .await
+    }
+
     async fn create_materialized_source_inner(
         &self,
-        mut source: Source,
+        source: Source,
         mut mview: Table,
         mut fragment_graph: StreamFragmentGraph,
-    ) -> RwResult<(SourceId, TableId, CatalogVersion)> {
-        // Generate source id.
-        let source_id = self
-            .env
-            .id_gen_manager()
-            .generate::<{ IdCategory::Table }>() // TODO: use source category
-            .await? as u32;
-        source.id = source_id;
+        job: &DdlJobGuard,
+    ) -> RwResult<CatalogVersion> {
+        let source_id = source.id;
 
         self.catalog_manager
             .start_create_materialized_source_procedure(&source, &mview)
@@ -498,19 +766,12 @@ where
         mview.optional_associated_source_id =
             Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id));
 
-        // Generate mview id.
-        let mview_id = self
-            .env
-            .id_gen_manager()
-            .generate::<{ IdCategory::Table }>()
-            .await? as u32;
-        mview.id = mview_id;
-
-        // Create mview on compute node.
+        // Create mview on compute node. It fails if the job is cancelled meanwhile.
         // Noted that this progress relies on the source just created, so we pass it here.
         if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, mview_id, Some(source.clone()))
+            .create_mview_on_compute_node(fragment_graph, &mview, Some(source.clone()))
             .await
+            .and_then(|()| job.commit())
         {
             self.catalog_manager
                 .cancel_create_materialized_source_procedure(&source, &mview)
@@ -521,12 +782,28 @@ where
         }
 
         // Finally, update the catalog.
-        let version = self
-            .catalog_manager
+        self.catalog_manager
             .finish_create_materialized_source_procedure(&source, &mview)
-            .await?;
+            .await
+    }
+
+    /// Drops the relations depending on `relation_id` for a cascading drop. Their catalogs are
+    /// removed at once, so that a failure never leaves some of them dropped. Then their actors,
+    /// fragments and states are torn down by the stream manager one by one, each before the ones
+    /// it depends on, so that no relation is ever left reading from a dropped one.
+    async fn drop_dependents(&self, relation_id: RelationId) -> RwResult<()> {
+        use risingwave_common::catalog::TableId;
 
-        Ok((source_id, mview_id, version))
+        for dependent in self.catalog_manager.drop_dependents(relation_id).await? {
+            let id = match dependent {
+                DependentRelation::Table(table_id) => table_id,
+                DependentRelation::Sink(sink_id) => sink_id,
+            };
+            self.stream_manager
+                .drop_materialized_view(&TableId::new(id))
+                .await?;
+        }
+        Ok(())
     }
 
     async fn drop_materialized_source_inner(
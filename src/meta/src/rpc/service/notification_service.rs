@@ -16,20 +16,20 @@ use risingwave_common::error::tonic_err;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::meta::notification_service_server::NotificationService;
-use risingwave_pb::meta::subscribe_response::{Info, Operation};
-use risingwave_pb::meta::{MetaSnapshot, SubscribeRequest, SubscribeResponse};
+use risingwave_pb::meta::{MetaSnapshot, SubscribeRequest};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::{Request, Response, Status};
 
 use crate::cluster::{ClusterManagerRef, WorkerKey};
-use crate::manager::{CatalogManagerRef, MetaSrvEnv, Notification};
+use crate::manager::{CatalogManagerRef, MetaSrvEnv, Notification, UserManagerRef};
 use crate::storage::MetaStore;
 pub struct NotificationServiceImpl<S: MetaStore> {
     env: MetaSrvEnv<S>,
 
     catalog_manager: CatalogManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
+    user_manager: UserManagerRef<S>,
 }
 
 impl<S> NotificationServiceImpl<S>
@@ -40,11 +40,13 @@ where
         env: MetaSrvEnv<S>,
         catalog_manager: CatalogManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
+        user_manager: UserManagerRef<S>,
     ) -> Self {
         Self {
             env,
             catalog_manager,
             cluster_manager,
+            user_manager,
         }
     }
 }
@@ -76,11 +78,17 @@ where
             }
             WorkerType::Frontend => {
                 let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
-                let (database, schema, table, source) = catalog_guard.get_catalog().await?;
+                let (database, schema, table, source, sink) = catalog_guard.get_catalog().await?;
 
                 let cluster_guard = self.cluster_manager.get_cluster_core_guard().await;
                 let nodes = cluster_guard.list_worker_node(WorkerType::ComputeNode, Some(Running));
 
+                let user_guard = self.user_manager.get_user_core_guard().await;
+                let users = user_guard.values().cloned().collect();
+
+                let params_guard = self.env.system_param_manager().get_params_guard().await;
+                let system_params = Some(params_guard.clone());
+
                 // Send the snapshot on subscription. After that we will send only updates.
                 let meta_snapshot = MetaSnapshot {
                     nodes,
@@ -89,17 +97,13 @@ where
                     source,
                     table,
                     view: Default::default(),
+                    users,
+                    sink,
+                    system_params,
                 };
-                tx.send(Ok(SubscribeResponse {
-                    status: None,
-                    operation: Operation::Snapshot as i32,
-                    info: Some(Info::Snapshot(meta_snapshot)),
-                    version: self.env.notification_manager().current_version().await,
-                }))
-                .unwrap();
                 self.env
                     .notification_manager()
-                    .insert_frontend_sender(WorkerKey(host_address), tx)
+                    .insert_frontend_sender(WorkerKey(host_address), tx, meta_snapshot)
                     .await
             }
             _ => unreachable!(),
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::TableId;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerService;
 use risingwave_pb::meta::*;
 use tonic::{Request, Response, Status};
 
+use crate::model::MetadataModel;
 use crate::storage::MetaStore;
-use crate::stream::GlobalStreamManagerRef;
+use crate::stream::{FragmentManagerRef, GlobalStreamManagerRef};
 
 pub type TonicResponse<T> = Result<Response<T>, Status>;
 
@@ -27,15 +29,20 @@ where
     S: MetaStore,
 {
     global_stream_manager: GlobalStreamManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
 }
 
 impl<S> StreamServiceImpl<S>
 where
     S: MetaStore,
 {
-    pub fn new(global_stream_manager: GlobalStreamManagerRef<S>) -> Self {
+    pub fn new(
+        global_stream_manager: GlobalStreamManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
+    ) -> Self {
         StreamServiceImpl {
             global_stream_manager,
+            fragment_manager,
         }
     }
 }
@@ -52,4 +59,53 @@ where
         self.global_stream_manager.flush().await?;
         Ok(Response::new(FlushResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn pause(&self, request: Request<PauseRequest>) -> TonicResponse<PauseResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager.pause().await?;
+        Ok(Response::new(PauseResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn resume(&self, request: Request<ResumeRequest>) -> TonicResponse<ResumeResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager.resume().await?;
+        Ok(Response::new(ResumeResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_table_fragments(
+        &self,
+        request: Request<ListTableFragmentsRequest>,
+    ) -> TonicResponse<ListTableFragmentsResponse> {
+        let _req = request.into_inner();
+
+        let table_fragments = self
+            .fragment_manager
+            .list_table_fragments()
+            .await?
+            .iter()
+            .map(|table_fragments| table_fragments.to_protobuf())
+            .collect();
+        Ok(Response::new(ListTableFragmentsResponse {
+            status: None,
+            table_fragments,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn alter_parallelism(
+        &self,
+        request: Request<AlterParallelismRequest>,
+    ) -> TonicResponse<AlterParallelismResponse> {
+        let req = request.into_inner();
+
+        self.global_stream_manager
+            .alter_parallelism(&TableId::new(req.table_id), req.parallelism as usize)
+            .await?;
+        Ok(Response::new(AlterParallelismResponse { status: None }))
+    }
 }
@@ -0,0 +1,68 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::tonic_err;
+use risingwave_pb::meta::system_params_service_server::SystemParamsService;
+use risingwave_pb::meta::{
+    GetSystemParamsRequest, GetSystemParamsResponse, SetSystemParamRequest, SetSystemParamResponse,
+};
+use tonic::{Request, Response, Status};
+
+use crate::manager::SystemParamManagerRef;
+use crate::storage::MetaStore;
+
+pub struct SystemParamsServiceImpl<S: MetaStore> {
+    system_param_manager: SystemParamManagerRef<S>,
+}
+
+impl<S> SystemParamsServiceImpl<S>
+where
+    S: MetaStore,
+{
+    pub fn new(system_param_manager: SystemParamManagerRef<S>) -> Self {
+        Self {
+            system_param_manager,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: MetaStore> SystemParamsService for SystemParamsServiceImpl<S> {
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_system_params(
+        &self,
+        _request: Request<GetSystemParamsRequest>,
+    ) -> Result<Response<GetSystemParamsResponse>, Status> {
+        let params = self.system_param_manager.get_params().await;
+
+        Ok(Response::new(GetSystemParamsResponse {
+            status: None,
+            params: Some(params),
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn set_system_param(
+        &self,
+        request: Request<SetSystemParamRequest>,
+    ) -> Result<Response<SetSystemParamResponse>, Status> {
+        let req = request.into_inner();
+        self.system_param_manager
+            .set_param(&req.param, &req.value)
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(SetSystemParamResponse { status: None }))
+    }
+}
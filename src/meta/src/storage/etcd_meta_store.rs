@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::atomic::{self, AtomicI64};
 
 use anyhow;
@@ -22,7 +23,7 @@ use etcd_client::{
 use futures::Future;
 use tokio::sync::Mutex;
 
-use super::{Error, Key, MetaStore, Result, Snapshot, Transaction, Value};
+use super::{Error, Key, MetaStore, Operation, Result, Snapshot, Transaction, Value};
 
 impl From<EtcdError> for Error {
     fn from(err: EtcdError) -> Self {
@@ -165,6 +166,25 @@ impl Snapshot for EtcdSnapshot {
     }
 }
 
+/// Etcd rejects a transaction that writes the same key more than once, while a [`Transaction`] may
+/// contain several operations on one key, of which the last one takes effect. Keep only the last
+/// operation on each key, in the order they were added.
+fn dedup_operations(operations: Vec<Operation>) -> Vec<Operation> {
+    let mut seen = HashSet::new();
+    let mut deduped = operations
+        .into_iter()
+        .rev()
+        .filter(|op| {
+            let (cf, key) = match op {
+                Operation::Put { cf, key, .. } | Operation::Delete { cf, key } => (cf, key),
+            };
+            seen.insert(encode_etcd_key(cf, key))
+        })
+        .collect::<Vec<_>>();
+    deduped.reverse();
+    deduped
+}
+
 impl EtcdMetaStore {
     pub fn new(client: Client) -> Self {
         Self { client }
@@ -210,15 +230,15 @@ impl MetaStore for EtcdMetaStore {
             })
             .collect::<Vec<_>>();
 
-        let then = operations
+        let then = dedup_operations(operations)
             .into_iter()
             .map(|op| match op {
-                super::Operation::Put { cf, key, value } => {
+                Operation::Put { cf, key, value } => {
                     let key = encode_etcd_key(&cf, &key);
                     let value = value.to_vec();
                     TxnOp::put(key, value, None)
                 }
-                super::Operation::Delete { cf, key } => {
+                Operation::Delete { cf, key } => {
                     let key = encode_etcd_key(&cf, &key);
                     TxnOp::delete(key, None)
                 }
@@ -233,3 +253,35 @@ impl MetaStore for EtcdMetaStore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_operations() {
+        let mut trx = Transaction::default();
+        trx.put("cf".to_owned(), b"k1".to_vec(), b"v1".to_vec());
+        trx.put("cf".to_owned(), b"k2".to_vec(), b"v2".to_vec());
+        trx.delete("cf".to_owned(), b"k1".to_vec());
+        trx.put("cf2".to_owned(), b"k1".to_vec(), b"v3".to_vec());
+        trx.put("cf".to_owned(), b"k2".to_vec(), b"v4".to_vec());
+        let (_, operations) = trx.into_parts();
+
+        let deduped = dedup_operations(operations)
+            .into_iter()
+            .map(|op| match op {
+                Operation::Put { cf, key, value } => (cf, key, Some(value)),
+                Operation::Delete { cf, key } => (cf, key, None),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            deduped,
+            vec![
+                ("cf".to_owned(), b"k1".to_vec(), None),
+                ("cf2".to_owned(), b"k1".to_vec(), Some(b"v3".to_vec())),
+                ("cf".to_owned(), b"k2".to_vec(), Some(b"v4".to_vec())),
+            ]
+        );
+    }
+}
@@ -16,21 +16,23 @@ use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
+use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::hash::VIRTUAL_NODE_COUNT;
 use risingwave_common::try_match_expand;
 use risingwave_common::util::compress::decompress_data;
+use risingwave_pb::common::ParallelUnit;
 use risingwave_pb::meta::table_fragments::ActorState;
-use risingwave_pb::stream_plan::{FragmentType, StreamActor};
+use risingwave_pb::stream_plan::{Dispatcher, SourceNode, StreamActor, StreamSourceState};
 use tokio::sync::RwLock;
 
 use crate::cluster::{ParallelUnitId, WorkerId};
 use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, MetadataModel, TableFragments, Transactional};
+use crate::model::{ActorId, DispatcherId, MetadataModel, TableFragments, Transactional};
 use crate::storage::{MetaStore, Transaction};
-use crate::stream::record_table_vnode_mappings;
+use crate::stream::{record_table_options, record_table_vnode_mappings};
 
 struct FragmentManagerCore {
     table_fragments: HashMap<TableId, TableFragments>,
@@ -40,6 +42,8 @@ struct FragmentManagerCore {
 pub struct FragmentManager<S> {
     meta_store: Arc<S>,
 
+    hash_mapping_manager: HashMappingManagerRef,
+
     core: RwLock<FragmentManagerCore>,
 }
 
@@ -77,9 +81,13 @@ where
             .collect();
 
         Self::restore_vnode_mappings(env.hash_mapping_manager_ref(), &table_fragments)?;
+        for fragments in table_fragments.values() {
+            record_table_options(&env.table_option_manager_ref(), fragments)?;
+        }
 
         Ok(Self {
             meta_store,
+            hash_mapping_manager: env.hash_mapping_manager_ref(),
             core: RwLock::new(FragmentManagerCore { table_fragments }),
         })
     }
@@ -90,6 +98,14 @@ where
         Ok(map.values().cloned().collect())
     }
 
+    /// Returns whether all actors of the table are running, or `None` if the table fragments don't
+    /// exist.
+    pub async fn is_table_fragments_created(&self, table_id: &TableId) -> Option<bool> {
+        let map = &self.core.read().await.table_fragments;
+
+        map.get(table_id).map(TableFragments::is_created)
+    }
+
     pub async fn update_table_fragments(&self, table_fragment: TableFragments) -> Result<()> {
         let map = &mut self.core.write().await.table_fragments;
 
@@ -174,7 +190,12 @@ where
                         if let Some(downstream_actors) =
                             extra_downstream_actors.get(&actor.actor_id)
                         {
-                            actor.dispatcher[0]
+                            // New outputs are always added to the cross-MV dispatcher with id 0.
+                            actor
+                                .dispatcher
+                                .iter_mut()
+                                .find(|dispatcher| dispatcher.dispatcher_id == 0)
+                                .unwrap()
                                 .downstream_actor_id
                                 .extend(downstream_actors.iter().cloned());
                         }
@@ -199,8 +220,120 @@ where
         }
     }
 
+    /// Persist the vnode bitmaps of the actors of the table after they're reassigned for scaling,
+    /// along with the new mappings of the hash dispatchers routing to them. The vnode mappings of
+    /// the fragments are updated accordingly.
+    pub async fn update_actor_vnode_bitmaps(
+        &self,
+        table_id: &TableId,
+        actor_vnode_bitmaps: &HashMap<ActorId, Vec<u8>>,
+        dispatcher_hash_mappings: &HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        match map.get_mut(table_id) {
+            Some(table_fragments) => {
+                let mut new_table_fragments = table_fragments.clone();
+                let fragment_vnode_mappings = new_table_fragments
+                    .update_vnode_bitmaps(actor_vnode_bitmaps, dispatcher_hash_mappings);
+                new_table_fragments.insert(&*self.meta_store).await?;
+                *table_fragments = new_table_fragments;
+                for (fragment_id, vnode_mapping) in fragment_vnode_mappings {
+                    self.hash_mapping_manager
+                        .set_fragment_hash_mapping(fragment_id, vnode_mapping);
+                }
+
+                Ok(())
+            }
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
+    /// Persist the splits newly assigned to the stream source actors, so that the actors read them
+    /// again after recovery.
+    pub async fn update_actor_splits(
+        &self,
+        actor_splits: &HashMap<ActorId, StreamSourceState>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        for table_fragments in map.values_mut() {
+            if !table_fragments
+                .actor_ids()
+                .iter()
+                .any(|actor_id| actor_splits.contains_key(actor_id))
+            {
+                continue;
+            }
+            let mut new_table_fragments = table_fragments.clone();
+            new_table_fragments.update_actor_splits(actor_splits);
+            new_table_fragments.insert(&*self.meta_store).await?;
+            *table_fragments = new_table_fragments;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the parallel units that the actors of all tables are on.
+    pub async fn all_actor_parallel_units(&self) -> HashMap<ParallelUnitId, ParallelUnit> {
+        let map = &self.core.read().await.table_fragments;
+
+        map.values()
+            .flat_map(|table_fragments| table_fragments.actor_parallel_units())
+            .map(|parallel_unit| (parallel_unit.id, parallel_unit.clone()))
+            .collect()
+    }
+
+    /// Returns the number of actors on each parallel unit.
+    pub async fn parallel_unit_actor_counts(&self) -> HashMap<ParallelUnitId, usize> {
+        let map = &self.core.read().await.table_fragments;
+
+        let mut actor_counts = HashMap::new();
+        map.values()
+            .flat_map(|table_fragments| table_fragments.actor_parallel_units())
+            .for_each(|parallel_unit| *actor_counts.entry(parallel_unit.id).or_default() += 1);
+        actor_counts
+    }
+
+    /// Move the actors on the parallel units in `migrate_map` to the mapped ones, so that they're
+    /// built there in recovery. The new locations of all tables are persisted at once.
+    pub async fn migrate_actors(
+        &self,
+        migrate_map: &HashMap<ParallelUnitId, ParallelUnit>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        let mut transaction = Transaction::default();
+        let mut migrated_tables = vec![];
+        for table_fragments in map.values() {
+            let mut table_fragments = table_fragments.clone();
+            if table_fragments.migrate_actors(migrate_map) {
+                table_fragments.upsert_in_transaction(&mut transaction)?;
+                migrated_tables.push(table_fragments);
+            }
+        }
+        self.meta_store.txn(transaction).await?;
+
+        for table_fragments in migrated_tables {
+            for fragment in table_fragments.fragments() {
+                if let Some(mapping) = fragment.vnode_mapping.as_ref() {
+                    self.hash_mapping_manager.set_fragment_hash_mapping(
+                        fragment.fragment_id,
+                        decompress_data(&mapping.original_indices, &mapping.data),
+                    );
+                }
+            }
+            map.insert(table_fragments.table_id(), table_fragments);
+        }
+
+        Ok(())
+    }
+
     /// Drop table fragments info and remove downstream actor infos in fragments from its dependent
-    /// tables.
+    /// tables, i.e., the tables it's created on and the tables sharing their source actors with it.
     pub async fn drop_table_fragments(&self, table_id: &TableId) -> Result<()> {
         let map = &mut self.core.write().await.table_fragments;
 
@@ -208,25 +341,33 @@ where
             let mut transaction = Transaction::default();
             table_fragments.delete_in_transaction(&mut transaction)?;
 
-            let dependent_table_ids = table_fragments.dependent_table_ids();
-            let chain_actor_ids = table_fragments.chain_actor_ids();
-            let mut dependent_tables = Vec::with_capacity(dependent_table_ids.len());
-            for dependent_table_id in dependent_table_ids {
-                let mut dependent_table = map
-                    .get(&dependent_table_id)
-                    .ok_or_else(|| {
-                        RwError::from(InternalError(format!(
-                            "table_fragment not exist: id={}",
-                            dependent_table_id
-                        )))
-                    })?
-                    .clone();
+            let actor_ids: HashSet<ActorId> = table_fragments.actor_ids().into_iter().collect();
+            let is_cross_mv_output = |dispatcher: &Dispatcher| {
+                dispatcher.dispatcher_id == 0
+                    && dispatcher
+                        .downstream_actor_id
+                        .iter()
+                        .any(|x| actor_ids.contains(x))
+            };
+            let mut dependent_tables = vec![];
+            for (dependent_table_id, dependent_table) in map.iter() {
+                if dependent_table_id == table_id
+                    || !dependent_table
+                        .actors()
+                        .iter()
+                        .any(|actor| actor.dispatcher.iter().any(is_cross_mv_output))
+                {
+                    continue;
+                }
+                let mut dependent_table = dependent_table.clone();
                 for fragment in dependent_table.fragments.values_mut() {
-                    if fragment.fragment_type == FragmentType::Sink as i32 {
-                        for actor in &mut fragment.actors {
-                            actor.dispatcher[0]
-                                .downstream_actor_id
-                                .retain(|x| !chain_actor_ids.contains(x));
+                    for actor in &mut fragment.actors {
+                        for dispatcher in &mut actor.dispatcher {
+                            if dispatcher.dispatcher_id == 0 {
+                                dispatcher
+                                    .downstream_actor_id
+                                    .retain(|x| !actor_ids.contains(x));
+                            }
                         }
                     }
                 }
@@ -250,13 +391,13 @@ where
     }
 
     /// Used in [`crate::barrier::GlobalBarrierManager`]
-    pub async fn load_all_actors(&self, with_creating_table: Option<TableId>) -> ActorInfos {
+    pub async fn load_all_actors(&self, with_creating_tables: &[TableId]) -> ActorInfos {
         let mut actor_maps = HashMap::new();
         let mut source_actor_ids = HashMap::new();
 
         let map = &self.core.read().await.table_fragments;
         for fragments in map.values() {
-            let include_inactive = with_creating_table.contains(&fragments.table_id());
+            let include_inactive = with_creating_tables.contains(&fragments.table_id());
             let check_state = |s: ActorState| {
                 s == ActorState::Running || include_inactive && s == ActorState::Inactive
             };
@@ -352,6 +493,20 @@ where
         }
     }
 
+    pub async fn select_table_fragments_by_table_id(
+        &self,
+        table_id: &TableId,
+    ) -> Result<TableFragments> {
+        let map = &self.core.read().await.table_fragments;
+        match map.get(table_id) {
+            Some(table_fragment) => Ok(table_fragment.clone()),
+            None => Err(RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))),
+        }
+    }
+
     // we will read three things at once, avoiding locking too much.
     pub async fn get_build_graph_info(
         &self,
@@ -379,6 +534,37 @@ where
         Ok(info)
     }
 
+    /// Returns the source job that can be shared by a new materialized view reading the stream
+    /// source with `source_node`, together with its actors grouped by node id. See
+    /// [`TableFragments::shared_source_node_actor_ids`].
+    pub async fn get_shared_source_actors(
+        &self,
+        source_node: &SourceNode,
+    ) -> Option<(TableId, BTreeMap<WorkerId, Vec<ActorId>>)> {
+        let map = &self.core.read().await.table_fragments;
+
+        // Prefer the earliest created one to keep the choice stable.
+        map.iter()
+            .sorted_by_key(|(table_id, _)| table_id.table_id)
+            .find_map(|(table_id, table_fragments)| {
+                table_fragments
+                    .shared_source_node_actor_ids(source_node)
+                    .map(|node_actors| (*table_id, node_actors))
+            })
+    }
+
+    /// Returns the created source jobs with no materialized view reading from them any more.
+    pub async fn list_orphaned_source_jobs(&self) -> Vec<TableId> {
+        let map = &self.core.read().await.table_fragments;
+
+        map.values()
+            .filter(|table_fragments| {
+                table_fragments.is_created() && table_fragments.is_orphaned_source_job()
+            })
+            .map(TableFragments::table_id)
+            .collect()
+    }
+
     pub async fn get_sink_parallel_unit_ids(
         &self,
         table_ids: &HashSet<TableId>,
@@ -23,6 +23,7 @@ mod test_fragmenter;
 
 pub use meta::*;
 use risingwave_common::error::Result;
+use risingwave_pb::hummock::TableOption;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::StreamNode;
 pub use scheduler::*;
@@ -30,8 +31,8 @@ pub use source_manager::*;
 pub use stream_graph::*;
 pub use stream_manager::*;
 
-use crate::manager::HashMappingManagerRef;
-use crate::model::FragmentId;
+use crate::manager::{HashMappingManagerRef, TableOptionManagerRef};
+use crate::model::{FragmentId, TableFragments};
 
 /// Record vnode mapping for stateful operators in meta.
 pub fn record_table_vnode_mappings(
@@ -68,3 +69,52 @@ pub fn record_table_vnode_mappings(
     }
     Ok(())
 }
+
+/// Record the options of the state tables of a materialized view in meta, which will be sent to
+/// compactors to drop expired states.
+pub fn record_table_options(
+    table_option_manager: &TableOptionManagerRef,
+    table_fragments: &TableFragments,
+) -> Result<()> {
+    fn record_inner(
+        table_option_manager: &TableOptionManagerRef,
+        stream_node: &StreamNode,
+        table_option: &TableOption,
+    ) -> Result<()> {
+        // Only the internal states of joins and aggregations are subject to the options. Rows of
+        // the materialized view itself never expire.
+        match stream_node.get_node_body()? {
+            NodeBody::HashAgg(node) => {
+                for table_id in node.get_table_ids() {
+                    table_option_manager.set_table_option(*table_id, table_option.clone());
+                }
+            }
+            NodeBody::GlobalSimpleAgg(node) => {
+                for table_id in node.get_table_ids() {
+                    table_option_manager.set_table_option(*table_id, table_option.clone());
+                }
+            }
+            NodeBody::HashJoin(node) => {
+                table_option_manager.set_table_option(node.left_table_id, table_option.clone());
+                table_option_manager.set_table_option(node.right_table_id, table_option.clone());
+            }
+            _ => {}
+        }
+        for input_node in stream_node.get_input() {
+            record_inner(table_option_manager, input_node, table_option)?;
+        }
+        Ok(())
+    }
+
+    let table_option = table_fragments.table_option();
+    if table_option.ttl == 0 {
+        return Ok(());
+    }
+    for fragment in table_fragments.fragments() {
+        // Looking at the first actor is enough, since all actors in one fragment have identical
+        // state table id.
+        let actor = fragment.actors.first().unwrap();
+        record_inner(table_option_manager, actor.get_nodes()?, table_option)?;
+    }
+    Ok(())
+}
@@ -12,8 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashMap};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{internal_error, Result};
@@ -24,7 +23,7 @@ use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
 use risingwave_pb::meta::table_fragments::Fragment;
 
 use super::record_table_vnode_mappings;
-use crate::cluster::{ClusterManagerRef, WorkerId, WorkerLocations};
+use crate::cluster::{ClusterManagerRef, ParallelUnitId, WorkerId, WorkerLocations};
 use crate::manager::HashMappingManagerRef;
 use crate::model::ActorId;
 use crate::storage::MetaStore;
@@ -34,8 +33,6 @@ pub struct Scheduler<S: MetaStore> {
     cluster_manager: ClusterManagerRef<S>,
     /// Maintains vnode mappings of all scheduled fragments.
     hash_mapping_manager: HashMappingManagerRef,
-    /// Round robin counter for singleton fragments
-    single_rr: AtomicUsize,
 }
 /// [`ScheduledLocations`] represents the location of scheduled result.
 pub struct ScheduledLocations {
@@ -43,6 +40,8 @@ pub struct ScheduledLocations {
     pub actor_locations: BTreeMap<ActorId, ParallelUnit>,
     /// worker location map.
     pub node_locations: WorkerLocations,
+    /// Number of actors on each parallel unit, including those of the existing streaming jobs.
+    pub actor_counts: HashMap<ParallelUnitId, usize>,
 }
 
 impl ScheduledLocations {
@@ -50,9 +49,42 @@ impl ScheduledLocations {
         Self {
             actor_locations: BTreeMap::new(),
             node_locations: HashMap::new(),
+            actor_counts: HashMap::new(),
         }
     }
 
+    /// Place the actor on the parallel unit and count it into the load of the parallel unit.
+    fn place(&mut self, actor_id: ActorId, parallel_unit: ParallelUnit) {
+        *self.actor_counts.entry(parallel_unit.id).or_default() += 1;
+        self.actor_locations.insert(actor_id, parallel_unit);
+    }
+
+    /// Pick at most `count` parallel units with the fewest actors, in their original order. Ties
+    /// are broken by the order in `parallel_units`.
+    fn least_loaded(&self, parallel_units: &[ParallelUnit], count: usize) -> Vec<ParallelUnit> {
+        let mut by_load = parallel_units.iter().enumerate().collect::<Vec<_>>();
+        by_load.sort_by_key(|(idx, parallel_unit)| {
+            (
+                self.actor_counts
+                    .get(&parallel_unit.id)
+                    .cloned()
+                    .unwrap_or(0),
+                *idx,
+            )
+        });
+        let picked = by_load
+            .into_iter()
+            .take(count)
+            .map(|(idx, _)| idx)
+            .collect::<HashSet<_>>();
+        parallel_units
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| picked.contains(idx))
+            .map(|(_, parallel_unit)| parallel_unit.clone())
+            .collect()
+    }
+
     /// [`Self::node_actors`] returns all actors for every node.
     pub fn node_actors(&self) -> HashMap<WorkerId, Vec<ActorId>> {
         let mut node_actors = HashMap::new();
@@ -137,16 +169,17 @@ where
         Self {
             cluster_manager,
             hash_mapping_manager,
-            single_rr: AtomicUsize::new(0),
         }
     }
 
     /// [`Self::schedule`] schedules input fragments to different parallel units (workers).
-    /// The schedule procedure is two-fold:
-    /// (1) For singleton fragments, we apply the round robin strategy. One single parallel unit in
-    /// the cluster is assigned to a singleton fragment once, and all the single parallel units take
-    /// turns.
-    /// (2) For normal fragments, we schedule them to all the hash parallel units in the cluster.
+    /// Actors that must be on the same worker node as their upstream are always colocated with
+    /// them. Otherwise, the schedule procedure is two-fold:
+    /// (1) For singleton fragments, we choose the single parallel unit with the fewest actors in
+    /// `locations`, so that singleton fragments of all streaming jobs are spread across the
+    /// cluster.
+    /// (2) For normal fragments, we schedule them to all the hash parallel units in the cluster. If
+    /// the fragment has fewer actors than the parallel units, the least loaded ones are chosen.
     pub async fn schedule(
         &self,
         fragment: &mut Fragment,
@@ -168,42 +201,44 @@ where
                 self.set_fragment_vnode_mapping(fragment, &[parallel_unit.clone()])?;
 
                 // Record actor locations.
-                locations
-                    .actor_locations
-                    .insert(fragment.actors[0].actor_id, parallel_unit);
+                locations.place(fragment.actors[0].actor_id, parallel_unit);
             } else {
-                // Choose one parallel unit to schedule from single parallel units.
+                // Choose the least loaded parallel unit to schedule from single parallel units.
                 let single_parallel_units = self
                     .cluster_manager
-                    .list_parallel_units(Some(ParallelUnitType::Single))
+                    .list_schedulable_parallel_units(Some(ParallelUnitType::Single))
                     .await;
-                let single_idx = self
-                    .single_rr
-                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |idx| {
-                        Some((idx + 1) % single_parallel_units.len())
-                    })
-                    .map_err(|_| internal_error("failed to round robin id"))?;
+                let parallel_unit = locations
+                    .least_loaded(&single_parallel_units, 1)
+                    .pop()
+                    .ok_or_else(|| internal_error("no schedulable single parallel unit"))?;
 
                 // Build vnode mapping.
-                self.set_fragment_vnode_mapping(
-                    fragment,
-                    &[single_parallel_units[single_idx].clone()],
-                )?;
+                self.set_fragment_vnode_mapping(fragment, &[parallel_unit.clone()])?;
 
                 // Record actor locations.
-                locations.actor_locations.insert(
-                    fragment.actors[0].actor_id,
-                    single_parallel_units[single_idx].clone(),
-                );
+                locations.place(fragment.actors[0].actor_id, parallel_unit);
             }
         } else {
             // Normal fragment
 
             // Find out all the hash parallel units in the cluster.
-            let parallel_units = self
+            let mut parallel_units = self
                 .cluster_manager
-                .list_parallel_units(Some(ParallelUnitType::Hash))
+                .list_schedulable_parallel_units(Some(ParallelUnitType::Hash))
                 .await;
+            if parallel_units.is_empty() {
+                return Err(internal_error("no schedulable hash parallel unit"));
+            }
+
+            // Colocated actors follow the parallel units of their upstreams, so only narrow down
+            // the parallel units if no actor is colocated.
+            let colocated = fragment.actors.iter().any(|actor| {
+                actor.same_worker_node_as_upstream && !actor.upstream_actor_id.is_empty()
+            });
+            if !colocated && fragment.actors.len() < parallel_units.len() {
+                parallel_units = locations.least_loaded(&parallel_units, fragment.actors.len());
+            }
 
             // Build vnode mapping according to the parallel units.
             self.set_fragment_vnode_mapping(fragment, &parallel_units)?;
@@ -230,18 +265,11 @@ where
                     let parallel_unit =
                         locations.schedule_colocate_with(&actor.upstream_actor_id)?;
                     actor.vnode_bitmap = vnode_bitmaps.get(&parallel_unit.id).unwrap().to_vec();
-                    locations
-                        .actor_locations
-                        .insert(actor.actor_id, parallel_unit);
+                    locations.place(actor.actor_id, parallel_unit);
                 } else {
-                    actor.vnode_bitmap = vnode_bitmaps
-                        .get(&parallel_units[idx % parallel_units.len()].id)
-                        .unwrap()
-                        .to_vec();
-                    locations.actor_locations.insert(
-                        actor.actor_id,
-                        parallel_units[idx % parallel_units.len()].clone(),
-                    );
+                    let parallel_unit = parallel_units[idx % parallel_units.len()].clone();
+                    actor.vnode_bitmap = vnode_bitmaps.get(&parallel_unit.id).unwrap().to_vec();
+                    locations.place(actor.actor_id, parallel_unit);
                 }
             }
         }
@@ -310,7 +338,7 @@ mod test {
                 port: i as i32,
             };
             cluster_manager
-                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, 0)
                 .await?;
             cluster_manager.activate_worker_node(host).await?;
         }
@@ -338,6 +366,7 @@ mod test {
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
                         vnode_bitmap: vec![],
+                        table_id: 0,
                     }],
                     vnode_mapping: None,
                 };
@@ -448,4 +477,93 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_schedule_least_loaded() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager =
+            Arc::new(ClusterManager::new(env.clone(), Duration::from_secs(3600)).await?);
+
+        let mut worker_ids = vec![];
+        for i in 0..2 {
+            let host = HostAddress {
+                host: "127.0.0.1".to_string(),
+                port: i as i32,
+            };
+            let (worker_node, _) = cluster_manager
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, 0)
+                .await?;
+            cluster_manager.activate_worker_node(host).await?;
+            worker_ids.push(worker_node.id);
+        }
+
+        let scheduler = Scheduler::new(cluster_manager.clone(), env.hash_mapping_manager_ref());
+        let mut locations = ScheduledLocations::new();
+
+        // Make the parallel units on the first worker busy.
+        for parallel_unit in cluster_manager.list_parallel_units(None).await {
+            if parallel_unit.worker_node_id == worker_ids[0] {
+                locations.actor_counts.insert(parallel_unit.id, 10);
+            }
+        }
+
+        let new_actor = |actor_id: ActorId, fragment_id: u32| StreamActor {
+            actor_id,
+            fragment_id,
+            nodes: Some(StreamNode {
+                node_body: Some(NodeBody::Materialize(MaterializeNode {
+                    table_ref_id: Some(TableRefId {
+                        table_id: fragment_id as i32,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }),
+            dispatcher: vec![],
+            upstream_actor_id: vec![],
+            same_worker_node_as_upstream: false,
+            vnode_bitmap: vec![],
+        };
+
+        let mut single_fragment = Fragment {
+            fragment_id: 1,
+            fragment_type: 0,
+            distribution_type: FragmentDistributionType::Single as i32,
+            actors: vec![new_actor(1, 1)],
+            vnode_mapping: None,
+        };
+        scheduler
+            .schedule(&mut single_fragment, &mut locations)
+            .await?;
+        assert_eq!(locations.actor_locations[&1].worker_node_id, worker_ids[1]);
+
+        // A hash fragment with as many actors as the hash parallel units on one worker.
+        let parallel_degree = DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1;
+        let mut hash_fragment = Fragment {
+            fragment_id: 2,
+            fragment_type: 0,
+            distribution_type: FragmentDistributionType::Hash as i32,
+            actors: (2..2 + parallel_degree as ActorId)
+                .map(|actor_id| new_actor(actor_id, 2))
+                .collect_vec(),
+            vnode_mapping: None,
+        };
+        scheduler
+            .schedule(&mut hash_fragment, &mut locations)
+            .await?;
+        let mut vnode_sum = 0;
+        for actor in hash_fragment.actors {
+            assert_eq!(
+                locations.actor_locations[&actor.actor_id].worker_node_id,
+                worker_ids[1]
+            );
+            for byte in actor.vnode_bitmap {
+                vnode_sum += byte.count_ones();
+            }
+        }
+        assert_eq!(vnode_sum as usize, VIRTUAL_NODE_COUNT);
+
+        Ok(())
+    }
 }
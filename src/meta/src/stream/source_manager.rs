@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
@@ -24,24 +25,32 @@ use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::Source;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::stream_plan::StreamSourceState;
 use risingwave_pb::stream_service::{
     CreateSourceRequest as ComputeNodeCreateSourceRequest,
     DropSourceRequest as ComputeNodeDropSourceRequest,
 };
 use risingwave_rpc_client::StreamClient;
 
-use crate::barrier::BarrierManagerRef;
+use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::ClusterManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, SourceId};
 use crate::model::ActorId;
 use crate::storage::MetaStore;
+use crate::stream::FragmentManagerRef;
 
 pub type SourceManagerRef<S> = Arc<SourceManager<S>>;
 
+/// How often the splits of the sources are listed to discover the new ones, e.g., the partitions
+/// added to a Kafka topic.
+const SPLIT_DISCOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
 pub struct SourceManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     cluster_manager: ClusterManagerRef<S>,
+    barrier_manager: BarrierManagerRef<S>,
     catalog_manager: CatalogManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
 }
 
 impl<S> SourceManager<S>
@@ -51,13 +60,16 @@ where
     pub async fn new(
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
-        _barrier_manager: BarrierManagerRef<S>,
+        barrier_manager: BarrierManagerRef<S>,
         catalog_manager: CatalogManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
     ) -> Result<Self> {
         Ok(Self {
             env,
             cluster_manager,
+            barrier_manager,
             catalog_manager,
+            fragment_manager,
         })
     }
 
@@ -131,6 +143,40 @@ where
         Ok(result)
     }
 
+    /// Whether the source actors reading the stream source can be shared among materialized views.
+    ///
+    /// A materialized view attached to running source actors only sees the messages consumed from
+    /// then on, which is exactly what it would read on its own only if the source starts from the
+    /// latest offset. Otherwise, e.g., with `scan.startup.mode = 'earliest'` or a startup
+    /// timestamp, each materialized view reads the source on its own from the start offset.
+    pub async fn is_source_shareable(
+        &self,
+        source_id: SourceId,
+        affiliated_source: &Option<Source>,
+    ) -> Result<bool> {
+        let source = match affiliated_source {
+            Some(source) if source.get_id() == source_id => Some(source.clone()),
+            _ => {
+                let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
+                catalog_guard.get_source(source_id).await?
+            }
+        };
+        let info = match source.as_ref().map(Source::get_info).transpose()? {
+            Some(Info::StreamSource(info)) => info,
+            _ => return Ok(false),
+        };
+
+        let properties = ConnectorProperties::extract(info.properties.clone())
+            .map_err(|e| RwError::from(ConnectorError(e.to_string())))?;
+        let (scan_startup_mode, time_offset) = match properties {
+            ConnectorProperties::Kafka(props) => (props.scan_startup_mode, props.time_offset),
+            ConnectorProperties::Pulsar(props) => (props.scan_startup_mode, props.time_offset),
+            _ => return Ok(false),
+        };
+        Ok(time_offset.is_none()
+            && scan_startup_mode.map_or(false, |mode| mode.eq_ignore_ascii_case("latest")))
+    }
+
     async fn all_stream_clients(&self) -> Result<impl Iterator<Item = StreamClient>> {
         // FIXME: there is gap between the compute node activate itself and source ddl operation,
         // create/drop source(non-stateful source like TableSource) before the compute node
@@ -183,8 +229,164 @@ where
         Ok(())
     }
 
+    /// Keeps discovering the new splits of the sources and assigning them to the source actors.
     pub async fn run(&self) -> Result<()> {
-        // todo: in the future, split change will be pushed as a long running service
+        let mut ticker = tokio::time::interval(SPLIT_DISCOVERY_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.discover_splits().await {
+                log::warn!("failed to discover source splits: {}", e);
+            }
+        }
+    }
+
+    /// Lists the splits of the sources read by the created materialized views, and assigns the ones
+    /// not being read yet to the source actors by a `SourceChangeSplit` barrier.
+    async fn discover_splits(&self) -> Result<()> {
+        let mut source_fragments = HashMap::<SourceId, Vec<_>>::new();
+        for table_fragments in self.fragment_manager.list_table_fragments().await? {
+            if !table_fragments.is_created() {
+                continue;
+            }
+            for (source_id, actor_splits) in table_fragments.stream_source_fragments() {
+                source_fragments
+                    .entry(source_id)
+                    .or_default()
+                    .push(actor_splits);
+            }
+        }
+
+        let mut actor_splits = HashMap::new();
+        for (source_id, fragments) in source_fragments {
+            let source = {
+                let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
+                catalog_guard.get_source(source_id).await?
+            };
+            // The source is being dropped.
+            let source = match source {
+                Some(source) => source,
+                None => continue,
+            };
+            let splits = match self.fetch_splits_for_source(&source).await {
+                Ok(splits) => splits,
+                Err(e) => {
+                    log::warn!("failed to list splits of source {}: {}", source_id, e);
+                    continue;
+                }
+            };
+            for fragment in fragments {
+                actor_splits.extend(assign_new_splits(fragment, &splits)?);
+            }
+        }
+
+        if !actor_splits.is_empty() {
+            log::info!(
+                "assigning new splits to source actors: {:?}",
+                actor_splits.keys()
+            );
+            self.barrier_manager
+                .run_command(Command::SourceChangeSplit(actor_splits))
+                .await?;
+        }
+
         Ok(())
     }
 }
+
+/// Assigns the splits not read by any actor of a source fragment yet, each to the actor reading the
+/// fewest splits. Returns the whole split assignment of the actors that get new splits.
+fn assign_new_splits(
+    fragment: BTreeMap<ActorId, Option<StreamSourceState>>,
+    splits: &[SplitImpl],
+) -> Result<HashMap<ActorId, StreamSourceState>> {
+    let mut assigned_split_ids = HashSet::new();
+    let mut assignment = BTreeMap::new();
+    for (actor_id, state) in fragment {
+        let actor_splits = match state {
+            Some(state) => {
+                for split in &state.stream_source_splits {
+                    let split = SplitImpl::restore_from_bytes(state.split_type.clone(), split)
+                        .to_rw_result()?;
+                    assigned_split_ids.insert(split.id());
+                }
+                state.stream_source_splits
+            }
+            None => vec![],
+        };
+        assignment.insert(actor_id, actor_splits);
+    }
+
+    let mut changed_actors = HashSet::new();
+    for split in splits {
+        if assigned_split_ids.contains(&split.id()) {
+            continue;
+        }
+        if let Some((&actor_id, actor_splits)) = assignment
+            .iter_mut()
+            .min_by_key(|(_, actor_splits)| actor_splits.len())
+        {
+            actor_splits.push(split.to_json_bytes().to_vec());
+            changed_actors.insert(actor_id);
+        }
+    }
+
+    Ok(assignment
+        .into_iter()
+        .filter(|(actor_id, _)| changed_actors.contains(actor_id))
+        .map(|(actor_id, actor_splits)| {
+            (
+                actor_id,
+                StreamSourceState {
+                    split_type: splits[0].get_type(),
+                    stream_source_splits: actor_splits,
+                },
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kafka_split(partition: i32) -> SplitImpl {
+        let json = format!(
+            r#"{{"topic":"t","partition":{},"start_offset":null,"stop_offset":null}}"#,
+            partition
+        );
+        SplitImpl::restore_from_bytes("kafka".to_string(), json.as_bytes()).unwrap()
+    }
+
+    fn source_state(splits: &[SplitImpl]) -> Option<StreamSourceState> {
+        Some(StreamSourceState {
+            split_type: "kafka".to_string(),
+            stream_source_splits: splits
+                .iter()
+                .map(|split| split.to_json_bytes().to_vec())
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_assign_new_splits() {
+        let splits = (0..5).map(kafka_split).collect::<Vec<_>>();
+        let fragment = BTreeMap::from([
+            (1, source_state(&splits[0..2])),
+            (2, source_state(&splits[2..3])),
+            (3, None),
+        ]);
+
+        // Nothing to do if all splits are being read.
+        assert!(assign_new_splits(fragment.clone(), &splits[0..3])
+            .unwrap()
+            .is_empty());
+
+        let assignment = assign_new_splits(fragment, &splits).unwrap();
+        assert_eq!(assignment.len(), 2);
+        assert_eq!(assignment[&3], source_state(&splits[3..4]).unwrap());
+        assert_eq!(
+            assignment[&2],
+            source_state(&[splits[2].clone(), splits[4].clone()]).unwrap()
+        );
+    }
+}
@@ -337,6 +337,8 @@ impl StreamActorBuilder {
                     )| *same_worker_node,
                 ),
             vnode_bitmap: vec![],
+            // will be filled later by `TableFragments::new`
+            table_id: 0,
         }
     }
 }
@@ -25,8 +25,11 @@ use risingwave_common::util::compress::compress_data;
 use risingwave_pb::catalog::Source;
 use risingwave_pb::common::{ActorInfo, ParallelUnitMapping, WorkerType};
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus};
+use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::{ActorMapping, DispatcherType, StreamNode, StreamSourceState};
+use risingwave_pb::stream_plan::{
+    ActorMapping, DispatcherType, FragmentType, MergeNode, StreamNode, StreamSourceState,
+};
 use risingwave_pb::stream_service::{
     BroadcastActorInfoTableRequest, BuildActorsRequest, HangingChannel, UpdateActorsRequest,
 };
@@ -36,10 +39,13 @@ use uuid::Uuid;
 use super::ScheduledLocations;
 use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::{ClusterManagerRef, ParallelUnitId, WorkerId};
-use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, DispatcherId, TableFragments};
+use crate::manager::{
+    HashMappingManagerRef, IdCategory, IdGeneratorManagerRef, MetaSrvEnv, SourceId,
+    TableOptionManagerRef,
+};
+use crate::model::{ActorId, DispatcherId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
-use crate::stream::{FragmentManagerRef, Scheduler, SourceManagerRef};
+use crate::stream::{record_table_options, FragmentManagerRef, Scheduler, SourceManagerRef};
 
 pub type GlobalStreamManagerRef<S> = Arc<GlobalStreamManager<S>>;
 
@@ -77,6 +83,12 @@ pub struct GlobalStreamManager<S: MetaStore> {
     /// Maintains vnode mapping of all fragments and state tables.
     hash_mapping_manager: HashMappingManagerRef,
 
+    /// Maintains options of state tables.
+    table_option_manager: TableOptionManagerRef,
+
+    /// Generates the ids of the source jobs.
+    id_gen_manager: IdGeneratorManagerRef<S>,
+
     /// Schedules streaming actors into compute nodes
     scheduler: Scheduler<S>,
 
@@ -102,6 +114,8 @@ where
             cluster_manager,
             source_manager,
             hash_mapping_manager: env.hash_mapping_manager_ref(),
+            table_option_manager: env.table_option_manager_ref(),
+            id_gen_manager: env.id_gen_manager_ref(),
             client_pool: env.stream_client_pool_ref(),
         })
     }
@@ -273,6 +287,141 @@ where
         Ok(())
     }
 
+    /// Share the actors reading a stream source among the materialized views on it, instead of
+    /// consuming the external system once for each of them. Only the sources starting from the
+    /// latest offset are shared, see [`SourceManager::is_source_shareable`] for the reason.
+    ///
+    /// The source fragment of the first materialized view on the source broadcasts its output
+    /// through the cross-MV dispatcher, and is split off into a source job of its own after being
+    /// scheduled. The source job lives until the last materialized view on it is dropped, so none
+    /// of the materialized views depends on another.
+    ///
+    /// The source fragments of the later ones are replaced with merge nodes reading from the
+    /// actors of the source job, which are added as outputs of the cross-MV dispatchers. Since the
+    /// output is broadcast, all materialized views see the same input in the same order, and each
+    /// downstream has its own channel to apply backpressure on. The fragments keep reading on their
+    /// own if there're more actors than the source job, since some actors would never receive
+    /// barriers otherwise.
+    ///
+    /// Returns the fragments to be split off into source jobs.
+    ///
+    /// [`SourceManager::is_source_shareable`]: crate::stream::SourceManager::is_source_shareable
+    async fn resolve_shared_source(
+        &self,
+        table_fragments: &mut TableFragments,
+        dispatches: &mut HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+        upstream_node_actors: &mut HashMap<WorkerId, Vec<ActorId>>,
+        table_sink_map: &mut HashMap<TableId, Vec<ActorId>>,
+        affiliated_source: &Option<Source>,
+    ) -> Result<Vec<FragmentId>> {
+        let mut source_job_fragment_ids = vec![];
+        for fragment in table_fragments.fragments.values_mut() {
+            if fragment.fragment_type != FragmentType::Source as i32 {
+                continue;
+            }
+            let Some(NodeBody::Source(source_node)) =
+                fragment.actors[0].get_nodes()?.node_body.as_ref()
+            else {
+                continue;
+            };
+            if source_node.source_type != SourceType::Source as i32 {
+                continue;
+            }
+            let source_id = source_node.get_table_ref_id()?.table_id as SourceId;
+            if !self
+                .source_manager
+                .is_source_shareable(source_id, affiliated_source)
+                .await?
+            {
+                continue;
+            }
+
+            let Some((upstream_table_id, node_actors)) = self
+                .fragment_manager
+                .get_shared_source_actors(source_node)
+                .await
+            else {
+                // Broadcast to the downstream fragment through the cross-MV dispatcher, to which
+                // the later materialized views add their outputs.
+                for actor in &mut fragment.actors {
+                    let [dispatcher] = actor.dispatcher.as_mut_slice() else {
+                        return Err(internal_error(format!(
+                            "source actor {} should have exactly one dispatcher",
+                            actor.actor_id
+                        )));
+                    };
+                    dispatcher.r#type = DispatcherType::Broadcast as i32;
+                    dispatcher.dispatcher_id = 0;
+                    dispatcher.column_indices.clear();
+                }
+                source_job_fragment_ids.push(fragment.fragment_id);
+                continue;
+            };
+
+            let upstream_actors = node_actors
+                .into_iter()
+                .flat_map(|(node_id, actor_ids)| {
+                    actor_ids
+                        .into_iter()
+                        .map(move |actor_id| (node_id, actor_id))
+                })
+                .sorted_by_key(|(_, actor_id)| *actor_id)
+                .collect_vec();
+            if upstream_actors.len() < fragment.actors.len() {
+                continue;
+            }
+
+            // Distribute the upstream actors to the actors of this fragment in a round-robin way.
+            let mut merge_upstreams: Vec<Vec<ActorId>> = vec![vec![]; fragment.actors.len()];
+            for (idx, (node_id, upstream_actor_id)) in upstream_actors.into_iter().enumerate() {
+                let actor_idx = idx % fragment.actors.len();
+                merge_upstreams[actor_idx].push(upstream_actor_id);
+                dispatches
+                    .entry((upstream_actor_id, 0))
+                    .or_default()
+                    .push(fragment.actors[actor_idx].actor_id);
+                upstream_node_actors
+                    .entry(node_id)
+                    .or_default()
+                    .push(upstream_actor_id);
+                table_sink_map
+                    .entry(upstream_table_id)
+                    .or_default()
+                    .push(upstream_actor_id);
+            }
+
+            for (actor, upstream_actor_id) in fragment.actors.iter_mut().zip_eq(merge_upstreams) {
+                let source_stream_node = actor.nodes.take().unwrap();
+                actor.nodes = Some(StreamNode {
+                    input: vec![],
+                    pk_indices: source_stream_node.pk_indices,
+                    node_body: Some(NodeBody::Merge(MergeNode {
+                        upstream_actor_id: upstream_actor_id.clone(),
+                        fields: source_stream_node.fields.clone(),
+                    })),
+                    fields: source_stream_node.fields,
+                    operator_id: source_stream_node.operator_id,
+                    identity: "MergeExecutor".to_string(),
+                    append_only: source_stream_node.append_only,
+                });
+                actor.upstream_actor_id = upstream_actor_id;
+            }
+            // Barriers reach the actors from the shared upstream now.
+            fragment.fragment_type = FragmentType::Others as i32;
+        }
+
+        for actor_ids in upstream_node_actors.values_mut() {
+            actor_ids.sort_unstable();
+            actor_ids.dedup();
+        }
+        for actor_ids in table_sink_map.values_mut() {
+            actor_ids.sort_unstable();
+            actor_ids.dedup();
+        }
+
+        Ok(source_job_fragment_ids)
+    }
+
     /// Create materialized view, it works as follows:
     /// 1. schedule the actors to nodes in the cluster.
     /// 2. broadcast the actor info table.
@@ -289,7 +438,7 @@ where
         CreateMaterializedViewContext {
             mut dispatches,
             mut upstream_node_actors,
-            table_sink_map,
+            mut table_sink_map,
             dependent_table_ids,
             affiliated_source,
             table_id_offset: _,
@@ -308,6 +457,7 @@ where
 
         let mut locations = ScheduledLocations::new();
         locations.node_locations = nodes.into_iter().map(|node| (node.id, node)).collect();
+        locations.actor_counts = self.fragment_manager.parallel_unit_actor_counts().await;
 
         let topological_order = table_fragments.generate_topological_order();
 
@@ -317,6 +467,7 @@ where
             let fragment = table_fragments.fragments.get_mut(&fragment_id).unwrap();
             self.scheduler.schedule(fragment, &mut locations).await?;
         }
+        record_table_options(&self.table_option_manager, &table_fragments)?;
 
         // resolve chain node infos, including:
         // 1. insert upstream actor id in merge node
@@ -330,6 +481,18 @@ where
         )
         .await?;
 
+        // Read from the source job of the stream source if possible, which must be done before
+        // assigning the splits.
+        let source_job_fragment_ids = self
+            .resolve_shared_source(
+                &mut table_fragments,
+                &mut dispatches,
+                &mut upstream_node_actors,
+                &mut table_sink_map,
+                &affiliated_source,
+            )
+            .await?;
+
         // Verify whether all same_as_upstream constraints are satisfied.
         //
         // Currently, the scheduler (when there's no scale-in or scale-out) will always schedule
@@ -431,10 +594,27 @@ where
             .collect();
 
         table_fragments.set_actor_status(actor_info);
+
+        // Split off the source fragments to be shared into source jobs.
+        let mut source_jobs = Vec::with_capacity(source_job_fragment_ids.len());
+        for fragment_id in source_job_fragment_ids {
+            let source_job_id = self
+                .id_gen_manager
+                .generate::<{ IdCategory::Table }>()
+                .await? as u32;
+            source_jobs
+                .push(table_fragments.split_off_fragment(fragment_id, TableId::new(source_job_id)));
+        }
+
         let mut actor_map = table_fragments.actor_map();
+        actor_map.extend(source_jobs.iter().flat_map(TableFragments::actor_map));
 
         let mut source_actors_group_by_fragment = HashMap::new();
-        for fragment in table_fragments.fragments() {
+        for fragment in table_fragments
+            .fragments()
+            .into_iter()
+            .chain(source_jobs.iter().flat_map(TableFragments::fragments))
+        {
             let mut source_actors = HashMap::new();
             for actor in &fragment.actors {
                 if let Some(source_id) =
@@ -625,6 +805,15 @@ where
         }
 
         // Add table fragments to meta store with state: `State::Creating`.
+        let source_job_ids = source_jobs
+            .iter()
+            .map(TableFragments::table_id)
+            .collect_vec();
+        for source_job in source_jobs {
+            self.fragment_manager
+                .start_create_table_fragments(source_job)
+                .await?;
+        }
         self.fragment_manager
             .start_create_table_fragments(table_fragments.clone())
             .await?;
@@ -633,14 +822,25 @@ where
             .barrier_manager
             .run_command(Command::CreateMaterializedView {
                 table_fragments,
+                source_job_ids: source_job_ids.clone(),
                 table_sink_map,
                 dispatches,
             })
             .await
         {
-            self.fragment_manager
-                .cancel_create_table_fragments(&table_id)
-                .await?;
+            // The table fragments are already dropped if the creation is cancelled.
+            for table_id in source_job_ids.iter().chain(std::iter::once(&table_id)) {
+                if self
+                    .fragment_manager
+                    .is_table_fragments_created(table_id)
+                    .await
+                    .is_some()
+                {
+                    self.fragment_manager
+                        .cancel_create_table_fragments(table_id)
+                        .await?;
+                }
+            }
             return Err(err);
         }
 
@@ -649,11 +849,41 @@ where
 
     /// Dropping materialized view is done by barrier manager. Check
     /// [`Command::DropMaterializedView`] for details.
+    ///
+    /// The source jobs no other materialized view reads from are dropped along with it.
     pub async fn drop_materialized_view(&self, table_id: &TableId) -> Result<()> {
         self.barrier_manager
             .run_command(Command::DropMaterializedView(*table_id))
             .await?;
 
+        for source_job_id in self.fragment_manager.list_orphaned_source_jobs().await {
+            self.barrier_manager
+                .run_command(Command::DropMaterializedView(source_job_id))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rescale the hash distributed fragments of the materialized view to `parallelism` actors by
+    /// reassigning the vnodes owned by the actors. Check [`TableFragments::reassign_vnodes`] and
+    /// [`Command::UpdateVnodeBitmaps`] for details.
+    pub async fn alter_parallelism(&self, table_id: &TableId, parallelism: usize) -> Result<()> {
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        let (actor_vnode_bitmaps, dispatcher_hash_mappings) =
+            table_fragments.reassign_vnodes(parallelism)?;
+
+        self.barrier_manager
+            .run_command(Command::UpdateVnodeBitmaps {
+                table_id: *table_id,
+                actor_vnode_bitmaps,
+                dispatcher_hash_mappings,
+            })
+            .await?;
+
         Ok(())
     }
 
@@ -672,6 +902,17 @@ where
         Ok(())
     }
 
+    /// Pauses all streaming sources by injecting a `Pause` barrier. Data already in flight will
+    /// still be processed, and barriers keep flowing.
+    pub async fn pause(&self) -> Result<()> {
+        self.barrier_manager.run_command(Command::pause()).await
+    }
+
+    /// Resumes streaming sources paused by [`GlobalStreamManager::pause`].
+    pub async fn resume(&self) -> Result<()> {
+        self.barrier_manager.run_command(Command::resume()).await
+    }
+
     // fn
 }
 
@@ -685,6 +926,7 @@ mod tests {
 
     use risingwave_common::catalog::TableId;
     use risingwave_common::error::tonic_err;
+    use risingwave_common::util::compress::decompress_data;
     use risingwave_pb::common::{HostAddress, WorkerType};
     use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
     use risingwave_pb::meta::table_fragments::Fragment;
@@ -784,6 +1026,20 @@ mod tests {
             Ok(Response::new(InjectBarrierResponse::default()))
         }
 
+        async fn get_inflight_barrier(
+            &self,
+            _request: Request<GetInflightBarrierRequest>,
+        ) -> std::result::Result<Response<GetInflightBarrierResponse>, Status> {
+            Ok(Response::new(GetInflightBarrierResponse::default()))
+        }
+
+        async fn stack_trace(
+            &self,
+            _request: Request<StackTraceRequest>,
+        ) -> std::result::Result<Response<StackTraceResponse>, Status> {
+            Ok(Response::new(StackTraceResponse::default()))
+        }
+
         async fn create_source(
             &self,
             _request: Request<CreateSourceRequest>,
@@ -811,6 +1067,13 @@ mod tests {
         ) -> std::result::Result<Response<SyncSourcesResponse>, Status> {
             Ok(Response::new(SyncSourcesResponse::default()))
         }
+
+        async fn shutdown(
+            &self,
+            _request: Request<ShutdownRequest>,
+        ) -> std::result::Result<Response<ShutdownResponse>, Status> {
+            Ok(Response::new(ShutdownResponse::default()))
+        }
     }
 
     struct MockServices {
@@ -852,7 +1115,7 @@ mod tests {
                 port: port as i32,
             };
             cluster_manager
-                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .add_worker_node(host.clone(), WorkerType::ComputeNode, 0)
                 .await?;
             cluster_manager.activate_worker_node(host).await?;
 
@@ -878,6 +1141,7 @@ mod tests {
                     cluster_manager.clone(),
                     barrier_manager.clone(),
                     catalog_manager.clone(),
+                    fragment_manager.clone(),
                 )
                 .await?,
             );
@@ -1011,4 +1275,202 @@ mod tests {
         services.stop().await;
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_migrate_actors() -> Result<()> {
+        let services = MockServices::start("127.0.0.1", 12334).await?;
+
+        let table_ref_id = TableRefId {
+            schema_ref_id: None,
+            table_id: 0,
+        };
+        let table_id = TableId::from(&Some(table_ref_id.clone()));
+        let actors = (0..5)
+            .map(|i| StreamActor {
+                actor_id: i,
+                nodes: Some(risingwave_pb::stream_plan::StreamNode {
+                    node_body: Some(
+                        risingwave_pb::stream_plan::stream_node::NodeBody::Materialize(
+                            risingwave_pb::stream_plan::MaterializeNode {
+                                table_ref_id: Some(table_ref_id.clone()),
+                                ..Default::default()
+                            },
+                        ),
+                    ),
+                    operator_id: 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut fragments = BTreeMap::default();
+        fragments.insert(
+            0,
+            Fragment {
+                fragment_id: 0,
+                fragment_type: FragmentType::Sink as i32,
+                distribution_type: FragmentDistributionType::Hash as i32,
+                actors,
+                vnode_mapping: None,
+            },
+        );
+        services
+            .global_stream_manager
+            .create_materialized_view(
+                TableFragments::new(table_id, fragments),
+                CreateMaterializedViewContext::default(),
+            )
+            .await?;
+
+        // Move every parallel unit in use to a new worker.
+        let old_parallel_units = services.fragment_manager.all_actor_parallel_units().await;
+        let migrate_map: HashMap<_, _> = old_parallel_units
+            .values()
+            .map(|unit| {
+                let mut new_unit = unit.clone();
+                new_unit.id += 100;
+                new_unit.worker_node_id = 42;
+                (unit.id, new_unit)
+            })
+            .collect();
+        services
+            .fragment_manager
+            .migrate_actors(&migrate_map)
+            .await?;
+
+        let new_parallel_units = services.fragment_manager.all_actor_parallel_units().await;
+        assert_eq!(new_parallel_units.len(), old_parallel_units.len());
+        assert!(new_parallel_units
+            .values()
+            .all(|unit| unit.worker_node_id == 42 && migrate_map.contains_key(&(unit.id - 100))));
+        for table_fragments in services.fragment_manager.list_table_fragments().await? {
+            for fragment in table_fragments.fragments() {
+                if let Some(vnode_mapping) = &fragment.vnode_mapping {
+                    assert!(vnode_mapping
+                        .data
+                        .iter()
+                        .all(|id| new_parallel_units.contains_key(id)));
+                }
+            }
+        }
+
+        services.stop().await;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_alter_parallelism() -> Result<()> {
+        let services = MockServices::start("127.0.0.1", 12335).await?;
+
+        let table_ref_id = TableRefId {
+            schema_ref_id: None,
+            table_id: 0,
+        };
+        let table_id = TableId::from(&Some(table_ref_id.clone()));
+
+        // A singleton actor hash dispatching to 4 materialize actors.
+        let upstream_actor = StreamActor {
+            actor_id: 0,
+            fragment_id: 1,
+            nodes: Some(StreamNode {
+                node_body: Some(NodeBody::Project(ProjectNode::default())),
+                operator_id: 2,
+                ..Default::default()
+            }),
+            dispatcher: vec![Dispatcher {
+                r#type: DispatcherType::Hash as i32,
+                column_indices: vec![0],
+                dispatcher_id: 3,
+                downstream_actor_id: (1..5).collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let actors = (1..5)
+            .map(|i| StreamActor {
+                actor_id: i,
+                fragment_id: 0,
+                nodes: Some(StreamNode {
+                    node_body: Some(NodeBody::Materialize(MaterializeNode {
+                        table_ref_id: Some(table_ref_id.clone()),
+                        ..Default::default()
+                    })),
+                    operator_id: 1,
+                    ..Default::default()
+                }),
+                upstream_actor_id: vec![0],
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut fragments = BTreeMap::default();
+        fragments.insert(
+            0,
+            Fragment {
+                fragment_id: 0,
+                fragment_type: FragmentType::Sink as i32,
+                distribution_type: FragmentDistributionType::Hash as i32,
+                actors,
+                vnode_mapping: None,
+            },
+        );
+        fragments.insert(
+            1,
+            Fragment {
+                fragment_id: 1,
+                fragment_type: FragmentType::Others as i32,
+                distribution_type: FragmentDistributionType::Single as i32,
+                actors: vec![upstream_actor],
+                vnode_mapping: None,
+            },
+        );
+        services
+            .global_stream_manager
+            .create_materialized_view(
+                TableFragments::new(table_id, fragments),
+                CreateMaterializedViewContext::default(),
+            )
+            .await?;
+
+        assert!(services
+            .global_stream_manager
+            .alter_parallelism(&table_id, 0)
+            .await
+            .is_err());
+        assert!(services
+            .global_stream_manager
+            .alter_parallelism(&table_id, 5)
+            .await
+            .is_err());
+        services
+            .global_stream_manager
+            .alter_parallelism(&table_id, 2)
+            .await?;
+
+        // The vnodes are split between actor 1 and 2, and actor 3 and 4 receive nothing.
+        let table_fragments = services
+            .fragment_manager
+            .select_table_fragments_by_table_id(&table_id)
+            .await?;
+        let actor_map = table_fragments.actor_map();
+        let vnode_count = |actor_id: ActorId| {
+            actor_map[&actor_id]
+                .vnode_bitmap
+                .iter()
+                .map(|byte| byte.count_ones() as usize)
+                .sum::<usize>()
+        };
+        assert_eq!(vnode_count(1), VIRTUAL_NODE_COUNT / 2);
+        assert_eq!(vnode_count(2), VIRTUAL_NODE_COUNT / 2);
+        assert_eq!(vnode_count(3), 0);
+        assert_eq!(vnode_count(4), 0);
+
+        let hash_mapping = actor_map[&0].dispatcher[0].get_hash_mapping()?;
+        let hash_mapping = decompress_data(&hash_mapping.original_indices, &hash_mapping.data);
+        let mut expected = vec![1; VIRTUAL_NODE_COUNT / 2];
+        expected.resize(VIRTUAL_NODE_COUNT, 2);
+        assert_eq!(hash_mapping, expected);
+
+        services.stop().await;
+        Ok(())
+    }
 }
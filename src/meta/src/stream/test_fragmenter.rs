@@ -24,7 +24,7 @@ use risingwave_pb::expr::expr_node::RexNode;
 use risingwave_pb::expr::expr_node::Type::{Add, GreaterThan, InputRef};
 use risingwave_pb::expr::{AggCall, ExprNode, FunctionCall, InputRefExpr};
 use risingwave_pb::plan_common::{
-    ColumnOrder, DatabaseRefId, Field, OrderType, SchemaRefId, TableRefId,
+    ColumnOrder, ConflictBehavior, DatabaseRefId, Field, OrderType, SchemaRefId, TableRefId,
 };
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
@@ -245,6 +245,7 @@ fn make_stream_node() -> StreamNode {
             column_ids: vec![0_i32, 1_i32],
             column_orders: vec![make_column_order(1), make_column_order(2)],
             distribution_keys: Default::default(),
+            conflict_behavior: ConflictBehavior::NoCheck as i32,
         })),
         fields: vec![], // TODO: fill this later
         operator_id: 7,
@@ -158,7 +158,11 @@ fn main() -> Result<()> {
                     writeln!(
                         log_buffer,
                         "-- Frontend --\nAccess inside cluster: {}\ntpch-bench args: {}\n",
-                        style(format!("psql -d dev -h {} -p {}", c.address, c.port)).green(),
+                        style(format!(
+                            "psql -d dev -h {} -p {} -U risingwave",
+                            c.address, c.port
+                        ))
+                        .green(),
                         style(format!(
                             "--frontend {} --frontend-port {}",
                             c.address, c.port
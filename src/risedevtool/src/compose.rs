@@ -204,7 +204,7 @@ impl Compose for FrontendConfig {
                 .collect(),
             command,
             ports: vec![format!("{}:{}", self.port, self.port)],
-            expose: vec![self.port.to_string()],
+            expose: vec![self.port.to_string(), self.exporter_port.to_string()],
             depends_on: provide_meta_node.iter().map(|x| x.id.clone()).collect(),
             ..Default::default()
         })
@@ -54,6 +54,14 @@ impl PrometheusGen {
             .map(|node| format!("\"{}:{}\"", node.address, node.exporter_port))
             .join(",");
 
+        let frontend_targets = config
+            .provide_frontend
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|node| format!("\"{}:{}\"", node.address, node.exporter_port))
+            .join(",");
+
         format!(
             r#"# --- THIS FILE IS AUTO GENERATED BY RISEDEV ---
 global:
@@ -81,6 +89,10 @@ scrape_configs:
   - job_name: compactor-job
     static_configs:
       - targets: [{compactor_targets}]
+
+  - job_name: frontend-job
+    static_configs:
+      - targets: [{frontend_targets}]
 "#,
         )
     }
@@ -56,6 +56,7 @@ pub struct MetaNodeConfig {
     pub user_managed: bool,
 
     pub provide_etcd_backend: Option<Vec<EtcdConfig>>,
+    pub provide_jaeger: Option<Vec<JaegerConfig>>,
 
     pub enable_dashboard_v2: bool,
     pub unsafe_disable_recovery: bool,
@@ -73,8 +74,10 @@ pub struct FrontendConfig {
     #[serde(with = "string")]
     pub port: u16,
     pub listen_address: String,
+    pub exporter_port: u16,
 
     pub provide_meta_node: Option<Vec<MetaNodeConfig>>,
+    pub provide_jaeger: Option<Vec<JaegerConfig>>,
     pub user_managed: bool,
 }
 
@@ -157,6 +160,7 @@ pub struct PrometheusConfig {
     pub provide_meta_node: Option<Vec<MetaNodeConfig>>,
     pub provide_minio: Option<Vec<MinioConfig>>,
     pub provide_compactor: Option<Vec<CompactorConfig>>,
+    pub provide_frontend: Option<Vec<FrontendConfig>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
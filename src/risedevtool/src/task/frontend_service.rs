@@ -43,7 +43,14 @@ impl FrontendService {
     /// Apply command args accroding to config
     pub fn apply_command_args(cmd: &mut Command, config: &FrontendConfig) -> Result<()> {
         cmd.arg("--host")
-            .arg(format!("{}:{}", config.listen_address, config.port));
+            .arg(format!("{}:{}", config.listen_address, config.port))
+            .arg("--prometheus-listener-addr")
+            .arg(format!(
+                "{}:{}",
+                config.listen_address, config.exporter_port
+            ))
+            .arg("--metrics-level")
+            .arg("1");
 
         let provide_meta_node = config.provide_meta_node.as_ref().unwrap();
         match provide_meta_node.len() {
@@ -65,6 +72,20 @@ impl FrontendService {
             }
         };
 
+        let provide_jaeger = config.provide_jaeger.as_ref().unwrap();
+        match provide_jaeger.len() {
+            0 => {}
+            1 => {
+                cmd.arg("--enable-jaeger-tracing");
+            }
+            other_size => {
+                return Err(anyhow!(
+                    "{} Jaeger instance found in config, but only 1 is needed",
+                    other_size
+                ))
+            }
+        }
+
         Ok(())
     }
 }
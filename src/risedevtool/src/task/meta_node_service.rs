@@ -73,6 +73,20 @@ impl MetaNodeService {
             }
         }
 
+        let provide_jaeger = config.provide_jaeger.as_ref().unwrap();
+        match provide_jaeger.len() {
+            0 => {}
+            1 => {
+                cmd.arg("--enable-jaeger-tracing");
+            }
+            other_size => {
+                return Err(anyhow!(
+                    "{} Jaeger instance found in config, but only 1 is needed",
+                    other_size
+                ))
+            }
+        }
+
         if config.enable_dashboard_v2 {
             cmd.arg("--dashboard-ui-path")
                 .arg(env::var("PREFIX_UI").unwrap_or_else(|_| ".risingwave/ui".to_owned()));
@@ -21,13 +21,14 @@ use risingwave_common::array::DataChunk;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, ToRwResult};
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::tracing_context::TracingContext;
 use risingwave_pb::batch_plan::exchange_info::DistributionMode;
 use risingwave_pb::batch_plan::{ExchangeInfo, PlanFragment, PlanNode, TaskId, TaskOutputId};
 use risingwave_pb::task_service::exchange_service_client::ExchangeServiceClient;
 use risingwave_pb::task_service::task_service_client::TaskServiceClient;
 use risingwave_pb::task_service::{
-    CreateTaskRequest, CreateTaskResponse, GetDataRequest, GetDataResponse, GetStreamRequest,
-    GetStreamResponse,
+    AbortTaskRequest, CreateTaskRequest, CreateTaskResponse, GetDataRequest, GetDataResponse,
+    GetStreamRequest, GetStreamResponse,
 };
 use tonic::transport::{Channel, Endpoint};
 use tonic::Streaming;
@@ -113,6 +114,7 @@ impl ComputeClient {
                 task_id: Some(task_id),
                 plan: Some(plan),
                 epoch,
+                tracing_context: TracingContext::from_current_span().to_protobuf(),
             })
             .await?;
         Ok(())
@@ -129,11 +131,25 @@ impl ComputeClient {
                 task_id: Some(task_id),
                 plan: Some(plan),
                 epoch,
+                tracing_context: TracingContext::from_current_span().to_protobuf(),
             })
             .await?;
         Ok(())
     }
 
+    /// Aborts a running task, e.g., when the query it belongs to is cancelled.
+    pub async fn abort_task(&self, task_id: TaskId) -> Result<()> {
+        let _ = self
+            .task_client
+            .to_owned()
+            .abort_task(AbortTaskRequest {
+                task_id: Some(task_id),
+            })
+            .await
+            .to_rw_result()?;
+        Ok(())
+    }
+
     async fn create_task_inner(&self, req: CreateTaskRequest) -> Result<CreateTaskResponse> {
         Ok(self
             .task_client
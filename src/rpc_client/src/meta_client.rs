@@ -23,17 +23,22 @@ use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Schema as ProstSchema, Sink as ProstSink, Source as ProstSource,
+    Table as ProstTable,
 };
-use risingwave_pb::common::{WorkerNode, WorkerType};
+use risingwave_pb::common::{HostAddress, WorkerNode, WorkerType};
 use risingwave_pb::ddl_service::ddl_service_client::DdlServiceClient;
 use risingwave_pb::ddl_service::{
-    CreateDatabaseRequest, CreateDatabaseResponse, CreateMaterializedSourceRequest,
-    CreateMaterializedSourceResponse, CreateMaterializedViewRequest,
-    CreateMaterializedViewResponse, CreateSchemaRequest, CreateSchemaResponse, CreateSourceRequest,
-    CreateSourceResponse, DropDatabaseRequest, DropDatabaseResponse, DropMaterializedSourceRequest,
-    DropMaterializedSourceResponse, DropMaterializedViewRequest, DropMaterializedViewResponse,
-    DropSchemaRequest, DropSchemaResponse, DropSourceRequest, DropSourceResponse,
+    AlterSourceAddColumnRequest, AlterSourceAddColumnResponse, CancelDdlJobRequest,
+    CancelDdlJobResponse, CreateDatabaseRequest, CreateDatabaseResponse,
+    CreateMaterializedSourceRequest, CreateMaterializedSourceResponse,
+    CreateMaterializedViewRequest, CreateMaterializedViewResponse, CreateSchemaRequest,
+    CreateSchemaResponse, CreateSinkRequest, CreateSinkResponse, CreateSourceRequest,
+    CreateSourceResponse, DdlJob, DropDatabaseRequest, DropDatabaseResponse,
+    DropMaterializedSourceRequest, DropMaterializedSourceResponse, DropMaterializedViewRequest,
+    DropMaterializedViewResponse, DropSchemaRequest, DropSchemaResponse, DropSinkRequest,
+    DropSinkResponse, DropSourceRequest, DropSourceResponse, ListDdlJobsRequest,
+    ListDdlJobsResponse,
 };
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
 use risingwave_pb::hummock::{
@@ -48,13 +53,23 @@ use risingwave_pb::meta::cluster_service_client::ClusterServiceClient;
 use risingwave_pb::meta::heartbeat_service_client::HeartbeatServiceClient;
 use risingwave_pb::meta::notification_service_client::NotificationServiceClient;
 use risingwave_pb::meta::stream_manager_service_client::StreamManagerServiceClient;
+use risingwave_pb::meta::system_params_service_client::SystemParamsServiceClient;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
-    AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, FlushRequest,
-    FlushResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest, ListAllNodesResponse,
-    SubscribeRequest, SubscribeResponse,
+    AddWorkerNodeResponse, AlterParallelismRequest, AlterParallelismResponse,
+    CordonWorkerNodeRequest, CordonWorkerNodeResponse, DeleteWorkerNodeRequest,
+    DeleteWorkerNodeResponse, FlushRequest, FlushResponse, GetSystemParamsRequest,
+    GetSystemParamsResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest,
+    ListAllNodesResponse, ListTableFragmentsRequest, ListTableFragmentsResponse, PauseRequest,
+    PauseResponse, ResumeRequest, ResumeResponse, SetSystemParamRequest, SetSystemParamResponse,
+    SubscribeRequest, SubscribeResponse, SystemParams, TableFragments,
 };
+use risingwave_pb::plan_common::ColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::user_service_client::UserServiceClient;
+use risingwave_pb::user::{
+    CreateUserRequest, CreateUserResponse, DropUserRequest, DropUserResponse, UserInfo,
+};
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
@@ -104,10 +119,17 @@ impl MetaClient {
     }
 
     /// Register the current node to the cluster and set the corresponding worker id.
-    pub async fn register(&mut self, addr: &HostAddr, worker_type: WorkerType) -> Result<u32> {
+    /// `worker_node_parallelism` is only meaningful for compute nodes, and 0 means the default.
+    pub async fn register(
+        &mut self,
+        addr: &HostAddr,
+        worker_type: WorkerType,
+        worker_node_parallelism: usize,
+    ) -> Result<u32> {
         let request = AddWorkerNodeRequest {
             worker_type: worker_type as i32,
             host: Some(addr.to_protobuf()),
+            worker_node_parallelism: worker_node_parallelism as u64,
         };
         let resp = self.inner.add_worker_node(request).await?;
         let worker_node =
@@ -165,15 +187,54 @@ impl MetaClient {
         Ok((resp.table_id.into(), resp.version))
     }
 
-    pub async fn drop_materialized_view(&self, table_id: TableId) -> Result<CatalogVersion> {
+    pub async fn drop_materialized_view(
+        &self,
+        table_id: TableId,
+        cascade: bool,
+    ) -> Result<CatalogVersion> {
         let request = DropMaterializedViewRequest {
             table_id: table_id.table_id(),
+            cascade,
         };
 
         let resp = self.inner.drop_materialized_view(request).await?;
         Ok(resp.version)
     }
 
+    pub async fn create_sink(
+        &self,
+        sink: ProstSink,
+        graph: StreamFragmentGraph,
+    ) -> Result<(u32, CatalogVersion)> {
+        let request = CreateSinkRequest {
+            sink: Some(sink),
+            fragment_graph: Some(graph),
+        };
+        let resp = self.inner.create_sink(request).await?;
+        // TODO: handle error in `resp.status` here
+        Ok((resp.sink_id, resp.version))
+    }
+
+    pub async fn drop_sink(&self, sink_id: u32) -> Result<CatalogVersion> {
+        let request = DropSinkRequest { sink_id };
+        let resp = self.inner.drop_sink(request).await?;
+        Ok(resp.version)
+    }
+
+    /// List the DDL jobs creating streaming jobs in progress, and the ones finished recently.
+    pub async fn list_ddl_jobs(&self) -> Result<Vec<DdlJob>> {
+        let request = ListDdlJobsRequest::default();
+        let resp = self.inner.list_ddl_jobs(request).await?;
+        Ok(resp.jobs)
+    }
+
+    /// Cancel a DDL job in progress, dropping the actors it has built.
+    pub async fn cancel_ddl_job(&self, job_id: u32) -> Result<()> {
+        let request = CancelDdlJobRequest { job_id };
+        self.inner.cancel_ddl_job(request).await?;
+        Ok(())
+    }
+
     pub async fn create_source(&self, source: ProstSource) -> Result<(u32, CatalogVersion)> {
         let request = CreateSourceRequest {
             source: Some(source),
@@ -203,22 +264,39 @@ impl MetaClient {
         &self,
         source_id: u32,
         table_id: TableId,
+        cascade: bool,
     ) -> Result<CatalogVersion> {
         let request = DropMaterializedSourceRequest {
             source_id,
             table_id: table_id.table_id(),
+            cascade,
         };
 
         let resp = self.inner.drop_materialized_source(request).await?;
         Ok(resp.version)
     }
 
-    pub async fn drop_source(&self, source_id: u32) -> Result<CatalogVersion> {
-        let request = DropSourceRequest { source_id };
+    pub async fn drop_source(&self, source_id: u32, cascade: bool) -> Result<CatalogVersion> {
+        let request = DropSourceRequest { source_id, cascade };
         let resp = self.inner.drop_source(request).await?;
         Ok(resp.version)
     }
 
+    pub async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        source_version: u64,
+        column: ColumnCatalog,
+    ) -> Result<CatalogVersion> {
+        let request = AlterSourceAddColumnRequest {
+            source_id,
+            source_version,
+            column: Some(column),
+        };
+        let resp = self.inner.alter_source_add_column(request).await?;
+        Ok(resp.version)
+    }
+
     pub async fn drop_database(&self, database_id: u32) -> Result<CatalogVersion> {
         let request = DropDatabaseRequest { database_id };
         let resp = self.inner.drop_database(request).await?;
@@ -231,6 +309,20 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn create_user(&self, user: UserInfo) -> Result<CatalogVersion> {
+        let request = CreateUserRequest { user: Some(user) };
+        let resp = self.inner.create_user(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn drop_user(&self, name: &str) -> Result<CatalogVersion> {
+        let request = DropUserRequest {
+            name: name.to_string(),
+        };
+        let resp = self.inner.drop_user(request).await?;
+        Ok(resp.version)
+    }
+
     /// Unregister the current node to the cluster.
     pub async fn unregister(&self, addr: HostAddr) -> Result<()> {
         let request = DeleteWorkerNodeRequest {
@@ -306,6 +398,59 @@ impl MetaClient {
         self.inner.flush(request).await?;
         Ok(())
     }
+
+    /// Pause all streaming sources in the cluster.
+    pub async fn pause(&self) -> Result<()> {
+        let request = PauseRequest::default();
+        self.inner.pause(request).await?;
+        Ok(())
+    }
+
+    /// Resume all streaming sources in the cluster.
+    pub async fn resume(&self) -> Result<()> {
+        let request = ResumeRequest::default();
+        self.inner.resume(request).await?;
+        Ok(())
+    }
+
+    pub async fn list_table_fragments(&self) -> Result<Vec<TableFragments>> {
+        let request = ListTableFragmentsRequest::default();
+        let resp = self.inner.list_table_fragments(request).await?;
+        Ok(resp.table_fragments)
+    }
+
+    /// Rescale the hash distributed fragments of the materialized view to `parallelism` actors.
+    pub async fn alter_parallelism(&self, table_id: TableId, parallelism: u32) -> Result<()> {
+        let request = AlterParallelismRequest {
+            table_id: table_id.table_id(),
+            parallelism,
+        };
+        self.inner.alter_parallelism(request).await?;
+        Ok(())
+    }
+
+    /// Mark the worker as (un)schedulable. No new actors will be scheduled onto a cordoned worker.
+    pub async fn cordon_worker_node(&self, host: HostAddress, unschedulable: bool) -> Result<()> {
+        let request = CordonWorkerNodeRequest {
+            host: Some(host),
+            unschedulable,
+        };
+        self.inner.cordon_worker_node(request).await?;
+        Ok(())
+    }
+
+    pub async fn get_system_params(&self) -> Result<SystemParams> {
+        let request = GetSystemParamsRequest::default();
+        let resp = self.inner.get_system_params(request).await?;
+        Ok(resp.params.unwrap_or_default())
+    }
+
+    /// Set a mutable system param of the cluster.
+    pub async fn set_system_param(&self, param: String, value: String) -> Result<()> {
+        let request = SetSystemParamRequest { param, value };
+        self.inner.set_system_param(request).await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -393,6 +538,8 @@ pub struct GrpcMetaClient {
     pub hummock_client: HummockManagerServiceClient<Channel>,
     pub notification_client: NotificationServiceClient<Channel>,
     pub stream_client: StreamManagerServiceClient<Channel>,
+    pub user_client: UserServiceClient<Channel>,
+    pub system_params_client: SystemParamsServiceClient<Channel>,
 }
 
 impl GrpcMetaClient {
@@ -409,7 +556,9 @@ impl GrpcMetaClient {
         let ddl_client = DdlServiceClient::new(channel.clone());
         let hummock_client = HummockManagerServiceClient::new(channel.clone());
         let notification_client = NotificationServiceClient::new(channel.clone());
-        let stream_client = StreamManagerServiceClient::new(channel);
+        let stream_client = StreamManagerServiceClient::new(channel.clone());
+        let user_client = UserServiceClient::new(channel.clone());
+        let system_params_client = SystemParamsServiceClient::new(channel);
         Ok(Self {
             cluster_client,
             heartbeat_client,
@@ -417,6 +566,8 @@ impl GrpcMetaClient {
             hummock_client,
             notification_client,
             stream_client,
+            user_client,
+            system_params_client,
         })
     }
 }
@@ -447,18 +598,28 @@ macro_rules! for_all_meta_rpc {
             ,{ cluster_client, activate_worker_node, ActivateWorkerNodeRequest, ActivateWorkerNodeResponse }
             ,{ cluster_client, delete_worker_node, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse }
             ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse }
+            ,{ cluster_client, cordon_worker_node, CordonWorkerNodeRequest, CordonWorkerNodeResponse }
             ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse }
             ,{ stream_client, flush, FlushRequest, FlushResponse }
+            ,{ stream_client, pause, PauseRequest, PauseResponse }
+            ,{ stream_client, resume, ResumeRequest, ResumeResponse }
+            ,{ stream_client, list_table_fragments, ListTableFragmentsRequest, ListTableFragmentsResponse }
+            ,{ stream_client, alter_parallelism, AlterParallelismRequest, AlterParallelismResponse }
             ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
             ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
             ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
+            ,{ ddl_client, create_sink, CreateSinkRequest, CreateSinkResponse }
             ,{ ddl_client, create_schema, CreateSchemaRequest, CreateSchemaResponse }
             ,{ ddl_client, create_database, CreateDatabaseRequest, CreateDatabaseResponse }
             ,{ ddl_client, drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse }
             ,{ ddl_client, drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse }
             ,{ ddl_client, drop_source, DropSourceRequest, DropSourceResponse }
+            ,{ ddl_client, alter_source_add_column, AlterSourceAddColumnRequest, AlterSourceAddColumnResponse }
+            ,{ ddl_client, drop_sink, DropSinkRequest, DropSinkResponse }
             ,{ ddl_client, drop_database, DropDatabaseRequest, DropDatabaseResponse }
             ,{ ddl_client, drop_schema, DropSchemaRequest, DropSchemaResponse }
+            ,{ ddl_client, list_ddl_jobs, ListDdlJobsRequest, ListDdlJobsResponse }
+            ,{ ddl_client, cancel_ddl_job, CancelDdlJobRequest, CancelDdlJobResponse }
             ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse }
             ,{ hummock_client, unpin_version, UnpinVersionRequest, UnpinVersionResponse }
             ,{ hummock_client, pin_snapshot, PinSnapshotRequest, PinSnapshotResponse }
@@ -467,6 +628,10 @@ macro_rules! for_all_meta_rpc {
             ,{ hummock_client, get_new_table_id, GetNewTableIdRequest, GetNewTableIdResponse }
             ,{ hummock_client, subscribe_compact_tasks, SubscribeCompactTasksRequest, Streaming<SubscribeCompactTasksResponse> }
             ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse }
+            ,{ user_client, create_user, CreateUserRequest, CreateUserResponse }
+            ,{ user_client, drop_user, DropUserRequest, DropUserResponse }
+            ,{ system_params_client, get_system_params, GetSystemParamsRequest, GetSystemParamsResponse }
+            ,{ system_params_client, set_system_param, SetSystemParamRequest, SetSystemParamResponse }
         }
     };
 }
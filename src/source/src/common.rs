@@ -24,14 +24,25 @@ use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
 use crate::SourceColumnDesc;
 
 pub(crate) trait SourceChunkBuilder {
+    fn create_builders(column_descs: &[SourceColumnDesc]) -> Result<Vec<ArrayBuilderImpl>> {
+        column_descs
+            .iter()
+            .map(|k| k.data_type.create_array_builder(DEFAULT_CHUNK_BUFFER_SIZE))
+            .collect::<Result<Vec<ArrayBuilderImpl>>>()
+    }
+
+    fn finish_builders(builders: Vec<ArrayBuilderImpl>) -> Result<Vec<Column>> {
+        builders
+            .into_iter()
+            .map(|builder| builder.finish().map(|arr| Column::new(Arc::new(arr))))
+            .collect::<Result<Vec<Column>>>()
+    }
+
     fn build_columns(
         column_descs: &[SourceColumnDesc],
         rows: &[Vec<Datum>],
     ) -> Result<Vec<Column>> {
-        let mut builders = column_descs
-            .iter()
-            .map(|k| k.data_type.create_array_builder(DEFAULT_CHUNK_BUFFER_SIZE))
-            .collect::<Result<Vec<ArrayBuilderImpl>>>()?;
+        let mut builders = Self::create_builders(column_descs)?;
 
         for row in rows {
             row.iter()
@@ -39,10 +50,7 @@ pub(crate) trait SourceChunkBuilder {
                 .try_for_each(|(datum, builder)| builder.append_datum(datum))?
         }
 
-        builders
-            .into_iter()
-            .map(|builder| builder.finish().map(|arr| Column::new(Arc::new(arr))))
-            .collect::<Result<Vec<Column>>>()
+        Self::finish_builders(builders)
     }
 
     fn build_datachunk(column_desc: &[SourceColumnDesc], rows: &[Vec<Datum>]) -> Result<DataChunk> {
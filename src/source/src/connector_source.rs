@@ -23,7 +23,7 @@ use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::ColumnId;
 use risingwave_common::error::{internal_error, Result, RwError, ToRwResult};
 use risingwave_connector::{
-    Column, ConnectorProperties, ConnectorState, SourceMessage, SplitReaderImpl,
+    Column, ConnectorProperties, ConnectorState, SourceMessage, SplitImpl, SplitReaderImpl,
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::{mpsc, oneshot};
@@ -147,32 +147,21 @@ impl StreamSourceReader for ConnectorSourceReader {
             Either::Right(e) => return Err(e),
         };
 
-        let mut events = Vec::with_capacity(batch.len());
-        let mut split_offset_mapping: HashMap<String, String> = HashMap::new();
-
-        for msg in batch {
-            if let Some(content) = msg.payload {
-                *split_offset_mapping
-                    .entry(msg.split_id.clone())
-                    .or_insert_with(|| "".to_string()) = msg.offset.to_string();
-                events.push(self.parser.parse(content.as_ref(), &self.columns)?);
-            }
+        if self.parser.may_block() {
+            // Run the parser on the blocking threads, so that the runtime is not stalled, which
+            // works with both the multi-threaded and the current-thread runtimes.
+            let parser = self.parser.clone();
+            let columns = self.columns.clone();
+            tokio::task::spawn_blocking(move || Self::parse_batch(&parser, &columns, batch))
+                .await
+                .map_err(|e| internal_error(e.to_string()))?
+        } else {
+            Self::parse_batch(&self.parser, &self.columns, batch)
         }
-        let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
-        let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
+    }
 
-        for event in events {
-            rows.extend(event.rows);
-            ops.extend(event.ops);
-        }
-        Ok(StreamChunkWithState {
-            chunk: StreamChunk::new(
-                ops,
-                Self::build_columns(&self.columns, rows.as_ref())?,
-                None,
-            ),
-            split_offset_mapping: Some(split_offset_mapping),
-        })
+    async fn add_splits(&mut self, splits: Vec<SplitImpl>) -> Result<()> {
+        self.add_split(Some(splits)).await
     }
 }
 
@@ -187,6 +176,29 @@ impl Drop for ConnectorSourceReader {
 }
 
 impl ConnectorSourceReader {
+    fn parse_batch(
+        parser: &SourceParserImpl,
+        columns: &[SourceColumnDesc],
+        batch: Vec<SourceMessage>,
+    ) -> Result<StreamChunkWithState> {
+        let mut builders = Self::create_builders(columns)?;
+        let mut ops = Vec::with_capacity(batch.len());
+        let mut split_offset_mapping: HashMap<String, String> = HashMap::new();
+
+        for msg in batch {
+            if let Some(content) = msg.payload {
+                *split_offset_mapping
+                    .entry(msg.split_id.clone())
+                    .or_insert_with(|| "".to_string()) = msg.offset.to_string();
+                ops.extend(parser.parse_into_builders(content.as_ref(), columns, &mut builders)?);
+            }
+        }
+        Ok(StreamChunkWithState {
+            chunk: StreamChunk::new(ops, Self::finish_builders(builders)?, None),
+            split_offset_mapping: Some(split_offset_mapping),
+        })
+    }
+
     pub async fn add_split(&mut self, split: ConnectorState) -> Result<()> {
         if let Some(append_splits) = split {
             for split in append_splits {
@@ -38,7 +38,8 @@ use madsim::collections::HashMap;
 pub use manager::*;
 pub use parser::*;
 use risingwave_common::array::StreamChunk;
-use risingwave_common::error::Result;
+use risingwave_common::error::{internal_error, Result};
+use risingwave_connector::SplitImpl;
 pub use table_v2::*;
 
 use crate::connector_source::{ConnectorSource, ConnectorSourceReader};
@@ -62,6 +63,7 @@ pub enum SourceFormat {
     Protobuf,
     DebeziumJson,
     Avro,
+    Csv,
 }
 
 #[derive(Debug, EnumAsInner)]
@@ -84,6 +86,13 @@ impl StreamSourceReader for SourceStreamReaderImpl {
             SourceStreamReaderImpl::Connector(c) => c.next().await,
         }
     }
+
+    async fn add_splits(&mut self, splits: Vec<SplitImpl>) -> Result<()> {
+        match self {
+            SourceStreamReaderImpl::TableV2(t) => t.add_splits(splits).await,
+            SourceStreamReaderImpl::Connector(c) => c.add_splits(splits).await,
+        }
+    }
 }
 
 /// [`StreamChunkWithState`] returns stream chunk together with offset for each split. In the
@@ -100,4 +109,9 @@ pub trait StreamSourceReader: Send + Sync + 'static {
     /// `next` always returns a StreamChunk. If the queue is empty, it will
     /// block until new data coming
     async fn next(&mut self) -> Result<StreamChunkWithState>;
+
+    /// Starts reading the newly assigned `splits` besides the current ones.
+    async fn add_splits(&mut self, _splits: Vec<SplitImpl>) -> Result<()> {
+        Err(internal_error("this source does not support adding splits"))
+    }
 }
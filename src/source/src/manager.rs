@@ -113,6 +113,7 @@ impl SourceManager for MemSourceManager {
             RowFormatType::Protobuf => SourceFormat::Protobuf,
             RowFormatType::DebeziumJson => SourceFormat::DebeziumJson,
             RowFormatType::Avro => SourceFormat::Avro,
+            RowFormatType::Csv => SourceFormat::Csv,
         };
 
         if format == SourceFormat::Protobuf && info.row_schema_location.is_empty() {
@@ -273,6 +274,7 @@ mod tests {
             .map(|c| ColumnCatalog {
                 column_desc: Some(c.to_owned()),
                 is_hidden: false,
+                ..Default::default()
             })
             .collect();
         let info = StreamSourceInfo {
@@ -282,6 +284,7 @@ mod tests {
             row_id_index: 0,
             pk_column_ids: vec![0],
             columns,
+            append_only: true,
         };
         let source_id = TableId::default();
 
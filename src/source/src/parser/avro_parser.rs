@@ -16,11 +16,14 @@ use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::future::Future;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
 
 use apache_avro::types::Value;
-use apache_avro::{Reader, Schema};
+use apache_avro::{from_avro_datum, Reader, Schema};
 use chrono::{Datelike, NaiveDate};
 use num_traits::FromPrimitive;
+use parking_lot::RwLock;
 use risingwave_common::array::Op;
 use risingwave_common::error::ErrorCode::{InternalError, InvalidConfigValue, ProtocolError};
 use risingwave_common::error::{ErrorCode, Result, RwError};
@@ -28,19 +31,80 @@ use risingwave_common::types::{
     DataType, Datum, Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, ScalarImpl,
 };
 use risingwave_connector::aws_utils::{default_conn_config, s3_client, AwsConfigV2};
+use risingwave_connector::schema_registry::{extract_schema_id, SchemaRegistryClient};
 use url::Url;
 
 use crate::{Event, SourceColumnDesc, SourceParser};
 
 const AVRO_SCHEMA_LOCATION_S3_REGION: &str = "region";
+/// The subject of the reader schema in the schema registry, which defaults to `<topic>-value`.
+const AVRO_SCHEMA_REGISTRY_SUBJECT: &str = "avro.schema.registry.subject";
+const KAFKA_TOPIC_KEYS: [&str; 2] = ["kafka.topic", "topic"];
 
 pub fn unix_epoch_days() -> i32 {
     NaiveDate::from_ymd(1970, 1, 1).num_days_from_ce()
 }
 
+/// Parser of Avro records. With a schema file as the location, the payloads are Avro object
+/// container files. With a Confluent Schema Registry as the location, each payload is a single
+/// datum in the Confluent wire format, which is decoded with the writer schema it refers to and
+/// then resolved against the latest schema of the subject at creation.
 #[derive(Debug)]
 pub struct AvroParser {
     schema: Schema,
+    schema_resolver: Option<ConfluentSchemaResolver>,
+}
+
+/// Fetches the writer schemas of the records in the Confluent wire format from a schema registry,
+/// and caches them by schema id.
+#[derive(Debug)]
+struct ConfluentSchemaResolver {
+    client: SchemaRegistryClient,
+    writer_schemas: RwLock<HashMap<i32, Arc<Schema>>>,
+}
+
+impl ConfluentSchemaResolver {
+    fn new(client: SchemaRegistryClient) -> Self {
+        Self {
+            client,
+            writer_schemas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn insert(&self, schema_id: i32, schema: Schema) -> Arc<Schema> {
+        let schema = Arc::new(schema);
+        self.writer_schemas
+            .write()
+            .insert(schema_id, schema.clone());
+        schema
+    }
+
+    /// Get the writer schema of the given id. A schema missing in the cache is fetched from the
+    /// registry synchronously, so it must be called in `spawn_blocking`. It only happens once per
+    /// schema id.
+    fn get_by_id(&self, schema_id: i32) -> Result<Arc<Schema>> {
+        if let Some(schema) = self.writer_schemas.read().get(&schema_id) {
+            return Ok(schema.clone());
+        }
+        let fetched = tokio::runtime::Handle::current()
+            .block_on(self.client.get_schema_by_id(schema_id))
+            .map_err(|e| {
+                RwError::from(InternalError(format!(
+                    "failed to fetch avro schema {}: {}",
+                    schema_id, e
+                )))
+            })?;
+        Ok(self.insert(schema_id, parse_avro_schema(&fetched.schema)?))
+    }
+}
+
+fn parse_avro_schema(content: &str) -> Result<Schema> {
+    Schema::parse_str(content).map_err(|e| {
+        RwError::from(InternalError(format!(
+            "Avro schema parse error {}",
+            anyhow::Error::from(e)
+        )))
+    })
 }
 
 impl AvroParser {
@@ -49,6 +113,9 @@ impl AvroParser {
             .map_err(|e| InternalError(format!("failed to parse url ({}): {}", schema_location, e)))
             .unwrap();
         let url_schema = url.scheme();
+        if url_schema == "http" || url_schema == "https" {
+            return Self::new_with_schema_registry(schema_location, &props).await;
+        }
         let schema_path = url.path();
         let arvo_schema =
             match url_schema {
@@ -72,11 +139,104 @@ impl AvroParser {
                 )))),
             };
         if let Ok(schema) = arvo_schema {
-            Ok(Self { schema })
+            Ok(Self {
+                schema,
+                schema_resolver: None,
+            })
         } else {
             Err(arvo_schema.err().unwrap())
         }
     }
+
+    async fn new_with_schema_registry(
+        registry_url: &str,
+        props: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let subject = match props.get(AVRO_SCHEMA_REGISTRY_SUBJECT) {
+            Some(subject) => subject.clone(),
+            None => {
+                let topic = KAFKA_TOPIC_KEYS
+                    .iter()
+                    .find_map(|key| props.get(*key))
+                    .ok_or_else(|| {
+                        RwError::from(InvalidConfigValue {
+                            config_entry: AVRO_SCHEMA_REGISTRY_SUBJECT.to_string(),
+                            config_value: "NONE".to_string(),
+                        })
+                    })?;
+                format!("{}-value", topic)
+            }
+        };
+        let client = SchemaRegistryClient::new(registry_url);
+        let latest = client.get_latest_schema(&subject).await.map_err(|e| {
+            RwError::from(InternalError(format!(
+                "failed to fetch avro schema of subject {}: {}",
+                subject, e
+            )))
+        })?;
+        let schema = parse_avro_schema(&latest.schema)?;
+        let schema_resolver = ConfluentSchemaResolver::new(client);
+        schema_resolver.insert(latest.id, schema.clone());
+        Ok(Self {
+            schema,
+            schema_resolver: Some(schema_resolver),
+        })
+    }
+
+    /// Whether the writer schemas are fetched from a schema registry on parsing.
+    pub fn uses_schema_registry(&self) -> bool {
+        self.schema_resolver.is_some()
+    }
+
+    /// Parse a single datum in the Confluent wire format.
+    fn parse_confluent_datum(
+        &self,
+        schema_resolver: &ConfluentSchemaResolver,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+    ) -> Result<Event> {
+        let (schema_id, mut datum) =
+            extract_schema_id(payload).map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+        let writer_schema = schema_resolver.get_by_id(schema_id)?;
+        let value = from_avro_datum(&writer_schema, &mut datum, Some(&self.schema))
+            .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
+        if let Value::Record(fields) = value {
+            Ok(Event {
+                ops: vec![Op::Insert],
+                rows: vec![self.record_to_row(fields, columns)],
+            })
+        } else {
+            Err(RwError::from(ProtocolError(
+                "avro parse error. the datum is not a record".to_string(),
+            )))
+        }
+    }
+
+    fn record_to_row(
+        &self,
+        fields: Vec<(String, Value)>,
+        columns: &[SourceColumnDesc],
+    ) -> Vec<Datum> {
+        columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    return None;
+                }
+                let (_, value) = fields.iter().find(|(name, _)| column.name.eq(name))?;
+                let field_schema = self.field_schema(&column.name)?;
+                from_avro_value(column, value.clone(), field_schema).ok()?
+            })
+            .collect::<Vec<Datum>>()
+    }
+
+    fn field_schema(&self, name: &str) -> Option<&Schema> {
+        if let Schema::Record { fields, lookup, .. } = &self.schema {
+            lookup.get(name).map(|index| &fields[*index].schema)
+        } else {
+            None
+        }
+    }
 }
 
 macro_rules! from_avro_datetime {
@@ -117,10 +277,29 @@ macro_rules! from_avro_primitive {
 ///  - float: f32
 ///  - double: f64
 ///  - string: String
+///  - decimal: the unscaled bytes with the scale of `field_schema`, or a double
 ///  - Date (the number of days from the unix epoch, 1970-1-1 UTC)
-///  - Timestamp (the number of milliseconds from the unix epoch,  1970-1-1 00:00:00.000 UTC)
-pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) -> Result<ScalarImpl> {
-    match column.data_type {
+///  - Timestamp (the number of milliseconds or microseconds from the unix epoch,  1970-1-1
+///    00:00:00.000 UTC)
+///
+/// Null and the unions with null are also accepted.
+pub(crate) fn from_avro_value(
+    column: &SourceColumnDesc,
+    field_value: Value,
+    field_schema: &Schema,
+) -> Result<Datum> {
+    match field_value {
+        Value::Null => return Ok(None),
+        Value::Union(index, inner) => {
+            let variant_schema = match field_schema {
+                Schema::Union(union_schema) => union_schema.variants().get(index as usize),
+                _ => None,
+            };
+            return from_avro_value(column, *inner, variant_schema.unwrap_or(field_schema));
+        }
+        _ => {}
+    }
+    let scalar = match column.data_type {
         DataType::Boolean => {
             from_avro_primitive!(field_value, Boolean, |b: bool| Ok(ScalarImpl::Bool(b)))
         }
@@ -140,8 +319,17 @@ pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) ->
                 d.into()
             )))
         }
-        DataType::Decimal => {
-            from_avro_primitive!(field_value, Double, |d: f64| {
+        DataType::Decimal => match field_value {
+            Value::Decimal(decimal) => {
+                let scale = match field_schema {
+                    Schema::Decimal { scale, .. } => *scale,
+                    _ => 0,
+                };
+                let unscaled = Vec::<u8>::try_from(&decimal)
+                    .map_err(|e| RwError::from(InternalError(e.to_string())))?;
+                avro_decimal_to_rw(&unscaled, scale).map(ScalarImpl::Decimal)
+            }
+            _ => from_avro_primitive!(field_value, Double, |d: f64| {
                 let decimal = Decimal::from_f64(d);
                 match decimal {
                     Some(v) => Ok(ScalarImpl::Decimal(v)),
@@ -149,8 +337,8 @@ pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) ->
                         "decimal parse error".to_string(),
                     ))),
                 }
-            })
-        }
+            }),
+        },
         DataType::Varchar => {
             from_avro_primitive!(field_value, String, |s: String| Ok(ScalarImpl::Utf8(s)))
         }
@@ -162,42 +350,76 @@ pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) ->
                 ScalarImpl::NaiveDate
             )
         }
-        DataType::Timestamp => {
-            from_avro_datetime!(
+        DataType::Timestamp => match field_value {
+            Value::TimestampMicros(_) => from_avro_datetime!(
+                field_value,
+                TimestampMicros,
+                |micros: i64| NaiveDateTimeWrapper::with_secs_nsecs(
+                    micros.div_euclid(1_000_000),
+                    (micros.rem_euclid(1_000_000) * 1_000) as u32
+                ),
+                ScalarImpl::NaiveDateTime
+            ),
+            _ => from_avro_datetime!(
                 field_value,
                 TimestampMillis,
-                |millis| NaiveDateTimeWrapper::with_secs_nsecs(millis, 0),
+                |millis: i64| NaiveDateTimeWrapper::with_secs_nsecs(
+                    millis.div_euclid(1_000),
+                    (millis.rem_euclid(1_000) * 1_000_000) as u32
+                ),
                 ScalarImpl::NaiveDateTime
-            )
-        }
+            ),
+        },
         _ => Err(ErrorCode::NotImplemented(
             "unsupported type for avro parser".to_string(),
             None.into(),
         )
         .into()),
+    }?;
+    Ok(Some(scalar))
+}
+
+/// Convert the big-endian two's-complement unscaled value of an Avro decimal to a decimal.
+fn avro_decimal_to_rw(unscaled: &[u8], scale: usize) -> Result<Decimal> {
+    if unscaled.len() > 16 {
+        return Err(RwError::from(InternalError(format!(
+            "avro decimal of {} bytes is out of range",
+            unscaled.len()
+        ))));
     }
+    let negative = unscaled.first().map_or(false, |b| b & 0x80 != 0);
+    let value = unscaled
+        .iter()
+        .fold(if negative { -1_i128 } else { 0 }, |acc, b| {
+            (acc << 8) | *b as i128
+        });
+    let digits = format!("{:0>width$}", value.unsigned_abs(), width = scale + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+    let sign = if negative { "-" } else { "" };
+    let text = if scale == 0 {
+        format!("{}{}", sign, int_part)
+    } else {
+        format!("{}{}.{}", sign, int_part, frac_part)
+    };
+    Decimal::from_str(&text).map_err(|e| {
+        RwError::from(InternalError(format!(
+            "avro decimal {} is out of range: {}",
+            text, e
+        )))
+    })
 }
 
 impl SourceParser for AvroParser {
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        if let Some(schema_resolver) = &self.schema_resolver {
+            return self.parse_confluent_datum(schema_resolver, payload, columns);
+        }
         let reader_rs = Reader::with_schema(&self.schema, payload);
         if let Ok(reader) = reader_rs {
             let mut rows = Vec::new();
             for record in reader {
                 if let Ok(Value::Record(fields)) = record {
-                    let vals = columns
-                        .iter()
-                        .map(|column| {
-                            if column.skip_parse {
-                                None
-                            } else {
-                                let tuple =
-                                    fields.iter().find(|val| column.name.eq(&val.0)).unwrap();
-                                from_avro_value(column, tuple.clone().1).ok()
-                            }
-                        })
-                        .collect::<Vec<Datum>>();
-                    rows.push(vals);
+                    rows.push(self.record_to_row(fields, columns));
                 } else {
                     return Err(RwError::from(ProtocolError(
                         record.err().unwrap().to_string(),
@@ -343,18 +565,24 @@ mod test {
     use std::collections::HashMap;
     use std::env;
     use std::ops::Sub;
+    use std::str::FromStr;
 
     use apache_avro::types::{Record, Value};
-    use apache_avro::{Codec, Schema, Writer};
+    use apache_avro::{to_avro_datum, Codec, Schema, Writer};
     use chrono::NaiveDate;
+    use itertools::Itertools;
     use risingwave_common::catalog::ColumnId;
     use risingwave_common::error;
     use risingwave_common::error::ErrorCode::InternalError;
     use risingwave_common::error::RwError;
-    use risingwave_common::types::{DataType, NaiveDateTimeWrapper, NaiveDateWrapper, ScalarImpl};
+    use risingwave_common::types::{
+        DataType, Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, ScalarImpl,
+    };
+    use risingwave_connector::schema_registry::SchemaRegistryClient;
 
     use crate::parser::avro_parser::{
-        load_schema_async, read_schema_from_local, read_schema_from_s3, unix_epoch_days, AvroParser,
+        avro_decimal_to_rw, load_schema_async, read_schema_from_local, read_schema_from_s3,
+        unix_epoch_days, AvroParser, ConfluentSchemaResolver,
     };
     use crate::{SourceColumnDesc, SourceParser};
 
@@ -459,7 +687,10 @@ mod test {
                     let datetime = from_avro_datetime!(
                         value,
                         TimestampMillis,
-                        |millis| NaiveDateTimeWrapper::with_secs_nsecs(millis, 0),
+                        |millis: i64| NaiveDateTimeWrapper::with_secs_nsecs(
+                            millis.div_euclid(1_000),
+                            (millis.rem_euclid(1_000) * 1_000_000) as u32
+                        ),
                         ScalarImpl::NaiveDateTime
                     )
                     .ok();
@@ -571,6 +802,118 @@ mod test {
         record
     }
 
+    #[test]
+    fn test_avro_decimal_to_rw() {
+        assert_eq!(
+            avro_decimal_to_rw(&[0x30, 0x39], 2).unwrap(),
+            Decimal::from_str("123.45").unwrap()
+        );
+        assert_eq!(
+            avro_decimal_to_rw(&[0xfb], 3).unwrap(),
+            Decimal::from_str("-0.005").unwrap()
+        );
+        assert_eq!(
+            avro_decimal_to_rw(&[0xff, 0x00], 0).unwrap(),
+            Decimal::from_str("-256").unwrap()
+        );
+        assert!(avro_decimal_to_rw(&[0x7f; 17], 0).is_err());
+    }
+
+    #[test]
+    fn test_avro_parser_confluent_wire_format() {
+        let writer_schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "order",
+                "fields": [
+                    {"name": "id", "type": "int"},
+                    {"name": "price", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+                    {"name": "created_at", "type": {"type": "long", "logicalType": "timestamp-micros"}},
+                    {"name": "note", "type": ["null", "string"]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        // The reader schema has evolved with a new field.
+        let reader_schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "order",
+                "fields": [
+                    {"name": "id", "type": "int"},
+                    {"name": "price", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+                    {"name": "created_at", "type": {"type": "long", "logicalType": "timestamp-micros"}},
+                    {"name": "note", "type": ["null", "string"]},
+                    {"name": "country", "type": "string", "default": "unknown"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let schema_resolver =
+            ConfluentSchemaResolver::new(SchemaRegistryClient::new("http://localhost:8081"));
+        schema_resolver.insert(3, writer_schema.clone());
+        let parser = AvroParser {
+            schema: reader_schema,
+            schema_resolver: Some(schema_resolver),
+        };
+
+        let columns = ["id", "price", "created_at", "note", "country"]
+            .into_iter()
+            .zip_eq([
+                DataType::Int32,
+                DataType::Decimal,
+                DataType::Timestamp,
+                DataType::Varchar,
+                DataType::Varchar,
+            ])
+            .enumerate()
+            .map(|(i, (name, data_type))| SourceColumnDesc {
+                name: name.to_string(),
+                data_type,
+                column_id: ColumnId::from(i as i32),
+                skip_parse: false,
+            })
+            .collect_vec();
+
+        let encode = |note: Value| {
+            let mut record = Record::new(&writer_schema).unwrap();
+            record.put("id", 1_i32);
+            record.put("price", Value::Decimal(vec![0x30, 0x39].into()));
+            record.put("created_at", Value::TimestampMicros(1_500_000));
+            record.put("note", note);
+            let mut payload = vec![0, 0, 0, 0, 3];
+            payload.extend(to_avro_datum(&writer_schema, record).unwrap());
+            payload
+        };
+
+        let event = parser
+            .parse(
+                &encode(Value::Union(1, Box::new(Value::String("fragile".into())))),
+                &columns,
+            )
+            .unwrap();
+        assert_eq!(
+            event.rows,
+            vec![vec![
+                Some(ScalarImpl::Int32(1)),
+                Some(ScalarImpl::Decimal(Decimal::from_str("123.45").unwrap())),
+                Some(ScalarImpl::NaiveDateTime(
+                    NaiveDateTimeWrapper::with_secs_nsecs(1, 500_000_000).unwrap()
+                )),
+                Some(ScalarImpl::Utf8("fragile".to_string())),
+                Some(ScalarImpl::Utf8("unknown".to_string())),
+            ]]
+        );
+
+        let event = parser
+            .parse(&encode(Value::Union(0, Box::new(Value::Null))), &columns)
+            .unwrap();
+        assert_eq!(event.rows[0][3], None);
+
+        // Not in the wire format.
+        assert!(parser.parse(&[1, 0, 0, 0, 3, 2], &columns).is_err());
+    }
+
     #[tokio::test]
     async fn test_new_avro_parser() {
         let avro_parser_rs = new_avro_parser_from_local("simple-schema.avsc").await;
@@ -12,92 +12,88 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use num_traits::FromPrimitive;
+use std::str::FromStr;
+
 use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, RwError};
-use risingwave_common::types::{DataType, Decimal, ScalarImpl, ScalarRef};
+use risingwave_common::types::{DataType, Datum, Decimal, ScalarImpl, ScalarRef};
 use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp};
 use serde_json::Value;
 
 use crate::SourceColumnDesc;
 
-macro_rules! make_ScalarImpl {
-    ($x:expr, $y:expr) => {
-        match $x {
-            Some(v) => return Ok($y(v)),
-            None => return Err(RwError::from(InternalError("json parse error".to_string()))),
-        }
+fn json_type_error(column: &SourceColumnDesc, value: &Value) -> RwError {
+    RwError::from(InternalError(format!(
+        "json parse error: cannot parse {} as {:?} for column {}",
+        value, column.data_type, column.name
+    )))
+}
+
+/// Parses an integer from either a JSON number or a string, failing if it's out of the range of
+/// `T`.
+fn json_parse_int<T: TryFrom<i64>>(column: &SourceColumnDesc, value: &Value) -> Result<T> {
+    let v = match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        _ => None,
     };
+    v.and_then(|v| T::try_from(v).ok())
+        .ok_or_else(|| json_type_error(column, value))
 }
 
-pub(crate) fn json_parse_value(
-    column: &SourceColumnDesc,
-    value: Option<&Value>,
-) -> Result<ScalarImpl> {
-    match column.data_type {
-        DataType::Boolean => {
-            make_ScalarImpl!(value.and_then(|v| v.as_bool()), |x| ScalarImpl::Bool(
-                x as bool
-            ))
-        }
-        DataType::Int16 => {
-            make_ScalarImpl!(value.and_then(|v| v.as_i64()), |x| ScalarImpl::Int16(
-                x as i16
-            ))
-        }
-        DataType::Int32 => {
-            make_ScalarImpl!(value.and_then(|v| v.as_i64()), |x| ScalarImpl::Int32(
-                x as i32
-            ))
-        }
-        DataType::Int64 => {
-            make_ScalarImpl!(value.and_then(|v| v.as_i64()), |x| ScalarImpl::Int64(
-                x as i64
-            ))
-        }
-        DataType::Float32 => {
-            make_ScalarImpl!(value.and_then(|v| v.as_f64()), |v| ScalarImpl::Float32(
-                (v as f32).into()
-            ))
-        }
-        DataType::Float64 => {
-            make_ScalarImpl!(
-                value.and_then(|v| v.as_f64()),
-                |v: f64| ScalarImpl::Float64(v.into())
-            )
-        }
-        DataType::Decimal => match value.and_then(|v| v.as_f64()) {
-            Some(v) => match Decimal::from_f64(v) {
-                Some(v) => Ok(ScalarImpl::Decimal(v)),
-                None => Err(RwError::from(InternalError(
-                    "decimal parse error".to_string(),
-                ))),
-            },
-            None => Err(RwError::from(InternalError("json parse error".to_string()))),
-        },
-        DataType::Varchar => {
-            make_ScalarImpl!(value.and_then(|v| v.as_str()), |v: &str| ScalarImpl::Utf8(
-                v.to_owned_scalar()
-            ))
+/// Parses the JSON `value` of the column. A missing field or a JSON `null` is parsed as NULL.
+/// Strings are accepted for numbers, decimals and timestamps as well.
+pub(crate) fn json_parse_value(column: &SourceColumnDesc, value: Option<&Value>) -> Result<Datum> {
+    let value = match value {
+        None | Some(Value::Null) => return Ok(None),
+        Some(value) => value,
+    };
+    let scalar = match column.data_type {
+        DataType::Boolean => value
+            .as_bool()
+            .map(ScalarImpl::Bool)
+            .ok_or_else(|| json_type_error(column, value))?,
+        DataType::Int16 => ScalarImpl::Int16(json_parse_int(column, value)?),
+        DataType::Int32 => ScalarImpl::Int32(json_parse_int(column, value)?),
+        DataType::Int64 => ScalarImpl::Int64(json_parse_int(column, value)?),
+        DataType::Float32 => value
+            .as_f64()
+            .map(|v| ScalarImpl::Float32((v as f32).into()))
+            .ok_or_else(|| json_type_error(column, value))?,
+        DataType::Float64 => value
+            .as_f64()
+            .map(|v| ScalarImpl::Float64(v.into()))
+            .ok_or_else(|| json_type_error(column, value))?,
+        // Parse the decimal from the text of the number, so that no precision is lost by a float.
+        DataType::Decimal => {
+            let text = match value {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.trim().to_string(),
+                _ => return Err(json_type_error(column, value)),
+            };
+            Decimal::from_str(&text)
+                .map(ScalarImpl::Decimal)
+                .map_err(|_| json_type_error(column, value))?
         }
-        DataType::Date => match value.and_then(|v| v.as_str()) {
-            None => Err(RwError::from(InternalError("parse error".to_string()))),
-            Some(date_str) => match str_to_date(date_str) {
-                Ok(date) => Ok(ScalarImpl::NaiveDate(date)),
-                Err(e) => Err(e),
-            },
+        DataType::Varchar => value
+            .as_str()
+            .map(|v| ScalarImpl::Utf8(v.to_owned_scalar()))
+            .ok_or_else(|| json_type_error(column, value))?,
+        DataType::Date => match value.as_str() {
+            None => return Err(json_type_error(column, value)),
+            Some(date_str) => ScalarImpl::NaiveDate(str_to_date(date_str)?),
         },
-        DataType::Timestamp => match value.and_then(|v| v.as_str()) {
-            None => Err(RwError::from(InternalError("parse error".to_string()))),
-            Some(date_str) => match str_to_timestamp(date_str) {
-                Ok(timestamp) => Ok(ScalarImpl::NaiveDateTime(timestamp)),
-                Err(e) => Err(e),
-            },
+        DataType::Timestamp => match value.as_str() {
+            None => return Err(json_type_error(column, value)),
+            Some(timestamp_str) => ScalarImpl::NaiveDateTime(str_to_timestamp(timestamp_str)?),
         },
-        _ => Err(ErrorCode::NotImplemented(
-            "unsupported type for json_parse_value".to_string(),
-            None.into(),
-        )
-        .into()),
-    }
+        _ => {
+            return Err(ErrorCode::NotImplemented(
+                "unsupported type for json_parse_value".to_string(),
+                None.into(),
+            )
+            .into())
+        }
+    };
+    Ok(Some(scalar))
 }
@@ -0,0 +1,541 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayBuilderImpl, DataChunk, Op};
+use risingwave_common::error::ErrorCode::{self, ProtocolError};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, Decimal, ScalarImpl};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_expr::vector_op::cast::{
+    str_parse, str_to_bool, str_to_date, str_to_time, str_to_timestamp, str_to_timestampz,
+};
+
+use crate::{Event, SourceColumnDesc, SourceParser};
+
+const CSV_DELIMITER_KEY: &str = "csv.delimiter";
+const CSV_QUOTE_KEY: &str = "csv.quote";
+/// The character escaping a quote inside a quoted field. Defaults to the quote itself, i.e., a
+/// doubled quote.
+const CSV_ESCAPE_KEY: &str = "csv.escape";
+/// Whether the first record of a payload is the header, either `true` or `false`.
+const CSV_HEADER_KEY: &str = "csv.header";
+/// What to do with a field that can't be converted to the type of its column, either `strict` or
+/// `lenient`.
+const CSV_CONVERSION_MODE_KEY: &str = "csv.conversion.mode";
+
+/// The options of [`CsvParser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvParserOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    /// `None` if a quote is escaped by doubling it.
+    pub escape: Option<u8>,
+    /// Skip the first record of each payload.
+    pub has_header: bool,
+    /// Fail on a field that can't be converted, or a record with extra fields. Otherwise such a
+    /// field is NULL and extra fields are ignored. Missing fields are always NULL, so that the
+    /// records written before a column is added are still accepted.
+    pub strict: bool,
+}
+
+impl Default for CsvParserOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            has_header: false,
+            strict: true,
+        }
+    }
+}
+
+impl CsvParserOptions {
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        let mut options = Self::default();
+        if let Some(delimiter) = properties.get(CSV_DELIMITER_KEY) {
+            options.delimiter = parse_single_byte(CSV_DELIMITER_KEY, delimiter)?;
+        }
+        if let Some(quote) = properties.get(CSV_QUOTE_KEY) {
+            options.quote = parse_single_byte(CSV_QUOTE_KEY, quote)?;
+        }
+        if let Some(escape) = properties.get(CSV_ESCAPE_KEY) {
+            options.escape = Some(parse_single_byte(CSV_ESCAPE_KEY, escape)?)
+                .filter(|escape| *escape != options.quote);
+        }
+        options.has_header = match properties
+            .get(CSV_HEADER_KEY)
+            .map(|s| s.to_lowercase())
+            .as_deref()
+        {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(other) => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "'{}' must be either 'true' or 'false', got '{}'",
+                    CSV_HEADER_KEY, other
+                ))))
+            }
+        };
+        options.strict = match properties
+            .get(CSV_CONVERSION_MODE_KEY)
+            .map(|s| s.to_lowercase())
+            .as_deref()
+        {
+            None | Some("strict") => true,
+            Some("lenient") => false,
+            Some(other) => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "'{}' must be either 'strict' or 'lenient', got '{}'",
+                    CSV_CONVERSION_MODE_KEY, other
+                ))))
+            }
+        };
+        if options.delimiter == options.quote || Some(options.delimiter) == options.escape {
+            return Err(RwError::from(ProtocolError(format!(
+                "'{}' must differ from the quote and the escape",
+                CSV_DELIMITER_KEY
+            ))));
+        }
+        Ok(options)
+    }
+}
+
+fn parse_single_byte(key: &str, value: &str) -> Result<u8> {
+    match value.as_bytes() {
+        [b] if b.is_ascii() && *b != b'\n' && *b != b'\r' => Ok(*b),
+        _ => Err(RwError::from(ProtocolError(format!(
+            "'{}' must be a single ASCII character, got '{}'",
+            key, value
+        )))),
+    }
+}
+
+/// Tokenizes the records of CSV. Quoted fields may contain delimiters, quotes and line breaks.
+/// Records are separated by LF or CRLF.
+struct CsvRecords<'a> {
+    data: &'a [u8],
+    pos: usize,
+    options: &'a CsvParserOptions,
+    field: Vec<u8>,
+}
+
+impl<'a> CsvRecords<'a> {
+    fn new(data: &'a [u8], options: &'a CsvParserOptions) -> Self {
+        Self {
+            data,
+            pos: 0,
+            options,
+            field: vec![],
+        }
+    }
+
+    /// Read the next record into `fields`, where an unquoted empty field is `None`, so an empty
+    /// line is a record of a single `None`. Returns `false` at the end of the data.
+    fn next_record(&mut self, fields: &mut Vec<Option<String>>) -> Result<bool> {
+        if self.pos >= self.data.len() {
+            return Ok(false);
+        }
+        self.read_record(fields)?;
+        Ok(true)
+    }
+
+    fn read_record(&mut self, fields: &mut Vec<Option<String>>) -> Result<()> {
+        let CsvParserOptions {
+            delimiter,
+            quote,
+            escape,
+            ..
+        } = *self.options;
+        fields.clear();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        loop {
+            let byte = match self.data.get(self.pos) {
+                Some(byte) => *byte,
+                None if in_quotes => {
+                    return Err(RwError::from(ProtocolError(
+                        "unterminated quoted field in csv".to_string(),
+                    )))
+                }
+                None => return self.finish_field(quoted, fields),
+            };
+            self.pos += 1;
+            if in_quotes {
+                if Some(byte) == escape && self.pos < self.data.len() {
+                    self.field.push(self.data[self.pos]);
+                    self.pos += 1;
+                } else if byte == quote {
+                    if self.data.get(self.pos) == Some(&quote) {
+                        self.field.push(quote);
+                        self.pos += 1;
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    self.field.push(byte);
+                }
+            } else if byte == quote && !quoted && self.field.is_empty() {
+                quoted = true;
+                in_quotes = true;
+            } else if byte == delimiter {
+                self.finish_field(quoted, fields)?;
+                quoted = false;
+            } else if byte == b'\n' {
+                return self.finish_field(quoted, fields);
+            } else if byte == b'\r' {
+                if self.data.get(self.pos) == Some(&b'\n') {
+                    self.pos += 1;
+                }
+                return self.finish_field(quoted, fields);
+            } else {
+                self.field.push(byte);
+            }
+        }
+    }
+
+    fn finish_field(&mut self, quoted: bool, fields: &mut Vec<Option<String>>) -> Result<()> {
+        let field = std::mem::take(&mut self.field);
+        if field.is_empty() && !quoted {
+            fields.push(None);
+        } else {
+            let field = String::from_utf8(field).map_err(|e| {
+                RwError::from(ProtocolError(format!("invalid utf-8 in csv: {}", e)))
+            })?;
+            fields.push(Some(field));
+        }
+        Ok(())
+    }
+}
+
+/// Convert the text of a CSV field to a scalar of `data_type`.
+fn csv_parse_value(data_type: &DataType, text: &str) -> Result<ScalarImpl> {
+    if let DataType::Varchar = data_type {
+        return Ok(ScalarImpl::Utf8(text.to_string()));
+    }
+    let text = text.trim();
+    let scalar = match data_type {
+        DataType::Boolean => ScalarImpl::Bool(str_to_bool(text)?),
+        DataType::Int16 => ScalarImpl::Int16(str_parse(text)?),
+        DataType::Int32 => ScalarImpl::Int32(str_parse(text)?),
+        DataType::Int64 => ScalarImpl::Int64(str_parse(text)?),
+        DataType::Float32 => ScalarImpl::Float32(str_parse::<f32>(text)?.into()),
+        DataType::Float64 => ScalarImpl::Float64(str_parse::<f64>(text)?.into()),
+        DataType::Decimal => ScalarImpl::Decimal(str_parse::<Decimal>(text)?),
+        DataType::Date => ScalarImpl::NaiveDate(str_to_date(text)?),
+        DataType::Time => ScalarImpl::NaiveTime(str_to_time(text)?),
+        DataType::Timestamp => ScalarImpl::NaiveDateTime(str_to_timestamp(text)?),
+        DataType::Timestampz => ScalarImpl::Int64(str_to_timestampz(text)?),
+        _ => {
+            return Err(ErrorCode::NotImplemented(
+                format!("unsupported type for csv parser: {:?}", data_type),
+                None.into(),
+            )
+            .into())
+        }
+    };
+    Ok(scalar)
+}
+
+/// Parser for CSV format. The fields of a record are mapped to the columns to parse in order.
+#[derive(Debug, Default)]
+pub struct CsvParser {
+    options: CsvParserOptions,
+}
+
+impl CsvParser {
+    pub fn new(options: CsvParserOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn with_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self::new(CsvParserOptions::from_properties(properties)?))
+    }
+
+    /// Parse all the records of `payload`, calling `f` with the datum of each column in order,
+    /// including the ones not to parse. Returns the number of records.
+    fn parse_records(
+        &self,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+        mut f: impl FnMut(usize, Datum) -> Result<()>,
+    ) -> Result<usize> {
+        let expected_fields = columns.iter().filter(|c| !c.skip_parse).count();
+        let mut records = CsvRecords::new(payload, &self.options);
+        let mut fields = Vec::with_capacity(expected_fields);
+        if self.options.has_header {
+            records.next_record(&mut fields)?;
+        }
+
+        let mut record_count = 0;
+        while records.next_record(&mut fields)? {
+            // An empty line is a NULL if there's a single column to parse, and skipped otherwise.
+            if expected_fields != 1 && matches!(fields.as_slice(), [None]) {
+                continue;
+            }
+            if self.options.strict && fields.len() > expected_fields {
+                return Err(RwError::from(ProtocolError(format!(
+                    "csv record has {} fields, expected at most {}",
+                    fields.len(),
+                    expected_fields
+                ))));
+            }
+            let mut fields = fields.drain(..);
+            for (i, column) in columns.iter().enumerate() {
+                if column.skip_parse {
+                    f(i, None)?;
+                    continue;
+                }
+                let datum = match fields.next().flatten() {
+                    None => None,
+                    Some(text) => match csv_parse_value(&column.data_type, &text) {
+                        Ok(scalar) => Some(scalar),
+                        Err(e) if self.options.strict => {
+                            return Err(RwError::from(ProtocolError(format!(
+                                "failed to parse csv field of column {}: {}",
+                                column.name, e
+                            ))))
+                        }
+                        Err(_) => None,
+                    },
+                };
+                f(i, datum)?;
+            }
+            record_count += 1;
+        }
+        Ok(record_count)
+    }
+
+    /// Parse the records of `payload` into a chunk.
+    pub fn parse_to_chunk(
+        &self,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+    ) -> Result<DataChunk> {
+        let mut builders = columns
+            .iter()
+            .map(|c| c.data_type.create_array_builder(DEFAULT_CHUNK_BUFFER_SIZE))
+            .collect::<Result<Vec<_>>>()?;
+        let cardinality = self
+            .parse_into_builders(payload, columns, &mut builders)?
+            .len();
+        let columns = builders
+            .into_iter()
+            .map(|builder| builder.finish().map(|array| Column::new(Arc::new(array))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DataChunk::new(columns, cardinality))
+    }
+}
+
+impl SourceParser for CsvParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        let mut rows = vec![];
+        let mut row = Vec::with_capacity(columns.len());
+        self.parse_records(payload, columns, |i, datum| {
+            row.push(datum);
+            if i + 1 == columns.len() {
+                rows.push(std::mem::replace(
+                    &mut row,
+                    Vec::with_capacity(columns.len()),
+                ));
+            }
+            Ok(())
+        })?;
+        Ok(Event {
+            ops: vec![Op::Insert; rows.len()],
+            rows,
+        })
+    }
+
+    /// Parse the records of `payload` into the array builders directly, without materializing the
+    /// rows.
+    fn parse_into_builders(
+        &self,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+        builders: &mut [ArrayBuilderImpl],
+    ) -> Result<Vec<Op>> {
+        assert_eq!(columns.len(), builders.len());
+        let record_count = self.parse_records(payload, columns, |i, datum| {
+            builders[i].append_datum(&datum)
+        })?;
+        Ok(vec![Op::Insert; record_count])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use maplit::hashmap;
+    use risingwave_common::array::Row;
+    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::types::NaiveDateWrapper;
+
+    use super::*;
+
+    fn columns(types: &[DataType]) -> Vec<SourceColumnDesc> {
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, data_type)| SourceColumnDesc {
+                name: format!("c{}", i),
+                data_type: data_type.clone(),
+                column_id: ColumnId::from(i as i32),
+                skip_parse: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_records() {
+        let options = CsvParserOptions::default();
+        let data = b"a,\"b,\"\"c\"\"\",\r\n\n\"\",\"multi\nline\"\nlast";
+        let mut records = CsvRecords::new(data, &options);
+        let mut fields = vec![];
+
+        assert!(records.next_record(&mut fields).unwrap());
+        assert_eq!(
+            fields,
+            vec![Some("a".to_string()), Some("b,\"c\"".to_string()), None]
+        );
+        assert!(records.next_record(&mut fields).unwrap());
+        assert_eq!(fields, vec![None]);
+        assert!(records.next_record(&mut fields).unwrap());
+        assert_eq!(
+            fields,
+            vec![Some("".to_string()), Some("multi\nline".to_string())]
+        );
+        assert!(records.next_record(&mut fields).unwrap());
+        assert_eq!(fields, vec![Some("last".to_string())]);
+        assert!(!records.next_record(&mut fields).unwrap());
+
+        let mut records = CsvRecords::new(b"\"open,1", &options);
+        assert!(records.next_record(&mut fields).is_err());
+    }
+
+    #[test]
+    fn test_csv_parser_options() {
+        let parser = CsvParser::with_properties(&hashmap! {
+            CSV_DELIMITER_KEY.to_string() => "|".to_string(),
+            CSV_QUOTE_KEY.to_string() => "'".to_string(),
+            CSV_ESCAPE_KEY.to_string() => "\\".to_string(),
+            CSV_HEADER_KEY.to_string() => "true".to_string(),
+        })
+        .unwrap();
+        let columns = columns(&[DataType::Int32, DataType::Varchar, DataType::Date]);
+        let event = parser
+            .parse(
+                b"id|name|day\n1|'it\\'s|ok'| 2022-06-01 \n\n2||\n",
+                &columns,
+            )
+            .unwrap();
+        assert_eq!(event.ops, vec![Op::Insert, Op::Insert]);
+        assert_eq!(
+            event.rows,
+            vec![
+                vec![
+                    Some(ScalarImpl::Int32(1)),
+                    Some(ScalarImpl::Utf8("it's|ok".to_string())),
+                    Some(ScalarImpl::NaiveDate(NaiveDateWrapper::new(
+                        NaiveDate::from_ymd(2022, 6, 1)
+                    ))),
+                ],
+                vec![Some(ScalarImpl::Int32(2)), None, None],
+            ]
+        );
+
+        assert!(CsvParser::with_properties(&hashmap! {
+            CSV_DELIMITER_KEY.to_string() => "||".to_string(),
+        })
+        .is_err());
+        assert!(CsvParser::with_properties(&hashmap! {
+            CSV_CONVERSION_MODE_KEY.to_string() => "loose".to_string(),
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_csv_parser_conversion_mode() {
+        let mut columns = columns(&[DataType::Int64, DataType::Int64, DataType::Float64]);
+        columns.insert(
+            0,
+            SourceColumnDesc {
+                name: "_row_id".to_string(),
+                data_type: DataType::Int64,
+                column_id: ColumnId::from(3),
+                skip_parse: true,
+            },
+        );
+        let payload = b"1,x,1.5\n2,3\n";
+
+        let strict = CsvParser::default();
+        assert!(strict.parse(payload, &columns).is_err());
+        assert!(strict.parse(b"1,2,3,4\n", &columns).is_err());
+        let chunk = strict.parse_to_chunk(b"1,2\n", &columns).unwrap();
+        assert_eq!(
+            chunk.row_at(0).unwrap().0.to_owned_row(),
+            Row(vec![
+                None,
+                Some(ScalarImpl::Int64(1)),
+                Some(ScalarImpl::Int64(2)),
+                None,
+            ])
+        );
+
+        let lenient = CsvParser::new(CsvParserOptions {
+            strict: false,
+            ..Default::default()
+        });
+        let chunk = lenient.parse_to_chunk(payload, &columns).unwrap();
+        assert_eq!(chunk.cardinality(), 2);
+        assert_eq!(
+            chunk.row_at(0).unwrap().0.to_owned_row(),
+            Row(vec![
+                None,
+                Some(ScalarImpl::Int64(1)),
+                None,
+                Some(ScalarImpl::Float64(1.5.into())),
+            ])
+        );
+        assert_eq!(
+            chunk.row_at(1).unwrap().0.to_owned_row(),
+            Row(vec![
+                None,
+                Some(ScalarImpl::Int64(2)),
+                Some(ScalarImpl::Int64(3)),
+                None,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_csv_parser_single_column() {
+        let columns = columns(&[DataType::Varchar]);
+        let event = CsvParser::default()
+            .parse(b"a\n\n\"\"\n", &columns)
+            .unwrap();
+        assert_eq!(
+            event.rows,
+            vec![
+                vec![Some(ScalarImpl::Utf8("a".to_string()))],
+                vec![None],
+                vec![Some(ScalarImpl::Utf8("".to_string()))],
+            ]
+        );
+    }
+}
@@ -38,16 +38,27 @@ pub struct DebeziumEvent {
     pub payload: Payload,
 }
 
+/// An event is wrapped with its schema, unless the JSON converter of Debezium is configured with
+/// `schemas.enable=false`, in which case only the payload is sent.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum DebeziumEnvelope {
+    WithSchema(DebeziumEvent),
+    PayloadOnly(Payload),
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Payload {
     pub before: Option<BTreeMap<String, Value>>,
     pub after: Option<BTreeMap<String, Value>>,
     pub op: String,
-    #[serde(rename = "ts_ms")]
+    #[serde(rename = "ts_ms", default)]
     pub ts_ms: i64,
 }
 
+/// Parser of the change events of Debezium in JSON, which turns the `before` and `after` images
+/// into the changelog of the table identified by its primary key.
 #[derive(Debug)]
 pub struct DebeziumJsonParser {}
 
@@ -59,7 +70,7 @@ impl DebeziumJsonParser {
                 if column.skip_parse {
                     None
                 } else {
-                    json_parse_value(column, map.get(&column.name)).ok()
+                    json_parse_value(column, map.get(&column.name)).unwrap_or(None)
                 }
             })
             .collect::<Vec<Datum>>()
@@ -68,10 +79,18 @@ impl DebeziumJsonParser {
 
 impl SourceParser for DebeziumJsonParser {
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
-        let event: DebeziumEvent = serde_json::from_slice(payload)
+        // Debezium follows a delete event with a tombstone, an empty message for log compaction.
+        if payload.is_empty() {
+            return Ok(Event::default());
+        }
+
+        let envelope: DebeziumEnvelope = serde_json::from_slice(payload)
             .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
 
-        let mut payload = event.payload;
+        let mut payload = match envelope {
+            DebeziumEnvelope::WithSchema(event) => event.payload,
+            DebeziumEnvelope::PayloadOnly(payload) => payload,
+        };
 
         match payload.op.as_str() {
             DEBEZIUM_UPDATE_OP => {
@@ -329,4 +348,25 @@ mod test {
         assert_eq!(result.rows.len(), 0);
         assert_eq!(result.ops.len(), 0);
     }
+
+    #[test]
+    fn test_debezium_json_parser_payload_only() {
+        let data = r#"{"before":{"id":102,"name":"car battery","description":"12V car battery","weight":8.1},"after":{"id":102,"name":"car battery","description":"24V car battery","weight":8.1},"source":{"version":"1.7.1.Final","connector":"mysql","name":"dbserver1","db":"inventory","table":"products"},"op":"u","ts_ms":1639551901165,"transaction":null}"#;
+        let parser = DebeziumJsonParser {};
+        let columns = get_test_columns();
+        let result = parser.parse(data.as_ref(), columns.as_ref()).unwrap();
+        assert_eq!(result.ops, vec![Op::UpdateDelete, Op::UpdateInsert]);
+        assert_eq!(
+            result.rows[1][2],
+            Some(ScalarImpl::Utf8("24V car battery".to_string()))
+        );
+
+        // Tombstone following a delete.
+        let result = parser.parse(&[], columns.as_ref()).unwrap();
+        assert!(result.ops.is_empty());
+        assert!(result.rows.is_empty());
+
+        let result = parser.parse(br#"{"op":"x"}"#, columns.as_ref());
+        assert!(result.is_err());
+    }
 }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use risingwave_common::array::Op;
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
@@ -21,28 +23,84 @@ use serde_json::Value;
 use crate::parser::common::json_parse_value;
 use crate::{Event, SourceColumnDesc, SourceParser};
 
+/// The option deciding what to do with the records that can't be parsed, either `fail` or `skip`.
+const JSON_ERROR_MODE_KEY: &str = "json.error.mode";
+
+/// What [`JSONParser`] does with a record that is not valid JSON, or has a field that can't be
+/// parsed as the type of its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonErrorMode {
+    /// Return the error, which fails the source.
+    Fail,
+    /// Drop the record with a warning.
+    Skip,
+}
+
 /// Parser for JSON format
 #[derive(Debug)]
-pub struct JSONParser;
+pub struct JSONParser {
+    error_mode: JsonErrorMode,
+}
 
-impl SourceParser for JSONParser {
-    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+impl Default for JSONParser {
+    fn default() -> Self {
+        Self::new(JsonErrorMode::Fail)
+    }
+}
+
+impl JSONParser {
+    pub fn new(error_mode: JsonErrorMode) -> Self {
+        Self { error_mode }
+    }
+
+    pub fn with_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        let error_mode = match properties
+            .get(JSON_ERROR_MODE_KEY)
+            .map(|s| s.to_lowercase())
+            .as_deref()
+        {
+            None | Some("fail") => JsonErrorMode::Fail,
+            Some("skip") => JsonErrorMode::Skip,
+            Some(other) => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "'{}' must be either 'fail' or 'skip', got '{}'",
+                    JSON_ERROR_MODE_KEY, other
+                ))))
+            }
+        };
+        Ok(Self::new(error_mode))
+    }
+
+    fn parse_row(payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Vec<Datum>> {
         let value: Value = serde_json::from_slice(payload)
             .map_err(|e| RwError::from(ProtocolError(e.to_string())))?;
 
-        Ok(Event {
-            ops: vec![Op::Insert],
-            rows: vec![columns
-                .iter()
-                .map(|column| {
-                    if column.skip_parse {
-                        None
-                    } else {
-                        json_parse_value(column, value.get(&column.name)).ok()
-                    }
-                })
-                .collect::<Vec<Datum>>()],
-        })
+        columns
+            .iter()
+            .map(|column| {
+                if column.skip_parse {
+                    Ok(None)
+                } else {
+                    json_parse_value(column, value.get(&column.name))
+                }
+            })
+            .collect()
+    }
+}
+
+impl SourceParser for JSONParser {
+    fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event> {
+        match Self::parse_row(payload, columns) {
+            Ok(row) => Ok(Event {
+                ops: vec![Op::Insert],
+                rows: vec![row],
+            }),
+            Err(e) if self.error_mode == JsonErrorMode::Skip => {
+                log::warn!("skip malformed json record: {}", e);
+                Ok(Event::default())
+            }
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -52,11 +110,11 @@ mod tests {
     use risingwave_common::types::{DataType, ScalarImpl};
     use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp};
 
-    use crate::{JSONParser, SourceColumnDesc, SourceParser};
+    use crate::{JSONParser, JsonErrorMode, SourceColumnDesc, SourceParser};
 
     #[test]
     fn test_json_parser() {
-        let parser = JSONParser {};
+        let parser = JSONParser::default();
         let payload = r#"{"i32":1,"bool":true,"i16":1,"i64":12345678,"f32":1.23,"f64":1.2345,"varchar":"varchar","date":"2021-01-01","timestamp":"2021-01-01 16:06:12.269"}"#.as_bytes();
         let descs = vec![
             SourceColumnDesc {
@@ -147,4 +205,51 @@ mod tests {
         let result = parser.parse(payload, &descs);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_json_parser_coercion_and_errors() {
+        let descs = vec![
+            SourceColumnDesc {
+                name: "d".to_string(),
+                data_type: DataType::Decimal,
+                column_id: ColumnId::from(0),
+                skip_parse: false,
+            },
+            SourceColumnDesc {
+                name: "ts".to_string(),
+                data_type: DataType::Timestamp,
+                column_id: ColumnId::from(1),
+                skip_parse: false,
+            },
+            SourceColumnDesc {
+                name: "i16".to_string(),
+                data_type: DataType::Int16,
+                column_id: ColumnId::from(2),
+                skip_parse: false,
+            },
+        ];
+
+        let parser = JSONParser::default();
+        let payload = r#"{"d":12.345,"ts":"2021-01-01 16:06:12","i16":null}"#.as_bytes();
+        let event = parser.parse(payload, &descs).unwrap();
+        let row = event.rows.first().unwrap();
+        assert_eq!(row[0], Some(ScalarImpl::Decimal("12.345".parse().unwrap())));
+        assert_eq!(
+            row[1],
+            Some(ScalarImpl::NaiveDateTime(
+                str_to_timestamp("2021-01-01 16:06:12").unwrap()
+            ))
+        );
+        assert_eq!(row[2], None);
+
+        // Out of the range of `i16`.
+        let payload = r#"{"i16":65536}"#.as_bytes();
+        assert!(parser.parse(payload, &descs).is_err());
+
+        let parser = JSONParser::new(JsonErrorMode::Skip);
+        let event = parser.parse(payload, &descs).unwrap();
+        assert!(event.ops.is_empty() && event.rows.is_empty());
+        let event = parser.parse(r#"{"i16":"#.as_bytes(), &descs).unwrap();
+        assert!(event.rows.is_empty());
+    }
 }
@@ -16,10 +16,12 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+pub use csv_parser::*;
 pub use debezium::*;
+use itertools::Itertools;
 pub use json_parser::*;
 pub use protobuf_parser::*;
-use risingwave_common::array::Op;
+use risingwave_common::array::{ArrayBuilderImpl, Op};
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::Datum;
@@ -30,6 +32,7 @@ use crate::{SourceColumnDesc, SourceFormat};
 #[allow(dead_code)]
 mod avro_parser;
 mod common;
+mod csv_parser;
 mod debezium;
 mod json_parser;
 mod protobuf_parser;
@@ -47,6 +50,23 @@ pub struct Event {
 pub trait SourceParser: Send + Sync + Debug + 'static {
     /// parse needs to be a member method because some format like Protobuf needs to be pre-compiled
     fn parse(&self, payload: &[u8], columns: &[SourceColumnDesc]) -> Result<Event>;
+
+    /// Parses `payload` and appends the rows to `builders`, one for each column. Returns the ops of
+    /// the rows. Parsers able to build the columns directly should override it.
+    fn parse_into_builders(
+        &self,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+        builders: &mut [ArrayBuilderImpl],
+    ) -> Result<Vec<Op>> {
+        let event = self.parse(payload, columns)?;
+        for row in &event.rows {
+            row.iter()
+                .zip_eq(builders.iter_mut())
+                .try_for_each(|(datum, builder)| builder.append_datum(datum))?;
+        }
+        Ok(event.ops)
+    }
 }
 
 #[derive(Debug)]
@@ -55,6 +75,7 @@ pub enum SourceParserImpl {
     Protobuf(ProtobufParser),
     DebeziumJson(DebeziumJsonParser),
     Avro(AvroParser),
+    Csv(CsvParser),
 }
 
 impl SourceParserImpl {
@@ -64,6 +85,28 @@ impl SourceParserImpl {
             Self::Protobuf(parser) => parser.parse(payload, columns),
             Self::DebeziumJson(parser) => parser.parse(payload, columns),
             Self::Avro(avro_parser) => avro_parser.parse(payload, columns),
+            Self::Csv(parser) => parser.parse(payload, columns),
+        }
+    }
+
+    /// Whether parsing may block on IO, e.g., to fetch a schema from a schema registry, in which
+    /// case it should be run with `spawn_blocking`.
+    pub fn may_block(&self) -> bool {
+        matches!(self, Self::Avro(avro_parser) if avro_parser.uses_schema_registry())
+    }
+
+    pub fn parse_into_builders(
+        &self,
+        payload: &[u8],
+        columns: &[SourceColumnDesc],
+        builders: &mut [ArrayBuilderImpl],
+    ) -> Result<Vec<Op>> {
+        match self {
+            Self::Json(parser) => parser.parse_into_builders(payload, columns, builders),
+            Self::Protobuf(parser) => parser.parse_into_builders(payload, columns, builders),
+            Self::DebeziumJson(parser) => parser.parse_into_builders(payload, columns, builders),
+            Self::Avro(avro_parser) => avro_parser.parse_into_builders(payload, columns, builders),
+            Self::Csv(parser) => parser.parse_into_builders(payload, columns, builders),
         }
     }
 
@@ -74,7 +117,7 @@ impl SourceParserImpl {
     ) -> Result<Arc<Self>> {
         const PROTOBUF_MESSAGE_KEY: &str = "proto.message";
         let parser = match format {
-            SourceFormat::Json => SourceParserImpl::Json(JSONParser {}),
+            SourceFormat::Json => SourceParserImpl::Json(JSONParser::with_properties(properties)?),
             SourceFormat::Protobuf => {
                 let message_name = properties.get(PROTOBUF_MESSAGE_KEY).ok_or_else(|| {
                     RwError::from(ProtocolError(format!(
@@ -88,6 +131,7 @@ impl SourceParserImpl {
             SourceFormat::Avro => {
                 SourceParserImpl::Avro(AvroParser::new(schema_location, properties.clone()).await?)
             }
+            SourceFormat::Csv => SourceParserImpl::Csv(CsvParser::with_properties(properties)?),
             _ => {
                 return Err(RwError::from(ProtocolError(
                     "format not support".to_string(),
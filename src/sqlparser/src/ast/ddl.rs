@@ -117,6 +117,42 @@ impl fmt::Display for AlterTableOperation {
     }
 }
 
+/// An `ALTER SOURCE` (`Statement::AlterSource`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterSourceOperation {
+    /// `ADD [ COLUMN ] <column_def>`
+    AddColumn { column_def: ColumnDef },
+}
+
+impl fmt::Display for AlterSourceOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterSourceOperation::AddColumn { column_def } => {
+                write!(f, "ADD COLUMN {}", column_def)
+            }
+        }
+    }
+}
+
+/// An `ALTER MATERIALIZED VIEW` (`Statement::AlterMaterializedView`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterMaterializedViewOperation {
+    /// `SET PARALLELISM { = | TO } <parallelism>`
+    SetParallelism { parallelism: u64 },
+}
+
+impl fmt::Display for AlterMaterializedViewOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterMaterializedViewOperation::SetParallelism { parallelism } => {
+                write!(f, "SET PARALLELISM = {}", parallelism)
+            }
+        }
+    }
+}
+
 /// An `ALTER COLUMN` (`Statement::AlterTable`) operation
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
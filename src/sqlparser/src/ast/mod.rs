@@ -32,8 +32,9 @@ use serde::{Deserialize, Serialize};
 
 pub use self::data_type::{DataType, StructField};
 pub use self::ddl::{
-    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
-    ReferentialAction, TableConstraint,
+    AlterColumnOperation, AlterMaterializedViewOperation, AlterSourceOperation,
+    AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef, ReferentialAction,
+    TableConstraint,
 };
 pub use self::operator::{BinaryOperator, UnaryOperator};
 pub use self::query::{
@@ -260,6 +261,8 @@ pub enum Expr {
     Nested(Box<Expr>),
     /// A literal value, such as string, number, date or NULL
     Value(Value),
+    /// A positional parameter of a prepared statement, e.g. `$1`
+    Parameter { index: u64 },
     /// A constant of form `<data_type> 'value'`.
     /// This can represent ANSI SQL `DATE`, `TIME`, and `TIMESTAMP` literals (such as `DATE
     /// '2020-01-01'`), as well as constants of other types (a non-standard PostgreSQL extension).
@@ -359,6 +362,7 @@ impl fmt::Display for Expr {
             Expr::Collate { expr, collation } => write!(f, "{} COLLATE {}", expr, collation),
             Expr::Nested(ast) => write!(f, "({})", ast),
             Expr::Value(v) => write!(f, "{}", v),
+            Expr::Parameter { index } => write!(f, "${}", index),
             Expr::TypedString { data_type, value } => {
                 write!(f, "{}", data_type)?;
                 write!(f, " '{}'", &value::escape_single_quote_string(value))
@@ -687,6 +691,8 @@ pub enum Statement {
         columns: Vec<Ident>,
         /// A SQL query that specifies what to insert
         source: Box<Query>,
+        /// Action taken when a row conflicts with an existing one
+        on: Option<OnInsert>,
     },
     Copy {
         /// TABLE
@@ -751,12 +757,33 @@ pub enum Statement {
         is_materialized: bool,
         stmt: CreateSourceStatement,
     },
+    /// CREATE SINK
+    CreateSink { stmt: CreateSinkStatement },
+    /// CREATE USER
+    CreateUser(CreateUserStatement),
     /// ALTER TABLE
     AlterTable {
         /// Table name
         name: ObjectName,
         operation: AlterTableOperation,
     },
+    /// ALTER SOURCE
+    AlterSource {
+        /// Source name
+        name: ObjectName,
+        operation: AlterSourceOperation,
+    },
+    /// ALTER MATERIALIZED VIEW
+    AlterMaterializedView {
+        /// Materialized view name
+        name: ObjectName,
+        operation: AlterMaterializedViewOperation,
+    },
+    /// ALTER SYSTEM SET <param> = <value>
+    AlterSystem {
+        param: Ident,
+        value: SetVariableValue,
+    },
     /// DESCRIBE TABLE OR SOURCE
     Describe {
         /// Table or Source name
@@ -780,6 +807,11 @@ pub enum Statement {
     ///
     /// Note: this is a PostgreSQL-specific statement.
     ShowVariable { variable: Vec<Ident> },
+    /// RESET <variable>
+    ///
+    /// Note: this is a PostgreSQL-specific statement. `RESET ALL` is represented by the identifier
+    /// `ALL`.
+    ResetVariable { variable: Ident },
     /// `{ BEGIN [ TRANSACTION | WORK ] | START TRANSACTION } ...`
     StartTransaction { modes: Vec<TransactionMode> },
     /// ABORT
@@ -912,12 +944,17 @@ impl fmt::Display for Statement {
                 table_name,
                 columns,
                 source,
+                on,
             } => {
                 write!(f, "INSERT INTO {table_name} ", table_name = table_name,)?;
                 if !columns.is_empty() {
                     write!(f, "({}) ", display_comma_separated(columns))?;
                 }
-                write!(f, "{}", source)
+                write!(f, "{}", source)?;
+                if let Some(on) = on {
+                    write!(f, "{}", on)?;
+                }
+                Ok(())
             }
 
             Statement::Copy {
@@ -1074,6 +1111,8 @@ impl fmt::Display for Statement {
                 table_name = table_name,
                 columns = display_separated(columns, ",")
             ),
+            Statement::CreateUser(statement) => write!(f, "CREATE USER {}", statement),
+            Statement::CreateSink { stmt } => write!(f, "CREATE SINK {}", stmt),
             Statement::CreateSource {
                 is_materialized,
                 stmt,
@@ -1090,6 +1129,15 @@ impl fmt::Display for Statement {
             Statement::AlterTable { name, operation } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
+            Statement::AlterSource { name, operation } => {
+                write!(f, "ALTER SOURCE {} {}", name, operation)
+            }
+            Statement::AlterMaterializedView { name, operation } => {
+                write!(f, "ALTER MATERIALIZED VIEW {} {}", name, operation)
+            }
+            Statement::AlterSystem { param, value } => {
+                write!(f, "ALTER SYSTEM SET {} = {}", param, value)
+            }
             Statement::Drop(stmt) => write!(f, "DROP {}", stmt),
             Statement::SetVariable {
                 local,
@@ -1114,6 +1162,7 @@ impl fmt::Display for Statement {
                 }
                 Ok(())
             }
+            Statement::ResetVariable { variable } => write!(f, "RESET {}", variable),
             Statement::StartTransaction { modes } => {
                 write!(f, "START TRANSACTION")?;
                 if !modes.is_empty() {
@@ -1241,6 +1290,8 @@ impl fmt::Display for Statement {
 pub enum OnInsert {
     /// ON DUPLICATE KEY UPDATE (MySQL when the key already exists, then execute an update instead)
     DuplicateKeyUpdate(Vec<Assignment>),
+    /// ON CONFLICT DO NOTHING (PostgreSQL, skip the rows whose key already exists)
+    DoNothing,
 }
 
 impl fmt::Display for OnInsert {
@@ -1251,6 +1302,7 @@ impl fmt::Display for OnInsert {
                 " ON DUPLICATE KEY UPDATE {}",
                 display_comma_separated(expr)
             ),
+            Self::DoNothing => write!(f, " ON CONFLICT DO NOTHING"),
         }
     }
 }
@@ -1479,7 +1531,9 @@ pub enum ObjectType {
     Schema,
     Source,
     MaterializedSource,
+    Sink,
     Database,
+    User,
 }
 
 impl fmt::Display for ObjectType {
@@ -1492,7 +1546,9 @@ impl fmt::Display for ObjectType {
             ObjectType::Schema => "SCHEMA",
             ObjectType::Source => "SOURCE",
             ObjectType::MaterializedSource => "MATERIALIZED SOURCE",
+            ObjectType::Sink => "SINK",
             ObjectType::Database => "DATABASE",
+            ObjectType::User => "USER",
         })
     }
 }
@@ -1509,15 +1565,19 @@ impl ParseTo for ObjectType {
             ObjectType::MaterializedSource
         } else if parser.parse_keyword(Keyword::SOURCE) {
             ObjectType::Source
+        } else if parser.parse_keyword(Keyword::SINK) {
+            ObjectType::Sink
         } else if parser.parse_keyword(Keyword::INDEX) {
             ObjectType::Index
         } else if parser.parse_keyword(Keyword::SCHEMA) {
             ObjectType::Schema
         } else if parser.parse_keyword(Keyword::DATABASE) {
             ObjectType::Database
+        } else if parser.parse_keyword(Keyword::USER) {
+            ObjectType::User
         } else {
             return parser.expected(
-                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, or SCHEMA after DROP",
+                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SINK, SCHEMA, DATABASE or USER after DROP",
                 parser.peek_token(),
             );
         };
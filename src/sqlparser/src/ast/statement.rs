@@ -23,6 +23,7 @@ use super::ObjectType;
 use crate::ast::{display_comma_separated, ColumnDef, ObjectName, SqlOption, TableConstraint};
 use crate::keywords::Keyword;
 use crate::parser::{Parser, ParserError};
+use crate::tokenizer::Token;
 
 /// Consumes token from the parser into an AST node.
 pub trait ParseTo: Sized {
@@ -84,6 +85,10 @@ pub enum SourceSchema {
     Protobuf(ProtobufSchema),
     // Keyword::PROTOBUF ProtobufSchema
     Json, // Keyword::JSON
+    Avro(AvroSchema),
+    // Keyword::AVRO AvroSchema
+    DebeziumJson, // Keyword::DEBEZIUM_JSON
+    Csv,          // Keyword::CSV
 }
 
 impl ParseTo for SourceSchema {
@@ -93,9 +98,17 @@ impl ParseTo for SourceSchema {
         } else if p.parse_keywords(&[Keyword::PROTOBUF]) {
             impl_parse_to!(protobuf_schema: ProtobufSchema, p);
             SourceSchema::Protobuf(protobuf_schema)
+        } else if p.parse_keywords(&[Keyword::AVRO]) {
+            impl_parse_to!(avro_schema: AvroSchema, p);
+            SourceSchema::Avro(avro_schema)
+        } else if p.parse_keywords(&[Keyword::DEBEZIUM_JSON]) {
+            SourceSchema::DebeziumJson
+        } else if p.parse_keywords(&[Keyword::CSV]) {
+            SourceSchema::Csv
         } else {
             return Err(ParserError::ParserError(
-                "expected JSON | PROTOBUF after ROW FORMAT".to_string(),
+                "expected JSON | PROTOBUF | AVRO | DEBEZIUM_JSON | CSV after ROW FORMAT"
+                    .to_string(),
             ));
         };
         Ok(schema)
@@ -107,6 +120,9 @@ impl fmt::Display for SourceSchema {
         match self {
             SourceSchema::Protobuf(protobuf_schema) => write!(f, "PROTOBUF {}", protobuf_schema),
             SourceSchema::Json => write!(f, "JSON"),
+            SourceSchema::Avro(avro_schema) => write!(f, "AVRO {}", avro_schema),
+            SourceSchema::DebeziumJson => write!(f, "DEBEZIUM_JSON"),
+            SourceSchema::Csv => write!(f, "CSV"),
         }
     }
 }
@@ -148,6 +164,35 @@ impl fmt::Display for ProtobufSchema {
     }
 }
 
+// sql_grammar!(AvroSchema {
+//     [Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION],
+//     row_schema_location: AstString,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AvroSchema {
+    pub row_schema_location: AstString,
+}
+
+impl ParseTo for AvroSchema {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!([Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION], p);
+        impl_parse_to!(row_schema_location: AstString, p);
+        Ok(Self {
+            row_schema_location,
+        })
+    }
+}
+
+impl fmt::Display for AvroSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!([Keyword::ROW, Keyword::SCHEMA, Keyword::LOCATION], v);
+        impl_fmt_display!(row_schema_location, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
 impl ParseTo for CreateSourceStatement {
     fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
         impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
@@ -182,6 +227,165 @@ impl fmt::Display for CreateSourceStatement {
     }
 }
 
+// sql_grammar!(CreateSinkStatement {
+//     if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS],
+//     sink_name: ObjectName,
+//     [Keyword::FROM],
+//     materialized_view: ObjectName,
+//     with_properties: WithProperties,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateSinkStatement {
+    pub if_not_exists: bool,
+    pub sink_name: ObjectName,
+    pub materialized_view: ObjectName,
+    pub with_properties: WithProperties,
+}
+
+impl ParseTo for CreateSinkStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
+        impl_parse_to!(sink_name: ObjectName, p);
+        impl_parse_to!([Keyword::FROM], p);
+        impl_parse_to!(materialized_view: ObjectName, p);
+        impl_parse_to!(with_properties: WithProperties, p);
+        Ok(Self {
+            if_not_exists,
+            sink_name,
+            materialized_view,
+            with_properties,
+        })
+    }
+}
+
+impl fmt::Display for CreateSinkStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
+        impl_fmt_display!(sink_name, v, self);
+        impl_fmt_display!([Keyword::FROM], v);
+        impl_fmt_display!(materialized_view, v, self);
+        impl_fmt_display!(with_properties, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
+// sql_grammar!(CreateUserStatement {
+//     user_name: ObjectName,
+//     with_options: CreateUserWithOptions,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateUserStatement {
+    pub user_name: ObjectName,
+    pub with_options: CreateUserWithOptions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CreateUserOption {
+    SuperUser,
+    NoSuperUser,
+    CreateDB,
+    NoCreateDB,
+    Login,
+    NoLogin,
+    /// `PASSWORD 'password'`, or `PASSWORD NULL` which is `None`.
+    Password(Option<AstString>),
+}
+
+impl fmt::Display for CreateUserOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateUserOption::SuperUser => f.write_str("SUPERUSER"),
+            CreateUserOption::NoSuperUser => f.write_str("NOSUPERUSER"),
+            CreateUserOption::CreateDB => f.write_str("CREATEDB"),
+            CreateUserOption::NoCreateDB => f.write_str("NOCREATEDB"),
+            CreateUserOption::Login => f.write_str("LOGIN"),
+            CreateUserOption::NoLogin => f.write_str("NOLOGIN"),
+            CreateUserOption::Password(Some(password)) => write!(f, "PASSWORD {}", password),
+            CreateUserOption::Password(None) => f.write_str("PASSWORD NULL"),
+        }
+    }
+}
+
+/// The options of `CREATE USER`, which may be preceded by an optional `WITH`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateUserWithOptions(pub Vec<CreateUserOption>);
+
+impl ParseTo for CreateUserWithOptions {
+    fn parse_to(parser: &mut Parser) -> Result<Self, ParserError> {
+        let mut options = vec![];
+        let _ = parser.parse_keyword(Keyword::WITH);
+        loop {
+            let token = parser.peek_token();
+            if token == Token::EOF || token == Token::SemiColon {
+                break;
+            }
+            let option = match parser.parse_one_of_keywords(&[
+                Keyword::SUPERUSER,
+                Keyword::NOSUPERUSER,
+                Keyword::CREATEDB,
+                Keyword::NOCREATEDB,
+                Keyword::LOGIN,
+                Keyword::NOLOGIN,
+                Keyword::PASSWORD,
+            ]) {
+                Some(Keyword::SUPERUSER) => CreateUserOption::SuperUser,
+                Some(Keyword::NOSUPERUSER) => CreateUserOption::NoSuperUser,
+                Some(Keyword::CREATEDB) => CreateUserOption::CreateDB,
+                Some(Keyword::NOCREATEDB) => CreateUserOption::NoCreateDB,
+                Some(Keyword::LOGIN) => CreateUserOption::Login,
+                Some(Keyword::NOLOGIN) => CreateUserOption::NoLogin,
+                Some(Keyword::PASSWORD) => {
+                    if parser.parse_keyword(Keyword::NULL) {
+                        CreateUserOption::Password(None)
+                    } else {
+                        CreateUserOption::Password(Some(AstString::parse_to(parser)?))
+                    }
+                }
+                _ => return parser.expected(
+                    "SUPERUSER | NOSUPERUSER | CREATEDB | NOCREATEDB | LOGIN | NOLOGIN | PASSWORD",
+                    token,
+                ),
+            };
+            options.push(option);
+        }
+        Ok(Self(options))
+    }
+}
+
+impl fmt::Display for CreateUserWithOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        write!(f, "WITH {}", self.0.iter().join(" "))
+    }
+}
+
+impl ParseTo for CreateUserStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(user_name: ObjectName, p);
+        impl_parse_to!(with_options: CreateUserWithOptions, p);
+        Ok(Self {
+            user_name,
+            with_options,
+        })
+    }
+}
+
+impl fmt::Display for CreateUserStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(user_name, v, self);
+        impl_fmt_display!(with_options, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AstVec<T>(pub Vec<T>);
@@ -130,6 +130,7 @@ define_keywords!(
     COMMIT,
     COMMITTED,
     CONDITION,
+    CONFLICT,
     CONNECT,
     CONSTRAINT,
     CONTAINS,
@@ -141,6 +142,7 @@ define_keywords!(
     COVAR_POP,
     COVAR_SAMP,
     CREATE,
+    CREATEDB,
     CROSS,
     CSV,
     CUBE,
@@ -165,6 +167,7 @@ define_keywords!(
     DATE,
     DAY,
     DEALLOCATE,
+    DEBEZIUM_JSON,
     DEC,
     DECIMAL,
     DECLARE,
@@ -178,6 +181,7 @@ define_keywords!(
     DIRECTORY,
     DISCONNECT,
     DISTINCT,
+    DO,
     DOUBLE,
     DROP,
     DYNAMIC,
@@ -266,6 +270,7 @@ define_keywords!(
     LOCALTIME,
     LOCALTIMESTAMP,
     LOCATION,
+    LOGIN,
     LOWER,
     MATCH,
     MATERIALIZED,
@@ -288,10 +293,14 @@ define_keywords!(
     NEW,
     NEXT,
     NO,
+    NOCREATEDB,
+    NOLOGIN,
     NONE,
     NORMALIZE,
     NOSCAN,
+    NOSUPERUSER,
     NOT,
+    NOTHING,
     NTH_VALUE,
     NTILE,
     NULL,
@@ -316,11 +325,13 @@ define_keywords!(
     OVER,
     OVERLAPS,
     OVERLAY,
+    PARALLELISM,
     PARAMETER,
     PARQUET,
     PARTITION,
     PARTITIONED,
     PARTITIONS,
+    PASSWORD,
     PERCENT,
     PERCENTILE_CONT,
     PERCENTILE_DISC,
@@ -364,6 +375,7 @@ define_keywords!(
     REPAIR,
     REPEATABLE,
     REPLACE,
+    RESET,
     RESTRICT,
     RESULT,
     RETURN,
@@ -396,6 +408,7 @@ define_keywords!(
     SETS,
     SHOW,
     SIMILAR,
+    SINK,
     SMALLINT,
     SNAPSHOT,
     SOME,
@@ -423,6 +436,7 @@ define_keywords!(
     SUBSTRING_REGEX,
     SUCCEEDS,
     SUM,
+    SUPERUSER,
     SYMMETRIC,
     SYNC,
     SYSTEM,
@@ -181,6 +181,9 @@ impl Parser {
                 Keyword::COPY => Ok(self.parse_copy()?),
                 Keyword::SET => Ok(self.parse_set()?),
                 Keyword::SHOW => Ok(self.parse_show()?),
+                Keyword::RESET => Ok(Statement::ResetVariable {
+                    variable: self.parse_identifier()?,
+                }),
                 Keyword::DESCRIBE => Ok(Statement::Describe {
                     name: self.parse_object_name()?,
                 }),
@@ -466,6 +469,12 @@ impl Parser {
                 self.prev_token();
                 Ok(Expr::Value(self.parse_value()?))
             }
+            Token::Placeholder(s) => {
+                let index = s[1..].parse::<u64>().map_err(|e| {
+                    ParserError::ParserError(format!("Could not parse '{}' as u64: {}", s, e))
+                })?;
+                Ok(Expr::Parameter { index })
+            }
 
             Token::LParen => {
                 let expr =
@@ -1378,6 +1387,8 @@ impl Parser {
             self.parse_create_source(false, or_replace)
         } else if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::SOURCE]) {
             self.parse_create_source(true, or_replace)
+        } else if self.parse_keyword(Keyword::SINK) {
+            self.parse_create_sink(or_replace)
         } else if or_replace {
             self.expected(
                 "[EXTERNAL] TABLE or [MATERIALIZED] VIEW after CREATE OR REPLACE",
@@ -1391,6 +1402,8 @@ impl Parser {
             self.parse_create_schema()
         } else if self.parse_keyword(Keyword::DATABASE) {
             self.parse_create_database()
+        } else if self.parse_keyword(Keyword::USER) {
+            self.parse_create_user()
         } else {
             self.expected("an object type after CREATE", self.peek_token())
         }
@@ -1416,6 +1429,10 @@ impl Parser {
         })
     }
 
+    pub fn parse_create_user(&mut self) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateUser(CreateUserStatement::parse_to(self)?))
+    }
+
     pub fn parse_create_view(
         &mut self,
         materialized: bool,
@@ -1458,6 +1475,19 @@ impl Parser {
         })
     }
 
+    // CREATE [OR REPLACE]?
+    // SINK
+    // [IF NOT EXISTS]?
+    // <sink_name: Ident>
+    // FROM
+    // <materialized_view: Ident>
+    // [WITH (properties)]?
+    pub fn parse_create_sink(&mut self, _or_replace: bool) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateSink {
+            stmt: CreateSinkStatement::parse_to(self)?,
+        })
+    }
+
     fn parse_with_properties(&mut self) -> Result<Vec<SqlOption>, ParserError> {
         Ok(self.parse_options(Keyword::WITH)?.to_vec())
     }
@@ -1733,8 +1763,59 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword(Keyword::TABLE)?;
-        self.parse_alter_table()
+        if self.parse_keyword(Keyword::TABLE) {
+            self.parse_alter_table()
+        } else if self.parse_keyword(Keyword::SOURCE) {
+            self.parse_alter_source()
+        } else if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW]) {
+            self.parse_alter_materialized_view()
+        } else if self.parse_keyword(Keyword::SYSTEM) {
+            self.parse_alter_system()
+        } else {
+            self.expected(
+                "TABLE, SOURCE, MATERIALIZED VIEW or SYSTEM after ALTER",
+                self.peek_token(),
+            )
+        }
+    }
+
+    pub fn parse_alter_materialized_view(&mut self) -> Result<Statement, ParserError> {
+        let view_name = self.parse_object_name()?;
+        self.expect_keywords(&[Keyword::SET, Keyword::PARALLELISM])?;
+        if !self.consume_token(&Token::Eq) {
+            self.expect_keyword(Keyword::TO)?;
+        }
+        let parallelism = self.parse_literal_uint()?;
+        Ok(Statement::AlterMaterializedView {
+            name: view_name,
+            operation: AlterMaterializedViewOperation::SetParallelism { parallelism },
+        })
+    }
+
+    pub fn parse_alter_system(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keyword(Keyword::SET)?;
+        let param = self.parse_identifier()?;
+        if !self.consume_token(&Token::Eq) {
+            self.expect_keyword(Keyword::TO)?;
+        }
+        let token = self.peek_token();
+        let value = match (self.parse_value(), token) {
+            (Ok(value), _) => SetVariableValue::Literal(value),
+            (Err(_), Token::Word(ident)) => SetVariableValue::Ident(ident.to_ident()),
+            (Err(_), unexpected) => self.expected("parameter value", unexpected)?,
+        };
+        Ok(Statement::AlterSystem { param, value })
+    }
+
+    pub fn parse_alter_source(&mut self) -> Result<Statement, ParserError> {
+        let source_name = self.parse_object_name()?;
+        self.expect_keyword(Keyword::ADD)?;
+        let _ = self.parse_keyword(Keyword::COLUMN);
+        let column_def = self.parse_column_def()?;
+        Ok(Statement::AlterSource {
+            name: source_name,
+            operation: AlterSourceOperation::AddColumn { column_def },
+        })
     }
 
     pub fn parse_alter_table(&mut self) -> Result<Statement, ParserError> {
@@ -2502,15 +2583,32 @@ impl Parser {
 
     pub fn parse_set(&mut self) -> Result<Statement, ParserError> {
         let modifier = self.parse_one_of_keywords(&[Keyword::SESSION, Keyword::LOCAL]);
-        let variable = self.parse_identifier()?;
-        if self.consume_token(&Token::Eq) || self.parse_keyword(Keyword::TO) {
+        let mut variable = self.parse_identifier()?;
+        // `SET TIME ZONE <value>` is an alias for `SET timezone = <value>`.
+        let time_zone =
+            variable.value.eq_ignore_ascii_case("TIME") && self.parse_keyword(Keyword::ZONE);
+        if time_zone {
+            variable = Ident::new("timezone");
+        }
+        if time_zone || self.consume_token(&Token::Eq) || self.parse_keyword(Keyword::TO) {
             let mut values = vec![];
             loop {
                 let token = self.peek_token();
-                let value = match (self.parse_value(), token) {
-                    (Ok(value), _) => SetVariableValue::Literal(value),
-                    (Err(_), Token::Word(ident)) => SetVariableValue::Ident(ident.to_ident()),
-                    (Err(_), unexpected) => self.expected("variable value", unexpected)?,
+                let value = if token == Token::Minus {
+                    // Negative numbers, e.g. `SET extra_float_digits = -3`.
+                    self.next_token();
+                    match self.next_token() {
+                        Token::Number(n, l) => {
+                            SetVariableValue::Literal(Value::Number(format!("-{}", n), l))
+                        }
+                        unexpected => self.expected("number after minus sign", unexpected)?,
+                    }
+                } else {
+                    match (self.parse_value(), token) {
+                        (Ok(value), _) => SetVariableValue::Literal(value),
+                        (Err(_), Token::Word(ident)) => SetVariableValue::Ident(ident.to_ident()),
+                        (Err(_), unexpected) => self.expected("variable value", unexpected)?,
+                    }
                 };
                 values.push(value);
                 if self.consume_token(&Token::Comma) {
@@ -2927,10 +3025,18 @@ impl Parser {
 
         let source = Box::new(self.parse_query()?);
 
+        let on = if self.parse_keywords(&[Keyword::ON, Keyword::CONFLICT]) {
+            self.expect_keywords(&[Keyword::DO, Keyword::NOTHING])?;
+            Some(OnInsert::DoNothing)
+        } else {
+            None
+        };
+
         Ok(Statement::Insert {
             table_name,
             columns,
             source,
+            on,
         })
     }
 
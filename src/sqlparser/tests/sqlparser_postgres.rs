@@ -356,6 +356,44 @@ fn parse_drop_schema_if_exists() {
     }
 }
 
+#[test]
+fn parse_create_user() {
+    let sql = "CREATE USER user WITH SUPERUSER NOLOGIN PASSWORD 'pwd'";
+    match verified_stmt(sql) {
+        Statement::CreateUser(stmt) => {
+            assert_eq!(stmt.user_name.to_string(), "user");
+            assert_eq!(
+                stmt.with_options.0,
+                vec![
+                    CreateUserOption::SuperUser,
+                    CreateUserOption::NoLogin,
+                    CreateUserOption::Password(Some(AstString("pwd".to_string()))),
+                ]
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    one_statement_parses_to(
+        "CREATE USER user CREATEDB PASSWORD NULL",
+        "CREATE USER user WITH CREATEDB PASSWORD NULL",
+    );
+    verified_stmt("CREATE USER user");
+    assert!(parse_sql_statements("CREATE USER user WITH UNKNOWN").is_err());
+}
+
+#[test]
+fn parse_drop_user() {
+    let sql = "DROP USER IF EXISTS user";
+    match verified_stmt(sql) {
+        Statement::Drop(stmt) => {
+            assert_eq!(stmt.object_type, ObjectType::User);
+            assert!(stmt.if_exists);
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_copy_example() {
     let sql = r#"COPY public.actor (actor_id, first_name, last_name, last_update, value) FROM stdin;
@@ -417,6 +455,16 @@ fn parse_set() {
         }
     );
 
+    let stmt = verified_stmt("SET a = -1");
+    assert_eq!(
+        stmt,
+        Statement::SetVariable {
+            local: false,
+            variable: "a".into(),
+            value: vec![SetVariableValue::Literal(number("-1"))],
+        }
+    );
+
     let stmt = verified_stmt("SET a = DEFAULT");
     assert_eq!(
         stmt,
@@ -439,6 +487,7 @@ fn parse_set() {
 
     one_statement_parses_to("SET a TO b", "SET a = b");
     one_statement_parses_to("SET SESSION a = b", "SET a = b");
+    one_statement_parses_to("SET TIME ZONE 'UTC'", "SET timezone = 'UTC'");
 
     assert_eq!(
         parse_sql_statements("SET"),
@@ -481,6 +530,25 @@ fn parse_show() {
     )
 }
 
+#[test]
+fn parse_reset() {
+    let stmt = verified_stmt("RESET a");
+    assert_eq!(
+        stmt,
+        Statement::ResetVariable {
+            variable: "a".into()
+        }
+    );
+
+    let stmt = verified_stmt("RESET ALL");
+    assert_eq!(
+        stmt,
+        Statement::ResetVariable {
+            variable: "ALL".into()
+        }
+    );
+}
+
 #[test]
 fn parse_deallocate() {
     let stmt = verified_stmt("DEALLOCATE a");
@@ -544,6 +612,21 @@ fn parse_execute() {
     );
 }
 
+#[test]
+fn parse_insert_on_conflict_do_nothing() {
+    match verified_stmt("INSERT INTO t VALUES (1, 2) ON CONFLICT DO NOTHING") {
+        Statement::Insert { table_name, on, .. } => {
+            assert_eq!(table_name.to_string(), "t");
+            assert_eq!(on, Some(OnInsert::DoNothing));
+        }
+        _ => unreachable!(),
+    }
+    verified_stmt("INSERT INTO t SELECT * FROM s ON CONFLICT DO NOTHING");
+
+    let res = parse_sql_statements("INSERT INTO t VALUES (1) ON CONFLICT DO UPDATE");
+    assert!(res.is_err());
+}
+
 #[test]
 fn parse_prepare() {
     let stmt = verified_stmt("PREPARE a AS INSERT INTO customers VALUES (a1, a2, a3)");
@@ -689,6 +772,27 @@ fn parse_pg_regex_match_ops() {
     }
 }
 
+#[test]
+fn parse_parameters() {
+    let select = verified_only_select("SELECT $1 + v1 FROM t WHERE v2 = $2");
+    assert_eq!(
+        SelectItem::UnnamedExpr(Expr::BinaryOp {
+            left: Box::new(Expr::Parameter { index: 1 }),
+            op: BinaryOperator::Plus,
+            right: Box::new(Expr::Identifier(Ident::new("v1"))),
+        }),
+        select.projection[0]
+    );
+    assert_eq!(
+        Some(Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(Ident::new("v2"))),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::Parameter { index: 2 }),
+        }),
+        select.selection
+    );
+}
+
 #[test]
 fn test_transaction_statement() {
     let statement = verified_stmt("SET TRANSACTION SNAPSHOT '000003A1-1'");
@@ -16,6 +16,7 @@ use bytes::{BufMut, Bytes, BytesMut};
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use risingwave_storage::hummock::{
     Block, BlockBuilder, BlockBuilderOptions, BlockHolder, BlockIterator, CompressionAlgorithm,
+    DEFAULT_COMPRESSION_LEVEL,
 };
 
 const TABLES_PER_SSTABLE: u32 = 10;
@@ -100,6 +101,7 @@ fn build_block_data(t: u32, i: u64) -> Bytes {
     let options = BlockBuilderOptions {
         capacity: BLOCK_CAPACITY,
         compression_algorithm: CompressionAlgorithm::None,
+        compression_level: DEFAULT_COMPRESSION_LEVEL,
         restart_interval: RESTART_INTERVAL,
     };
     let mut builder = BlockBuilder::new(options);
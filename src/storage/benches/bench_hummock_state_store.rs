@@ -0,0 +1,137 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use risingwave_common::config::StorageConfig;
+use risingwave_meta::hummock::test_utils::setup_compute_env;
+use risingwave_meta::hummock::MockHummockMetaClient;
+use risingwave_rpc_client::HummockMetaClient;
+use risingwave_storage::hummock::HummockStorage;
+use risingwave_storage::monitor::{MonitoredStateStore, ObjectStoreMetrics, StateStoreMetrics};
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{StateStore, StateStoreImpl};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+const KEY_COUNT: usize = 100000;
+const EPOCH: u64 = 1;
+
+fn gen_key(idx: usize) -> Bytes {
+    Bytes::from(format!("key_{:08}", idx))
+}
+
+fn default_config_for_bench() -> StorageConfig {
+    StorageConfig {
+        sstable_size_mb: 256,
+        block_size_kb: 64,
+        bloom_false_positive: 0.1,
+        compression_algorithm: "none".to_string(),
+        compression_level: 4,
+        share_buffers_sync_parallelism: 2,
+        share_buffer_compaction_worker_threads_number: 1,
+        shared_buffer_threshold_mb: 192,
+        shared_buffer_capacity_mb: 256,
+        write_stall_l0_sst_count: 0,
+        data_directory: "hummock_001".to_string(),
+        write_conflict_detection_enabled: false,
+        block_cache_capacity_mb: 64,
+        meta_cache_capacity_mb: 64,
+        disable_remote_compactor: true,
+        enable_local_spill: false,
+        local_object_store: "memory".to_string(),
+    }
+}
+
+/// Creates a Hummock state store over the object store of `url`, and loads `KEY_COUNT` keys into
+/// it. The keys are committed so that reads go through SSTs instead of the shared buffer.
+async fn prepare_hummock(url: &str) -> MonitoredStateStore<HummockStorage> {
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref,
+        worker_node.id,
+    ));
+    let store = StateStoreImpl::new(
+        url,
+        Arc::new(default_config_for_bench()),
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+        Arc::new(ObjectStoreMetrics::unused()),
+    )
+    .await
+    .unwrap()
+    .into_hummock_state_store()
+    .unwrap();
+
+    let batch = (0..KEY_COUNT)
+        .map(|idx| (gen_key(idx), StorageValue::new_default_put("value")))
+        .collect();
+    store.ingest_batch(batch, EPOCH).await.unwrap();
+    store.sync(Some(EPOCH)).await.unwrap();
+    meta_client
+        .commit_epoch(
+            EPOCH,
+            store
+                .inner()
+                .local_version_manager()
+                .get_uncommitted_ssts(EPOCH),
+        )
+        .await
+        .unwrap();
+    store.wait_epoch(EPOCH).await.unwrap();
+    store
+}
+
+fn bench_state_store<S: StateStore>(c: &mut Criterion, runtime: &Runtime, name: &str, store: S) {
+    c.bench_with_input(BenchmarkId::new("hummock get", name), &store, |b, store| {
+        let mut idx = 0;
+        b.iter(|| {
+            idx = (idx + 7919) % KEY_COUNT;
+            let value = runtime.block_on(store.get(&gen_key(idx), EPOCH)).unwrap();
+            assert!(value.is_some());
+        });
+    });
+
+    c.bench_with_input(
+        BenchmarkId::new("hummock scan", name),
+        &store,
+        |b, store| {
+            let mut idx = 0;
+            b.iter(|| {
+                idx = (idx + 7919) % (KEY_COUNT - 1000);
+                let range = gen_key(idx).to_vec()..gen_key(idx + 1000).to_vec();
+                let kvs = runtime.block_on(store.scan(range, None, EPOCH)).unwrap();
+                assert_eq!(kvs.len(), 1000);
+            });
+        },
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let store = runtime.block_on(prepare_hummock("hummock+memory"));
+    bench_state_store(c, &runtime, "memory", store);
+
+    let dir = TempDir::new().unwrap();
+    let url = format!("hummock+disk://{}", dir.path().to_str().unwrap());
+    let store = runtime.block_on(prepare_hummock(&url));
+    bench_state_store(c, &runtime, "disk", store);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
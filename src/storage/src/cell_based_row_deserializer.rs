@@ -17,9 +17,9 @@ use std::collections::HashMap;
 use bytes::Bytes;
 use risingwave_common::array::Row;
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::error::Result;
 use risingwave_common::types::Datum;
-use risingwave_common::util::ordered::deserialize_column_id;
+use risingwave_common::util::ordered::split_cell_key;
 use risingwave_common::util::value_encoding::deserialize_cell;
 
 #[derive(Clone)]
@@ -57,14 +57,8 @@ impl CellBasedRowDeserializer {
         pk_with_cell_id: &Bytes,
         cell: &Bytes,
     ) -> Result<Option<(Vec<u8>, Row)>> {
-        let pk_with_cell_id = pk_with_cell_id.to_vec();
-        let pk_vec_len = pk_with_cell_id.len();
-        if pk_vec_len < 4 {
-            return Err(ErrorCode::InternalError("corrupted key".to_owned()).into());
-        }
-        let (cur_pk_bytes, cell_id_bytes) = pk_with_cell_id.split_at(pk_vec_len - 4);
+        let (cur_pk_bytes, cell_id) = split_cell_key(pk_with_cell_id)?;
         let mut result = None;
-        let cell_id = deserialize_column_id(cell_id_bytes)?;
         if let Some(prev_pk_bytes) = &self.pk_bytes && prev_pk_bytes != cur_pk_bytes  {
             result = self.take();
             self.pk_bytes = Some(cur_pk_bytes.to_vec());
@@ -34,6 +34,16 @@ pub enum StorageError {
         RwError,
     ),
 
+    #[error("Keyspace error: {0}")]
+    Keyspace(
+        #[backtrace]
+        #[source]
+        RwError,
+    ),
+
+    #[error("RocksDB error: {0}")]
+    RocksDB(String),
+
     #[error("State table error: {0}")]
     StateTable(
         #[backtrace]
@@ -143,3 +143,95 @@ impl BlockCache {
         self.inner.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+    use crate::hummock::{BlockBuilder, BlockBuilderOptions, HummockError};
+
+    fn build_block(value: &[u8]) -> Box<Block> {
+        let mut builder = BlockBuilder::new(BlockBuilderOptions::default());
+        builder.add(&key_with_epoch(b"k".to_vec(), 1), value);
+        Box::new(Block::decode(builder.build()).unwrap())
+    }
+
+    #[test]
+    fn test_block_cache_lru() {
+        let block_len = build_block(b"v").len();
+        let cache = BlockCache::new(block_len * 3);
+        for block_idx in 0..3 {
+            cache.insert(1, block_idx, build_block(b"v"));
+        }
+        assert_eq!(cache.size(), block_len * 3);
+
+        // Touch the first block so that the second one becomes the least recently used.
+        assert!(cache.get(1, 0).is_some());
+        cache.insert(2, 0, build_block(b"v"));
+        assert!(cache.get(1, 1).is_none());
+        assert!(cache.get(1, 0).is_some());
+        assert!(cache.get(1, 2).is_some());
+        assert!(cache.get(2, 0).is_some());
+        assert_eq!(cache.size(), block_len * 3);
+
+        // Blocks in use are not evicted.
+        let holder = cache.get(1, 0).unwrap();
+        for block_idx in 0..3 {
+            cache.insert(3, block_idx, build_block(b"v"));
+        }
+        assert!(cache.get(1, 0).is_some());
+        assert_eq!(holder.data(), build_block(b"v").data());
+    }
+
+    #[tokio::test]
+    async fn test_block_cache_get_or_insert_with() {
+        let cache = BlockCache::new(1 << 20);
+        let block = cache
+            .get_or_insert_with(1, 0, async { Ok(build_block(b"v1")) })
+            .await
+            .unwrap();
+        assert_eq!(block.data(), build_block(b"v1").data());
+
+        // The cached block is returned without fetching again.
+        let block = cache
+            .get_or_insert_with(1, 0, async { Err(HummockError::invalid_block()) })
+            .await
+            .unwrap();
+        assert_eq!(block.data(), build_block(b"v1").data());
+        assert!(cache
+            .get_or_insert_with(1, 1, async { Err(HummockError::invalid_block()) })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_block_cache_concurrent_get_or_insert_with() {
+        let cache = BlockCache::new(1 << 20);
+        let fetch_count = AtomicUsize::new(0);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let fetch = |value: &'static [u8], rx: Option<tokio::sync::oneshot::Receiver<()>>| {
+            let fetch_count = &fetch_count;
+            async move {
+                fetch_count.fetch_add(1, Ordering::SeqCst);
+                if let Some(rx) = rx {
+                    rx.await.unwrap();
+                }
+                Ok(build_block(value))
+            }
+        };
+
+        // The second request comes while the first one is still fetching the block, and waits
+        // for it instead of fetching again.
+        let (first, second, _) = tokio::join!(
+            cache.get_or_insert_with(1, 0, fetch(b"v1", Some(rx))),
+            cache.get_or_insert_with(1, 0, fetch(b"v2", None)),
+            async { tx.send(()).unwrap() },
+        );
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap().data(), build_block(b"v1").data());
+        assert_eq!(second.unwrap().data(), build_block(b"v1").data());
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use risingwave_common::util::epoch::Epoch;
+use risingwave_hummock_sdk::key::{get_epoch, get_table_id};
+use risingwave_pb::hummock::TableOption;
+
+/// Decides whether a kv pair should be dropped in compaction, besides the versions shadowed below
+/// the watermark.
+pub trait CompactionFilter {
+    /// Returns `true` if the kv pair of `full_key` should be dropped.
+    fn should_delete(&mut self, _full_key: &[u8]) -> bool {
+        false
+    }
+}
+
+/// Keeps all kv pairs.
+pub struct DummyCompactionFilter;
+
+impl CompactionFilter for DummyCompactionFilter {}
+
+/// Drops the kv pairs written earlier than the `ttl` of their tables.
+pub struct TTLCompactionFilter {
+    /// Kv pairs of a table with an epoch less than its expire epoch are expired.
+    expire_epochs: HashMap<u32, u64>,
+}
+
+impl TTLCompactionFilter {
+    pub fn new(table_options: &HashMap<u32, TableOption>, now: Epoch) -> Self {
+        let expire_epochs = table_options
+            .iter()
+            .filter(|(_, option)| option.ttl > 0)
+            .map(|(table_id, option)| {
+                let ttl_ms = option.ttl as u64 * 1000;
+                (*table_id, now.subtract_ms(ttl_ms).0)
+            })
+            .collect();
+        Self { expire_epochs }
+    }
+}
+
+impl CompactionFilter for TTLCompactionFilter {
+    fn should_delete(&mut self, full_key: &[u8]) -> bool {
+        get_table_id(full_key)
+            .and_then(|table_id| self.expire_epochs.get(&table_id))
+            .map_or(false, |expire_epoch| get_epoch(full_key) < *expire_epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+
+    fn table_key(table_id: u32, epoch: u64) -> Vec<u8> {
+        let mut key = vec![b't'];
+        key.put_u32(table_id);
+        key.extend_from_slice(b"key");
+        key_with_epoch(key, epoch)
+    }
+
+    #[test]
+    fn test_ttl_compaction_filter() {
+        let now = Epoch::now();
+        let table_options =
+            HashMap::from([(1, TableOption { ttl: 60 }), (2, TableOption { ttl: 0 })]);
+        let mut filter = TTLCompactionFilter::new(&table_options, now);
+
+        let expired = now.subtract_ms(61 * 1000).0;
+        let alive = now.subtract_ms(59 * 1000).0;
+        assert!(filter.should_delete(&table_key(1, expired)));
+        assert!(!filter.should_delete(&table_key(1, alive)));
+        // Tables without a ttl never expire.
+        assert!(!filter.should_delete(&table_key(2, expired)));
+        assert!(!filter.should_delete(&table_key(3, expired)));
+        assert!(!filter.should_delete(&key_with_epoch(b"key".to_vec(), expired)));
+    }
+}
@@ -37,8 +37,12 @@ use tokio::task::JoinHandle;
 use super::group_builder::KeyValueGroupingImpl::VirtualNode;
 use super::group_builder::{GroupedSstableBuilder, VirtualNodeGrouping};
 use super::iterator::{BoxedForwardHummockIterator, ConcatIterator, MergeIterator};
-use super::{HummockResult, SSTableBuilder, SSTableIterator, SSTableIteratorType, Sstable};
+use super::{
+    HummockResult, SSTableBuilder, SSTableBuilderOptions, SSTableIterator, SSTableIteratorType,
+    Sstable,
+};
 use crate::hummock::compaction_executor::CompactionExecutor;
+use crate::hummock::compaction_filter::{CompactionFilter, TTLCompactionFilter};
 use crate::hummock::iterator::ReadOptions;
 use crate::hummock::shared_buffer::shared_buffer_uploader::UploadTaskPayload;
 use crate::hummock::sstable_store::SstableStoreRef;
@@ -156,6 +160,8 @@ impl Compactor {
             prefix_pairs: vec![],
             // VNode mappings are not required when compacting shared buffer to L0
             vnode_mappings: vec![],
+            // Expired kv pairs are only dropped when compacting SSTs
+            table_options: HashMap::default(),
         };
 
         let parallelism = compact_task.splits.len();
@@ -456,7 +462,9 @@ impl Compactor {
                 let timer = Instant::now();
                 let table_id = (self.context.sstable_id_generator)().await?;
                 let cost = (timer.elapsed().as_secs_f64() * 1000000.0).round() as u64;
-                let builder = SSTableBuilder::new(self.context.options.as_ref().into());
+                let builder = SSTableBuilder::new(SSTableBuilderOptions::try_from(
+                    self.context.options.as_ref(),
+                )?);
                 get_id_time.fetch_add(cost, Ordering::Relaxed);
                 Ok((table_id, builder))
             },
@@ -469,12 +477,17 @@ impl Compactor {
         } else {
             self.context.stats.compact_sst_duration.start_timer()
         };
+        let compaction_filter = TTLCompactionFilter::new(
+            &self.compact_task.table_options,
+            risingwave_common::util::epoch::Epoch::now(),
+        );
         Compactor::compact_and_build_sst(
             &mut builder,
             kr,
             iter,
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
+            compaction_filter,
         )
         .await?;
 
@@ -714,6 +727,7 @@ impl Compactor {
         mut iter: BoxedForwardHummockIterator,
         has_user_key_overlap: bool,
         watermark: Epoch,
+        mut compaction_filter: impl CompactionFilter,
     ) -> HummockResult<()>
     where
         B: Clone + Fn() -> F,
@@ -758,10 +772,13 @@ impl Compactor {
             let epoch = get_epoch(iter_key);
 
             // Among keys with same user key, only retain keys which satisfy `epoch` >= `watermark`,
-            // and the latest key which satisfies `epoch` < `watermark`
+            // and the latest key which satisfies `epoch` < `watermark`. The latter is dropped as
+            // well if it's a tombstone or filtered out, given no older version in lower levels.
             if epoch < watermark {
                 skip_key = BytesMut::from(iter_key);
-                if iter.value().is_delete() && !has_user_key_overlap {
+                if (iter.value().is_delete() || compaction_filter.should_delete(iter_key))
+                    && !has_user_key_overlap
+                {
                     iter.next().await?;
                     continue;
                 }
@@ -777,3 +794,115 @@ impl Compactor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    use super::*;
+    use crate::hummock::compaction_filter::DummyCompactionFilter;
+    use crate::hummock::iterator::test_utils::{
+        gen_iterator_test_sstable_from_kv_pair, iterator_test_key_of_epoch, mock_sstable_store,
+    };
+    use crate::hummock::test_utils::default_builder_opt_for_test;
+    use crate::hummock::value::HummockValue;
+    use crate::hummock::SstableMeta;
+
+    async fn compact_test_sstable(
+        has_user_key_overlap: bool,
+        watermark: Epoch,
+        compaction_filter: impl CompactionFilter,
+    ) -> Vec<(u64, Bytes, SstableMeta, Vec<VNodeBitmap>)> {
+        let sstable_store = mock_sstable_store();
+        let table = gen_iterator_test_sstable_from_kv_pair(
+            1,
+            vec![
+                (1, 300, HummockValue::put(b"v3".to_vec())),
+                (1, 200, HummockValue::put(b"v2".to_vec())),
+                (1, 100, HummockValue::put(b"v1".to_vec())),
+                (2, 300, HummockValue::delete()),
+                (2, 100, HummockValue::put(b"v1".to_vec())),
+                (3, 100, HummockValue::put(b"v1".to_vec())),
+            ],
+            sstable_store.clone(),
+        )
+        .await;
+        let iter = Box::new(SSTableIterator::create(
+            sstable_store
+                .sstable(table.id, &mut StoreLocalStatistic::default())
+                .await
+                .unwrap(),
+            sstable_store,
+            Arc::new(ReadOptions::default()),
+        ));
+
+        let next_id = AtomicU64::new(1001);
+        let mut builder = GroupedSstableBuilder::new(
+            || async {
+                Ok((
+                    next_id.fetch_add(1, SeqCst),
+                    SSTableBuilder::new(default_builder_opt_for_test()),
+                ))
+            },
+            VirtualNode(VirtualNodeGrouping::new(Arc::new(HashMap::new()))),
+        );
+        Compactor::compact_and_build_sst(
+            &mut builder,
+            KeyRange::inf(),
+            iter,
+            has_user_key_overlap,
+            watermark,
+            compaction_filter,
+        )
+        .await
+        .unwrap();
+        builder.seal_current();
+        builder.finish()
+    }
+
+    #[tokio::test]
+    async fn test_compact_drop_stale_versions() {
+        // Versions above the watermark and the latest version below it are retained.
+        let ssts = compact_test_sstable(true, 250, DummyCompactionFilter).await;
+        assert_eq!(ssts.len(), 1);
+        let meta = &ssts[0].2;
+        assert_eq!(meta.key_count, 5);
+        assert_eq!(meta.smallest_key, iterator_test_key_of_epoch(1, 300));
+        assert_eq!(meta.largest_key, iterator_test_key_of_epoch(3, 100));
+
+        // Tombstones below the watermark are retained if the key may exist in lower levels.
+        let ssts = compact_test_sstable(true, 400, DummyCompactionFilter).await;
+        assert_eq!(ssts[0].2.key_count, 3);
+
+        // Otherwise they are dropped along with the versions they shadow.
+        let ssts = compact_test_sstable(false, 400, DummyCompactionFilter).await;
+        let meta = &ssts[0].2;
+        assert_eq!(meta.key_count, 2);
+        assert_eq!(meta.smallest_key, iterator_test_key_of_epoch(1, 300));
+        assert_eq!(meta.largest_key, iterator_test_key_of_epoch(3, 100));
+    }
+
+    /// Drops kv pairs written before the given epoch.
+    struct ExpireBefore(Epoch);
+
+    impl CompactionFilter for ExpireBefore {
+        fn should_delete(&mut self, full_key: &[u8]) -> bool {
+            get_epoch(full_key) < self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compact_with_compaction_filter() {
+        // Filtered kv pairs are retained if the key may exist in lower levels.
+        let ssts = compact_test_sstable(true, 250, ExpireBefore(150)).await;
+        assert_eq!(ssts[0].2.key_count, 5);
+
+        // Otherwise they are dropped. Versions above the watermark are never filtered.
+        let ssts = compact_test_sstable(false, 250, ExpireBefore(150)).await;
+        let meta = &ssts[0].2;
+        assert_eq!(meta.key_count, 3);
+        assert_eq!(meta.smallest_key, iterator_test_key_of_epoch(1, 300));
+        assert_eq!(meta.largest_key, iterator_test_key_of_epoch(2, 300));
+    }
+}
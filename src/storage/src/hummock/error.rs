@@ -28,6 +28,8 @@ enum HummockErrorInner {
     ChecksumMismatch { expected: u64, found: u64 },
     #[error("Invalid block.")]
     InvalidBlock,
+    #[error("Corrupted SST {sst_id}: {reason}")]
+    CorruptedSstable { sst_id: u64, reason: String },
     #[error("Encode error {0}.")]
     EncodeError(String),
     #[error("Decode error {0}.")]
@@ -74,6 +76,14 @@ impl HummockError {
         HummockErrorInner::InvalidBlock.into()
     }
 
+    pub fn corrupted_sstable(sst_id: u64, reason: impl ToString) -> HummockError {
+        HummockErrorInner::CorruptedSstable {
+            sst_id,
+            reason: reason.to_string(),
+        }
+        .into()
+    }
+
     pub fn encode_error(error: impl ToString) -> HummockError {
         HummockErrorInner::EncodeError(error.to_string()).into()
     }
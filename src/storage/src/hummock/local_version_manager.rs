@@ -12,8 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::atomic::AtomicUsize;
-use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 
@@ -28,7 +28,7 @@ use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot;
 use tokio_retry::strategy::jitter;
-use tracing::error;
+use tracing::{error, warn};
 
 use super::local_version::{LocalVersion, PinnedVersion, ReadVersion};
 use super::shared_buffer::shared_buffer_batch::SharedBufferBatch;
@@ -51,6 +51,7 @@ struct WorkerContext {
 }
 
 struct BufferTracker {
+    flush_threshold: usize,
     capacity: usize,
     upload_size: Arc<AtomicUsize>,
     replicate_size: Arc<AtomicUsize>,
@@ -73,6 +74,11 @@ impl BufferTracker {
     pub fn can_write(&self) -> bool {
         self.get_upload_size() + self.get_replicate_size() <= self.capacity
     }
+
+    /// Whether the batches to upload have grown large enough to be flushed in background.
+    pub fn need_flush(&self) -> bool {
+        self.get_upload_size() > self.flush_threshold
+    }
 }
 
 /// The `LocalVersionManager` maintains a local copy of storage service's hummock version data.
@@ -83,7 +89,14 @@ pub struct LocalVersionManager {
     local_version: RwLock<LocalVersion>,
     worker_context: WorkerContext,
     buffer_tracker: BufferTracker,
+    /// Whether a background flush triggered by `shared_buffer_threshold_mb` is in progress.
+    flushing: AtomicBool,
+    /// Local writes stall once L0 holds this many SSTs. 0 means never stall.
+    write_stall_l0_sst_count: usize,
     write_conflict_detector: Option<Arc<ConflictDetector>>,
+    /// Worker thread count of the shared buffer compaction, picked up by the uploader before its
+    /// next upload.
+    compaction_worker_threads: Arc<AtomicU32>,
 }
 
 impl LocalVersionManager {
@@ -113,6 +126,9 @@ impl LocalVersionManager {
 
         let global_upload_batches_size = Arc::new(AtomicUsize::new(0));
         let global_replicate_batches_size = Arc::new(AtomicUsize::new(0));
+        let compaction_worker_threads = Arc::new(AtomicU32::new(
+            options.share_buffer_compaction_worker_threads_number,
+        ));
 
         let local_version_manager = Arc::new(LocalVersionManager {
             local_version: RwLock::new(LocalVersion::new(pinned_version, version_unpin_worker_tx)),
@@ -121,11 +137,15 @@ impl LocalVersionManager {
                 shared_buffer_uploader_tx,
             },
             buffer_tracker: BufferTracker {
+                flush_threshold: (options.shared_buffer_threshold_mb as usize) * (1 << 20),
                 capacity: (options.shared_buffer_capacity_mb as usize) * (1 << 20),
                 upload_size: global_upload_batches_size,
                 replicate_size: global_replicate_batches_size,
             },
+            flushing: AtomicBool::new(false),
+            write_stall_l0_sst_count: options.write_stall_l0_sst_count as usize,
             write_conflict_detector: write_conflict_detector.clone(),
+            compaction_worker_threads: compaction_worker_threads.clone(),
         });
 
         // Pin and get the latest version.
@@ -148,12 +168,20 @@ impl LocalVersionManager {
             shared_buffer_uploader_rx,
             stats,
             write_conflict_detector,
+            compaction_worker_threads,
         );
         tokio::spawn(async move { uploader.run().await });
 
         local_version_manager
     }
 
+    /// Changes the worker thread count of the shared buffer compaction. 0 means compacting on the
+    /// uploader's own runtime. Takes effect from the next upload on.
+    pub fn set_compaction_worker_threads(&self, worker_threads: u32) {
+        self.compaction_worker_threads
+            .store(worker_threads, Relaxed);
+    }
+
     /// Updates cached version if the new version is of greater id.
     /// You shouldn't unpin even the method returns false, as it is possible `hummock_version` is
     /// being referenced by some readers.
@@ -222,7 +250,7 @@ impl LocalVersionManager {
     }
 
     pub async fn write_shared_buffer(
-        &self,
+        self: &Arc<Self>,
         epoch: HummockEpoch,
         kv_pairs: Vec<(Bytes, StorageValue)>,
         is_remote_batch: bool,
@@ -230,6 +258,9 @@ impl LocalVersionManager {
         let sorted_items = Self::build_shared_buffer_item_batches(kv_pairs, epoch);
 
         let batch_size = SharedBufferBatch::measure_batch_size(&sorted_items);
+        if !is_remote_batch {
+            self.stall_write_if_l0_too_deep().await;
+        }
         while !self.buffer_tracker.can_write() {
             self.sync_shared_buffer(None).await?;
         }
@@ -260,11 +291,62 @@ impl LocalVersionManager {
         } else {
             // The batch will be synced to S3 asynchronously if it is a local batch
             shared_buffer.write().write_batch(batch);
+            self.try_flush_in_background();
         }
 
         Ok(batch_size)
     }
 
+    /// Spawns a flush of all the shared buffers into L0 SSTs once the batches to upload exceed
+    /// the flush threshold, so that the shared buffer seldom reaches its capacity.
+    fn try_flush_in_background(self: &Arc<Self>) {
+        if !self.buffer_tracker.need_flush() || self.flushing.swap(true, SeqCst) {
+            return;
+        }
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = this.sync_shared_buffer(None).await {
+                warn!("failed to flush shared buffer in background: {:?}", e);
+            }
+            this.flushing.store(false, SeqCst);
+        });
+    }
+
+    /// Waits until the number of L0 SSTs drops below `write_stall_l0_sst_count`, which gives
+    /// compaction a chance to catch up with the writes.
+    async fn stall_write_if_l0_too_deep(&self) {
+        if self.write_stall_l0_sst_count == 0 {
+            return;
+        }
+        let mut receiver = self.worker_context.version_update_notifier_tx.subscribe();
+        let mut stalled = false;
+        loop {
+            let l0_sst_count = self.l0_sst_count();
+            if l0_sst_count < self.write_stall_l0_sst_count {
+                return;
+            }
+            if !stalled {
+                warn!(
+                    "write stalled since L0 has {} SSTs, which reaches the limit {}",
+                    l0_sst_count, self.write_stall_l0_sst_count
+                );
+                stalled = true;
+            }
+            if receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn l0_sst_count(&self) -> usize {
+        self.local_version
+            .read()
+            .pinned_version()
+            .levels()
+            .first()
+            .map_or(0, |level| level.table_infos.len())
+    }
+
     pub async fn sync_shared_buffer(&self, epoch: Option<HummockEpoch>) -> HummockResult<()> {
         if self.buffer_tracker.is_empty() {
             return Ok(());
@@ -544,12 +626,14 @@ impl LocalVersionManager {
 mod tests {
     use std::sync::atomic::AtomicUsize;
     use std::sync::Arc;
+    use std::time::Duration;
 
     use bytes::Bytes;
+    use risingwave_common::config::StorageConfig;
     use risingwave_hummock_sdk::HummockSSTableId;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
-    use risingwave_pb::hummock::{HummockVersion, KeyRange, SstableInfo};
+    use risingwave_pb::hummock::{HummockVersion, KeyRange, Level, LevelType, SstableInfo};
 
     use super::LocalVersionManager;
     use crate::hummock::conflict_detector::ConflictDetector;
@@ -858,4 +942,96 @@ mod tests {
         assert!(local_version.get_shared_buffer(epochs[0]).is_none());
         assert!(local_version.get_shared_buffer(epochs[1]).is_none());
     }
+
+    #[tokio::test]
+    async fn test_write_stall_on_deep_l0() {
+        let opt = Arc::new(StorageConfig {
+            write_stall_l0_sst_count: 1,
+            ..default_config_for_test()
+        });
+        let (_, hummock_manager_ref, _, worker_node) = setup_compute_env(8080).await;
+        let local_version_manager = LocalVersionManager::new(
+            opt.clone(),
+            mock_sstable_store(),
+            Arc::new(StateStoreMetrics::unused()),
+            Arc::new(MockHummockMetaClient::new(
+                hummock_manager_ref.clone(),
+                worker_node.id,
+            )),
+            ConflictDetector::new_from_config(opt),
+        )
+        .await;
+        let pinned_version = local_version_manager.get_pinned_version();
+        let initial_id = pinned_version.id();
+        let epoch = pinned_version.max_committed_epoch() + 1;
+        let batch = gen_dummy_batch(epoch);
+
+        // Fill L0 up to the limit.
+        let sst = gen_dummy_sst_info(
+            1,
+            vec![SharedBufferBatch::new(
+                LocalVersionManager::build_shared_buffer_item_batches(batch.clone(), epoch),
+                epoch,
+                Arc::new(AtomicUsize::new(0)),
+            )],
+        );
+        let version = HummockVersion {
+            id: initial_id + 1,
+            levels: vec![Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![sst],
+            }],
+            ..Default::default()
+        };
+        assert!(local_version_manager.try_update_pinned_version(version));
+
+        let mut write_task = {
+            let local_version_manager = local_version_manager.clone();
+            let batch = batch.clone();
+            tokio::spawn(async move {
+                local_version_manager
+                    .write_shared_buffer(epoch, batch, false)
+                    .await
+                    .unwrap();
+            })
+        };
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), &mut write_task)
+                .await
+                .is_err()
+        );
+        assert!(local_version_manager
+            .get_local_version()
+            .get_shared_buffer(epoch)
+            .is_none());
+
+        // Remote batches are never stalled.
+        local_version_manager
+            .write_shared_buffer(epoch, batch, true)
+            .await
+            .unwrap();
+
+        // The write proceeds once L0 is compacted.
+        let version = HummockVersion {
+            id: initial_id + 2,
+            ..Default::default()
+        };
+        assert!(local_version_manager.try_update_pinned_version(version));
+        write_task.await.unwrap();
+        assert_eq!(
+            local_version_manager
+                .get_local_version()
+                .get_shared_buffer(epoch)
+                .unwrap()
+                .read()
+                .size(),
+            2 * SharedBufferBatch::measure_batch_size(
+                &LocalVersionManager::build_shared_buffer_item_batches(
+                    gen_dummy_batch(epoch),
+                    epoch
+                )
+            )
+        );
+    }
 }
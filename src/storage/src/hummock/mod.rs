@@ -28,6 +28,7 @@ mod sstable;
 pub use sstable::*;
 mod cache;
 pub mod compaction_executor;
+pub mod compaction_filter;
 pub mod compactor;
 #[cfg(test)]
 mod compactor_tests;
@@ -103,6 +104,8 @@ impl HummockStorage {
         // For conflict key detection. Enabled by setting `write_conflict_detection_enabled` to
         // true in `StorageConfig`
         let write_conflict_detector = ConflictDetector::new_from_config(options.clone());
+        // Fail early on an invalid config rather than on building the first SST.
+        SSTableBuilderOptions::try_from(options.as_ref())?;
 
         let local_version_manager = LocalVersionManager::new(
             options.clone(),
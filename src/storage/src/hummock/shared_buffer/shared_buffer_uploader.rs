@@ -13,8 +13,8 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
-use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
 
 use futures::FutureExt;
@@ -72,6 +72,10 @@ pub struct SharedBufferUploader {
     next_local_sstable_id: Arc<AtomicU64>,
     stats: Arc<StateStoreMetrics>,
     compaction_executor: Option<Arc<CompactionExecutor>>,
+    /// The latest worker thread count of the compaction executor, which can be changed at runtime.
+    compaction_worker_threads: Arc<AtomicU32>,
+    /// The worker thread count `compaction_executor` was built with.
+    current_compaction_worker_threads: u32,
 }
 
 impl SharedBufferUploader {
@@ -83,14 +87,10 @@ impl SharedBufferUploader {
         uploader_rx: mpsc::UnboundedReceiver<UploadItem>,
         stats: Arc<StateStoreMetrics>,
         write_conflict_detector: Option<Arc<ConflictDetector>>,
+        compaction_worker_threads: Arc<AtomicU32>,
     ) -> Self {
-        let compaction_executor = if options.share_buffer_compaction_worker_threads_number == 0 {
-            None
-        } else {
-            Some(Arc::new(CompactionExecutor::new(Some(
-                options.share_buffer_compaction_worker_threads_number as usize,
-            ))))
-        };
+        let current_compaction_worker_threads = compaction_worker_threads.load(Relaxed);
+        let compaction_executor = Self::new_compaction_executor(current_compaction_worker_threads);
         Self {
             options,
             write_conflict_detector,
@@ -100,6 +100,28 @@ impl SharedBufferUploader {
             next_local_sstable_id: Arc::new(AtomicU64::new(0)),
             stats,
             compaction_executor,
+            compaction_worker_threads,
+            current_compaction_worker_threads,
+        }
+    }
+
+    fn new_compaction_executor(worker_threads: u32) -> Option<Arc<CompactionExecutor>> {
+        if worker_threads == 0 {
+            None
+        } else {
+            Some(Arc::new(CompactionExecutor::new(Some(
+                worker_threads as usize,
+            ))))
+        }
+    }
+
+    /// Rebuilds the compaction executor if its worker thread count has been changed. Called
+    /// between upload items, so that no flush is running on the executor being replaced.
+    fn refresh_compaction_executor(&mut self) {
+        let worker_threads = self.compaction_worker_threads.load(Relaxed);
+        if worker_threads != self.current_compaction_worker_threads {
+            self.compaction_executor = Self::new_compaction_executor(worker_threads);
+            self.current_compaction_worker_threads = worker_threads;
         }
     }
 
@@ -116,6 +138,7 @@ impl SharedBufferUploader {
 impl SharedBufferUploader {
     async fn run_inner(&mut self) -> HummockResult<()> {
         while let Some(item) = self.uploader_rx.recv().await {
+            self.refresh_compaction_executor();
             let mut task_results = BTreeMap::new();
             let mut failed = false;
             for UploadTask {
@@ -29,6 +29,8 @@ use crate::hummock::{HummockError, HummockResult};
 pub const DEFAULT_BLOCK_SIZE: usize = 4 * 1024;
 pub const DEFAULT_RESTART_INTERVAL: usize = 16;
 pub const DEFAULT_ENTRY_SIZE: usize = 16;
+/// Compression level of LZ4 and Zstd if not specified.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 4;
 
 pub struct Block {
     /// Uncompressed entries data.
@@ -39,6 +41,13 @@ pub struct Block {
 
 impl Block {
     pub fn decode(buf: Bytes) -> HummockResult<Self> {
+        if buf.len() < 9 {
+            return Err(HummockError::decode_error(format!(
+                "block of {} bytes is too short",
+                buf.len()
+            )));
+        }
+
         // Verify checksum.
         let xxhash64_checksum = (&buf[buf.len() - 8..]).get_u64_le();
         xxhash64_verify(&buf[..buf.len() - 8], xxhash64_checksum)?;
@@ -48,14 +57,16 @@ impl Block {
         let buf = match compression {
             CompressionAlgorithm::None => buf.slice(..buf.len() - 9),
             CompressionAlgorithm::Lz4 => {
-                let mut decoder = Decoder::new(buf.reader())
-                    .map_err(HummockError::decode_error)
-                    .unwrap();
+                let mut decoder = Decoder::new(buf.reader()).map_err(HummockError::decode_error)?;
                 let mut decoded = Vec::with_capacity(DEFAULT_BLOCK_SIZE);
                 decoder
                     .read_to_end(&mut decoded)
-                    .map_err(HummockError::decode_error)
-                    .unwrap();
+                    .map_err(HummockError::decode_error)?;
+                Bytes::from(decoded)
+            }
+            CompressionAlgorithm::Zstd => {
+                let decoded = zstd::decode_all(buf.slice(..buf.len() - 9).reader())
+                    .map_err(HummockError::decode_error)?;
                 Bytes::from(decoded)
             }
         };
@@ -175,6 +186,8 @@ pub struct BlockBuilderOptions {
     pub capacity: usize,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Compression level, whose range depends on the compression algorithm.
+    pub compression_level: i32,
     /// Restart point interval.
     pub restart_interval: usize,
 }
@@ -184,6 +197,7 @@ impl Default for BlockBuilderOptions {
         Self {
             capacity: DEFAULT_BLOCK_SIZE,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
             restart_interval: DEFAULT_RESTART_INTERVAL,
         }
     }
@@ -203,6 +217,8 @@ pub struct BlockBuilder {
     entry_count: usize,
     /// Compression algorithm.
     compression_algorithm: CompressionAlgorithm,
+    /// Compression level.
+    compression_level: i32,
 }
 
 impl BlockBuilder {
@@ -216,6 +232,7 @@ impl BlockBuilder {
             last_key: vec![],
             entry_count: 0,
             compression_algorithm: options.compression_algorithm,
+            compression_level: options.compression_level,
         }
     }
 
@@ -286,7 +303,7 @@ impl BlockBuilder {
             CompressionAlgorithm::None => self.buf,
             CompressionAlgorithm::Lz4 => {
                 let mut encoder = lz4::EncoderBuilder::new()
-                    .level(4)
+                    .level(self.compression_level.max(0) as u32)
                     .build(BytesMut::with_capacity(self.buf.len()).writer())
                     .map_err(HummockError::encode_error)
                     .unwrap();
@@ -298,6 +315,23 @@ impl BlockBuilder {
                 result.map_err(HummockError::encode_error).unwrap();
                 writer.into_inner()
             }
+            CompressionAlgorithm::Zstd => {
+                let mut encoder = zstd::Encoder::new(
+                    BytesMut::with_capacity(self.buf.len()).writer(),
+                    self.compression_level,
+                )
+                .map_err(HummockError::encode_error)
+                .unwrap();
+                encoder
+                    .write_all(&self.buf[..])
+                    .map_err(HummockError::encode_error)
+                    .unwrap();
+                let writer = encoder
+                    .finish()
+                    .map_err(HummockError::encode_error)
+                    .unwrap();
+                writer.into_inner()
+            }
         };
         self.compression_algorithm.encode(&mut buf);
         let checksum = xxhash64_checksum(&buf);
@@ -354,8 +388,13 @@ mod tests {
 
     #[test]
     fn test_compressed_block_enc_dec() {
+        inner_test_compressed(CompressionAlgorithm::Lz4);
+        inner_test_compressed(CompressionAlgorithm::Zstd);
+    }
+
+    fn inner_test_compressed(algo: CompressionAlgorithm) {
         let options = BlockBuilderOptions {
-            compression_algorithm: CompressionAlgorithm::Lz4,
+            compression_algorithm: algo,
             ..Default::default()
         };
         let mut builder = BlockBuilder::new(options);
@@ -24,9 +24,10 @@ use super::bloom::Bloom;
 use super::utils::CompressionAlgorithm;
 use super::{
     BlockBuilder, BlockBuilderOptions, BlockMeta, SstableMeta, DEFAULT_BLOCK_SIZE,
-    DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    DEFAULT_COMPRESSION_LEVEL, DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
 };
 use crate::hummock::value::HummockValue;
+use crate::hummock::{HummockError, HummockResult};
 
 pub const DEFAULT_SSTABLE_SIZE: usize = 4 * 1024 * 1024;
 pub const DEFAULT_BLOOM_FALSE_POSITIVE: f64 = 0.1;
@@ -43,18 +44,22 @@ pub struct SSTableBuilderOptions {
     pub bloom_false_positive: f64,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Compression level.
+    pub compression_level: i32,
 }
 
-impl From<&StorageConfig> for SSTableBuilderOptions {
-    fn from(options: &StorageConfig) -> SSTableBuilderOptions {
-        SSTableBuilderOptions {
+impl TryFrom<&StorageConfig> for SSTableBuilderOptions {
+    type Error = HummockError;
+
+    fn try_from(options: &StorageConfig) -> HummockResult<SSTableBuilderOptions> {
+        Ok(SSTableBuilderOptions {
             capacity: (options.sstable_size_mb as usize) * (1 << 20),
             block_capacity: (options.block_size_kb as usize) * (1 << 10),
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: options.bloom_false_positive,
-            // TODO: Make this configurable.
-            compression_algorithm: CompressionAlgorithm::None,
-        }
+            compression_algorithm: options.compression_algorithm.parse()?,
+            compression_level: options.compression_level,
+        })
     }
 }
 
@@ -66,6 +71,7 @@ impl Default for SSTableBuilderOptions {
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: DEFAULT_BLOOM_FALSE_POSITIVE,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
         }
     }
 }
@@ -111,6 +117,7 @@ impl SSTableBuilder {
                 capacity: self.options.capacity,
                 restart_interval: self.options.restart_interval,
                 compression_algorithm: self.options.compression_algorithm,
+                compression_level: self.options.compression_level,
             }));
             self.block_metas.push(BlockMeta {
                 offset: self.buf.len() as u32,
@@ -246,6 +253,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: 0.1,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
         };
 
         let b = SSTableBuilder::new(opt);
@@ -276,6 +284,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: if with_blooms { 0.01 } else { 0.0 },
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
         };
 
         // build remote table
@@ -183,7 +183,9 @@ mod tests {
 
     use super::*;
     use crate::hummock::sstable::utils::CompressionAlgorithm;
-    use crate::hummock::{SSTableBuilderOptions, DEFAULT_RESTART_INTERVAL};
+    use crate::hummock::{
+        SSTableBuilderOptions, DEFAULT_COMPRESSION_LEVEL, DEFAULT_RESTART_INTERVAL,
+    };
 
     #[tokio::test]
     async fn test_compaction_group_grouping() {
@@ -199,6 +201,7 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_level: DEFAULT_COMPRESSION_LEVEL,
                 }),
             ))
         };
@@ -178,6 +178,12 @@ impl SstableMeta {
     }
 
     pub fn decode(buf: &mut &[u8]) -> HummockResult<Self> {
+        if buf.len() < 16 {
+            return Err(HummockError::decode_error(format!(
+                "meta of {} bytes is too short",
+                buf.len()
+            )));
+        }
         let mut cursor = buf.len();
 
         cursor -= 4;
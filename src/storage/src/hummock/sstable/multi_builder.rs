@@ -146,7 +146,9 @@ mod tests {
     use super::*;
     use crate::hummock::sstable::utils::CompressionAlgorithm;
     use crate::hummock::test_utils::default_builder_opt_for_test;
-    use crate::hummock::{SSTableBuilderOptions, DEFAULT_RESTART_INTERVAL};
+    use crate::hummock::{
+        SSTableBuilderOptions, DEFAULT_COMPRESSION_LEVEL, DEFAULT_RESTART_INTERVAL,
+    };
 
     #[tokio::test]
     async fn test_empty() {
@@ -162,6 +164,7 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_level: DEFAULT_COMPRESSION_LEVEL,
                 }),
             ))
         };
@@ -185,6 +188,7 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    compression_level: DEFAULT_COMPRESSION_LEVEL,
                 }),
             ))
         };
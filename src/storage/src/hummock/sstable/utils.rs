@@ -17,6 +17,7 @@
 use std::cmp::{self};
 use std::hash::Hasher;
 use std::ptr;
+use std::str::FromStr;
 
 use serde::Deserialize;
 
@@ -154,6 +155,7 @@ pub fn get_length_prefixed_slice(buf: &mut &[u8]) -> Vec<u8> {
 pub enum CompressionAlgorithm {
     None,
     Lz4,
+    Zstd,
 }
 
 impl CompressionAlgorithm {
@@ -161,6 +163,7 @@ impl CompressionAlgorithm {
         let v = match self {
             Self::None => 0,
             Self::Lz4 => 1,
+            Self::Zstd => 2,
         };
         buf.put_u8(v);
     }
@@ -169,6 +172,7 @@ impl CompressionAlgorithm {
         match buf.get_u8() {
             0 => Ok(Self::None),
             1 => Ok(Self::Lz4),
+            2 => Ok(Self::Zstd),
             _ => Err(HummockError::decode_error(
                 "not valid compression algorithm",
             )),
@@ -181,6 +185,7 @@ impl From<CompressionAlgorithm> for u8 {
         match ca {
             CompressionAlgorithm::None => 0,
             CompressionAlgorithm::Lz4 => 1,
+            CompressionAlgorithm::Zstd => 2,
         }
     }
 }
@@ -190,6 +195,7 @@ impl From<CompressionAlgorithm> for u64 {
         match ca {
             CompressionAlgorithm::None => 0,
             CompressionAlgorithm::Lz4 => 1,
+            CompressionAlgorithm::Zstd => 2,
         }
     }
 }
@@ -201,9 +207,26 @@ impl TryFrom<u8> for CompressionAlgorithm {
         match v {
             0 => Ok(Self::None),
             1 => Ok(Self::Lz4),
+            2 => Ok(Self::Zstd),
             _ => Err(HummockError::decode_error(
                 "not valid compression algorithm",
             )),
         }
     }
 }
+
+impl FromStr for CompressionAlgorithm {
+    type Err = HummockError;
+
+    fn from_str(s: &str) -> HummockResult<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "lz4" => Ok(Self::Lz4),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(HummockError::other(format!(
+                "unknown compression algorithm: {}",
+                s
+            ))),
+        }
+    }
+}
@@ -172,7 +172,7 @@ impl SstableStore {
         block_idx: u64,
         block_data: Bytes,
     ) -> HummockResult<()> {
-        let block = Box::new(Block::decode(block_data)?);
+        let block = Box::new(Self::decode_block(sst_id, block_idx, block_data)?);
         self.block_cache.insert(sst_id, block_idx, block);
         Ok(())
     }
@@ -204,7 +204,11 @@ impl SstableStore {
             .read(&data_path, Some(block_loc))
             .await
             .map_err(HummockError::object_io_error)?;
-        let block = Block::decode(block_data.slice(..block_meta.len as usize))?;
+        let block = Self::decode_block(
+            sst.id,
+            block_index as u64,
+            block_data.slice(..block_meta.len as usize),
+        )?;
         let ret = self
             .block_cache
             .insert(sst.id, block_index as u64, Box::new(block));
@@ -213,7 +217,8 @@ impl SstableStore {
             let mut offset = block_meta.len as usize;
             for block_meta in &sst.meta.block_metas[(block_index + 1)..end_index] {
                 let end_offset = offset + block_meta.len as usize;
-                let block = Block::decode(block_data.slice(offset..end_offset))?;
+                let block =
+                    Self::decode_block(sst.id, index_offset, block_data.slice(offset..end_offset))?;
                 self.block_cache
                     .insert(sst.id, index_offset, Box::new(block));
                 offset = end_offset;
@@ -248,7 +253,7 @@ impl SstableStore {
                 .read(&data_path, Some(block_loc))
                 .await
                 .map_err(HummockError::object_io_error)?;
-            let block = Block::decode(block_data)?;
+            let block = Self::decode_block(sst.id, block_index, block_data)?;
             Ok(Box::new(block))
         };
 
@@ -291,7 +296,7 @@ impl SstableStore {
                         .await
                         .map_err(HummockError::object_io_error)?;
                     let size = buf.len();
-                    let meta = SstableMeta::decode(&mut &buf[..])?;
+                    let meta = Self::decode_meta(id, &buf)?;
                     let sst = Box::new(Sstable { id, meta });
                     Ok((sst, size))
                 });
@@ -319,7 +324,7 @@ impl SstableStore {
                     .await
                     .map_err(HummockError::object_io_error)?;
                 let size = buf.len();
-                let meta = SstableMeta::decode(&mut &buf[..])?;
+                let meta = Self::decode_meta(sst_id, &buf)?;
                 let sst = Box::new(Sstable { id: sst_id, meta });
                 Ok((sst, size))
             })
@@ -327,6 +332,58 @@ impl SstableStore {
         Ok(entry)
     }
 
+    /// Reads the meta and all the blocks of an SST from the object store, bypassing the caches,
+    /// and verifies their checksums.
+    pub async fn verify_sstable(&self, sst_id: HummockSSTableId) -> HummockResult<()> {
+        let meta_data = self
+            .store
+            .read(&self.get_sst_meta_path(sst_id), None)
+            .await
+            .map_err(HummockError::object_io_error)?;
+        let meta = Self::decode_meta(sst_id, &meta_data)?;
+        let data = self
+            .store
+            .read(&self.get_sst_data_path(sst_id), None)
+            .await
+            .map_err(HummockError::object_io_error)?;
+        for (block_index, block_meta) in meta.block_metas.iter().enumerate() {
+            let start_offset = block_meta.offset as usize;
+            let end_offset = start_offset + block_meta.len as usize;
+            if end_offset > data.len() {
+                return Err(HummockError::corrupted_sstable(
+                    sst_id,
+                    format!(
+                        "block {} ends at {} beyond the data of {} bytes",
+                        block_index,
+                        end_offset,
+                        data.len()
+                    ),
+                ));
+            }
+            Self::decode_block(
+                sst_id,
+                block_index as u64,
+                data.slice(start_offset..end_offset),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn decode_block(
+        sst_id: HummockSSTableId,
+        block_index: u64,
+        block_data: Bytes,
+    ) -> HummockResult<Block> {
+        Block::decode(block_data).map_err(|e| {
+            HummockError::corrupted_sstable(sst_id, format!("block {}: {}", block_index, e))
+        })
+    }
+
+    fn decode_meta(sst_id: HummockSSTableId, mut buf: &[u8]) -> HummockResult<SstableMeta> {
+        SstableMeta::decode(&mut buf)
+            .map_err(|e| HummockError::corrupted_sstable(sst_id, format!("meta: {}", e)))
+    }
+
     pub fn get_sst_meta_path(&self, sst_id: HummockSSTableId) -> String {
         let mut ret = format!("{}/{}.meta", self.path, sst_id);
         if !is_remote_sst_id(sst_id) {
@@ -362,3 +419,56 @@ impl SstableStore {
 }
 
 pub type SstableStoreRef = Arc<SstableStore>;
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::hummock::iterator::test_utils::mock_sstable_store;
+    use crate::hummock::test_utils::{default_builder_opt_for_test, gen_default_test_sstable};
+
+    #[tokio::test]
+    async fn test_verify_corrupted_sstable() {
+        let sstable_store = mock_sstable_store();
+        let sst =
+            gen_default_test_sstable(default_builder_opt_for_test(), 1, sstable_store.clone())
+                .await;
+        sstable_store.verify_sstable(sst.id).await.unwrap();
+
+        // Flip a byte in the first block.
+        let data_path = sstable_store.get_sst_data_path(sst.id);
+        let data = sstable_store.store().read(&data_path, None).await.unwrap();
+        let mut corrupted = BytesMut::from(&data[..]);
+        corrupted[sst.meta.block_metas[0].offset as usize] ^= 1;
+        sstable_store
+            .store()
+            .upload(&data_path, corrupted.freeze())
+            .await
+            .unwrap();
+
+        let err = sstable_store.verify_sstable(sst.id).await.unwrap_err();
+        assert!(err.to_string().starts_with("Corrupted SST 1: block 0"));
+        let err = sstable_store
+            .get(
+                &sst,
+                0,
+                CachePolicy::Disable,
+                &mut StoreLocalStatistic::default(),
+            )
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("Corrupted SST 1: block 0"));
+
+        // Truncate the meta.
+        let meta_path = sstable_store.get_sst_meta_path(sst.id);
+        sstable_store
+            .store()
+            .upload(&meta_path, Bytes::from_static(b"meta"))
+            .await
+            .unwrap();
+        let err = sstable_store.verify_sstable(sst.id).await.unwrap_err();
+        assert!(err.to_string().starts_with("Corrupted SST 1: meta"));
+    }
+}
@@ -19,7 +19,7 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use itertools::Itertools;
-use risingwave_hummock_sdk::key::key_with_epoch;
+use risingwave_hummock_sdk::key::{key_with_epoch, next_key};
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::VNodeBitmap;
 use risingwave_pb::hummock::SstableInfo;
@@ -28,7 +28,9 @@ use super::iterator::{
     BackwardUserIterator, ConcatIteratorInner, DirectedUserIterator, UserIterator,
 };
 use super::utils::{can_concat, search_sst_idx, validate_epoch};
-use super::{BackwardSSTableIterator, HummockStorage, SSTableIterator, SSTableIteratorType};
+use super::{
+    BackwardSSTableIterator, HummockStorage, SSTableIterator, SSTableIteratorType, Sstable,
+};
 use crate::error::StorageResult;
 use crate::hummock::iterator::{
     Backward, BoxedHummockIterator, DirectedUserIteratorBuilder, DirectionEnum, Forward,
@@ -44,6 +46,38 @@ use crate::storage_value::StorageValue;
 use crate::store::*;
 use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
 
+/// Returns the key if `key_range` contains a single user key, in which case the bloom filters can
+/// be used to skip the SSTs in a scan.
+fn point_key<R, B>(key_range: &R) -> Option<&[u8]>
+where
+    R: RangeBounds<B>,
+    B: AsRef<[u8]>,
+{
+    match (key_range.start_bound(), key_range.end_bound()) {
+        (Included(start), Included(end)) if start.as_ref() == end.as_ref() => Some(start.as_ref()),
+        _ => None,
+    }
+}
+
+/// Checks the bloom filter of `table` if the scan is on a single key.
+fn surely_not_have_point_key(
+    table: &Sstable,
+    point_key: Option<&[u8]>,
+    stats: &mut StoreLocalStatistic,
+) -> bool {
+    match point_key {
+        Some(key) if table.surely_not_have_user_key(key) => {
+            stats.bloom_filter_true_negative_count += 1;
+            true
+        }
+        Some(_) => {
+            stats.bloom_filter_might_positive_count += 1;
+            false
+        }
+        None => false,
+    }
+}
+
 trait HummockIteratorType {
     type Direction: HummockIteratorDirection;
     type SstableIteratorType: SSTableIteratorType<Direction = Self::Direction>;
@@ -86,6 +120,7 @@ impl HummockStorage {
         let (shared_buffer_data, pinned_version) = self.read_filter(epoch, &key_range, None)?;
 
         let mut stats = StoreLocalStatistic::default();
+        let point_key = point_key(&key_range);
 
         for (replicated_batches, uncommitted_data) in shared_buffer_data {
             for batch in replicated_batches {
@@ -103,6 +138,9 @@ impl HummockStorage {
                             .sstable_store
                             .sstable(table_info.id, &mut stats)
                             .await?;
+                        if surely_not_have_point_key(table.value(), point_key, &mut stats) {
+                            continue;
+                        }
                         overlapped_iters.push(Box::new(T::SstableIteratorType::create(
                             table,
                             self.sstable_store(),
@@ -132,17 +170,22 @@ impl HummockStorage {
                 assert!(start_table_idx < table_infos.len() && end_table_idx < table_infos.len());
                 let matched_table_infos = &table_infos[start_table_idx..=end_table_idx];
 
-                let tables = match T::Direction::direction() {
-                    DirectionEnum::Backward => matched_table_infos
-                        .iter()
-                        .rev()
-                        .map(|&info| info.clone())
-                        .collect_vec(),
-                    DirectionEnum::Forward => matched_table_infos
-                        .iter()
-                        .map(|&info| info.clone())
-                        .collect_vec(),
-                };
+                let mut tables = Vec::with_capacity(matched_table_infos.len());
+                for &info in matched_table_infos {
+                    if point_key.is_some() {
+                        let table = self.sstable_store.sstable(info.id, &mut stats).await?;
+                        if surely_not_have_point_key(table.value(), point_key, &mut stats) {
+                            continue;
+                        }
+                    }
+                    tables.push(info.clone());
+                }
+                if tables.is_empty() {
+                    continue;
+                }
+                if let DirectionEnum::Backward = T::Direction::direction() {
+                    tables.reverse();
+                }
 
                 overlapped_iters.push(Box::new(ConcatIteratorInner::<T::SstableIteratorType>::new(
                     tables,
@@ -155,6 +198,9 @@ impl HummockStorage {
                         .sstable_store
                         .sstable(table_info.id, &mut stats)
                         .await?;
+                    if surely_not_have_point_key(table.value(), point_key, &mut stats) {
+                        continue;
+                    }
                     overlapped_iters.push(Box::new(T::SstableIteratorType::create(
                         table,
                         self.sstable_store(),
@@ -389,6 +435,22 @@ impl StateStore for HummockStorage {
         }
     }
 
+    /// Writes a delete tombstone at `epoch` for each key with the `prefix`, which will be cleaned
+    /// up by compaction.
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            let mut iter = self.iter(prefix.to_vec()..next_key(prefix), epoch).await?;
+            let mut kv_pairs = vec![];
+            while let Some((key, _)) = iter.next().await? {
+                kv_pairs.push((key, StorageValue::new_default_delete()));
+            }
+            if !kv_pairs.is_empty() {
+                self.ingest_batch(kv_pairs, epoch).await?;
+            }
+            Ok(())
+        }
+    }
+
     /// Returns an iterator that scan from the begin key to the end key
     /// The result is based on a snapshot corresponding to the given `epoch`.
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use bytes::{BufMut, Bytes, BytesMut};
+use itertools::Itertools;
 use risingwave_common::hash::VNODE_BITMAP_LEN;
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_meta::hummock::test_utils::setup_compute_env;
@@ -256,6 +257,153 @@ async fn test_vnode_filter() {
     assert!(value_with_mismatch_dummy_filter.is_none());
 }
 
+#[tokio::test]
+async fn test_point_scan() {
+    let sstable_store = mock_sstable_store();
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let stats = Arc::new(StateStoreMetrics::unused());
+    let storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        meta_client.clone(),
+        stats.clone(),
+    )
+    .await
+    .unwrap();
+
+    let batch = ["aa", "bb", "cc"]
+        .into_iter()
+        .map(|key| {
+            (
+                Bytes::from(key),
+                StorageValue::new_default_put(Bytes::from("value")),
+            )
+        })
+        .collect_vec();
+    let epoch: u64 = 1;
+    storage.ingest_batch(batch, epoch).await.unwrap();
+    storage.sync(Some(epoch)).await.unwrap();
+    meta_client
+        .commit_epoch(
+            epoch,
+            storage.local_version_manager.get_uncommitted_ssts(epoch),
+        )
+        .await
+        .unwrap();
+
+    let counters = || {
+        (
+            stats.bloom_filter_true_negative_counts.get(),
+            stats.bloom_filter_might_positive_counts.get(),
+            stats
+                .sst_store_block_request_counts
+                .with_label_values(&["data_total"])
+                .get(),
+        )
+    };
+
+    // A scan on a single key must still see the existing keys.
+    for key in ["aa", "bb", "cc"] {
+        let key = key.as_bytes().to_vec();
+        let mut iter = storage.iter(key.clone()..=key, epoch).await.unwrap();
+        assert_eq!(count_iter(&mut iter).await, 1);
+    }
+    let (_, might_positive, _) = counters();
+    assert!(might_positive > 0);
+
+    // A scan on a missing key skips the SSTs whose bloom filters rule the key out, without
+    // reading any of their blocks. Some of the keys may be false positives of the filters.
+    let mut pruned_scans = 0;
+    for i in 0..20 {
+        let key = format!("missing-{}", i).into_bytes();
+        let (true_negative, might_positive, data_blocks) = counters();
+        let mut iter = storage.iter(key.clone()..=key, epoch).await.unwrap();
+        assert_eq!(count_iter(&mut iter).await, 0);
+        drop(iter);
+        let (new_true_negative, new_might_positive, new_data_blocks) = counters();
+        if new_might_positive == might_positive {
+            assert!(new_true_negative > true_negative);
+            assert_eq!(new_data_blocks, data_blocks);
+            pruned_scans += 1;
+        }
+    }
+    assert!(pruned_scans > 0);
+}
+
+#[tokio::test]
+async fn test_snapshot_read() {
+    let sstable_store = mock_sstable_store();
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let epoch1: u64 = 1;
+    let batch1 = vec![
+        (Bytes::from("aa"), StorageValue::new_default_put("v1")),
+        (Bytes::from("bb"), StorageValue::new_default_put("v1")),
+    ];
+    storage.ingest_batch(batch1, epoch1).await.unwrap();
+    storage.sync(Some(epoch1)).await.unwrap();
+    meta_client
+        .commit_epoch(
+            epoch1,
+            storage.local_version_manager.get_uncommitted_ssts(epoch1),
+        )
+        .await
+        .unwrap();
+    storage.wait_epoch(epoch1).await.unwrap();
+
+    // A reader of the committed epoch doesn't see the writes of newer epochs, no matter whether
+    // they are committed.
+    let epoch2 = epoch1 + 1;
+    let batch2 = vec![
+        (Bytes::from("aa"), StorageValue::new_default_put("v2")),
+        (Bytes::from("bb"), StorageValue::new_default_delete()),
+    ];
+    storage.ingest_batch(batch2, epoch2).await.unwrap();
+    for commit in [false, true] {
+        if commit {
+            storage.sync(Some(epoch2)).await.unwrap();
+            meta_client
+                .commit_epoch(
+                    epoch2,
+                    storage.local_version_manager.get_uncommitted_ssts(epoch2),
+                )
+                .await
+                .unwrap();
+            storage.wait_epoch(epoch2).await.unwrap();
+        }
+        let value = storage.get(b"aa", epoch1).await.unwrap();
+        assert_eq!(value, Some(Bytes::from("v1")));
+        let value = storage.get(b"bb", epoch1).await.unwrap();
+        assert_eq!(value, Some(Bytes::from("v1")));
+        let mut iter = storage.iter(..=b"bb".to_vec(), epoch1).await.unwrap();
+        assert_eq!(count_iter(&mut iter).await, 2);
+    }
+
+    let value = storage.get(b"aa", epoch2).await.unwrap();
+    assert_eq!(value, Some(Bytes::from("v2")));
+    assert!(storage.get(b"bb", epoch2).await.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn test_state_store_sync() {
     let sstable_store = mock_sstable_store();
@@ -25,7 +25,9 @@ use risingwave_meta::hummock::test_utils::setup_compute_env;
 use risingwave_meta::hummock::MockHummockMetaClient;
 use risingwave_pb::common::VNodeBitmap;
 
-use super::{CompressionAlgorithm, SstableMeta, DEFAULT_RESTART_INTERVAL};
+use super::{
+    CompressionAlgorithm, SstableMeta, DEFAULT_COMPRESSION_LEVEL, DEFAULT_RESTART_INTERVAL,
+};
 use crate::hummock::iterator::test_utils::mock_sstable_store;
 use crate::hummock::value::HummockValue;
 use crate::hummock::{
@@ -40,9 +42,13 @@ pub fn default_config_for_test() -> StorageConfig {
         sstable_size_mb: 256,
         block_size_kb: 64,
         bloom_false_positive: 0.1,
+        compression_algorithm: "none".to_string(),
+        compression_level: 4,
         share_buffers_sync_parallelism: 2,
         share_buffer_compaction_worker_threads_number: 1,
+        shared_buffer_threshold_mb: 48,
         shared_buffer_capacity_mb: 64,
+        write_stall_l0_sst_count: 0,
         data_directory: "hummock_001".to_string(),
         write_conflict_detection_enabled: true,
         block_cache_capacity_mb: 64,
@@ -81,6 +87,7 @@ pub fn default_builder_opt_for_test() -> SSTableBuilderOptions {
         restart_interval: DEFAULT_RESTART_INTERVAL,
         bloom_false_positive: 0.1,
         compression_algorithm: CompressionAlgorithm::None,
+        compression_level: DEFAULT_COMPRESSION_LEVEL,
     }
 }
 
@@ -13,12 +13,18 @@
 // limitations under the License.
 
 use std::future::Future;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::RangeBounds;
 
 use bytes::{BufMut, Bytes, BytesMut};
-use risingwave_common::catalog::TableId;
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{ColumnDesc, TableId};
+use risingwave_common::util::ordered::{serialize_column_id, SENTINEL_CELL_ID};
 use risingwave_hummock_sdk::key::next_key;
 
-use crate::error::StorageResult;
+use crate::cell_based_row_deserializer::CellBasedRowDeserializer;
+use crate::error::{StorageError, StorageResult};
+use crate::write_batch::{KeySpaceWriteBatch, WriteBatch};
 use crate::{StateStore, StateStoreIter};
 
 /// Provides API to read key-value pairs of a prefix in the storage backend.
@@ -114,6 +120,47 @@ impl<S: StateStore> Keyspace<S> {
         self.store.get(&self.prefixed_key(key), epoch).await
     }
 
+    /// Gets a row of cell-based encoding, i.e., `[pk | column_id] -> cell`, by point-getting its
+    /// cells instead of scanning the prefix of `pk`, so that the bloom filters of the state store
+    /// can be used. `pk` is the serialized primary key of the row, and `column_descs` are the
+    /// columns to get. Returns `None` if the sentinel cell of the row doesn't exist.
+    pub async fn get_row(
+        &self,
+        pk: &[u8],
+        column_descs: &[ColumnDesc],
+        epoch: u64,
+    ) -> StorageResult<Option<Row>> {
+        let sentinel_key = [
+            pk,
+            &serialize_column_id(&SENTINEL_CELL_ID).map_err(StorageError::Keyspace)?,
+        ]
+        .concat();
+        let sentinel_cell = match self.get(&sentinel_key, epoch).await? {
+            Some(cell) => cell,
+            // The row doesn't exist if its sentinel cell doesn't.
+            None => return Ok(None),
+        };
+
+        let mut deserializer = CellBasedRowDeserializer::new(column_descs.to_vec());
+        deserializer
+            .deserialize(&Bytes::from(sentinel_key), &sentinel_cell)
+            .map_err(StorageError::Keyspace)?;
+        for column_desc in column_descs {
+            let key = [
+                pk,
+                &serialize_column_id(&column_desc.column_id).map_err(StorageError::Keyspace)?,
+            ]
+            .concat();
+            if let Some(cell) = self.get(&key, epoch).await? {
+                let prev_row = deserializer
+                    .deserialize(&Bytes::from(key), &cell)
+                    .map_err(StorageError::Keyspace)?;
+                assert!(prev_row.is_none());
+            }
+        }
+        Ok(deserializer.take().map(|(_pk, row)| row))
+    }
+
     /// Scans `limit` keys from the keyspace using an inclusive `start_key` and get their values. If
     /// `limit` is None, all keys of the given prefix will be scanned. Note that the prefix of this
     /// keyspace will be stripped. The returned values are based on a snapshot corresponding to
@@ -165,6 +212,45 @@ impl<S: StateStore> Keyspace<S> {
         Ok(strip_prefix_iterator)
     }
 
+    /// Gets an iterator of the keys within `key_range`, which doesn't contain the prefix of this
+    /// keyspace. An unbounded side of the range is bounded by the prefix.
+    pub async fn iter_with_range<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+    ) -> StorageResult<StripPrefixIterator<S::Iter>>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        let start: Bound<Vec<u8>> = match key_range.start_bound() {
+            Included(key) => Included(self.prefixed_key(key)),
+            Excluded(key) => Excluded(self.prefixed_key(key)),
+            Unbounded => Included(self.prefix.to_owned()),
+        };
+        let end: Bound<Vec<u8>> = match key_range.end_bound() {
+            Included(key) => Included(self.prefixed_key(key)),
+            Excluded(key) => Excluded(self.prefixed_key(key)),
+            Unbounded => Excluded(next_key(self.prefix.as_slice())),
+        };
+        let iter = self.store.iter((start, end), epoch).await?;
+        Ok(StripPrefixIterator {
+            iter,
+            prefix_len: self.prefix.len(),
+        })
+    }
+
+    /// Deletes all keys of the keyspace at the given `epoch`.
+    pub async fn delete_all(&self, epoch: u64) -> StorageResult<()> {
+        self.store.delete_range(&self.prefix, epoch).await
+    }
+
+    /// Creates a write batch of the underlying state store, which prefixes the keys written with
+    /// this keyspace. Use [`WriteBatch::prefixify`] instead for a batch shared by keyspaces.
+    pub fn start_write_batch(&self) -> KeySpaceWriteBatch<'_, S, WriteBatch<S>> {
+        KeySpaceWriteBatch::new(self)
+    }
+
     /// Gets the underlying state store.
     pub fn state_store(&self) -> S {
         self.store.clone()
@@ -195,6 +195,16 @@ impl StateStore for MemoryStateStore {
         async move { unimplemented!() }
     }
 
+    /// Removes all versions of the keys directly, since the in-memory state store never does GC.
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], _epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            self.inner
+                .write()
+                .retain(|(key, _), _| !key.starts_with(prefix));
+            Ok(())
+        }
+    }
+
     fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -321,4 +331,25 @@ mod tests {
         assert_eq!(state_store.get(b"b", 1).await.unwrap(), None);
         assert_eq!(state_store.get(b"c", 1).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_delete_range() {
+        let state_store = MemoryStateStore::new();
+        let kv_pairs = ["a1", "a2", "b1"]
+            .into_iter()
+            .map(|key| {
+                (
+                    Bytes::from(key),
+                    StorageValue::new_default_put(b"v".to_vec()),
+                )
+            })
+            .collect();
+        state_store.ingest_batch(kv_pairs, 0).await.unwrap();
+
+        state_store.delete_range(b"a", 1).await.unwrap();
+        assert_eq!(
+            state_store.scan("a"..="c", None, 1).await.unwrap(),
+            vec![(b"b1".to_vec().into(), b"v".to_vec().into())]
+        );
+    }
 }
@@ -31,7 +31,7 @@ pub struct StoreLocalStatistic {
 
 impl StoreLocalStatistic {
     pub fn add(&mut self, other: &StoreLocalStatistic) {
-        self.cache_meta_block_miss += other.cache_data_block_miss;
+        self.cache_meta_block_miss += other.cache_meta_block_miss;
         self.cache_meta_block_total += other.cache_meta_block_total;
 
         self.cache_data_block_miss += other.cache_data_block_miss;
@@ -66,13 +66,20 @@ impl StoreLocalStatistic {
                 .inc_by(self.cache_meta_block_total);
         }
 
-        if self.cache_data_block_miss > 0 {
+        if self.cache_meta_block_miss > 0 {
             metrics
                 .sst_store_block_request_counts
                 .with_label_values(&["meta_miss"])
                 .inc_by(self.cache_meta_block_miss);
         }
 
+        if self.scan_key_count > 0 {
+            metrics
+                .iter_scan_key_counts
+                .with_label_values(&["scanned"])
+                .inc_by(self.scan_key_count);
+        }
+
         if self.bloom_filter_true_negative_count > 0 {
             metrics
                 .bloom_filter_true_negative_counts
@@ -55,11 +55,18 @@ where
     where
         I: Future<Output = StorageResult<S::Iter>>,
     {
+        let timer = self.stats.iter_duration.start_timer();
         let iter = iter
             .await
             .inspect_err(|e| error!("Failed in iter: {:?}", e))?;
-
-        let monitored = MonitoredStateStoreIter { inner: iter };
+        timer.observe_duration();
+
+        let monitored = MonitoredStateStoreIter {
+            inner: iter,
+            total_items: 0,
+            total_size: 0,
+            stats: self.stats.clone(),
+        };
         Ok(monitored)
     }
 
@@ -117,6 +124,10 @@ where
             self.stats
                 .range_scan_size
                 .observe(result.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>() as _);
+            self.stats
+                .iter_scan_key_counts
+                .with_label_values(&["returned"])
+                .inc_by(result.len() as _);
 
             Ok(result)
         }
@@ -136,7 +147,7 @@ where
             let timer = self.stats.range_backward_scan_duration.start_timer();
             let result = self
                 .inner
-                .scan(key_range, limit, epoch)
+                .backward_scan(key_range, limit, epoch)
                 .await
                 .inspect_err(|e| error!("Failed in backward_scan: {:?}", e))?;
             timer.observe_duration();
@@ -144,6 +155,10 @@ where
             self.stats
                 .range_backward_scan_size
                 .observe(result.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>() as _);
+            self.stats
+                .iter_scan_key_counts
+                .with_label_values(&["returned"])
+                .inc_by(result.len() as _);
 
             Ok(result)
         }
@@ -232,6 +247,15 @@ where
         }
     }
 
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            self.inner
+                .delete_range(prefix, epoch)
+                .await
+                .inspect_err(|e| error!("Failed in delete_range: {:?}", e))
+        }
+    }
+
     fn get_uncommitted_ssts(&self, epoch: u64) -> Vec<SstableInfo> {
         self.inner.get_uncommitted_ssts(epoch)
     }
@@ -240,6 +264,14 @@ where
 /// A state store iterator wrapper for monitoring metrics.
 pub struct MonitoredStateStoreIter<I> {
     inner: I,
+
+    /// The number of the key-value pairs returned so far.
+    total_items: usize,
+
+    /// The bytes of the key-value pairs returned so far.
+    total_size: usize,
+
+    stats: Arc<StateStoreMetrics>,
 }
 
 impl<I> StateStoreIter for MonitoredStateStoreIter<I>
@@ -259,7 +291,22 @@ where
                 .await
                 .inspect_err(|e| error!("Failed in next: {:?}", e))?;
 
+            if let Some((key, value)) = pair.as_ref() {
+                self.total_items += 1;
+                self.total_size += key.len() + value.len();
+            }
+
             Ok(pair)
         }
     }
 }
+
+impl<I> Drop for MonitoredStateStoreIter<I> {
+    fn drop(&mut self) {
+        self.stats.iter_size.observe(self.total_size as _);
+        self.stats
+            .iter_scan_key_counts
+            .with_label_values(&["returned"])
+            .inc_by(self.total_items as _);
+    }
+}
@@ -52,6 +52,9 @@ macro_rules! for_all_metrics {
 
             iter_merge_sstable_counts: Histogram,
             iter_merge_seek_duration: Histogram,
+            iter_duration: Histogram,
+            iter_size: Histogram,
+            iter_scan_key_counts: GenericCounterVec<AtomicU64>,
 
             sst_store_block_request_counts: GenericCounterVec<AtomicU64>,
 
@@ -233,6 +236,28 @@ impl StateStoreMetrics {
         );
         let iter_merge_seek_duration = register_histogram_with_registry!(opts, registry).unwrap();
 
+        let opts = histogram_opts!(
+            "state_store_iter_duration",
+            "Total time of creating iterators of state store, including seeking to the start key",
+            exponential_buckets(0.0001, 2.0, 21).unwrap() // max 104s
+        );
+        let iter_duration = register_histogram_with_registry!(opts, registry).unwrap();
+
+        let opts = histogram_opts!(
+            "state_store_iter_size",
+            "Total bytes returned by an iterator of state store during its lifetime",
+            exponential_buckets(1.0, 2.0, 25).unwrap() // max 16MB
+        );
+        let iter_size = register_histogram_with_registry!(opts, registry).unwrap();
+
+        let iter_scan_key_counts = register_int_counter_vec_with_registry!(
+            "state_store_iter_scan_key_counts",
+            "Total number of keys read by iterators, where `scanned` includes all the versions and tombstones read from SSTs, and `returned` only the ones returned to the user",
+            &["type"],
+            registry
+        )
+        .unwrap();
+
         // ----- sst store -----
         let sst_store_block_request_counts = register_int_counter_vec_with_registry!(
             "state_store_sst_store_block_request_counts",
@@ -311,6 +336,9 @@ impl StateStoreMetrics {
             write_build_l0_bytes,
             iter_merge_sstable_counts,
             iter_merge_seek_duration,
+            iter_duration,
+            iter_size,
+            iter_scan_key_counts,
             sst_store_block_request_counts,
             shared_buffer_to_l0_duration,
             shared_buffer_to_sstable_size,
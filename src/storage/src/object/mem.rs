@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use bytes::Bytes;
 use fail::fail_point;
@@ -23,11 +24,17 @@ use tokio::sync::Mutex;
 use super::{ObjectError, ObjectResult};
 use crate::object::{strip_path_local, BlockLocation, ObjectMetadata, ObjectStore};
 
+type Objects = Arc<Mutex<HashMap<String, Bytes>>>;
+
+lazy_static::lazy_static! {
+    static ref SHARED_OBJECTS: Objects = Default::default();
+}
+
 /// In-memory object storage, useful for testing.
 #[derive(Default)]
 pub struct InMemObjectStore {
     is_local: bool,
-    objects: Mutex<HashMap<String, Bytes>>,
+    objects: Objects,
 }
 
 #[async_trait::async_trait]
@@ -86,7 +93,16 @@ impl InMemObjectStore {
     pub fn new(is_local: bool) -> Self {
         Self {
             is_local,
-            objects: Mutex::new(HashMap::new()),
+            objects: Default::default(),
+        }
+    }
+
+    /// An object store sharing the objects with all the other shared ones in this process, for the
+    /// nodes of a cluster to run in a single process, e.g., in the simulation tests.
+    pub fn shared(is_local: bool) -> Self {
+        Self {
+            is_local,
+            objects: SHARED_OBJECTS.clone(),
         }
     }
 
@@ -265,6 +265,10 @@ pub async fn parse_object_store(url: &str, is_local: bool) -> Box<dyn ObjectStor
                 .to_owned();
             Box::new(LocalDiskObjectStore::new(path.as_str(), true))
         }
+        memory if memory.starts_with("memory-shared") => {
+            tracing::warn!("You're using Hummock shared in-memory object store. This should only be used in simulation tests.");
+            Box::new(InMemObjectStore::shared(is_local))
+        }
         memory if memory.starts_with("memory") => {
             tracing::warn!("You're using Hummock in-memory object store. This should never be used in benchmarks and production environment.");
             Box::new(InMemObjectStore::new(is_local))
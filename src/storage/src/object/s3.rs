@@ -12,15 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::future::Future;
+use std::ops::Range;
+use std::time::Duration;
+
+use aws_sdk_s3::model::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::{Client, Endpoint, Region};
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::SdkError;
 use fail::fail_point;
 use futures::future::try_join_all;
 use itertools::Itertools;
+use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tokio_retry::RetryIf;
 
 use super::{BlockLocation, ObjectError, ObjectMetadata, ObjectResult};
 use crate::object::{Bytes, ObjectStore};
 
+/// Objects larger than this are uploaded in parts, which are uploaded in parallel and retried
+/// separately.
+const MULTIPART_UPLOAD_THRESHOLD: usize = 16 * 1024 * 1024;
+/// The size of each part but the last one of a multipart upload. S3 requires at least 5 MiB.
+const MULTIPART_UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
+/// Failed requests are retried with exponential backoff for at most this many times.
+const MAX_RETRY_TIMES: usize = 4;
+
 /// Object store with S3 backend
 pub struct S3ObjectStore {
     client: Client,
@@ -33,13 +49,18 @@ impl ObjectStore for S3ObjectStore {
         fail_point!("s3_upload_err", |_| Err(ObjectError::internal(
             "s3 upload error"
         )));
-        self.client
-            .put_object()
-            .bucket(&self.bucket)
-            .body(SdkBody::from(obj).into())
-            .key(path)
-            .send()
-            .await?;
+        if obj.len() > MULTIPART_UPLOAD_THRESHOLD {
+            return self.upload_multipart(path, obj).await;
+        }
+        with_retry(|| {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .body(SdkBody::from(obj.clone()).into())
+                .key(path)
+                .send()
+        })
+        .await?;
         Ok(())
     }
 
@@ -48,20 +69,21 @@ impl ObjectStore for S3ObjectStore {
         fail_point!("s3_read_err", |_| Err(ObjectError::internal(
             "s3 read error"
         )));
-        let req = self.client.get_object().bucket(&self.bucket).key(path);
-
         let range = match block_loc.as_ref() {
             None => None,
             Some(block_location) => block_location.byte_range_specifier(),
         };
 
-        let req = if let Some(range) = range {
-            req.range(range)
-        } else {
-            req
-        };
-
-        let resp = req.send().await?;
+        let resp = with_retry(|| {
+            let req = self.client.get_object().bucket(&self.bucket).key(path);
+            let req = if let Some(range) = range.clone() {
+                req.range(range)
+            } else {
+                req
+            };
+            req.send()
+        })
+        .await?;
         let val = resp.body.collect().await?.into_bytes();
 
         if block_loc.is_some() && block_loc.as_ref().unwrap().size != val.len() {
@@ -88,13 +110,14 @@ impl ObjectStore for S3ObjectStore {
         fail_point!("s3_metadata_err", |_| Err(ObjectError::internal(
             "s3 metadata error"
         )));
-        let resp = self
-            .client
-            .head_object()
-            .bucket(&self.bucket)
-            .key(path)
-            .send()
-            .await?;
+        let resp = with_retry(|| {
+            self.client
+                .head_object()
+                .bucket(&self.bucket)
+                .key(path)
+                .send()
+        })
+        .await?;
         Ok(ObjectMetadata {
             total_size: resp.content_length as usize,
         })
@@ -106,17 +129,99 @@ impl ObjectStore for S3ObjectStore {
         fail_point!("s3_delete_err", |_| Err(ObjectError::internal(
             "s3 delete error"
         )));
-        self.client
-            .delete_object()
-            .bucket(&self.bucket)
-            .key(path)
-            .send()
-            .await?;
+        with_retry(|| {
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(path)
+                .send()
+        })
+        .await?;
         Ok(())
     }
 }
 
 impl S3ObjectStore {
+    /// Uploads a large object in parts. The upload is aborted if any part fails, so that the
+    /// uploaded parts don't linger in the bucket.
+    async fn upload_multipart(&self, path: &str, obj: Bytes) -> ObjectResult<()> {
+        let resp = with_retry(|| {
+            self.client
+                .create_multipart_upload()
+                .bucket(&self.bucket)
+                .key(path)
+                .send()
+        })
+        .await?;
+        let upload_id = resp
+            .upload_id
+            .ok_or_else(|| ObjectError::internal("no upload id of multipart upload"))?;
+
+        let result = self.upload_parts(path, &upload_id, obj).await;
+        if result.is_err() {
+            if let Err(e) = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(path)
+                .upload_id(&upload_id)
+                .send()
+                .await
+            {
+                tracing::warn!("failed to abort multipart upload of {}: {}", path, e);
+            }
+        }
+        result
+    }
+
+    async fn upload_parts(&self, path: &str, upload_id: &str, obj: Bytes) -> ObjectResult<()> {
+        let futures = split_parts(obj.len(), MULTIPART_UPLOAD_PART_SIZE)
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let part = obj.slice(range);
+                // Part numbers start from 1.
+                let part_number = i as i32 + 1;
+                async move {
+                    let resp = with_retry(|| {
+                        self.client
+                            .upload_part()
+                            .bucket(&self.bucket)
+                            .key(path)
+                            .upload_id(upload_id)
+                            .part_number(part_number)
+                            .body(SdkBody::from(part.clone()).into())
+                            .send()
+                    })
+                    .await?;
+                    Ok::<_, ObjectError>(
+                        CompletedPart::builder()
+                            .set_e_tag(resp.e_tag)
+                            .part_number(part_number)
+                            .build(),
+                    )
+                }
+            })
+            .collect_vec();
+        let parts = try_join_all(futures).await?;
+
+        with_retry(|| {
+            self.client
+                .complete_multipart_upload()
+                .bucket(&self.bucket)
+                .key(path)
+                .upload_id(upload_id)
+                .multipart_upload(
+                    CompletedMultipartUpload::builder()
+                        .set_parts(Some(parts.clone()))
+                        .build(),
+                )
+                .send()
+        })
+        .await?;
+        Ok(())
+    }
+
     /// Creates an S3 object store from environment variable.
     ///
     /// See [AWS Docs](https://docs.aws.amazon.com/sdk-for-rust/latest/dg/credentials.html) on how to provide credentials and region from env variable. If you are running compute-node on EC2, no configuration is required.
@@ -153,3 +258,52 @@ impl S3ObjectStore {
         }
     }
 }
+
+/// Splits an object of `len` bytes into the ranges of the parts of a multipart upload.
+fn split_parts(len: usize, part_size: usize) -> Vec<Range<usize>> {
+    (0..len)
+        .step_by(part_size)
+        .map(|start| start..len.min(start + part_size))
+        .collect()
+}
+
+/// Runs an S3 request, and retries it with exponential backoff if it fails transiently, e.g., on
+/// timeouts, network errors, throttling or server errors.
+async fn with_retry<T, E, F, Fut>(request: F) -> Result<T, SdkError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SdkError<E>>>,
+{
+    let retry_strategy = ExponentialBackoff::from_millis(2)
+        .factor(50)
+        .max_delay(Duration::from_secs(5))
+        .map(jitter)
+        .take(MAX_RETRY_TIMES);
+    RetryIf::spawn(retry_strategy, request, |err: &SdkError<E>| {
+        is_retryable(err)
+    })
+    .await
+}
+
+fn is_retryable<E>(err: &SdkError<E>) -> bool {
+    match err {
+        SdkError::ServiceError { raw, .. } => {
+            let status = raw.http().status();
+            status.is_server_error() || status.as_u16() == 429
+        }
+        SdkError::ConstructionFailure(_) => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_parts() {
+        assert_eq!(split_parts(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(split_parts(8, 4), vec![0..4, 4..8]);
+        assert!(split_parts(0, 4).is_empty());
+    }
+}
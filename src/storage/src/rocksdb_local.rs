@@ -12,32 +12,68 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
 use std::future::Future;
 use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
 
 use bytes::Bytes;
-use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::{Result, RwError};
+use parking_lot::Mutex;
+use risingwave_hummock_sdk::key::next_key;
 use rocksdb::{DBIterator, ReadOptions, SeekKey, Writable, WriteBatch, WriteOptions, DB};
-use tokio::sync::OnceCell;
+use tokio::sync::{watch, OnceCell};
 use tokio::task;
 
+use crate::error::{StorageError, StorageResult as Result};
 use crate::storage_value::StorageValue;
 use crate::store::*;
 use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
 
+fn err(e: impl ToString) -> StorageError {
+    StorageError::RocksDB(e.to_string())
+}
+
+/// A write that is buffered in memory until its epoch is synced.
+#[derive(Clone)]
+enum PendingWrite {
+    /// Puts (`Some`) and deletes (`None`) of keys.
+    Batch(Vec<(Bytes, Option<Bytes>)>),
+    /// Deletes the keys in `[start, end)`.
+    DeleteRange(Vec<u8>, Vec<u8>),
+}
+
+fn in_range(key: &[u8], start: &[u8], end: &[u8]) -> bool {
+    key >= start && key < end
+}
+
+/// A state store on an embedded RocksDB, for single-node deployments that need durability without
+/// an object store.
+///
+/// Writes are buffered in memory per epoch and readable right away, and are persisted atomically
+/// when their epoch is synced, i.e., on checkpoints. So after a restart, the database holds exactly
+/// the states of the last synced epoch. Reads of an epoch see the buffered writes up to that epoch
+/// over the persisted data, i.e., there are no snapshot reads of the persisted epochs.
 #[derive(Clone)]
 pub struct RocksDBStateStore {
     storage: Arc<OnceCell<RocksDBStorage>>,
     db_path: String,
+    /// The writes that are not synced yet, by epoch and in the order they are made.
+    pending: Arc<Mutex<BTreeMap<u64, Vec<PendingWrite>>>>,
+    /// Notified after each sync.
+    synced_tx: Arc<watch::Sender<()>>,
+    synced_rx: watch::Receiver<()>,
 }
 
 impl RocksDBStateStore {
     pub fn new(db_path: &str) -> Self {
+        let (synced_tx, synced_rx) = watch::channel(());
         Self {
             storage: Arc::new(OnceCell::new()),
             db_path: db_path.to_string(),
+            pending: Arc::new(Mutex::new(BTreeMap::new())),
+            synced_tx: Arc::new(synced_tx),
+            synced_rx,
         }
     }
 
@@ -46,6 +82,63 @@ impl RocksDBStateStore {
             .get_or_init(|| async { RocksDBStorage::new(&self.db_path).await })
             .await
     }
+
+    fn push_pending(&self, epoch: u64, write: PendingWrite) {
+        self.pending.lock().entry(epoch).or_default().push(write);
+    }
+
+    /// Returns the latest buffered write of `key` up to `epoch`, or `None` if there's none, in
+    /// which case the persisted value is the latest.
+    fn get_pending(&self, key: &[u8], epoch: u64) -> Option<Option<Bytes>> {
+        let pending = self.pending.lock();
+        for writes in pending.range(..=epoch).rev().map(|(_, writes)| writes) {
+            for write in writes.iter().rev() {
+                match write {
+                    PendingWrite::Batch(kvs) => {
+                        if let Some((_, value)) = kvs.iter().rev().find(|(k, _)| k == key) {
+                            return Some(value.clone());
+                        }
+                    }
+                    PendingWrite::DeleteRange(start, end) => {
+                        if in_range(key, start, end) {
+                            return Some(None);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Folds the buffered writes up to `epoch` into the latest writes of the keys in `key_range`,
+    /// and the ranges whose persisted keys are deleted.
+    fn pending_overlay(
+        &self,
+        key_range: &(Bound<Vec<u8>>, Bound<Vec<u8>>),
+        epoch: u64,
+    ) -> (VecDeque<(Bytes, Option<Bytes>)>, Vec<(Vec<u8>, Vec<u8>)>) {
+        let mut overlay = BTreeMap::new();
+        let mut deleted_ranges = vec![];
+        let pending = self.pending.lock();
+        for writes in pending.range(..=epoch).map(|(_, writes)| writes) {
+            for write in writes {
+                match write {
+                    PendingWrite::Batch(kvs) => {
+                        for (key, value) in kvs {
+                            if key_range.contains(&key.to_vec()) {
+                                overlay.insert(key.clone(), value.clone());
+                            }
+                        }
+                    }
+                    PendingWrite::DeleteRange(start, end) => {
+                        overlay.retain(|key: &Bytes, _| !in_range(key, start, end));
+                        deleted_ranges.push((start.clone(), end.clone()));
+                    }
+                }
+            }
+        }
+        (overlay.into_iter().collect(), deleted_ranges)
+    }
 }
 
 impl StateStore for RocksDBStateStore {
@@ -53,8 +146,13 @@ impl StateStore for RocksDBStateStore {
 
     define_state_store_associated_type!();
 
-    fn get<'a>(&'a self, key: &'a [u8], _epoch: u64) -> Self::GetFuture<'_> {
-        async move { self.storage().await.get(key).await }
+    fn get<'a>(&'a self, key: &'a [u8], epoch: u64) -> Self::GetFuture<'_> {
+        async move {
+            match self.get_pending(key, epoch) {
+                Some(value) => Ok(value),
+                None => self.storage().await.get(key).await,
+            }
+        }
     }
 
     fn scan<R, B>(
@@ -98,9 +196,20 @@ impl StateStore for RocksDBStateStore {
     fn ingest_batch(
         &self,
         kv_pairs: Vec<(Bytes, StorageValue)>,
-        _epoch: u64,
+        epoch: u64,
     ) -> Self::IngestBatchFuture<'_> {
-        async move { self.storage().await.write_batch(kv_pairs).await }
+        async move {
+            let size = kv_pairs
+                .iter()
+                .map(|(key, value)| key.len() + value.size())
+                .sum();
+            let kvs = kv_pairs
+                .into_iter()
+                .map(|(key, value)| (key, value.user_value()))
+                .collect();
+            self.push_pending(epoch, PendingWrite::Batch(kvs));
+            Ok(size)
+        }
     }
 
     fn replicate_batch(
@@ -111,7 +220,16 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
-    fn iter<R, B>(&self, key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            let end = next_key(prefix);
+            assert!(!end.is_empty(), "prefix should not consist of 0xff only");
+            self.push_pending(epoch, PendingWrite::DeleteRange(prefix.to_vec(), end));
+            Ok(())
+        }
+    }
+
+    fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
@@ -121,7 +239,8 @@ impl StateStore for RocksDBStateStore {
                 key_range.start_bound().map(|b| b.as_ref().to_owned()),
                 key_range.end_bound().map(|b| b.as_ref().to_owned()),
             );
-            RocksDBStateStoreIter::new(self.clone(), range).await
+            let (overlay, deleted_ranges) = self.pending_overlay(&range, epoch);
+            RocksDBStateStoreIter::new(self.clone(), range, overlay, deleted_ranges).await
         }
     }
 
@@ -133,33 +252,72 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
-    fn wait_epoch(&self, _epoch: u64) -> Self::WaitEpochFuture<'_> {
-        async move { unimplemented!() }
+    fn wait_epoch(&self, epoch: u64) -> Self::WaitEpochFuture<'_> {
+        async move {
+            // Wait until no write up to `epoch` is pending.
+            let mut synced_rx = self.synced_rx.clone();
+            loop {
+                synced_rx.borrow_and_update();
+                if self.pending.lock().range(..=epoch).next().is_none() {
+                    return Ok(());
+                }
+                synced_rx.changed().await.map_err(err)?;
+            }
+        }
     }
 
-    fn sync(&self, _epoch: Option<u64>) -> Self::SyncFuture<'_> {
-        async move { unimplemented!() }
-    }
-}
+    fn sync(&self, epoch: Option<u64>) -> Self::SyncFuture<'_> {
+        async move {
+            // The writes of the earlier epochs are synced together, so that the persisted data are
+            // always the states of an epoch.
+            let epoch = epoch.unwrap_or(u64::MAX);
+            let writes = self
+                .pending
+                .lock()
+                .range(..=epoch)
+                .map(|(epoch, writes)| (*epoch, writes.clone()))
+                .collect::<Vec<_>>();
+            if writes.is_empty() {
+                return Ok(());
+            }
 
-pub fn next_prefix(prefix: &[u8]) -> Vec<u8> {
-    let pos = prefix.iter().rposition(|b| *b != 0xff).unwrap();
-    let (s, e) = (&prefix[..pos], prefix[pos] + 1);
-    let mut res = Vec::with_capacity(s.len() + 1);
-    res.extend_from_slice(s);
-    res.push(e);
-    res
+            self.storage()
+                .await
+                .write_batch(writes.iter().flat_map(|(_, writes)| writes))
+                .await?;
+
+            // Only remove the writes once they're persisted, so that they're always readable.
+            {
+                let mut pending = self.pending.lock();
+                for (epoch, _) in writes {
+                    pending.remove(&epoch);
+                }
+            }
+            let _ = self.synced_tx.send(());
+            Ok(())
+        }
+    }
 }
 
+/// Merges the persisted data with the buffered writes.
 pub struct RocksDBStateStoreIter {
     iter: Option<Box<DBIterator<Arc<DB>>>>,
     key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    /// The next persisted key-value pair, if it has been read.
+    db_peeked: Option<(Bytes, Bytes)>,
+    db_finished: bool,
+    /// The latest buffered writes of the keys in the range, ordered by key.
+    overlay: VecDeque<(Bytes, Option<Bytes>)>,
+    /// The ranges of persisted keys that are deleted by the buffered writes.
+    deleted_ranges: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 impl RocksDBStateStoreIter {
     async fn new(
         store: RocksDBStateStore,
         range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        overlay: VecDeque<(Bytes, Option<Bytes>)>,
+        deleted_ranges: Vec<(Vec<u8>, Vec<u8>)>,
     ) -> Result<Self> {
         let mut start_key = vec![];
         let mut is_start_unbounded = false;
@@ -171,7 +329,7 @@ impl RocksDBStateStoreIter {
                 is_start_unbounded = true;
             }
             _ => {
-                return Err(InternalError("invalid range start".to_string()).into());
+                return Err(err("invalid range start"));
             }
         };
 
@@ -182,14 +340,72 @@ impl RocksDBStateStoreIter {
             } else {
                 SeekKey::from(start_key.as_slice())
             };
-            iter.seek(seek_key)
-                .map_err(|e| RwError::from(InternalError(e)))?;
+            iter.seek(seek_key).map_err(err)?;
             Ok(Self {
                 iter: Some(Box::new(iter)),
                 key_range: range,
+                db_peeked: None,
+                db_finished: false,
+                overlay,
+                deleted_ranges,
             })
         })
-        .await?
+        .await
+        .map_err(err)?
+    }
+
+    /// Reads the next persisted key-value pair in the range.
+    async fn next_from_db(&mut self) -> Result<Option<(Bytes, Bytes)>> {
+        let mut end_key = Bytes::new();
+        let mut is_end_exclude = false;
+        let mut is_end_unbounded = false;
+        match self.key_range.end_bound() {
+            Bound::Included(e_key) => {
+                end_key = Bytes::from(e_key.clone());
+            }
+            Bound::Excluded(e_key) => {
+                end_key = Bytes::from(e_key.clone());
+                is_end_exclude = true;
+            }
+            Bound::Unbounded => {
+                is_end_unbounded = true;
+            }
+        }
+
+        let mut iter = self.iter.take().unwrap();
+        let (kv, iter) = tokio::task::spawn_blocking(move || {
+            let result = iter.valid().map_err(err);
+            if let Err(e) = result {
+                return (Err(e), iter);
+            }
+            if !result.unwrap() {
+                return (Ok(None), iter);
+            }
+            let k = Bytes::from(iter.key().to_vec());
+            let v = Bytes::from(iter.value().to_vec());
+
+            if !is_end_unbounded && (k > end_key || (k == end_key && is_end_exclude)) {
+                return (Ok(None), iter);
+            }
+            if let Err(e) = iter.next().map_err(err) {
+                return (Err(e), iter);
+            }
+            (Ok(Some((k, v))), iter)
+        })
+        .await
+        .unwrap();
+
+        self.iter = Some(iter);
+        kv
+    }
+
+    /// Fills `db_peeked` with the next persisted key-value pair, if any.
+    async fn peek_db(&mut self) -> Result<()> {
+        if self.db_peeked.is_none() && !self.db_finished {
+            self.db_peeked = self.next_from_db().await?;
+            self.db_finished = self.db_peeked.is_none();
+        }
+        Ok(())
     }
 }
 
@@ -201,50 +417,37 @@ impl StateStoreIter for RocksDBStateStoreIter {
 
     fn next(&mut self) -> Self::NextFuture<'_> {
         async move {
-            let mut end_key = Bytes::new();
-            let mut is_end_exclude = false;
-            let mut is_end_unbounded = false;
-            match self.key_range.end_bound() {
-                Bound::Included(e_key) => {
-                    end_key = Bytes::from(e_key.clone());
-                }
-                Bound::Excluded(e_key) => {
-                    end_key = Bytes::from(e_key.clone());
-                    is_end_exclude = true;
-                }
-                Bound::Unbounded => {
-                    is_end_unbounded = true;
+            loop {
+                self.peek_db().await?;
+                let order = match (&self.db_peeked, self.overlay.front()) {
+                    (None, None) => return Ok(None),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (Some((db_key, _)), Some((key, _))) => db_key.cmp(key),
+                };
+                match order {
+                    Ordering::Less => {
+                        let (key, value) = self.db_peeked.take().unwrap();
+                        if !self
+                            .deleted_ranges
+                            .iter()
+                            .any(|(start, end)| in_range(&key, start, end))
+                        {
+                            return Ok(Some((key, value)));
+                        }
+                    }
+                    Ordering::Equal | Ordering::Greater => {
+                        if order == Ordering::Equal {
+                            // The buffered write shadows the persisted value.
+                            self.db_peeked = None;
+                        }
+                        let (key, value) = self.overlay.pop_front().unwrap();
+                        if let Some(value) = value {
+                            return Ok(Some((key, value)));
+                        }
+                    }
                 }
             }
-
-            let mut iter = self.iter.take().unwrap();
-            let (kv, iter) = tokio::task::spawn_blocking(move || {
-                let result = iter.valid().map_err(|e| RwError::from(InternalError(e)));
-                if let Err(e) = result {
-                    return (Err(e), iter);
-                }
-                if !result.unwrap() {
-                    return (Ok(None), iter);
-                }
-                let k = Bytes::from(iter.key().to_vec());
-                let v = Bytes::from(iter.value().to_vec());
-
-                if is_end_unbounded {
-                    return (Ok(Some((k, v))), iter);
-                }
-                if k > end_key || (k == end_key && is_end_exclude) {
-                    return (Ok(None), iter);
-                }
-                if let Err(e) = iter.next().map_err(|e| RwError::from(InternalError(e))) {
-                    return (Err(e), iter);
-                }
-                (Ok(Some((k, v))), iter)
-            })
-            .await
-            .unwrap();
-
-            self.iter = Some(iter);
-            kv
         }
     }
 }
@@ -255,42 +458,34 @@ pub struct RocksDBStorage {
 }
 
 impl RocksDBStorage {
+    /// Opens the database at `path`, or creates one if it doesn't exist. The existing data will be
+    /// kept, so that the states can be recovered after restart.
     pub async fn new(path: &str) -> Self {
         let path = path.to_string();
         let db = task::spawn_blocking(move || DB::open_default(path.as_str()).unwrap())
             .await
             .unwrap();
-        let storage = RocksDBStorage { db: Arc::new(db) };
-        storage.clear_all().await.unwrap();
-        storage
+        RocksDBStorage { db: Arc::new(db) }
     }
 
-    async fn clear_all(&self) -> Result<()> {
-        let db = self.db.clone();
-        task::spawn_blocking(move || {
-            let mut it = db.iter();
-            if !it.seek(SeekKey::Start).unwrap() {
-                return Ok(());
-            }
-            let start_key = it.key().to_vec();
-            it.seek(SeekKey::End).unwrap();
-            let end_key = next_prefix(it.key());
-            db.delete_range(start_key.as_slice(), end_key.as_slice())
-                .map_err(|e| RwError::from(InternalError(e)))
-        })
-        .await?
-    }
-
-    async fn write_batch(&self, kv_pairs: Vec<(Bytes, StorageValue)>) -> Result<()> {
+    /// Persists the `writes` in order, atomically.
+    async fn write_batch<'a>(&self, writes: impl Iterator<Item = &'a PendingWrite>) -> Result<()> {
         let wb = WriteBatch::new();
-        for (key, value) in kv_pairs {
-            let value = value.user_value();
-            if let Some(value) = value {
-                if let Err(e) = wb.put(key.as_ref(), value.as_ref()) {
-                    return Err(InternalError(e).into());
+        for write in writes {
+            match write {
+                PendingWrite::Batch(kvs) => {
+                    for (key, value) in kvs {
+                        if let Some(value) = value {
+                            wb.put(key.as_ref(), value.as_ref()).map_err(err)?;
+                        } else {
+                            wb.delete(key.as_ref()).map_err(err)?;
+                        }
+                    }
+                }
+                PendingWrite::DeleteRange(start, end) => {
+                    wb.delete_range(start.as_slice(), end.as_slice())
+                        .map_err(err)?;
                 }
-            } else if let Err(e) = wb.delete(key.as_ref()) {
-                return Err(InternalError(e).into());
             }
         }
 
@@ -298,22 +493,22 @@ impl RocksDBStorage {
         task::spawn_blocking(move || {
             let mut opts = WriteOptions::default();
             opts.set_sync(true);
-            db.write_opt(&wb, &opts)
-                .map_or_else(|e| Err(InternalError(e).into()), |_| Ok(()))
+            db.write_opt(&wb, &opts).map_err(err)
         })
-        .await?
+        .await
+        .map_err(err)?
     }
 
     async fn get(&self, key: &[u8]) -> Result<Option<Bytes>> {
         let db = self.db.clone();
         let seek_key = key.to_vec();
         task::spawn_blocking(move || {
-            db.get(&seek_key).map_or_else(
-                |e| Err(InternalError(e).into()),
-                |option_v| Ok(option_v.map(|v| Bytes::from(v.to_vec()))),
-            )
+            db.get(&seek_key)
+                .map(|option_v| option_v.map(|v| Bytes::from(v.to_vec())))
+                .map_err(err)
         })
-        .await?
+        .await
+        .map_err(err)?
     }
 
     async fn iter(&self) -> DBIterator<Arc<DB>> {
@@ -332,7 +527,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_rocksdb() {
-        let rocksdb_state_store = RocksDBStateStore::new("/tmp/default");
+        let dir = tempfile::tempdir().unwrap();
+        let rocksdb_state_store = RocksDBStateStore::new(dir.path().to_str().unwrap());
         let result = rocksdb_state_store.get("key1".as_bytes(), 0).await;
         assert_eq!(result.unwrap(), None);
         let result = rocksdb_state_store.get("key2".as_bytes(), 0).await;
@@ -372,4 +568,86 @@ mod tests {
         assert!(result.get(0).unwrap().0.eq(&Bytes::from("key1")));
         assert!(result.get(1).unwrap().0.eq(&Bytes::from("key2")));
     }
+
+    #[tokio::test]
+    async fn test_rocksdb_pending_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let rocksdb_state_store = RocksDBStateStore::new(dir.path().to_str().unwrap());
+        let kv_pairs: Vec<(Bytes, StorageValue)> = vec![
+            ("a1".into(), StorageValue::new_default_put("val1")),
+            ("b1".into(), StorageValue::new_default_put("val2")),
+        ];
+        rocksdb_state_store.ingest_batch(kv_pairs, 1).await.unwrap();
+        rocksdb_state_store.sync(Some(1)).await.unwrap();
+
+        rocksdb_state_store.delete_range(b"a", 2).await.unwrap();
+        let kv_pairs: Vec<(Bytes, StorageValue)> = vec![
+            ("a2".into(), StorageValue::new_default_put("val3")),
+            ("b1".into(), StorageValue::new_default_delete()),
+            ("c1".into(), StorageValue::new_default_put("val4")),
+        ];
+        rocksdb_state_store.ingest_batch(kv_pairs, 2).await.unwrap();
+
+        // The buffered writes are only visible to the reads of their epochs or later.
+        let scan = |epoch| rocksdb_state_store.scan::<_, &[u8]>(.., None, epoch);
+        assert_eq!(
+            scan(1).await.unwrap(),
+            vec![
+                (Bytes::from("a1"), Bytes::from("val1")),
+                (Bytes::from("b1"), Bytes::from("val2")),
+            ]
+        );
+        assert_eq!(
+            scan(2).await.unwrap(),
+            vec![
+                (Bytes::from("a2"), Bytes::from("val3")),
+                (Bytes::from("c1"), Bytes::from("val4")),
+            ]
+        );
+        assert_eq!(rocksdb_state_store.get(b"a1", 2).await.unwrap(), None);
+        assert_eq!(
+            rocksdb_state_store.get(b"a1", 1).await.unwrap(),
+            Some(Bytes::from("val1"))
+        );
+
+        rocksdb_state_store.sync(Some(2)).await.unwrap();
+        rocksdb_state_store.wait_epoch(2).await.unwrap();
+        assert_eq!(
+            scan(2).await.unwrap(),
+            vec![
+                (Bytes::from("a2"), Bytes::from("val3")),
+                (Bytes::from("c1"), Bytes::from("val4")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_recover() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        {
+            let rocksdb_state_store = RocksDBStateStore::new(path);
+            let kv_pairs: Vec<(Bytes, StorageValue)> = vec![
+                ("a1".into(), StorageValue::new_default_put("val1")),
+                ("a2".into(), StorageValue::new_default_put("val2")),
+                ("b1".into(), StorageValue::new_default_put("val3")),
+            ];
+            rocksdb_state_store.ingest_batch(kv_pairs, 0).await.unwrap();
+            rocksdb_state_store.delete_range(b"a", 1).await.unwrap();
+            rocksdb_state_store.sync(Some(1)).await.unwrap();
+
+            // The writes of an epoch that is never synced are lost on restart.
+            let kv_pairs: Vec<(Bytes, StorageValue)> =
+                vec![("c1".into(), StorageValue::new_default_put("val4"))];
+            rocksdb_state_store.ingest_batch(kv_pairs, 2).await.unwrap();
+        }
+
+        // The synced data should survive reopening the database.
+        let rocksdb_state_store = RocksDBStateStore::new(path);
+        let result = rocksdb_state_store
+            .scan::<_, &[u8]>(.., None, 2)
+            .await
+            .unwrap();
+        assert_eq!(result, vec![(Bytes::from("b1"), Bytes::from("val3"))]);
+    }
 }
@@ -85,6 +85,10 @@ impl StateStore for RocksDBStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_range<'a>(&'a self, _prefix: &'a [u8], _epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move { unimplemented!() }
+    }
+
     fn iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
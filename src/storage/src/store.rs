@@ -36,6 +36,7 @@ macro_rules! define_state_store_associated_type {
         type BackwardScanFuture<'a, R, B> = impl ScanFutureTrait<'a, R, B> where R: 'static + Send, B: 'static + Send;
         type IngestBatchFuture<'a> = impl IngestBatchFutureTrait<'a>;
         type ReplicateBatchFuture<'a> = impl EmptyFutureTrait<'a>;
+        type DeleteRangeFuture<'a> = impl EmptyFutureTrait<'a>;
         type WaitEpochFuture<'a> = impl EmptyFutureTrait<'a>;
         type SyncFuture<'a> = impl EmptyFutureTrait<'a>;
         type IterFuture<'a, R, B> = impl Future<Output = $crate::error::StorageResult<Self::Iter>> + Send where R: 'static + Send, B: 'static + Send;
@@ -62,6 +63,8 @@ pub trait StateStore: Send + Sync + 'static + Clone {
 
     type ReplicateBatchFuture<'a>: EmptyFutureTrait<'a>;
 
+    type DeleteRangeFuture<'a>: EmptyFutureTrait<'a>;
+
     type WaitEpochFuture<'a>: EmptyFutureTrait<'a>;
 
     type SyncFuture<'a>: EmptyFutureTrait<'a>;
@@ -127,6 +130,10 @@ pub trait StateStore: Send + Sync + 'static + Clone {
         epoch: u64,
     ) -> Self::ReplicateBatchFuture<'_>;
 
+    /// Deletes all keys with the given `prefix` at `epoch`, e.g., to clean up the state of a
+    /// dropped actor. Keys written at later epochs are not affected.
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], epoch: u64) -> Self::DeleteRangeFuture<'_>;
+
     /// Opens and returns an iterator for given `key_range`.
     /// The returned iterator will iterate data based on a snapshot corresponding to the given
     /// `epoch`.
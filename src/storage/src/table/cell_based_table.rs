@@ -13,15 +13,17 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::RangeBounds;
 use std::sync::Arc;
 
-use bytes::Bytes;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{DataChunk, Row};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema};
 use risingwave_common::error::RwError;
+use risingwave_common::types::Datum;
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_common::util::ordered::*;
 use risingwave_common::util::sort_util::OrderType;
@@ -135,38 +137,10 @@ impl<S: StateStore> CellBasedTable<S> {
         // get row by state_store get
         // TODO: use multi-get for cell_based get_row
         let pk_serializer = self.pk_serializer.as_ref().expect("pk_serializer is None");
-        let serialized_pk = &serialize_pk(pk, pk_serializer).map_err(err)?[..];
-        let sentinel_key = [
-            serialized_pk,
-            &serialize_column_id(&SENTINEL_CELL_ID).map_err(err)?,
-        ]
-        .concat();
-        let mut get_res = Vec::new();
-        let sentinel_cell = self.keyspace.get(&sentinel_key, epoch).await?;
-
-        if sentinel_cell.is_none() {
-            // if sentinel cell is none, this row doesn't exist
-            return Ok(None);
-        } else {
-            get_res.push((sentinel_key, sentinel_cell.unwrap()));
-        }
-        for column_id in &self.column_ids {
-            let key = [serialized_pk, &serialize_column_id(column_id).map_err(err)?].concat();
-            let state_store_get_res = self.keyspace.get(&key, epoch).await?;
-            if let Some(state_store_get_res) = state_store_get_res {
-                get_res.push((key, state_store_get_res));
-            }
-        }
-        let mut cell_based_row_deserializer =
-            CellBasedRowDeserializer::new(self.column_descs.clone());
-        for (key, value) in get_res {
-            let deserialize_res = cell_based_row_deserializer
-                .deserialize(&Bytes::from(key), &value)
-                .map_err(err)?;
-            assert!(deserialize_res.is_none());
-        }
-        let pk_and_row = cell_based_row_deserializer.take();
-        Ok(pk_and_row.map(|(_pk, row)| row))
+        let serialized_pk = serialize_pk(pk, pk_serializer).map_err(err)?;
+        self.keyspace
+            .get_row(&serialized_pk, &self.column_descs, epoch)
+            .await
     }
 
     pub async fn get_row_by_scan(&self, pk: &Row, epoch: u64) -> StorageResult<Option<Row>> {
@@ -202,8 +176,7 @@ impl<S: StateStore> CellBasedTable<S> {
         epoch: u64,
     ) -> StorageResult<()> {
         // stateful executors need to compute vnode.
-        let mut batch = self.keyspace.state_store().start_write_batch();
-        let mut local = batch.prefixify(&self.keyspace);
+        let mut batch = self.keyspace.start_write_batch();
         let ordered_row_serializer = self.pk_serializer.as_ref().unwrap();
         let hash_builder = CRC32FastBuilder {};
         for (pk, row_op) in buffer {
@@ -229,7 +202,7 @@ impl<S: StateStore> CellBasedTable<S> {
                         .serialize(&arrange_key_buf, row, &self.column_ids)
                         .map_err(err)?;
                     for (key, value) in bytes {
-                        local.put(key, StorageValue::new_put(value_meta, value))
+                        batch.put(key, StorageValue::new_put(value_meta, value))
                     }
                 }
                 RowOp::Delete(old_row) => {
@@ -239,7 +212,7 @@ impl<S: StateStore> CellBasedTable<S> {
                         .serialize(&arrange_key_buf, old_row, &self.column_ids)
                         .map_err(err)?;
                     for (key, _) in bytes {
-                        local.delete_with_value_meta(key, value_meta);
+                        batch.delete_with_value_meta(key, value_meta);
                     }
                 }
                 RowOp::Update((old_row, new_row)) => {
@@ -256,15 +229,15 @@ impl<S: StateStore> CellBasedTable<S> {
                     {
                         match (delete, insert) {
                             (Some((delete_pk, _)), None) => {
-                                local.delete_with_value_meta(delete_pk, value_meta);
+                                batch.delete_with_value_meta(delete_pk, value_meta);
                             }
                             (None, Some((insert_pk, insert_row))) => {
-                                local.put(insert_pk, StorageValue::new_put(value_meta, insert_row));
+                                batch.put(insert_pk, StorageValue::new_put(value_meta, insert_row));
                             }
                             (None, None) => {}
                             (Some((delete_pk, _)), Some((insert_pk, insert_row))) => {
                                 debug_assert_eq!(delete_pk, insert_pk);
-                                local.put(insert_pk, StorageValue::new_put(value_meta, insert_row));
+                                batch.put(insert_pk, StorageValue::new_put(value_meta, insert_row));
                             }
                         }
                     }
@@ -302,6 +275,204 @@ impl<S: StateStore> CellBasedTable<S> {
         .await
     }
 
+    /// Iterates the rows whose pk starts with `pk_prefix`, and whose pk column next to the prefix
+    /// is within `next_col_bounds`. Null is regarded as less than any value in the bounds, so
+    /// `(Excluded(None), ..)` excludes the nulls.
+    pub async fn iter_with_pk_bounds(
+        &self,
+        epoch: u64,
+        pk_prefix: Row,
+        next_col_bounds: impl RangeBounds<Datum>,
+    ) -> StorageResult<CellBasedTableRowIter<S>> {
+        CellBasedTableRowIter::new_with_bounds(
+            self.keyspace.clone(),
+            self.column_descs.clone(),
+            self.serialize_pk_bounds(pk_prefix, next_col_bounds),
+            epoch,
+            self.stats.clone(),
+        )
+        .await
+    }
+
+    /// Serializes the pk bounds accepted by [`Self::iter_with_pk_bounds`] into a range of
+    /// serialized pks.
+    pub fn serialize_pk_bounds(
+        &self,
+        pk_prefix: Row,
+        next_col_bounds: impl RangeBounds<Datum>,
+    ) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+        let pk_serializer = self
+            .pk_serializer
+            .as_ref()
+            .expect("pk serializer is required to iterate with pk bounds");
+        let prefix_len = pk_prefix.size();
+
+        let mut prefix_key = vec![];
+        pk_serializer
+            .prefix(prefix_len)
+            .serialize(&pk_prefix, &mut prefix_key);
+
+        let (start, end) = if prefix_len < pk_serializer.order_types().len() {
+            let next_col_serializer = pk_serializer.prefix(prefix_len + 1);
+            let serialize_next_col = |datum: &Datum| {
+                let mut key = vec![];
+                next_col_serializer
+                    .serialize_datums(pk_prefix.values().chain(std::iter::once(datum)), &mut key);
+                key
+            };
+            let start_of = |bound: Bound<&Datum>| match bound {
+                Included(datum) => Included(serialize_next_col(datum)),
+                Excluded(datum) => Included(next_key(&serialize_next_col(datum))),
+                Unbounded => Included(prefix_key.clone()),
+            };
+            let end_of = |bound: Bound<&Datum>| match bound {
+                Included(datum) => Excluded(next_key(&serialize_next_col(datum))),
+                Excluded(datum) => Excluded(serialize_next_col(datum)),
+                Unbounded => Excluded(next_key(&prefix_key)),
+            };
+            // The keys of a descending column are in the reversed order of its values.
+            match pk_serializer.order_types()[prefix_len] {
+                OrderType::Ascending => (
+                    start_of(next_col_bounds.start_bound()),
+                    end_of(next_col_bounds.end_bound()),
+                ),
+                OrderType::Descending => (
+                    start_of(next_col_bounds.end_bound()),
+                    end_of(next_col_bounds.start_bound()),
+                ),
+            }
+        } else {
+            (
+                Included(prefix_key.clone()),
+                Excluded(next_key(&prefix_key)),
+            )
+        };
+        // An empty key means there is no key after, i.e., unbounded.
+        let unbounded_if_empty = |bound: Bound<Vec<u8>>| match bound {
+            Included(key) | Excluded(key) if key.is_empty() => Unbounded,
+            bound => bound,
+        };
+
+        (unbounded_if_empty(start), unbounded_if_empty(end))
+    }
+
+    /// Iterates the rows whose serialized pks are within `pk_range`. Unlike
+    /// [`Self::iter_with_pk_bounds`], the bounds can be arbitrary bytes, e.g., the ones given by
+    /// [`Self::split_pk_range`]. Each row is compared as a whole with the bounds, so the cells of a
+    /// row are never divided between the iterators of adjacent ranges.
+    pub async fn iter_with_pk_range(
+        &self,
+        epoch: u64,
+        pk_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    ) -> StorageResult<CellBasedTableRowIter<S>> {
+        CellBasedTableRowIter::new_with_pk_range(
+            self.keyspace.clone(),
+            self.column_descs.clone(),
+            pk_range,
+            epoch,
+            self.stats.clone(),
+        )
+        .await
+    }
+
+    /// Splits `pk_range`, a range of serialized pks, into at most `count` adjacent sub-ranges, so
+    /// that their rows can be read concurrently with [`Self::iter_with_pk_range`].
+    ///
+    /// The boundaries evenly divide the key space between the first and the last key in the range,
+    /// where the last key is located by a binary search of about 64 seeks. So the sub-ranges hold
+    /// similar numbers of rows only if the keys are evenly distributed.
+    pub async fn split_pk_range(
+        &self,
+        epoch: u64,
+        pk_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        count: usize,
+    ) -> StorageResult<Vec<(Bound<Vec<u8>>, Bound<Vec<u8>>)>> {
+        if count <= 1 {
+            return Ok(vec![pk_range]);
+        }
+        self.keyspace.state_store().wait_epoch(epoch).await?;
+
+        let start_key = match &pk_range.0 {
+            Included(key) | Excluded(key) => key.clone(),
+            Unbounded => vec![],
+        };
+        let first_key = match self.first_key_from(start_key, &pk_range.1, epoch).await? {
+            Some(key) => key,
+            None => return Ok(vec![pk_range]),
+        };
+
+        // All the keys in the range share the common prefix of the first key and the end bound.
+        // The 8 bytes after the prefix are taken as the position of a key in the key space.
+        let prefix_len = match &pk_range.1 {
+            Included(end) | Excluded(end) => first_key
+                .iter()
+                .zip(end)
+                .take_while(|(a, b)| a == b)
+                .count(),
+            Unbounded => 0,
+        };
+        let prefix = &first_key[..prefix_len];
+        let key_at = |position: u64| [prefix, &position.to_be_bytes()].concat();
+        let first = {
+            let rest = &first_key[prefix_len..];
+            let mut bytes = [0u8; 8];
+            let len = rest.len().min(8);
+            bytes[..len].copy_from_slice(&rest[..len]);
+            u64::from_be_bytes(bytes)
+        };
+
+        // Find the position of the last key, i.e., the largest one with keys at or after it.
+        let (mut lo, mut hi) = (first, u64::MAX);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2 + (hi - lo) % 2;
+            if self
+                .first_key_from(key_at(mid), &pk_range.1, epoch)
+                .await?
+                .is_some()
+            {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        let last = lo;
+
+        let span = (last - first) as u128 + 1;
+        let boundaries = (1..count as u128)
+            .map(|i| first + (span * i / count as u128) as u64)
+            .filter(|position| *position > first)
+            .dedup()
+            .collect_vec();
+
+        let (mut start, end) = pk_range;
+        let mut ranges = Vec::with_capacity(boundaries.len() + 1);
+        for position in boundaries {
+            let boundary = key_at(position);
+            ranges.push((start, Excluded(boundary.clone())));
+            start = Included(boundary);
+        }
+        ranges.push((start, end));
+        Ok(ranges)
+    }
+
+    /// Returns the first key at or after `from` whose pk is before `pk_end`.
+    async fn first_key_from(
+        &self,
+        from: Vec<u8>,
+        pk_end: &Bound<Vec<u8>>,
+        epoch: u64,
+    ) -> StorageResult<Option<Vec<u8>>> {
+        let mut iter = self
+            .keyspace
+            .iter_with_range::<_, Vec<u8>>((Included(from), Unbounded), epoch)
+            .await?;
+        Ok(iter.next().await?.and_then(|(key, _)| {
+            // Strip the column id.
+            let pk = &key[..key.len().saturating_sub(4)];
+            (!is_after_end(pk, pk_end)).then(|| key.to_vec())
+        }))
+    }
+
     // streaming_iter is uesed for streaming executors, which is regarded as a short-term iterator
     // and will not wait for epoch.
     pub async fn streaming_iter(
@@ -320,6 +491,22 @@ fn generate_column_id(column_descs: &[ColumnDesc]) -> Vec<ColumnId> {
     column_descs.iter().map(|d| d.column_id).collect()
 }
 
+fn is_before_start(pk: &[u8], start: &Bound<Vec<u8>>) -> bool {
+    match start {
+        Included(key) => pk < key.as_slice(),
+        Excluded(key) => pk <= key.as_slice(),
+        Unbounded => false,
+    }
+}
+
+fn is_after_end(pk: &[u8], end: &Bound<Vec<u8>>) -> bool {
+    match end {
+        Included(key) => pk > key.as_slice(),
+        Excluded(key) => pk >= key.as_slice(),
+        Unbounded => false,
+    }
+}
+
 // (st1page): Maybe we will have a "ChunkIter" trait which returns a chunk each time, so the name
 // "RowTableIter" is reserved now
 pub struct CellBasedTableRowIter<S: StateStore> {
@@ -327,6 +514,11 @@ pub struct CellBasedTableRowIter<S: StateStore> {
     iter: StripPrefixIterator<S::Iter>,
     /// Cell-based row deserializer
     cell_based_row_deserializer: CellBasedRowDeserializer,
+    /// The rows whose pks are out of the range are skipped, in case that the storage range doesn't
+    /// exactly match the rows.
+    pk_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    /// Whether all the rows have been returned.
+    finished: bool,
     /// Statistics
     _stats: Arc<StateStoreMetrics>,
 }
@@ -337,21 +529,56 @@ impl<S: StateStore> CellBasedTableRowIter<S> {
         table_descs: Vec<ColumnDesc>,
         epoch: u64,
         _stats: Arc<StateStoreMetrics>,
+    ) -> StorageResult<Self> {
+        Self::new_with_bounds(keyspace, table_descs, (Unbounded, Unbounded), epoch, _stats).await
+    }
+
+    /// Creates the iterator of the rows within `pk_bounds`, which are of the serialized pks.
+    pub async fn new_with_bounds(
+        keyspace: Keyspace<S>,
+        table_descs: Vec<ColumnDesc>,
+        pk_bounds: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        epoch: u64,
+        _stats: Arc<StateStoreMetrics>,
     ) -> StorageResult<Self> {
         keyspace.state_store().wait_epoch(epoch).await?;
 
         let cell_based_row_deserializer = CellBasedRowDeserializer::new(table_descs);
 
-        let iter = keyspace.iter(epoch).await?;
+        let iter = keyspace.iter_with_range(pk_bounds, epoch).await?;
 
         let iter = Self {
             iter,
             cell_based_row_deserializer,
+            pk_range: (Unbounded, Unbounded),
+            finished: false,
             _stats,
         };
         Ok(iter)
     }
 
+    /// Creates the iterator of the rows whose pks are within `pk_range`, where the bounds can be
+    /// arbitrary bytes instead of the serialized pks.
+    pub async fn new_with_pk_range(
+        keyspace: Keyspace<S>,
+        table_descs: Vec<ColumnDesc>,
+        pk_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+        epoch: u64,
+        _stats: Arc<StateStoreMetrics>,
+    ) -> StorageResult<Self> {
+        // The cells of a row whose pk is a prefix of the start bound may be after the bound, and
+        // so may the ones whose pk is a prefix of the end bound. Begin with the start bound and
+        // stop at the first row after the end.
+        let start = match &pk_range.0 {
+            Included(key) | Excluded(key) => Included(key.clone()),
+            Unbounded => Unbounded,
+        };
+        let mut iter =
+            Self::new_with_bounds(keyspace, table_descs, (start, Unbounded), epoch, _stats).await?;
+        iter.pk_range = pk_range;
+        Ok(iter)
+    }
+
     pub async fn collect_data_chunk(
         &mut self,
         schema: &Schema,
@@ -398,11 +625,11 @@ impl<S: StateStore> CellBasedTableRowIter<S> {
 #[async_trait::async_trait]
 impl<S: StateStore> TableIter for CellBasedTableRowIter<S> {
     async fn next(&mut self) -> StorageResult<Option<Row>> {
-        loop {
-            match self.iter.next().await? {
+        while !self.finished {
+            let pk_and_row = match self.iter.next().await? {
                 None => {
-                    let pk_and_row = self.cell_based_row_deserializer.take();
-                    return Ok(pk_and_row.map(|(_pk, row)| row));
+                    self.finished = true;
+                    self.cell_based_row_deserializer.take()
                 }
                 Some((key, value)) => {
                     tracing::trace!(
@@ -411,17 +638,20 @@ impl<S: StateStore> TableIter for CellBasedTableRowIter<S> {
                         key,
                         value
                     );
-                    let pk_and_row = self
-                        .cell_based_row_deserializer
+                    self.cell_based_row_deserializer
                         .deserialize(&key, &value)
-                        .map_err(err)?;
-                    match pk_and_row {
-                        Some(_) => return Ok(pk_and_row.map(|(_pk, row)| row)),
-                        None => {}
-                    }
+                        .map_err(err)?
+                }
+            };
+            if let Some((pk, row)) = pk_and_row {
+                if is_after_end(&pk, &self.pk_range.1) {
+                    self.finished = true;
+                } else if !is_before_start(&pk, &self.pk_range.0) {
+                    return Ok(Some(row));
                 }
             }
         }
+        Ok(None)
     }
 }
 
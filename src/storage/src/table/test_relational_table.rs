@@ -17,6 +17,7 @@ use futures::stream::StreamExt;
 use risingwave_common::array::Row;
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
 use risingwave_common::types::DataType;
+use risingwave_common::util::ordered::{serialize_pk, OrderedRowSerializer};
 use risingwave_common::util::sort_util::OrderType;
 
 use crate::error::StorageResult;
@@ -1434,3 +1435,168 @@ async fn test_cell_based_scan_empty_column_ids_cardinality() {
     };
     assert_eq!(chunk.cardinality(), 2);
 }
+
+#[tokio::test]
+async fn test_keyspace_get_row() {
+    let state_store = MemoryStateStore::new();
+    let column_ids = vec![ColumnId::from(0), ColumnId::from(1), ColumnId::from(2)];
+    let column_descs = vec![
+        ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[2], DataType::Int32),
+    ];
+    let order_types = vec![OrderType::Ascending];
+    let keyspace = Keyspace::executor_root(state_store, 0x42);
+    let mut state = StateTable::new(
+        keyspace.clone(),
+        column_descs.clone(),
+        order_types.clone(),
+        None,
+    );
+    let epoch: u64 = 0;
+
+    state
+        .insert(
+            Row(vec![Some(1_i32.into())]),
+            Row(vec![Some(1_i32.into()), None, Some(111_i32.into())]),
+        )
+        .unwrap();
+    state.commit(epoch).await.unwrap();
+
+    let pk_serializer = OrderedRowSerializer::new(order_types);
+    let serialize = |pk: i32| serialize_pk(&Row(vec![Some(pk.into())]), &pk_serializer).unwrap();
+
+    let row = keyspace
+        .get_row(&serialize(1), &column_descs, epoch)
+        .await
+        .unwrap();
+    assert_eq!(
+        row,
+        Some(Row(vec![Some(1_i32.into()), None, Some(111_i32.into())]))
+    );
+
+    // Only get the cells of the given columns.
+    let row = keyspace
+        .get_row(&serialize(1), &column_descs[2..], epoch)
+        .await
+        .unwrap();
+    assert_eq!(row, Some(Row(vec![Some(111_i32.into())])));
+
+    let row = keyspace
+        .get_row(&serialize(2), &column_descs, epoch)
+        .await
+        .unwrap();
+    assert_eq!(row, None);
+}
+
+#[tokio::test]
+async fn test_cell_based_table_iter_with_pk_bounds() {
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+    use risingwave_common::types::Datum;
+
+    use crate::table::cell_based_table::CellBasedTableRowIter;
+
+    let state_store = MemoryStateStore::new();
+    let order_types = vec![OrderType::Ascending, OrderType::Descending];
+    let keyspace = Keyspace::executor_root(state_store, 0x42);
+    let column_ids = vec![ColumnId::from(0), ColumnId::from(1), ColumnId::from(2)];
+    let column_descs = vec![
+        ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[2], DataType::Int32),
+    ];
+
+    let mut state = StateTable::new(
+        keyspace.clone(),
+        column_descs.clone(),
+        order_types.clone(),
+        None,
+    );
+    let table = CellBasedTable::new_for_test(keyspace.clone(), column_descs, order_types);
+    let epoch: u64 = 0;
+
+    let pks = [
+        (1, Some(11)),
+        (1, Some(12)),
+        (1, Some(13)),
+        (1, None),
+        (2, Some(21)),
+        (3, Some(31)),
+    ];
+    for (pk0, pk1) in pks {
+        let pk = Row(vec![Some(pk0.into()), pk1.map(|v: i32| v.into())]);
+        let row = Row(vec![
+            Some(pk0.into()),
+            pk1.map(|v: i32| v.into()),
+            Some((pk0 * 100).into()),
+        ]);
+        state.insert(pk, row).unwrap();
+    }
+    state.commit(epoch).await.unwrap();
+
+    let epoch = u64::MAX;
+    let collect_pks = |mut iter: CellBasedTableRowIter<MemoryStateStore>| async move {
+        let mut pks = vec![];
+        while let Some(row) = iter.next().await.unwrap() {
+            pks.push((row.0[0].clone(), row.0[1].clone()));
+        }
+        pks
+    };
+    let pk = |pk0: i32, pk1: Option<i32>| -> (Datum, Datum) {
+        (Some(pk0.into()), pk1.map(|v| v.into()))
+    };
+
+    // Range on the first column.
+    let iter = table
+        .iter_with_pk_bounds(
+            epoch,
+            Row(vec![]),
+            (Excluded(Some(1_i32.into())), Included(Some(3_i32.into()))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        collect_pks(iter).await,
+        vec![pk(2, Some(21)), pk(3, Some(31))]
+    );
+
+    // Range on the descending second column with the first one fixed.
+    let iter = table
+        .iter_with_pk_bounds(
+            epoch,
+            Row(vec![Some(1_i32.into())]),
+            (Included(Some(12_i32.into())), Unbounded),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        collect_pks(iter).await,
+        vec![pk(1, Some(13)), pk(1, Some(12))]
+    );
+
+    // Nulls are excluded with an excluded null lower bound.
+    let iter = table
+        .iter_with_pk_bounds(
+            epoch,
+            Row(vec![Some(1_i32.into())]),
+            (Excluded(None), Excluded(Some(13_i32.into()))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        collect_pks(iter).await,
+        vec![pk(1, Some(12)), pk(1, Some(11))]
+    );
+
+    // Whole pk fixed.
+    let iter = table
+        .iter_with_pk_bounds(
+            epoch,
+            Row(vec![Some(1_i32.into()), None]),
+            (Bound::<Datum>::Unbounded, Bound::<Datum>::Unbounded),
+        )
+        .await
+        .unwrap();
+    assert_eq!(collect_pks(iter).await, vec![pk(1, None)]);
+}
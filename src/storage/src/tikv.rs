@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use std::future::Future;
-use std::ops::Bound::Excluded;
+use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
 
 use bytes::Bytes;
 use risingwave_common::error::{Result, ToRwResult};
+use risingwave_hummock_sdk::key::next_key;
 use tikv_client::{BoundRange, KvPair, TransactionClient};
 use tokio::sync::OnceCell;
 
@@ -166,6 +167,37 @@ impl StateStore for TikvStateStore {
         async move { unimplemented!() }
     }
 
+    /// Deletes the keys with `prefix` in a single transaction.
+    fn delete_range<'a>(&'a self, prefix: &'a [u8], _epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move {
+            let end = next_key(prefix);
+            let range = (
+                Included(prefix.to_vec()),
+                if end.is_empty() {
+                    Unbounded
+                } else {
+                    Excluded(end)
+                },
+            );
+
+            let mut txn = self.client().await.begin_optimistic().await.unwrap();
+            let keys: Vec<tikv_client::Key> = txn
+                .scan_keys(BoundRange::from(range), u32::MAX)
+                .await
+                .map_err(anyhow::Error::new)
+                .to_rw_result()?
+                .collect();
+            for key in keys {
+                txn.delete(key)
+                    .await
+                    .map_err(anyhow::Error::new)
+                    .to_rw_result()?;
+            }
+            txn.commit().await.unwrap();
+            Ok(())
+        }
+    }
+
     fn iter<R, B>(&self, key_range: R, _epoch: u64) -> Self::IterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -64,6 +64,10 @@ impl StateStore for TikvStateStore {
         async move { unimplemented!() }
     }
 
+    fn delete_range<'a>(&'a self, _prefix: &'a [u8], _epoch: u64) -> Self::DeleteRangeFuture<'_> {
+        async move { unimplemented!() }
+    }
+
     fn backward_iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::BackwardIterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::BorrowMut;
+
 use bytes::Bytes;
 
 use crate::error::StorageResult;
@@ -57,7 +59,7 @@ where
         self.batch.len()
     }
 
-    /// Preprocesses the batch to make it sorted. It returns `false` if duplicate keys are found.
+    /// Preprocesses the batch to make it sorted. It returns an error if duplicate keys are found.
     pub fn preprocess(&mut self) -> StorageResult<()> {
         if self.is_empty() {
             return Ok(());
@@ -103,15 +105,52 @@ where
     }
 }
 
-/// [`KeySpaceWriteBatch`] attaches a [`Keyspace`] to a mutable reference of global [`WriteBatch`],
-/// which automatically prepends the keyspace prefix when writing.
-pub struct KeySpaceWriteBatch<'a, S: StateStore> {
+/// [`KeySpaceWriteBatch`] attaches a [`Keyspace`] to a global [`WriteBatch`], which automatically
+/// prepends the keyspace prefix when writing. The global batch is either a mutable reference shared
+/// by several keyspaces, given by [`WriteBatch::prefixify`], or owned by the keyspace batch, given
+/// by [`Keyspace::start_write_batch`].
+pub struct KeySpaceWriteBatch<
+    'a,
+    S: StateStore,
+    B: BorrowMut<WriteBatch<S>> = &'a mut WriteBatch<S>,
+> {
     keyspace: &'a Keyspace<S>,
 
-    global: &'a mut WriteBatch<S>,
+    global: B,
+}
+
+impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S, WriteBatch<S>> {
+    /// Creates a keyspace batch owning a new, empty global batch of the keyspace's state store.
+    pub(crate) fn new(keyspace: &'a Keyspace<S>) -> Self {
+        Self {
+            keyspace,
+            global: WriteBatch::new(keyspace.state_store()),
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more key-value pairs to be inserted in the
+    /// batch.
+    pub fn reserve(&mut self, additional: usize) {
+        self.global.reserve(additional);
+    }
+
+    /// Returns the number of key-value pairs in the batch.
+    pub fn len(&self) -> usize {
+        self.global.len()
+    }
+
+    /// Returns `true` if the batch contains no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty()
+    }
+
+    /// Ingests this batch into the state store of the keyspace.
+    pub async fn ingest(self, epoch: u64) -> StorageResult<()> {
+        self.global.ingest(epoch).await
+    }
 }
 
-impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S> {
+impl<'a, S: StateStore, B: BorrowMut<WriteBatch<S>>> KeySpaceWriteBatch<'a, S, B> {
     /// Pushes `key` and `value` into the `WriteBatch`.
     /// If `key` is valid, it will be prefixed with `keyspace` key.
     /// Otherwise, only `keyspace` key is pushed.
@@ -121,7 +160,7 @@ impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S> {
             None => self.keyspace.key().to_vec(),
         }
         .into();
-        self.global.batch.push((key, value));
+        self.global.borrow_mut().batch.push((key, value));
     }
 
     /// Treats the keyspace as a single key, and put a value.
@@ -156,7 +195,6 @@ impl<'a, S: StateStore> KeySpaceWriteBatch<'a, S> {
 mod tests {
     use bytes::Bytes;
 
-    use super::WriteBatch;
     use crate::memory::MemoryStateStore;
     use crate::storage_value::StorageValue;
     use crate::Keyspace;
@@ -164,15 +202,14 @@ mod tests {
     #[tokio::test]
     async fn test_invalid_write_batch() {
         let state_store = MemoryStateStore::new();
-        let mut write_batch = WriteBatch::new(state_store.clone());
         let key_space = Keyspace::executor_root(state_store, 0x118);
+        let mut write_batch = key_space.start_write_batch();
 
         assert!(write_batch.is_empty());
-        let mut key_space_batch = write_batch.prefixify(&key_space);
-        key_space_batch.put(Bytes::from("aa"), StorageValue::new_default_put("444"));
-        key_space_batch.put(Bytes::from("cc"), StorageValue::new_default_put("444"));
-        key_space_batch.put(Bytes::from("bb"), StorageValue::new_default_put("444"));
-        key_space_batch.delete(Bytes::from("aa"));
+        write_batch.put(Bytes::from("aa"), StorageValue::new_default_put("444"));
+        write_batch.put(Bytes::from("cc"), StorageValue::new_default_put("444"));
+        write_batch.put(Bytes::from("bb"), StorageValue::new_default_put("444"));
+        write_batch.delete(Bytes::from("aa"));
 
         write_batch
             .ingest(1)
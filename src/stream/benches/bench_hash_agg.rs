@@ -0,0 +1,202 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::StreamExt;
+use risingwave_common::array::{Column, I64Array, Op, StreamChunk};
+use risingwave_common::catalog::{Field, Schema, TableId};
+use risingwave_common::config::StorageConfig;
+use risingwave_common::hash::Key64;
+use risingwave_common::types::DataType;
+use risingwave_expr::expr::AggKind;
+use risingwave_meta::hummock::test_utils::setup_compute_env;
+use risingwave_meta::hummock::MockHummockMetaClient;
+use risingwave_storage::memory::MemoryStateStore;
+use risingwave_storage::monitor::{ObjectStoreMetrics, StateStoreMetrics};
+use risingwave_storage::{Keyspace, StateStore, StateStoreImpl};
+use risingwave_stream::executor::aggregation::{AggArgs, AggCall};
+use risingwave_stream::executor::{
+    Barrier, BoxedMessageStream, Executor, HashAggExecutor, Message, PkIndices, PkIndicesRef,
+};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+const CHUNK_SIZE: usize = 1024;
+const CHUNK_COUNT: usize = 100;
+const GROUP_COUNT: i64 = 1000;
+
+/// Yields the given messages and then finishes.
+struct BenchSource {
+    schema: Schema,
+    pk_indices: PkIndices,
+    messages: Vec<Message>,
+}
+
+impl Executor for BenchSource {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        futures::stream::iter(self.messages.into_iter().map(Ok)).boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        "BenchSource"
+    }
+}
+
+fn gen_chunks() -> Vec<StreamChunk> {
+    (0..CHUNK_COUNT)
+        .map(|chunk_idx| {
+            let values = (0..CHUNK_SIZE)
+                .map(|i| Some((chunk_idx * CHUNK_SIZE + i) as i64))
+                .collect::<Vec<_>>();
+            let keys = values
+                .iter()
+                .map(|v| v.map(|v| v % GROUP_COUNT))
+                .collect::<Vec<_>>();
+            let columns = vec![
+                Column::new(Arc::new(I64Array::from_slice(&keys).unwrap().into())),
+                Column::new(Arc::new(I64Array::from_slice(&values).unwrap().into())),
+            ];
+            StreamChunk::new(vec![Op::Insert; CHUNK_SIZE], columns, None)
+        })
+        .collect()
+}
+
+/// `SELECT count(*), count(v), sum(v) FROM t GROUP BY k`
+fn gen_agg_calls() -> Vec<AggCall> {
+    vec![
+        AggCall {
+            kind: AggKind::RowCount,
+            args: AggArgs::None,
+            return_type: DataType::Int64,
+            append_only: false,
+        },
+        AggCall {
+            kind: AggKind::Count,
+            args: AggArgs::Unary(DataType::Int64, 1),
+            return_type: DataType::Int64,
+            append_only: false,
+        },
+        AggCall {
+            kind: AggKind::Sum,
+            args: AggArgs::Unary(DataType::Int64, 1),
+            return_type: DataType::Int64,
+            append_only: false,
+        },
+    ]
+}
+
+/// Feeds the chunks into a hash agg with a barrier after every 10 chunks, and drains the output.
+async fn run_hash_agg<S: StateStore>(store: S, chunks: &[StreamChunk], epoch: &mut u64) {
+    let schema = Schema {
+        fields: vec![
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ],
+    };
+    let mut messages = vec![];
+    for (i, chunk) in chunks.iter().enumerate() {
+        if i % 10 == 0 {
+            *epoch += 1;
+            messages.push(Message::Barrier(Barrier::new_test_barrier(*epoch)));
+        }
+        messages.push(Message::Chunk(chunk.clone()));
+    }
+    *epoch += 1;
+    messages.push(Message::Barrier(Barrier::new_test_barrier(*epoch)));
+    let source = BenchSource {
+        schema,
+        pk_indices: vec![],
+        messages,
+    };
+
+    let agg_calls = gen_agg_calls();
+    let keyspace = (0..agg_calls.len())
+        .map(|idx| Keyspace::table_root(store.clone(), &TableId::new(idx as u32)))
+        .collect();
+    let hash_agg = HashAggExecutor::<Key64, S>::new(
+        Box::new(source),
+        agg_calls,
+        keyspace,
+        vec![],
+        1,
+        1,
+        vec![0],
+    )
+    .unwrap();
+    let mut stream = Box::new(hash_agg).execute();
+    while let Some(msg) = stream.next().await {
+        msg.unwrap();
+    }
+}
+
+async fn create_hummock_store(dir: &TempDir) -> StateStoreImpl {
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref,
+        worker_node.id,
+    ));
+    let config = StorageConfig {
+        shared_buffer_capacity_mb: 1024,
+        write_conflict_detection_enabled: false,
+        disable_remote_compactor: true,
+        ..Default::default()
+    };
+    StateStoreImpl::new(
+        &format!("hummock+disk://{}", dir.path().to_str().unwrap()),
+        Arc::new(config),
+        meta_client,
+        Arc::new(StateStoreMetrics::unused()),
+        Arc::new(ObjectStoreMetrics::unused()),
+    )
+    .await
+    .unwrap()
+}
+
+fn bench_hash_agg<S: StateStore>(c: &mut Criterion, runtime: &Runtime, name: &str, store: S) {
+    let chunks = gen_chunks();
+    let mut epoch = 0;
+    let mut group = c.benchmark_group("hash agg");
+    group.throughput(Throughput::Elements((CHUNK_SIZE * CHUNK_COUNT) as u64));
+    group.bench_with_input(BenchmarkId::from_parameter(name), &chunks, |b, chunks| {
+        b.iter(|| runtime.block_on(run_hash_agg(store.clone(), chunks, &mut epoch)));
+    });
+    group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    bench_hash_agg(c, &runtime, "memory", MemoryStateStore::new());
+
+    let dir = TempDir::new().unwrap();
+    let store = runtime
+        .block_on(create_hummock_store(&dir))
+        .into_hummock_state_store()
+        .unwrap();
+    bench_hash_agg(c, &runtime, "hummock disk", store);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
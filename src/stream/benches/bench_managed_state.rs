@@ -0,0 +1,142 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::config::StorageConfig;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::ordered::{OrderedRow, OrderedRowDeserializer};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_meta::hummock::test_utils::setup_compute_env;
+use risingwave_meta::hummock::MockHummockMetaClient;
+use risingwave_storage::cell_based_row_deserializer::CellBasedRowDeserializer;
+use risingwave_storage::memory::MemoryStateStore;
+use risingwave_storage::monitor::{ObjectStoreMetrics, StateStoreMetrics};
+use risingwave_storage::{Keyspace, StateStore, StateStoreImpl};
+use risingwave_stream::executor::managed_state::top_n::ManagedTopNBottomNState;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+const ROW_COUNT: usize = 1000;
+
+fn create_managed_top_n_bottom_n_state<S: StateStore>(store: S) -> ManagedTopNBottomNState<S> {
+    let data_types = vec![DataType::Varchar, DataType::Int64];
+    let order_types = vec![OrderType::Descending, OrderType::Ascending];
+    let ordered_row_deserializer = OrderedRowDeserializer::new(data_types.clone(), order_types);
+    let table_column_descs = data_types
+        .iter()
+        .enumerate()
+        .map(|(id, data_type)| ColumnDesc::unnamed(ColumnId::from(id as i32), data_type.clone()))
+        .collect();
+    let cell_based_row_deserializer = CellBasedRowDeserializer::new(table_column_descs);
+
+    ManagedTopNBottomNState::new(
+        None,
+        0,
+        Keyspace::executor_root(store, 0x2333),
+        data_types,
+        ordered_row_deserializer,
+        cell_based_row_deserializer,
+    )
+}
+
+fn gen_rows() -> Vec<(OrderedRow, Row)> {
+    let order_types = [OrderType::Descending, OrderType::Ascending];
+    (0..ROW_COUNT)
+        .map(|i| {
+            let row = Row(vec![
+                Some(ScalarImpl::Utf8(format!("key_{}", i % 17))),
+                Some(ScalarImpl::Int64(i as i64)),
+            ]);
+            (OrderedRow::new(row.clone(), &order_types), row)
+        })
+        .collect()
+}
+
+async fn create_hummock_store(dir: &TempDir) -> StateStoreImpl {
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref,
+        worker_node.id,
+    ));
+    let config = StorageConfig {
+        shared_buffer_capacity_mb: 1024,
+        write_conflict_detection_enabled: false,
+        disable_remote_compactor: true,
+        ..Default::default()
+    };
+    StateStoreImpl::new(
+        &format!("hummock+disk://{}", dir.path().to_str().unwrap()),
+        Arc::new(config),
+        meta_client,
+        Arc::new(StateStoreMetrics::unused()),
+        Arc::new(ObjectStoreMetrics::unused()),
+    )
+    .await
+    .unwrap()
+}
+
+/// Inserts all rows and flushes them, then deletes all of them and flushes again.
+fn bench_top_n_bottom_n_state<S: StateStore>(
+    c: &mut Criterion,
+    runtime: &Runtime,
+    name: &str,
+    store: S,
+) {
+    let rows = gen_rows();
+    let mut state = create_managed_top_n_bottom_n_state(store);
+    let mut epoch = 0;
+    c.bench_with_input(
+        BenchmarkId::new("managed top n bottom n state", name),
+        &rows,
+        |b, rows| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    epoch += 1;
+                    for (key, row) in rows {
+                        state.insert(key.clone(), row.clone()).await;
+                    }
+                    state.flush(epoch).await.unwrap();
+
+                    epoch += 1;
+                    for (key, _) in rows {
+                        state.delete(key, epoch).await.unwrap();
+                    }
+                    state.flush(epoch).await.unwrap();
+                    assert_eq!(state.total_count(), 0);
+                })
+            });
+        },
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    bench_top_n_bottom_n_state(c, &runtime, "memory", MemoryStateStore::new());
+
+    let dir = TempDir::new().unwrap();
+    let store = runtime
+        .block_on(create_hummock_store(&dir))
+        .into_hummock_state_store()
+        .unwrap();
+    bench_top_n_bottom_n_state(c, &runtime, "hummock disk", store);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
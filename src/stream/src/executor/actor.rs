@@ -19,12 +19,13 @@ use futures::pin_mut;
 use madsim::time::Instant;
 use parking_lot::Mutex;
 use risingwave_common::error::Result;
+use risingwave_common::util::epoch::INVALID_EPOCH;
 use tokio_stream::StreamExt;
 use tracing_futures::Instrument;
 
 use super::monitor::StreamingMetrics;
 use super::{Message, StreamConsumer};
-use crate::task::{ActorId, SharedContext};
+use crate::task::{ActorId, SharedContext, StackTrace};
 
 pub struct OperatorInfo {
     pub operator_id: u64,
@@ -152,8 +153,19 @@ where
         let stream = Box::new(self.consumer).execute();
         pin_mut!(stream);
 
+        // The epoch whose data is being processed, i.e., the current epoch of the last barrier.
+        let mut epoch = INVALID_EPOCH;
+
         // Drive the streaming task with an infinite loop
-        while let Some(barrier) = stream.next().instrument(span).await.transpose()? {
+        while let Some(barrier) = stream
+            .next()
+            .instrument(span)
+            .stack_trace(format!("Epoch {}", epoch))
+            .await
+            .transpose()?
+        {
+            epoch = barrier.epoch.curr;
+
             {
                 // Calculate metrics
                 let prev_epoch = barrier.epoch.prev;
@@ -173,4 +173,56 @@ impl<S: StateStore> AggState<S> {
 
         Ok(appended)
     }
+
+    /// Build changes for [`crate::executor::SimpleAggExecutor`], whose output always consists of
+    /// exactly one row regardless of the row count. If the row has not been emitted yet, an
+    /// `Insert` of the current outputs is built. Otherwise, an `UpdateDelete` and `UpdateInsert`
+    /// pair is built, or nothing if the outputs are unchanged.
+    ///
+    /// Returns how many rows are appended in builders.
+    pub async fn build_single_row_changes(
+        &mut self,
+        builders: &mut [ArrayBuilderImpl],
+        new_ops: &mut Vec<Op>,
+        row_emitted: bool,
+        epoch: u64,
+    ) -> Result<usize> {
+        let prev_states = match self.prev_states.take() {
+            Some(prev_states) => prev_states,
+            None => return Ok(0),
+        };
+
+        let mut cur_states = Vec::with_capacity(self.managed_states.len());
+        for state in &mut self.managed_states {
+            cur_states.push(state.get_output(epoch).await?);
+        }
+
+        let appended = if !row_emitted {
+            new_ops.push(Op::Insert);
+            for (builder, cur_state) in builders.iter_mut().zip_eq(cur_states.iter()) {
+                trace!("append_datum (first emit): {:?}", cur_state);
+                builder.append_datum(cur_state)?;
+            }
+            1
+        } else if prev_states == cur_states {
+            0
+        } else {
+            new_ops.push(Op::UpdateDelete);
+            new_ops.push(Op::UpdateInsert);
+            for (builder, prev_state, cur_state) in
+                itertools::multizip((builders.iter_mut(), prev_states.iter(), cur_states.iter()))
+            {
+                trace!(
+                    "append_datum (update): prev = {:?}, cur = {:?}",
+                    prev_state,
+                    cur_state
+                );
+                builder.append_datum(prev_state)?;
+                builder.append_datum(cur_state)?;
+            }
+            2
+        };
+
+        Ok(appended)
+    }
 }
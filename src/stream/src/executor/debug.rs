@@ -16,25 +16,45 @@ use std::sync::Arc;
 
 use futures::StreamExt;
 use risingwave_common::catalog::Schema;
+use risingwave_common::util::epoch::INVALID_EPOCH;
+use risingwave_expr::expr::WithEpoch;
 
 use super::monitor::StreamingMetrics;
 use super::{
-    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, MessageStream, PkIndicesRef,
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, MessageStream, PkIndicesRef,
 };
-use crate::task::ActorId;
+use crate::task::{ActorId, CacheUsage};
 
 mod epoch_check;
+mod executor_metrics;
 mod schema_check;
 mod trace;
 mod update_check;
 
+pub use executor_metrics::ExecutorLabels;
+
 struct DebugExtraInfo {
     input_pos: usize,
     actor_id: ActorId,
+    labels: ExecutorLabels,
+    downstream_labels: Option<ExecutorLabels>,
+    cache_usage: Option<CacheUsage>,
     metrics: Arc<StreamingMetrics>,
+    stack_trace: bool,
+}
+
+/// Evaluates the expressions of the executor producing `stream`, e.g. `now()`, against the epoch of
+/// the last barrier it has passed on, which is the epoch of the chunks it's processing.
+pub(crate) fn with_barrier_epoch(stream: BoxedMessageStream) -> BoxedMessageStream {
+    WithEpoch::new(INVALID_EPOCH, stream, |message| match message {
+        Ok(Message::Barrier(barrier)) => Some(barrier.epoch.curr),
+        _ => None,
+    })
+    .boxed()
 }
 
-/// [`DebugExecutor`] will do some sanity checks and logging for the wrapped executor.
+/// [`DebugExecutor`] will do some sanity checks and logging for the wrapped executor. It also
+/// provides the epoch for the expressions evaluated by the wrapped executor.
 pub struct DebugExecutor {
     input: BoxedExecutor,
 
@@ -46,14 +66,22 @@ impl DebugExecutor {
         input: BoxedExecutor,
         input_pos: usize,
         actor_id: ActorId,
+        labels: ExecutorLabels,
+        downstream_labels: Option<ExecutorLabels>,
+        cache_usage: Option<CacheUsage>,
         metrics: Arc<StreamingMetrics>,
+        stack_trace: bool,
     ) -> Self {
         Self {
             input,
             extra: DebugExtraInfo {
                 input_pos,
                 actor_id,
+                labels,
+                downstream_labels,
+                cache_usage,
                 metrics,
+                stack_trace,
             },
         }
     }
@@ -64,6 +92,15 @@ impl DebugExecutor {
         extra: DebugExtraInfo,
         stream: impl MessageStream + 'static,
     ) -> impl MessageStream + 'static {
+        // Executor metrics
+        let stream = executor_metrics::executor_metrics(
+            &extra.labels,
+            extra.downstream_labels.as_ref(),
+            extra.cache_usage,
+            &extra.metrics,
+            stream,
+        );
+
         // Trace
         let stream = trace::trace(
             info.clone(),
@@ -89,6 +126,15 @@ impl DebugExecutor {
         extra: DebugExtraInfo,
         stream: impl MessageStream + 'static,
     ) -> impl MessageStream + 'static {
+        // Executor metrics
+        let stream = executor_metrics::executor_metrics(
+            &extra.labels,
+            extra.downstream_labels.as_ref(),
+            extra.cache_usage,
+            &extra.metrics,
+            stream,
+        );
+
         // Metrics
         let stream = trace::metrics(extra.actor_id, extra.metrics, stream);
 
@@ -103,11 +149,19 @@ impl DebugExecutor {
         extra: DebugExtraInfo,
         stream: impl MessageStream + 'static,
     ) -> BoxedMessageStream {
-        if cfg!(debug_assertions) {
-            Self::wrap_debug(info, extra, stream).boxed()
+        let stack_trace = extra.stack_trace;
+        let stream = if cfg!(debug_assertions) {
+            Self::wrap_debug(info.clone(), extra, stream).boxed()
         } else {
-            Self::wrap_release(info, extra, stream).boxed()
-        }
+            Self::wrap_release(info.clone(), extra, stream).boxed()
+        };
+        // Stack trace
+        let stream = if stack_trace {
+            trace::stack_trace(info, stream).boxed()
+        } else {
+            stream
+        };
+        with_barrier_epoch(stream)
     }
 }
 
@@ -0,0 +1,141 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use pin_project::pin_project;
+use prometheus::core::{AtomicI64, AtomicU64, GenericCounter, GenericGauge};
+use prometheus::Histogram;
+
+use crate::executor::monitor::StreamingMetrics;
+use crate::executor::{Message, MessageStream, MessageStreamItem};
+use crate::task::CacheUsage;
+
+thread_local! {
+    /// For each executor being polled on this thread, from the outermost to the innermost, the
+    /// time spent on polling its inputs so far. As the executors of an actor poll their inputs
+    /// in place, this is how an executor tells its own processing time from that of its inputs.
+    static INPUT_POLL_TIME: RefCell<Vec<Duration>> = RefCell::new(Vec::new());
+}
+
+/// The label values of the metrics of an executor. See
+/// [`crate::executor::monitor::EXECUTOR_LABELS`].
+pub struct ExecutorLabels {
+    pub table_id: String,
+    pub fragment_id: String,
+    pub actor_id: String,
+    pub executor_id: String,
+}
+
+impl ExecutorLabels {
+    fn values(&self) -> [&str; 4] {
+        [
+            &self.table_id,
+            &self.fragment_id,
+            &self.actor_id,
+            &self.executor_id,
+        ]
+    }
+}
+
+/// Streams wrapped by `executor_metrics` will update the metrics of the executor producing the
+/// stream, as well as the input row count of the executor consuming it.
+#[pin_project]
+pub struct ExecutorMetricsStream<S> {
+    #[pin]
+    input: S,
+
+    output_row_count: GenericCounter<AtomicU64>,
+    /// The input row count of the downstream executor. `None` for the root executor of an actor.
+    downstream_input_row_count: Option<GenericCounter<AtomicU64>>,
+    processing_time: Histogram,
+    cache_entries: Option<(CacheUsage, GenericGauge<AtomicI64>)>,
+
+    /// The processing time since the latest chunk is produced.
+    elapsed: Duration,
+}
+
+pub fn executor_metrics<S: MessageStream>(
+    labels: &ExecutorLabels,
+    downstream_labels: Option<&ExecutorLabels>,
+    cache_usage: Option<CacheUsage>,
+    metrics: &StreamingMetrics,
+    input: S,
+) -> ExecutorMetricsStream<S> {
+    let values = labels.values();
+    ExecutorMetricsStream {
+        input,
+        output_row_count: metrics.executor_output_row_count.with_label_values(&values),
+        downstream_input_row_count: downstream_labels.map(|labels| {
+            metrics
+                .executor_input_row_count
+                .with_label_values(&labels.values())
+        }),
+        processing_time: metrics.executor_processing_time.with_label_values(&values),
+        cache_entries: cache_usage.map(|usage| {
+            (
+                usage,
+                metrics.executor_cache_entries.with_label_values(&values),
+            )
+        }),
+        elapsed: Duration::ZERO,
+    }
+}
+
+impl<S: MessageStream> Stream for ExecutorMetricsStream<S> {
+    type Item = MessageStreamItem;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        INPUT_POLL_TIME.with(|stack| stack.borrow_mut().push(Duration::ZERO));
+        let start = Instant::now();
+        let poll = this.input.poll_next(cx);
+        let total = start.elapsed();
+        let input_time = INPUT_POLL_TIME.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let input_time = stack.pop().unwrap();
+            // We're an input of the executor polling us.
+            if let Some(downstream_input_time) = stack.last_mut() {
+                *downstream_input_time += total;
+            }
+            input_time
+        });
+        *this.elapsed += total.saturating_sub(input_time);
+
+        match &poll {
+            Poll::Ready(Some(Ok(Message::Chunk(chunk)))) => {
+                let cardinality = chunk.cardinality() as u64;
+                this.output_row_count.inc_by(cardinality);
+                if let Some(counter) = this.downstream_input_row_count {
+                    counter.inc_by(cardinality);
+                }
+                this.processing_time.observe(this.elapsed.as_secs_f64());
+                *this.elapsed = Duration::ZERO;
+            }
+            Poll::Ready(Some(Ok(Message::Barrier(_)))) => {
+                if let Some((usage, gauge)) = this.cache_entries {
+                    gauge.set(usage.entries() as i64);
+                }
+            }
+            _ => {}
+        }
+
+        poll
+    }
+}
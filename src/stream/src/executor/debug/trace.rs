@@ -22,7 +22,7 @@ use tracing_futures::Instrument;
 use crate::executor::error::StreamExecutorError;
 use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{ExecutorInfo, Message, MessageStream};
-use crate::task::ActorId;
+use crate::task::{ActorId, SpanValue, StackTrace};
 
 /// Streams wrapped by `trace` will print data passing in the stream graph to stdout.
 #[try_stream(ok = Message, error = StreamExecutorError)]
@@ -82,3 +82,16 @@ pub async fn metrics(actor_id: ActorId, metrics: Arc<StreamingMetrics>, input: i
         yield message;
     }
 }
+
+/// Streams wrapped by `stack_trace` will record the executor in the async call tree of the actor
+/// while waiting for its next message.
+#[try_stream(ok = Message, error = StreamExecutorError)]
+pub async fn stack_trace(info: Arc<ExecutorInfo>, input: impl MessageStream) {
+    let span: SpanValue = info.identity.clone().into();
+
+    pin_mut!(input);
+
+    while let Some(message) = input.next().stack_trace(span.clone()).await.transpose()? {
+        yield message;
+    }
+}
@@ -30,7 +30,7 @@ use risingwave_common::util::hash_util::CRC32FastBuilder;
 use tracing::event;
 
 use crate::executor::{Barrier, BoxedExecutor, Message, Mutation, StreamConsumer};
-use crate::task::{ActorId, DispatcherId, SharedContext};
+use crate::task::{ActorId, BufferOccupancy, DispatcherId, SharedContext, StackTrace};
 
 /// `Output` provides an interface for `Dispatcher` to send data into downstream actors.
 #[async_trait]
@@ -40,13 +40,16 @@ pub trait Output: Debug + Send + Sync + 'static {
     fn actor_id(&self) -> ActorId;
 }
 
-type BoxedOutput = Box<dyn Output>;
+pub type BoxedOutput = Box<dyn Output>;
 
 /// `LocalOutput` sends data to a local `mpsc::Channel`
 pub struct LocalOutput {
     actor_id: ActorId,
 
     ch: Sender<Message>,
+
+    /// Number of messages buffered in `ch`.
+    occupancy: BufferOccupancy,
 }
 
 impl Debug for LocalOutput {
@@ -59,18 +62,34 @@ impl Debug for LocalOutput {
 
 impl LocalOutput {
     pub fn new(actor_id: ActorId, ch: Sender<Message>) -> Self {
-        Self { actor_id, ch }
+        Self {
+            actor_id,
+            ch,
+            occupancy: BufferOccupancy::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_buffer_occupancy(mut self, occupancy: BufferOccupancy) -> Self {
+        self.occupancy = occupancy;
+        self
     }
 }
 
 #[async_trait]
 impl Output for LocalOutput {
     async fn send(&mut self, message: Message) -> Result<()> {
+        // The receiver may take the message out before `send` returns, so count it in advance.
+        self.occupancy.inc();
         // local channel should never fail
         self.ch
             .send(message)
+            .stack_trace(format!("Output::send to actor {}", self.actor_id))
             .await
-            .map_err(|_| internal_error("failed to send"))?;
+            .map_err(|_| {
+                self.occupancy.dec();
+                internal_error("failed to send")
+            })?;
         Ok(())
     }
 
@@ -84,6 +103,9 @@ pub struct RemoteOutput {
     actor_id: ActorId,
 
     ch: Sender<Message>,
+
+    /// Number of messages buffered in `ch`.
+    occupancy: BufferOccupancy,
 }
 
 impl Debug for RemoteOutput {
@@ -96,7 +118,17 @@ impl Debug for RemoteOutput {
 
 impl RemoteOutput {
     pub fn new(actor_id: ActorId, ch: Sender<Message>) -> Self {
-        Self { actor_id, ch }
+        Self {
+            actor_id,
+            ch,
+            occupancy: BufferOccupancy::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_buffer_occupancy(mut self, occupancy: BufferOccupancy) -> Self {
+        self.occupancy = occupancy;
+        self
     }
 }
 
@@ -107,11 +139,17 @@ impl Output for RemoteOutput {
             Message::Chunk(chk) => Message::Chunk(chk.compact()?),
             _ => message,
         };
+        // The receiver may take the message out before `send` returns, so count it in advance.
+        self.occupancy.inc();
         // local channel should never fail
         self.ch
             .send(message)
+            .stack_trace(format!("Output::send to actor {}", self.actor_id))
             .await
-            .map_err(|_| internal_error("failed to send"))?;
+            .map_err(|_| {
+                self.occupancy.dec();
+                internal_error("failed to send")
+            })?;
         Ok(())
     }
 
@@ -127,12 +165,14 @@ pub fn new_output(
     down_id: ActorId,
 ) -> Result<Box<dyn Output>> {
     let tx = context.take_sender(&(actor_id, down_id))?;
-    if is_local_address(&addr, &context.addr) {
+    let occupancy = context.buffer_occupancy((actor_id, down_id));
+    let output = if is_local_address(&addr, &context.addr) {
         // if this is a local downstream actor
-        Ok(Box::new(LocalOutput::new(down_id, tx)) as Box<dyn Output>)
+        Box::new(LocalOutput::new(down_id, tx).with_buffer_occupancy(occupancy)) as Box<dyn Output>
     } else {
-        Ok(Box::new(RemoteOutput::new(down_id, tx)) as Box<dyn Output>)
-    }
+        Box::new(RemoteOutput::new(down_id, tx).with_buffer_occupancy(occupancy)) as Box<dyn Output>
+    };
+    Ok(output)
 }
 
 /// [`DispatchExecutor`] consumes messages and send them into downstream actors. Usually,
@@ -246,15 +286,35 @@ impl DispatchExecutorInner {
         Ok(())
     }
 
-    /// For `Stop`, update the outputs after we dispatch the barrier.
+    /// For `Stop`, update the outputs after we dispatch the barrier. For `UpdateVnodeBitmaps`,
+    /// the chunks after the barrier are routed with the new hash mappings.
     async fn post_mutate_outputs(&mut self, mutation: &Option<Arc<Mutation>>) -> Result<()> {
-        if let Some(Mutation::Stop(stops)) = mutation.as_deref() {
-            // Remove outputs only if this actor itself is not to be stopped.
-            if !stops.contains(&self.actor_id) {
+        match mutation.as_deref() {
+            Some(Mutation::Stop(stops)) => {
+                // Remove outputs only if this actor itself is not to be stopped.
+                if !stops.contains(&self.actor_id) {
+                    for dispatcher in &mut self.dispatchers {
+                        dispatcher.remove_outputs(stops);
+                    }
+                }
+            }
+
+            Some(Mutation::UpdateVnodeBitmaps {
+                dispatcher_hash_mappings,
+                ..
+            }) => {
                 for dispatcher in &mut self.dispatchers {
-                    dispatcher.remove_outputs(stops);
+                    if let DispatcherImpl::Hash(dispatcher) = dispatcher {
+                        if let Some(hash_mapping) =
+                            dispatcher_hash_mappings.get(&(self.actor_id, dispatcher.dispatcher_id))
+                        {
+                            dispatcher.hash_mapping = hash_mapping.clone();
+                        }
+                    }
                 }
             }
+
+            _ => {}
         }
 
         Ok(())
@@ -291,7 +351,10 @@ impl StreamConsumer for DispatchExecutor {
             for msg in input {
                 let msg: Message = msg?;
                 let barrier = msg.as_barrier().cloned();
-                self.inner.dispatch(msg).await?;
+                self.inner
+                    .dispatch(msg)
+                    .stack_trace("DispatchExecutor::dispatch")
+                    .await?;
                 if let Some(barrier) = barrier {
                     yield barrier;
                 }
@@ -779,6 +842,7 @@ mod tests {
     use risingwave_common::array::{Array, ArrayBuilder, I32ArrayBuilder, Op};
     use risingwave_common::catalog::Schema;
     use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+    use risingwave_common::util::hash_util::combine_hash;
     use risingwave_pb::common::{ActorInfo, HostAddress};
 
     use super::*;
@@ -1003,6 +1067,81 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_update_hash_mapping() {
+        let schema = Schema { fields: vec![] };
+        let (mut tx, rx) = channel(16);
+        let input = Box::new(ReceiverExecutor::new(
+            schema.clone(),
+            vec![],
+            rx,
+            ActorContext::create(),
+            0,
+        ));
+        let data_sinks = (0..2).map(|_| Arc::new(Mutex::new(vec![]))).collect_vec();
+        let outputs = data_sinks
+            .iter()
+            .enumerate()
+            .map(|(idx, data)| {
+                Box::new(MockOutput::new(1 + idx as u32, data.clone())) as BoxedOutput
+            })
+            .collect_vec();
+        let ctx = Arc::new(SharedContext::for_test());
+        let actor_id = 233;
+        let dispatcher_id = 666;
+
+        let executor = Box::new(DispatchExecutor::new(
+            input,
+            vec![DispatcherImpl::Hash(HashDataDispatcher::new(
+                vec![0],
+                outputs,
+                vec![0],
+                vec![1; VIRTUAL_NODE_COUNT],
+                dispatcher_id,
+            ))],
+            actor_id,
+            ctx,
+        ))
+        .execute();
+        pin_mut!(executor);
+
+        let chunk = StreamChunk::from_pretty(
+            " I
+            + 1
+            + 2
+            + 3",
+        );
+
+        tx.send(Message::Chunk(chunk.clone())).await.unwrap();
+
+        let mut dispatcher_hash_mappings = HashMap::new();
+        dispatcher_hash_mappings.insert((actor_id, dispatcher_id), vec![2; VIRTUAL_NODE_COUNT]);
+        let b1 = Barrier::new_test_barrier(1).with_mutation(Mutation::UpdateVnodeBitmaps {
+            actor_vnode_bitmaps: HashMap::new(),
+            dispatcher_hash_mappings,
+        });
+        tx.send(Message::Barrier(b1)).await.unwrap();
+        executor.next().await.unwrap().unwrap();
+
+        tx.send(Message::Chunk(chunk)).await.unwrap();
+        tx.send(Message::Barrier(Barrier::new_test_barrier(2)))
+            .await
+            .unwrap();
+        executor.next().await.unwrap().unwrap();
+
+        // All rows go to actor 1 before the barrier and to actor 2 after it.
+        let messages = data_sinks[0].lock().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0], Message::Chunk(_)));
+        assert!(matches!(messages[1], Message::Barrier(_)));
+        assert!(matches!(messages[2], Message::Barrier(_)));
+        let messages = data_sinks[1].lock().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0], Message::Barrier(_)));
+        assert!(matches!(messages[1], Message::Chunk(_)));
+        assert!(matches!(messages[2], Message::Barrier(_)));
+    }
+
     #[tokio::test]
     async fn test_hash_dispatcher() {
         let num_outputs = 5; // actor id ranges from 1 to 5
@@ -1048,15 +1187,15 @@ mod tests {
         let mut output_ops = vec![vec![]; num_outputs];
         for op in &ops {
             let hash_builder = CRC32FastBuilder {};
-            let mut hasher = hash_builder.build_hasher();
             let one_row = (0..dimension).map(|_| start.next().unwrap()).collect_vec();
+            let mut hash = 0;
             for key_idx in key_indices.iter() {
                 let val = one_row[*key_idx];
-                let bytes = val.to_le_bytes();
-                hasher.update(&bytes);
+                let mut hasher = hash_builder.build_hasher();
+                hasher.update(&val.to_le_bytes());
+                hash = combine_hash(hash, hasher.finish());
             }
-            let output_idx =
-                hash_mapping[hasher.finish() as usize % VIRTUAL_NODE_COUNT] as usize - 1;
+            let output_idx = hash_mapping[hash as usize % VIRTUAL_NODE_COUNT] as usize - 1;
             for (builder, val) in builders.iter_mut().zip_eq(one_row.iter()) {
                 builder.append(Some(*val)).unwrap();
             }
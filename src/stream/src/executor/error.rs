@@ -53,11 +53,17 @@ enum StreamExecutorErrorInner {
     #[error("Source error: {0}")]
     SourceError(RwError),
 
+    #[error("Sink error: {0}")]
+    SinkError(anyhow::Error),
+
     #[error("Channel `{0}` closed")]
     ChannelClosed(String),
 
     #[error("Failed to align barrier: expected {0:?} but got {1:?}")]
     AlignBarrier(Box<Barrier>, Box<Barrier>),
+
+    #[error("Inconsistent op: {0}")]
+    InconsistentOp(String),
 }
 
 impl StreamExecutorError {
@@ -93,6 +99,10 @@ impl StreamExecutorError {
         StreamExecutorErrorInner::SourceError(error.into()).into()
     }
 
+    pub fn sink_error(error: impl Into<anyhow::Error>) -> Self {
+        StreamExecutorErrorInner::SinkError(error.into()).into()
+    }
+
     pub fn channel_closed(name: impl Into<String>) -> Self {
         StreamExecutorErrorInner::ChannelClosed(name.into()).into()
     }
@@ -104,6 +114,10 @@ impl StreamExecutorError {
     pub fn invalid_argument(error: impl Into<String>) -> Self {
         StreamExecutorErrorInner::InvalidArgument(error.into()).into()
     }
+
+    pub fn inconsistent_op(error: impl Into<String>) -> Self {
+        StreamExecutorErrorInner::InconsistentOp(error.into()).into()
+    }
 }
 
 #[derive(Error)]
@@ -14,9 +14,8 @@
 
 use std::fmt::{Debug, Formatter};
 
-use itertools::Itertools;
-use risingwave_common::array::{Array, ArrayImpl, DataChunk, Op, StreamChunk, Vis};
-use risingwave_common::buffer::BitmapBuilder;
+use risingwave_common::array::{ArrayImpl, DataChunk, Op, StreamChunk, Vis};
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::Schema;
 use risingwave_expr::expr::BoxedExpression;
 
@@ -42,7 +41,8 @@ impl FilterExecutor {
 /// `FilterExecutor` filters data with the `expr`. The `expr` takes a chunk of data,
 /// and returns a boolean array on whether each item should be retained. And then,
 /// `FilterExecutor` will insert, delete or update element into next executor according
-/// to the result of the expression.
+/// to the result of the expression. An update pair of which only one side passes the filter is
+/// degraded to a standalone `Insert` or `Delete`.
 pub struct SimpleFilterExecutor {
     info: ExecutorInfo,
 
@@ -90,74 +90,31 @@ impl SimpleExecutor for SimpleFilterExecutor {
         let (columns, vis) = data_chunk.into_parts();
 
         let n = ops.len();
-
-        // TODO: Can we update ops and visibility inplace?
-        let mut new_ops = Vec::with_capacity(n);
-        let mut new_visibility = BitmapBuilder::with_capacity(n);
-        let mut last_res = false;
-
         assert!(match vis {
             Vis::Compact(c) => c == n,
             Vis::Bitmap(ref m) => m.len() == n,
         });
-        assert!(matches!(&*pred_output, ArrayImpl::Bool(_)));
 
-        if let ArrayImpl::Bool(bool_array) = &*pred_output {
-            for (op, res) in ops.into_iter().zip_eq(bool_array.iter()) {
-                // SAFETY: ops.len() == pred_output.len() == visibility.len()
-                let res = res.unwrap_or(false);
-                match op {
-                    Op::Insert | Op::Delete => {
-                        new_ops.push(op);
-                        if res {
-                            new_visibility.append(true);
-                        } else {
-                            new_visibility.append(false);
-                        }
-                    }
-                    Op::UpdateDelete => {
-                        last_res = res;
-                    }
-                    Op::UpdateInsert => match (last_res, res) {
-                        (true, false) => {
-                            new_ops.push(Op::Delete);
-                            new_ops.push(Op::UpdateInsert);
-                            new_visibility.append(true);
-                            new_visibility.append(false);
-                        }
-                        (false, true) => {
-                            new_ops.push(Op::UpdateDelete);
-                            new_ops.push(Op::Insert);
-                            new_visibility.append(false);
-                            new_visibility.append(true);
-                        }
-                        (true, true) => {
-                            new_ops.push(Op::UpdateDelete);
-                            new_ops.push(Op::UpdateInsert);
-                            new_visibility.append(true);
-                            new_visibility.append(true);
-                        }
-                        (false, false) => {
-                            new_ops.push(Op::UpdateDelete);
-                            new_ops.push(Op::UpdateInsert);
-                            new_visibility.append(false);
-                            new_visibility.append(false);
-                        }
-                    },
-                }
+        // The visibility is simply the predicate, which is computed on the bitmaps of the output
+        // at once.
+        let new_visibility = match &*pred_output {
+            ArrayImpl::Bool(bool_array) => {
+                Bitmap::try_from(bool_array).map_err(StreamExecutorError::eval_error)?
             }
-        } else {
-            panic!("unmatched type: filter expr returns a non-null array");
+            _ => panic!("unmatched type: filter expr returns a non-null array"),
+        };
+        if new_visibility.num_high_bits() == 0 {
+            return Ok(None);
         }
 
-        let new_visibility = new_visibility.finish();
+        let mut new_ops = ops;
+        degrade_straddling_updates(&mut new_ops, &new_visibility);
 
-        Ok(if new_visibility.num_high_bits() > 0 {
-            let new_chunk = StreamChunk::new(new_ops, columns, Some(new_visibility));
-            Some(new_chunk)
-        } else {
-            None
-        })
+        Ok(Some(StreamChunk::new(
+            new_ops,
+            columns,
+            Some(new_visibility),
+        )))
     }
 
     fn schema(&self) -> &Schema {
@@ -173,6 +130,31 @@ impl SimpleExecutor for SimpleFilterExecutor {
     }
 }
 
+/// Degrades the update pairs of which only one side passes the filter to standalone ops, i.e.,
+/// the visible `UpdateDelete` to `Delete` or the visible `UpdateInsert` to `Insert`. An update
+/// whose other side is not in this chunk is degraded as well, since its pair can't be checked.
+fn degrade_straddling_updates(ops: &mut [Op], visibility: &Bitmap) {
+    let visible = |i: usize| visibility.is_set(i).unwrap();
+    let mut i = 0;
+    while i < ops.len() {
+        match ops[i] {
+            Op::UpdateDelete if i + 1 < ops.len() && ops[i + 1] == Op::UpdateInsert => {
+                match (visible(i), visible(i + 1)) {
+                    (true, false) => ops[i] = Op::Delete,
+                    (false, true) => ops[i + 1] = Op::Insert,
+                    _ => {}
+                }
+                i += 2;
+                continue;
+            }
+            Op::UpdateDelete => ops[i] = Op::Delete,
+            Op::UpdateInsert => ops[i] = Op::Insert,
+            Op::Insert | Op::Delete => {}
+        }
+        i += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use futures::StreamExt;
@@ -208,13 +190,21 @@ mod tests {
             U- 3 5  // false -> false
             U+ 4 6  // expect nothing",
         );
+        let chunk3 = StreamChunk::from_pretty(
+            "  I I
+            U+ 7 5  // the other side is in the previous chunk, expect Insert
+            U- 3 5  // false -> true
+            U+ 5 3  // expect Insert
+            U- 5 3  // the other side is in the next chunk, expect Delete",
+        );
         let schema = Schema {
             fields: vec![
                 Field::unnamed(DataType::Int64),
                 Field::unnamed(DataType::Int64),
             ],
         };
-        let source = MockSource::with_chunks(schema, PkIndices::new(), vec![chunk1, chunk2]);
+        let source =
+            MockSource::with_chunks(schema, PkIndices::new(), vec![chunk1, chunk2, chunk3]);
 
         let left_expr = InputRefExpression::new(DataType::Int64, 0);
         let right_expr = InputRefExpression::new(DataType::Int64, 1);
@@ -255,6 +245,18 @@ mod tests {
             )
         );
 
+        let chunk = filter.next().await.unwrap().unwrap().into_chunk().unwrap();
+        assert_eq!(
+            chunk,
+            StreamChunk::from_pretty(
+                "  I I
+                +  7 5
+                U- 3 5 D
+                +  5 3
+                -  5 3",
+            )
+        );
+
         assert!(filter.next().await.unwrap().unwrap().is_stop());
     }
 }
@@ -21,7 +21,11 @@ use risingwave_common::array::column::Column;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
 use risingwave_common::error::Result;
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::ordered::serialize_column_id;
 use risingwave_common::util::sort_util::OrderType;
+use risingwave_common::util::value_encoding::serialize_cell;
+use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::{Keyspace, StateStore};
 
@@ -31,7 +35,9 @@ use crate::executor::aggregation::{
     AggState,
 };
 use crate::executor::error::StreamExecutorError;
+use crate::executor::managed_state::state_version::with_version_column;
 use crate::executor::{BoxedMessageStream, Message, PkIndices};
+use crate::task::StackTrace;
 
 /// `SimpleAggExecutor` is the aggregation operator for streaming system.
 /// To create an aggregation operator, states and expressions should be passed along the
@@ -46,6 +52,11 @@ use crate::executor::{BoxedMessageStream, Message, PkIndices};
 /// Current `SimpleAggExecutor` will only emit one row for a whole chunk.
 /// Therefore, we "automatically" implemented a window function inside
 /// `SimpleAggExecutor`.
+///
+/// The output of `SimpleAggExecutor` always consists of a single row. The first time it's
+/// emitted, it's an `Insert`, and all later changes are `UpdateDelete` and `UpdateInsert` pairs.
+/// Whether the row has been emitted is persisted along with the row count state, so that an
+/// executor recovered from a checkpoint won't insert the row again and duplicate it downstream.
 pub struct SimpleAggExecutor<S: StateStore> {
     input: Box<dyn Executor>,
     info: ExecutorInfo,
@@ -74,8 +85,17 @@ pub struct SimpleAggExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Whether the result row has been emitted. This is `None` until loaded from the state
+    /// store on the first flush.
+    result_row_emitted: Option<bool>,
 }
 
+/// The cell id under which the "result row emitted" flag is stored, in the keyspace of the row
+/// count state. The row count state has an empty pk, so the flag is stored as another cell of
+/// its only row, which is ignored when the row count is read.
+const RESULT_ROW_EMITTED_CELL_ID: ColumnId = ColumnId::new(-2);
+
 impl<S: StateStore> Executor for SimpleAggExecutor<S> {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
         self.execute_inner().boxed()
@@ -111,10 +131,10 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         for (agg_call, ks) in agg_calls.iter().zip_eq(&keyspace) {
             let state_table = StateTable::new(
                 ks.clone(),
-                vec![ColumnDesc::unnamed(
+                with_version_column(vec![ColumnDesc::unnamed(
                     ColumnId::new(0),
                     agg_call.return_type.clone(),
-                )],
+                )]),
                 // Primary key do not includes group key.
                 vec![OrderType::Descending; get_key_len(agg_call)],
                 None,
@@ -136,6 +156,7 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             agg_calls,
             key_indices,
             state_tables,
+            result_row_emitted: None,
         })
     }
 
@@ -209,6 +230,7 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         keyspace: &[Keyspace<S>],
         epoch: u64,
         state_tables: &mut [StateTable<S>],
+        result_row_emitted: &mut Option<bool>,
     ) -> StreamExecutorResult<Option<StreamChunk>> {
         // The state store of each keyspace is the same so just need the first.
         let store = keyspace[0].state_store();
@@ -221,7 +243,27 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             _ => return Ok(None), // Nothing to flush.
         };
 
+        let flag_key = serialize_column_id(&RESULT_ROW_EMITTED_CELL_ID)
+            .map_err(StreamExecutorError::agg_state_error)?;
+        let row_emitted = match *result_row_emitted {
+            Some(row_emitted) => row_emitted,
+            None => keyspace[0]
+                .get(&flag_key, epoch)
+                .await
+                .map_err(StreamExecutorError::agg_state_error)?
+                .is_some(),
+        };
+
         let mut write_batch = store.start_write_batch();
+        if !row_emitted {
+            // Persist the flag together with the states, as the row will be emitted in this
+            // epoch.
+            let flag_value = serialize_cell(&Some(ScalarImpl::Bool(true)))
+                .map_err(StreamExecutorError::agg_state_error)?;
+            write_batch
+                .prefixify(&keyspace[0])
+                .put(&flag_key, StorageValue::new_default_put(flag_value));
+        }
         for (state, state_table) in states
             .managed_states
             .iter_mut()
@@ -234,12 +276,16 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         }
         write_batch
             .ingest(epoch)
+            .stack_trace("WriteBatch::ingest")
             .await
             .map_err(StreamExecutorError::agg_state_error)?;
 
         // Batch commit state tables.
         for state_table in state_tables.iter_mut() {
-            state_table.commit(epoch).await?;
+            state_table
+                .commit(epoch)
+                .stack_trace("StateTable::commit")
+                .await?;
         }
 
         // --- Create array builders ---
@@ -251,10 +297,14 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         let mut new_ops = Vec::with_capacity(2);
 
         // --- Retrieve modified states and put the changes into the builders ---
-        states
-            .build_changes(&mut builders, &mut new_ops, epoch)
+        let appended = states
+            .build_single_row_changes(&mut builders, &mut new_ops, row_emitted, epoch)
             .await
             .map_err(StreamExecutorError::agg_state_error)?;
+        *result_row_emitted = Some(true);
+        if appended == 0 {
+            return Ok(None);
+        }
 
         let columns: Vec<Column> = builders
             .into_iter()
@@ -279,6 +329,7 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             agg_calls,
             key_indices: _,
             mut state_tables,
+            mut result_row_emitted,
         } = self;
         let mut input = input.execute();
 
@@ -305,12 +356,15 @@ impl<S: StateStore> SimpleAggExecutor<S> {
                 }
                 Message::Barrier(barrier) => {
                     let next_epoch = barrier.epoch.curr;
+                    // The states are flushed on every barrier, not only on checkpoints, as the
+                    // outputs are produced from the flushed states.
                     if let Some(chunk) = Self::flush_data(
                         &info.schema,
                         &mut states,
                         &keyspace,
                         epoch,
                         &mut state_tables,
+                        &mut result_row_emitted,
                     )
                     .await?
                     {
@@ -432,4 +486,87 @@ mod tests {
             )
         );
     }
+
+    #[tokio::test]
+    async fn test_simple_aggregation_recovery() {
+        let keyspace = create_in_memory_keyspace_agg(2);
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                // primary key column
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let append_only = false;
+        let agg_calls = vec![
+            AggCall {
+                kind: AggKind::RowCount,
+                args: AggArgs::None,
+                return_type: DataType::Int64,
+                append_only,
+            },
+            AggCall {
+                kind: AggKind::Sum,
+                args: AggArgs::Unary(DataType::Int64, 0),
+                return_type: DataType::Int64,
+                append_only,
+            },
+        ];
+
+        let (mut tx, source) = MockSource::channel(schema.clone(), vec![1]);
+        tx.push_barrier(1, false);
+        tx.push_chunk(StreamChunk::from_pretty(
+            "  I    I
+            + 10 1001
+            + 20 1002",
+        ));
+        tx.push_barrier(2, false);
+        let simple_agg = Box::new(
+            SimpleAggExecutor::new(
+                Box::new(source),
+                agg_calls.clone(),
+                keyspace.clone(),
+                vec![],
+                1,
+                vec![],
+            )
+            .unwrap(),
+        );
+        let mut simple_agg = simple_agg.execute();
+        simple_agg.next().await.unwrap().unwrap();
+        let msg = simple_agg.next().await.unwrap().unwrap();
+        assert_eq!(
+            *msg.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I  I
+                + 2 30"
+            )
+        );
+
+        // A recovered executor updates the row emitted before, instead of inserting it again,
+        // even if all the rows are deleted.
+        let (mut tx, source) = MockSource::channel(schema, vec![1]);
+        tx.push_barrier(3, false);
+        tx.push_chunk(StreamChunk::from_pretty(
+            "  I    I
+            - 10 1001
+            - 20 1002",
+        ));
+        tx.push_barrier(4, false);
+        let simple_agg = Box::new(
+            SimpleAggExecutor::new(Box::new(source), agg_calls, keyspace, vec![], 1, vec![])
+                .unwrap(),
+        );
+        let mut simple_agg = simple_agg.execute();
+        simple_agg.next().await.unwrap().unwrap();
+        let msg = simple_agg.next().await.unwrap().unwrap();
+        assert_eq!(
+            *msg.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I  I
+                U- 2 30
+                U+ 0  0"
+            )
+        );
+    }
 }
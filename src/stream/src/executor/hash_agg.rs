@@ -19,7 +19,7 @@ use futures::{stream, StreamExt};
 use futures_async_stream::try_stream;
 use iter_chunks::IterChunks;
 use itertools::Itertools;
-use madsim::collections::HashMap;
+use madsim::collections::{HashMap, HashSet};
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{StreamChunk, Vis};
 use risingwave_common::buffer::Bitmap;
@@ -27,6 +27,7 @@ use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
 use risingwave_common::collection::evictable::EvictableHashMap;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::hash::{HashCode, HashKey};
+use risingwave_common::util::bit_util::get_bit;
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_storage::table::state_table::StateTable;
@@ -41,7 +42,10 @@ use crate::executor::aggregation::{
     AggState,
 };
 use crate::executor::error::StreamExecutorError;
+use crate::executor::managed_state::state_version::with_version_column;
+use crate::executor::managed_state::ManagedCache;
 use crate::executor::{BoxedMessageStream, Message, PkIndices, PROCESSING_WINDOW_SIZE};
+use crate::task::{ActorId, CacheUsage, StackTrace};
 
 /// [`HashAggExecutor`] could process large amounts of data using a state backend. It works as
 /// follows:
@@ -53,6 +57,11 @@ use crate::executor::{BoxedMessageStream, Message, PkIndices, PROCESSING_WINDOW_
 /// * Upon a barrier is received, the executor will call `.flush` on the storage backend, so that
 ///   all modifications will be flushed to the storage backend. Meanwhile, the executor will go
 ///   through `modified_keys`, and produce a stream chunk based on the state changes.
+///
+/// The states of each group are persisted in the keyspace with the group key as the prefix, so
+/// only the recently used groups are cached. Groups not modified in the current epoch are evicted
+/// as soon as the cache grows beyond its target capacity, while the modified ones are kept until
+/// they are flushed.
 pub struct HashAggExecutor<K: HashKey, S: StateStore> {
     input: Box<dyn Executor>,
 
@@ -88,6 +97,12 @@ struct HashAggExecutorExtra<S: StateStore> {
     key_indices: Vec<usize>,
 
     state_tables: Vec<StateTable<S>>,
+
+    /// The actor of the executor, whose vnodes may be reassigned by barriers.
+    actor_id: ActorId,
+
+    /// Reports the cached states to the memory manager, which may ask for evicting them.
+    cache_usage: CacheUsage,
 }
 
 impl<K: HashKey, S: StateStore> Executor for HashAggExecutor<K, S> {
@@ -114,8 +129,10 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         agg_calls: Vec<AggCall>,
         keyspace: Vec<Keyspace<S>>,
         pk_indices: PkIndices,
+        actor_id: ActorId,
         executor_id: u64,
         key_indices: Vec<usize>,
+        cache_usage: CacheUsage,
     ) -> Result<Self> {
         let input_info = input.info();
         let schema = generate_agg_schema(input.as_ref(), &agg_calls, Some(&key_indices));
@@ -124,10 +141,10 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         for (agg_call, ks) in agg_calls.iter().zip_eq(&keyspace) {
             let state_table = StateTable::new(
                 ks.clone(),
-                vec![ColumnDesc::unnamed(
+                with_version_column(vec![ColumnDesc::unnamed(
                     ColumnId::new(0),
                     agg_call.return_type.clone(),
-                )],
+                )]),
                 // Primary key includes group key.
                 vec![OrderType::Descending; key_indices.len() + get_key_len(agg_call)],
                 Some(key_indices.clone()),
@@ -147,6 +164,8 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 agg_calls,
                 key_indices,
                 state_tables,
+                actor_id,
+                cache_usage,
             },
             _phantom: PhantomData,
         })
@@ -215,6 +234,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             ..
         }: &HashAggExecutorExtra<S>,
         state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        modified_keys: &mut HashSet<K>,
         chunk: StreamChunk,
         epoch: u64,
     ) -> StreamExecutorResult<()> {
@@ -261,6 +281,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         for (key, hash_code, vis_map) in unique_keys {
             // Retrieve previous state from the KeyedState.
             let states = state_map.put(key.to_owned(), None);
+            modified_keys.insert(key.clone());
 
             let key = key.clone();
             // To leverage more parallelism in IO operations, fetching and updating states for every
@@ -319,9 +340,34 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             state_map.put(key, Some(state));
         }
 
+        Self::evict_unmodified_states(state_map, modified_keys);
+
         Ok(())
     }
 
+    /// Evicts the least recently used groups not modified in the current epoch, so that the cache
+    /// doesn't grow far beyond its target capacity with high-cardinality group keys. The states of
+    /// these groups have been flushed, and will be fetched again when they're needed.
+    fn evict_unmodified_states(
+        state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        modified_keys: &HashSet<K>,
+    ) {
+        let excess = state_map.len().saturating_sub(state_map.target_cap());
+        if excess == 0 {
+            return;
+        }
+        let evicted_keys = state_map
+            .iter()
+            .rev()
+            .filter(|(key, _)| !modified_keys.contains(*key))
+            .take(excess)
+            .map(|(key, _)| key.clone())
+            .collect_vec();
+        for key in evicted_keys {
+            state_map.pop(&key);
+        }
+    }
+
     #[try_stream(ok = StreamChunk, error = StreamExecutorError)]
     async fn flush_data<'a>(
         &mut HashAggExecutorExtra::<S> {
@@ -329,9 +375,11 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             ref keyspace,
             ref schema,
             ref mut state_tables,
+            ref cache_usage,
             ..
         }: &'a mut HashAggExecutorExtra<S>,
         state_map: &'a mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        modified_keys: &'a mut HashSet<K>,
         epoch: u64,
     ) {
         // The state store of each keyspace is the same so just need the first.
@@ -339,16 +387,18 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         // --- Flush states to the state store ---
         // Some state will have the correct output only after their internal states have been
         // fully flushed.
+        // Only the groups modified in this epoch can be dirty, so there's no need to go through
+        // all the cached groups.
+        let modified_keys = std::mem::take(modified_keys).into_iter().collect_vec();
         let (write_batch, dirty_cnt) = {
             let mut write_batch = store.start_write_batch();
             let mut dirty_cnt = 0;
 
-            for states in state_map.values_mut() {
-                if states.as_ref().unwrap().is_dirty() {
+            for key in &modified_keys {
+                let states = state_map.get_mut(key).unwrap().as_mut().unwrap();
+                if states.is_dirty() {
                     dirty_cnt += 1;
                     for (state, state_table) in states
-                        .as_mut()
-                        .unwrap()
                         .managed_states
                         .iter_mut()
                         .zip_eq(state_tables.iter_mut())
@@ -363,7 +413,10 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
 
             // Batch commit state table.
             for state_table in state_tables.iter_mut() {
-                state_table.commit(epoch).await?;
+                state_table
+                    .commit(epoch)
+                    .stack_trace("StateTable::commit")
+                    .await?;
             }
 
             (write_batch, dirty_cnt)
@@ -376,11 +429,12 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         } else {
             write_batch
                 .ingest(epoch)
+                .stack_trace("WriteBatch::ingest")
                 .await
                 .map_err(StreamExecutorError::agg_state_error)?;
 
             // --- Produce the stream chunk ---
-            let mut batches = IterChunks::chunks(state_map.iter_mut(), PROCESSING_WINDOW_SIZE);
+            let mut batches = IterChunks::chunks(modified_keys.iter(), PROCESSING_WINDOW_SIZE);
             while let Some(batch) = batches.next() {
                 // --- Create array builders ---
                 // As the datatype is retrieved from schema, it contains both group key and
@@ -391,8 +445,10 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 let mut new_ops = Vec::with_capacity(dirty_cnt);
 
                 // --- Retrieve modified states and put the changes into the builders ---
-                for (key, states) in batch {
-                    let appended = states
+                for key in batch {
+                    let appended = state_map
+                        .get_mut(key)
+                        .unwrap()
                         .as_mut()
                         .unwrap()
                         .build_changes(&mut builders[key_indices.len()..], &mut new_ops, epoch)
@@ -418,14 +474,37 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 yield chunk;
             }
 
-            // evict cache to target capacity
+            // evict cache to target capacity, or further if the memory manager asks to
             // In current implementation, we need to fetch the RowCount from the state store
             // once a key is deleted and added again. We should find a way to
             // eliminate this extra fetch.
-            assert!(!state_map
-                .values()
-                .any(|state| state.as_ref().unwrap().is_dirty()));
-            state_map.evict_to_target_cap();
+            // Evicting a dirty group would lose its unflushed changes. Only the modified groups
+            // can be dirty, so checking them is enough and keeps the check cheap.
+            assert!(!modified_keys.iter().any(|key| state_map
+                .peek(key)
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .is_dirty()));
+            state_map.evict(cache_usage.watermark());
+            cache_usage.report(state_map.cached_entries());
+        }
+    }
+
+    /// Evicts the cached groups whose vnodes are not owned by this actor in `vnode_bitmap` any
+    /// more, as they may be updated by other actors later.
+    fn evict_moved_out_groups(
+        state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        vnode_bitmap: &[u8],
+    ) {
+        let moved_out_keys = state_map
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| !get_bit(vnode_bitmap, key.hash_code().to_vnode() as usize))
+            .cloned()
+            .collect_vec();
+        for key in moved_out_keys {
+            state_map.pop(&key);
         }
     }
 
@@ -437,6 +516,11 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
 
         // The cached states. `HashKey -> (prev_value, value)`.
         let mut state_map = EvictableHashMap::new(1 << 16);
+        // The groups modified in the current epoch, whose states are dirty.
+        let mut modified_keys = HashSet::new();
+        // The epoch in which the previous owners of the vnodes moved in last wrote their states.
+        // Those writes must be visible before the states are read.
+        let mut handover_epoch = None;
 
         let mut input = input.execute();
         let barrier = expect_first_barrier(&mut input).await?;
@@ -448,17 +532,34 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             let msg = msg?;
             match msg {
                 Message::Chunk(chunk) => {
-                    Self::apply_chunk(&extra, &mut state_map, chunk, epoch).await?;
+                    if let Some(handover_epoch) = handover_epoch.take() {
+                        extra.keyspace[0]
+                            .state_store()
+                            .wait_epoch(handover_epoch)
+                            .stack_trace("StateStore::wait_epoch")
+                            .await?;
+                    }
+                    Self::apply_chunk(&extra, &mut state_map, &mut modified_keys, chunk, epoch)
+                        .await?;
                 }
                 Message::Barrier(barrier) => {
                     let next_epoch = barrier.epoch.curr;
                     assert_eq!(epoch, barrier.epoch.prev);
 
+                    // The states are flushed on every barrier, not only on checkpoints, as the
+                    // outputs are produced from the flushed states.
                     #[for_await]
-                    for chunk in Self::flush_data(&mut extra, &mut state_map, epoch) {
+                    for chunk in
+                        Self::flush_data(&mut extra, &mut state_map, &mut modified_keys, epoch)
+                    {
                         yield Message::Chunk(chunk?);
                     }
 
+                    if let Some(vnode_bitmap) = barrier.as_update_vnode_bitmap(extra.actor_id) {
+                        Self::evict_moved_out_groups(&mut state_map, vnode_bitmap);
+                        handover_epoch = Some(barrier.epoch.prev);
+                    }
+
                     yield Message::Barrier(barrier);
                     epoch = next_epoch;
                 }
@@ -487,6 +588,7 @@ mod tests {
     use crate::executor::aggregation::{AggArgs, AggCall};
     use crate::executor::test_utils::*;
     use crate::executor::{Executor, HashAggExecutor, Message, PkIndices};
+    use crate::task::CacheUsage;
 
     struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
 
@@ -509,8 +611,10 @@ mod tests {
                 args.agg_calls,
                 args.keyspace,
                 args.pk_indices,
+                1,
                 args.executor_id,
                 args.key_indices,
+                CacheUsage::default(),
             )?))
         }
     }
@@ -26,11 +26,15 @@ use risingwave_storage::{Keyspace, StateStore};
 
 use super::barrier_align::*;
 use super::error::StreamExecutorError;
+use super::managed_state::evict_in_proportion;
 use super::managed_state::join::*;
 use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndices, PkIndicesRef};
 use crate::common::StreamChunkBuilder;
 use crate::executor::PROCESSING_WINDOW_SIZE;
+use crate::task::CacheUsage;
 
+/// The number of join keys of each side kept in memory. States of the other keys are evicted to
+/// the state store on each barrier and fetched back when they are matched again.
 pub const JOIN_CACHE_SIZE: usize = 1 << 16;
 
 /// The `JoinType` and `SideType` are to mimic a enum, because currently
@@ -189,6 +193,9 @@ pub struct HashJoinExecutor<K: HashKey, S: StateStore, const T: JoinTypePrimitiv
 
     /// Whether the logic can be optimized for append-only stream
     append_only_optimize: bool,
+
+    /// Reports the cached join rows to the memory manager, which may ask for evicting them.
+    cache_usage: CacheUsage,
 }
 
 impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> std::fmt::Debug
@@ -364,6 +371,8 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         ks_l: Keyspace<S>,
         ks_r: Keyspace<S>,
         append_only: bool,
+        cache_size: usize,
+        cache_usage: CacheUsage,
     ) -> Self {
         let side_l_column_n = input_l.schema().len();
 
@@ -425,12 +434,12 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             },
             side_l: JoinSide {
                 ht: JoinHashMap::new(
-                    JOIN_CACHE_SIZE,
+                    cache_size,
                     pk_indices_l.clone(),
                     params_l.key_indices.clone(),
                     col_l_datatypes.clone(),
                     ks_l.clone(),
-                ), // TODO: decide the target cap
+                ),
                 key_indices: params_l.key_indices,
                 col_types: col_l_datatypes,
                 pk_indices: pk_indices_l,
@@ -439,12 +448,12 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             },
             side_r: JoinSide {
                 ht: JoinHashMap::new(
-                    JOIN_CACHE_SIZE,
+                    cache_size,
                     pk_indices_r.clone(),
                     params_r.key_indices.clone(),
                     col_r_datatypes.clone(),
                     ks_r.clone(),
-                ), // TODO: decide the target cap
+                ),
                 key_indices: params_r.key_indices,
                 col_types: col_r_datatypes,
                 pk_indices: pk_indices_r,
@@ -458,6 +467,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             key_indices,
             epoch: 0,
             append_only_optimize,
+            cache_usage,
         }
     }
 
@@ -498,9 +508,13 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                     }
                 }
                 AlignedMessage::Barrier(barrier) => {
-                    self.flush_data()
-                        .await
-                        .map_err(StreamExecutorError::hash_join_error)?;
+                    // The dirty states are kept in the cache until the next checkpoint, as they
+                    // are never evicted.
+                    if barrier.checkpoint {
+                        self.flush_data()
+                            .await
+                            .map_err(StreamExecutorError::hash_join_error)?;
+                    }
                     let epoch = barrier.epoch.curr;
                     self.side_l.ht.update_epoch(epoch);
                     self.side_r.ht.update_epoch(epoch);
@@ -521,11 +535,14 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             write_batch.ingest(epoch).await.unwrap();
         }
 
-        // evict the LRU cache
+        // evict the LRU cache, and shrink both sides in proportion if the memory manager asks to
         assert!(!self.side_l.is_dirty());
-        self.side_l.ht.evict_to_target_cap();
         assert!(!self.side_r.is_dirty());
-        self.side_r.ht.evict_to_target_cap();
+        let cached_entries = evict_in_proportion(
+            &mut [&mut self.side_l.ht, &mut self.side_r.ht],
+            self.cache_usage.watermark(),
+        );
+        self.cache_usage.report(cached_entries);
         Ok(())
     }
 
@@ -579,7 +596,8 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         chunk: StreamChunk,
         append_only_optimize: bool,
     ) {
-        let chunk = chunk.compact()?;
+        // A dense chunk is not compacted, and the invisible rows are skipped below.
+        let chunk = chunk.compact_if_sparse()?;
         let (ops, columns, visibility) = chunk.into_inner();
 
         let data_chunk = {
@@ -630,8 +648,8 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         };
 
         let keys = K::build(&side_update.key_indices, &data_chunk)?;
-        for (idx, (row, op)) in data_chunk.rows().zip_eq(ops.iter()).enumerate() {
-            let key = &keys[idx];
+        for row in data_chunk.rows() {
+            let (key, op) = (&keys[row.index()], &ops[row.index()]);
             let value = row.to_owned_row();
             let pk = row.row_by_indices(&side_update.pk_indices);
             let mut matched_rows = Self::hash_eq_match(key, &mut side_match.ht).await;
@@ -695,7 +713,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                     }
                 }
                 Op::Delete | Op::UpdateDelete => {
-                    if let Some(v) = side_update.ht.get_mut_without_cached(key).await? {
+                    if let Some(v) = side_update.ht.get_mut_without_cached(key, &pk).await? {
                         // remove the row by it's primary key
                         v.remove(pk);
                     }
@@ -754,6 +772,7 @@ mod tests {
     use super::{HashJoinExecutor, JoinParams, JoinType, *};
     use crate::executor::test_utils::{MessageSender, MockSource};
     use crate::executor::{Barrier, Epoch, Message};
+    use crate::task::CacheUsage;
 
     fn create_in_memory_keyspace() -> (Keyspace<MemoryStateStore>, Keyspace<MemoryStateStore>) {
         let mem_state = MemoryStateStore::new();
@@ -777,6 +796,13 @@ mod tests {
 
     fn create_executor<const T: JoinTypePrimitive>(
         with_condition: bool,
+    ) -> (MessageSender, MessageSender, BoxedMessageStream) {
+        create_executor_with_cache_size::<T>(with_condition, JOIN_CACHE_SIZE)
+    }
+
+    fn create_executor_with_cache_size<const T: JoinTypePrimitive>(
+        with_condition: bool,
+        cache_size: usize,
     ) -> (MessageSender, MessageSender, BoxedMessageStream) {
         let schema = Schema {
             fields: vec![
@@ -805,6 +831,8 @@ mod tests {
             ks_l,
             ks_r,
             false,
+            cache_size,
+            CacheUsage::default(),
         );
         (tx_l, tx_r, Box::new(executor).execute())
     }
@@ -840,6 +868,8 @@ mod tests {
             ks_l,
             ks_r,
             true,
+            JOIN_CACHE_SIZE,
+            CacheUsage::default(),
         );
         (tx_l, tx_r, Box::new(executor).execute())
     }
@@ -1905,6 +1935,144 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_streaming_hash_left_join_with_evicted_cache() {
+        let chunk_l1 = StreamChunk::from_pretty(
+            "  I I
+             + 1 4
+             + 2 5
+             + 3 6",
+        );
+        let chunk_r1 = StreamChunk::from_pretty(
+            "  I  I
+             + 2 7
+             + 3 10",
+        );
+        let chunk_r2 = StreamChunk::from_pretty(
+            "  I I
+             - 2 7",
+        );
+        // Keep only one join key in memory, so that most of the states have to be fetched back
+        // from the state store after each barrier.
+        let (mut tx_l, mut tx_r, mut hash_join) =
+            create_executor_with_cache_size::<{ JoinType::LeftOuter }>(false, 1);
+
+        // push the init barrier for left and right
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        // push the 1st left chunk
+        tx_l.push_chunk(chunk_l1);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I I I
+                + 1 4 . .
+                + 2 5 . .
+                + 3 6 . ."
+            )
+        );
+
+        // flush the states and evict the cache
+        tx_l.push_barrier(2, false);
+        tx_r.push_barrier(2, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        // push the 1st right chunk, whose matched rows are loaded from the state store
+        tx_r.push_chunk(chunk_r1);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I  I
+                U- 2 5 . .
+                U+ 2 5 2 7
+                U- 3 6 . .
+                U+ 3 6 3 10"
+            )
+        );
+
+        tx_l.push_barrier(3, false);
+        tx_r.push_barrier(3, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        // the degree of the left row is persisted, so the retraction brings back the null row
+        tx_r.push_chunk(chunk_r2);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I I
+                U- 2 5 2 7
+                U+ 2 5 . ."
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_hash_left_join_with_non_checkpoint_barrier() {
+        let chunk_l1 = StreamChunk::from_pretty(
+            "  I I
+             + 1 4
+             + 2 5",
+        );
+        let chunk_r1 = StreamChunk::from_pretty(
+            "  I I
+             + 2 7",
+        );
+        let chunk_r2 = StreamChunk::from_pretty(
+            "  I I
+             - 2 7",
+        );
+        let (mut tx_l, mut tx_r, mut hash_join) =
+            create_executor_with_cache_size::<{ JoinType::LeftOuter }>(false, 1);
+
+        // push the init barrier for left and right
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        tx_l.push_chunk(chunk_l1);
+        hash_join.next().await.unwrap().unwrap();
+
+        // the dirty states are neither flushed nor evicted on a non-checkpoint barrier
+        let barrier = Barrier::new_test_barrier(2).with_checkpoint(false);
+        tx_l.send_barrier(barrier.clone());
+        tx_r.send_barrier(barrier);
+        let msg = hash_join.next().await.unwrap().unwrap();
+        assert!(!msg.as_barrier().unwrap().checkpoint);
+
+        tx_r.push_chunk(chunk_r1);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I I
+                U- 2 5 . .
+                U+ 2 5 2 7"
+            )
+        );
+
+        // flush the states and evict the cache on the checkpoint
+        tx_l.push_barrier(3, false);
+        tx_r.push_barrier(3, false);
+        hash_join.next().await.unwrap().unwrap();
+
+        // the states of both epochs are fetched back from the state store
+        tx_r.push_chunk(chunk_r2);
+        let chunk = hash_join.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I I I
+                U- 2 5 2 7
+                U+ 2 5 . ."
+            )
+        );
+    }
+
     #[tokio::test]
     async fn test_streaming_hash_right_join() {
         let chunk_l1 = StreamChunk::from_pretty(
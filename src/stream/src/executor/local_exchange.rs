@@ -0,0 +1,443 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::channel::mpsc::{channel, Sender};
+use futures::stream::FuturesUnordered;
+use futures::{SinkExt, StreamExt};
+use futures_async_stream::try_stream;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+use tokio::task::JoinHandle;
+
+use super::dispatch::{
+    BoxedOutput, DispatcherImpl, HashDataDispatcher, LocalOutput, RoundRobinDataDispatcher,
+};
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::receiver::ReceiverExecutor;
+use super::{
+    ActorContextRef, Barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message,
+    PkIndicesRef,
+};
+use crate::task::{ActorId, BufferOccupancy, LOCAL_OUTPUT_CHANNEL_SIZE};
+
+/// `LocalExchangeExecutor` runs a heavy operator of an actor in several branches, so that it can
+/// make use of multiple cores without a remote shuffle. The input is repartitioned into in-memory
+/// channels by the key columns, or round-robin if there's no key, and each branch builds the
+/// operator upon a [`ReceiverExecutor`] of one channel. The branches are driven in their own
+/// tasks, and their outputs are merged back with barriers aligned. The tasks are aborted once the
+/// executor is dropped.
+pub struct LocalExchangeExecutor {
+    input: BoxedExecutor,
+
+    /// Sends the input to the channels of the branches. Barriers are broadcast.
+    dispatcher: DispatcherImpl,
+
+    /// The operator built upon each partition of the input.
+    branches: Vec<BoxedExecutor>,
+
+    info: ExecutorInfo,
+}
+
+impl std::fmt::Debug for LocalExchangeExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalExchangeExecutor")
+            .field("dispatcher", &self.dispatcher)
+            .field("parallelism", &self.branches.len())
+            .finish()
+    }
+}
+
+impl LocalExchangeExecutor {
+    /// Splits the operator built by `build_branch` into `parallelism` branches. Rows with the same
+    /// values on `key_indices` always go to the same branch. `build_branch` is given the index of
+    /// the branch along with its input.
+    pub fn new(
+        input: BoxedExecutor,
+        key_indices: Vec<usize>,
+        parallelism: usize,
+        mut build_branch: impl FnMut(usize, BoxedExecutor) -> Result<BoxedExecutor>,
+        actor_context: ActorContextRef,
+        executor_id: u64,
+    ) -> Result<Self> {
+        assert!(
+            parallelism > 0,
+            "local exchange requires at least one branch"
+        );
+
+        let mut outputs: Vec<BoxedOutput> = Vec::with_capacity(parallelism);
+        let mut branches = Vec::with_capacity(parallelism);
+        for partition in 0..parallelism {
+            let (tx, rx) = channel(LOCAL_OUTPUT_CHANNEL_SIZE);
+            let occupancy = BufferOccupancy::default();
+            // The branches are addressed by their partition in place of the actor id.
+            outputs.push(Box::new(
+                LocalOutput::new(partition as ActorId, tx).with_buffer_occupancy(occupancy.clone()),
+            ));
+            let receiver = ReceiverExecutor::new(
+                input.schema().clone(),
+                input.pk_indices().to_vec(),
+                rx,
+                actor_context.clone(),
+                executor_id,
+            )
+            .with_buffer_occupancy(occupancy);
+            branches.push(build_branch(partition, Box::new(receiver))?);
+        }
+
+        let dispatcher = if key_indices.is_empty() {
+            DispatcherImpl::RoundRobin(RoundRobinDataDispatcher::new(outputs, executor_id))
+        } else {
+            let hash_mapping = (0..VIRTUAL_NODE_COUNT)
+                .map(|vnode| (vnode % parallelism) as ActorId)
+                .collect();
+            DispatcherImpl::Hash(HashDataDispatcher::new(
+                (0..parallelism as u32).collect(),
+                outputs,
+                key_indices,
+                hash_mapping,
+                executor_id,
+            ))
+        };
+
+        Ok(Self {
+            info: ExecutorInfo {
+                schema: branches[0].schema().clone(),
+                pk_indices: branches[0].pk_indices().to_vec(),
+                identity: format!("LocalExchangeExecutor {:X}", executor_id),
+            },
+            input,
+            dispatcher,
+            branches,
+        })
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let mut scatter = AbortOnDrop(tokio::spawn(scatter(self.input, self.dispatcher)));
+
+        let mut upstreams = Vec::with_capacity(self.branches.len());
+        // Held to abort the branches on drop.
+        let mut forwards = Vec::with_capacity(self.branches.len());
+        for branch in self.branches {
+            let (tx, rx) = channel(LOCAL_OUTPUT_CHANNEL_SIZE);
+            forwards.push(AbortOnDrop(tokio::spawn(forward(branch, tx))));
+            upstreams.push(rx);
+        }
+
+        loop {
+            let mut pending: FuturesUnordered<_> =
+                upstreams.drain(..).map(|rx| rx.into_future()).collect();
+            let mut barrier: Option<Barrier> = None;
+            let mut finished = 0;
+            while let Some((msg, rx)) = pending.next().await {
+                match msg.transpose()? {
+                    Some(Message::Chunk(chunk)) => {
+                        pending.push(rx.into_future());
+                        yield Message::Chunk(chunk);
+                    }
+                    Some(Message::Barrier(received)) => {
+                        if let Some(barrier) = &barrier {
+                            if barrier.epoch != received.epoch {
+                                return Err(StreamExecutorError::align_barrier(
+                                    barrier.clone(),
+                                    received,
+                                ));
+                            }
+                        } else {
+                            barrier = Some(received);
+                        }
+                        // Blocked until all branches reach the barrier.
+                        upstreams.push(rx);
+                    }
+                    None => finished += 1,
+                }
+            }
+
+            match barrier {
+                Some(_) if finished > 0 => {
+                    return Err(StreamExecutorError::channel_closed("local exchange branch"));
+                }
+                Some(barrier) => yield Message::Barrier(barrier),
+                // All branches exit after the input ends.
+                None => break,
+            }
+        }
+
+        (&mut scatter.0)
+            .await
+            .map_err(|_| StreamExecutorError::channel_closed("local exchange input"))??;
+    }
+}
+
+/// Aborts the task on drop, so that no task outlives the executor, e.g., when the actor is dropped
+/// or exits with an error.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Repartitions the input into the channels of the branches. Dropping the dispatcher on exit
+/// closes the channels, after which the branches exit as well.
+async fn scatter(input: BoxedExecutor, mut dispatcher: DispatcherImpl) -> StreamExecutorResult<()> {
+    let mut input = input.execute();
+    while let Some(msg) = input.next().await {
+        let result = match msg? {
+            Message::Chunk(chunk) => dispatcher.dispatch_data(chunk).await,
+            Message::Barrier(barrier) => dispatcher.dispatch_barrier(barrier).await,
+        };
+        result.map_err(|_| StreamExecutorError::channel_closed("local exchange branch"))?;
+    }
+    Ok(())
+}
+
+/// Drives a branch and forwards its output, including the error, to the merging side.
+async fn forward(branch: BoxedExecutor, mut tx: Sender<StreamExecutorResult<Message>>) {
+    let mut branch = branch.execute();
+    while let Some(msg) = branch.next().await {
+        let is_err = msg.is_err();
+        if tx.send(msg).await.is_err() || is_err {
+            break;
+        }
+    }
+}
+
+impl Executor for LocalExchangeExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::{Op, StreamChunk};
+    use risingwave_common::catalog::Field;
+    use risingwave_common::test_prelude::StreamChunkTestExt;
+    use risingwave_common::types::{DataType, Datum};
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+    use crate::executor::{ActorContext, ProjectExecutor};
+
+    /// Splits the output by barriers and sorts the rows of each epoch, as the branches are merged
+    /// in arbitrary order.
+    fn rows_by_epoch(outputs: Vec<Message>) -> Vec<(u64, Vec<(Op, Vec<Datum>)>)> {
+        let mut epochs = vec![];
+        let mut rows = vec![];
+        for msg in outputs {
+            match msg {
+                Message::Chunk(chunk) => {
+                    rows.extend(chunk.rows().map(|(op, row)| (op, row.to_owned_row().0)))
+                }
+                Message::Barrier(barrier) => {
+                    rows.sort();
+                    epochs.push((barrier.epoch.curr, std::mem::take(&mut rows)));
+                }
+            }
+        }
+        assert!(rows.is_empty());
+        epochs
+    }
+
+    fn sorted_rows(chunk: &str) -> Vec<(Op, Vec<Datum>)> {
+        StreamChunk::from_pretty(chunk)
+            .rows()
+            .map(|(op, row)| (op, row.to_owned_row().0))
+            .sorted()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_hash_local_exchange() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let source = MockSource::with_messages(
+            schema,
+            vec![],
+            vec![
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    + 1 10
+                    + 2 20
+                    + 3 30
+                    + 4 40",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    - 1 10
+                    + 5 50",
+                )),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    + 6 60",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+            ],
+        )
+        .stop_on_finish(false);
+
+        let exchange = Box::new(
+            LocalExchangeExecutor::new(
+                Box::new(source),
+                vec![0],
+                3,
+                |_, input| {
+                    let expr = new_binary_expr(
+                        Type::Add,
+                        DataType::Int64,
+                        Box::new(InputRefExpression::new(DataType::Int64, 0)),
+                        Box::new(InputRefExpression::new(DataType::Int64, 1)),
+                    );
+                    Ok(Box::new(ProjectExecutor::new(input, vec![], vec![expr], 1)))
+                },
+                ActorContext::create(),
+                1,
+            )
+            .unwrap(),
+        );
+        assert_eq!(exchange.schema().len(), 1);
+
+        let outputs: Vec<_> = exchange.execute().try_collect().await.unwrap();
+        let epoch1 = Barrier::new_test_barrier(1).epoch.curr;
+        let epoch2 = Barrier::new_test_barrier(2).epoch.curr;
+        assert_eq!(
+            rows_by_epoch(outputs),
+            vec![
+                (
+                    epoch1,
+                    sorted_rows(
+                        " I
+                        + 11
+                        + 22
+                        + 33
+                        + 44"
+                    )
+                ),
+                (
+                    epoch2,
+                    sorted_rows(
+                        " I
+                        - 11
+                        + 55
+                        + 66"
+                    )
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_local_exchange() {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let source = MockSource::with_messages(
+            schema,
+            vec![],
+            vec![
+                Message::Chunk(StreamChunk::from_pretty("I\n + 1")),
+                Message::Chunk(StreamChunk::from_pretty("I\n + 2")),
+                Message::Chunk(StreamChunk::from_pretty("I\n + 3")),
+                Message::Barrier(Barrier::new_test_barrier(1)),
+            ],
+        );
+
+        let exchange = Box::new(
+            LocalExchangeExecutor::new(
+                Box::new(source),
+                vec![],
+                2,
+                |_, input| Ok(input),
+                ActorContext::create(),
+                1,
+            )
+            .unwrap(),
+        );
+
+        let outputs: Vec<_> = exchange.execute().try_collect().await.unwrap();
+        // The chunks are forwarded as a whole, followed by the aligned barrier and the stop
+        // barrier on finish of the source.
+        assert_eq!(outputs.len(), 5);
+        let epochs = rows_by_epoch(outputs);
+        assert_eq!(epochs.len(), 2);
+        assert_eq!(epochs[0].1, sorted_rows("I\n + 1\n + 2\n + 3"));
+        assert!(epochs[1].1.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drop_local_exchange() {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        // The source never finishes as long as `tx` is alive.
+        let (mut tx, source) = MockSource::channel(schema, vec![]);
+        tx.push_barrier(1, false);
+
+        let actor_context = ActorContext::create();
+        let exchange = Box::new(
+            LocalExchangeExecutor::new(
+                Box::new(source),
+                vec![],
+                2,
+                |_, input| Ok(input),
+                actor_context.clone(),
+                1,
+            )
+            .unwrap(),
+        );
+        let mut stream = exchange.execute();
+        assert!(matches!(
+            stream.next().await.unwrap().unwrap(),
+            Message::Barrier(_)
+        ));
+        assert!(Arc::strong_count(&actor_context) > 1);
+
+        // The tasks of the branches are aborted, which releases the receivers of the branches.
+        drop(stream);
+        for _ in 0..10 {
+            if Arc::strong_count(&actor_context) == 1 {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(Arc::strong_count(&actor_context), 1);
+    }
+}
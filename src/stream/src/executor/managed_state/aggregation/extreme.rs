@@ -28,6 +28,7 @@ use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::super::flush_status::BtreeMapFlushStatus as FlushStatus;
+use super::super::state_version::{decode_state_value, encode_state_value};
 use super::extreme_serializer::{variants, ExtremePk, ExtremeSerializer};
 use crate::executor::aggregation::{AggArgs, AggCall};
 use crate::executor::PkDataTypes;
@@ -303,8 +304,8 @@ where
             // following logic.
             let all_data = self.keyspace.scan(self.top_n_count, epoch).await?;
 
-            for (raw_key, mut raw_value) in all_data {
-                // let mut deserializer = value_encoding::Deserializer::new(raw_value);
+            for (raw_key, raw_value) in all_data {
+                let mut raw_value = decode_state_value(raw_value)?;
                 let value = deserialize_cell(&mut raw_value, &self.data_type)?;
                 let key = value.clone().map(|x| x.try_into().unwrap());
                 let pks = self.serializer.get_pk(&raw_key[..])?;
@@ -340,7 +341,7 @@ where
                 Some(v) => {
                     local.put(
                         key_encoded,
-                        StorageValue::new_put(value_meta, serialize_cell(&v)?),
+                        StorageValue::new_put(value_meta, encode_state_value(&serialize_cell(&v)?)),
                     );
                 }
                 None => {
@@ -397,8 +398,8 @@ where
         let all_data = self.keyspace.scan(None, epoch).await?;
         let mut result = vec![];
 
-        for (raw_key, mut raw_value) in all_data {
-            // let mut deserializer = value_encoding::Deserializer::new(raw_value);
+        for (raw_key, raw_value) in all_data {
+            let mut raw_value = decode_state_value(raw_value)?;
             let value = deserialize_cell(&mut raw_value, &self.data_type)?;
             let key = value.clone().map(|x| x.try_into().unwrap());
             let pks = self.serializer.get_pk(&raw_key[..])?;
@@ -171,6 +171,11 @@ impl<S: StateStore> ManagedStateImpl<S> {
             AggKind::SingleValue => Ok(Self::Value(
                 ManagedValueState::new(agg_call, row_count, pk, state_table).await?,
             )),
+            AggKind::StddevSamp | AggKind::VarPop => Err(ErrorCode::InternalError(format!(
+                "{} should have been rewritten into sum and count by the planner",
+                agg_call.kind
+            ))
+            .into()),
         }
     }
 }
@@ -28,6 +28,7 @@ use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::super::flush_status::BtreeMapFlushStatus as FlushStatus;
+use super::super::state_version::{decode_state_value, encode_state_value};
 use super::ManagedTableState;
 
 pub struct ManagedStringAggState<S: StateStore> {
@@ -115,8 +116,9 @@ impl<S: StateStore> ManagedStringAggState<S> {
         assert!(!self.is_dirty());
         // Read all.
         let all_data = self.keyspace.scan(None, epoch).await?;
-        for (raw_key, mut raw_value) in all_data {
+        for (raw_key, raw_value) in all_data {
             // We only need to deserialize the value, and keep the key as bytes.
+            let mut raw_value = decode_state_value(raw_value)?;
             let value = deserialize_cell(&mut raw_value, &DataType::Varchar)?.unwrap();
             let value_string: String = value.into_utf8();
             self.cache.insert(
@@ -253,7 +255,9 @@ impl<S: StateStore> ManagedTableState<S> for ManagedStringAggState<S> {
                     // TODO(Yuanxin): Implement value meta
                     local.put(
                         key,
-                        StorageValue::new_default_put(serialize_cell(&Some(val))?),
+                        StorageValue::new_default_put(encode_state_value(&serialize_cell(&Some(
+                            val,
+                        ))?)),
                     );
                 }
                 None => {
@@ -21,6 +21,7 @@ use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::StateStore;
 
+use super::super::state_version::{decode_state_row, encode_state_row};
 use crate::executor::aggregation::{create_streaming_agg_state, AggCall, StreamingAggStateImpl};
 
 /// A wrapper around [`StreamingAggStateImpl`], which fetches data from the state store and helps
@@ -57,7 +58,10 @@ impl ManagedValueState {
                 .get_row(pk.unwrap_or(&Row(vec![])), epoch)
                 .await?;
 
-            raw_data.and_then(|row| row.values().next().cloned())
+            match raw_data {
+                Some(row) => decode_state_row(row)?.0.into_iter().next(),
+                None => None,
+            }
         } else {
             None
         };
@@ -114,7 +118,7 @@ impl ManagedValueState {
         let v = self.state.get_output()?;
         state_table.insert(
             self.pk.as_ref().cloned().unwrap_or(Row(vec![])),
-            Row(vec![v]),
+            encode_state_row(Row(vec![v])),
         )?;
 
         self.is_dirty = false;
@@ -131,6 +135,7 @@ mod tests {
 
     use super::*;
     use crate::executor::aggregation::AggArgs;
+    use crate::executor::managed_state::state_version::with_version_column;
     use crate::executor::test_utils::create_in_memory_keyspace;
 
     fn create_test_count_state() -> AggCall {
@@ -147,7 +152,7 @@ mod tests {
         let keyspace = create_in_memory_keyspace();
         let mut state_table = StateTable::new(
             keyspace.clone(),
-            vec![ColumnDesc::unnamed(ColumnId::new(0), DataType::Int64)],
+            with_version_column(vec![ColumnDesc::unnamed(ColumnId::new(0), DataType::Int64)]),
             vec![],
             None,
         );
@@ -210,7 +215,7 @@ mod tests {
         let keyspace = create_in_memory_keyspace();
         let mut state_table = StateTable::new(
             keyspace.clone(),
-            vec![ColumnDesc::unnamed(ColumnId::new(0), DataType::Int64)],
+            with_version_column(vec![ColumnDesc::unnamed(ColumnId::new(0), DataType::Int64)]),
             vec![],
             None,
         );
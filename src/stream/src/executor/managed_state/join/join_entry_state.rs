@@ -24,6 +24,7 @@ use risingwave_storage::write_batch::WriteBatch;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::super::flush_status::BtreeMapFlushStatus as FlushStatus;
+use super::super::state_version::{decode_state_value, encode_state_value};
 use super::*;
 
 type JoinEntryStateIter<'a> = btree_map::Iter<'a, PkType, StateValueType>;
@@ -95,10 +96,11 @@ impl<S: StateStore> JoinEntryState<S> {
         pk_data_types: Arc<[DataType]>,
     ) -> Result<BTreeMap<PkType, StateValueType>> {
         let mut cached = BTreeMap::new();
+        let pk_deserializer = RowDeserializer::new(pk_data_types);
+        let deserializer = JoinRowDeserializer::new(data_types);
         for (raw_key, raw_value) in data {
-            let pk_deserializer = RowDeserializer::new(pk_data_types.to_vec());
             let key = pk_deserializer.value_decode(raw_key)?;
-            let deserializer = JoinRowDeserializer::new(data_types.to_vec());
+            let raw_value = decode_state_value(raw_value)?;
             let value = deserializer.deserialize(raw_value)?;
             cached.insert(key, value);
         }
@@ -138,7 +140,7 @@ impl<S: StateStore> JoinEntryState<S> {
 
             match v.into_option() {
                 Some(v) => {
-                    let value = v.serialize()?;
+                    let value = encode_state_value(&v.serialize()?);
                     // TODO(Yuanxin): Implement value meta
                     local.put(key_encoded, StorageValue::new_default_put(value));
                 }
@@ -26,6 +26,8 @@ use risingwave_common::hash::{HashKey, PrecomputedBuildHasher};
 use risingwave_common::types::{DataType, Datum};
 use risingwave_storage::{Keyspace, StateStore};
 
+use super::ManagedCache;
+
 /// This is a row with a match degree
 #[derive(Clone, Debug)]
 pub struct JoinRow {
@@ -82,7 +84,7 @@ impl JoinRow {
         let mut vec = Vec::with_capacity(10);
 
         // Serialize row.
-        vec.extend(self.row.value_encode()?);
+        vec.extend(self.row.compact_value_encode()?);
 
         // Serialize degree.
         vec.extend(self.degree.to_le_bytes());
@@ -93,19 +95,20 @@ impl JoinRow {
 
 /// Deserializer of the `JoinRow`.
 pub struct JoinRowDeserializer {
-    data_types: Vec<DataType>,
+    row_deserializer: RowDeserializer,
 }
 
 impl JoinRowDeserializer {
-    /// Creates a new `RowDeserializer` with row schema.
-    pub fn new(schema: Vec<DataType>) -> Self {
-        JoinRowDeserializer { data_types: schema }
+    /// Creates a new `JoinRowDeserializer` with row schema.
+    pub fn new(schema: impl Into<Arc<[DataType]>>) -> Self {
+        JoinRowDeserializer {
+            row_deserializer: RowDeserializer::new(schema),
+        }
     }
 
     /// Deserialize the [`JoinRow`] from a value encoding bytes.
     pub fn deserialize(&self, mut data: impl Buf) -> RwResult<JoinRow> {
-        let deserializer = RowDeserializer::new(self.data_types.clone());
-        let row = deserializer.value_decode(&mut data)?;
+        let row = self.row_deserializer.value_decode(&mut data)?;
         let degree = data.get_u64_le();
         Ok(JoinRow { row, degree })
     }
@@ -206,11 +209,12 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
     }
 
     /// Returns a mutable reference to the value of the key in the memory, if does not exist, look
-    /// up in remote storage and return the [`JoinEntryState`] without cached state, if still not
-    /// exist, return None.
+    /// up the row of `pk` in remote storage with a point-get and return the [`JoinEntryState`]
+    /// without cached state, if still not exist, return None.
     pub async fn get_mut_without_cached<'a, 'b: 'a>(
         &'a mut self,
         key: &'b K,
+        pk: &Row,
     ) -> RwResult<Option<&'a mut HashValueType<S>>> {
         let state = self.inner.get(key);
         // TODO: we should probably implement a entry function for `LruCache`
@@ -218,9 +222,8 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
             Some(_) => Ok(self.inner.get_mut(key)),
             None => {
                 let keyspace = self.get_state_keyspace(key)?;
-                let all_data = keyspace.scan(None, self.current_epoch).await.unwrap();
-                let total_count = all_data.len();
-                if total_count > 0 {
+                let row = keyspace.get(pk.value_encode()?, self.current_epoch).await?;
+                if row.is_some() {
                     let state = JoinEntryState::new(
                         keyspace,
                         self.data_types.clone(),
@@ -295,6 +298,16 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
     }
 }
 
+impl<K: HashKey, S: StateStore> ManagedCache for JoinHashMap<K, S> {
+    fn cached_entries(&self) -> usize {
+        self.inner.cached_entries()
+    }
+
+    fn evict(&mut self, watermark: usize) {
+        self.inner.evict(watermark)
+    }
+}
+
 impl<K: HashKey, S: StateStore> Deref for JoinHashMap<K, S> {
     type Target = EvictableHashMap<K, HashValueType<S>, PrecomputedBuildHasher>;
 
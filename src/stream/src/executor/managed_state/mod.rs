@@ -15,4 +15,74 @@
 pub mod aggregation;
 pub mod flush_status;
 pub mod join;
+pub mod state_version;
 pub mod top_n;
+
+use std::hash::{BuildHasher, Hash};
+
+use risingwave_common::collection::evictable::EvictableHashMap;
+
+/// A managed state caching entries in memory, which shrinks on the request of the
+/// [`MemoryManager`](crate::task::MemoryManager).
+pub trait ManagedCache {
+    /// The number of entries cached in memory.
+    fn cached_entries(&self) -> usize;
+
+    /// Evict the cached entries so that at most `watermark` of them remain. Should only be called
+    /// when the state is not dirty, i.e., right after flushing.
+    fn evict(&mut self, watermark: usize);
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ManagedCache for EvictableHashMap<K, V, S> {
+    fn cached_entries(&self) -> usize {
+        self.len()
+    }
+
+    /// Besides the `watermark`, never keep more than the target capacity.
+    fn evict(&mut self, watermark: usize) {
+        self.resize(self.target_cap().min(watermark));
+        self.resize(usize::MAX);
+    }
+}
+
+/// Evict the `caches` in proportion to their sizes, so that at most `watermark` entries remain in
+/// total. Returns the number of the remaining entries.
+pub fn evict_in_proportion(caches: &mut [&mut dyn ManagedCache], watermark: usize) -> usize {
+    let total: usize = caches.iter().map(|cache| cache.cached_entries()).sum();
+    for cache in caches.iter_mut() {
+        let share = if total == 0 {
+            watermark
+        } else {
+            (watermark as u128 * cache.cached_entries() as u128 / total as u128) as usize
+        };
+        cache.evict(share);
+    }
+    caches.iter().map(|cache| cache.cached_entries()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evict_in_proportion() {
+        let mut small = EvictableHashMap::new(100);
+        let mut large = EvictableHashMap::new(100);
+        (0..20).for_each(|i| {
+            small.put(i, ());
+        });
+        (0..60).for_each(|i| {
+            large.put(i, ());
+        });
+
+        // Only evict to the target capacity without a watermark.
+        assert_eq!(
+            evict_in_proportion(&mut [&mut small, &mut large], usize::MAX),
+            80
+        );
+
+        assert_eq!(evict_in_proportion(&mut [&mut small, &mut large], 40), 40);
+        assert_eq!(small.cached_entries(), 10);
+        assert_eq!(large.cached_entries(), 30);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Every value written by managed states is prefixed with one byte of the version of its encoding,
+//! and every row written into state tables ends with a column of the version. Values and rows of
+//! any other version are rejected on read instead of being misread. When the encoding changes, bump
+//! [`STATE_ENCODING_VERSION`] and convert the values of the previous versions where they're
+//! decoded.
+
+use bytes::{Buf, Bytes};
+use risingwave_common::array::Row;
+use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::{DataType, ScalarImpl};
+
+/// The version of the encoding of the values written by managed states.
+pub const STATE_ENCODING_VERSION: u8 = 1;
+
+/// Prefixes an encoded value with the current version.
+pub fn encode_state_value(value: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(value.len() + 1);
+    buf.push(STATE_ENCODING_VERSION);
+    buf.extend_from_slice(value);
+    buf
+}
+
+/// Strips the version from a value read from the storage. Fails if it's not written in the current
+/// encoding.
+pub fn decode_state_value(mut value: Bytes) -> Result<Bytes> {
+    if value.is_empty() {
+        return Err(ErrorCode::InternalError(
+            "state value is missing the encoding version".to_string(),
+        )
+        .into());
+    }
+    let version = value.get_u8();
+    if version != STATE_ENCODING_VERSION {
+        return Err(unsupported_version(version));
+    }
+    Ok(value)
+}
+
+/// Appends the column of the version to the columns of a state table, whose ids are assumed to be
+/// consecutive from 0.
+pub fn with_version_column(mut column_descs: Vec<ColumnDesc>) -> Vec<ColumnDesc> {
+    let column_id = ColumnId::new(column_descs.len() as i32);
+    column_descs.push(ColumnDesc::unnamed(column_id, DataType::Int16));
+    column_descs
+}
+
+/// Appends the current version to a row to be written into a state table.
+pub fn encode_state_row(mut row: Row) -> Row {
+    row.0
+        .push(Some(ScalarImpl::Int16(STATE_ENCODING_VERSION as i16)));
+    row
+}
+
+/// Strips the version from a row read from a state table. Fails if it's not written in the current
+/// encoding.
+pub fn decode_state_row(mut row: Row) -> Result<Row> {
+    match row.0.pop() {
+        Some(Some(ScalarImpl::Int16(version))) if version == STATE_ENCODING_VERSION as i16 => {
+            Ok(row)
+        }
+        Some(Some(ScalarImpl::Int16(version))) => Err(unsupported_version(version as u8)),
+        _ => Err(
+            ErrorCode::InternalError("state row is missing the encoding version".to_string())
+                .into(),
+        ),
+    }
+}
+
+fn unsupported_version(version: u8) -> RwError {
+    ErrorCode::InternalError(format!(
+        "unsupported state encoding version {}, the current version is {}",
+        version, STATE_ENCODING_VERSION
+    ))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_value_version() {
+        let encoded = encode_state_value(b"value");
+        assert_eq!(encoded[0], STATE_ENCODING_VERSION);
+
+        let decoded = decode_state_value(Bytes::from(encoded)).unwrap();
+        assert_eq!(&decoded[..], b"value");
+
+        assert!(decode_state_value(Bytes::new()).is_err());
+        assert!(decode_state_value(Bytes::from_static(&[STATE_ENCODING_VERSION + 1])).is_err());
+    }
+
+    #[test]
+    fn test_state_row_version() {
+        let row = Row(vec![Some(ScalarImpl::Int64(1)), None]);
+        let encoded = encode_state_row(row.clone());
+        assert_eq!(encoded.0.len(), 3);
+
+        let decoded = decode_state_row(encoded).unwrap();
+        assert_eq!(decoded, row);
+
+        assert!(decode_state_row(Row(vec![])).is_err());
+        assert!(decode_state_row(Row(vec![Some(ScalarImpl::Int64(1)), None])).is_err());
+        assert!(decode_state_row(Row(vec![Some(ScalarImpl::Int16(0))])).is_err());
+    }
+}
@@ -24,6 +24,8 @@ use risingwave_storage::StateStoreIter;
 pub use top_n_bottom_n_state::ManagedTopNBottomNState;
 pub use top_n_state::ManagedTopNState;
 
+use super::state_version::decode_state_value;
+
 pub mod variants {
     pub const TOP_N_MIN: usize = 0;
     pub const TOP_N_MAX: usize = 1;
@@ -62,35 +64,34 @@ impl<'a, I: StateStoreIter<Item = (Bytes, Bytes)>, const TOP_N_TYPE: usize>
         }
     }
 
-    async fn deserialize_bytes_to_pk_and_row(&mut self) -> Result<Option<(OrderedRow, Row)>> {
+    /// Returns the next row with its pk left encoded as it is in the storage, i.e., reversely
+    /// serialized for `TOP_N_MAX`. The encoding is memcomparable, so the pks can be compared with
+    /// each other in the order of the storage without being decoded.
+    pub async fn next_encoded(&mut self) -> Result<Option<(Vec<u8>, Row)>> {
         while let Some((key, value)) = self.iter.next().await? {
+            let value = decode_state_value(value)?;
             let pk_buf_and_row = self.cell_based_row_deserializer.deserialize(&key, &value)?;
-            match pk_buf_and_row {
-                Some((mut pk_buf, row)) => {
-                    let pk =
-                        deserialize_pk::<TOP_N_TYPE>(&mut pk_buf, self.ordered_row_deserializer)?;
-                    return Ok(Some((pk, row)));
-                }
-                None => {}
+            if pk_buf_and_row.is_some() {
+                return Ok(pk_buf_and_row);
             }
         }
         // Reaching here implies that all the key value pairs have been drained from `self.iter`.
         // Try to take out the final row.
         // It is possible that `self.iter` is empty, so we may read nothing and there is no such
         // final row.
-        let pk_buf_and_row = self.cell_based_row_deserializer.take();
-        if let Some(mut pk_buf_and_row) = pk_buf_and_row {
-            let pk =
-                deserialize_pk::<TOP_N_TYPE>(&mut pk_buf_and_row.0, self.ordered_row_deserializer)?;
-            Ok(Some((pk, pk_buf_and_row.1)))
-        } else {
-            Ok(None)
-        }
+        Ok(self.cell_based_row_deserializer.take())
+    }
+
+    /// Decodes a pk returned by [`Self::next_encoded`].
+    pub fn decode_pk(&mut self, mut pk_buf: Vec<u8>) -> Result<OrderedRow> {
+        deserialize_pk::<TOP_N_TYPE>(&mut pk_buf, self.ordered_row_deserializer)
     }
 
     pub async fn next(&mut self) -> Result<Option<(OrderedRow, Row)>> {
-        let pk_and_row = self.deserialize_bytes_to_pk_and_row().await?;
-        Ok(pk_and_row)
+        match self.next_encoded().await? {
+            Some((pk_buf, row)) => Ok(Some((self.decode_pk(pk_buf)?, row))),
+            None => Ok(None),
+        }
     }
 }
 
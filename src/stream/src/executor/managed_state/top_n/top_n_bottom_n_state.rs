@@ -28,6 +28,7 @@ use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::super::flush_status::BtreeMapFlushStatus as FlushStatus;
+use super::super::state_version::encode_state_value;
 use super::variants::TOP_N_MIN;
 use super::PkAndRowIterator;
 
@@ -286,8 +287,7 @@ impl<S: StateStore> ManagedTopNBottomNState<S> {
             return Ok(());
         }
 
-        let mut write_batch = self.keyspace.state_store().start_write_batch();
-        let mut local = write_batch.prefixify(&self.keyspace);
+        let mut write_batch = self.keyspace.start_write_batch();
 
         for (pk, cells) in std::mem::take(&mut self.flush_buffer) {
             let row = cells.into_option();
@@ -300,8 +300,9 @@ impl<S: StateStore> ManagedTopNBottomNState<S> {
             for (key, value) in bytes {
                 match value {
                     // TODO(Yuanxin): Implement value meta
-                    Some(val) => local.put(key, StorageValue::new_default_put(val)),
-                    None => local.delete(key),
+                    Some(val) => write_batch
+                        .put(key, StorageValue::new_default_put(encode_state_value(&val))),
+                    None => write_batch.delete(key),
                 }
             }
         }
@@ -25,6 +25,8 @@ use risingwave_storage::storage_value::StorageValue;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::super::flush_status::BtreeMapFlushStatus as FlushStatus;
+use super::super::state_version::encode_state_value;
+use super::super::ManagedCache;
 use super::variants::*;
 use super::PkAndRowIterator;
 
@@ -184,101 +186,72 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         // We remark that:
         // 1. if TOP_N_MIN, kv_pairs is sorted in ascending order.
         // 2. if TOP_N_MAX, kv_pairs is sorted in descending order.
-        // while flush_buffer is always sorted in ascending order.
         // This `order` is defined by the order between two `OrderedRow`.
+        // So the keys from the flush buffer are encoded the same as the ones in the storage and
+        // walked in the same order, by which the keys from the storage are compared as bytes, and
+        // only the ones going into the cache are decoded.
         // We have to scan all because the top n on the storage may have been deleted by the flush
         // buffer.
+        let flush_buffer = match TOP_N_TYPE {
+            TOP_N_MIN => self
+                .flush_buffer
+                .iter()
+                .map(|(key, value)| -> Result<_> { Ok((key.serialize()?, key, value)) })
+                .collect::<Result<Vec<_>>>()?,
+            TOP_N_MAX => self
+                .flush_buffer
+                .iter()
+                .rev()
+                .map(|(key, value)| -> Result<_> { Ok((key.reverse_serialize()?, key, value)) })
+                .collect::<Result<Vec<_>>>()?,
+            _ => unreachable!(),
+        };
         let iter = self.keyspace.iter(epoch).await?;
         let mut pk_and_row_iter = PkAndRowIterator::<_, TOP_N_TYPE>::new(
             iter,
             &mut self.ordered_row_deserializer,
             &mut self.cell_based_row_deserializer,
         );
-        match TOP_N_TYPE {
-            TOP_N_MIN => {
-                let mut flush_buffer_iter = self.flush_buffer.iter().peekable();
-                while let Some((key_from_storage, row_from_storage)) =
-                    pk_and_row_iter.next().await?
-                {
-                    // If we inserted enough values, break as we will only retain `top_n_count`
-                    // elements in the cache.
-                    if let Some(top_n_count) = self.top_n_count && self.top_n.len() >= top_n_count {
+        let mut flush_buffer_iter = flush_buffer.iter().peekable();
+        while let Some((pk_buf, row_from_storage)) = pk_and_row_iter.next_encoded().await? {
+            // If we inserted enough values, break as we will only retain `top_n_count`
+            // elements in the cache.
+            if let Some(top_n_count) = self.top_n_count && self.top_n.len() >= top_n_count {
+                break;
+            }
+            let mut encounter_same_key = false;
+            while let Some((pk_buf_from_buffer, key_from_buffer, value_from_buffer)) =
+                flush_buffer_iter.peek()
+            {
+                match pk_buf_from_buffer.cmp(&pk_buf) {
+                    Ordering::Greater => {
+                        // We need to break to determine whether the next `pk_buf` will be
+                        // shadowed by `key_from_buffer` in the next for loop.
                         break;
                     }
-                    let mut encounter_same_key = false;
-                    while let Some((key_from_buffer, value_from_buffer)) = flush_buffer_iter.peek()
-                    {
-                        match key_from_buffer.cmp(&&key_from_storage) {
-                            Ordering::Greater => {
-                                // We need to break to determine whether the next `key_from_storage`
-                                // will be shadowed by `key_from_buffer` in the next for loop.
-                                break;
-                            }
-                            Ordering::Equal => {
-                                // The `key_from_storage` is shadowed by `key_from_buffer`.
-                                // We do not want to add `key_from_storage` into the result set
-                                // after the while loop.
-                                encounter_same_key = true;
-                            }
-                            _ => {
-                                // do nothing
-                            }
-                        }
-                        match value_from_buffer {
-                            FlushStatus::Insert(row) | FlushStatus::DeleteInsert(row) => {
-                                self.top_n.insert((*key_from_buffer).clone(), row.clone());
-                            }
-                            FlushStatus::Delete => {
-                                // do nothing
-                            }
-                        }
-                        flush_buffer_iter.next();
+                    Ordering::Equal => {
+                        // The key from the storage is shadowed by `key_from_buffer`.
+                        // We do not want to add it into the result set after the while loop.
+                        encounter_same_key = true;
                     }
-                    if !encounter_same_key {
-                        self.top_n.insert(key_from_storage, row_from_storage);
+                    _ => {
+                        // do nothing
                     }
                 }
-            }
-            TOP_N_MAX => {
-                let mut flush_buffer_iter = self.flush_buffer.iter().rev().peekable();
-                while let Some((key_from_storage, row_from_storage)) =
-                    pk_and_row_iter.next().await?
-                {
-                    if let Some(top_n_count) = self.top_n_count && self.top_n.len() >= top_n_count {
-                        break;
-                    }
-                    let mut encounter_same_key = false;
-                    // This is similar to `TOP_N_MIN` branch above. May refer to its comments for
-                    // the code below.
-                    while let Some((key_from_buffer, value_from_buffer)) = flush_buffer_iter.peek()
-                    {
-                        match key_from_buffer.cmp(&&key_from_storage) {
-                            Ordering::Less => {
-                                break;
-                            }
-                            Ordering::Equal => {
-                                encounter_same_key = true;
-                            }
-                            _ => {
-                                // do nothing
-                            }
-                        }
-                        match value_from_buffer {
-                            FlushStatus::Insert(row) | FlushStatus::DeleteInsert(row) => {
-                                self.top_n.insert((*key_from_buffer).clone(), row.clone());
-                            }
-                            FlushStatus::Delete => {
-                                // do nothing
-                            }
-                        }
-                        flush_buffer_iter.next();
+                match value_from_buffer {
+                    FlushStatus::Insert(row) | FlushStatus::DeleteInsert(row) => {
+                        self.top_n.insert((*key_from_buffer).clone(), row.clone());
                     }
-                    if !encounter_same_key {
-                        self.top_n.insert(key_from_storage, row_from_storage);
+                    FlushStatus::Delete => {
+                        // do nothing
                     }
                 }
+                flush_buffer_iter.next();
+            }
+            if !encounter_same_key {
+                let key_from_storage = pk_and_row_iter.decode_pk(pk_buf)?;
+                self.top_n.insert(key_from_storage, row_from_storage);
             }
-            _ => unreachable!(),
         }
         Ok(())
     }
@@ -326,8 +299,7 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         iterator: impl Iterator<Item = (OrderedRow, FlushStatus<Row>)>,
         epoch: u64,
     ) -> Result<()> {
-        let mut write_batch = self.keyspace.state_store().start_write_batch();
-        let mut local = write_batch.prefixify(&self.keyspace);
+        let mut write_batch = self.keyspace.start_write_batch();
         for (pk, cells) in iterator {
             let row = cells.into_option();
             let pk_buf = match TOP_N_TYPE {
@@ -342,8 +314,9 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
             for (key, value) in bytes {
                 match value {
                     // TODO(Yuanxin): Implement value meta
-                    Some(val) => local.put(key, StorageValue::new_default_put(val)),
-                    None => local.delete(key),
+                    Some(val) => write_batch
+                        .put(key, StorageValue::new_default_put(encode_state_value(&val))),
+                    None => write_batch.delete(key),
                 }
             }
         }
@@ -370,6 +343,29 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
     }
 }
 
+impl<S: StateStore, const TOP_N_TYPE: usize> ManagedCache for ManagedTopNState<S, TOP_N_TYPE> {
+    fn cached_entries(&self) -> usize {
+        self.top_n.len()
+    }
+
+    /// The cache always keeps the top element if the state is not empty, and is refilled from the
+    /// storage once drained.
+    fn evict(&mut self, watermark: usize) {
+        debug_assert!(!self.is_dirty());
+        while self.top_n.len() > watermark.max(1) {
+            match TOP_N_TYPE {
+                TOP_N_MIN => {
+                    self.top_n.pop_last();
+                }
+                TOP_N_MAX => {
+                    self.top_n.pop_first();
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
 /// Test-related methods
 impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
     #[cfg(test)]
@@ -380,6 +376,7 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use risingwave_common::catalog::ColumnDesc;
     use risingwave_common::types::DataType;
     use risingwave_common::util::sort_util::OrderType;
@@ -552,4 +549,53 @@ mod tests {
             Some((&ordered_rows[3], &rows[3]))
         );
     }
+
+    #[tokio::test]
+    async fn test_managed_top_n_state_evict() {
+        let store = MemoryStateStore::new();
+        let data_types = vec![DataType::Varchar, DataType::Int64];
+        let order_types = vec![OrderType::Descending, OrderType::Ascending];
+
+        let mut managed_state =
+            create_managed_top_n_state::<_, TOP_N_MAX>(&store, 0, data_types, order_types.clone());
+
+        let rows = vec![
+            row_nonnull!["abc".to_string(), 3i64],
+            row_nonnull!["abd".to_string(), 3i64],
+            row_nonnull!["ab".to_string(), 4i64],
+        ];
+        let ordered_rows = rows
+            .iter()
+            .map(|row| OrderedRow::new(row.clone(), &order_types))
+            .collect::<Vec<_>>();
+
+        let epoch = 0;
+        for (ordered_row, row) in ordered_rows.iter().zip_eq(&rows) {
+            managed_state
+                .insert(ordered_row.clone(), row.clone(), epoch)
+                .await
+                .unwrap();
+        }
+        managed_state.flush(epoch).await.unwrap();
+        assert_eq!(managed_state.cached_entries(), 2);
+
+        // The top element is always kept.
+        managed_state.evict(0);
+        assert_eq!(managed_state.cached_entries(), 1);
+        assert_eq!(
+            managed_state.top_element(),
+            Some((&ordered_rows[2], &rows[2]))
+        );
+
+        // The evicted elements are fetched from the storage again when needed.
+        assert_eq!(
+            managed_state.pop_top_element(epoch).await.unwrap(),
+            Some((ordered_rows[2].clone(), rows[2].clone()))
+        );
+        assert_eq!(
+            managed_state.top_element(),
+            Some((&ordered_rows[0], &rows[0]))
+        );
+        assert_eq!(managed_state.total_count(), 2);
+    }
 }
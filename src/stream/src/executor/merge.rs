@@ -19,6 +19,7 @@ use async_trait::async_trait;
 use futures::channel::mpsc::{Receiver, Sender};
 use futures::{SinkExt, Stream, StreamExt};
 use futures_async_stream::for_await;
+use itertools::Itertools;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 use risingwave_pb::task_service::GetStreamResponse;
@@ -28,7 +29,7 @@ use tonic::Streaming;
 use super::error::StreamExecutorError;
 use super::*;
 use crate::executor::monitor::StreamingMetrics;
-use crate::task::UpDownActorIds;
+use crate::task::{BufferOccupancy, UpDownActorIds};
 
 /// Receive data from `gRPC` and forwards to `MergerExecutor`/`ReceiverExecutor`
 pub struct RemoteInput {
@@ -98,6 +99,9 @@ pub struct MergeExecutor {
     /// Upstream channels.
     upstreams: Vec<Receiver<Message>>,
 
+    /// Occupancy of each upstream channel, decreased on receiving.
+    occupancies: Vec<BufferOccupancy>,
+
     /// Belonged actor id.
     actor_id: u32,
 
@@ -117,6 +121,7 @@ impl MergeExecutor {
         receiver_id: u64,
     ) -> Self {
         Self {
+            occupancies: vec![BufferOccupancy::default(); inputs.len()],
             upstreams: inputs,
             actor_id,
             info: ExecutorInfo {
@@ -127,12 +132,23 @@ impl MergeExecutor {
             status: OperatorInfoStatus::new(actor_context, receiver_id),
         }
     }
+
+    #[must_use]
+    pub fn with_buffer_occupancies(mut self, occupancies: Vec<BufferOccupancy>) -> Self {
+        assert_eq!(occupancies.len(), self.upstreams.len());
+        self.occupancies = occupancies;
+        self
+    }
 }
 
 #[async_trait]
 impl Executor for MergeExecutor {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
-        let upstreams = self.upstreams;
+        let upstreams = self
+            .upstreams
+            .into_iter()
+            .zip_eq(self.occupancies)
+            .collect();
         // Futures of all active upstreams.
         let status = self.status;
         let select_all = SelectReceivers::new(self.actor_id, status, upstreams);
@@ -154,8 +170,8 @@ impl Executor for MergeExecutor {
 }
 
 pub struct SelectReceivers {
-    blocks: Vec<Receiver<Message>>,
-    upstreams: Vec<Receiver<Message>>,
+    blocks: Vec<(Receiver<Message>, BufferOccupancy)>,
+    upstreams: Vec<(Receiver<Message>, BufferOccupancy)>,
     barrier: Option<Barrier>,
     last_base: usize,
     status: OperatorInfoStatus,
@@ -163,7 +179,11 @@ pub struct SelectReceivers {
 }
 
 impl SelectReceivers {
-    fn new(actor_id: u32, status: OperatorInfoStatus, upstreams: Vec<Receiver<Message>>) -> Self {
+    fn new(
+        actor_id: u32,
+        status: OperatorInfoStatus,
+        upstreams: Vec<(Receiver<Message>, BufferOccupancy)>,
+    ) -> Self {
         Self {
             blocks: Vec::with_capacity(upstreams.len()),
             upstreams,
@@ -184,7 +204,8 @@ impl Stream for SelectReceivers {
         let mut poll_count = 0;
         while poll_count < self.upstreams.len() {
             let idx = (poll_count + self.last_base) % self.upstreams.len();
-            match self.upstreams[idx].poll_next_unpin(cx) {
+            let (upstream, occupancy) = &mut self.upstreams[idx];
+            match upstream.poll_next_unpin(cx) {
                 Poll::Pending => {
                     poll_count += 1;
                     continue;
@@ -193,6 +214,7 @@ impl Stream for SelectReceivers {
                     let message = item.expect(
                         "upstream channel closed unexpectedly, please check error in upstream executors"
                     );
+                    occupancy.dec();
                     match message {
                         Message::Barrier(barrier) => {
                             let rc = self.upstreams.swap_remove(idx);
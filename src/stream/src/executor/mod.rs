@@ -31,9 +31,10 @@ use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation as ProstMutation;
 use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::data::{
-    AddMutation, Barrier as ProstBarrier, DispatcherMutation, Epoch as ProstEpoch, NothingMutation,
-    SourceChangeSplit, SourceChangeSplitMutation, StopMutation,
-    StreamMessage as ProstStreamMessage, UpdateMutation,
+    AddMutation, Barrier as ProstBarrier, DispatcherHashMapping, DispatcherMutation,
+    Epoch as ProstEpoch, NothingMutation, PauseMutation, ResumeMutation, SourceChangeSplit,
+    SourceChangeSplitMutation, StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
+    UpdateVnodeBitmapMutation,
 };
 use smallvec::SmallVec;
 use tracing::trace_span;
@@ -53,17 +54,20 @@ mod global_simple_agg;
 mod hash_agg;
 pub mod hash_join;
 mod hop_window;
+mod local_exchange;
 mod local_simple_agg;
 mod lookup;
 mod lookup_union;
-mod managed_state;
+pub mod managed_state;
 pub mod merge;
 pub mod monitor;
 mod mview;
 mod project;
+mod project_set;
 mod rearranged_chain;
 pub mod receiver;
 mod simple;
+mod sink;
 mod source;
 mod top_n;
 mod top_n_appendonly;
@@ -78,21 +82,24 @@ mod test_utils;
 pub use actor::{Actor, ActorContext, ActorContextRef, OperatorInfo, OperatorInfoStatus};
 pub use batch_query::BatchQueryExecutor;
 pub use chain::ChainExecutor;
-pub use debug::DebugExecutor;
+pub use debug::{DebugExecutor, ExecutorLabels};
 pub use dispatch::DispatchExecutor;
 pub use filter::FilterExecutor;
 pub use global_simple_agg::SimpleAggExecutor;
 pub use hash_agg::HashAggExecutor;
 pub use hash_join::*;
 pub use hop_window::HopWindowExecutor;
+pub use local_exchange::LocalExchangeExecutor;
 pub use local_simple_agg::LocalSimpleAggExecutor;
 pub use lookup::*;
 pub use lookup_union::LookupUnionExecutor;
 pub use merge::MergeExecutor;
 pub use mview::*;
-pub use project::ProjectExecutor;
+pub use project::{ExprErrorPolicy, ProjectExecutor};
+pub use project_set::ProjectSetExecutor;
 pub use rearranged_chain::RearrangedChainExecutor;
 use simple::{SimpleExecutor, SimpleExecutorWrapper};
+pub use sink::SinkExecutor;
 pub use source::*;
 pub use top_n::TopNExecutor;
 pub use top_n_appendonly::AppendOnlyTopNExecutor;
@@ -168,6 +175,17 @@ pub enum Mutation {
     UpdateOutputs(HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>),
     AddOutput(HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>),
     SourceChangeSplit(HashMap<ActorId, ConnectorState>),
+    /// Pause the sources. Barriers still flow through the graph.
+    Pause,
+    /// Resume the sources paused by [`Mutation::Pause`].
+    Resume,
+    /// Reassign the vnodes owned by the actors, and switch the hash dispatchers routing to them to
+    /// the new mappings. Stateful executors of these actors should drop the cached states of the
+    /// vnodes moved out, since they may be changed by others.
+    UpdateVnodeBitmaps {
+        actor_vnode_bitmaps: HashMap<ActorId, Vec<u8>>,
+        dispatcher_hash_mappings: HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -210,6 +228,10 @@ pub struct Barrier {
     pub epoch: Epoch,
     pub mutation: Option<Arc<Mutation>>,
     pub span: tracing::Span,
+
+    /// Whether the states should be persisted at this barrier. Executors are allowed to keep their
+    /// dirty states in memory until the next checkpoint barrier, as hash join and top-n do.
+    pub checkpoint: bool,
 }
 
 impl Default for Barrier {
@@ -218,6 +240,7 @@ impl Default for Barrier {
             span: tracing::Span::none(),
             epoch: Epoch::default(),
             mutation: None,
+            checkpoint: true,
         }
     }
 }
@@ -239,13 +262,18 @@ impl Barrier {
         }
     }
 
+    #[must_use]
+    pub fn with_checkpoint(self, checkpoint: bool) -> Self {
+        Self { checkpoint, ..self }
+    }
+
     #[must_use]
     pub fn with_stop(self) -> Self {
         self.with_mutation(Mutation::Stop(HashSet::default()))
     }
 
-    // TODO: The barrier should always contain trace info after we migrated barrier generation to
-    // meta service.
+    /// Attaches the span that the actors will poll under after collecting this barrier, which
+    /// follows the one of the meta node if it's injected with a tracing context.
     #[must_use]
     pub fn with_span(self, span: tracing::span::Span) -> Self {
         Self { span, ..self }
@@ -255,6 +283,37 @@ impl Barrier {
         matches!(self.mutation.as_deref(), Some(Mutation::Stop(actors)) if actors.contains(&actor_id))
     }
 
+    pub fn is_pause(&self) -> bool {
+        matches!(self.mutation.as_deref(), Some(Mutation::Pause))
+    }
+
+    pub fn is_resume(&self) -> bool {
+        matches!(self.mutation.as_deref(), Some(Mutation::Resume))
+    }
+
+    /// Returns the new vnode bitmap of the actor if the barrier reassigns its vnodes.
+    pub fn as_update_vnode_bitmap(&self, actor_id: ActorId) -> Option<&[u8]> {
+        match self.mutation.as_deref() {
+            Some(Mutation::UpdateVnodeBitmaps {
+                actor_vnode_bitmaps,
+                ..
+            }) => actor_vnode_bitmaps
+                .get(&actor_id)
+                .map(|bitmap| bitmap.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns the splits assigned to the source actor if the barrier changes them.
+    pub fn as_source_change_split(&self, actor_id: ActorId) -> Option<&[SplitImpl]> {
+        match self.mutation.as_deref() {
+            Some(Mutation::SourceChangeSplit(changes)) => {
+                changes.get(&actor_id).and_then(|splits| splits.as_deref())
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_to_add_output(&self, actor_id: ActorId) -> bool {
         matches!(
             self.mutation.as_deref(),
@@ -268,7 +327,9 @@ impl Barrier {
 
 impl PartialEq for Barrier {
     fn eq(&self, other: &Self) -> bool {
-        self.epoch == other.epoch && self.mutation == other.mutation
+        self.epoch == other.epoch
+            && self.mutation == other.mutation
+            && self.checkpoint == other.checkpoint
     }
 }
 
@@ -285,7 +346,10 @@ impl Mutation {
 impl Barrier {
     pub fn to_protobuf(&self) -> ProstBarrier {
         let Barrier {
-            epoch, mutation, ..
+            epoch,
+            mutation,
+            checkpoint,
+            ..
         }: Barrier = self.clone();
         ProstBarrier {
             epoch: Some(ProstEpoch {
@@ -341,8 +405,32 @@ impl Barrier {
                             .collect(),
                     }))
                 }
+                Some(Mutation::Pause) => Some(ProstMutation::Pause(PauseMutation {})),
+                Some(Mutation::Resume) => Some(ProstMutation::Resume(ResumeMutation {})),
+                Some(Mutation::UpdateVnodeBitmaps {
+                    actor_vnode_bitmaps,
+                    dispatcher_hash_mappings,
+                }) => Some(ProstMutation::UpdateVnodeBitmap(
+                    UpdateVnodeBitmapMutation {
+                        actor_vnode_bitmaps: actor_vnode_bitmaps
+                            .iter()
+                            .map(|(&actor_id, bitmap)| (actor_id, bitmap.clone()))
+                            .collect(),
+                        dispatcher_hash_mappings: dispatcher_hash_mappings
+                            .iter()
+                            .map(|(&(actor_id, dispatcher_id), hash_mapping)| {
+                                DispatcherHashMapping {
+                                    actor_id,
+                                    dispatcher_id,
+                                    hash_mapping: hash_mapping.clone(),
+                                }
+                            })
+                            .collect(),
+                    },
+                )),
             },
             span: vec![],
+            checkpoint,
         }
     }
 
@@ -411,6 +499,28 @@ impl Barrier {
                     .into(),
                 )
             }
+            ProstMutation::Pause(_) => Some(Mutation::Pause.into()),
+            ProstMutation::Resume(_) => Some(Mutation::Resume.into()),
+            ProstMutation::UpdateVnodeBitmap(update) => Some(
+                Mutation::UpdateVnodeBitmaps {
+                    actor_vnode_bitmaps: update
+                        .actor_vnode_bitmaps
+                        .iter()
+                        .map(|(&actor_id, bitmap)| (actor_id, bitmap.clone()))
+                        .collect(),
+                    dispatcher_hash_mappings: update
+                        .dispatcher_hash_mappings
+                        .iter()
+                        .map(|mapping| {
+                            (
+                                (mapping.actor_id, mapping.dispatcher_id),
+                                mapping.hash_mapping.clone(),
+                            )
+                        })
+                        .collect(),
+                }
+                .into(),
+            ),
         };
         let epoch = prost.get_epoch().unwrap();
         Ok(Barrier {
@@ -421,6 +531,7 @@ impl Barrier {
             },
             epoch: Epoch::new(epoch.curr, epoch.prev),
             mutation,
+            checkpoint: prost.checkpoint,
         })
     }
 }
@@ -12,18 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use prometheus::core::{AtomicF64, AtomicU64, GenericCounterVec, GenericGaugeVec};
+use prometheus::core::{AtomicF64, AtomicI64, AtomicU64, GenericCounterVec, GenericGaugeVec};
 use prometheus::{
-    register_gauge_vec_with_registry, register_int_counter_vec_with_registry, Registry,
+    exponential_buckets, histogram_opts, register_gauge_vec_with_registry,
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, HistogramVec, Registry,
 };
 
+/// The labels of the metrics of each executor.
+pub const EXECUTOR_LABELS: &[&str] = &["table_id", "fragment_id", "actor_id", "executor_id"];
+
 pub struct StreamingMetrics {
     pub registry: Registry,
     pub actor_row_count: GenericCounterVec<AtomicU64>,
     pub actor_processing_time: GenericGaugeVec<AtomicF64>,
     pub actor_barrier_time: GenericGaugeVec<AtomicF64>,
+    pub actor_barrier_latency: HistogramVec,
     pub source_output_row_count: GenericCounterVec<AtomicU64>,
     pub exchange_recv_size: GenericCounterVec<AtomicU64>,
+    pub executor_input_row_count: GenericCounterVec<AtomicU64>,
+    pub executor_output_row_count: GenericCounterVec<AtomicU64>,
+    pub executor_processing_time: HistogramVec,
+    pub executor_cache_entries: GenericGaugeVec<AtomicI64>,
+    pub expr_error_count: GenericCounterVec<AtomicU64>,
 }
 
 impl StreamingMetrics {
@@ -60,6 +71,14 @@ impl StreamingMetrics {
         )
         .unwrap();
 
+        let opts = histogram_opts!(
+            "stream_actor_barrier_latency",
+            "Time between the barrier is issued by the meta service and it gets collected from actor_id",
+            exponential_buckets(0.001, 2.0, 16).unwrap()
+        );
+        let actor_barrier_latency =
+            register_histogram_vec_with_registry!(opts, &["actor_id"], registry).unwrap();
+
         let exchange_recv_size = register_int_counter_vec_with_registry!(
             "stream_exchange_recv_size",
             "Total size of messages that have been received from upstream Actor",
@@ -68,13 +87,59 @@ impl StreamingMetrics {
         )
         .unwrap();
 
+        let executor_input_row_count = register_int_counter_vec_with_registry!(
+            "stream_executor_input_row_count",
+            "Total number of rows that have been received by each executor from its inputs",
+            EXECUTOR_LABELS,
+            registry
+        )
+        .unwrap();
+
+        let executor_output_row_count = register_int_counter_vec_with_registry!(
+            "stream_executor_output_row_count",
+            "Total number of rows that have been output from each executor",
+            EXECUTOR_LABELS,
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "stream_executor_processing_time",
+            "Time spent by each executor on producing a chunk, excluding the time spent by its inputs",
+            exponential_buckets(0.00001, 2.0, 20).unwrap()
+        );
+        let executor_processing_time =
+            register_histogram_vec_with_registry!(opts, EXECUTOR_LABELS, registry).unwrap();
+
+        let executor_cache_entries = register_int_gauge_vec_with_registry!(
+            "stream_executor_cache_entries",
+            "Number of entries cached by each executor, as of the latest barrier",
+            EXECUTOR_LABELS,
+            registry
+        )
+        .unwrap();
+
+        let expr_error_count = register_int_counter_vec_with_registry!(
+            "stream_expr_error_count",
+            "Total number of rows on which the expressions of each executor fail and produce NULL",
+            &["actor_id", "executor_id"],
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
             actor_row_count,
             actor_processing_time,
             actor_barrier_time,
+            actor_barrier_latency,
             source_output_row_count,
             exchange_recv_size,
+            executor_input_row_count,
+            executor_output_row_count,
+            executor_processing_time,
+            executor_cache_entries,
+            expr_error_count,
         }
     }
 
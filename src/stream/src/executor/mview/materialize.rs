@@ -12,12 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::Op::*;
-use risingwave_common::array::Row;
+use risingwave_common::array::{Op, Row, StreamChunk};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::error::{ErrorCode, Result as RwResult, RwError};
+use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_common::util::sort_util::OrderPair;
 use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::{Keyspace, StateStore};
@@ -27,6 +31,50 @@ use crate::executor::{
     BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef,
 };
 
+/// How [`MaterializeExecutor`] handles an insertion whose key already exists in the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictBehavior {
+    /// The keys of the input are known to be unique, e.g. the row id of a table or the pk of
+    /// a materialized view. Rows are written without looking up the existing ones.
+    NoCheck,
+    /// The new row replaces the existing one, and an update is emitted downstream.
+    Overwrite,
+    /// The new row is dropped and the existing one is kept.
+    IgnoreConflict,
+}
+
+/// How [`MaterializeExecutor`] checks the ops against the existing rows, when the keys of the
+/// input are known to be unique, i.e., with [`ConflictBehavior::NoCheck`]. An insertion of an
+/// existing key, or a deletion of a missing key or of a different row, indicates a bug of the
+/// upstream operators, which would otherwise corrupt the table silently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanityCheckMode {
+    /// The ops are written without looking up the existing rows.
+    Off,
+    /// Fails on the first inconsistent op.
+    Strict,
+    /// Logs the inconsistent ops and reconciles them, as if the conflict behavior is
+    /// [`ConflictBehavior::Overwrite`]. Deletions of missing keys are dropped.
+    Lenient,
+}
+
+impl FromStr for SanityCheckMode {
+    type Err = RwError;
+
+    fn from_str(s: &str) -> RwResult<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            _ => Err(ErrorCode::InvalidParameterValue(format!(
+                "unknown sanity check mode of materialize: {}",
+                s
+            ))
+            .into()),
+        }
+    }
+}
+
 /// `MaterializeExecutor` materializes changes in stream into a materialized view on storage.
 pub struct MaterializeExecutor<S: StateStore> {
     input: BoxedExecutor,
@@ -36,6 +84,13 @@ pub struct MaterializeExecutor<S: StateStore> {
     /// Columns of arrange keys (including pk, group keys, join keys, etc.)
     arrange_columns: Vec<usize>,
 
+    conflict_behavior: ConflictBehavior,
+
+    sanity_check: SanityCheckMode,
+
+    /// Epoch of the latest barrier, used to look up existing rows on conflict checks.
+    epoch: u64,
+
     info: ExecutorInfo,
 }
 
@@ -81,6 +136,9 @@ impl<S: StateStore> MaterializeExecutor<S> {
                 Some(pk_dist_indices),
             ),
             arrange_columns: arrange_columns.clone(),
+            conflict_behavior: ConflictBehavior::NoCheck,
+            sanity_check: SanityCheckMode::Off,
+            epoch: INVALID_EPOCH,
             info: ExecutorInfo {
                 schema,
                 pk_indices: arrange_columns,
@@ -89,6 +147,27 @@ impl<S: StateStore> MaterializeExecutor<S> {
         }
     }
 
+    /// Sets how insertions with an existing key are handled. The arrange keys must be the pk
+    /// of the table when a check is required.
+    #[must_use]
+    pub fn with_conflict_behavior(mut self, conflict_behavior: ConflictBehavior) -> Self {
+        self.conflict_behavior = conflict_behavior;
+        self
+    }
+
+    /// Sets how the ops are checked against the existing rows if no conflict is expected.
+    #[must_use]
+    pub fn with_sanity_check(mut self, sanity_check: SanityCheckMode) -> Self {
+        self.sanity_check = sanity_check;
+        self
+    }
+
+    /// Whether the existing row of each key should be looked up before applying the ops.
+    fn requires_lookup(&self) -> bool {
+        self.conflict_behavior != ConflictBehavior::NoCheck
+            || self.sanity_check != SanityCheckMode::Off
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(mut self) {
         let input = self.input.execute();
@@ -96,55 +175,147 @@ impl<S: StateStore> MaterializeExecutor<S> {
         for msg in input {
             let msg = msg?;
             yield match msg {
-                Message::Chunk(chunk) => {
-                    for (idx, op) in chunk.ops().iter().enumerate() {
-                        // check visibility
-                        let visible = chunk
-                            .visibility()
-                            .as_ref()
-                            .map(|x| x.is_set(idx).unwrap())
-                            .unwrap_or(true);
-                        if !visible {
-                            continue;
+                Message::Chunk(chunk) => match self.requires_lookup() {
+                    false => {
+                        for (op, arrange_row, row) in self.visible_rows(&chunk) {
+                            match op {
+                                Insert | UpdateInsert => {
+                                    self.state_table.insert(arrange_row, row)?;
+                                }
+                                Delete | UpdateDelete => {
+                                    self.state_table.delete(arrange_row, row)?;
+                                }
+                            }
                         }
 
-                        // assemble pk row
-                        let arrange_row = Row(self
-                            .arrange_columns
-                            .iter()
-                            .map(|col_idx| chunk.column_at(*col_idx).array_ref().datum_at(idx))
-                            .collect_vec());
-
-                        // assemble row
-                        let row = Row(chunk
-                            .columns()
-                            .iter()
-                            .map(|x| x.array_ref().datum_at(idx))
-                            .collect_vec());
-
-                        match op {
-                            Insert | UpdateInsert => {
-                                self.state_table.insert(arrange_row, row)?;
-                            }
-                            Delete | UpdateDelete => {
-                                self.state_table.delete(arrange_row, row)?;
-                            }
+                        Message::Chunk(chunk)
+                    }
+                    true => {
+                        let rows = self.visible_rows(&chunk);
+                        let output = self.apply_with_conflict_check(rows).await?;
+                        if output.is_empty() {
+                            continue;
                         }
+                        let data_types = self.info.schema.data_types();
+                        Message::Chunk(
+                            StreamChunk::from_rows(&output, &data_types)
+                                .map_err(StreamExecutorError::executor_v1)?,
+                        )
                     }
-
-                    Message::Chunk(chunk)
-                }
+                },
                 Message::Barrier(b) => {
                     // FIXME(ZBW): use a better error type
                     self.state_table
                         .commit_with_value_meta(b.epoch.prev)
                         .await
                         .map_err(StreamExecutorError::executor_v1)?;
+                    self.epoch = b.epoch.curr;
                     Message::Barrier(b)
                 }
             }
         }
     }
+
+    /// Returns the op, arrange key and the full row of each visible row in `chunk`.
+    fn visible_rows(&self, chunk: &StreamChunk) -> Vec<(Op, Row, Row)> {
+        chunk
+            .ops()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| {
+                chunk
+                    .visibility()
+                    .as_ref()
+                    .map(|x| x.is_set(*idx).unwrap())
+                    .unwrap_or(true)
+            })
+            .map(|(idx, op)| {
+                // assemble pk row
+                let arrange_row = Row(self
+                    .arrange_columns
+                    .iter()
+                    .map(|col_idx| chunk.column_at(*col_idx).array_ref().datum_at(idx))
+                    .collect_vec());
+
+                // assemble row
+                let row = Row(chunk
+                    .columns()
+                    .iter()
+                    .map(|x| x.array_ref().datum_at(idx))
+                    .collect_vec());
+
+                (*op, arrange_row, row)
+            })
+            .collect_vec()
+    }
+
+    /// Applies the rows to the state table, looking up the existing row of each key first.
+    /// Returns the changes that actually happened to the table, which are sent downstream.
+    async fn apply_with_conflict_check(
+        &mut self,
+        rows: Vec<(Op, Row, Row)>,
+    ) -> Result<Vec<(Op, Row)>, StreamExecutorError> {
+        // Conflicts are expected unless the keys are unique, in which case they are reported.
+        let check = self.conflict_behavior == ConflictBehavior::NoCheck;
+        let mut output = Vec::with_capacity(rows.len());
+        for (op, arrange_row, row) in rows {
+            let existing = self.state_table.get_row(&arrange_row, self.epoch).await?;
+            match (op, existing) {
+                (Insert | UpdateInsert, None) => {
+                    self.state_table.insert(arrange_row, row.clone())?;
+                    output.push((Insert, row));
+                }
+                (Insert | UpdateInsert, Some(old_row)) => {
+                    if check {
+                        self.report_inconsistent_op(op, &row, Some(&old_row))?;
+                    }
+                    let overwrite = self.conflict_behavior != ConflictBehavior::IgnoreConflict;
+                    if overwrite && old_row != row {
+                        self.state_table
+                            .delete(arrange_row.clone(), old_row.clone())?;
+                        self.state_table.insert(arrange_row, row.clone())?;
+                        output.push((UpdateDelete, old_row));
+                        output.push((UpdateInsert, row));
+                    }
+                }
+                (Delete | UpdateDelete, Some(old_row)) => {
+                    if check && old_row != row {
+                        self.report_inconsistent_op(op, &row, Some(&old_row))?;
+                    }
+                    // Delete the stored row, which may differ from `row` if it's been overwritten.
+                    self.state_table.delete(arrange_row, old_row.clone())?;
+                    output.push((Delete, old_row));
+                }
+                (Delete | UpdateDelete, None) => {
+                    if check {
+                        self.report_inconsistent_op(op, &row, None)?;
+                    }
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// Fails with the inconsistent op in the strict mode, or logs it in the lenient mode, after
+    /// which the op will be reconciled.
+    fn report_inconsistent_op(
+        &self,
+        op: Op,
+        row: &Row,
+        existing: Option<&Row>,
+    ) -> Result<(), StreamExecutorError> {
+        let message = format!(
+            "{}: {:?} {:?} while the existing row is {:?}",
+            self.info.identity, op, row, existing
+        );
+        match self.sanity_check {
+            SanityCheckMode::Strict => Err(StreamExecutorError::inconsistent_op(message)),
+            SanityCheckMode::Off | SanityCheckMode::Lenient => {
+                tracing::warn!("inconsistent op reconciled: {}", message);
+                Ok(())
+            }
+        }
+    }
 }
 
 impl<S: StateStore> Executor for MaterializeExecutor<S> {
@@ -170,6 +341,8 @@ impl<S: StateStore> std::fmt::Debug for MaterializeExecutor<S> {
         f.debug_struct("MaterializeExecutor")
             .field("input info", &self.info())
             .field("arrange_columns", &self.arrange_columns)
+            .field("conflict_behavior", &self.conflict_behavior)
+            .field("sanity_check", &self.sanity_check)
             .finish()
     }
 }
@@ -270,4 +443,196 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    /// Builds a materialize executor on a table with two int32 columns, where the first one is
+    /// the pk. `chunk1` and `chunk2` are sent in two epochs.
+    fn build_materialize(
+        chunk1: StreamChunk,
+        chunk2: StreamChunk,
+        conflict_behavior: ConflictBehavior,
+        sanity_check: SanityCheckMode,
+    ) -> (BoxedMessageStream, CellBasedTable<MemoryStateStore>) {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk2),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store, &table_id);
+        let column_descs = vec![
+            ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+            ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ];
+        let table = CellBasedTable::new_for_test(
+            keyspace.clone(),
+            column_descs,
+            vec![OrderType::Ascending],
+        );
+        let materialize_executor = Box::new(
+            MaterializeExecutor::new(
+                Box::new(source),
+                keyspace,
+                vec![OrderPair::new(0, OrderType::Ascending)],
+                column_ids,
+                1,
+                vec![0],
+            )
+            .with_conflict_behavior(conflict_behavior)
+            .with_sanity_check(sanity_check),
+        )
+        .execute();
+        (materialize_executor, table)
+    }
+
+    async fn run_with_conflict_behavior(
+        conflict_behavior: ConflictBehavior,
+    ) -> (Vec<StreamChunk>, Option<Row>) {
+        // The second chunk inserts key 1 again, both in a new epoch and within the same chunk.
+        let chunk1 = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5",
+        );
+        let chunk2 = StreamChunk::from_pretty(
+            " i i
+            + 1 6
+            + 3 7
+            + 3 8",
+        );
+        let (mut materialize_executor, table) =
+            build_materialize(chunk1, chunk2, conflict_behavior, SanityCheckMode::Off);
+
+        let mut chunks = vec![];
+        while let Some(msg) = materialize_executor.next().await.transpose().unwrap() {
+            if let Message::Chunk(chunk) = msg {
+                chunks.push(chunk);
+            }
+        }
+        let row = table
+            .get_row(&Row(vec![Some(1_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        (chunks, row)
+    }
+
+    #[tokio::test]
+    async fn test_materialize_overwrite() {
+        let (chunks, row) = run_with_conflict_behavior(ConflictBehavior::Overwrite).await;
+        assert_eq!(row, Some(Row(vec![Some(1_i32.into()), Some(6_i32.into())])));
+        assert_eq!(
+            chunks[1],
+            StreamChunk::from_pretty(
+                " i i
+                U- 1 4
+                U+ 1 6
+                + 3 7
+                U- 3 7
+                U+ 3 8"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_ignore_conflict() {
+        let (chunks, row) = run_with_conflict_behavior(ConflictBehavior::IgnoreConflict).await;
+        assert_eq!(row, Some(Row(vec![Some(1_i32.into()), Some(4_i32.into())])));
+        assert_eq!(
+            chunks[1],
+            StreamChunk::from_pretty(
+                " i i
+                + 3 7"
+            )
+        );
+    }
+    /// The second chunk inserts an existing key, and deletes a missing key.
+    fn inconsistent_chunks() -> (StreamChunk, StreamChunk) {
+        let chunk1 = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5",
+        );
+        let chunk2 = StreamChunk::from_pretty(
+            " i i
+            + 1 6
+            - 3 7
+            - 2 5",
+        );
+        (chunk1, chunk2)
+    }
+
+    #[tokio::test]
+    async fn test_materialize_sanity_check_strict() {
+        let (chunk1, chunk2) = inconsistent_chunks();
+        let (mut materialize_executor, _) = build_materialize(
+            chunk1,
+            chunk2,
+            ConflictBehavior::NoCheck,
+            SanityCheckMode::Strict,
+        );
+
+        // The first chunk is consistent.
+        for _ in 0..3 {
+            materialize_executor.next().await.unwrap().unwrap();
+        }
+        assert!(materialize_executor.next().await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_materialize_sanity_check_lenient() {
+        let (chunk1, chunk2) = inconsistent_chunks();
+        let (mut materialize_executor, table) = build_materialize(
+            chunk1,
+            chunk2,
+            ConflictBehavior::NoCheck,
+            SanityCheckMode::Lenient,
+        );
+
+        let mut chunks = vec![];
+        while let Some(msg) = materialize_executor.next().await.transpose().unwrap() {
+            if let Message::Chunk(chunk) = msg {
+                chunks.push(chunk);
+            }
+        }
+        assert_eq!(
+            chunks[1],
+            StreamChunk::from_pretty(
+                "  i i
+                U- 1 4
+                U+ 1 6
+                -  2 5"
+            )
+        );
+        let row = table
+            .get_row(&Row(vec![Some(1_i32.into())]), u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(row, Some(Row(vec![Some(1_i32.into()), Some(6_i32.into())])));
+    }
+
+    #[test]
+    fn test_parse_sanity_check_mode() {
+        assert_eq!(
+            "strict".parse::<SanityCheckMode>().unwrap(),
+            SanityCheckMode::Strict
+        );
+        assert_eq!(
+            "Lenient".parse::<SanityCheckMode>().unwrap(),
+            SanityCheckMode::Lenient
+        );
+        assert!("unknown".parse::<SanityCheckMode>().is_err());
+    }
 }
@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use std::fmt::{Debug, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
 
 use itertools::Itertools;
+use prometheus::core::{AtomicU64, GenericCounter};
 use risingwave_common::array::column::Column;
-use risingwave_common::array::{DataChunk, StreamChunk};
+use risingwave_common::array::{ArrayRef, DataChunk, StreamChunk};
 use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_expr::expr::BoxedExpression;
 
 use super::{
@@ -45,6 +49,46 @@ impl ProjectExecutor {
             inner: SimpleProjectExecutor::new(info, exprs, execuotr_id),
         }
     }
+
+    /// Sets how the rows on which the expressions fail are handled. The errors are counted by
+    /// `error_count` with [`ExprErrorPolicy::Null`].
+    #[must_use]
+    pub fn with_error_policy(
+        mut self,
+        error_policy: ExprErrorPolicy,
+        error_count: GenericCounter<AtomicU64>,
+    ) -> Self {
+        self.inner.error_policy = error_policy;
+        self.inner.error_count = Some(error_count);
+        self
+    }
+}
+
+/// How [`ProjectExecutor`] handles the rows on which the expressions fail, e.g., due to a
+/// malformed row from the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprErrorPolicy {
+    /// Fails the job.
+    Fail,
+    /// Produces NULL for the failed rows and counts the errors, so that a bad row doesn't stop
+    /// the whole pipeline.
+    Null,
+}
+
+impl FromStr for ExprErrorPolicy {
+    type Err = RwError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(Self::Fail),
+            "null" => Ok(Self::Null),
+            _ => Err(ErrorCode::InvalidParameterValue(format!(
+                "unknown expression error policy: {}",
+                s
+            ))
+            .into()),
+        }
+    }
 }
 
 /// `ProjectExecutor` project data with the `expr`. The `expr` takes a chunk of data,
@@ -55,6 +99,11 @@ pub struct SimpleProjectExecutor {
 
     /// Expressions of the current projection.
     exprs: Vec<BoxedExpression>,
+
+    error_policy: ExprErrorPolicy,
+
+    /// The number of rows on which the expressions fail, with [`ExprErrorPolicy::Null`].
+    error_count: Option<GenericCounter<AtomicU64>>,
 }
 
 impl SimpleProjectExecutor {
@@ -72,14 +121,50 @@ impl SimpleProjectExecutor {
                 identity: format!("ProjectExecutor {:X}", executor_id),
             },
             exprs,
+            error_policy: ExprErrorPolicy::Fail,
+            error_count: None,
         }
     }
+
+    /// Evaluates `expr` row by row after it fails on the whole chunk, producing NULL for the
+    /// rows on which it fails.
+    fn eval_rows_or_null(
+        &self,
+        expr: &BoxedExpression,
+        data_chunk: &DataChunk,
+        chunk_error: RwError,
+    ) -> Result<ArrayRef> {
+        let mut builder = expr
+            .return_type()
+            .create_array_builder(data_chunk.capacity())?;
+        let mut num_errors = 0;
+        for idx in 0..data_chunk.capacity() {
+            let row = data_chunk.row_at_unchecked_vis(idx).to_owned_row();
+            let datum = expr.eval_row(&row).unwrap_or_else(|_| {
+                num_errors += 1;
+                None
+            });
+            builder.append_datum(&datum)?;
+        }
+        tracing::warn!(
+            "{}: expression {:?} failed on {} rows, which are projected to NULL: {}",
+            self.info.identity,
+            expr,
+            num_errors,
+            chunk_error
+        );
+        if let Some(error_count) = &self.error_count {
+            error_count.inc_by(num_errors);
+        }
+        Ok(Arc::new(builder.finish()?))
+    }
 }
 
 impl Debug for SimpleProjectExecutor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ProjectExecutor")
             .field("exprs", &self.exprs)
+            .field("error_policy", &self.error_policy)
             .finish()
     }
 }
@@ -103,13 +188,19 @@ impl SimpleExecutor for SimpleProjectExecutor {
 
         let projected_columns = self
             .exprs
-            .iter_mut()
+            .iter()
             .map(|expr| {
-                expr.eval(&data_chunk)
-                    .map(Column::new)
-                    .map_err(StreamExecutorError::eval_error)
+                let array = match (expr.eval(&data_chunk), self.error_policy) {
+                    (Ok(array), _) => array,
+                    (Err(err), ExprErrorPolicy::Fail) => return Err(err),
+                    (Err(err), ExprErrorPolicy::Null) => {
+                        self.eval_rows_or_null(expr, &data_chunk, err)?
+                    }
+                };
+                Ok(Column::new(array))
             })
-            .collect::<Result<Vec<Column>, _>>()?;
+            .collect::<Result<Vec<Column>>>()
+            .map_err(StreamExecutorError::eval_error)?;
 
         let new_chunk = StreamChunk::new(ops, projected_columns, None);
         Ok(Some(new_chunk))
@@ -132,13 +223,15 @@ impl SimpleExecutor for SimpleProjectExecutor {
 mod tests {
     use futures::StreamExt;
     use risingwave_common::array::stream_chunk::StreamChunkTestExt;
-    use risingwave_common::array::StreamChunk;
+    use risingwave_common::array::{Row, StreamChunk};
     use risingwave_common::catalog::{Field, Schema};
     use risingwave_common::types::DataType;
+    use risingwave_common::util::epoch::Epoch;
     use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
-    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_expr::expr::{with_epoch, Expression, InputRefExpression, NowExpression};
     use risingwave_pb::expr::expr_node::Type;
 
+    use super::super::debug::with_barrier_epoch;
     use super::super::test_utils::MockSource;
     use super::super::*;
     use super::*;
@@ -204,4 +297,94 @@ mod tests {
 
         assert!(project.next().await.unwrap().unwrap().is_stop());
     }
+
+    #[tokio::test]
+    async fn test_projection_error_policy() {
+        let chunk = StreamChunk::from_pretty(
+            " I I
+            + 8 4
+            + 1 0
+            + 9 3",
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let build_project = |error_policy, error_count| {
+            let source =
+                MockSource::with_chunks(schema.clone(), PkIndices::new(), vec![chunk.clone()]);
+            let test_expr = new_binary_expr(
+                Type::Divide,
+                DataType::Int64,
+                Box::new(InputRefExpression::new(DataType::Int64, 0)),
+                Box::new(InputRefExpression::new(DataType::Int64, 1)),
+            );
+            Box::new(
+                ProjectExecutor::new(Box::new(source), vec![], vec![test_expr], 1)
+                    .with_error_policy(error_policy, error_count),
+            )
+            .execute()
+        };
+        let error_count = GenericCounter::<AtomicU64>::new("error_count", "error count").unwrap();
+
+        let mut project = build_project(ExprErrorPolicy::Fail, error_count.clone());
+        assert!(project.next().await.unwrap().is_err());
+
+        let mut project = build_project(ExprErrorPolicy::Null, error_count.clone());
+        let msg = project.next().await.unwrap().unwrap();
+        assert_eq!(
+            *msg.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I
+                + 2
+                + .
+                + 3"
+            )
+        );
+        assert_eq!(error_count.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_project_now() {
+        let schema = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let (mut tx, source) = MockSource::channel(schema, PkIndices::new());
+        let epoch = Epoch::now().0;
+        tx.push_barrier(epoch, false);
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I
+            + 1
+            + 2",
+        ));
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I
+            + 3",
+        ));
+
+        let project = Box::new(ProjectExecutor::new(
+            Box::new(source),
+            vec![],
+            vec![NowExpression::new().boxed()],
+            1,
+        ));
+        let mut project = with_barrier_epoch(project.execute());
+
+        let msg = project.next().await.unwrap().unwrap();
+        assert!(matches!(msg, Message::Barrier(_)));
+
+        // `now()` is the time of the epoch for every row in the epoch.
+        let expected = with_epoch(epoch, || NowExpression::new().eval_row(&Row(vec![]))).unwrap();
+        for _ in 0..2 {
+            let msg = project.next().await.unwrap().unwrap();
+            let chunk = msg.as_chunk().unwrap();
+            assert!(chunk
+                .column_at(0)
+                .array_ref()
+                .iter()
+                .all(|datum| datum.map(|d| d.into_scalar_impl()) == expected));
+        }
+    }
 }
@@ -0,0 +1,292 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayBuilderImpl, ArrayRef, DataChunk, Op, StreamChunk};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_expr::expr::Expression;
+use risingwave_expr::table_function::{ProjectSetSelectItem, TableFunction};
+
+use super::{
+    Executor, ExecutorInfo, PkIndices, PkIndicesRef, SimpleExecutor, SimpleExecutorWrapper,
+    StreamExecutorResult,
+};
+use crate::executor::error::StreamExecutorError;
+
+pub type ProjectSetExecutor = SimpleExecutorWrapper<SimpleProjectSetExecutor>;
+
+impl ProjectSetExecutor {
+    pub fn new(
+        input: Box<dyn Executor>,
+        pk_indices: PkIndices,
+        select_list: Vec<ProjectSetSelectItem>,
+        executor_id: u64,
+    ) -> Self {
+        let info = ExecutorInfo {
+            schema: input.schema().to_owned(),
+            pk_indices,
+            identity: "ProjectSet".to_owned(),
+        };
+        SimpleExecutorWrapper {
+            input,
+            inner: SimpleProjectSetExecutor::new(info, select_list, executor_id),
+        }
+    }
+}
+
+/// `ProjectSetExecutor` projects data with the `select_list`, which may contain table functions
+/// like `unnest` and `generate_series`. Each input row is expanded to as many rows as the longest
+/// output of its table functions, where shorter outputs and scalar expressions are padded with
+/// nulls and repeated respectively.
+///
+/// The first output column is the index of the row in the expansion of its input row, which
+/// identifies the output rows together with the pk of the input.
+pub struct SimpleProjectSetExecutor {
+    info: ExecutorInfo,
+
+    /// Items of the current projection.
+    select_list: Vec<ProjectSetSelectItem>,
+}
+
+impl SimpleProjectSetExecutor {
+    pub fn new(
+        input_info: ExecutorInfo,
+        select_list: Vec<ProjectSetSelectItem>,
+        executor_id: u64,
+    ) -> Self {
+        let schema = Schema {
+            fields: std::iter::once(Field::with_name(DataType::Int64, "projected_row_id"))
+                .chain(select_list.iter().map(|i| Field::unnamed(i.return_type())))
+                .collect_vec(),
+        };
+        Self {
+            info: ExecutorInfo {
+                schema,
+                pk_indices: input_info.pk_indices,
+                identity: format!("ProjectSetExecutor {:X}", executor_id),
+            },
+            select_list,
+        }
+    }
+}
+
+impl Debug for SimpleProjectSetExecutor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProjectSetExecutor")
+            .field("select_list", &self.select_list)
+            .finish()
+    }
+}
+
+/// The evaluated result of a select item on a chunk.
+enum SelectItemOutput {
+    /// A single value for each input row.
+    Expr(ArrayRef),
+    /// A set of values for each input row.
+    TableFunction(Vec<ArrayRef>),
+}
+
+impl SimpleExecutor for SimpleProjectSetExecutor {
+    fn map_filter_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let chunk = chunk.compact().map_err(StreamExecutorError::eval_error)?;
+
+        let (ops, columns, visibility) = chunk.into_inner();
+        let data_chunk = {
+            let data_chunk_builder = DataChunk::builder().columns(columns);
+            if let Some(visibility) = visibility {
+                data_chunk_builder.visibility(visibility).build()
+            } else {
+                data_chunk_builder.build()
+            }
+        };
+
+        let outputs = self
+            .select_list
+            .iter()
+            .map(|item| match item {
+                ProjectSetSelectItem::Expr(expr) => {
+                    expr.eval(&data_chunk).map(SelectItemOutput::Expr)
+                }
+                ProjectSetSelectItem::TableFunction(table_function) => table_function
+                    .eval(&data_chunk)
+                    .map(SelectItemOutput::TableFunction),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StreamExecutorError::eval_error)?;
+
+        let mut new_ops = Vec::with_capacity(ops.len());
+        let mut builders = self
+            .info
+            .schema
+            .create_array_builders(ops.len())
+            .map_err(StreamExecutorError::eval_error)?;
+
+        for (i, op) in ops.into_iter().enumerate() {
+            // The expansions of the two sides of an update don't pair up, so emit them as a
+            // deletion and an insertion.
+            let op = match op {
+                Op::Insert | Op::UpdateInsert => Op::Insert,
+                Op::Delete | Op::UpdateDelete => Op::Delete,
+            };
+            let expanded_len = outputs
+                .iter()
+                .filter_map(|output| match output {
+                    SelectItemOutput::Expr(_) => None,
+                    SelectItemOutput::TableFunction(arrays) => Some(arrays[i].len()),
+                })
+                .max()
+                .unwrap_or(1);
+
+            for j in 0..expanded_len {
+                new_ops.push(op);
+                let (row_id_builder, item_builders) = builders.split_first_mut().unwrap();
+                append_datum(row_id_builder, &Some(ScalarImpl::Int64(j as i64)))?;
+                for (builder, output) in item_builders.iter_mut().zip_eq(&outputs) {
+                    let datum = match output {
+                        SelectItemOutput::Expr(array) => array.datum_at(i),
+                        SelectItemOutput::TableFunction(arrays) if j < arrays[i].len() => {
+                            arrays[i].datum_at(j)
+                        }
+                        SelectItemOutput::TableFunction(_) => None,
+                    };
+                    append_datum(builder, &datum)?;
+                }
+            }
+        }
+
+        if new_ops.is_empty() {
+            return Ok(None);
+        }
+
+        let new_columns = builders
+            .into_iter()
+            .map(|builder| builder.finish().map(|array| Column::new(Arc::new(array))))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StreamExecutorError::eval_error)?;
+
+        let new_chunk = StreamChunk::new(new_ops, new_columns, None);
+        Ok(Some(new_chunk))
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+fn append_datum(builder: &mut ArrayBuilderImpl, datum: &Datum) -> StreamExecutorResult<()> {
+    builder
+        .append_datum(datum)
+        .map_err(StreamExecutorError::eval_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+    use risingwave_expr::expr::{Expression, InputRefExpression, LiteralExpression};
+    use risingwave_expr::table_function::new_generate_series;
+
+    use super::super::test_utils::MockSource;
+    use super::super::*;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_project_set() {
+        let chunk1 = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5",
+        );
+        let chunk2 = StreamChunk::from_pretty(
+            " i i
+            U- 2 5
+            U+ 3 6",
+        );
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int32),
+            ],
+        };
+        let source = MockSource::with_chunks(schema, PkIndices::new(), vec![chunk1, chunk2]);
+
+        let literal = |v: i32| LiteralExpression::new(DataType::Int32, Some(v.into())).boxed();
+        let input_ref = |i| InputRefExpression::new(DataType::Int32, i).boxed();
+        let series = |start| {
+            let f = new_generate_series(
+                vec![literal(start), input_ref(0), literal(1)],
+                DataType::Int32,
+            )
+            .unwrap();
+            ProjectSetSelectItem::TableFunction(f)
+        };
+
+        let project_set = Box::new(ProjectSetExecutor::new(
+            Box::new(source),
+            vec![],
+            vec![
+                ProjectSetSelectItem::Expr(input_ref(1)),
+                series(1),
+                series(2),
+            ],
+            1,
+        ));
+        let mut project_set = project_set.execute();
+
+        let msg = project_set.next().await.unwrap().unwrap();
+        assert_eq!(
+            *msg.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I i i i
+                + 0 4 1 .
+                + 0 5 1 2
+                + 1 5 2 ."
+            )
+        );
+
+        let msg = project_set.next().await.unwrap().unwrap();
+        assert_eq!(
+            *msg.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I i i i
+                - 0 5 1 2
+                - 1 5 2 .
+                + 0 6 1 2
+                + 1 6 2 3
+                + 2 6 3 ."
+            )
+        );
+
+        assert!(project_set.next().await.unwrap().unwrap().is_stop());
+    }
+}
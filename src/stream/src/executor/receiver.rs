@@ -20,6 +20,7 @@ use super::{ActorContextRef, OperatorInfoStatus};
 use crate::executor::{
     BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
 };
+use crate::task::BufferOccupancy;
 
 /// `ReceiverExecutor` is used along with a channel. After creating a mpsc channel,
 /// there should be a `ReceiverExecutor` running in the background, so as to push
@@ -27,6 +28,9 @@ use crate::executor::{
 pub struct ReceiverExecutor {
     receiver: Receiver<Message>,
 
+    /// Occupancy of the channel, decreased on receiving.
+    occupancy: BufferOccupancy,
+
     /// Logical Operator Info
     info: ExecutorInfo,
 
@@ -53,6 +57,7 @@ impl ReceiverExecutor {
     ) -> Self {
         Self {
             receiver,
+            occupancy: BufferOccupancy::default(),
             info: ExecutorInfo {
                 schema,
                 pk_indices,
@@ -61,13 +66,21 @@ impl ReceiverExecutor {
             status: OperatorInfoStatus::new(actor_context, receiver_id),
         }
     }
+
+    #[must_use]
+    pub fn with_buffer_occupancy(mut self, occupancy: BufferOccupancy) -> Self {
+        self.occupancy = occupancy;
+        self
+    }
 }
 
 impl Executor for ReceiverExecutor {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
         let mut status = self.status;
+        let occupancy = self.occupancy;
         self.receiver
             .map(move |msg| {
+                occupancy.dec();
                 status.next_message(&msg);
                 Ok(msg)
             })
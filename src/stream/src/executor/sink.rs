@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use risingwave_common::catalog::Schema;
+use risingwave_connector::sink::{Sink, SinkConfig, SinkImpl};
+
+use super::error::StreamExecutorError;
+use super::{BoxedExecutor, Executor, ExecutorInfo, Message, PkIndicesRef};
+use crate::executor::BoxedMessageStream;
+
+/// `SinkExecutor` writes the changes from its input to an external system, and forwards them
+/// unchanged. A barrier is only forwarded after all the changes before it are delivered.
+pub struct SinkExecutor {
+    input: BoxedExecutor,
+    config: SinkConfig,
+    info: ExecutorInfo,
+}
+
+impl SinkExecutor {
+    pub fn new(input: BoxedExecutor, config: SinkConfig, executor_id: u64) -> Self {
+        let info = ExecutorInfo {
+            schema: input.schema().clone(),
+            pk_indices: input.pk_indices().to_vec(),
+            identity: format!("SinkExecutor {:X}", executor_id),
+        };
+        Self {
+            input,
+            config,
+            info,
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let mut sink = SinkImpl::new(
+            self.config,
+            self.info.schema.clone(),
+            self.info.pk_indices.clone(),
+        )
+        .await
+        .map_err(StreamExecutorError::sink_error)?;
+
+        #[for_await]
+        for msg in self.input.execute() {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    sink.write_batch(chunk.clone())
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                    yield Message::Chunk(chunk);
+                }
+                Message::Barrier(barrier) => {
+                    sink.commit()
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl Executor for SinkExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
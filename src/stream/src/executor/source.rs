@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use either::Either;
 use futures::stream::{select_with_strategy, PollNext};
@@ -26,22 +28,28 @@ use risingwave_common::catalog::{ColumnId, Schema, TableId};
 use risingwave_common::error::{internal_error, Result, RwError, ToRwResult};
 use risingwave_connector::state::SourceStateHandler;
 use risingwave_connector::{
-    ConnectorState, SplitImpl, DATAGEN_CONNECTOR, KAFKA_CONNECTOR, KINESIS_CONNECTOR,
-    NEXMARK_CONNECTOR, PULSAR_CONNECTOR,
+    ConnectorState, SplitImpl, DATAGEN_CONNECTOR, FILE_CONNECTOR, KAFKA_CONNECTOR,
+    KINESIS_CONNECTOR, NEXMARK_CONNECTOR, PULSAR_CONNECTOR,
 };
 use risingwave_source::*;
 use risingwave_storage::{Keyspace, StateStore};
-use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::sync::Notify;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{watch, Notify};
 use tokio::time::Instant;
 
 use super::error::StreamExecutorError;
 use super::monitor::StreamingMetrics;
 use super::*;
+use crate::task::OutputBackpressure;
+
+/// How long a throttled source waits before checking the output buffers again.
+const BACKPRESSURE_BACKOFF: Duration = Duration::from_millis(10);
 
 /// [`SourceExecutor`] is a streaming source, from risingwave's batch table, or external systems
 /// such as Kafka.
 pub struct SourceExecutor<S: StateStore> {
+    actor_id: ActorId,
+
     source_id: TableId,
     source_desc: SourceDesc,
 
@@ -69,6 +77,12 @@ pub struct SourceExecutor<S: StateStore> {
 
     /// Expected barrier latency
     expected_barrier_latency_ms: u64,
+
+    /// Throttles the source when the downstream can't keep up.
+    backpressure: Option<OutputBackpressure>,
+
+    /// Set when the compute node is shutting down, after which the source stops polling for good.
+    stopped: Arc<AtomicBool>,
 }
 
 impl<S: StateStore> SourceExecutor<S> {
@@ -81,6 +95,7 @@ impl<S: StateStore> SourceExecutor<S> {
         schema: Schema,
         pk_indices: PkIndices,
         barrier_receiver: UnboundedReceiver<Barrier>,
+        actor_id: ActorId,
         executor_id: u64,
         _operator_id: u64,
         _op_info: String,
@@ -89,6 +104,7 @@ impl<S: StateStore> SourceExecutor<S> {
         expected_barrier_latency_ms: u64,
     ) -> Result<Self> {
         Ok(Self {
+            actor_id,
             source_id,
             source_desc,
             column_ids,
@@ -102,9 +118,23 @@ impl<S: StateStore> SourceExecutor<S> {
             split_state_store: SourceStateHandler::new(keyspace),
             state_cache: HashMap::new(),
             expected_barrier_latency_ms,
+            backpressure: None,
+            stopped: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    #[must_use]
+    pub fn with_backpressure(mut self, backpressure: OutputBackpressure) -> Self {
+        self.backpressure = Some(backpressure);
+        self
+    }
+
+    #[must_use]
+    pub fn with_stop_signal(mut self, stopped: Arc<AtomicBool>) -> Self {
+        self.stopped = stopped;
+        self
+    }
+
     /// Generate a row ID column.
     fn gen_row_id_column(&mut self, len: usize) -> Column {
         let mut builder = I64ArrayBuilder::new(len).unwrap();
@@ -139,25 +169,57 @@ struct SourceReader {
     stream_reader: Box<dyn StreamSourceReader>,
     /// The reader for barrier.
     barrier_receiver: UnboundedReceiver<Barrier>,
+    /// The splits newly assigned to this source, which the stream reader should start reading.
+    split_receiver: UnboundedReceiver<Vec<SplitImpl>>,
     /// Expected barrier latency in ms. If there are no barrier within the expected barrier
     /// latency, source will stall.
     expected_barrier_latency_ms: u64,
+    /// Pauses polling when the output buffers are about to be full.
+    backpressure: Option<OutputBackpressure>,
+    /// Stops polling when the compute node is shutting down.
+    stopped: Arc<AtomicBool>,
 }
 
 impl SourceReader {
     #[try_stream(ok = StreamChunkWithState, error = RwError)]
     async fn stream_reader(
         mut stream_reader: Box<dyn StreamSourceReader>,
+        mut split_receiver: UnboundedReceiver<Vec<SplitImpl>>,
         notifier: Arc<Notify>,
+        mut paused: watch::Receiver<bool>,
         expected_barrier_latency_ms: u64,
+        mut backpressure: Option<OutputBackpressure>,
+        stopped: Arc<AtomicBool>,
     ) {
         'outer: loop {
             let now = Instant::now();
 
-            // We allow data to flow for `expected_barrier_latency_ms` milliseconds.
-            while now.elapsed().as_millis() < expected_barrier_latency_ms as u128 {
-                match stream_reader.next().await {
-                    Ok(chunk) => yield chunk,
+            // We allow data to flow for `expected_barrier_latency_ms` milliseconds, unless the
+            // source is paused or stopped.
+            while !*paused.borrow()
+                && !stopped.load(Ordering::Relaxed)
+                && now.elapsed().as_millis() < expected_barrier_latency_ms as u128
+            {
+                // Stop polling while the downstream is congested, so that the data stays in the
+                // external system instead of piling up in the exchange.
+                if let Some(backpressure) = backpressure.as_mut() {
+                    if backpressure.is_throttled() {
+                        tokio::time::sleep(BACKPRESSURE_BACKOFF).await;
+                        continue;
+                    }
+                }
+                // A pause takes effect at once, without waiting for the next chunk to be read.
+                let chunk = tokio::select! {
+                    biased;
+                    Ok(()) = paused.changed() => Ok(None),
+                    Some(splits) = split_receiver.recv() => {
+                        stream_reader.add_splits(splits).await.map(|_| None)
+                    }
+                    chunk = stream_reader.next() => chunk.map(Some),
+                };
+                match chunk {
+                    Ok(Some(chunk)) => yield chunk,
+                    Ok(None) => {}
                     Err(e) => {
                         // TODO: report this error to meta service to mark the actors failed.
                         error!("hang up stream reader due to polling error: {}", e);
@@ -175,6 +237,9 @@ impl SourceReader {
             // 1. Barrier arrived before waiting for notified. In this case, this await will
             // complete instantly, and we will continue to produce new data.
             // 2. Barrier arrived after waiting for notified. Then source will be stalled.
+            //
+            // If the source is paused, it will be woken up by every barrier and stall again until
+            // a `Resume` barrier arrives.
 
             notifier.notified().await;
         }
@@ -183,8 +248,17 @@ impl SourceReader {
     }
 
     #[try_stream(ok = Message, error = RwError)]
-    async fn barrier_receiver(mut rx: UnboundedReceiver<Barrier>, notifier: Arc<Notify>) {
+    async fn barrier_receiver(
+        mut rx: UnboundedReceiver<Barrier>,
+        notifier: Arc<Notify>,
+        paused: watch::Sender<bool>,
+    ) {
         while let Some(barrier) = rx.recv().await {
+            if barrier.is_pause() {
+                let _ = paused.send(true);
+            } else if barrier.is_resume() {
+                let _ = paused.send(false);
+            }
             yield Message::Barrier(barrier);
             notifier.notify_one();
         }
@@ -197,12 +271,18 @@ impl SourceReader {
         self,
     ) -> impl Stream<Item = Either<Result<Message>, Result<StreamChunkWithState>>> {
         let notifier = Arc::new(Notify::new());
+        let (paused_tx, paused_rx) = watch::channel(false);
 
-        let barrier_receiver = Self::barrier_receiver(self.barrier_receiver, notifier.clone());
+        let barrier_receiver =
+            Self::barrier_receiver(self.barrier_receiver, notifier.clone(), paused_tx);
         let stream_reader = Self::stream_reader(
             self.stream_reader,
+            self.split_receiver,
             notifier,
+            paused_rx,
             self.expected_barrier_latency_ms,
+            self.backpressure,
+            self.stopped,
         );
         select_with_strategy(
             barrier_receiver.map(Either::Left),
@@ -229,6 +309,40 @@ macro_rules! impl_take_snapshot {
 }
 
 impl<S: StateStore> SourceExecutor<S> {
+    /// Starts reading the splits newly assigned to this actor. The assignment of meta only grows,
+    /// so the splits that are being read are left untouched. The new splits are put into the state
+    /// cache to be persisted with the barrier bringing them, so that they can be recovered.
+    fn assign_splits(
+        &mut self,
+        splits: &[SplitImpl],
+        split_sender: &UnboundedSender<Vec<SplitImpl>>,
+    ) -> Result<()> {
+        let new_splits = splits
+            .iter()
+            .filter(|split| {
+                !self
+                    .stream_source_splits
+                    .iter()
+                    .any(|assigned| assigned.id() == split.id())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if new_splits.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "source actor {} is assigned with new splits {:?}",
+            self.actor_id, new_splits
+        );
+        self.state_cache
+            .extend(new_splits.iter().map(|split| (split.id(), split.clone())));
+        self.stream_source_splits.extend(new_splits.iter().cloned());
+        split_sender
+            .send(new_splits)
+            .map_err(|_| internal_error("stream reader closed unexpectedly"))
+    }
+
     async fn take_snapshot(&mut self, epoch: u64) -> Result<()> {
         let cache = self
             .state_cache
@@ -241,7 +355,8 @@ impl<S: StateStore> SourceExecutor<S> {
                 { kinesis, KINESIS_CONNECTOR },
                 { nexmark, NEXMARK_CONNECTOR },
                 { pulsar, PULSAR_CONNECTOR },
-                { datagen, DATAGEN_CONNECTOR}
+                { datagen, DATAGEN_CONNECTOR},
+                { file, FILE_CONNECTOR }
 
             );
             self.state_cache.clear();
@@ -293,10 +408,14 @@ impl<S: StateStore> SourceExecutor<S> {
         }
         .map_err(StreamExecutorError::source_error)?;
 
+        let (split_sender, split_receiver) = unbounded_channel();
         let reader = SourceReader {
             stream_reader: Box::new(stream_reader),
             barrier_receiver,
+            split_receiver,
             expected_barrier_latency_ms: self.expected_barrier_latency_ms,
+            backpressure: self.backpressure.take(),
+            stopped: self.stopped.clone(),
         };
         yield Message::Barrier(barrier);
 
@@ -307,6 +426,10 @@ impl<S: StateStore> SourceExecutor<S> {
                 Either::Left(barrier) => {
                     match barrier.map_err(StreamExecutorError::source_error)? {
                         Message::Barrier(barrier) => {
+                            if let Some(splits) = barrier.as_source_change_split(self.actor_id) {
+                                self.assign_splits(splits, &split_sender)
+                                    .map_err(StreamExecutorError::source_error)?;
+                            }
                             let epoch = barrier.epoch.prev;
                             self.take_snapshot(epoch)
                                 .await
@@ -477,6 +600,7 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::new(prometheus::Registry::new())),
             vec![],
@@ -599,6 +723,7 @@ mod tests {
             barrier_receiver,
             1,
             1,
+            1,
             "SourceExecutor".to_string(),
             Arc::new(StreamingMetrics::unused()),
             vec![],
@@ -627,4 +752,65 @@ mod tests {
 
         Ok(())
     }
+
+    /// A reader producing the chunks sent to it, which blocks while there are none.
+    struct MockStreamReader(UnboundedReceiver<StreamChunk>);
+
+    #[async_trait::async_trait]
+    impl StreamSourceReader for MockStreamReader {
+        async fn next(&mut self) -> Result<StreamChunkWithState> {
+            Ok(StreamChunkWithState {
+                chunk: self.0.recv().await.unwrap(),
+                split_offset_mapping: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pause_while_waiting_for_data() {
+        let (chunk_sender, chunk_receiver) = unbounded_channel();
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let (_split_sender, split_receiver) = unbounded_channel();
+        let reader = SourceReader {
+            stream_reader: Box::new(MockStreamReader(chunk_receiver)),
+            barrier_receiver,
+            split_receiver,
+            expected_barrier_latency_ms: u64::MAX,
+            backpressure: None,
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        let stream = reader.into_stream();
+        futures::pin_mut!(stream);
+
+        let chunk = StreamChunk::from_pretty(
+            " I
+            + 1",
+        );
+        let is_barrier = |msg| matches!(msg, Some(Either::Left(Ok(Message::Barrier(_)))));
+        let is_chunk = |msg| matches!(msg, Some(Either::Right(Ok(_))));
+
+        barrier_sender.send(Barrier::new_test_barrier(1)).unwrap();
+        assert!(is_barrier(stream.next().await));
+        chunk_sender.send(chunk.clone()).unwrap();
+        assert!(is_chunk(stream.next().await));
+
+        // The reader is waiting for data when the source is paused, and data arriving later are
+        // not read.
+        barrier_sender
+            .send(Barrier::new_test_barrier(2).with_mutation(Mutation::Pause))
+            .unwrap();
+        assert!(is_barrier(stream.next().await));
+        chunk_sender.send(chunk).unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream.next())
+                .await
+                .is_err()
+        );
+
+        barrier_sender
+            .send(Barrier::new_test_barrier(3).with_mutation(Mutation::Resume))
+            .unwrap();
+        assert!(is_barrier(stream.next().await));
+        assert!(is_chunk(stream.next().await));
+    }
 }
@@ -48,6 +48,11 @@ impl MessageSender {
         }
         self.0.send(Message::Barrier(barrier)).unwrap();
     }
+
+    #[allow(dead_code)]
+    pub fn send_barrier(&mut self, barrier: Barrier) {
+        self.0.send(Message::Barrier(barrier)).unwrap();
+    }
 }
 
 impl std::fmt::Debug for MockSource {
@@ -24,10 +24,12 @@ use risingwave_storage::cell_based_row_deserializer::CellBasedRowDeserializer;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::managed_state::evict_in_proportion;
 use super::managed_state::top_n::variants::{TOP_N_MAX, TOP_N_MIN};
 use super::managed_state::top_n::{ManagedTopNBottomNState, ManagedTopNState};
 use super::top_n_executor::{generate_output, TopNExecutorBase, TopNExecutorWrapper};
 use super::{BoxedMessageStream, Executor, ExecutorInfo, PkIndices, PkIndicesRef};
+use crate::task::CacheUsage;
 
 /// `TopNExecutor` works with input with modification, it keeps all the data
 /// records/rows that have been seen, and returns topN records overall.
@@ -45,6 +47,7 @@ impl<S: StateStore> TopNExecutor<S> {
         total_count: (usize, usize, usize),
         executor_id: u64,
         key_indices: Vec<usize>,
+        cache_usage: CacheUsage,
     ) -> Result<Self> {
         let info = input.info();
         let schema = input.schema().clone();
@@ -62,6 +65,7 @@ impl<S: StateStore> TopNExecutor<S> {
                 total_count,
                 executor_id,
                 key_indices,
+                cache_usage,
             )?,
         })
     }
@@ -100,6 +104,9 @@ pub struct InnerTopNExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Reports the cached rows to the memory manager, which may ask for evicting them.
+    cache_usage: CacheUsage,
 }
 
 pub fn generate_internal_key(
@@ -145,6 +152,7 @@ impl<S: StateStore> InnerTopNExecutor<S> {
         total_count: (usize, usize, usize),
         executor_id: u64,
         key_indices: Vec<usize>,
+        cache_usage: CacheUsage,
     ) -> Result<Self> {
         let (internal_key_indices, internal_key_data_types, internal_key_order_types) =
             generate_internal_key(&order_pairs, &pk_indices, &schema);
@@ -209,6 +217,7 @@ impl<S: StateStore> InnerTopNExecutor<S> {
             internal_key_order_types,
             first_execution: true,
             key_indices,
+            cache_usage,
         })
     }
 
@@ -224,7 +233,19 @@ impl<S: StateStore> InnerTopNExecutor<S> {
         self.managed_lowest_state
             .flush(epoch)
             .await
-            .map_err(StreamExecutorError::top_n_state_error)
+            .map_err(StreamExecutorError::top_n_state_error)?;
+
+        // Only the rows out of the output range are evicted, while those to be output are always
+        // kept by the middle state.
+        let cached_entries = evict_in_proportion(
+            &mut [
+                &mut self.managed_lowest_state,
+                &mut self.managed_highest_state,
+            ],
+            self.cache_usage.watermark(),
+        );
+        self.cache_usage.report(cached_entries);
+        Ok(())
     }
 }
 
@@ -572,6 +593,7 @@ mod tests {
                 (0, 0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
@@ -652,6 +674,70 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_top_n_executor_with_non_checkpoint_barrier() {
+        let mut chunks = create_stream_chunks();
+        let source = Box::new(MockSource::with_messages(
+            create_schema(),
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(std::mem::take(&mut chunks[0])),
+                Message::Barrier(Barrier::new_test_barrier(2).with_checkpoint(false)),
+                Message::Chunk(std::mem::take(&mut chunks[1])),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        ));
+        let keyspace = create_in_memory_keyspace();
+        let top_n_executor = Box::new(
+            TopNExecutor::new(
+                source as Box<dyn Executor>,
+                create_order_pairs(),
+                (3, None),
+                vec![0, 1],
+                keyspace.clone(),
+                Some(2),
+                (0, 0, 0),
+                1,
+                vec![],
+                CacheUsage::default(),
+            )
+            .unwrap(),
+        );
+        let mut top_n_executor = top_n_executor.execute();
+
+        // consume the init barrier
+        top_n_executor.next().await.unwrap().unwrap();
+        top_n_executor.next().await.unwrap().unwrap();
+
+        // the states are kept in memory on the non-checkpoint barrier
+        let barrier = top_n_executor.next().await.unwrap().unwrap();
+        assert!(!barrier.as_barrier().unwrap().checkpoint);
+        assert!(keyspace.scan(None, u64::MAX).await.unwrap().is_empty());
+
+        // the dirty states are still used to compute the output
+        let res = top_n_executor.next().await.unwrap().unwrap();
+        assert_eq!(
+            *res.as_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  I I
+                +  7 6
+                -  7 6
+                -  8 5
+                +  8 5
+                -  8 5
+                + 11 8"
+            )
+        );
+
+        // the states of both epochs are persisted on the checkpoint barrier
+        assert_matches!(
+            top_n_executor.next().await.unwrap().unwrap(),
+            Message::Barrier(_)
+        );
+        assert!(!keyspace.scan(None, u64::MAX).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_top_n_executor_with_limit() {
         let order_types = create_order_pairs();
@@ -668,6 +754,7 @@ mod tests {
                 (0, 0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
@@ -773,6 +860,7 @@ mod tests {
                 (0, 0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
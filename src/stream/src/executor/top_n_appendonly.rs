@@ -22,11 +22,13 @@ use risingwave_storage::cell_based_row_deserializer::CellBasedRowDeserializer;
 use risingwave_storage::{Keyspace, StateStore};
 
 use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::managed_state::evict_in_proportion;
 use super::managed_state::top_n::variants::TOP_N_MAX;
 use super::managed_state::top_n::ManagedTopNState;
 use super::top_n_executor::{generate_output, TopNExecutorBase, TopNExecutorWrapper};
 use super::{Executor, ExecutorInfo, PkIndices, PkIndicesRef};
 use crate::executor::top_n::generate_internal_key;
+use crate::task::CacheUsage;
 
 /// If the input contains only append, `AppendOnlyTopNExecutor` does not need
 /// to keep all the data records/rows that have been seen. As long as a record
@@ -47,6 +49,7 @@ impl<S: StateStore> AppendOnlyTopNExecutor<S> {
         total_count: (usize, usize),
         executor_id: u64,
         key_indices: Vec<usize>,
+        cache_usage: CacheUsage,
     ) -> Result<Self> {
         let info = input.info();
         let schema = input.schema().clone();
@@ -64,6 +67,7 @@ impl<S: StateStore> AppendOnlyTopNExecutor<S> {
                 total_count,
                 executor_id,
                 key_indices,
+                cache_usage,
             )?,
         })
     }
@@ -105,6 +109,9 @@ pub struct InnerAppendOnlyTopNExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// Reports the cached rows to the memory manager, which may ask for evicting them.
+    cache_usage: CacheUsage,
 }
 
 impl<S: StateStore> InnerAppendOnlyTopNExecutor<S> {
@@ -120,6 +127,7 @@ impl<S: StateStore> InnerAppendOnlyTopNExecutor<S> {
         total_count: (usize, usize),
         executor_id: u64,
         key_indices: Vec<usize>,
+        cache_usage: CacheUsage,
     ) -> Result<Self> {
         let (internal_key_indices, internal_key_data_types, internal_key_order_types) =
             generate_internal_key(&order_pairs, &pk_indices, &schema);
@@ -171,6 +179,7 @@ impl<S: StateStore> InnerAppendOnlyTopNExecutor<S> {
             internal_key_order_types,
             first_execution: true,
             key_indices,
+            cache_usage,
         })
     }
 
@@ -182,7 +191,17 @@ impl<S: StateStore> InnerAppendOnlyTopNExecutor<S> {
         self.managed_lower_state
             .flush(epoch)
             .await
-            .map_err(StreamExecutorError::top_n_state_error)
+            .map_err(StreamExecutorError::top_n_state_error)?;
+
+        let cached_entries = evict_in_proportion(
+            &mut [
+                &mut self.managed_lower_state,
+                &mut self.managed_higher_state,
+            ],
+            self.cache_usage.watermark(),
+        );
+        self.cache_usage.report(cached_entries);
+        Ok(())
     }
 }
 
@@ -319,6 +338,7 @@ mod tests {
     use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
     use crate::executor::top_n_appendonly::AppendOnlyTopNExecutor;
     use crate::executor::{Barrier, Epoch, Executor, Message, PkIndices};
+    use crate::task::CacheUsage;
 
     fn create_stream_chunks() -> Vec<StreamChunk> {
         let chunk1 = StreamChunk::from_pretty(
@@ -406,6 +426,7 @@ mod tests {
                 (0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
@@ -480,6 +501,7 @@ mod tests {
                 (0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
@@ -560,6 +582,7 @@ mod tests {
                 (0, 0),
                 1,
                 vec![],
+                CacheUsage::default(),
             )
             .unwrap(),
         );
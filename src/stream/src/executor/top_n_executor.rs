@@ -82,7 +82,8 @@ where
 {
     /// We remark that topN executor diffs from aggregate executor as it must output diffs
     /// whenever it applies a batch of input data. Therefore, topN executor flushes data only
-    /// instead of computing diffs and flushing when receiving a barrier.
+    /// instead of computing diffs and flushing when receiving a barrier. As the output doesn't
+    /// depend on flushing, the dirty states are kept in memory until the next checkpoint barrier.
     #[try_stream(ok = Message, error = StreamExecutorError)]
     pub(crate) async fn top_n_executor_execute(mut self: Box<Self>) {
         let mut input = self.input.execute();
@@ -99,7 +100,9 @@ where
                     yield Message::Chunk(self.inner.apply_chunk(chunk, epoch).await?)
                 }
                 Message::Barrier(barrier) => {
-                    self.inner.flush_data(epoch).await?;
+                    if barrier.checkpoint {
+                        self.inner.flush_data(epoch).await?;
+                    }
                     epoch = barrier.epoch.curr;
                     yield Message::Barrier(barrier)
                 }
@@ -12,17 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
-
+use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use risingwave_common::catalog::Schema;
-use risingwave_common::util::select_all;
 
+use super::error::StreamExecutorError;
 use super::*;
 use crate::executor::{BoxedMessageStream, ExecutorInfo};
 
-/// `UnionExecutor` merges data from multiple inputs.
+/// `UnionExecutor` merges data from multiple inputs. The chunks are forwarded as soon as they
+/// arrive, while a barrier is only forwarded after it's received from all the inputs.
 pub struct UnionExecutor {
     inputs: Vec<BoxedExecutor>,
     info: ExecutorInfo,
@@ -53,7 +53,7 @@ impl UnionExecutor {
 impl Executor for UnionExecutor {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
         let streams = self.inputs.into_iter().map(|e| e.execute()).collect();
-        merge(streams)
+        merge(streams).boxed()
     }
 
     fn schema(&self) -> &Schema {
@@ -70,37 +70,53 @@ impl Executor for UnionExecutor {
 }
 
 /// Merges input streams and aligns with barriers.
-pub fn merge(inputs: Vec<BoxedMessageStream>) -> BoxedMessageStream {
-    let barrier = Arc::new(tokio::sync::Barrier::new(inputs.len()));
-    let mut streams = vec![];
-    for input in inputs {
-        let barrier = barrier.clone();
-        let stream = #[try_stream]
-        async move {
-            #[for_await]
-            for item in input {
-                match item? {
-                    msg @ Message::Chunk(_) => yield msg,
-                    msg @ Message::Barrier(_) => {
-                        if barrier.wait().await.is_leader() {
-                            // one leader is responsible for sending barrier
-                            yield msg;
-                        }
+///
+/// Each input is polled for one message at a time, so that a busy input can't starve the others.
+/// Once an input yields a barrier, it's not polled anymore until the barrier is received from all
+/// the inputs, so no chunk of the next epoch can overtake the barrier.
+#[try_stream(ok = Message, error = StreamExecutorError)]
+pub async fn merge(inputs: Vec<BoxedMessageStream>) {
+    let mut active_num = inputs.len();
+    let mut active: FuturesUnordered<_> = inputs.into_iter().map(|s| s.into_future()).collect();
+    let mut blocked = Vec::with_capacity(active_num);
+    let mut current_barrier: Option<Barrier> = None;
+
+    while let Some((message, remaining)) = active.next().await {
+        match message.transpose()? {
+            Some(Message::Chunk(chunk)) => {
+                active.push(remaining.into_future());
+                yield Message::Chunk(chunk);
+            }
+            Some(Message::Barrier(barrier)) => {
+                match &current_barrier {
+                    Some(current) if current.epoch != barrier.epoch => {
+                        return Err(StreamExecutorError::align_barrier(current.clone(), barrier));
                     }
+                    Some(_) => {}
+                    None => current_barrier = Some(barrier),
                 }
+                blocked.push(remaining);
             }
-        };
-        streams.push(stream.boxed());
+            // The input is finished, which should only happen after a stop barrier.
+            None => active_num -= 1,
+        }
+
+        if !blocked.is_empty() && blocked.len() == active_num {
+            active.extend(blocked.drain(..).map(|s| s.into_future()));
+            yield Message::Barrier(current_barrier.take().unwrap());
+        }
     }
-    select_all(streams).boxed()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use async_stream::try_stream;
     use futures::TryStreamExt;
     use risingwave_common::array::stream_chunk::StreamChunkTestExt;
     use risingwave_common::array::StreamChunk;
+    use tokio::time::sleep;
 
     use super::*;
 
@@ -135,4 +151,47 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn union_with_slow_input() {
+        let streams = vec![
+            try_stream! {
+                yield Message::Barrier(Barrier::new_test_barrier(1));
+                yield Message::Chunk(StreamChunk::from_pretty("I\n + 2"));
+            }
+            .boxed(),
+            try_stream! {
+                sleep(Duration::from_millis(1)).await;
+                yield Message::Chunk(StreamChunk::from_pretty("I\n + 1"));
+                yield Message::Barrier(Barrier::new_test_barrier(1));
+            }
+            .boxed(),
+        ];
+        let output: Vec<_> = merge(streams).try_collect().await.unwrap();
+        // The chunk after the barrier of the fast input must not overtake the barrier.
+        assert_eq!(
+            output,
+            vec![
+                Message::Chunk(StreamChunk::from_pretty("I\n + 1")),
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty("I\n + 2")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn union_with_misaligned_barriers() {
+        let streams = vec![
+            try_stream! {
+                yield Message::Barrier(Barrier::new_test_barrier(1));
+            }
+            .boxed(),
+            try_stream! {
+                yield Message::Barrier(Barrier::new_test_barrier(2));
+            }
+            .boxed(),
+        ];
+        let output: Result<Vec<_>, _> = merge(streams).try_collect().await;
+        assert!(output.is_err());
+    }
 }
@@ -22,6 +22,7 @@ use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
 use super::*;
 use crate::executor::aggregation::AggCall;
 use crate::executor::{HashAggExecutor, PkIndices};
+use crate::task::{ActorId, CacheUsage};
 
 struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
 
@@ -31,7 +32,9 @@ struct HashAggExecutorDispatcherArgs<S: StateStore> {
     key_indices: Vec<usize>,
     keyspace: Vec<Keyspace<S>>,
     pk_indices: PkIndices,
+    actor_id: ActorId,
     executor_id: u64,
+    cache_usage: CacheUsage,
 }
 
 impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
@@ -44,8 +47,10 @@ impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
             args.agg_calls,
             args.keyspace,
             args.pk_indices,
+            args.actor_id,
             args.executor_id,
             args.key_indices,
+            args.cache_usage,
         )?
         .boxed())
     }
@@ -58,7 +63,7 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
         mut params: ExecutorParams,
         node: &StreamNode,
         store: impl StateStore,
-        _stream: &mut LocalStreamManagerCore,
+        stream: &mut LocalStreamManagerCore,
     ) -> Result<BoxedExecutor> {
         let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::HashAgg)?;
         let key_indices = node
@@ -91,7 +96,9 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             key_indices,
             keyspace,
             pk_indices: params.pk_indices,
+            actor_id: params.actor_id,
             executor_id: params.executor_id,
+            cache_usage: stream.memory_manager.register(params.executor_id),
         };
         HashAggExecutorDispatcher::dispatch_by_kind(kind, args)
     }
@@ -22,6 +22,7 @@ use risingwave_pb::plan_common::JoinType as JoinTypeProto;
 use super::*;
 use crate::executor::hash_join::*;
 use crate::executor::PkIndices;
+use crate::task::CacheUsage;
 
 pub struct HashJoinExecutorBuilder;
 
@@ -30,7 +31,7 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
         mut params: ExecutorParams,
         node: &StreamNode,
         store: impl StateStore,
-        _stream: &mut LocalStreamManagerCore,
+        stream: &mut LocalStreamManagerCore,
     ) -> Result<BoxedExecutor> {
         // Get table id and used as keyspace prefix.
         let append_only = node.get_append_only();
@@ -115,6 +116,8 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
             keyspace_l: Keyspace::table_root(store.clone(), &left_table_id),
             keyspace_r: Keyspace::table_root(store, &right_table_id),
             append_only,
+            cache_size: params.env.config().join_cache_size,
+            cache_usage: stream.memory_manager.register(params.executor_id),
         };
 
         for_all_join_types! { impl_create_hash_join_executor };
@@ -138,6 +141,8 @@ struct HashJoinExecutorDispatcherArgs<S: StateStore> {
     keyspace_l: Keyspace<S>,
     keyspace_r: Keyspace<S>,
     append_only: bool,
+    cache_size: usize,
+    cache_usage: CacheUsage,
 }
 
 impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
@@ -160,6 +165,8 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
             args.keyspace_l,
             args.keyspace_r,
             args.append_only,
+            args.cache_size,
+            args.cache_usage,
         )))
     }
 }
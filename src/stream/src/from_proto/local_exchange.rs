@@ -0,0 +1,51 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::executor::LocalExchangeExecutor;
+
+pub struct LocalExchangeExecutorBuilder;
+
+impl ExecutorBuilder for LocalExchangeExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        // The input executor is the one of the operator, see `create_nodes_inner`.
+        let operator = &node.get_input()[0];
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::LocalExchange)?;
+        let key_indices = node
+            .get_key_indices()
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+        let input = params.input.remove(0);
+        let actor_context = params.actor_context.clone();
+        let executor_id = params.executor_id;
+
+        Ok(LocalExchangeExecutor::new(
+            input,
+            key_indices,
+            node.parallelism as usize,
+            |branch, input| {
+                stream.create_node_with_input(operator, branch, input, &params, store.clone())
+            },
+            actor_context,
+            executor_id,
+        )?
+        .boxed())
+    }
+}
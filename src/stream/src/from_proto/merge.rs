@@ -32,6 +32,10 @@ impl ExecutorBuilder for MergeExecutorBuilder {
         let fields = node.fields.iter().map(Field::from).collect();
         let schema = Schema::new(fields);
         let mut rxs = stream.get_receive_message(params.actor_id, upstreams)?;
+        let mut occupancies = upstreams
+            .iter()
+            .map(|up_id| stream.context.buffer_occupancy((*up_id, params.actor_id)))
+            .collect_vec();
         let actor_context = params.actor_context;
 
         if upstreams.len() == 1 {
@@ -42,6 +46,7 @@ impl ExecutorBuilder for MergeExecutorBuilder {
                 actor_context,
                 x_node.operator_id,
             )
+            .with_buffer_occupancy(occupancies.remove(0))
             .boxed())
         } else {
             Ok(MergeExecutor::new(
@@ -52,6 +57,7 @@ impl ExecutorBuilder for MergeExecutorBuilder {
                 actor_context,
                 x_node.operator_id,
             )
+            .with_buffer_occupancies(occupancies)
             .boxed())
         }
     }
@@ -21,12 +21,15 @@ mod global_simple_agg;
 mod hash_agg;
 mod hash_join;
 mod hop_window;
+mod local_exchange;
 mod local_simple_agg;
 mod lookup;
 mod lookup_union;
 mod merge;
 mod mview;
 mod project;
+mod project_set;
+mod sink;
 mod source;
 mod top_n;
 mod top_n_appendonly;
@@ -47,12 +50,15 @@ use self::global_simple_agg::*;
 use self::hash_agg::*;
 use self::hash_join::*;
 use self::hop_window::*;
+use self::local_exchange::*;
 use self::local_simple_agg::*;
 use self::lookup::*;
 use self::lookup_union::*;
 use self::merge::*;
 use self::mview::*;
 use self::project::*;
+use self::project_set::*;
+use self::sink::*;
 use self::source::*;
 use self::top_n::*;
 use self::top_n_appendonly::*;
@@ -102,6 +108,7 @@ pub fn create_executor(
         stream,
         NodeBody::Source => SourceExecutorBuilder,
         NodeBody::Project => ProjectExecutorBuilder,
+        NodeBody::ProjectSet => ProjectSetExecutorBuilder,
         NodeBody::TopN => TopNExecutorBuilder,
         NodeBody::AppendOnlyTopN => AppendOnlyTopNExecutorBuilder,
         NodeBody::LocalSimpleAgg => LocalSimpleAggExecutorBuilder,
@@ -118,5 +125,7 @@ pub fn create_executor(
         NodeBody::Lookup => LookupExecutorBuilder,
         NodeBody::Union => UnionExecutorBuilder,
         NodeBody::LookupUnion => LookupUnionExecutorBuilder,
+        NodeBody::Sink => SinkExecutorBuilder,
+        NodeBody::LocalExchange => LocalExchangeExecutorBuilder,
     }
 }
@@ -14,9 +14,10 @@
 
 use risingwave_common::catalog::{ColumnId, TableId};
 use risingwave_common::util::sort_util::OrderPair;
+use risingwave_pb::plan_common::ConflictBehavior as ProstConflictBehavior;
 
 use super::*;
-use crate::executor::MaterializeExecutor;
+use crate::executor::{ConflictBehavior, MaterializeExecutor, SanityCheckMode};
 
 pub struct MaterializeExecutorBuilder;
 
@@ -50,6 +51,17 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             .map(|key| *key as usize)
             .collect();
 
+        let conflict_behavior = match node.get_conflict_behavior()? {
+            ProstConflictBehavior::NoCheck => ConflictBehavior::NoCheck,
+            ProstConflictBehavior::Overwrite => ConflictBehavior::Overwrite,
+            // Conflicts are reported by the insert executor. Rows that race past the check are
+            // dropped, so that the first written row wins.
+            ProstConflictBehavior::IgnoreConflict | ProstConflictBehavior::ErrorOnConflict => {
+                ConflictBehavior::IgnoreConflict
+            }
+        };
+        let sanity_check: SanityCheckMode = params.env.config().materialize_sanity_check.parse()?;
+
         let executor = MaterializeExecutor::new(
             params.input.remove(0),
             keyspace,
@@ -57,7 +69,9 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             column_ids,
             params.executor_id,
             distribution_keys,
-        );
+        )
+        .with_conflict_behavior(conflict_behavior)
+        .with_sanity_check(sanity_check);
 
         Ok(executor.boxed())
     }
@@ -15,7 +15,7 @@
 use risingwave_expr::expr::build_from_prost;
 
 use super::*;
-use crate::executor::ProjectExecutor;
+use crate::executor::{ExprErrorPolicy, ProjectExecutor};
 
 pub struct ProjectExecutorBuilder;
 
@@ -32,6 +32,11 @@ impl ExecutorBuilder for ProjectExecutorBuilder {
             .iter()
             .map(build_from_prost)
             .collect::<Result<Vec<_>>>()?;
+        let error_policy: ExprErrorPolicy = params.env.config().expr_error_policy.parse()?;
+        let error_count = params.executor_stats.expr_error_count.with_label_values(&[
+            &params.actor_id.to_string(),
+            &params.executor_id.to_string(),
+        ]);
 
         Ok(ProjectExecutor::new(
             params.input.remove(0),
@@ -39,6 +44,7 @@ impl ExecutorBuilder for ProjectExecutorBuilder {
             project_exprs,
             params.executor_id,
         )
+        .with_error_policy(error_policy, error_count)
         .boxed())
     }
 }
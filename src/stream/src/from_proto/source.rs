@@ -19,6 +19,7 @@ use tokio::sync::mpsc::unbounded_channel;
 
 use super::*;
 use crate::executor::SourceExecutor;
+use crate::task::OutputBackpressure;
 
 pub struct SourceExecutorBuilder;
 
@@ -66,20 +67,28 @@ impl ExecutorBuilder for SourceExecutorBuilder {
         let schema = Schema::new(fields);
         let keyspace = Keyspace::executor_root(store, params.executor_id);
 
-        Ok(Box::new(SourceExecutor::new(
-            source_id,
-            source_desc,
-            keyspace,
-            column_ids,
-            schema,
-            params.pk_indices,
-            barrier_receiver,
-            params.executor_id,
-            params.operator_id,
-            params.op_info,
-            params.executor_stats,
-            stream_source_splits,
-            stream.config.checkpoint_interval_ms as u64,
-        )?))
+        Ok(Box::new(
+            SourceExecutor::new(
+                source_id,
+                source_desc,
+                keyspace,
+                column_ids,
+                schema,
+                params.pk_indices,
+                barrier_receiver,
+                params.actor_id,
+                params.executor_id,
+                params.operator_id,
+                params.op_info,
+                params.executor_stats,
+                stream_source_splits,
+                stream.config.checkpoint_interval_ms as u64,
+            )?
+            .with_backpressure(OutputBackpressure::new(
+                stream.context.buffer_monitor.clone(),
+                params.actor_id,
+            ))
+            .with_stop_signal(stream.context.sources_stopped.clone()),
+        ))
     }
 }
@@ -24,7 +24,7 @@ impl ExecutorBuilder for AppendOnlyTopNExecutorBuilder {
         mut params: ExecutorParams,
         node: &StreamNode,
         store: impl StateStore,
-        _stream: &mut LocalStreamManagerCore,
+        stream: &mut LocalStreamManagerCore,
     ) -> Result<BoxedExecutor> {
         let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::AppendOnlyTopN)?;
         let order_pairs: Vec<_> = node
@@ -56,6 +56,7 @@ impl ExecutorBuilder for AppendOnlyTopNExecutorBuilder {
             total_count,
             params.executor_id,
             key_indices,
+            stream.memory_manager.register(params.executor_id),
         )?
         .boxed())
     }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use madsim::collections::{HashMap, HashSet};
 use risingwave_common::error::Result;
 use risingwave_pb::hummock::SstableInfo;
@@ -40,6 +42,21 @@ pub struct CollectResult {
     pub create_mview_progress: Vec<ProstCreateMviewProgress>,
 
     pub synced_sstables: Vec<SstableInfo>,
+
+    /// How long it took for each actor to report the barrier.
+    pub actor_latencies: Vec<(ActorId, Duration)>,
+}
+
+/// A barrier which has been issued by the meta service but not fully collected yet.
+#[derive(Debug, Clone)]
+pub struct InflightBarrier {
+    pub epoch: u64,
+
+    /// Time since the barrier is issued.
+    pub elapsed: Duration,
+
+    /// Actors that haven't reported the barrier, which are holding up the epoch.
+    pub remaining_actors: Vec<ActorId>,
 }
 
 enum BarrierState {
@@ -156,6 +173,16 @@ impl LocalBarrierManager {
         Ok(rx)
     }
 
+    /// Returns the barrier being collected in managed mode.
+    pub fn inflight_barrier(&self) -> Option<InflightBarrier> {
+        match &self.state {
+            #[cfg(test)]
+            BarrierState::Local => None,
+
+            BarrierState::Managed(managed_state) => managed_state.inflight_barrier(),
+        }
+    }
+
     /// When a [`StreamConsumer`] (typically [`DispatchExecutor`]) get a barrier, it should report
     /// and collect this barrier with its own `actor_id` using this function.
     pub fn collect(&mut self, actor_id: ActorId, barrier: &Barrier) -> Result<()> {
@@ -14,13 +14,15 @@
 
 use std::collections::HashMap;
 use std::iter::once;
+use std::time::{Duration, Instant};
 
+use itertools::Itertools;
 use madsim::collections::HashSet;
 use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress;
 use tokio::sync::oneshot;
 
 use super::progress::ChainState;
-use super::CollectResult;
+use super::{CollectResult, InflightBarrier};
 use crate::executor::Barrier;
 use crate::task::ActorId;
 
@@ -50,6 +52,13 @@ enum ManagedBarrierStateInner {
         /// Actor ids remaining to be collected.
         remaining_actors: HashSet<ActorId>,
 
+        /// When the `send_barrier` request is issued.
+        issued_at: Instant,
+
+        /// How long it took for each actor to report the barrier after the request is issued.
+        /// Actors collected before that are recorded as zero.
+        actor_latencies: Vec<(ActorId, Duration)>,
+
         /// Notify that the collection is finished.
         collect_notifier: oneshot::Sender<CollectResult>,
     },
@@ -78,6 +87,23 @@ impl ManagedBarrierState {
         &mut self.inner
     }
 
+    /// Returns the barrier being collected, if the meta service has issued it.
+    pub(super) fn inflight_barrier(&self) -> Option<InflightBarrier> {
+        match &self.inner {
+            ManagedBarrierStateInner::Issued {
+                epoch,
+                remaining_actors,
+                issued_at,
+                ..
+            } => Some(InflightBarrier {
+                epoch: *epoch,
+                elapsed: issued_at.elapsed(),
+                remaining_actors: remaining_actors.iter().cloned().sorted().collect(),
+            }),
+            _ => None,
+        }
+    }
+
     /// Notify if we have collected barriers from all actor ids. The state must be `Issued`.
     fn may_notify(&mut self) {
         let (epoch, to_notify) = match self.inner_mut() {
@@ -114,12 +140,15 @@ impl ManagedBarrierState {
 
             match state {
                 ManagedBarrierStateInner::Issued {
-                    collect_notifier, ..
+                    collect_notifier,
+                    actor_latencies,
+                    ..
                 } => {
                     // Notify about barrier finishing.
                     let result = CollectResult {
                         create_mview_progress,
                         synced_sstables: vec![],
+                        actor_latencies,
                     };
                     if collect_notifier.send(result).is_err() {
                         warn!("failed to notify barrier collection with epoch {}", epoch)
@@ -166,12 +195,15 @@ impl ManagedBarrierState {
             ManagedBarrierStateInner::Issued {
                 epoch,
                 remaining_actors,
+                issued_at,
+                actor_latencies,
                 ..
             } => {
                 assert_eq!(barrier.epoch.curr, *epoch);
 
                 let exist = remaining_actors.remove(&actor_id);
                 assert!(exist);
+                actor_latencies.push((actor_id, issued_at.elapsed()));
                 self.may_notify();
             }
         }
@@ -192,6 +224,8 @@ impl ManagedBarrierState {
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
                     remaining_actors,
+                    issued_at: Instant::now(),
+                    actor_latencies: vec![],
                     collect_notifier,
                 };
                 self.may_notify();
@@ -207,10 +241,16 @@ impl ManagedBarrierState {
                     .into_iter()
                     .filter(|a| !collected_actors.contains(a))
                     .collect();
+                let actor_latencies = collected_actors
+                    .iter()
+                    .map(|&actor_id| (actor_id, Duration::ZERO))
+                    .collect();
 
                 *self.inner_mut() = ManagedBarrierStateInner::Issued {
                     epoch: barrier.epoch.curr,
                     remaining_actors,
+                    issued_at: Instant::now(),
+                    actor_latencies,
                     collect_notifier,
                 };
                 self.may_notify();
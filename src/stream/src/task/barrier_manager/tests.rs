@@ -126,3 +126,48 @@ async fn test_managed_barrier_collection_before_send_request() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_managed_barrier_inflight() -> Result<()> {
+    let mut manager = LocalBarrierManager::new();
+    assert!(manager.inflight_barrier().is_none());
+
+    let actor_ids = vec![235, 233, 234];
+    let _rxs = actor_ids
+        .iter()
+        .map(|&actor_id| {
+            let (barrier_tx, barrier_rx) = unbounded_channel();
+            manager.register_sender(actor_id, barrier_tx);
+            barrier_rx
+        })
+        .collect_vec();
+
+    let epoch = 114514;
+    let barrier = Barrier::new_test_barrier(epoch);
+    let mut collect_rx = manager
+        .send_barrier(&barrier, actor_ids.clone(), actor_ids)
+        .unwrap()
+        .unwrap();
+
+    manager.collect(234, &barrier).unwrap();
+    let inflight = manager.inflight_barrier().unwrap();
+    assert_eq!(inflight.epoch, epoch);
+    assert_eq!(inflight.remaining_actors, vec![233, 235]);
+
+    manager.collect(233, &barrier).unwrap();
+    manager.collect(235, &barrier).unwrap();
+    assert!(manager.inflight_barrier().is_none());
+
+    let collect_result = collect_rx.try_recv().unwrap();
+    assert_eq!(
+        collect_result
+            .actor_latencies
+            .iter()
+            .map(|(actor_id, _)| *actor_id)
+            .sorted()
+            .collect_vec(),
+        vec![233, 234, 235]
+    );
+
+    Ok(())
+}
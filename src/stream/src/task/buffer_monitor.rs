@@ -0,0 +1,162 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use madsim::collections::HashMap;
+use parking_lot::Mutex;
+
+use super::{ActorId, UpDownActorIds, LOCAL_OUTPUT_CHANNEL_SIZE};
+
+/// Once any output buffer of an actor holds this many messages, its source stops polling.
+pub const OUTPUT_BUFFER_HIGH_WATER_MARK: usize = LOCAL_OUTPUT_CHANNEL_SIZE * 3 / 4;
+
+/// A throttled source resumes polling after all its output buffers drain below this.
+pub const OUTPUT_BUFFER_LOW_WATER_MARK: usize = LOCAL_OUTPUT_CHANNEL_SIZE / 4;
+
+/// The number of messages sent into an exchange channel but not received by the other side yet.
+/// It's shared by the `Output` of the upstream actor, which increases it, and the `MergeExecutor`,
+/// `ReceiverExecutor` or exchange service consuming the channel, which decreases it.
+#[derive(Clone, Debug, Default)]
+pub struct BufferOccupancy(Arc<AtomicUsize>);
+
+impl BufferOccupancy {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        // The receiving side may not be paired with a sender on this node, e.g., for a channel fed
+        // by `RemoteInput`. Saturate instead of underflowing in that case.
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| x.checked_sub(1));
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Tracks the occupancy of all exchange channels on this node.
+#[derive(Debug, Default)]
+pub struct BufferMonitor {
+    channels: Mutex<HashMap<UpDownActorIds, BufferOccupancy>>,
+}
+
+impl BufferMonitor {
+    /// Get the occupancy of the channel between the given actors, registering it if it doesn't
+    /// exist yet.
+    pub fn occupancy(&self, ids: UpDownActorIds) -> BufferOccupancy {
+        self.channels.lock().entry(ids).or_default().clone()
+    }
+
+    /// The highest occupancy among the output channels of the given actor.
+    pub fn max_output_occupancy(&self, actor_id: ActorId) -> usize {
+        self.channels
+            .lock()
+            .iter()
+            .filter(|((up_id, _), _)| *up_id == actor_id)
+            .map(|(_, occupancy)| occupancy.get())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn retain<F>(&self, mut f: F)
+    where
+        F: FnMut(&UpDownActorIds) -> bool,
+    {
+        self.channels.lock().retain(|ids, _| f(ids));
+    }
+}
+
+/// Decides whether a source should stop polling according to the output buffers of its actor, so
+/// that a slow downstream doesn't make the data pile up in the exchange. The source is throttled
+/// once any buffer reaches the high-water mark, and released after all of them drop to the
+/// low-water mark.
+#[derive(Debug)]
+pub struct OutputBackpressure {
+    monitor: Arc<BufferMonitor>,
+    actor_id: ActorId,
+    high_water_mark: usize,
+    low_water_mark: usize,
+    throttled: bool,
+}
+
+impl OutputBackpressure {
+    pub fn new(monitor: Arc<BufferMonitor>, actor_id: ActorId) -> Self {
+        Self {
+            monitor,
+            actor_id,
+            high_water_mark: OUTPUT_BUFFER_HIGH_WATER_MARK,
+            low_water_mark: OUTPUT_BUFFER_LOW_WATER_MARK,
+            throttled: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_water_marks(mut self, high_water_mark: usize, low_water_mark: usize) -> Self {
+        assert!(low_water_mark <= high_water_mark);
+        self.high_water_mark = high_water_mark;
+        self.low_water_mark = low_water_mark;
+        self
+    }
+
+    /// Check the output buffers and return whether the source should hold off polling.
+    pub fn is_throttled(&mut self) -> bool {
+        let occupancy = self.monitor.max_output_occupancy(self.actor_id);
+        if self.throttled {
+            self.throttled = occupancy > self.low_water_mark;
+        } else {
+            self.throttled = occupancy >= self.high_water_mark;
+        }
+        self.throttled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_backpressure() {
+        let monitor = Arc::new(BufferMonitor::default());
+        let to_fast = monitor.occupancy((1, 2));
+        let to_slow = monitor.occupancy((1, 3));
+        let other = monitor.occupancy((4, 5));
+        let mut backpressure = OutputBackpressure::new(monitor.clone(), 1).with_water_marks(4, 1);
+
+        (0..10).for_each(|_| other.inc());
+        to_fast.inc();
+        assert!(!backpressure.is_throttled());
+
+        (0..4).for_each(|_| to_slow.inc());
+        assert_eq!(monitor.max_output_occupancy(1), 4);
+        assert!(backpressure.is_throttled());
+
+        // Stay throttled until all buffers drain to the low-water mark.
+        (0..2).for_each(|_| to_slow.dec());
+        assert!(backpressure.is_throttled());
+        to_slow.dec();
+        assert!(!backpressure.is_throttled());
+
+        // Never underflow.
+        (0..3).for_each(|_| to_fast.dec());
+        assert_eq!(to_fast.get(), 0);
+
+        monitor.retain(|&(up_id, _)| up_id != 1);
+        assert_eq!(monitor.max_output_occupancy(1), 0);
+    }
+}
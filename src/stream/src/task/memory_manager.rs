@@ -0,0 +1,191 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use madsim::collections::HashMap;
+use parking_lot::Mutex;
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+#[derive(Debug)]
+struct CacheUsageInner {
+    /// The number of entries cached by the executor, as of its latest report.
+    entries: AtomicUsize,
+    /// The max number of entries the executor should keep on its next eviction.
+    watermark: AtomicUsize,
+}
+
+/// The cache usage of an executor, e.g., the agg states of `HashAggExecutor`, the hash tables of
+/// `HashJoinExecutor` or the caches of `TopNExecutor`. It's shared by the executor, which reports
+/// how many entries it caches, and the [`MemoryManager`], which lowers the watermark of the
+/// executor under memory pressure.
+#[derive(Clone, Debug)]
+pub struct CacheUsage(Arc<CacheUsageInner>);
+
+impl Default for CacheUsage {
+    /// A cache usage not registered to any memory manager, which never asks to evict.
+    fn default() -> Self {
+        Self(Arc::new(CacheUsageInner {
+            entries: AtomicUsize::new(0),
+            watermark: AtomicUsize::new(usize::MAX),
+        }))
+    }
+}
+
+impl CacheUsage {
+    pub fn report(&self, entries: usize) {
+        self.0.entries.store(entries, Ordering::Relaxed);
+    }
+
+    pub fn entries(&self) -> usize {
+        self.0.entries.load(Ordering::Relaxed)
+    }
+
+    /// The max number of entries the executor should keep after evicting its caches. It's
+    /// `usize::MAX` when there's no memory pressure.
+    pub fn watermark(&self) -> usize {
+        self.0.watermark.load(Ordering::Relaxed)
+    }
+
+    fn set_watermark(&self, watermark: usize) {
+        self.0.watermark.store(watermark, Ordering::Relaxed);
+    }
+}
+
+/// Tracks the cache usage of all stream executors on this node. Once the cached entries in total
+/// exceed the budget, every executor is asked to shrink its caches in proportion to its usage, so
+/// that large jobs don't get the compute node killed for running out of memory.
+#[derive(Debug)]
+pub struct MemoryManager {
+    /// The max number of entries cached by all executors.
+    budget: usize,
+    caches: Mutex<HashMap<u64, Weak<CacheUsageInner>>>,
+}
+
+impl MemoryManager {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            caches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register the caches of an executor. They're unregistered once the executor is dropped.
+    pub fn register(&self, executor_id: u64) -> CacheUsage {
+        let usage = CacheUsage::default();
+        self.caches
+            .lock()
+            .insert(executor_id, Arc::downgrade(&usage.0));
+        usage
+    }
+
+    /// Get the cache usage of an executor, if it has registered its caches.
+    pub fn cache_usage(&self, executor_id: u64) -> Option<CacheUsage> {
+        self.caches
+            .lock()
+            .get(&executor_id)
+            .and_then(Weak::upgrade)
+            .map(CacheUsage)
+    }
+
+    /// The number of entries cached by all executors.
+    pub fn total_entries(&self) -> usize {
+        self.live_caches().iter().map(CacheUsage::entries).sum()
+    }
+
+    fn live_caches(&self) -> Vec<CacheUsage> {
+        let mut caches = self.caches.lock();
+        caches.retain(|_, usage| usage.strong_count() > 0);
+        caches
+            .values()
+            .filter_map(|usage| usage.upgrade().map(CacheUsage))
+            .collect()
+    }
+
+    /// Check the cache usage and update the watermarks of the executors accordingly.
+    pub fn check(&self) {
+        let caches = self.live_caches();
+        let total: usize = caches.iter().map(CacheUsage::entries).sum();
+
+        if total <= self.budget {
+            caches
+                .iter()
+                .for_each(|usage| usage.set_watermark(usize::MAX));
+            return;
+        }
+
+        tracing::warn!(
+            "stream executors cache {} entries, exceeding the budget {}, start evicting",
+            total,
+            self.budget
+        );
+        for usage in &caches {
+            let watermark = usage.entries() as u128 * self.budget as u128 / total as u128;
+            usage.set_watermark(watermark as usize);
+        }
+    }
+
+    /// Start a loop checking the cache usage every `interval`.
+    pub fn start(self: Arc<Self>, interval: Duration) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {},
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Memory manager is shutting down");
+                        return;
+                    }
+                }
+                self.check();
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_manager() {
+        let manager = MemoryManager::new(100);
+        let small = manager.register(1);
+        let large = manager.register(2);
+
+        small.report(20);
+        large.report(60);
+        manager.check();
+        assert_eq!(manager.total_entries(), 80);
+        assert_eq!(small.watermark(), usize::MAX);
+        assert_eq!(large.watermark(), usize::MAX);
+
+        // Shrink in proportion to the usage.
+        large.report(180);
+        manager.check();
+        assert_eq!(small.watermark(), 10);
+        assert_eq!(large.watermark(), 90);
+
+        // Dropped executors are no longer counted.
+        drop(large);
+        manager.check();
+        assert_eq!(manager.total_entries(), 20);
+        assert_eq!(small.watermark(), usize::MAX);
+    }
+}
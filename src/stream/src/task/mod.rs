@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use futures::channel::mpsc::{Receiver, Sender};
@@ -23,11 +24,17 @@ use risingwave_common::util::addr::HostAddr;
 use crate::executor::Message;
 
 mod barrier_manager;
+mod buffer_monitor;
 mod env;
+mod memory_manager;
+mod stack_trace;
 mod stream_manager;
 
 pub use barrier_manager::*;
+pub use buffer_monitor::*;
 pub use env::*;
+pub use memory_manager::*;
+pub use stack_trace::*;
 pub use stream_manager::*;
 
 /// Default capacity of channel if two actors are on the same node
@@ -68,6 +75,13 @@ pub struct SharedContext {
     pub(crate) addr: HostAddr,
 
     pub(crate) barrier_manager: Arc<Mutex<LocalBarrierManager>>,
+
+    /// Tracks how many messages are buffered in each channel in `channel_map`, so that sources
+    /// can back off when their downstream can't keep up.
+    pub(crate) buffer_monitor: Arc<BufferMonitor>,
+
+    /// Set when the node is shutting down, which stops all the sources on this node from polling.
+    pub(crate) sources_stopped: Arc<AtomicBool>,
 }
 
 impl std::fmt::Debug for SharedContext {
@@ -84,6 +98,8 @@ impl SharedContext {
             channel_map: Mutex::new(HashMap::new()),
             addr,
             barrier_manager: Arc::new(Mutex::new(LocalBarrierManager::new())),
+            buffer_monitor: Arc::new(BufferMonitor::default()),
+            sources_stopped: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -93,6 +109,8 @@ impl SharedContext {
             channel_map: Mutex::new(HashMap::new()),
             addr: LOCAL_TEST_ADDR.clone(),
             barrier_manager: Arc::new(Mutex::new(LocalBarrierManager::for_test())),
+            buffer_monitor: Arc::new(BufferMonitor::default()),
+            sources_stopped: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -145,6 +163,12 @@ impl SharedContext {
             })
     }
 
+    /// Get the occupancy of the channel between the given actors.
+    #[inline]
+    pub fn buffer_occupancy(&self, ids: UpDownActorIds) -> BufferOccupancy {
+        self.buffer_monitor.occupancy(ids)
+    }
+
     #[inline]
     pub fn add_channel_pairs(&self, ids: UpDownActorIds, channels: ConsumableChannelPair) {
         self.lock_channel_map().insert(ids, channels);
@@ -156,6 +180,7 @@ impl SharedContext {
     {
         self.lock_channel_map()
             .retain(|up_down_ids, _| f(up_down_ids));
+        self.buffer_monitor.retain(f);
     }
 
     #[cfg(test)]
@@ -170,6 +195,15 @@ pub fn unique_executor_id(actor_id: u32, operator_id: u64) -> u64 {
     ((actor_id as u64) << 32) + operator_id
 }
 
+/// Generate a globally unique executor id for the `branch`-th instance of an operator, which runs
+/// in parallel branches within an actor under a local exchange. The branch is kept in the highest
+/// byte, which is left unused by [`unique_executor_id`] as long as the actor ids are below 2^24.
+pub fn unique_branch_executor_id(actor_id: u32, operator_id: u64, branch: usize) -> u64 {
+    assert!(actor_id < 1 << 24);
+    assert!(branch < u8::MAX as usize);
+    unique_executor_id(actor_id, operator_id) | ((branch as u64 + 1) << 56)
+}
+
 /// Generate a globally unique operator id. Useful when constructing per-fragment keyspace.
 pub fn unique_operator_id(fragment_id: u32, operator_id: u64) -> u64 {
     assert!(operator_id <= u32::MAX as u64);
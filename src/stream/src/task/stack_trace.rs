@@ -0,0 +1,324 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records the async call tree of each actor, i.e., the futures it's awaiting at the moment, so
+//! that an actor stuck on a channel or a storage read can be diagnosed by dumping the tree.
+//!
+//! An actor is run under a [`TraceContext`], and the futures it awaits are registered in the tree
+//! if they're wrapped with [`StackTrace::stack_trace`]. Actors are only run under a context if
+//! `async_stack_trace` is enabled in the streaming config, otherwise the wrapped futures are polled
+//! as is.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use madsim::collections::HashMap;
+use parking_lot::Mutex;
+use pin_project::{pin_project, pinned_drop};
+
+/// The description of a future in the async call tree.
+pub type SpanValue = Cow<'static, str>;
+
+type NodeId = usize;
+
+const ROOT_NODE_ID: NodeId = 0;
+
+#[derive(Debug)]
+struct SpanNode {
+    span: SpanValue,
+    start: Instant,
+    parent: NodeId,
+    children: Vec<NodeId>,
+}
+
+/// The async call tree of an actor. The root node is the actor itself, and the children of a node
+/// are the traced futures it's awaiting.
+#[derive(Debug)]
+struct TraceTree {
+    nodes: HashMap<NodeId, SpanNode>,
+    next_id: NodeId,
+    /// The node being polled.
+    current: NodeId,
+}
+
+impl TraceTree {
+    fn new(root_span: SpanValue) -> Self {
+        let root = SpanNode {
+            span: root_span,
+            start: Instant::now(),
+            parent: ROOT_NODE_ID,
+            children: vec![],
+        };
+        Self {
+            nodes: [(ROOT_NODE_ID, root)].into_iter().collect(),
+            next_id: ROOT_NODE_ID + 1,
+            current: ROOT_NODE_ID,
+        }
+    }
+
+    /// Adds a node as the child of the one being polled.
+    fn add_child(&mut self, span: SpanValue) -> NodeId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.insert(
+            id,
+            SpanNode {
+                span,
+                start: Instant::now(),
+                parent: self.current,
+                children: vec![],
+            },
+        );
+        self.nodes.get_mut(&self.current).unwrap().children.push(id);
+        id
+    }
+
+    /// Removes the node along with its descendants, e.g., the ones not finished when the future is
+    /// dropped.
+    fn remove(&mut self, id: NodeId) {
+        let Some(node) = self.nodes.remove(&id) else {
+            return;
+        };
+        if let Some(parent) = self.nodes.get_mut(&node.parent) {
+            parent.children.retain(|&child| child != id);
+        }
+        let mut to_remove = node.children;
+        while let Some(id) = to_remove.pop() {
+            if let Some(node) = self.nodes.remove(&id) {
+                to_remove.extend(node.children);
+            }
+        }
+    }
+
+    fn fmt_node(&self, f: &mut Formatter<'_>, id: NodeId, depth: usize) -> fmt::Result {
+        let node = &self.nodes[&id];
+        writeln!(
+            f,
+            "{}{} [{:.3?}]",
+            "  ".repeat(depth),
+            node.span,
+            node.start.elapsed()
+        )?;
+        for &child in &node.children {
+            self.fmt_node(f, child, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TraceTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_node(f, ROOT_NODE_ID, 0)
+    }
+}
+
+thread_local! {
+    /// The context of the root future being polled on this thread, if any.
+    static CURRENT_CONTEXT: RefCell<Option<TraceContext>> = RefCell::new(None);
+}
+
+/// The handle of the async call tree of an actor, which can be dumped with [`Display`] while the
+/// actor is running.
+#[derive(Debug, Clone)]
+pub struct TraceContext(Arc<Mutex<TraceTree>>);
+
+impl TraceContext {
+    pub fn new(root_span: impl Into<SpanValue>) -> Self {
+        Self(Arc::new(Mutex::new(TraceTree::new(root_span.into()))))
+    }
+
+    /// Runs `future` as the root of this context. The traced futures awaited by it are recorded
+    /// as its descendants.
+    pub fn instrument<F: Future>(self, future: F) -> TraceRoot<F> {
+        TraceRoot {
+            inner: future,
+            context: self,
+        }
+    }
+
+    fn current() -> Option<Self> {
+        CURRENT_CONTEXT.with(|context| context.borrow().clone())
+    }
+}
+
+impl Display for TraceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.lock().fmt(f)
+    }
+}
+
+/// The future returned by [`TraceContext::instrument`].
+#[pin_project]
+pub struct TraceRoot<F> {
+    #[pin]
+    inner: F,
+    context: TraceContext,
+}
+
+impl<F: Future> Future for TraceRoot<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        this.context.0.lock().current = ROOT_NODE_ID;
+        let prev_context =
+            CURRENT_CONTEXT.with(|context| context.replace(Some(this.context.clone())));
+        let result = this.inner.poll(cx);
+        CURRENT_CONTEXT.with(|context| *context.borrow_mut() = prev_context);
+        result
+    }
+}
+
+/// The future returned by [`StackTrace::stack_trace`].
+#[pin_project(PinnedDrop)]
+pub struct StackTraced<F> {
+    #[pin]
+    inner: F,
+    span: SpanValue,
+    /// The node of this future in the tree, registered on the first poll.
+    node: Option<(TraceContext, NodeId)>,
+}
+
+impl<F: Future> Future for StackTraced<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        // Not polled by an actor, e.g., in tests.
+        let Some(context) = TraceContext::current() else {
+            return this.inner.poll(cx);
+        };
+
+        let parent = {
+            let mut tree = context.0.lock();
+            // The node may be gone with its ancestor if the future is moved around.
+            let registered = this
+                .node
+                .as_ref()
+                .map(|(_, id)| *id)
+                .filter(|id| tree.nodes.contains_key(id));
+            let id = match registered {
+                Some(id) => id,
+                None => {
+                    let id = tree.add_child(this.span.clone());
+                    *this.node = Some((context.clone(), id));
+                    id
+                }
+            };
+            std::mem::replace(&mut tree.current, id)
+        };
+
+        let result = this.inner.poll(cx);
+
+        let mut tree = context.0.lock();
+        tree.current = parent;
+        if result.is_ready() {
+            if let Some((_, id)) = this.node.take() {
+                tree.remove(id);
+            }
+        }
+        result
+    }
+}
+
+#[pinned_drop]
+impl<F> PinnedDrop for StackTraced<F> {
+    fn drop(self: Pin<&mut Self>) {
+        if let Some((context, id)) = self.project().node.take() {
+            context.0.lock().remove(id);
+        }
+    }
+}
+
+pub trait StackTrace: Future + Sized {
+    /// Records `span` in the async call tree of the actor while this future is pending.
+    fn stack_trace(self, span: impl Into<SpanValue>) -> StackTraced<Self> {
+        StackTraced {
+            inner: self,
+            span: span.into(),
+            node: None,
+        }
+    }
+}
+
+impl<F: Future> StackTrace for F {}
+
+#[cfg(test)]
+mod tests {
+    use futures::task::noop_waker_ref;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    fn spans(context: &TraceContext) -> Vec<String> {
+        context
+            .to_string()
+            .lines()
+            .map(|line| line.split(" [").next().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stack_trace() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let context = TraceContext::new("actor");
+        let mut future = Box::pin(context.clone().instrument(async move {
+            async { 1 }.stack_trace("ready").await;
+            async move { rx.stack_trace("recv").await.unwrap() }
+                .stack_trace("outer")
+                .await
+        }));
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(spans(&context), vec!["actor", "  outer", "    recv"]);
+
+        tx.send(()).unwrap();
+        assert!(future.as_mut().poll(&mut cx).is_ready());
+        assert_eq!(spans(&context), vec!["actor"]);
+    }
+
+    #[tokio::test]
+    async fn test_stack_trace_drop() {
+        let (_tx, rx) = oneshot::channel::<()>();
+        let context = TraceContext::new("actor");
+        let mut future = Box::pin(context.clone().instrument(async move {
+            let mut pending = Box::pin(async move { rx.stack_trace("recv").await });
+            futures::future::poll_fn(|cx| {
+                let _ = pending.as_mut().poll(cx);
+                Poll::Ready(())
+            })
+            .stack_trace("poll once")
+            .await;
+            // The pending future has been removed from the tree along with its finished parent.
+            drop(pending);
+            futures::future::pending::<()>()
+                .stack_trace("pending")
+                .await
+        }));
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(spans(&context), vec!["actor", "  pending"]);
+
+        drop(future);
+        assert_eq!(spans(&context), vec!["actor"]);
+    }
+}
@@ -12,36 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::channel::mpsc::{channel, Receiver};
+use futures::future::try_join_all;
 use itertools::Itertools;
 use madsim::collections::{HashMap, HashSet};
 use parking_lot::Mutex;
+use risingwave_common::catalog::TableId;
 use risingwave_common::config::StreamingConfig;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::compress::decompress_data;
+use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::{stream_plan, stream_service};
 use risingwave_rpc_client::ComputeClientPool;
-use risingwave_storage::{dispatch_state_store, StateStore, StateStoreImpl};
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
-use super::{unique_executor_id, unique_operator_id, CollectResult};
+use super::{
+    unique_branch_executor_id, unique_executor_id, unique_operator_id, CollectResult,
+    InflightBarrier,
+};
 use crate::executor::dispatch::*;
 use crate::executor::merge::RemoteInput;
 use crate::executor::monitor::StreamingMetrics;
 use crate::executor::*;
 use crate::from_proto::create_executor;
 use crate::task::{
-    ActorId, ConsumableChannelPair, SharedContext, StreamEnvironment, UpDownActorIds,
-    LOCAL_OUTPUT_CHANNEL_SIZE,
+    ActorId, BufferOccupancy, ConsumableChannelPair, MemoryManager, SharedContext,
+    StreamEnvironment, TraceContext, UpDownActorIds, LOCAL_OUTPUT_CHANNEL_SIZE,
 };
 
 #[cfg(test)]
@@ -51,6 +60,13 @@ lazy_static::lazy_static! {
 
 pub type ActorHandle = JoinHandle<()>;
 
+/// If a barrier is not collected within this duration, report the actors holding it up, and keep
+/// reporting at this interval until it's collected.
+const STUCK_BARRIER_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often to check whether the barriers are synced when draining the node.
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 pub struct LocalStreamManagerCore {
     /// Each processor runs in a future. Upon receiving a `Terminate` message, they will exit.
     /// `handles` store join handles of these futures, and therefore we could wait their
@@ -65,6 +81,23 @@ pub struct LocalStreamManagerCore {
     /// Stores all actor information, taken after actor built.
     actors: HashMap<ActorId, stream_plan::StreamActor>,
 
+    /// Describes the fragment and the plan of each built actor, for diagnostics.
+    actor_descriptions: HashMap<ActorId, String>,
+
+    /// The async call tree of each running actor, for diagnostics. Only recorded if
+    /// `async_stack_trace` is enabled in the config.
+    actor_traces: HashMap<ActorId, TraceContext>,
+
+    /// Epochs of the barriers collected since the last checkpoint barrier. Their data in the
+    /// shared buffer are synced together on the next checkpoint.
+    uncheckpointed_epochs: Vec<u64>,
+
+    /// The `curr` epoch of the latest barrier sent to the actors.
+    last_barrier_epoch: u64,
+
+    /// The latest epoch whose data are synced to the state store.
+    last_synced_epoch: u64,
+
     /// Mock source, `actor_id = 0`.
     /// TODO: remove this
     mock_source: ConsumableChannelPair,
@@ -83,6 +116,9 @@ pub struct LocalStreamManagerCore {
 
     /// Config of streaming engine
     pub(crate) config: StreamingConfig,
+
+    /// Tracks the cache usage of the executors and asks them to evict under memory pressure.
+    pub(crate) memory_manager: Arc<MemoryManager>,
 }
 
 /// `LocalStreamManager` manages all stream executors in this project.
@@ -111,6 +147,9 @@ pub struct ExecutorParams {
     /// Id of the actor.
     pub actor_id: ActorId,
 
+    /// Id of the fragment.
+    pub fragment_id: u32,
+
     /// Metrics
     pub executor_stats: Arc<StreamingMetrics>,
 
@@ -130,6 +169,7 @@ impl Debug for ExecutorParams {
             .field("op_info", &self.op_info)
             .field("input", &self.input.len())
             .field("actor_id", &self.actor_id)
+            .field("fragment_id", &self.fragment_id)
             .finish_non_exhaustive()
     }
 }
@@ -160,6 +200,10 @@ impl LocalStreamManager {
         Self::with_core(LocalStreamManagerCore::for_test())
     }
 
+    pub fn memory_manager(&self) -> Arc<MemoryManager> {
+        self.core.lock().memory_manager.clone()
+    }
+
     /// Broadcast a barrier to all senders. Returns a receiver which will get notified when this
     /// barrier is finished.
     fn send_barrier(
@@ -168,7 +212,8 @@ impl LocalStreamManager {
         actor_ids_to_send: impl IntoIterator<Item = ActorId>,
         actor_ids_to_collect: impl IntoIterator<Item = ActorId>,
     ) -> Result<oneshot::Receiver<CollectResult>> {
-        let core = self.core.lock();
+        let mut core = self.core.lock();
+        core.last_barrier_epoch = barrier.epoch.curr;
         let mut barrier_manager = core.context.lock_barrier_manager();
         let rx = barrier_manager
             .send_barrier(barrier, actor_ids_to_send, actor_ids_to_collect)?
@@ -184,32 +229,149 @@ impl LocalStreamManager {
         actor_ids_to_collect: impl IntoIterator<Item = ActorId>,
         need_sync: bool,
     ) -> Result<CollectResult> {
-        let rx = self.send_barrier(barrier, actor_ids_to_send, actor_ids_to_collect)?;
+        let mut rx = self.send_barrier(barrier, actor_ids_to_send, actor_ids_to_collect)?;
 
         // Wait for all actors finishing this barrier.
-        let mut collect_result = rx.await.unwrap();
+        let mut collect_result = loop {
+            match tokio::time::timeout(STUCK_BARRIER_REPORT_INTERVAL, &mut rx).await {
+                Ok(result) => break result.unwrap(),
+                Err(_) => {
+                    if let Some(inflight) = self.inflight_barrier() {
+                        warn!(
+                            "barrier {} is not collected after {:?}, remaining actors: [{}]",
+                            inflight.epoch,
+                            inflight.elapsed,
+                            inflight
+                                .remaining_actors
+                                .iter()
+                                .map(|&actor_id| format!(
+                                    "{} ({})",
+                                    actor_id,
+                                    self.describe_actor(actor_id).unwrap_or_default()
+                                ))
+                                .join(", ")
+                        );
+                    }
+                }
+            }
+        };
+        {
+            let core = self.core.lock();
+            for (actor_id, latency) in &collect_result.actor_latencies {
+                core.streaming_metrics
+                    .actor_barrier_latency
+                    .with_label_values(&[&actor_id.to_string()])
+                    .observe(latency.as_secs_f64());
+            }
+        }
 
-        // Sync states from shared buffer to S3 before telling meta service we've done.
+        // Sync states from shared buffer to S3 before telling meta service we've done. The epochs
+        // since the last checkpoint are committed together with this one.
         if need_sync {
+            let epochs = {
+                let mut core = self.core.lock();
+                let mut epochs = std::mem::take(&mut core.uncheckpointed_epochs);
+                epochs.push(barrier.epoch.prev);
+                epochs
+            };
             dispatch_state_store!(self.state_store(), store, {
-                match store.sync(Some(barrier.epoch.prev)).await {
-                    Ok(_) => {
-                        collect_result.synced_sstables =
-                            store.get_uncommitted_ssts(barrier.epoch.prev);
+                for epoch in epochs {
+                    match store.sync(Some(epoch)).await {
+                        Ok(_) => {
+                            collect_result
+                                .synced_sstables
+                                .extend(store.get_uncommitted_ssts(epoch));
+                        }
+                        // TODO: Handle sync failure by propagating it
+                        // back to global barrier manager
+                        Err(e) => panic!(
+                            "Failed to sync state store after receiving barrier {:?} due to {}",
+                            barrier, e
+                        ),
                     }
-                    // TODO: Handle sync failure by propagating it
-                    // back to global barrier manager
-                    Err(e) => panic!(
-                        "Failed to sync state store after receiving barrier {:?} due to {}",
-                        barrier, e
-                    ),
                 }
             });
+            self.core.lock().last_synced_epoch = barrier.epoch.prev;
+        } else {
+            self.core
+                .lock()
+                .uncheckpointed_epochs
+                .push(barrier.epoch.prev);
         }
 
         Ok(collect_result)
     }
 
+    /// Stops all the sources on this node from polling, e.g., before shutting down the node. Barriers
+    /// still flow through the actors.
+    pub fn stop_sources(&self) {
+        self.core
+            .lock()
+            .context
+            .sources_stopped
+            .store(true, Ordering::Relaxed);
+    }
+
+    /// Lets the sources stopped by [`Self::stop_sources`] poll again, e.g., when the shutdown is
+    /// aborted.
+    pub fn resume_sources(&self) {
+        self.core
+            .lock()
+            .context
+            .sources_stopped
+            .store(false, Ordering::Relaxed);
+    }
+
+    /// Stops the sources on this node, and waits until the data they have produced are committed,
+    /// i.e., until a checkpoint barrier following the data is synced and its epoch is committed.
+    /// Returns the committed epoch, or `None` if no actor runs on this node.
+    pub async fn drain(&self) -> Result<Option<u64>> {
+        self.stop_sources();
+        // Once stopped, the sources produce nothing after the barriers already sent, so their data
+        // all belong to the epochs up to the latest one.
+        let last_epoch = {
+            let core = self.core.lock();
+            if core.handles.is_empty() || core.last_barrier_epoch == INVALID_EPOCH {
+                return Ok(None);
+            }
+            core.last_barrier_epoch
+        };
+        let synced_epoch = loop {
+            let synced_epoch = self.core.lock().last_synced_epoch;
+            if synced_epoch >= last_epoch {
+                break synced_epoch;
+            }
+            tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+        };
+        dispatch_state_store!(self.state_store(), store, {
+            store.wait_epoch(synced_epoch).await?;
+        });
+        Ok(Some(synced_epoch))
+    }
+
+    /// Returns the barrier being collected on this node, if any.
+    pub fn inflight_barrier(&self) -> Option<InflightBarrier> {
+        let core = self.core.lock();
+        let barrier_manager = core.context.lock_barrier_manager();
+        barrier_manager.inflight_barrier()
+    }
+
+    /// Describes the fragment and the plan of a running actor.
+    pub fn describe_actor(&self, actor_id: ActorId) -> Option<String> {
+        self.core.lock().actor_descriptions.get(&actor_id).cloned()
+    }
+
+    /// Dumps the async call tree of each running actor, which is empty unless `async_stack_trace`
+    /// is enabled in the config.
+    pub fn actor_stack_traces(&self) -> BTreeMap<ActorId, String> {
+        self.core
+            .lock()
+            .actor_traces
+            .iter()
+            .map(|(&actor_id, trace)| (actor_id, trace.to_string()))
+            .collect()
+    }
+
     /// Broadcast a barrier to all senders. Returns immediately, and caller won't be notified when
     /// this barrier is finished.
     #[cfg(test)]
@@ -223,12 +385,29 @@ impl LocalStreamManager {
         Ok(())
     }
 
-    pub fn drop_actor(&self, actors: &[ActorId]) -> Result<()> {
-        let mut core = self.core.lock();
-        for id in actors {
-            core.drop_actor(*id);
-        }
+    /// Drops the actors, and deletes the states written by them at `epoch`, which should be the
+    /// epoch of the barrier that stopped them.
+    pub async fn drop_actor(&self, actors: &[ActorId], epoch: u64) -> Result<()> {
+        let (dropped_actors, state_store) = {
+            let mut core = self.core.lock();
+            let dropped_actors = actors
+                .iter()
+                .filter_map(|id| core.drop_actor(*id))
+                .collect_vec();
+            (dropped_actors, core.state_store.clone())
+        };
         tracing::debug!(actors = ?actors, "drop actors");
+
+        dispatch_state_store!(state_store, store, {
+            let mut keyspaces = vec![];
+            for actor in &dropped_actors {
+                collect_state_keyspaces(&store, actor.actor_id, actor.get_nodes()?, &mut keyspaces);
+            }
+            // Parallel actors of the same fragment share the keyspaces of tables.
+            keyspaces.sort_by(|a, b| a.key().cmp(b.key()));
+            keyspaces.dedup_by(|a, b| a.key() == b.key());
+            try_join_all(keyspaces.iter().map(|keyspace| keyspace.delete_all(epoch))).await?;
+        });
         Ok(())
     }
 
@@ -247,6 +426,7 @@ impl LocalStreamManager {
             epoch,
             mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
             span: tracing::Span::none(),
+            checkpoint: false,
         };
 
         self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect, false)
@@ -261,6 +441,13 @@ impl LocalStreamManager {
         core.context.take_receiver(&ids)
     }
 
+    /// Get the occupancy of the channel between the given actors, which should be decreased once
+    /// a message is taken out of the receiver.
+    pub fn buffer_occupancy(&self, ids: UpDownActorIds) -> BufferOccupancy {
+        let core = self.core.lock();
+        core.context.buffer_occupancy(ids)
+    }
+
     pub fn update_actors(
         &self,
         actors: &[stream_plan::StreamActor],
@@ -331,6 +518,47 @@ fn update_upstreams(context: &SharedContext, ids: &[UpDownActorIds]) {
         .count();
 }
 
+/// Collects the keyspaces written by the executors of an actor, which should be deleted once the
+/// actor is dropped. The keyspaces only read by the actor, e.g., the upstream tables of `Chain`
+/// and `Lookup`, are excluded.
+fn collect_state_keyspaces<S: StateStore>(
+    store: &S,
+    actor_id: ActorId,
+    node: &stream_plan::StreamNode,
+    keyspaces: &mut Vec<Keyspace<S>>,
+) {
+    let table_root = |table_id: u32| Keyspace::table_root(store.clone(), &TableId::new(table_id));
+    match node.node_body.as_ref().unwrap() {
+        NodeBody::Materialize(node) => {
+            keyspaces.push(Keyspace::table_root(
+                store.clone(),
+                &TableId::from(&node.table_ref_id),
+            ));
+        }
+        NodeBody::Arrange(node) => keyspaces.push(table_root(node.table_id)),
+        NodeBody::HashAgg(node) => {
+            keyspaces.extend(node.table_ids.iter().map(|id| table_root(*id)));
+        }
+        NodeBody::GlobalSimpleAgg(node) => {
+            keyspaces.extend(node.table_ids.iter().map(|id| table_root(*id)));
+        }
+        NodeBody::HashJoin(node) => {
+            keyspaces.push(table_root(node.left_table_id));
+            keyspaces.push(table_root(node.right_table_id));
+        }
+        NodeBody::Source(_) | NodeBody::TopN(_) | NodeBody::AppendOnlyTopN(_) => {
+            keyspaces.push(Keyspace::executor_root(
+                store.clone(),
+                unique_executor_id(actor_id, node.operator_id),
+            ));
+        }
+        _ => {}
+    }
+    for input in &node.input {
+        collect_state_keyspaces(store, actor_id, input, keyspaces);
+    }
+}
+
 impl LocalStreamManagerCore {
     fn new(
         addr: HostAddr,
@@ -349,17 +577,24 @@ impl LocalStreamManagerCore {
         config: StreamingConfig,
     ) -> Self {
         let (tx, rx) = channel(LOCAL_OUTPUT_CHANNEL_SIZE);
+        let memory_manager = Arc::new(MemoryManager::new(config.max_cached_entries));
 
         Self {
             handles: HashMap::new(),
             context: Arc::new(context),
             actor_infos: HashMap::new(),
             actors: HashMap::new(),
+            actor_descriptions: HashMap::new(),
+            actor_traces: HashMap::new(),
+            uncheckpointed_epochs: vec![],
+            last_barrier_epoch: INVALID_EPOCH,
+            last_synced_epoch: INVALID_EPOCH,
             mock_source: (Some(tx), Some(rx)),
             state_store,
             streaming_metrics,
             compute_client_pool: ComputeClientPool::new(u64::MAX),
             config,
+            memory_manager,
         }
     }
 
@@ -454,28 +689,49 @@ impl LocalStreamManagerCore {
     #[allow(clippy::too_many_arguments)]
     fn create_nodes_inner(
         &mut self,
+        table_id: u32,
         fragment_id: u32,
         actor_id: ActorId,
         node: &stream_plan::StreamNode,
         input_pos: usize,
+        downstream_executor_id: Option<u64>,
         env: StreamEnvironment,
         store: impl StateStore,
         actor_context: &ActorContextRef,
         vnode_bitmap: Rc<Vec<u8>>,
     ) -> Result<BoxedExecutor> {
         let op_info = node.get_identity().clone();
+        // We assume that the operator_id of different instances from the same RelNode will be the
+        // same.
+        let executor_id = unique_executor_id(actor_id, node.operator_id);
+
+        // The operator under a local exchange is created by the exchange for each of its branches,
+        // so the exchange takes the input of the operator instead.
+        let input_nodes = match node.get_node_body()? {
+            NodeBody::LocalExchange(_) => {
+                assert_eq!(
+                    node.input.len(),
+                    1,
+                    "local exchange has exactly one operator"
+                );
+                &node.input[0].input
+            }
+            _ => &node.input,
+        };
+
         // Create the input executor before creating itself
         // The node with no input must be a `MergeNode`
-        let input: Vec<_> = node
-            .input
+        let input: Vec<_> = input_nodes
             .iter()
             .enumerate()
             .map(|(input_pos, input)| {
                 self.create_nodes_inner(
+                    table_id,
                     fragment_id,
                     actor_id,
                     input,
                     input_pos,
+                    Some(executor_id),
                     env.clone(),
                     store.clone(),
                     actor_context,
@@ -490,9 +746,6 @@ impl LocalStreamManagerCore {
             .map(|idx| *idx as usize)
             .collect::<Vec<_>>();
 
-        // We assume that the operator_id of different instances from the same RelNode will be the
-        // same.
-        let executor_id = unique_executor_id(actor_id, node.operator_id);
         let operator_id = unique_operator_id(fragment_id, node.operator_id);
 
         let executor_params = ExecutorParams {
@@ -503,24 +756,61 @@ impl LocalStreamManagerCore {
             op_info,
             input,
             actor_id,
+            fragment_id,
             executor_stats: self.streaming_metrics.clone(),
             actor_context: actor_context.clone(),
             vnode_bitmap,
         };
 
         let executor = create_executor(executor_params, self, node, store)?;
-        let executor = Self::wrap_executor_for_debug(
+        let executor = self.wrap_executor_for_debug(
             executor,
+            table_id,
+            fragment_id,
             actor_id,
+            executor_id,
+            downstream_executor_id,
             input_pos,
-            self.streaming_metrics.clone(),
         );
         Ok(executor)
     }
 
+    /// Create the operator `node` upon `input` in place of its own inputs, for the `branch`-th
+    /// branch of a local exchange. Each branch gets an executor id of its own, e.g., to register
+    /// its cache in the memory manager. The other parameters follow the ones of `parent`.
+    pub(crate) fn create_node_with_input(
+        &mut self,
+        node: &stream_plan::StreamNode,
+        branch: usize,
+        input: BoxedExecutor,
+        parent: &ExecutorParams,
+        store: impl StateStore,
+    ) -> Result<BoxedExecutor> {
+        let executor_params = ExecutorParams {
+            env: parent.env.clone(),
+            pk_indices: node
+                .get_pk_indices()
+                .iter()
+                .map(|idx| *idx as usize)
+                .collect(),
+            executor_id: unique_branch_executor_id(parent.actor_id, node.operator_id, branch),
+            operator_id: unique_operator_id(parent.fragment_id, node.operator_id),
+            op_info: node.get_identity().clone(),
+            input: vec![input],
+            actor_id: parent.actor_id,
+            fragment_id: parent.fragment_id,
+            executor_stats: parent.executor_stats.clone(),
+            actor_context: parent.actor_context.clone(),
+            vnode_bitmap: Rc::clone(&parent.vnode_bitmap),
+        };
+        create_executor(executor_params, self, node, store)
+    }
+
     /// Create a chain(tree) of nodes and return the head executor.
+    #[allow(clippy::too_many_arguments)]
     fn create_nodes(
         &mut self,
+        table_id: u32,
         fragment_id: u32,
         actor_id: ActorId,
         node: &stream_plan::StreamNode,
@@ -530,10 +820,12 @@ impl LocalStreamManagerCore {
     ) -> Result<BoxedExecutor> {
         dispatch_state_store!(self.state_store.clone(), store, {
             self.create_nodes_inner(
+                table_id,
                 fragment_id,
                 actor_id,
                 node,
                 0,
+                None,
                 env,
                 store,
                 actor_context,
@@ -542,13 +834,34 @@ impl LocalStreamManagerCore {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn wrap_executor_for_debug(
+        &self,
         executor: BoxedExecutor,
+        table_id: u32,
+        fragment_id: u32,
         actor_id: ActorId,
+        executor_id: u64,
+        downstream_executor_id: Option<u64>,
         input_pos: usize,
-        streaming_metrics: Arc<StreamingMetrics>,
     ) -> BoxedExecutor {
-        DebugExecutor::new(executor, input_pos, actor_id, streaming_metrics).boxed()
+        let labels = |executor_id: u64| ExecutorLabels {
+            table_id: table_id.to_string(),
+            fragment_id: fragment_id.to_string(),
+            actor_id: actor_id.to_string(),
+            executor_id: format!("{:X}", executor_id),
+        };
+        DebugExecutor::new(
+            executor,
+            input_pos,
+            actor_id,
+            labels(executor_id),
+            downstream_executor_id.map(labels),
+            self.memory_manager.cache_usage(executor_id),
+            self.streaming_metrics.clone(),
+            self.config.async_stack_trace,
+        )
+        .boxed()
     }
 
     pub(crate) fn get_receive_message(
@@ -616,8 +929,17 @@ impl LocalStreamManagerCore {
             let actor_id = *actor_id;
             let actor = self.actors.remove(&actor_id).unwrap();
             let actor_context = Arc::new(Mutex::new(ActorContext::default()));
+            self.actor_descriptions.insert(
+                actor_id,
+                format!(
+                    "fragment {}: {}",
+                    actor.fragment_id,
+                    actor.get_nodes()?.identity
+                ),
+            );
 
             let executor = self.create_nodes(
+                actor.table_id,
                 actor.fragment_id,
                 actor_id,
                 actor.get_nodes()?,
@@ -634,13 +956,18 @@ impl LocalStreamManagerCore {
                 self.streaming_metrics.clone(),
                 actor_context,
             );
-            self.handles.insert(
-                actor_id,
-                tokio::spawn(async move {
-                    // unwrap the actor result to panic on error
-                    actor.run().await.expect("actor failed");
-                }),
-            );
+            let actor = async move {
+                // unwrap the actor result to panic on error
+                actor.run().await.expect("actor failed");
+            };
+            let handle = if self.config.async_stack_trace {
+                let trace = TraceContext::new(format!("Actor {}", actor_id));
+                self.actor_traces.insert(actor_id, trace.clone());
+                tokio::spawn(trace.instrument(actor))
+            } else {
+                tokio::spawn(actor)
+            };
+            self.handles.insert(actor_id, handle);
         }
 
         Ok(())
@@ -683,14 +1010,16 @@ impl LocalStreamManagerCore {
 
     /// `drop_actor` is invoked by meta node via RPC once the stop barrier arrives at the
     /// sink. All the actors in the actors should stop themselves before this method is invoked.
-    fn drop_actor(&mut self, actor_id: ActorId) {
+    fn drop_actor(&mut self, actor_id: ActorId) -> Option<stream_plan::StreamActor> {
         let handle = self.handles.remove(&actor_id).unwrap();
         self.context.retain(|&(up_id, _)| up_id != actor_id);
 
         self.actor_infos.remove(&actor_id);
-        self.actors.remove(&actor_id);
+        self.actor_descriptions.remove(&actor_id);
+        self.actor_traces.remove(&actor_id);
         // Task should have already stopped when this method is invoked.
         handle.abort();
+        self.actors.remove(&actor_id)
     }
 
     /// `drop_all_actors` is invoked by meta node via RPC once the stop barrier arrives at all the
@@ -703,6 +1032,9 @@ impl LocalStreamManagerCore {
             handle.abort();
         }
         self.actor_infos.clear();
+        self.actor_descriptions.clear();
+        self.actor_traces.clear();
+        self.uncheckpointed_epochs.clear();
     }
 
     fn build_channel_for_chain_node(
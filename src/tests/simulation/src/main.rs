@@ -0,0 +1,48 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic simulation test of streaming recovery. The meta node, the compute nodes and the
+//! frontend run in a single simulated runtime, where the time, the network and the randomness are
+//! all controlled by the seed. While data is inserted into a table, compute nodes are killed and
+//! restarted, RPCs are delayed and uploads to the object store fail. The test then checks that the
+//! materialized views on the table match the results of the same queries run in batch.
+//!
+//! It only runs in simulation mode:
+//!
+//! ```shell
+//! ./risedev ssim --seed 42
+//! ```
+
+#![cfg_attr(coverage, feature(no_coverage))]
+
+#[cfg(madsim)]
+mod recovery;
+
+#[cfg(not(madsim))]
+fn main() {
+    eprintln!("The simulation test must be built with `RUSTFLAGS=\"--cfg madsim\"`.");
+    std::process::exit(1);
+}
+
+#[cfg(madsim)]
+fn main() {
+    use clap::Parser;
+
+    let args = recovery::Args::parse();
+    let mut config = madsim::Config::default();
+    config.net.send_latency = std::time::Duration::from_millis(1)
+        ..std::time::Duration::from_millis(args.max_rpc_delay_ms.max(2));
+    let runtime = madsim::runtime::Runtime::with_seed_and_config(args.seed, config);
+    runtime.block_on(recovery::run(args));
+}
@@ -0,0 +1,266 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
+use futures::StreamExt;
+use itertools::Itertools;
+use madsim::rand::{thread_rng, Rng};
+use madsim::runtime::Handle;
+use madsim::task::NodeId;
+use madsim::time::sleep;
+use pgwire::pg_server::{BoxedError, Session, SessionManager};
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
+use risingwave_frontend::session::{SessionImpl, SessionManagerImpl};
+
+/// Shared by all the nodes, as if it were an S3 bucket.
+const STATE_STORE: &str = "hummock+memory-shared";
+const META_ADDRESS: &str = "http://192.168.1.1:5690";
+
+/// The failpoint of the uploads to the in-memory object store.
+const UPLOAD_ERROR: &str = "mem_upload_err";
+
+/// The times to retry a statement failing while the cluster is recovering.
+const MAX_RETRIES: usize = 30;
+
+/// A materialized view to verify.
+struct View {
+    name: &'static str,
+    definition: &'static str,
+    /// Selects all the rows of the view in order.
+    select: &'static str,
+    /// Computes the same rows as `select` in batch.
+    batch_select: &'static str,
+}
+
+const VIEWS: [View; 2] = [
+    View {
+        name: "mv_agg",
+        definition: "SELECT k, COUNT(*) AS cnt, SUM(v) AS total FROM t GROUP BY k",
+        select: "SELECT k, cnt, total FROM mv_agg ORDER BY k",
+        batch_select: "SELECT k, COUNT(*), SUM(v) FROM t GROUP BY k ORDER BY k",
+    },
+    View {
+        name: "mv_filter",
+        definition: "SELECT k, v FROM t WHERE v % 3 = 0",
+        select: "SELECT k, v FROM mv_filter ORDER BY k, v",
+        batch_select: "SELECT k, v FROM t WHERE v % 3 = 0 ORDER BY k, v",
+    },
+];
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The seed of the simulation. A failure is reproduced by running with the same seed.
+    #[clap(long, default_value_t = 1)]
+    pub seed: u64,
+
+    #[clap(long, default_value_t = 3)]
+    compute_nodes: u8,
+
+    /// The number of `INSERT`s into the table.
+    #[clap(long, default_value_t = 50)]
+    rounds: usize,
+
+    #[clap(long, default_value_t = 100)]
+    rows_per_round: usize,
+
+    /// Kill a random compute node every few seconds, and restart it a few seconds later.
+    #[clap(long)]
+    kill_compute: bool,
+
+    /// The max latency of sending a message over the network.
+    #[clap(long, default_value_t = 10)]
+    pub max_rpc_delay_ms: u64,
+
+    /// The percentage of the uploads to the object store that fail.
+    #[clap(long, default_value_t = 0)]
+    upload_error_percent: u32,
+}
+
+pub async fn run(args: Args) {
+    let handle = Handle::current();
+    println!("seed = {}, {:?}", args.seed, args);
+
+    handle
+        .create_node()
+        .name("meta")
+        .ip([192, 168, 1, 1].into())
+        .init(|| async {
+            let opts = risingwave_meta::MetaNodeOpts::parse_from([
+                "meta-node",
+                "--host",
+                "0.0.0.0:5690",
+                "--state-store",
+                STATE_STORE,
+                "--max-heartbeat-interval",
+                "3000",
+            ]);
+            risingwave_meta::start(opts).await
+        })
+        .build();
+    // Wait for the meta service to be ready.
+    sleep(Duration::from_secs(1)).await;
+
+    let compute_nodes = (1..=args.compute_nodes)
+        .map(|i| {
+            handle
+                .create_node()
+                .name(format!("compute-{}", i))
+                .ip([192, 168, 3, i].into())
+                .init(move || async move {
+                    let client_address = format!("192.168.3.{}:5688", i);
+                    let opts = risingwave_compute::ComputeNodeOpts::parse_from([
+                        "compute-node",
+                        "--host",
+                        "0.0.0.0:5688",
+                        "--client-address",
+                        &client_address,
+                        "--meta-address",
+                        META_ADDRESS,
+                        "--state-store",
+                        STATE_STORE,
+                    ]);
+                    risingwave_compute::start(opts).await
+                })
+                .build()
+                .id()
+        })
+        .collect_vec();
+    sleep(Duration::from_secs(1)).await;
+
+    // The workload runs in the frontend node, on the sessions of its own.
+    handle
+        .create_node()
+        .name("frontend")
+        .ip([192, 168, 2, 1].into())
+        .build()
+        .spawn(workload(args, compute_nodes))
+        .await
+        .unwrap();
+}
+
+async fn workload(args: Args, compute_nodes: Vec<NodeId>) {
+    let opts = risingwave_frontend::FrontendOpts::parse_from([
+        "frontend-node",
+        "--host",
+        "0.0.0.0:4566",
+        "--client-address",
+        "192.168.2.1:4566",
+        "--meta-addr",
+        META_ADDRESS,
+    ]);
+    let session_mgr = SessionManagerImpl::new(&opts).await.unwrap();
+    let session = session_mgr
+        .connect(DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER)
+        .unwrap();
+
+    run_with_retry(&session, "CREATE TABLE t (k INT, v INT)").await;
+    for view in &VIEWS {
+        run_with_retry(
+            &session,
+            &format!(
+                "CREATE MATERIALIZED VIEW {} AS {}",
+                view.name, view.definition
+            ),
+        )
+        .await;
+    }
+
+    if args.upload_error_percent > 0 {
+        fail::cfg(
+            UPLOAD_ERROR,
+            &format!("{}%return", args.upload_error_percent),
+        )
+        .unwrap();
+    }
+    let stop_chaos = Arc::new(AtomicBool::new(false));
+    let chaos = args
+        .kill_compute
+        .then(|| madsim::task::spawn(kill_compute_nodes(compute_nodes, stop_chaos.clone())));
+
+    let mut next_value = 0;
+    for _ in 0..args.rounds {
+        let rows = (0..args.rows_per_round)
+            .map(|_| {
+                next_value += 1;
+                format!("({}, {})", thread_rng().gen_range(0..10), next_value)
+            })
+            .join(", ");
+        // A retried `INSERT` may insert the rows twice, which doesn't matter as the views are
+        // checked against the table.
+        run_with_retry(&session, &format!("INSERT INTO t VALUES {}", rows)).await;
+    }
+
+    stop_chaos.store(true, Ordering::Relaxed);
+    if let Some(chaos) = chaos {
+        chaos.await.unwrap();
+    }
+    fail::remove(UPLOAD_ERROR);
+
+    run_with_retry(&session, "FLUSH").await;
+    for view in &VIEWS {
+        let rows = run_with_retry(&session, view.select).await;
+        let batch_rows = run_with_retry(&session, view.batch_select).await;
+        assert_eq!(
+            rows, batch_rows,
+            "the rows of {} differ from the batch query",
+            view.name
+        );
+        println!("{}: {} rows verified", view.name, rows.len());
+    }
+}
+
+/// Kills a random compute node and restarts it a few seconds later, until `stop` is set.
+async fn kill_compute_nodes(compute_nodes: Vec<NodeId>, stop: Arc<AtomicBool>) {
+    let handle = Handle::current();
+    while !stop.load(Ordering::Relaxed) {
+        sleep(Duration::from_millis(thread_rng().gen_range(1000..5000))).await;
+        let node = compute_nodes[thread_rng().gen_range(0..compute_nodes.len())];
+        println!("kill compute node {}", node);
+        handle.kill(node);
+        sleep(Duration::from_millis(thread_rng().gen_range(500..3000))).await;
+        println!("restart compute node {}", node);
+        handle.restart(node);
+    }
+}
+
+type Rows = Vec<Vec<Option<String>>>;
+
+async fn run_statement(session: &Arc<SessionImpl>, sql: &str) -> Result<Rows, BoxedError> {
+    let mut rsp = session.clone().run_statement(sql).await?;
+    let mut rows = vec![];
+    while let Some(chunk) = rsp.values_stream().next().await {
+        rows.extend(chunk?.into_iter().map(|row| row.values().to_vec()));
+    }
+    Ok(rows)
+}
+
+/// Runs `sql` until it succeeds, as it fails while the cluster is recovering.
+async fn run_with_retry(session: &Arc<SessionImpl>, sql: &str) -> Rows {
+    let mut retries = 0;
+    loop {
+        match run_statement(session, sql).await {
+            Ok(rows) => return rows,
+            Err(e) if retries < MAX_RETRIES => {
+                println!("failed to run statement, retrying: {}", e);
+                retries += 1;
+                sleep(Duration::from_secs(1)).await;
+            }
+            Err(e) => panic!("failed to run `{}` after {} retries: {}", sql, retries, e),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use clap::Parser;
+use pgwire::pg_server::SessionManager;
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
+use risingwave_frontend::session::{SessionImpl, SessionManagerImpl};
+
+use crate::opts::Opts;
+
+/// A meta node, a compute node and a frontend running in this process. All the states are kept in
+/// memory, so every run starts with an empty cluster.
+pub(crate) struct EmbeddedCluster {
+    session_mgr: SessionManagerImpl,
+}
+
+impl EmbeddedCluster {
+    pub(crate) async fn start(opts: &Opts) -> anyhow::Result<Self> {
+        let meta_host = format!("127.0.0.1:{}", opts.meta_port);
+        let meta_addr = format!("http://{}", meta_host);
+
+        let meta_opts = risingwave_meta::MetaNodeOpts::parse_from([
+            "meta-node",
+            "--host",
+            &meta_host,
+            "--backend",
+            "mem",
+            "--state-store",
+            "hummock+memory",
+        ]);
+        tokio::spawn(risingwave_meta::start(meta_opts));
+        // Wait for the meta service to be ready.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let compute_host = format!("127.0.0.1:{}", opts.compute_port);
+        let compute_opts = risingwave_compute::ComputeNodeOpts::parse_from([
+            "compute-node",
+            "--host",
+            &compute_host,
+            "--meta-address",
+            &meta_addr,
+            "--state-store",
+            "hummock+memory",
+        ]);
+        tokio::spawn(risingwave_compute::start(compute_opts));
+        // Wait for the compute node to register itself, or the first DDL will find no worker.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let frontend_opts = risingwave_frontend::FrontendOpts::parse_from([
+            "frontend-node",
+            "--meta-addr",
+            &meta_addr,
+        ]);
+        let session_mgr = SessionManagerImpl::new(&frontend_opts).await?;
+        Ok(Self { session_mgr })
+    }
+
+    /// Opens a new session of the default user on the default database.
+    pub(crate) fn session(&self) -> anyhow::Result<Arc<SessionImpl>> {
+        self.session_mgr
+            .connect(DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER)
+            .map_err(|e| anyhow!("failed to connect to the embedded cluster: {}", e))
+    }
+}
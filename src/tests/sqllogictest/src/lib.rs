@@ -0,0 +1,96 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs sqllogictest scripts against a cluster embedded in this process, which consists of a meta
+//! node with the in-memory backend, a compute node with the in-memory state store, and a frontend
+//! serving the sessions of the runner. No `risedev` cluster is required.
+
+#![warn(clippy::dbg_macro)]
+#![warn(clippy::disallowed_methods)]
+#![warn(clippy::doc_markdown)]
+#![warn(clippy::explicit_into_iter_loop)]
+#![warn(clippy::explicit_iter_loop)]
+#![warn(clippy::inconsistent_struct_constructor)]
+#![warn(clippy::map_flatten)]
+#![warn(clippy::no_effect_underscore_binding)]
+#![warn(clippy::await_holding_lock)]
+#![deny(unused_must_use)]
+#![deny(rustdoc::broken_intra_doc_links)]
+
+mod cluster;
+mod opts;
+mod parser;
+mod runner;
+
+use clap::Parser;
+use itertools::Itertools;
+
+use crate::cluster::EmbeddedCluster;
+use crate::opts::Opts;
+use crate::runner::Runner;
+
+/// Exit code of this process
+pub async fn sqllogictest_main() -> i32 {
+    let opts = Opts::parse();
+
+    match run(opts).await {
+        Ok(true) => {
+            println!("All sqllogictest files passed!");
+            0
+        }
+        Ok(false) => 1,
+        Err(e) => {
+            eprintln!("Failed to run sqllogictest: {:?}", e);
+            1
+        }
+    }
+}
+
+/// Runs all the files matching the patterns, and returns whether all of them passed.
+async fn run(opts: Opts) -> anyhow::Result<bool> {
+    let mut files = vec![];
+    for pattern in &opts.files {
+        for path in glob::glob(pattern)? {
+            files.push(path?);
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("no file matches {:?}", opts.files);
+    }
+
+    let cluster = EmbeddedCluster::start(&opts).await?;
+
+    let mut failed = vec![];
+    for file in files {
+        println!("Running {}", file.display());
+        // Each file runs in a new session, so that session variables don't leak across files.
+        let mut runner = Runner::new(cluster.session()?, opts.verbose);
+        if let Err(e) = runner.run_file(&file).await {
+            eprintln!("{:?}\n", e);
+            failed.push(file);
+            if opts.fail_fast {
+                break;
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        eprintln!(
+            "{} file(s) failed:\n{}",
+            failed.len(),
+            failed.iter().map(|file| file.display()).join("\n")
+        );
+    }
+    Ok(failed.is_empty())
+}
@@ -0,0 +1,39 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+#[clap(about = "Run sqllogictest scripts against an embedded RisingWave cluster")]
+pub(crate) struct Opts {
+    /// Glob patterns of the files to run, e.g. `./e2e_test/streaming/**/*.slt`.
+    #[clap(required = true)]
+    pub(crate) files: Vec<String>,
+
+    /// The port of the embedded meta node.
+    #[clap(long, default_value_t = 15690)]
+    pub(crate) meta_port: u16,
+
+    /// The port of the embedded compute node.
+    #[clap(long, default_value_t = 15688)]
+    pub(crate) compute_port: u16,
+
+    /// Stop at the first failed file.
+    #[clap(long)]
+    pub(crate) fail_fast: bool,
+
+    /// Print every record before running it.
+    #[clap(long, short)]
+    pub(crate) verbose: bool,
+}
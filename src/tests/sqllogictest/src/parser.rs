@@ -0,0 +1,305 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser of the sqllogictest scripts. Only the directives used by our e2e tests are supported:
+//!
+//! ```text
+//! # comment
+//! statement ok|error
+//! <sql>
+//!
+//! query <column types> [nosort|rowsort|valuesort]
+//! <sql>
+//! ----
+//! <expected rows>
+//!
+//! include <glob pattern relative to the current file>
+//! sleep <n>s|<n>ms
+//! halt
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+
+/// Where a record comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Location {
+    pub(crate) file: PathBuf,
+    pub(crate) line: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// How the results of a query are sorted before being compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortMode {
+    /// Compare the rows in the order they are returned.
+    NoSort,
+    /// Sort the rows.
+    RowSort,
+    /// Sort all the values regardless of the rows they belong to.
+    ValueSort,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Record {
+    /// Run a statement, which is expected to succeed or fail.
+    Statement {
+        loc: Location,
+        sql: String,
+        expect_error: bool,
+    },
+    /// Run a query and compare its results to the expected ones.
+    Query {
+        loc: Location,
+        sql: String,
+        sort_mode: SortMode,
+        expected_results: Vec<String>,
+    },
+    /// Run the records of the matched files. Expanded by [`parse_file`].
+    Include {
+        loc: Location,
+        pattern: String,
+    },
+    Sleep {
+        loc: Location,
+        duration: Duration,
+    },
+    /// Skip the rest of the file.
+    Halt {
+        loc: Location,
+    },
+}
+
+/// Parses a file and the files it includes.
+pub(crate) fn parse_file(path: &Path) -> anyhow::Result<Vec<Record>> {
+    let script = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut records = vec![];
+    for record in parse(&script, path)? {
+        match record {
+            Record::Include { loc, pattern } => {
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                let pattern = dir.join(pattern);
+                let pattern = pattern.to_str().ok_or_else(|| anyhow!("invalid path"))?;
+                let mut matched = false;
+                for included in glob::glob(pattern).with_context(|| loc.to_string())? {
+                    records.extend(parse_file(&included?)?);
+                    matched = true;
+                }
+                if !matched {
+                    bail!("{}: no file matches {}", loc, pattern);
+                }
+            }
+            record => records.push(record),
+        }
+    }
+    Ok(records)
+}
+
+/// Parses a script without expanding the includes. `file` is only used for the locations.
+pub(crate) fn parse(script: &str, file: &Path) -> anyhow::Result<Vec<Record>> {
+    let mut lines = script.lines().enumerate().peekable();
+    let mut records = vec![];
+
+    while let Some((idx, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let loc = Location {
+            file: file.to_path_buf(),
+            line: idx + 1,
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        // Consumes the lines until an empty one, or one equal to `until`.
+        let mut take_block = |until: Option<&str>| {
+            let mut block = vec![];
+            while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+                if Some(line.trim()) == until {
+                    return (block, true);
+                }
+                block.push(line);
+            }
+            (block, false)
+        };
+
+        let record = match tokens[0] {
+            "statement" => {
+                let expect_error = match tokens.get(1) {
+                    Some(&"ok") => false,
+                    Some(&"error") => true,
+                    _ => bail!("{}: expected `statement ok` or `statement error`", loc),
+                };
+                let (sql, _) = take_block(None);
+                if sql.is_empty() {
+                    bail!("{}: missing the statement", loc);
+                }
+                Record::Statement {
+                    loc,
+                    sql: sql.join("\n"),
+                    expect_error,
+                }
+            }
+            "query" => {
+                if tokens.len() < 2 {
+                    bail!("{}: missing the column types of the query", loc);
+                }
+                let sort_mode = match tokens.get(2) {
+                    None | Some(&"nosort") => SortMode::NoSort,
+                    Some(&"rowsort") => SortMode::RowSort,
+                    Some(&"valuesort") => SortMode::ValueSort,
+                    Some(mode) => bail!("{}: unknown sort mode {}", loc, mode),
+                };
+                let (sql, has_results) = take_block(Some("----"));
+                if sql.is_empty() {
+                    bail!("{}: missing the query", loc);
+                }
+                let expected_results = if has_results {
+                    take_block(None).0.into_iter().map(String::from).collect()
+                } else {
+                    vec![]
+                };
+                Record::Query {
+                    loc,
+                    sql: sql.join("\n"),
+                    sort_mode,
+                    expected_results,
+                }
+            }
+            "include" => match tokens.get(1) {
+                Some(pattern) => Record::Include {
+                    loc,
+                    pattern: pattern.to_string(),
+                },
+                None => bail!("{}: missing the file to include", loc),
+            },
+            "sleep" => {
+                let duration = tokens
+                    .get(1)
+                    .and_then(|d| parse_duration(d))
+                    .ok_or_else(|| anyhow!("{}: expected `sleep <n>s` or `sleep <n>ms`", loc))?;
+                Record::Sleep { loc, duration }
+            }
+            "halt" => Record::Halt { loc },
+            directive => bail!("{}: unknown directive {}", loc, directive),
+        };
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    if let Some(ms) = s.strip_suffix("ms") {
+        ms.parse().ok().map(Duration::from_millis)
+    } else if let Some(secs) = s.strip_suffix('s') {
+        secs.parse().ok().map(Duration::from_secs)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let script = r#"
+# create a table
+statement ok
+create table t (v1 int, v2 varchar);
+
+statement error
+create table t (v1 int);
+
+query IT rowsort
+select v1, v2
+from t;
+----
+1 a
+2 NULL
+
+query I
+select count(*) from t where v1 > 10;
+----
+0
+
+include ./tpch/*.slt.part
+sleep 500ms
+halt
+"#;
+        let file = Path::new("test.slt");
+        let loc = |line| Location {
+            file: file.to_path_buf(),
+            line,
+        };
+        let records = parse(script, file).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                Record::Statement {
+                    loc: loc(3),
+                    sql: "create table t (v1 int, v2 varchar);".to_string(),
+                    expect_error: false,
+                },
+                Record::Statement {
+                    loc: loc(6),
+                    sql: "create table t (v1 int);".to_string(),
+                    expect_error: true,
+                },
+                Record::Query {
+                    loc: loc(9),
+                    sql: "select v1, v2\nfrom t;".to_string(),
+                    sort_mode: SortMode::RowSort,
+                    expected_results: vec!["1 a".to_string(), "2 NULL".to_string()],
+                },
+                Record::Query {
+                    loc: loc(16),
+                    sql: "select count(*) from t where v1 > 10;".to_string(),
+                    sort_mode: SortMode::NoSort,
+                    expected_results: vec!["0".to_string()],
+                },
+                Record::Include {
+                    loc: loc(21),
+                    pattern: "./tpch/*.slt.part".to_string(),
+                },
+                Record::Sleep {
+                    loc: loc(22),
+                    duration: Duration::from_millis(500),
+                },
+                Record::Halt { loc: loc(23) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let file = Path::new("test.slt");
+        assert!(parse("statement maybe\nselect 1;", file).is_err());
+        assert!(parse("query\nselect 1;", file).is_err());
+        assert!(parse("query I unsorted\nselect 1;", file).is_err());
+        assert!(parse("sleep forever", file).is_err());
+        assert!(parse("select 1;", file).is_err());
+    }
+}
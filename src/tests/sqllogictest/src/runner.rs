@@ -0,0 +1,138 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use futures::StreamExt;
+use itertools::Itertools;
+use pgwire::pg_server::Session;
+use risingwave_frontend::session::SessionImpl;
+
+use crate::parser::{parse_file, Record, SortMode};
+
+/// Runs the records of the files on a session.
+pub(crate) struct Runner {
+    session: Arc<SessionImpl>,
+    verbose: bool,
+}
+
+impl Runner {
+    pub(crate) fn new(session: Arc<SessionImpl>, verbose: bool) -> Self {
+        Self { session, verbose }
+    }
+
+    /// Runs a file, and returns the error of the first failed record.
+    pub(crate) async fn run_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        for record in parse_file(path)? {
+            if self.verbose {
+                println!("{:?}", record);
+            }
+            match record {
+                Record::Statement {
+                    loc,
+                    sql,
+                    expect_error,
+                } => match (self.run_sql(&sql).await, expect_error) {
+                    (Ok(_), true) => bail!("{}: statement is expected to fail\n{}", loc, sql),
+                    (Err(e), false) => bail!("{}: statement failed: {}\n{}", loc, e, sql),
+                    _ => {}
+                },
+                Record::Query {
+                    loc,
+                    sql,
+                    sort_mode,
+                    expected_results,
+                } => {
+                    let results = self
+                        .run_sql(&sql)
+                        .await
+                        .map_err(|e| anyhow!("{}: query failed: {}\n{}", loc, e, sql))?;
+                    let actual = normalize(&results, sort_mode);
+                    let expected = normalize(&expected_results, sort_mode);
+                    if actual != expected {
+                        bail!(
+                            "{}: query result mismatch\n{}\n[Expected]\n{}\n[Actual]\n{}",
+                            loc,
+                            sql,
+                            expected.join("\n"),
+                            actual.join("\n")
+                        );
+                    }
+                }
+                Record::Sleep { duration, .. } => tokio::time::sleep(duration).await,
+                Record::Halt { .. } => break,
+                Record::Include { .. } => unreachable!("includes are expanded by the parser"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs a SQL and formats the returned rows in the way of sqllogictest, i.e., one line for a
+    /// row, with the values separated by spaces.
+    async fn run_sql(&self, sql: &str) -> anyhow::Result<Vec<String>> {
+        let mut rsp = self
+            .session
+            .clone()
+            .run_statement(sql)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+        let mut rows = vec![];
+        while let Some(chunk) = rsp.values_stream().next().await {
+            let chunk = chunk.map_err(|e| anyhow!("{}", e))?;
+            rows.extend(chunk.iter().map(|row| {
+                row.values()
+                    .iter()
+                    .map(|value| match value.as_deref() {
+                        None => "NULL",
+                        Some("") => "(empty)",
+                        Some(value) => value,
+                    })
+                    .join(" ")
+            }));
+        }
+        Ok(rows)
+    }
+}
+
+/// Collapses the whitespaces of each row, and sorts the rows or values as required, so that the
+/// alignment in the scripts doesn't matter.
+fn normalize(rows: &[String], sort_mode: SortMode) -> Vec<String> {
+    let rows = rows.iter().map(|row| row.split_whitespace().join(" "));
+    match sort_mode {
+        SortMode::NoSort => rows.collect(),
+        SortMode::RowSort => rows.sorted().collect(),
+        SortMode::ValueSort => rows
+            .flat_map(|row| row.split(' ').map(String::from).collect_vec())
+            .sorted()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let rows = vec!["2  b".to_string(), "1\ta  ".to_string()];
+        assert_eq!(normalize(&rows, SortMode::NoSort), vec!["2 b", "1 a"]);
+        assert_eq!(normalize(&rows, SortMode::RowSort), vec!["1 a", "2 b"]);
+        assert_eq!(
+            normalize(&rows, SortMode::ValueSort),
+            vec!["1", "2", "a", "b"]
+        );
+    }
+}
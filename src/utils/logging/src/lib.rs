@@ -31,6 +31,10 @@ fn configure_risingwave_targets_jaeger(targets: filter::Targets) -> filter::Targ
         .with_target("risingwave_stream", Level::TRACE)
         .with_target("risingwave_batch", Level::TRACE)
         .with_target("risingwave_storage", Level::TRACE)
+        .with_target("risingwave_compute", Level::TRACE)
+        .with_target("risingwave_frontend", Level::TRACE)
+        .with_target("risingwave_meta", Level::TRACE)
+        .with_target("pgwire", Level::TRACE)
         .with_target("risingwave_sqlparser", Level::INFO)
         // disable events that are too verbose
         // if you want to enable any of them, find the target name and set it to `TRACE`
@@ -64,8 +68,13 @@ fn configure_risingwave_targets_fmt(targets: filter::Targets) -> filter::Targets
     // }
 }
 
-/// Init logger for RisingWave binaries.
-pub fn init_risingwave_logger(enable_jaeger_tracing: bool, colorful: bool) {
+/// The default endpoint of the Jaeger collector, which can be overridden by the environment
+/// variable `RW_JAEGER_ENDPOINT`.
+const DEFAULT_JAEGER_ENDPOINT: &str = "http://127.0.0.1:14268/api/traces";
+
+/// Init logger for RisingWave binaries. The spans are reported to Jaeger under the given service
+/// name if it's set.
+pub fn init_risingwave_logger(jaeger_service_name: Option<&str>, colorful: bool) {
     use std::panic;
 
     let default_hook = panic::take_hook();
@@ -102,16 +111,18 @@ pub fn init_risingwave_logger(enable_jaeger_tracing: bool, colorful: bool) {
         fmt_layer.with_filter(filter)
     };
 
-    if enable_jaeger_tracing {
+    if let Some(service_name) = jaeger_service_name {
         // With Jaeger tracing enabled, we should configure opentelemetry endpoints.
 
+        // The propagator is also used to pass the tracing context across RPCs.
         opentelemetry::global::set_text_map_propagator(opentelemetry_jaeger::Propagator::new());
 
+        let endpoint = std::env::var("RW_JAEGER_ENDPOINT")
+            .unwrap_or_else(|_| DEFAULT_JAEGER_ENDPOINT.to_string());
         let tracer = opentelemetry_jaeger::new_pipeline()
             // TODO: use UDP tracing in production environment
-            .with_collector_endpoint("http://127.0.0.1:14268/api/traces")
-            // TODO: change service name to compute-{port}
-            .with_service_name("compute")
+            .with_collector_endpoint(endpoint)
+            .with_service_name(service_name)
             // disable proxy
             .with_http_client(isahc::HttpClient::builder().proxy(None).build().unwrap())
             .install_batch(trace_runtime::RwTokio)
@@ -12,13 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+
 use thiserror::Error;
 
+use crate::pg_server::BoxedError;
+
+/// The SQLSTATE code of the errors that don't carry one.
+pub const SQLSTATE_INTERNAL_ERROR: &str = "XX000";
+
 /// Error type used in pgwire crates.
 #[derive(Error, Debug)]
 pub enum PsqlError {
     #[error("Encode error {0}.")]
     CancelError(String),
+    #[error("SSL connection is required")]
+    SslRequiredError,
 }
 
 impl PsqlError {
@@ -26,4 +35,58 @@ impl PsqlError {
     pub fn cancel() -> Self {
         PsqlError::CancelError("ERROR:  canceling statement due to user request".to_string())
     }
+
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            // query_canceled
+            PsqlError::CancelError(_) => "57014",
+            // invalid_authorization_specification
+            PsqlError::SslRequiredError => "28000",
+        }
+    }
+}
+
+/// An error with its SQLSTATE code, which is sent to the client in the `ErrorResponse`. The
+/// message is the one of the wrapped error.
+#[derive(Debug)]
+pub struct PgError {
+    sqlstate: &'static str,
+    source: BoxedError,
+}
+
+impl PgError {
+    pub fn new(sqlstate: &'static str, source: impl Into<BoxedError>) -> Self {
+        Self {
+            sqlstate,
+            source: source.into(),
+        }
+    }
+
+    pub fn sqlstate(&self) -> &'static str {
+        self.sqlstate
+    }
+}
+
+impl fmt::Display for PgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.source, f)
+    }
+}
+
+impl std::error::Error for PgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// The SQLSTATE code to report for an error, which is [`SQLSTATE_INTERNAL_ERROR`] unless the error
+/// is a [`PgError`] or a [`PsqlError`].
+pub fn sqlstate_of(error: &BoxedError) -> &'static str {
+    if let Some(e) = error.downcast_ref::<PgError>() {
+        e.sqlstate()
+    } else if let Some(e) = error.downcast_ref::<PsqlError>() {
+        e.sqlstate()
+    } else {
+        SQLSTATE_INTERNAL_ERROR
+    }
 }
@@ -15,9 +15,12 @@
 // limitations under the License.
 
 pub mod error;
+pub mod pg_auth;
+pub mod pg_extended;
 pub mod pg_field_descriptor;
 pub mod pg_message;
 pub mod pg_protocol;
 pub mod pg_response;
 pub mod pg_server;
+pub mod pg_ssl;
 pub mod types;
@@ -0,0 +1,379 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Password authentication of pgwire. The way a user is authenticated is decided by the form its
+//! password is stored in, like Postgres does:
+//! * in clear text: the client sends the password in clear text.
+//! * hashed by MD5 (`md5<hex>`): the client sends the hash, salted by a random salt.
+//! * as a SCRAM-SHA-256 verifier (`SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>`): the
+//!   client and the server prove to each other that they know the password by the SASL exchange of
+//!   RFC 5802, without sending it.
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::pg_server::BoxedError;
+
+/// The name of the only supported SASL mechanism.
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+const MD5_PREFIX: &str = "md5";
+const SCRAM_ITERATIONS: u32 = 4096;
+const SCRAM_SALT_LEN: usize = 16;
+const SCRAM_NONCE_LEN: usize = 18;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How a user is authenticated when connecting.
+pub enum UserAuthenticator {
+    /// The user has no password, and is trusted.
+    None,
+    /// The client sends the password in clear text.
+    ClearText(Vec<u8>),
+    /// The client sends the MD5 hash of the password, salted by `salt`.
+    Md5WithSalt {
+        /// The stored password, i.e., `md5<hex>`.
+        encrypted_password: Vec<u8>,
+        salt: [u8; 4],
+    },
+    /// The SASL exchange of SCRAM-SHA-256.
+    ScramSha256(ScramVerifier),
+}
+
+impl UserAuthenticator {
+    /// Checks the password sent by the client in a `PasswordMessage`. Not for SCRAM.
+    pub fn authenticate(&self, password: &[u8]) -> bool {
+        match self {
+            UserAuthenticator::None => true,
+            UserAuthenticator::ClearText(expected) => password == expected.as_slice(),
+            UserAuthenticator::Md5WithSalt {
+                encrypted_password,
+                salt,
+            } => {
+                let hash = encrypted_password
+                    .strip_prefix(MD5_PREFIX.as_bytes())
+                    .unwrap_or(encrypted_password);
+                let mut salted = hash.to_vec();
+                salted.extend_from_slice(salt);
+                password == format!("{}{:x}", MD5_PREFIX, md5::compute(salted)).as_bytes()
+            }
+            UserAuthenticator::ScramSha256(_) => false,
+        }
+    }
+
+    /// Checks a password given in plain text, e.g., by a client of Arrow Flight, which doesn't run
+    /// the challenge-response of pgwire.
+    pub fn authenticate_plaintext(&self, user_name: &str, password: &str) -> bool {
+        match self {
+            UserAuthenticator::None => true,
+            UserAuthenticator::ClearText(expected) => password.as_bytes() == expected.as_slice(),
+            UserAuthenticator::Md5WithSalt {
+                encrypted_password, ..
+            } => {
+                let expected = encrypted_password
+                    .strip_prefix(MD5_PREFIX.as_bytes())
+                    .unwrap_or(encrypted_password);
+                md5_hash_password(user_name, password).as_bytes()[MD5_PREFIX.len()..] == *expected
+            }
+            UserAuthenticator::ScramSha256(verifier) => verifier.verify_password(password),
+        }
+    }
+}
+
+/// Hashes the password of a user by MD5 like Postgres, i.e., `md5` followed by the hex of
+/// `md5(password + user_name)`.
+pub fn md5_hash_password(user_name: &str, password: &str) -> String {
+    let digest = md5::compute(format!("{}{}", password, user_name));
+    format!("{}{:x}", MD5_PREFIX, digest)
+}
+
+/// Whether the password is already hashed by [`md5_hash_password`].
+pub fn is_md5_hashed(password: &str) -> bool {
+    password.len() == 35
+        && password.starts_with(MD5_PREFIX)
+        && password[3..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// What the server stores to verify a user by SCRAM-SHA-256, derived from the password.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScramVerifier {
+    iterations: u32,
+    salt: Vec<u8>,
+    stored_key: Vec<u8>,
+    server_key: Vec<u8>,
+}
+
+impl ScramVerifier {
+    /// Derives the verifier from the password with a random salt.
+    pub fn new(password: &str) -> Self {
+        let salt: [u8; SCRAM_SALT_LEN] = rand::thread_rng().gen();
+        Self::with_salt(password, &salt, SCRAM_ITERATIONS)
+    }
+
+    fn with_salt(password: &str, salt: &[u8], iterations: u32) -> Self {
+        let salted_password = hi(password.as_bytes(), salt, iterations);
+        let client_key = hmac(&salted_password, b"Client Key");
+        Self {
+            iterations,
+            salt: salt.to_vec(),
+            stored_key: Sha256::digest(&client_key).to_vec(),
+            server_key: hmac(&salted_password, b"Server Key"),
+        }
+    }
+
+    /// Whether the verifier is derived from `password`.
+    pub fn verify_password(&self, password: &str) -> bool {
+        Self::with_salt(password, &self.salt, self.iterations).stored_key == self.stored_key
+    }
+
+    /// Parses a verifier in the form of
+    /// `SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (mechanism, rest) = s.split_once('$')?;
+        if mechanism != SCRAM_SHA_256 {
+            return None;
+        }
+        let (iterations_and_salt, keys) = rest.split_once('$')?;
+        let (iterations, salt) = iterations_and_salt.split_once(':')?;
+        let (stored_key, server_key) = keys.split_once(':')?;
+        let verifier = Self {
+            iterations: iterations.parse().ok()?,
+            salt: base64::decode(salt).ok()?,
+            stored_key: base64::decode(stored_key).ok()?,
+            server_key: base64::decode(server_key).ok()?,
+        };
+        if verifier.stored_key.len() != 32 || verifier.server_key.len() != 32 {
+            return None;
+        }
+        Some(verifier)
+    }
+
+    /// Handles the `client-first-message`, and returns the `server-first-message` along with the
+    /// state to verify the `client-final-message`.
+    pub fn server_first(&self, client_first: &[u8]) -> Result<(ScramExchange, String), BoxedError> {
+        let client_first = std::str::from_utf8(client_first)?;
+        // The GS2 header is made up of the channel binding flag and the authzid, followed by the
+        // `client-first-message-bare`.
+        let mut parts = client_first.splitn(3, ',');
+        let (cbind_flag, _authzid, client_first_bare) =
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(cbind_flag), Some(authzid), Some(bare)) => (cbind_flag, authzid, bare),
+                _ => return Err("malformed SCRAM message".into()),
+            };
+        if cbind_flag.starts_with('p') {
+            return Err("SCRAM channel binding is not supported".into());
+        }
+        let client_nonce = scram_attribute(client_first_bare, 'r')
+            .ok_or("malformed SCRAM message: missing the client nonce")?;
+
+        let server_nonce: [u8; SCRAM_NONCE_LEN] = rand::thread_rng().gen();
+        let nonce = format!("{}{}", client_nonce, base64::encode(server_nonce));
+        let server_first = format!(
+            "r={},s={},i={}",
+            nonce,
+            base64::encode(&self.salt),
+            self.iterations
+        );
+        let exchange = ScramExchange {
+            gs2_header: client_first[..client_first.len() - client_first_bare.len()].to_string(),
+            client_first_bare: client_first_bare.to_string(),
+            server_first: server_first.clone(),
+            nonce,
+        };
+        Ok((exchange, server_first))
+    }
+
+    /// Verifies the proof in the `client-final-message`, and returns the `server-final-message`.
+    pub fn server_final(
+        &self,
+        exchange: &ScramExchange,
+        client_final: &[u8],
+    ) -> Result<String, BoxedError> {
+        let client_final = std::str::from_utf8(client_final)?;
+        let (client_final_without_proof, proof) = client_final
+            .rsplit_once(",p=")
+            .ok_or("malformed SCRAM message: missing the proof")?;
+        let channel_binding = scram_attribute(client_final_without_proof, 'c')
+            .ok_or("malformed SCRAM message: missing the channel binding")?;
+        if channel_binding != base64::encode(&exchange.gs2_header) {
+            return Err("SCRAM channel binding check failed".into());
+        }
+        if scram_attribute(client_final_without_proof, 'r') != Some(exchange.nonce.as_str()) {
+            return Err("SCRAM nonce does not match".into());
+        }
+        let proof = base64::decode(proof)?;
+        if proof.len() != self.stored_key.len() {
+            return Err("malformed SCRAM message: invalid proof".into());
+        }
+
+        let auth_message = format!(
+            "{},{},{}",
+            exchange.client_first_bare, exchange.server_first, client_final_without_proof
+        );
+        let client_signature = hmac(&self.stored_key, auth_message.as_bytes());
+        let client_key: Vec<u8> = (0..proof.len())
+            .map(|i| proof[i] ^ client_signature[i])
+            .collect();
+        if Sha256::digest(&client_key).as_slice() != self.stored_key.as_slice() {
+            return Err("password authentication failed".into());
+        }
+
+        let server_signature = hmac(&self.server_key, auth_message.as_bytes());
+        Ok(format!("v={}", base64::encode(server_signature)))
+    }
+}
+
+impl std::fmt::Display for ScramVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}${}:{}${}:{}",
+            SCRAM_SHA_256,
+            self.iterations,
+            base64::encode(&self.salt),
+            base64::encode(&self.stored_key),
+            base64::encode(&self.server_key)
+        )
+    }
+}
+
+/// The state of an ongoing SCRAM exchange between the first and the final messages.
+pub struct ScramExchange {
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+    nonce: String,
+}
+
+/// Finds the value of the attribute `name` in a SCRAM message, e.g. `r` in `n=,r=abc`.
+fn scram_attribute(message: &str, name: char) -> Option<&str> {
+    message.split(',').find_map(|attr| {
+        let mut chars = attr.chars();
+        if chars.next() == Some(name) && chars.next() == Some('=') {
+            Some(&attr[2..])
+        } else {
+            None
+        }
+    })
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The `Hi` function of RFC 5802, i.e., PBKDF2 with HMAC-SHA-256.
+fn hi(password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut message = salt.to_vec();
+    message.extend_from_slice(&1u32.to_be_bytes());
+    let mut u = hmac(password, &message);
+    let mut result = u.clone();
+    for _ in 1..iterations {
+        u = hmac(password, &u);
+        result.iter_mut().enumerate().for_each(|(i, r)| *r ^= u[i]);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5_authenticate() {
+        let encrypted_password = md5_hash_password("user", "pwd");
+        assert!(is_md5_hashed(&encrypted_password));
+        assert!(!is_md5_hashed("pwd"));
+
+        let salt = [1, 2, 3, 4];
+        let authenticator = UserAuthenticator::Md5WithSalt {
+            encrypted_password: encrypted_password.clone().into_bytes(),
+            salt,
+        };
+        // What the client computes.
+        let mut salted = encrypted_password[3..].as_bytes().to_vec();
+        salted.extend_from_slice(&salt);
+        let password = format!("md5{:x}", md5::compute(salted));
+        assert!(authenticator.authenticate(password.as_bytes()));
+        assert!(!authenticator.authenticate(encrypted_password.as_bytes()));
+    }
+
+    #[test]
+    fn test_plaintext_authenticate() {
+        let md5 = UserAuthenticator::Md5WithSalt {
+            encrypted_password: md5_hash_password("user", "pwd").into_bytes(),
+            salt: [1, 2, 3, 4],
+        };
+        assert!(md5.authenticate_plaintext("user", "pwd"));
+        assert!(!md5.authenticate_plaintext("user", "wrong"));
+        assert!(!md5.authenticate_plaintext("another_user", "pwd"));
+
+        let scram = UserAuthenticator::ScramSha256(ScramVerifier::new("pwd"));
+        assert!(scram.authenticate_plaintext("user", "pwd"));
+        assert!(!scram.authenticate_plaintext("user", "wrong"));
+    }
+
+    #[test]
+    fn test_scram_exchange() {
+        let verifier = ScramVerifier::parse(&ScramVerifier::new("pwd").to_string()).unwrap();
+
+        // Compute the proof like the client does.
+        let client_first = "n,,n=,r=rOprNGfwEbeRWgbNEkqO";
+        let (exchange, server_first) = verifier.server_first(client_first.as_bytes()).unwrap();
+        let nonce = scram_attribute(&server_first, 'r').unwrap();
+        assert!(nonce.starts_with("rOprNGfwEbeRWgbNEkqO"));
+        let salt = base64::decode(scram_attribute(&server_first, 's').unwrap()).unwrap();
+        let iterations = scram_attribute(&server_first, 'i')
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        let salted_password = hi(b"pwd", &salt, iterations);
+        let client_key = hmac(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(&client_key);
+        let client_final_without_proof = format!("c=biws,r={}", nonce);
+        let auth_message = format!(
+            "n=,r=rOprNGfwEbeRWgbNEkqO,{},{}",
+            server_first, client_final_without_proof
+        );
+        let client_signature = hmac(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = (0..client_key.len())
+            .map(|i| client_key[i] ^ client_signature[i])
+            .collect();
+
+        let client_final = format!(
+            "{},p={}",
+            client_final_without_proof,
+            base64::encode(&proof)
+        );
+        let server_final = verifier
+            .server_final(&exchange, client_final.as_bytes())
+            .unwrap();
+        let server_key = hmac(&salted_password, b"Server Key");
+        let server_signature = hmac(&server_key, auth_message.as_bytes());
+        assert_eq!(
+            server_final,
+            format!("v={}", base64::encode(server_signature))
+        );
+
+        // A wrong password.
+        let wrong_proof = base64::encode(vec![0u8; 32]);
+        let client_final = format!("{},p={}", client_final_without_proof, wrong_proof);
+        assert!(verifier
+            .server_final(&exchange, client_final.as_bytes())
+            .is_err());
+    }
+}
@@ -0,0 +1,704 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prepared statements and portals of the extended query protocol.
+//!
+//! A prepared statement keeps the query string with its parameters `$1`, `$2`, ... as is. Binding
+//! the parameters decodes their values to the text format. The portal is executed by
+//! [`crate::pg_server::Session::run_portal`], which either runs the query string with the
+//! parameters substituted by literals casted to the parameter types, or binds the values to a
+//! plan of the statement cached by the session. The values of the rows are sent in the text or
+//! the binary format, following the result format codes requested in the Bind message.
+
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use futures::StreamExt;
+
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use crate::pg_response::PgResponse;
+use crate::pg_server::BoxedError;
+use crate::types::Row;
+
+/// The description of a statement returned by [`crate::pg_server::Session::describe_statement`].
+#[derive(Debug, Clone)]
+pub struct StatementDescription {
+    /// The types of all parameters, either specified by the client or inferred.
+    pub param_types: Vec<TypeOid>,
+    /// The fields of the rows returned by the statement, which is empty if it returns no rows.
+    /// `None` if the fields can't be known without executing the statement.
+    pub row_description: Option<Vec<PgFieldDescriptor>>,
+}
+
+/// A prepared statement created by a Parse message.
+pub struct PgStatement {
+    query_string: String,
+    description: StatementDescription,
+}
+
+impl PgStatement {
+    pub fn new(query_string: String, description: StatementDescription) -> Self {
+        Self {
+            query_string,
+            description,
+        }
+    }
+
+    pub fn param_types(&self) -> &[TypeOid] {
+        &self.description.param_types
+    }
+
+    pub fn row_description(&self) -> Option<&[PgFieldDescriptor]> {
+        self.description.row_description.as_deref()
+    }
+
+    /// Creates a portal with the parameters bound to the given values, whose results are sent in
+    /// the formats of `result_format_codes`.
+    pub fn bind(
+        &self,
+        param_format_codes: &[i16],
+        params: &[Option<Bytes>],
+        result_format_codes: &[i16],
+    ) -> Result<PgPortal> {
+        let param_types = self.param_types();
+        if params.len() != param_types.len() {
+            return Err(invalid_input(format!(
+                "bind message supplies {} parameters, but prepared statement requires {}",
+                params.len(),
+                param_types.len()
+            )));
+        }
+        let params = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let format_code = format_code_of(param_format_codes, i, "parameter")?;
+                param_to_text(param.clone(), param_types[i], format_code)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let literals = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| text_to_literal(param.as_deref(), param_types[i]))
+            .collect::<Vec<_>>();
+
+        let row_description = match &self.description.row_description {
+            Some(fields) => Some(with_result_formats(fields.clone(), result_format_codes)?),
+            None => None,
+        };
+
+        Ok(PgPortal {
+            query_string: substitute_params(&self.query_string, &literals)?,
+            statement: self.query_string.clone(),
+            param_types: param_types.to_vec(),
+            params,
+            result_format_codes: result_format_codes.to_vec(),
+            row_description,
+            result: None,
+            buffered_rows: VecDeque::new(),
+        })
+    }
+}
+
+/// A portal created by a Bind message, i.e., a prepared statement with its parameters bound. The
+/// statement is executed on the first Execute message, and the rows are pulled from its result as
+/// they are fetched, which may take several Execute messages with a row limit.
+pub struct PgPortal {
+    /// The query string with the parameters substituted by literals.
+    query_string: String,
+    /// The query string of the prepared statement, with the parameters as is.
+    statement: String,
+    param_types: Vec<TypeOid>,
+    /// The values of the parameters in the text format. `None` for nulls.
+    params: Vec<Option<String>>,
+    result_format_codes: Vec<i16>,
+    /// The fields of the rows, with the format codes requested for them.
+    row_description: Option<Vec<PgFieldDescriptor>>,
+    result: Option<PgResponse>,
+    /// The rows pulled from the stream of `result` but not sent yet.
+    buffered_rows: VecDeque<Row>,
+}
+
+impl PgPortal {
+    pub fn query_string(&self) -> &str {
+        &self.query_string
+    }
+
+    pub fn statement(&self) -> &str {
+        &self.statement
+    }
+
+    pub fn param_types(&self) -> &[TypeOid] {
+        &self.param_types
+    }
+
+    pub fn params(&self) -> &[Option<String>] {
+        &self.params
+    }
+
+    /// The formats requested for the columns of the results, as given by the Bind message.
+    pub fn result_format_codes(&self) -> &[i16] {
+        &self.result_format_codes
+    }
+
+    pub fn row_description(&self) -> Option<&[PgFieldDescriptor]> {
+        self.row_description.as_deref()
+    }
+
+    pub fn result(&self) -> Option<&PgResponse> {
+        self.result.as_ref()
+    }
+
+    pub fn set_result(&mut self, result: PgResponse) -> Result<()> {
+        let fields = if result.is_query() {
+            with_result_formats(result.get_row_desc(), &self.result_format_codes)?
+        } else {
+            vec![]
+        };
+        self.row_description = Some(fields);
+        self.result = Some(result);
+        Ok(())
+    }
+
+    /// Takes at most `max_rows` rows that haven't been sent from the result. Only the rows of one
+    /// set of the result stream are taken at a time, so that they can be sent before pulling
+    /// more. Returns no rows once the result is exhausted.
+    pub async fn fetch(&mut self, max_rows: usize) -> std::result::Result<Vec<Row>, BoxedError> {
+        if !self.fill_buffer().await? {
+            return Ok(vec![]);
+        }
+        let count = max_rows.min(self.buffered_rows.len());
+        Ok(self.buffered_rows.drain(..count).collect())
+    }
+
+    /// Whether there are rows that haven't been sent.
+    pub async fn has_more_rows(&mut self) -> std::result::Result<bool, BoxedError> {
+        self.fill_buffer().await
+    }
+
+    /// Pulls the next non-empty set of rows from the result if all buffered rows have been sent.
+    /// Returns false if there're no more rows.
+    async fn fill_buffer(&mut self) -> std::result::Result<bool, BoxedError> {
+        let result = self.result.as_mut().expect("portal is not executed");
+        while self.buffered_rows.is_empty() {
+            match result.values_stream().next().await {
+                Some(rows) => self.buffered_rows.extend(rows?),
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn invalid_input(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, msg)
+}
+
+/// Returns the format code of the `i`-th value. No codes means all values are in the text format,
+/// and a single code applies to all of them.
+fn format_code_of(format_codes: &[i16], i: usize, kind: &str) -> Result<i16> {
+    match format_codes {
+        [] => Ok(0),
+        [code] => Ok(*code),
+        codes => codes.get(i).copied().ok_or_else(|| {
+            invalid_input(format!("the number of {} format codes mismatches", kind))
+        }),
+    }
+}
+
+fn with_result_formats(
+    fields: Vec<PgFieldDescriptor>,
+    format_codes: &[i16],
+) -> Result<Vec<PgFieldDescriptor>> {
+    if format_codes.len() > 1 && format_codes.len() != fields.len() {
+        return Err(invalid_input(format!(
+            "bind message has {} result formats but query has {} columns",
+            format_codes.len(),
+            fields.len()
+        )));
+    }
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let format_code = format_code_of(format_codes, i, "result")?;
+            Ok(field.with_format_code(format_code))
+        })
+        .collect()
+}
+
+/// Encodes the values of a row in the formats of `fields`.
+pub fn encode_row(row: &Row, fields: &[PgFieldDescriptor]) -> Result<Vec<Option<Bytes>>> {
+    row.values()
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let value = match value {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+            // Values without a field are sent as they are.
+            let (format_code, type_oid) = fields.get(i).map_or((0, TypeOid::Varchar), |f| {
+                (f.get_format_code(), f.get_type_oid())
+            });
+            match format_code {
+                0 => Ok(Some(Bytes::copy_from_slice(value.as_bytes()))),
+                1 => text_to_binary(value, type_oid).map(Some),
+                format_code => Err(invalid_input(format!(
+                    "unsupported format code: {}",
+                    format_code
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Converts a value in the text format into the binary format of Postgres for its type.
+fn text_to_binary(text: &str, type_oid: TypeOid) -> Result<Bytes> {
+    let invalid = || {
+        invalid_input(format!(
+            "invalid value for binary format of type {:?}: {}",
+            type_oid, text
+        ))
+    };
+    let mut buf = BytesMut::new();
+    match type_oid {
+        TypeOid::Boolean => match text {
+            "t" | "true" => buf.put_u8(1),
+            "f" | "false" => buf.put_u8(0),
+            _ => return Err(invalid()),
+        },
+        TypeOid::SmallInt => buf.put_i16(text.parse().map_err(|_| invalid())?),
+        TypeOid::Int => buf.put_i32(text.parse().map_err(|_| invalid())?),
+        TypeOid::BigInt => buf.put_i64(text.parse().map_err(|_| invalid())?),
+        TypeOid::Float4 => buf.put_f32(text.parse().map_err(|_| invalid())?),
+        TypeOid::Float8 => buf.put_f64(text.parse().map_err(|_| invalid())?),
+        TypeOid::Varchar => buf.put_slice(text.as_bytes()),
+        TypeOid::Date => {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| invalid())?;
+            buf.put_i32((date - pg_epoch().date()).num_days() as i32);
+        }
+        TypeOid::Time => {
+            let time = NaiveTime::parse_from_str(text, "%H:%M:%S%.f").map_err(|_| invalid())?;
+            let micros = (time - NaiveTime::from_hms(0, 0, 0)).num_microseconds();
+            buf.put_i64(micros.ok_or_else(invalid)?);
+        }
+        TypeOid::Timestamp => {
+            let timestamp = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+                .map_err(|_| invalid())?;
+            let micros = (timestamp - pg_epoch()).num_microseconds();
+            buf.put_i64(micros.ok_or_else(invalid)?);
+        }
+        TypeOid::Timestampz => {
+            // Timestamps with time zone in the binary format are passed as their values, i.e., the
+            // microseconds since the Unix epoch, instead of the texts displayed in the session.
+            let unix_micros: i64 = text.parse().map_err(|_| invalid())?;
+            let epoch_micros = pg_epoch().timestamp() * 1_000_000;
+            buf.put_i64(unix_micros.checked_sub(epoch_micros).ok_or_else(invalid)?);
+        }
+        TypeOid::Decimal => encode_numeric(text, &mut buf).ok_or_else(invalid)?,
+        TypeOid::CharArray => {
+            return Err(invalid_input(format!(
+                "binary format of type {:?} is not supported",
+                type_oid
+            )))
+        }
+    }
+    Ok(buf.freeze())
+}
+
+/// Dates and timestamps are encoded relative to 2000-01-01 in the binary format.
+fn pg_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0)
+}
+
+/// Encodes a decimal in the binary format of `numeric`, which consists of the number of digits,
+/// the weight of the first digit, the sign and the display scale, followed by the digits in base
+/// 10000. Returns `None` if `text` isn't a valid decimal.
+fn encode_numeric(text: &str, buf: &mut BytesMut) -> Option<()> {
+    const NUMERIC_POS: u16 = 0x0000;
+    const NUMERIC_NEG: u16 = 0x4000;
+    const NUMERIC_NAN: u16 = 0xC000;
+    const NUMERIC_PINF: u16 = 0xD000;
+    const NUMERIC_NINF: u16 = 0xF000;
+
+    let special = match text {
+        "NaN" => Some(NUMERIC_NAN),
+        "+Inf" | "Infinity" => Some(NUMERIC_PINF),
+        "-Inf" | "-Infinity" => Some(NUMERIC_NINF),
+        _ => None,
+    };
+    if let Some(sign) = special {
+        buf.put_i16(0);
+        buf.put_i16(0);
+        buf.put_u16(sign);
+        buf.put_u16(0);
+        return Some(());
+    }
+
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (NUMERIC_NEG, unsigned),
+        None => (NUMERIC_POS, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    // Pad both parts to whole base-10000 digits around the decimal point.
+    let int_part = int_part.trim_start_matches('0');
+    let int_padding = (4 - int_part.len() % 4) % 4;
+    let frac_padding = (4 - frac_part.len() % 4) % 4;
+    let padded = format!(
+        "{}{}{}{}",
+        "0".repeat(int_padding),
+        int_part,
+        frac_part,
+        "0".repeat(frac_padding)
+    );
+    let mut digits = padded
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap().parse::<i16>().unwrap())
+        .collect::<Vec<_>>();
+    let mut weight = ((int_padding + int_part.len()) / 4) as i16 - 1;
+    // Strip the leading and the trailing zeros.
+    let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+    digits.drain(..leading_zeros);
+    weight -= leading_zeros as i16;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        weight = 0;
+    }
+
+    buf.put_i16(digits.len() as i16);
+    buf.put_i16(weight);
+    buf.put_u16(sign);
+    buf.put_u16(frac_part.len() as u16);
+    for digit in digits {
+        buf.put_i16(digit);
+    }
+    Some(())
+}
+
+/// Decodes the value of a parameter into the text format.
+fn param_to_text(
+    param: Option<Bytes>,
+    param_type: TypeOid,
+    format_code: i16,
+) -> Result<Option<String>> {
+    let mut value = match param {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let text = match format_code {
+        0 => String::from_utf8(value.to_vec()).map_err(|e| invalid_input(e.to_string()))?,
+        1 => {
+            let expected_len = match param_type {
+                TypeOid::Boolean => 1,
+                TypeOid::SmallInt => 2,
+                TypeOid::Int | TypeOid::Float4 => 4,
+                TypeOid::BigInt | TypeOid::Float8 => 8,
+                _ => value.len(),
+            };
+            if value.len() != expected_len {
+                return Err(invalid_input(format!(
+                    "incorrect binary data format in bind parameter of type {:?}",
+                    param_type
+                )));
+            }
+            match param_type {
+                TypeOid::Boolean => (value.get_u8() != 0).to_string(),
+                TypeOid::SmallInt => value.get_i16().to_string(),
+                TypeOid::Int => value.get_i32().to_string(),
+                TypeOid::BigInt => value.get_i64().to_string(),
+                TypeOid::Float4 => value.get_f32().to_string(),
+                TypeOid::Float8 => value.get_f64().to_string(),
+                TypeOid::Varchar => {
+                    String::from_utf8(value.to_vec()).map_err(|e| invalid_input(e.to_string()))?
+                }
+                _ => {
+                    return Err(invalid_input(format!(
+                        "binary format of parameter type {:?} is not supported",
+                        param_type
+                    )))
+                }
+            }
+        }
+        _ => {
+            return Err(invalid_input(format!(
+                "unsupported format code: {}",
+                format_code
+            )))
+        }
+    };
+    Ok(Some(text))
+}
+
+/// Converts the value of a parameter in the text format into a SQL literal of its type.
+fn text_to_literal(text: Option<&str>, param_type: TypeOid) -> String {
+    let sql_type = sql_type_name(param_type);
+    match text {
+        Some(text) => format!("'{}'::{}", text.replace('\'', "''"), sql_type),
+        None => format!("NULL::{}", sql_type),
+    }
+}
+
+fn sql_type_name(type_oid: TypeOid) -> &'static str {
+    match type_oid {
+        TypeOid::Boolean => "BOOLEAN",
+        TypeOid::BigInt => "BIGINT",
+        TypeOid::SmallInt => "SMALLINT",
+        TypeOid::Int => "INT",
+        TypeOid::Float4 => "REAL",
+        TypeOid::Float8 => "DOUBLE PRECISION",
+        TypeOid::CharArray | TypeOid::Varchar => "VARCHAR",
+        TypeOid::Date => "DATE",
+        TypeOid::Time => "TIME",
+        TypeOid::Timestamp => "TIMESTAMP",
+        TypeOid::Timestampz => "TIMESTAMP WITH TIME ZONE",
+        TypeOid::Decimal => "DECIMAL",
+    }
+}
+
+/// Replaces the parameters `$1`, `$2`, ... in `query_string` with `literals`. Parameters inside
+/// string literals, quoted identifiers and comments are left untouched.
+fn substitute_params(query_string: &str, literals: &[String]) -> Result<String> {
+    let mut result = String::with_capacity(query_string.len());
+    let mut chars = query_string.chars().peekable();
+    // Whether the previous character can be part of an identifier, e.g. `$1` in `a$1` is not a
+    // parameter.
+    let mut in_word = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                result.push(c);
+                // A doubled quote is an escaped one, which just reopens the quoted part.
+                for next in chars.by_ref() {
+                    result.push(next);
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                result.push(c);
+                for next in chars.by_ref() {
+                    result.push(next);
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                result.push(c);
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    result.push(next);
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            '$' if !in_word && chars.peek().map_or(false, |c| c.is_ascii_digit()) => {
+                let mut index = String::new();
+                while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    index.push(*digit);
+                    chars.next();
+                }
+                let literal = index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| literals.get(i))
+                    .ok_or_else(|| invalid_input(format!("there is no parameter ${}", index)))?;
+                result.push('(');
+                result.push_str(literal);
+                result.push(')');
+                in_word = false;
+                continue;
+            }
+            _ => result.push(c),
+        }
+        in_word = c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+    use crate::pg_response::StatementType;
+
+    #[test]
+    fn test_bind_params() {
+        let statement = PgStatement::new(
+            "SELECT $1, '$1', v$1 FROM t WHERE v1 = $2 -- $1\n AND v2 = $1".to_string(),
+            StatementDescription {
+                param_types: vec![TypeOid::Varchar, TypeOid::Int],
+                row_description: None,
+            },
+        );
+        let params = [Some(Bytes::from_static(b"it's")), None];
+        let portal = statement.bind(&[], &params, &[]).unwrap();
+        assert_eq!(portal.statement(), statement.query_string);
+        assert_eq!(portal.params(), &[Some("it's".to_string()), None]);
+        assert_eq!(
+            portal.query_string(),
+            "SELECT ('it''s'::VARCHAR), '$1', v$1 FROM t WHERE v1 = (NULL::INT) -- $1\n \
+             AND v2 = ('it''s'::VARCHAR)"
+        );
+
+        let params = [
+            Some(Bytes::from_static(b"abc")),
+            Some(Bytes::from(42i32.to_be_bytes().to_vec())),
+        ];
+        let portal = statement.bind(&[0, 1], &params, &[]).unwrap();
+        assert!(portal.query_string().contains("('42'::INT)"));
+        assert_eq!(portal.params()[1].as_deref(), Some("42"));
+
+        assert!(statement.bind(&[], &params[..1], &[]).is_err());
+        let params = [
+            Some(Bytes::from_static(b"abc")),
+            Some(Bytes::from_static(b"42")),
+        ];
+        assert!(statement.bind(&[0, 1], &params, &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_portal_fetch() {
+        let statement = PgStatement::new(
+            "SELECT 1".to_string(),
+            StatementDescription {
+                param_types: vec![],
+                row_description: None,
+            },
+        );
+        let mut portal = statement.bind(&[], &[], &[]).unwrap();
+        let row_sets = [0..3, 3..3, 3..4]
+            .map(|range| Ok(range.map(|i| Row::new(vec![Some(i.to_string())])).collect()));
+        portal
+            .set_result(PgResponse::new_for_stream(
+                StatementType::SELECT,
+                0,
+                stream::iter(row_sets).boxed(),
+                vec![PgFieldDescriptor::new("v".to_string(), TypeOid::Int)],
+            ))
+            .unwrap();
+        assert_eq!(portal.row_description().unwrap().len(), 1);
+
+        let rows = portal.fetch(2).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(portal.has_more_rows().await.unwrap());
+        // The rest of the first set.
+        let rows = portal.fetch(2).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        // The empty set is skipped.
+        let rows = portal.fetch(usize::MAX).await.unwrap();
+        assert_eq!(rows[0][0].as_deref(), Some("3"));
+        assert!(!portal.has_more_rows().await.unwrap());
+        assert!(portal.fetch(usize::MAX).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_binary_results() {
+        let types = [
+            TypeOid::Boolean,
+            TypeOid::Int,
+            TypeOid::Float8,
+            TypeOid::Varchar,
+            TypeOid::Date,
+            TypeOid::Timestamp,
+            TypeOid::Decimal,
+        ];
+        let statement = PgStatement::new(
+            "SELECT ...".to_string(),
+            StatementDescription {
+                param_types: vec![],
+                row_description: None,
+            },
+        );
+        // The number of result format codes must match the number of columns.
+        let mut portal = statement.bind(&[], &[], &[1, 0]).unwrap();
+        let fields = types
+            .iter()
+            .map(|t| PgFieldDescriptor::new("v".to_string(), *t))
+            .collect::<Vec<_>>();
+        let result = || PgResponse::new(StatementType::SELECT, 0, vec![], fields.clone());
+        assert!(portal.set_result(result()).is_err());
+
+        let mut portal = statement.bind(&[], &[], &[1]).unwrap();
+        portal.set_result(result()).unwrap();
+        let fields = portal.row_description().unwrap();
+        assert!(fields.iter().all(|f| f.get_format_code() == 1));
+
+        let row = Row::new(
+            [
+                "t",
+                "42",
+                "1.5",
+                "abc",
+                "2000-01-02",
+                "2000-01-01 00:00:01.5",
+                "-12345.678",
+            ]
+            .iter()
+            .map(|v| Some(v.to_string()))
+            .collect(),
+        );
+        let values = encode_row(&row, fields).unwrap();
+        let values = values
+            .iter()
+            .map(|v| v.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values[0], [1]);
+        assert_eq!(values[1], 42i32.to_be_bytes());
+        assert_eq!(values[2], 1.5f64.to_be_bytes());
+        assert_eq!(values[3], b"abc");
+        assert_eq!(values[4], 1i32.to_be_bytes());
+        assert_eq!(values[5], 1_500_000i64.to_be_bytes());
+        // 1 2345 . 6780: 3 digits, weight 1, negative, scale 3.
+        assert_eq!(
+            values[6],
+            [0, 3, 0, 1, 0x40, 0, 0, 3, 0, 1, 0x09, 0x29, 0x1a, 0x7c]
+        );
+
+        let mut buf = BytesMut::new();
+        encode_numeric("0.00001", &mut buf).unwrap();
+        assert_eq!(&buf[..], [0, 1, 0xff, 0xfe, 0, 0, 0, 5, 0x03, 0xe8]);
+        assert!(encode_numeric("1.2.3", &mut buf).is_none());
+
+        // Nulls and text columns are left as they are.
+        let fields = [PgFieldDescriptor::new("v".to_string(), TypeOid::Int)];
+        let row = Row::new(vec![None]);
+        assert_eq!(encode_row(&row, &fields).unwrap(), vec![None]);
+        let row = Row::new(vec![Some("42".to_string())]);
+        assert_eq!(
+            encode_row(&row, &fields).unwrap(),
+            vec![Some(Bytes::from_static(b"42"))]
+        );
+    }
+}
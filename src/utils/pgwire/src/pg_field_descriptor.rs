@@ -58,6 +58,13 @@ impl PgFieldDescriptor {
         }
     }
 
+    /// Sets the format of the values of this field, 0 for text and 1 for binary.
+    #[must_use]
+    pub fn with_format_code(mut self, format_code: i16) -> Self {
+        self.format_code = format_code;
+        self
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
@@ -87,7 +94,7 @@ impl PgFieldDescriptor {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum TypeOid {
     Boolean,
     BigInt,
@@ -122,4 +129,24 @@ impl TypeOid {
             TypeOid::Decimal => 1231,
         }
     }
+
+    /// The inverse of [`TypeOid::as_number`]. `text` is taken as `varchar`.
+    pub fn from_number(oid: i32) -> Option<Self> {
+        match oid {
+            16 => Some(TypeOid::Boolean),
+            20 => Some(TypeOid::BigInt),
+            21 => Some(TypeOid::SmallInt),
+            23 => Some(TypeOid::Int),
+            25 | 1043 => Some(TypeOid::Varchar),
+            700 => Some(TypeOid::Float4),
+            701 => Some(TypeOid::Float8),
+            1002 => Some(TypeOid::CharArray),
+            1082 => Some(TypeOid::Date),
+            1083 => Some(TypeOid::Time),
+            1114 => Some(TypeOid::Timestamp),
+            1184 => Some(TypeOid::Timestampz),
+            1231 => Some(TypeOid::Decimal),
+            _ => None,
+        }
+    }
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::io::{Error, ErrorKind, IoSlice, Result, Write};
 
@@ -20,7 +21,8 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
-use crate::pg_field_descriptor::PgFieldDescriptor;
+use crate::error::sqlstate_of;
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_response::StatementType;
 use crate::pg_server::BoxedError;
 use crate::types::Row;
@@ -34,12 +36,32 @@ pub enum FeMessage {
     Describe(FeDescribeMessage),
     Bind(FeBindMessage),
     Execute(FeExecuteMessage),
+    Close(FeCloseMessage),
+    Password(FePasswordMessage),
     Sync,
-    CancelQuery,
+    Flush,
+    CancelQuery(FeCancelMessage),
     Terminate,
 }
 
-pub struct FeStartupMessage {}
+pub struct FeStartupMessage {
+    /// The run-time parameters, e.g. `user` and `database`.
+    pub config: HashMap<String, String>,
+}
+
+/// Sent on a new connection instead of a startup message to cancel the query running in another
+/// session, which is identified by the key data sent to the client at its startup.
+#[derive(Debug)]
+pub struct FeCancelMessage {
+    pub process_id: i32,
+    pub secret_key: i32,
+}
+
+/// The response to an authentication request, i.e., `PasswordMessage`, `SASLInitialResponse` or
+/// `SASLResponse`, which can only be told apart by the authentication method.
+pub struct FePasswordMessage {
+    pub payload: Bytes,
+}
 
 /// Query message contains the string sql.
 pub struct FeQueryMessage {
@@ -47,47 +69,98 @@ pub struct FeQueryMessage {
 }
 
 #[derive(Debug)]
-pub struct FeBindMessage {}
+pub struct FeBindMessage {
+    pub portal_name: Bytes,
+    pub statement_name: Bytes,
+    /// Either empty (all parameters in text), a single code applied to all parameters, or one
+    /// code for each parameter. 0 for text and 1 for binary.
+    pub param_format_codes: Vec<i16>,
+    /// The values of the parameters. `None` for null.
+    pub params: Vec<Option<Bytes>>,
+    pub result_format_codes: Vec<i16>,
+}
 
 #[derive(Debug)]
 pub struct FeExecuteMessage {
+    pub portal_name: Bytes,
+    /// The maximum number of rows to return. 0 for no limit.
     pub max_rows: i32,
 }
 
 #[derive(Debug)]
 pub struct FeParseMessage {
+    pub statement_name: Bytes,
     pub query_string: Bytes,
+    /// The type oids of the parameters specified by the client. 0 for unspecified.
+    pub type_ids: Vec<i32>,
 }
 
 #[derive(Debug)]
 pub struct FeDescribeMessage {
     // 'S' to describe a prepared statement; or 'P' to describe a portal.
     pub kind: u8,
+    pub name: Bytes,
+}
+
+#[derive(Debug)]
+pub struct FeCloseMessage {
+    // 'S' to close a prepared statement; or 'P' to close a portal.
+    pub kind: u8,
+    pub name: Bytes,
 }
 
 impl FeDescribeMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
         let kind = buf.get_u8();
-        let _pstmt_name = read_null_terminated(&mut buf)?;
+        let name = read_null_terminated(&mut buf)?;
+        check_object_kind(kind)?;
 
-        if kind != b'S' {
-            unimplemented!("only prepared statement Describe is implemented");
-        }
+        Ok(FeMessage::Describe(FeDescribeMessage { kind, name }))
+    }
+}
 
-        Ok(FeMessage::Describe(FeDescribeMessage { kind }))
+impl FeCloseMessage {
+    pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
+        let kind = buf.get_u8();
+        let name = read_null_terminated(&mut buf)?;
+        check_object_kind(kind)?;
+
+        Ok(FeMessage::Close(FeCloseMessage { kind, name }))
     }
 }
 
 impl FeBindMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
         let portal_name = read_null_terminated(&mut buf)?;
-        let _pstmt_name = read_null_terminated(&mut buf)?;
+        let statement_name = read_null_terminated(&mut buf)?;
 
-        if !portal_name.is_empty() {
-            unimplemented!("named portals not implemented");
-        }
+        let num_param_format_codes = buf.get_i16();
+        let param_format_codes = (0..num_param_format_codes).map(|_| buf.get_i16()).collect();
 
-        Ok(FeMessage::Bind(FeBindMessage {}))
+        let num_params = buf.get_i16();
+        let params = (0..num_params)
+            .map(|_| {
+                let len = buf.get_i32();
+                if len == -1 {
+                    None
+                } else {
+                    Some(buf.split_to(len as usize))
+                }
+            })
+            .collect();
+
+        let num_result_format_codes = buf.get_i16();
+        let result_format_codes = (0..num_result_format_codes)
+            .map(|_| buf.get_i16())
+            .collect();
+
+        Ok(FeMessage::Bind(FeBindMessage {
+            portal_name,
+            statement_name,
+            param_format_codes,
+            params,
+            result_format_codes,
+        }))
     }
 }
 
@@ -96,30 +169,84 @@ impl FeExecuteMessage {
         let portal_name = read_null_terminated(&mut buf)?;
         let max_rows = buf.get_i32();
 
-        if !portal_name.is_empty() {
-            unimplemented!("named portals not implemented");
-        }
-
-        if max_rows != 0 {
-            unimplemented!("row limit in Execute message not supported");
-        }
-
-        Ok(FeMessage::Execute(FeExecuteMessage { max_rows }))
+        Ok(FeMessage::Execute(FeExecuteMessage {
+            portal_name,
+            max_rows,
+        }))
     }
 }
 
 impl FeParseMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
-        let _pstmt_name = read_null_terminated(&mut buf)?;
+        let statement_name = read_null_terminated(&mut buf)?;
         let query_string = read_null_terminated(&mut buf)?;
-        let nparams = buf.get_i16();
+        let num_params = buf.get_i16();
+        let type_ids = (0..num_params).map(|_| buf.get_i32()).collect();
+
+        Ok(FeMessage::Parse(FeParseMessage {
+            statement_name,
+            query_string,
+            type_ids,
+        }))
+    }
+}
+
+fn check_object_kind(kind: u8) -> Result<()> {
+    if kind != b'S' && kind != b'P' {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid object kind: {}", kind as char),
+        ));
+    }
+    Ok(())
+}
 
-        if nparams != 0 {
-            unimplemented!("query params not implemented");
+impl FeStartupMessage {
+    fn parse(mut buf: Bytes) -> Result<FeMessage> {
+        let mut config = HashMap::new();
+        // The parameters are pairs of null-terminated names and values, terminated by an empty
+        // name.
+        while buf.has_remaining() {
+            let name = read_null_terminated(&mut buf)?;
+            if name.is_empty() {
+                break;
+            }
+            let value = read_null_terminated(&mut buf)?;
+            config.insert(bytes_to_string(&name)?, bytes_to_string(&value)?);
         }
+        Ok(FeMessage::Startup(FeStartupMessage { config }))
+    }
+}
 
-        Ok(FeMessage::Parse(FeParseMessage { query_string }))
+impl FePasswordMessage {
+    /// Reads the password of a `PasswordMessage`.
+    pub fn password(&self) -> Result<Bytes> {
+        read_null_terminated(&mut self.payload.clone())
     }
+
+    /// Reads the mechanism and the initial response of a `SASLInitialResponse`.
+    pub fn sasl_initial_response(&self) -> Result<(String, Bytes)> {
+        let mut buf = self.payload.clone();
+        let mechanism = bytes_to_string(&read_null_terminated(&mut buf)?)?;
+        if buf.remaining() < 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "malformed SASLInitialResponse",
+            ));
+        }
+        let len = buf.get_i32();
+        if len < 0 || len as usize > buf.remaining() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "malformed SASLInitialResponse",
+            ));
+        }
+        Ok((mechanism, buf.split_to(len as usize)))
+    }
+}
+
+fn bytes_to_string(b: &Bytes) -> Result<String> {
+    String::from_utf8(b.to_vec()).map_err(|e| Error::new(ErrorKind::InvalidInput, e))
 }
 
 impl FeQueryMessage {
@@ -158,7 +285,12 @@ impl FeMessage {
             b'D' => FeDescribeMessage::parse(sql_bytes),
             b'B' => FeBindMessage::parse(sql_bytes),
             b'E' => FeExecuteMessage::parse(sql_bytes),
+            b'C' => FeCloseMessage::parse(sql_bytes),
+            b'p' => Ok(FeMessage::Password(FePasswordMessage {
+                payload: sql_bytes,
+            })),
             b'S' => Ok(FeMessage::Sync),
+            b'H' => Ok(FeMessage::Flush),
             b'X' => Ok(FeMessage::Terminate),
             _ => Err(std::io::Error::new(
                 ErrorKind::InvalidInput,
@@ -168,6 +300,23 @@ impl FeMessage {
     }
 }
 
+impl FeCancelMessage {
+    fn parse(mut buf: Bytes) -> Result<FeMessage> {
+        if buf.remaining() < 8 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "cancel request is too short",
+            ));
+        }
+        let process_id = buf.get_i32();
+        let secret_key = buf.get_i32();
+        Ok(FeMessage::CancelQuery(FeCancelMessage {
+            process_id,
+            secret_key,
+        }))
+    }
+}
+
 impl FeStartupMessage {
     /// Read startup message from the stream.
     pub async fn read(stream: &mut (impl AsyncRead + Unpin)) -> Result<FeMessage> {
@@ -180,10 +329,10 @@ impl FeStartupMessage {
         }
         match protocol_num {
             // code from: https://www.postgresql.org/docs/current/protocol-message-formats.html
-            196608 => Ok(FeMessage::Startup(FeStartupMessage {})),
+            196608 => FeStartupMessage::parse(Bytes::from(payload)),
             80877103 => Ok(FeMessage::Ssl),
             // Cancel request code.
-            80877102 => Ok(FeMessage::CancelQuery),
+            80877102 => FeCancelMessage::parse(Bytes::from(payload)),
             _ => Err(std::io::Error::new(
                 ErrorKind::InvalidInput,
                 format!(
@@ -201,7 +350,10 @@ fn read_null_terminated(buf: &mut Bytes) -> Result<Bytes> {
 
     loop {
         if !buf.has_remaining() {
-            panic!("no null-terminator in string");
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "no null-terminator in string",
+            ));
         }
 
         let byte = buf.get_u8();
@@ -219,15 +371,28 @@ fn read_null_terminated(buf: &mut Bytes) -> Result<Bytes> {
 #[derive(Debug)]
 pub enum BeMessage<'a> {
     AuthenticationOk,
+    AuthenticationCleartextPassword,
+    AuthenticationMd5Password(&'a [u8; 4]),
+    AuthenticationSasl(&'a [&'a str]),
+    AuthenticationSaslContinue(&'a [u8]),
+    AuthenticationSaslFinal(&'a [u8]),
+    /// The process id and the secret key of the session, with which the client can cancel the
+    /// running query.
+    BackendKeyData((i32, i32)),
     CommandComplete(BeCommandCompleteMessage),
     // Single byte - used in response to SSLRequest/GSSENCRequest.
-    EncryptionResponse,
+    EncryptionResponseSsl,
+    EncryptionResponseNo,
     EmptyQueryResponse,
     ParseComplete,
     BindComplete,
-    ParameterDescription,
+    CloseComplete,
+    ParameterDescription(&'a [TypeOid]),
     NoData,
+    PortalSuspended,
     DataRow(&'a Row),
+    /// A DataRow whose values are already encoded in the formats requested by the client.
+    EncodedDataRow(&'a [Option<Bytes>]),
     ParameterStatus(BeParameterStatusMessage<'a>),
     ReadyForQuery,
     RowDescription(&'a [PgFieldDescriptor]),
@@ -262,6 +427,69 @@ impl<'a> BeMessage<'a> {
                 buf.put_i32(0);
             }
 
+            // AuthenticationCleartextPassword
+            // +-----+----------+-----------+
+            // | 'R' | int32(8) | int32(3)  |
+            // +-----+----------+-----------+
+            BeMessage::AuthenticationCleartextPassword => {
+                buf.put_u8(b'R');
+                buf.put_i32(8);
+                buf.put_i32(3);
+            }
+
+            // AuthenticationMD5Password
+            // +-----+-----------+-----------+----------------+
+            // | 'R' | int32(12) | int32(5)  | byte4 salt     |
+            // +-----+-----------+-----------+----------------+
+            BeMessage::AuthenticationMd5Password(salt) => {
+                buf.put_u8(b'R');
+                buf.put_i32(12);
+                buf.put_i32(5);
+                buf.put_slice(&salt[..]);
+            }
+
+            // AuthenticationSASL
+            // +-----+-----------+-----------+---------------+-----+------+
+            // | 'R' | int32 len | int32(10) | str mechanism | ... | '\0' |
+            // +-----+-----------+-----------+---------------+-----+------+
+            BeMessage::AuthenticationSasl(mechanisms) => {
+                buf.put_u8(b'R');
+                write_body(buf, |buf| {
+                    buf.put_i32(10);
+                    for mechanism in *mechanisms {
+                        write_cstr(buf, mechanism.as_bytes())?;
+                    }
+                    buf.put_u8(0);
+                    Ok(())
+                })?;
+            }
+
+            // AuthenticationSASLContinue
+            // +-----+-----------+-----------+------------+
+            // | 'R' | int32 len | int32(11) | bytes data |
+            // +-----+-----------+-----------+------------+
+            BeMessage::AuthenticationSaslContinue(data) => {
+                buf.put_u8(b'R');
+                write_body(buf, |buf| {
+                    buf.put_i32(11);
+                    buf.put_slice(data);
+                    Ok(())
+                })?;
+            }
+
+            // AuthenticationSASLFinal
+            // +-----+-----------+-----------+------------+
+            // | 'R' | int32 len | int32(12) | bytes data |
+            // +-----+-----------+-----------+------------+
+            BeMessage::AuthenticationSaslFinal(data) => {
+                buf.put_u8(b'R');
+                write_body(buf, |buf| {
+                    buf.put_i32(12);
+                    buf.put_slice(data);
+                    Ok(())
+                })?;
+            }
+
             // ParameterStatus
             // +-----+-----------+----------+------+-----------+------+
             // | 'S' | int32 len | str name | '\0' | str value | '\0' |
@@ -341,20 +569,14 @@ impl<'a> BeMessage<'a> {
             //                          | int32 len | bytes |
             //                          +-----------+-------+
             BeMessage::DataRow(vals) => {
-                buf.put_u8(b'D');
-                write_body(buf, |buf| {
-                    buf.put_u16(vals.len() as u16); // num of cols
-                    for val_opt in vals.values() {
-                        if let Some(val) = val_opt {
-                            buf.put_u32(val.len() as u32);
-                            buf.put_slice(val.as_bytes());
-                        } else {
-                            buf.put_i32(-1);
-                        }
-                    }
-                    Ok(())
-                })
-                .unwrap();
+                let vals = vals
+                    .values()
+                    .iter()
+                    .map(|v| v.as_ref().map(|v| v.as_bytes()));
+                write_data_row(buf, vals);
+            }
+            BeMessage::EncodedDataRow(vals) => {
+                write_data_row(buf, vals.iter().map(|v| v.as_deref()));
             }
             // RowDescription
             // +-----+-----------+--------------+-------+-----+-------+
@@ -396,6 +618,16 @@ impl<'a> BeMessage<'a> {
                 buf.put_u8(b'I');
             }
 
+            // +-----+-----------+-----------------+-----------------+
+            // | 'K' | int32(12) | int32(process)  | int32(secret)   |
+            // +-----+-----------+-----------------+-----------------+
+            BeMessage::BackendKeyData((process_id, secret_key)) => {
+                buf.put_u8(b'K');
+                buf.put_i32(12);
+                buf.put_i32(*process_id);
+                buf.put_i32(*secret_key);
+            }
+
             BeMessage::ParseComplete => {
                 buf.put_u8(b'1');
                 write_body(buf, |_| Ok(()))?;
@@ -406,11 +638,22 @@ impl<'a> BeMessage<'a> {
                 write_body(buf, |_| Ok(()))?;
             }
 
-            BeMessage::ParameterDescription => {
+            BeMessage::CloseComplete => {
+                buf.put_u8(b'3');
+                write_body(buf, |_| Ok(()))?;
+            }
+
+            // ParameterDescription
+            // +-----+-----------+-----------------+-----------+-----+-----------+
+            // | 't' | int32 len | int16 paramNum  | int32 oid | ... | int32 oid |
+            // +-----+-----------+-----------------+-----------+-----+-----------+
+            BeMessage::ParameterDescription(param_types) => {
                 buf.put_u8(b't');
                 write_body(buf, |buf| {
-                    // we don't support params, so always 0
-                    buf.put_i16(0);
+                    buf.put_i16(param_types.len() as i16);
+                    for param_type in param_types.iter() {
+                        buf.put_i32(param_type.as_number());
+                    }
                     Ok(())
                 })
                 .unwrap();
@@ -421,7 +664,16 @@ impl<'a> BeMessage<'a> {
                 write_body(buf, |_| Ok(())).unwrap();
             }
 
-            BeMessage::EncryptionResponse => {
+            BeMessage::PortalSuspended => {
+                buf.put_u8(b's');
+                write_body(buf, |_| Ok(())).unwrap();
+            }
+
+            BeMessage::EncryptionResponseSsl => {
+                buf.put_u8(b'S');
+            }
+
+            BeMessage::EncryptionResponseNo => {
                 buf.put_u8(b'N');
             }
 
@@ -435,8 +687,8 @@ impl<'a> BeMessage<'a> {
             }
 
             BeMessage::ErrorResponse(error) => {
-                // For all the errors set Severity to Error and error code to
-                // 'internal error'.
+                // For all the errors set Severity to Error. The error code is 'internal error'
+                // unless the error carries its own SQLSTATE.
 
                 // 'E' signalizes ErrorResponse messages
                 buf.put_u8(b'E');
@@ -445,7 +697,7 @@ impl<'a> BeMessage<'a> {
                     write_cstr(buf, &Bytes::from("ERROR"))?;
 
                     buf.put_u8(b'C'); // SQLSTATE error code
-                    write_cstr(buf, &Bytes::from("XX000"))?;
+                    write_cstr(buf, sqlstate_of(error).as_bytes())?;
 
                     buf.put_u8(b'M'); // the message
                     write_cstr(buf, error.to_string().as_bytes())?;
@@ -513,11 +765,32 @@ fn write_cstr(buf: &mut BytesMut, s: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Writes a DataRow of the encoded values, where `None` is a null.
+fn write_data_row<'a>(buf: &mut BytesMut, vals: impl ExactSizeIterator<Item = Option<&'a [u8]>>) {
+    buf.put_u8(b'D');
+    write_body(buf, |buf| {
+        buf.put_u16(vals.len() as u16); // num of cols
+        for val_opt in vals {
+            if let Some(val) = val_opt {
+                buf.put_u32(val.len() as u32);
+                buf.put_slice(val);
+            } else {
+                buf.put_i32(-1);
+            }
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
 #[cfg(test)]
 mod tests {
-    use bytes::Bytes;
+    use bytes::{Bytes, BytesMut};
 
-    use crate::pg_message::FeQueryMessage;
+    use crate::error::PgError;
+    use crate::pg_message::{BeCommandCompleteMessage, BeMessage, FeQueryMessage};
+    use crate::pg_response::StatementType;
+    use crate::pg_server::BoxedError;
 
     #[tokio::test]
     async fn test_get_sql() {
@@ -530,4 +803,34 @@ mod tests {
         };
         assert!(fe.get_sql().is_err(), "{}", true);
     }
+
+    #[test]
+    fn test_error_response_sqlstate() {
+        let error_fields = |error: BoxedError| {
+            let mut buf = BytesMut::new();
+            BeMessage::write(&mut buf, &BeMessage::ErrorResponse(error)).unwrap();
+            String::from_utf8_lossy(&buf[5..]).to_string()
+        };
+        assert_eq!(error_fields("oops".into()), "SERROR\0CXX000\0Moops\0\0");
+        assert_eq!(
+            error_fields(Box::new(PgError::new("42601", "syntax error"))),
+            "SERROR\0C42601\0Msyntax error\0\0"
+        );
+    }
+    #[test]
+    fn test_command_complete_tag() {
+        let tag = |stmt_type, rows_cnt| {
+            let mut buf = BytesMut::new();
+            let msg = BeCommandCompleteMessage {
+                stmt_type,
+                notice: None,
+                rows_cnt,
+            };
+            BeMessage::write(&mut buf, &BeMessage::CommandComplete(msg)).unwrap();
+            String::from_utf8_lossy(&buf[5..]).to_string()
+        };
+        assert_eq!(tag(StatementType::INSERT, 3), "INSERT 0 3\0");
+        assert_eq!(tag(StatementType::CREATE_TABLE_AS, 3), "SELECT 3\0");
+        assert_eq!(tag(StatementType::CREATE_TABLE, 0), "CREATE_TABLE\0");
+    }
 }
@@ -12,19 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::{Error as IoError, ErrorKind, Result};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Result};
 use std::sync::Arc;
 
 use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_rustls::TlsAcceptor;
+use tracing::Instrument;
 
 use crate::error::PsqlError;
+use crate::pg_auth::{ScramExchange, UserAuthenticator, SCRAM_SHA_256};
+use crate::pg_extended::{encode_row, PgPortal, PgStatement};
 use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_message::{
-    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeMessage, FeStartupMessage,
+    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeBindMessage,
+    FeDescribeMessage, FeExecuteMessage, FeMessage, FeParseMessage, FePasswordMessage,
+    FeStartupMessage,
 };
 use crate::pg_response::PgResponse;
-use crate::pg_server::{Session, SessionManager};
+use crate::pg_server::{BoxedError, Session, SessionManager};
+use crate::pg_ssl::PgStream;
 
 /// The state machine for each psql connection.
 /// Read pg messages from tcp stream and write results back.
@@ -32,8 +41,9 @@ pub struct PgProtocol<S, SM>
 where
     SM: SessionManager,
 {
-    /// Used for write/read message in tcp connection.
-    stream: S,
+    /// Used for write/read message in tcp connection. It's only taken out when being upgraded to
+    /// SSL.
+    stream: Option<PgStream<S>>,
     /// Write into buffer before flush to stream.
     buf_out: BytesMut,
     /// Current states of pg connection.
@@ -43,11 +53,28 @@ where
 
     session_mgr: Arc<SM>,
     session: Option<Arc<SM::Session>>,
+
+    /// Prepared statements and portals of the extended query protocol, by name. The unnamed ones
+    /// are stored with an empty name.
+    prepared_statements: HashMap<String, PgStatement>,
+    portals: HashMap<String, PgPortal>,
+    /// Set when an error occurs in processing an extended query message, after which the messages
+    /// are discarded until a Sync.
+    ignore_till_sync: bool,
+
+    /// Used for the TLS handshake if the client asks for SSL. `None` if SSL is not enabled.
+    tls_acceptor: Option<TlsAcceptor>,
+    /// Whether to reject the clients that don't ask for SSL.
+    require_ssl: bool,
+    /// The state of the SCRAM exchange after the client sends the first message.
+    scram_exchange: Option<ScramExchange>,
 }
 
 /// States flow happened from top to down.
 enum PgProtocolState {
     Startup,
+    /// Waiting for the password of the user.
+    Authenticate,
     Regular,
 }
 
@@ -69,24 +96,37 @@ where
 {
     pub fn new(stream: S, session_mgr: Arc<SM>) -> Self {
         Self {
-            stream,
+            stream: Some(PgStream::Unencrypted(stream)),
             is_terminate: false,
             state: PgProtocolState::Startup,
             buf_out: BytesMut::with_capacity(10 * 1024),
             session_mgr,
             session: None,
+            prepared_statements: HashMap::new(),
+            portals: HashMap::new(),
+            ignore_till_sync: false,
+            tls_acceptor: None,
+            require_ssl: false,
+            scram_exchange: None,
         }
     }
 
-    pub async fn process(&mut self, unnamed_query_string: &mut Bytes) -> Result<bool> {
-        if self.do_process(unnamed_query_string).await? {
+    #[must_use]
+    pub fn with_ssl(mut self, tls_acceptor: TlsAcceptor, require_ssl: bool) -> Self {
+        self.tls_acceptor = Some(tls_acceptor);
+        self.require_ssl = require_ssl;
+        self
+    }
+
+    pub async fn process(&mut self) -> Result<bool> {
+        if self.do_process().await? {
             return Ok(true);
         }
 
         Ok(self.is_terminate())
     }
 
-    async fn do_process(&mut self, unnamed_query_string: &mut Bytes) -> Result<bool> {
+    async fn do_process(&mut self) -> Result<bool> {
         let msg = match self.read_message().await {
             Ok(msg) => msg,
             Err(e) => {
@@ -99,58 +139,100 @@ where
                 return Ok(false);
             }
         };
+        if self.ignore_till_sync && !matches!(msg, FeMessage::Sync | FeMessage::Terminate) {
+            return Ok(false);
+        }
+        if matches!(self.state, PgProtocolState::Authenticate)
+            && !matches!(msg, FeMessage::Password(_) | FeMessage::Terminate)
+        {
+            self.write_message_no_flush(&BeMessage::ErrorResponse(
+                "expected a password message".into(),
+            ))?;
+            self.process_terminate();
+            self.flush().await?;
+            return Ok(true);
+        }
         match msg {
             FeMessage::Ssl => {
-                self.write_message_no_flush(&BeMessage::EncryptionResponse)
-                    .map_err(|e| {
-                        tracing::error!("failed to handle ssl request: {}", e);
-                        e
-                    })?;
+                if let Err(e) = self.process_ssl_msg().await {
+                    // The state of the stream is unknown after a failed handshake.
+                    tracing::error!("failed to handle ssl request: {}", e);
+                    self.process_terminate();
+                    return Ok(true);
+                }
+            }
+            FeMessage::Startup(_) if self.require_ssl && !self.is_encrypted() => {
+                self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(
+                    PsqlError::SslRequiredError,
+                )))?;
+                self.process_terminate();
             }
             FeMessage::Startup(msg) => {
-                self.process_startup_msg(msg).map_err(|e| {
+                if let Err(e) = self.process_startup_msg(msg) {
                     tracing::error!("failed to set up pg session: {}", e);
-                    e
-                })?;
-                self.state = PgProtocolState::Regular;
+                    self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
+                    self.process_terminate();
+                }
+            }
+            FeMessage::Password(msg) => {
+                if let Err(e) = self.process_password_msg(msg) {
+                    tracing::error!("failed to authenticate: {}", e);
+                    self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
+                    self.process_terminate();
+                }
             }
             FeMessage::Query(query_msg) => {
-                self.process_query_msg(query_msg.get_sql(), false).await?;
+                // A simple query destroys the unnamed prepared statement and portal.
+                self.prepared_statements.remove("");
+                self.portals.remove("");
+                self.process_query_msg(query_msg.get_sql()).await?;
                 self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
             }
-            FeMessage::CancelQuery => {
-                self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(
-                    PsqlError::cancel(),
-                )))?;
+            FeMessage::CancelQuery(m) => {
+                // The cancel request comes in a new connection, which is closed without any
+                // response.
+                self.session_mgr
+                    .cancel_queries_in_session((m.process_id, m.secret_key));
+                self.process_terminate();
             }
             FeMessage::Terminate => {
                 self.process_terminate();
             }
             FeMessage::Parse(m) => {
-                *unnamed_query_string = m.query_string;
-                self.write_message(&BeMessage::ParseComplete).await?;
+                if let Err(e) = self.process_parse_msg(m) {
+                    self.process_extended_error(e)?;
+                }
             }
-            FeMessage::Bind(_) => {
-                self.write_message(&BeMessage::BindComplete).await?;
+            FeMessage::Bind(m) => {
+                if let Err(e) = self.process_bind_msg(m) {
+                    self.process_extended_error(e)?;
+                }
+            }
+            FeMessage::Describe(m) => {
+                if let Err(e) = self.process_describe_msg(m).await {
+                    self.process_extended_error(e)?;
+                }
             }
-            FeMessage::Execute(_) => {
-                self.process_query_msg(cstr_to_str(unnamed_query_string), true)
-                    .await?;
+            FeMessage::Execute(m) => {
+                if let Err(e) = self.process_execute_msg(m).await {
+                    self.process_extended_error(e)?;
+                }
                 // NOTE there is no ReadyForQuery message.
             }
-            FeMessage::Describe(_) => {
-                self.write_message_no_flush(&BeMessage::ParameterDescription)?;
-                // FIXME: Introduce parser to analyze statements and bind data type. Here just
-                // hard-code a VARCHAR.
-                self.write_message(&BeMessage::RowDescription(&[PgFieldDescriptor::new(
-                    "VARCHAR".to_string(),
-                    TypeOid::Varchar,
-                )]))
-                .await?;
+            FeMessage::Close(m) => {
+                let name = cstr_to_str(&m.name)?.to_string();
+                if m.kind == b'S' {
+                    self.prepared_statements.remove(&name);
+                } else {
+                    self.portals.remove(&name);
+                }
+                self.write_message_no_flush(&BeMessage::CloseComplete)?;
             }
             FeMessage::Sync => {
-                self.write_message(&BeMessage::ReadyForQuery).await?;
+                self.ignore_till_sync = false;
+                self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
             }
+            FeMessage::Flush => {}
         }
         self.flush().await?;
         Ok(false)
@@ -158,14 +240,100 @@ where
 
     async fn read_message(&mut self) -> Result<FeMessage> {
         match self.state {
-            PgProtocolState::Startup => FeStartupMessage::read(&mut self.stream).await,
-            PgProtocolState::Regular => FeMessage::read(&mut self.stream).await,
+            PgProtocolState::Startup => FeStartupMessage::read(self.stream()).await,
+            PgProtocolState::Authenticate | PgProtocolState::Regular => {
+                FeMessage::read(self.stream()).await
+            }
         }
     }
 
-    fn process_startup_msg(&mut self, _msg: FeStartupMessage) -> Result<()> {
+    /// Accepts the SSL request and performs the TLS handshake if SSL is enabled, otherwise tells
+    /// the client to go on unencrypted.
+    async fn process_ssl_msg(&mut self) -> Result<()> {
+        let tls_acceptor = match &self.tls_acceptor {
+            Some(tls_acceptor) if !self.is_encrypted() => tls_acceptor.clone(),
+            _ => return self.write_message(&BeMessage::EncryptionResponseNo).await,
+        };
+        self.write_message(&BeMessage::EncryptionResponseSsl)
+            .await?;
+        let stream = self.stream.take().unwrap();
+        self.stream = Some(stream.upgrade(&tls_acceptor).await?);
+        Ok(())
+    }
+
+    fn process_startup_msg(
+        &mut self,
+        msg: FeStartupMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let user_name = msg
+            .config
+            .get("user")
+            .map(String::as_str)
+            .unwrap_or_default();
         // TODO: Replace `DEFAULT_DATABASE_NAME` with true database name in `FeStartupMessage`.
-        self.session = Some(self.session_mgr.connect("dev").map_err(IoError::other)?);
+        let session = self.session_mgr.connect("dev", user_name)?;
+        self.session = Some(session.clone());
+        match session.user_authenticator() {
+            UserAuthenticator::None => {
+                self.finish_startup()?;
+                return Ok(());
+            }
+            UserAuthenticator::ClearText(_) => {
+                self.write_message_no_flush(&BeMessage::AuthenticationCleartextPassword)?;
+            }
+            UserAuthenticator::Md5WithSalt { salt, .. } => {
+                self.write_message_no_flush(&BeMessage::AuthenticationMd5Password(salt))?;
+            }
+            UserAuthenticator::ScramSha256(_) => {
+                self.write_message_no_flush(&BeMessage::AuthenticationSasl(&[SCRAM_SHA_256]))?;
+            }
+        }
+        self.state = PgProtocolState::Authenticate;
+        Ok(())
+    }
+
+    /// Checks the password, or goes on with the SCRAM exchange. The session is ready once the
+    /// user is authenticated.
+    fn process_password_msg(
+        &mut self,
+        msg: FePasswordMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        if !matches!(self.state, PgProtocolState::Authenticate) {
+            return Err("unexpected password message".into());
+        }
+        let session = self.session.clone().unwrap();
+        match session.user_authenticator() {
+            UserAuthenticator::ScramSha256(verifier) => match self.scram_exchange.take() {
+                None => {
+                    let (mechanism, client_first) = msg.sasl_initial_response()?;
+                    if mechanism != SCRAM_SHA_256 {
+                        return Err(format!("unsupported SASL mechanism: {}", mechanism).into());
+                    }
+                    let (exchange, server_first) = verifier.server_first(&client_first)?;
+                    self.scram_exchange = Some(exchange);
+                    self.write_message_no_flush(&BeMessage::AuthenticationSaslContinue(
+                        server_first.as_bytes(),
+                    ))?;
+                    return Ok(());
+                }
+                Some(exchange) => {
+                    let server_final = verifier.server_final(&exchange, &msg.payload)?;
+                    self.write_message_no_flush(&BeMessage::AuthenticationSaslFinal(
+                        server_final.as_bytes(),
+                    ))?;
+                }
+            },
+            authenticator => {
+                if !authenticator.authenticate(&msg.password()?) {
+                    return Err("password authentication failed".into());
+                }
+            }
+        }
+        self.finish_startup()?;
+        Ok(())
+    }
+
+    fn finish_startup(&mut self) -> Result<()> {
         self.write_message_no_flush(&BeMessage::AuthenticationOk)?;
         self.write_message_no_flush(&BeMessage::ParameterStatus(
             BeParameterStatusMessage::ClientEncoding("utf8"),
@@ -176,7 +344,10 @@ where
         self.write_message_no_flush(&BeMessage::ParameterStatus(
             BeParameterStatusMessage::ServerVersion("9.5.0"),
         ))?;
+        let session_id = self.session.as_ref().unwrap().id();
+        self.write_message_no_flush(&BeMessage::BackendKeyData(session_id))?;
         self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
+        self.state = PgProtocolState::Regular;
         Ok(())
     }
 
@@ -184,68 +355,292 @@ where
         self.is_terminate = true;
     }
 
-    async fn process_query_msg(
-        &mut self,
-        query_string: Result<&str>,
-        extended: bool,
-    ) -> Result<()> {
-        match query_string {
-            Ok(sql) => {
-                tracing::trace!("receive query: {}", sql);
-                let session = self.session.clone().unwrap();
-                // execute query
-                let process_res = session.run_statement(sql).await;
-                match process_res {
-                    Ok(res) => {
-                        if res.is_empty() {
-                            self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
-                        } else if res.is_query() {
-                            self.process_query_with_results(res, extended).await?;
-                        } else {
-                            self.write_message_no_flush(&BeMessage::CommandComplete(
-                                BeCommandCompleteMessage {
-                                    stmt_type: res.get_stmt_type(),
-                                    notice: res.get_notice(),
-                                    rows_cnt: res.get_effected_rows_cnt(),
-                                },
-                            ))?;
-                        }
-                    }
-                    Err(e) => {
-                        self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
-                    }
-                }
-            }
+    /// Tells the session manager that the session of this connection is closed.
+    pub fn end_session(&mut self) {
+        if let Some(session) = self.session.take() {
+            self.session_mgr.end_session(&session);
+        }
+    }
+
+    /// Runs the statements of a simple query one by one, each followed by its own
+    /// CommandComplete. Once a statement fails, the rest of them are skipped.
+    async fn process_query_msg(&mut self, query_string: Result<&str>) -> Result<()> {
+        let sql = match query_string {
+            Ok(sql) => sql,
             Err(err) => {
-                self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(err)))?;
+                return self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(err)));
             }
         };
-
+        tracing::trace!("receive query: {}", sql);
+        let session = self.session.clone().unwrap();
+        let stmts = match session.split_statements(sql) {
+            Ok(stmts) => stmts,
+            Err(e) => return self.write_message_no_flush(&BeMessage::ErrorResponse(e)),
+        };
+        if stmts.is_empty() {
+            return self.write_message_no_flush(&BeMessage::EmptyQueryResponse);
+        }
+        for stmt in stmts {
+            let span = tracing::trace_span!("statement", sql = %stmt);
+            if !self
+                .process_statement(session.clone(), &stmt)
+                .instrument(span)
+                .await?
+            {
+                break;
+            }
+        }
         Ok(())
     }
 
-    async fn process_query_with_results(&mut self, res: PgResponse, extended: bool) -> Result<()> {
-        // The possible responses to Execute are the same as those described above for queries
-        // issued via simple query protocol, except that Execute doesn't cause ReadyForQuery or
-        // RowDescription to be issued.
-        // Quoted from: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY
-        if !extended {
-            self.write_message(&BeMessage::RowDescription(&res.get_row_desc()))
-                .await?;
+    /// Runs a single statement of a simple query. Returns whether it succeeds.
+    async fn process_statement(&mut self, session: Arc<SM::Session>, sql: &str) -> Result<bool> {
+        match session.run_statement(sql).await {
+            Ok(res) => {
+                if res.is_empty() {
+                    self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
+                } else if res.is_query() {
+                    return self.process_query_with_results(res).await;
+                } else {
+                    self.write_command_complete(&res, res.get_effected_rows_cnt())?;
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
+                Ok(false)
+            }
         }
+    }
+
+    /// Sends the rows set by set as they are produced. The next set is pulled only after the
+    /// previous one is flushed, so a slow client holds back the query instead of piling up the rows
+    /// in memory.
+    /// Returns whether all rows are sent without errors.
+    async fn process_query_with_results(&mut self, mut res: PgResponse) -> Result<bool> {
+        self.write_message(&BeMessage::RowDescription(&res.get_row_desc()))
+            .await?;
 
         let mut rows_cnt = 0;
-        let iter = res.iter();
-        for val in iter {
-            self.write_message(&BeMessage::DataRow(val)).await?;
-            rows_cnt += 1;
+        while let Some(rows) = res.values_stream().next().await {
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => {
+                    // The query fails after some rows may have been sent.
+                    self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
+                    return Ok(false);
+                }
+            };
+            for row in &rows {
+                self.write_message_no_flush(&BeMessage::DataRow(row))?;
+            }
+            rows_cnt += rows.len() as i32;
+            self.flush().await?;
+        }
+        self.write_command_complete(&res, rows_cnt)?;
+        Ok(true)
+    }
+
+    /// Reports an error in processing an extended query message, and discards the following
+    /// messages until a Sync.
+    fn process_extended_error(&mut self, e: BoxedError) -> Result<()> {
+        self.ignore_till_sync = true;
+        self.write_message_no_flush(&BeMessage::ErrorResponse(e))
+    }
+
+    fn process_parse_msg(&mut self, msg: FeParseMessage) -> std::result::Result<(), BoxedError> {
+        let name = cstr_to_str(&msg.statement_name)?.to_string();
+        let sql = cstr_to_str(&msg.query_string)?.to_string();
+        tracing::trace!("parse query: {}", sql);
+        if !name.is_empty() && self.prepared_statements.contains_key(&name) {
+            return Err(format!("prepared statement \"{}\" already exists", name).into());
+        }
+        let param_types = msg
+            .type_ids
+            .iter()
+            .map(|&oid| {
+                if oid == 0 {
+                    return Ok(None);
+                }
+                TypeOid::from_number(oid)
+                    .map(Some)
+                    .ok_or_else(|| format!("unsupported parameter type oid: {}", oid))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let session = self.session.clone().unwrap();
+        let description = session.describe_statement(&sql, param_types)?;
+        self.prepared_statements
+            .insert(name, PgStatement::new(sql, description));
+        self.write_message_no_flush(&BeMessage::ParseComplete)?;
+        Ok(())
+    }
+
+    fn process_bind_msg(&mut self, msg: FeBindMessage) -> std::result::Result<(), BoxedError> {
+        let statement_name = cstr_to_str(&msg.statement_name)?;
+        let portal_name = cstr_to_str(&msg.portal_name)?.to_string();
+        if !portal_name.is_empty() && self.portals.contains_key(&portal_name) {
+            return Err(format!("portal \"{}\" already exists", portal_name).into());
         }
+        let statement = self
+            .prepared_statements
+            .get(statement_name)
+            .ok_or_else(|| format!("prepared statement \"{}\" does not exist", statement_name))?;
+        let portal = statement.bind(
+            &msg.param_format_codes,
+            &msg.params,
+            &msg.result_format_codes,
+        )?;
+        self.portals.insert(portal_name, portal);
+        self.write_message_no_flush(&BeMessage::BindComplete)?;
+        Ok(())
+    }
+
+    async fn process_describe_msg(
+        &mut self,
+        msg: FeDescribeMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let name = cstr_to_str(&msg.name)?.to_string();
+        if msg.kind == b'S' {
+            let statement = self
+                .prepared_statements
+                .get(&name)
+                .ok_or_else(|| format!("prepared statement \"{}\" does not exist", name))?;
+            let param_types = statement.param_types().to_vec();
+            // The fields of a statement that can't be described without executing it are left
+            // to the Describe of the portal.
+            let row_description = statement.row_description().unwrap_or_default().to_vec();
+            self.write_message_no_flush(&BeMessage::ParameterDescription(&param_types))?;
+            self.write_row_description(&row_description)?;
+        } else {
+            let mut portal = self
+                .portals
+                .remove(&name)
+                .ok_or_else(|| format!("portal \"{}\" does not exist", name))?;
+            let res = self.describe_portal(&mut portal).await;
+            self.portals.insert(name, portal);
+            self.write_row_description(&res?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the fields of the rows returned by the portal. If they can't be known without
+    /// executing it, the portal is executed now, and the rows are held for the following Execute.
+    async fn describe_portal(
+        &mut self,
+        portal: &mut PgPortal,
+    ) -> std::result::Result<Vec<PgFieldDescriptor>, BoxedError> {
+        if portal.row_description().is_none() {
+            self.execute_portal(portal).await?;
+        }
+        Ok(portal.row_description().unwrap().to_vec())
+    }
+
+    async fn execute_portal(
+        &mut self,
+        portal: &mut PgPortal,
+    ) -> std::result::Result<(), BoxedError> {
+        if portal.result().is_none() {
+            tracing::trace!("execute query: {}", portal.query_string());
+            let session = self.session.clone().unwrap();
+            let res = session.run_portal(portal).await?;
+            portal.set_result(res)?;
+        }
+        Ok(())
+    }
+
+    async fn process_execute_msg(
+        &mut self,
+        msg: FeExecuteMessage,
+    ) -> std::result::Result<(), BoxedError> {
+        let name = cstr_to_str(&msg.portal_name)?.to_string();
+        let mut portal = self
+            .portals
+            .remove(&name)
+            .ok_or_else(|| format!("portal \"{}\" does not exist", name))?;
+        let res = self
+            .execute_and_fetch(&mut portal, msg.max_rows)
+            .instrument(tracing::trace_span!("portal", name = %name))
+            .await;
+        self.portals.insert(name, portal);
+        res
+    }
+
+    /// Executes the portal if it's not yet, and sends at most `max_rows` rows of it that haven't
+    /// been sent. If there're rows left afterwards, the portal is suspended and the rest can be
+    /// fetched by another Execute.
+    async fn execute_and_fetch(
+        &mut self,
+        portal: &mut PgPortal,
+        max_rows: i32,
+    ) -> std::result::Result<(), BoxedError> {
+        self.execute_portal(portal).await?;
+        let res = portal.result().unwrap();
+        if res.is_empty() {
+            self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
+        } else if res.is_query() {
+            // The possible responses to Execute are the same as those described above for queries
+            // issued via simple query protocol, except that Execute doesn't cause ReadyForQuery
+            // or RowDescription to be issued.
+            // Quoted from: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY
+            let stmt_type = res.get_stmt_type();
+            let notice = res.get_notice();
+            let fields = portal.row_description().unwrap().to_vec();
+            let max_rows = if max_rows > 0 {
+                max_rows as usize
+            } else {
+                usize::MAX
+            };
+            let mut rows_cnt = 0;
+            while rows_cnt < max_rows {
+                let rows = portal.fetch(max_rows - rows_cnt).await?;
+                if rows.is_empty() {
+                    break;
+                }
+                for row in &rows {
+                    let values = encode_row(row, &fields)?;
+                    self.write_message_no_flush(&BeMessage::EncodedDataRow(&values))?;
+                }
+                rows_cnt += rows.len();
+                self.flush().await?;
+            }
+            if rows_cnt == max_rows && portal.has_more_rows().await? {
+                self.write_message_no_flush(&BeMessage::PortalSuspended)?;
+            } else {
+                self.write_message_no_flush(&BeMessage::CommandComplete(
+                    BeCommandCompleteMessage {
+                        stmt_type,
+                        notice,
+                        rows_cnt: rows_cnt as i32,
+                    },
+                ))?;
+            }
+        } else {
+            let rows_cnt = res.get_effected_rows_cnt();
+            let msg = BeMessage::CommandComplete(BeCommandCompleteMessage {
+                stmt_type: res.get_stmt_type(),
+                notice: res.get_notice(),
+                rows_cnt,
+            });
+            self.write_message_no_flush(&msg)?;
+        }
+        Ok(())
+    }
+
+    fn write_row_description(&mut self, row_description: &[PgFieldDescriptor]) -> Result<()> {
+        if row_description.is_empty() {
+            self.write_message_no_flush(&BeMessage::NoData)
+        } else {
+            self.write_message_no_flush(&BeMessage::RowDescription(row_description))
+        }
+    }
+
+    fn write_command_complete(&mut self, res: &PgResponse, rows_cnt: i32) -> Result<()> {
         self.write_message_no_flush(&BeMessage::CommandComplete(BeCommandCompleteMessage {
             stmt_type: res.get_stmt_type(),
             notice: res.get_notice(),
             rows_cnt,
-        }))?;
-        Ok(())
+        }))
     }
 
     fn is_terminate(&self) -> bool {
@@ -263,9 +658,18 @@ where
     }
 
     async fn flush(&mut self) -> Result<()> {
-        self.stream.write_all(&self.buf_out).await?;
+        let stream = self.stream.as_mut().unwrap();
+        stream.write_all(&self.buf_out).await?;
         self.buf_out.clear();
-        self.stream.flush().await?;
+        stream.flush().await?;
         Ok(())
     }
+
+    fn stream(&mut self) -> &mut PgStream<S> {
+        self.stream.as_mut().unwrap()
+    }
+
+    fn is_encrypted(&self) -> bool {
+        self.stream.as_ref().unwrap().is_encrypted()
+    }
 }
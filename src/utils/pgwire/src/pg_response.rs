@@ -14,8 +14,18 @@
 
 use std::fmt::Formatter;
 
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+
 use crate::pg_field_descriptor::PgFieldDescriptor;
+use crate::pg_server::BoxedError;
 use crate::types::Row;
+
+/// A set of rows of a query result, or the error that fails the query.
+pub type RowSetResult = Result<Vec<Row>, BoxedError>;
+/// The rows of a query result, which are produced set by set while the query runs, so that they
+/// can be sent to the client without holding all of them in memory.
+pub type RowSetStream = BoxStream<'static, RowSetResult>;
 /// Port from StatementType.java.
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -30,17 +40,27 @@ pub enum StatementType {
     COPY,
     EXPLAIN,
     CREATE_TABLE,
+    // `CREATE TABLE AS` completes with the tag `SELECT n` like in Postgres, where n is the number
+    // of rows inserted, but it doesn't return the rows as a query does.
+    CREATE_TABLE_AS,
     CREATE_MATERIALIZED_VIEW,
     CREATE_SOURCE,
+    CREATE_SINK,
     CREATE_DATABASE,
     CREATE_SCHEMA,
+    CREATE_USER,
     DESCRIBE_TABLE,
     DROP_TABLE,
     DROP_MATERIALIZED_VIEW,
     DROP_INDEX,
     DROP_SOURCE,
+    DROP_SINK,
     DROP_SCHEMA,
     DROP_DATABASE,
+    DROP_USER,
+    ALTER_SOURCE,
+    ALTER_MATERIALIZED_VIEW,
+    ALTER_SYSTEM,
     // Introduce ORDER_BY statement type cuz Calcite unvalidated AST has SqlKind.ORDER_BY. Note
     // that Statement Type is not designed to be one to one mapping with SqlKind.
     ORDER_BY,
@@ -57,19 +77,34 @@ pub enum StatementType {
 
 impl std::fmt::Display for StatementType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            StatementType::CREATE_TABLE_AS => write!(f, "SELECT"),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
-#[derive(Debug)]
 pub struct PgResponse {
     stmt_type: StatementType,
+    /// The number of affected rows of a command. The rows of a query are counted when they are
+    /// sent, since they are not known in advance.
     row_cnt: i32,
     notice: Option<String>,
-    values: Vec<Row>,
+    values_stream: RowSetStream,
     row_desc: Vec<PgFieldDescriptor>,
 }
 
+impl std::fmt::Debug for PgResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgResponse")
+            .field("stmt_type", &self.stmt_type)
+            .field("row_cnt", &self.row_cnt)
+            .field("notice", &self.notice)
+            .field("row_desc", &self.row_desc)
+            .finish()
+    }
+}
+
 impl StatementType {
     pub fn is_command(&self) -> bool {
         matches!(
@@ -81,6 +116,7 @@ impl StatementType {
                 | StatementType::COPY
                 | StatementType::FETCH
                 | StatementType::SELECT
+                | StatementType::CREATE_TABLE_AS
         )
     }
 }
@@ -91,11 +127,27 @@ impl PgResponse {
         row_cnt: i32,
         values: Vec<Row>,
         row_desc: Vec<PgFieldDescriptor>,
+    ) -> Self {
+        Self::new_for_stream(
+            stmt_type,
+            row_cnt,
+            stream::iter([Ok(values)]).boxed(),
+            row_desc,
+        )
+    }
+
+    /// Creates a response whose rows are produced by `values_stream`, which is polled only as
+    /// fast as the rows are sent to the client.
+    pub fn new_for_stream(
+        stmt_type: StatementType,
+        row_cnt: i32,
+        values_stream: RowSetStream,
+        row_desc: Vec<PgFieldDescriptor>,
     ) -> Self {
         Self {
             stmt_type,
             row_cnt,
-            values,
+            values_stream,
             row_desc,
             notice: None,
         }
@@ -107,11 +159,8 @@ impl PgResponse {
 
     pub fn empty_result_with_notice(stmt_type: StatementType, notice: String) -> Self {
         Self {
-            stmt_type,
-            row_cnt: 0,
-            values: vec![],
-            row_desc: vec![],
             notice: Some(notice),
+            ..Self::empty_result(stmt_type)
         }
     }
 
@@ -145,7 +194,7 @@ impl PgResponse {
         self.row_desc.clone()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Row> + '_ {
-        self.values.iter()
+    pub fn values_stream(&mut self) -> &mut RowSetStream {
+        &mut self.values_stream
     }
 }
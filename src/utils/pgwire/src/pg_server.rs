@@ -18,18 +18,35 @@ use std::result::Result;
 use std::sync::Arc;
 
 use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
 
+use crate::pg_auth::UserAuthenticator;
+use crate::pg_extended::{PgPortal, StatementDescription};
+use crate::pg_field_descriptor::TypeOid;
 use crate::pg_protocol::PgProtocol;
 use crate::pg_response::PgResponse;
+use crate::pg_ssl::{build_tls_acceptor, SslConfig};
 
 pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+/// The process id and the secret key of a session, which are sent to the client in
+/// `BackendKeyData` and identify the session in a `CancelRequest`.
+pub type SessionId = (i32, i32);
 
 /// The interface for a database system behind pgwire protocol.
 /// We can mock it for testing purpose.
 pub trait SessionManager: Send + Sync + 'static {
     type Session: Session;
 
-    fn connect(&self, database: &str) -> Result<Arc<Self::Session>, BoxedError>;
+    /// Creates a session for `user_name`, which fails if the user can't login. The session isn't
+    /// usable until the user is authenticated by [`Session::user_authenticator`].
+    fn connect(&self, database: &str, user_name: &str) -> Result<Arc<Self::Session>, BoxedError>;
+
+    /// Cancels the running query of the session with `session_id`, if any. Does nothing if no
+    /// session matches, as required by the protocol.
+    fn cancel_queries_in_session(&self, session_id: SessionId);
+
+    /// Called when the connection of `session` is closed.
+    fn end_session(&self, session: &Self::Session);
 }
 
 /// A psql connection. Each connection binds with a database. Switching database will need to
@@ -37,22 +54,61 @@ pub trait SessionManager: Send + Sync + 'static {
 #[async_trait::async_trait]
 pub trait Session: Send + Sync {
     async fn run_statement(self: Arc<Self>, sql: &str) -> Result<PgResponse, BoxedError>;
+
+    /// Splits the query string of a simple query, which may hold several statements separated by
+    /// semicolons, into the statements to run one by one with [`Session::run_statement`]. No
+    /// statement runs if the query string fails to be split, e.g., on a syntax error. By default,
+    /// the query string is run as a single statement.
+    fn split_statements(&self, sql: &str) -> Result<Vec<String>, BoxedError> {
+        Ok(vec![sql.to_string()])
+    }
+
+    /// Describes a statement with parameters `$1`, `$2`, ... for the extended query protocol.
+    /// `param_types` are the types specified by the client, and the others should be inferred.
+    fn describe_statement(
+        self: Arc<Self>,
+        sql: &str,
+        param_types: Vec<Option<TypeOid>>,
+    ) -> Result<StatementDescription, BoxedError>;
+
+    /// Executes a portal, i.e., a prepared statement with its parameters bound. By default, the
+    /// query string with the parameters substituted by their values is run like a simple query.
+    /// Implementations may override it to reuse the plan of the statement across executions.
+    async fn run_portal(self: Arc<Self>, portal: &PgPortal) -> Result<PgResponse, BoxedError> {
+        self.run_statement(portal.query_string()).await
+    }
+
+    /// How the user of the session is authenticated.
+    fn user_authenticator(&self) -> &UserAuthenticator;
+
+    /// The id sent to the client in `BackendKeyData`.
+    fn id(&self) -> SessionId;
 }
 
-/// Binds a Tcp listener at `addr`. Spawn a coroutine to serve every new connection.
-pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::Result<()> {
+/// Binds a Tcp listener at `addr`. Spawn a coroutine to serve every new connection. SSL is
+/// enabled if `ssl_config` is given.
+pub async fn pg_serve(
+    addr: &str,
+    session_mgr: Arc<impl SessionManager>,
+    ssl_config: Option<SslConfig>,
+) -> io::Result<()> {
+    let ssl = match ssl_config {
+        Some(config) => Some((build_tls_acceptor(&config)?, config.require_ssl)),
+        None => None,
+    };
     let listener = TcpListener::bind(addr).await.unwrap();
     // accept connections and process them, spawning a new thread for each one
     tracing::info!("Server Listening at {}", addr);
     loop {
         let session_mgr = session_mgr.clone();
+        let ssl = ssl.clone();
         let conn_ret = listener.accept().await;
         match conn_ret {
             Ok((stream, peer_addr)) => {
                 tracing::info!("New connection: {}", peer_addr);
                 tokio::spawn(async move {
                     // connection succeeded
-                    pg_serve_conn(stream, session_mgr).await;
+                    pg_serve_conn(stream, session_mgr, ssl).await;
                     tracing::info!("Connection {} closed", peer_addr);
                 });
             }
@@ -64,11 +120,17 @@ pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::
     }
 }
 
-async fn pg_serve_conn(socket: TcpStream, session_mgr: Arc<impl SessionManager>) {
+async fn pg_serve_conn(
+    socket: TcpStream,
+    session_mgr: Arc<impl SessionManager>,
+    ssl: Option<(TlsAcceptor, bool)>,
+) {
     let mut pg_proto = PgProtocol::new(socket, session_mgr);
-    let mut unnamed_query_string = bytes::Bytes::new();
+    if let Some((tls_acceptor, require_ssl)) = ssl {
+        pg_proto = pg_proto.with_ssl(tls_acceptor, require_ssl);
+    }
     loop {
-        let terminate = pg_proto.process(&mut unnamed_query_string).await;
+        let terminate = pg_proto.process().await;
         match terminate {
             Ok(is_ter) => {
                 if is_ter {
@@ -85,21 +147,32 @@ async fn pg_serve_conn(socket: TcpStream, session_mgr: Arc<impl SessionManager>)
             }
         }
     }
+    pg_proto.end_session();
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
-    use tokio_postgres::NoTls;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_postgres::{NoTls, SimpleQueryMessage};
 
+    use crate::pg_auth::{md5_hash_password, ScramVerifier, UserAuthenticator};
+    use crate::pg_extended::{PgPortal, StatementDescription};
     use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
     use crate::pg_response::{PgResponse, StatementType};
-    use crate::pg_server::{pg_serve, Session, SessionManager};
+    use crate::pg_server::{pg_serve, Session, SessionId, SessionManager};
+    use crate::pg_ssl::SslConfig;
     use crate::types::Row;
 
-    struct MockSessionManager {}
+    const MOCK_SESSION_ID: SessionId = (1, 42);
+
+    #[derive(Default)]
+    struct MockSessionManager {
+        cancelled_sessions: Mutex<Vec<SessionId>>,
+    }
 
     impl SessionManager for MockSessionManager {
         type Session = MockSession;
@@ -107,12 +180,30 @@ mod tests {
         fn connect(
             &self,
             _database: &str,
+            user_name: &str,
         ) -> Result<Arc<Self::Session>, Box<dyn Error + Send + Sync>> {
-            Ok(Arc::new(MockSession {}))
+            let user_authenticator = match user_name {
+                "clear_user" => UserAuthenticator::ClearText(b"pwd".to_vec()),
+                "md5_user" => UserAuthenticator::Md5WithSalt {
+                    encrypted_password: md5_hash_password(user_name, "pwd").into_bytes(),
+                    salt: [1, 2, 3, 4],
+                },
+                "scram_user" => UserAuthenticator::ScramSha256(ScramVerifier::new("pwd")),
+                _ => UserAuthenticator::None,
+            };
+            Ok(Arc::new(MockSession { user_authenticator }))
+        }
+
+        fn cancel_queries_in_session(&self, session_id: SessionId) {
+            self.cancelled_sessions.lock().unwrap().push(session_id);
         }
+
+        fn end_session(&self, _session: &Self::Session) {}
     }
 
-    struct MockSession {}
+    struct MockSession {
+        user_authenticator: UserAuthenticator,
+    }
 
     #[async_trait::async_trait]
     impl Session for MockSession {
@@ -130,13 +221,47 @@ mod tests {
                 )],
             ))
         }
+
+        fn split_statements(&self, sql: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+            Ok(sql
+                .split(';')
+                .map(str::trim)
+                .filter(|stmt| !stmt.is_empty())
+                .map(str::to_string)
+                .collect())
+        }
+
+        fn describe_statement(
+            self: Arc<Self>,
+            _sql: &str,
+            param_types: Vec<Option<TypeOid>>,
+        ) -> Result<StatementDescription, Box<dyn Error + Send + Sync>> {
+            Ok(StatementDescription {
+                param_types: param_types
+                    .into_iter()
+                    .map(|t| t.unwrap_or(TypeOid::Varchar))
+                    .collect(),
+                row_description: Some(vec![PgFieldDescriptor::new(
+                    "VARCHAR".to_owned(),
+                    TypeOid::Varchar,
+                )]),
+            })
+        }
+
+        fn user_authenticator(&self) -> &UserAuthenticator {
+            &self.user_authenticator
+        }
+
+        fn id(&self) -> SessionId {
+            MOCK_SESSION_ID
+        }
     }
 
     #[tokio::test]
     /// The test below is copied from tokio-postgres doc.
     async fn test_psql_extended_mode_connect() {
-        let session_mgr = Arc::new(MockSessionManager {});
-        tokio::spawn(async move { pg_serve("127.0.0.1:10000", session_mgr).await });
+        let session_mgr = Arc::new(MockSessionManager::default());
+        tokio::spawn(async move { pg_serve("127.0.0.1:10000", session_mgr, None).await });
 
         // Connect to the database.
         let (client, connection) = tokio_postgres::connect("host=localhost port=10000", NoTls)
@@ -153,13 +278,97 @@ mod tests {
 
         // Now we can execute a simple statement that just returns its parameter.
         let rows = client.query("SELECT 'Hello, World'", &[]).await.unwrap();
-        // FIXME: Enable this after handle prepared statement.
-        // let rows = client
-        //     .query("SELECT $1::TEXT", &[&"hello world"])
-        //     .await
-        //     .unwrap();
+        let value: &str = rows[0].get(0);
+        assert_eq!(value, "Hello, World");
 
+        let rows = client
+            .query("SELECT $1::TEXT", &[&"Hello, World"])
+            .await
+            .unwrap();
         let value: &str = rows[0].get(0);
         assert_eq!(value, "Hello, World");
+
+        // Each statement of a simple query gets its own CommandComplete.
+        let messages = client
+            .simple_query("SELECT 'Hello'; SELECT 'World';")
+            .await
+            .unwrap();
+        let completed = messages
+            .iter()
+            .filter(|m| matches!(m, SimpleQueryMessage::CommandComplete(_)))
+            .count();
+        assert_eq!(completed, 2);
+
+        // Prepared statements can be executed more than once.
+        let statement = client.prepare("SELECT $1::TEXT").await.unwrap();
+        for _ in 0..2 {
+            let rows = client.query(&statement, &[&"Hello, World"]).await.unwrap();
+            assert_eq!(rows.len(), 1);
+        }
+    }
+    #[tokio::test]
+    async fn test_psql_require_ssl() {
+        let session_mgr = Arc::new(MockSessionManager::default());
+        let ssl_config = SslConfig {
+            cert_path: concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/server.crt").to_string(),
+            key_path: concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/server.key").to_string(),
+            require_ssl: true,
+        };
+        tokio::spawn(
+            async move { pg_serve("127.0.0.1:10001", session_mgr, Some(ssl_config)).await },
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        // The SSLRequest is accepted.
+        let mut stream = TcpStream::connect("127.0.0.1:10001").await.unwrap();
+        stream.write_i32(8).await.unwrap();
+        stream.write_i32(80877103).await.unwrap();
+        assert_eq!(stream.read_u8().await.unwrap(), b'S');
+
+        // Clients without SSL are rejected.
+        assert!(tokio_postgres::connect("host=localhost port=10001", NoTls)
+            .await
+            .is_err());
+    }
+    #[tokio::test]
+    async fn test_psql_password_auth() {
+        let session_mgr = Arc::new(MockSessionManager::default());
+        tokio::spawn(async move { pg_serve("127.0.0.1:10002", session_mgr, None).await });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        for user in ["clear_user", "md5_user", "scram_user"] {
+            let config = format!("host=localhost port=10002 user={} password=pwd", user);
+            let (client, connection) = tokio_postgres::connect(&config, NoTls).await.unwrap();
+            tokio::spawn(connection);
+            let rows = client.query("SELECT 'Hello, World'", &[]).await.unwrap();
+            assert_eq!(rows.len(), 1);
+
+            let config = format!("host=localhost port=10002 user={} password=wrong", user);
+            assert!(tokio_postgres::connect(&config, NoTls).await.is_err());
+        }
+    }
+    #[tokio::test]
+    async fn test_psql_cancel_request() {
+        let session_mgr = Arc::new(MockSessionManager::default());
+        let server_mgr = session_mgr.clone();
+        tokio::spawn(async move { pg_serve("127.0.0.1:10003", server_mgr, None).await });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let (client, connection) = tokio_postgres::connect("host=localhost port=10003", NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+
+        // The cancel token carries the key data sent at startup.
+        client.cancel_token().cancel_query(NoTls).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(
+            *session_mgr.cancelled_sessions.lock().unwrap(),
+            vec![MOCK_SESSION_ID]
+        );
+
+        // The session is still usable.
+        let rows = client.query("SELECT 'Hello, World'", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
     }
 }
@@ -0,0 +1,151 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SSL support of pgwire. A client asks for an encrypted connection by sending an `SSLRequest`
+//! before the startup message, after which the TLS handshake is performed on the same stream and
+//! the rest of the protocol runs over it.
+
+use std::fs::File;
+use std::io::{BufReader, Error as IoError, ErrorKind, Result};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// The SSL settings of the pg server.
+#[derive(Clone, Debug)]
+pub struct SslConfig {
+    /// Path of the PEM file of the certificate chain.
+    pub cert_path: String,
+    /// Path of the PEM file of the private key, either in PKCS#8 or in PKCS#1 (RSA).
+    pub key_path: String,
+    /// Whether to reject the clients that don't ask for SSL.
+    pub require_ssl: bool,
+}
+
+/// Loads the certificate and the key of `config` and builds the acceptor for TLS handshakes.
+pub fn build_tls_acceptor(config: &SslConfig) -> Result<TlsAcceptor> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_private_key(&config.key_path)?;
+    let server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| IoError::new(ErrorKind::InvalidInput, e))?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(IoError::new(
+            ErrorKind::InvalidInput,
+            format!("no certificate found in {}", path),
+        ));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey> {
+    let mut reader = BufReader::new(File::open(path)?);
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::RSAKey(key)) => {
+                return Ok(PrivateKey(key))
+            }
+            Some(_) => {}
+            None => {
+                return Err(IoError::new(
+                    ErrorKind::InvalidInput,
+                    format!("no private key found in {}", path),
+                ))
+            }
+        }
+    }
+}
+
+/// The stream of a pg connection, which is encrypted if the client asked for SSL.
+pub enum PgStream<S> {
+    Unencrypted(S),
+    Ssl(Box<TlsStream<S>>),
+}
+
+impl<S> PgStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, PgStream::Ssl(_))
+    }
+
+    /// Performs the TLS handshake on an unencrypted stream.
+    pub async fn upgrade(self, acceptor: &TlsAcceptor) -> Result<Self> {
+        match self {
+            PgStream::Unencrypted(stream) => {
+                Ok(PgStream::Ssl(Box::new(acceptor.accept(stream).await?)))
+            }
+            PgStream::Ssl(_) => Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "the connection is already encrypted",
+            )),
+        }
+    }
+}
+
+impl<S> AsyncRead for PgStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_read(cx, buf),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<S> AsyncWrite for PgStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_write(cx, buf),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_flush(cx),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_shutdown(cx),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}